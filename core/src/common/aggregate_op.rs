@@ -1,35 +1,76 @@
 //! # Aggregate Operations
 
 use super::value_type::*;
+use serde::{Deserialize, Serialize};
 
 /// The aggregate operators for low level representation
-#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum AggregateOp {
   Count,
+  CountDistinct,
   Sum(ValueType),
   Prod(ValueType),
+  WeightedSum(ValueType),
+  Variance(ValueType),
+  StdDev(ValueType),
+  Median(ValueType),
+  Collect,
+  GeometricMean(ValueType),
+  HarmonicMean(ValueType),
   Min,
   Argmin,
   Max,
   Argmax,
+  ArgminTuple,
+  ArgmaxTuple,
   Exists,
+  Any,
   TopK(usize),
+  ArgTopK(usize),
+  Rank,
+  ArgRank,
   CategoricalK(usize),
+  Sample(Option<ValueType>),
+  Normalize(ValueType),
+  Window { op: Box<AggregateOp>, size: usize },
+  Multiple(Vec<AggregateOp>),
 }
 
 impl std::fmt::Display for AggregateOp {
   fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
     match self {
       Self::Count => f.write_str("count"),
+      Self::CountDistinct => f.write_str("count_distinct"),
       Self::Sum(t) => f.write_fmt(format_args!("sum<{}>", t)),
       Self::Prod(t) => f.write_fmt(format_args!("prod<{}>", t)),
+      Self::WeightedSum(t) => f.write_fmt(format_args!("weighted_sum<{}>", t)),
+      Self::Variance(t) => f.write_fmt(format_args!("variance<{}>", t)),
+      Self::StdDev(t) => f.write_fmt(format_args!("stddev<{}>", t)),
+      Self::Median(t) => f.write_fmt(format_args!("median<{}>", t)),
+      Self::Collect => f.write_str("collect"),
+      Self::GeometricMean(t) => f.write_fmt(format_args!("geometric_mean<{}>", t)),
+      Self::HarmonicMean(t) => f.write_fmt(format_args!("harmonic_mean<{}>", t)),
       Self::Min => f.write_str("min"),
       Self::Max => f.write_str("max"),
       Self::Argmin => f.write_str("argmin"),
       Self::Argmax => f.write_str("argmax"),
+      Self::ArgminTuple => f.write_str("argmin_tuple"),
+      Self::ArgmaxTuple => f.write_str("argmax_tuple"),
       Self::Exists => f.write_str("exists"),
+      Self::Any => f.write_str("any"),
       Self::TopK(k) => f.write_fmt(format_args!("top<{}>", k)),
+      Self::ArgTopK(k) => f.write_fmt(format_args!("arg_top<{}>", k)),
+      Self::Rank => f.write_str("rank"),
+      Self::ArgRank => f.write_str("arg_rank"),
       Self::CategoricalK(k) => f.write_fmt(format_args!("categorical<{}>", k)),
+      Self::Sample(None) => f.write_str("sample"),
+      Self::Sample(Some(t)) => f.write_fmt(format_args!("sample<{}>", t)),
+      Self::Normalize(t) => f.write_fmt(format_args!("normalize<{}>", t)),
+      Self::Window { op, size } => f.write_fmt(format_args!("window<{}, {}>", size, op)),
+      Self::Multiple(ops) => {
+        let ops = ops.iter().map(|op| op.to_string()).collect::<Vec<_>>();
+        f.write_fmt(format_args!("({})", ops.join(", ")))
+      }
     }
   }
 }
@@ -55,7 +96,31 @@ impl AggregateOp {
     Self::TopK(k)
   }
 
+  pub fn top_k_with_arg(k: usize, has_arg: bool) -> Self {
+    if has_arg {
+      Self::ArgTopK(k)
+    } else {
+      Self::TopK(k)
+    }
+  }
+
+  pub fn rank(has_arg: bool) -> Self {
+    if has_arg {
+      Self::ArgRank
+    } else {
+      Self::Rank
+    }
+  }
+
   pub fn categorical_k(k: usize) -> Self {
     Self::CategoricalK(k)
   }
+
+  pub fn window(op: AggregateOp, size: usize) -> Self {
+    Self::Window { op: Box::new(op), size }
+  }
+
+  pub fn multiple(ops: Vec<AggregateOp>) -> Self {
+    Self::Multiple(ops)
+  }
 }
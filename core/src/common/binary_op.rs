@@ -1,6 +1,8 @@
 //! # Binary Operations
 
-#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum BinaryOp {
   Add,
   Sub,
@@ -4,8 +4,9 @@ use super::binary_op::BinaryOp;
 use super::tuple_access::TupleAccessor;
 use super::unary_op::UnaryOp;
 use super::value::Value;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum Expr {
   Tuple(Vec<Expr>),
   Access(TupleAccessor),
@@ -100,6 +101,54 @@ impl Expr {
       (Self::Call(c), e) => Self::call(c.function.clone(), c.args.iter().map(|a| a.compose(e)).collect()),
     }
   }
+
+  /// Check if the expression contains any function call
+  pub fn has_call(&self) -> bool {
+    match self {
+      Self::Tuple(t) => t.iter().any(Self::has_call),
+      Self::Access(_) | Self::Constant(_) => false,
+      Self::Binary(b) => b.op1.has_call() || b.op2.has_call(),
+      Self::Unary(u) => u.op1.has_call(),
+      Self::IfThenElse(i) => i.cond.has_call() || i.then_br.has_call() || i.else_br.has_call(),
+      Self::Call(_) => true,
+    }
+  }
+
+  /// Get the only function call in the expression, if it is always evaluated (i.e. not inside of an
+  /// if-then-else) and its arguments do not call other functions
+  pub fn single_call(&self) -> Option<&CallExpr> {
+    fn single_call_among<'a, I: Iterator<Item = &'a Expr>>(es: I) -> Option<&'a CallExpr> {
+      let mut with_calls = es.filter(|e| e.has_call());
+      match (with_calls.next(), with_calls.next()) {
+        (Some(e), None) => e.single_call(),
+        _ => None,
+      }
+    }
+
+    match self {
+      Self::Tuple(t) => single_call_among(t.iter()),
+      Self::Binary(b) => single_call_among(vec![&*b.op1, &*b.op2].into_iter()),
+      Self::Unary(u) => u.op1.single_call(),
+      Self::Call(c) if !c.args.iter().any(Self::has_call) => Some(c),
+      _ => None,
+    }
+  }
+
+  /// Replace every function call in the expression by the given value
+  pub fn substitute_call(&self, value: &Value) -> Self {
+    match self {
+      Self::Tuple(t) => Self::Tuple(t.iter().map(|e| e.substitute_call(value)).collect()),
+      Self::Access(_) | Self::Constant(_) => self.clone(),
+      Self::Binary(b) => Self::binary(b.op.clone(), b.op1.substitute_call(value), b.op2.substitute_call(value)),
+      Self::Unary(u) => Self::unary(u.op.clone(), u.op1.substitute_call(value)),
+      Self::IfThenElse(i) => Self::ite(
+        i.cond.substitute_call(value),
+        i.then_br.substitute_call(value),
+        i.else_br.substitute_call(value),
+      ),
+      Self::Call(_) => Self::Constant(value.clone()),
+    }
+  }
 }
 
 impl std::ops::Add<Expr> for Expr {
@@ -240,7 +289,7 @@ impl FromIterator<Expr> for Expr {
   }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct BinaryExpr {
   pub op: BinaryOp,
   pub op1: Box<Expr>,
@@ -257,7 +306,7 @@ impl BinaryExpr {
   }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct UnaryExpr {
   pub op: UnaryOp,
   pub op1: Box<Expr>,
@@ -269,7 +318,7 @@ impl UnaryExpr {
   }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct IfThenElseExpr {
   pub cond: Box<Expr>,
   pub then_br: Box<Expr>,
@@ -286,7 +335,7 @@ impl IfThenElseExpr {
   }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct CallExpr {
   pub function: String,
   pub args: Vec<Expr>,
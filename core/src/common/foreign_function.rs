@@ -10,6 +10,9 @@
 //! in which case the computation will be omitted and neglected.
 //! All foreign functions need to be pure; no interior state is allowed and
 //! given the same input, only one single output can be produced.
+//! The only exception are non-deterministic functions such as `$random`, which
+//! draw from the random number generator of the runtime environment; they are
+//! reproducible given the same random seed, and are never evaluated at compile time.
 //!
 //! ## Type declarations
 //!
@@ -72,8 +75,12 @@
 //! After this, the function will be available in the standard library
 
 use std::collections::*;
+use std::any::{Any, TypeId};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::Arc;
 
 use dyn_clone::DynClone;
+use rand::rngs::SmallRng;
 
 use super::type_family::*;
 use super::value::*;
@@ -83,7 +90,7 @@ use super::foreign_functions as ffs;
 /// A type used for defining a foreign function.
 ///
 /// It could be generic or a specific base type.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
 pub enum ForeignFunctionParameterType {
   /// A generic type parameter, referenced by its ID
   Generic(usize),
@@ -112,12 +119,26 @@ pub enum ArgumentKind {
   Variable,
 }
 
+impl std::fmt::Display for ArgumentKind {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      Self::Static => f.write_str("static"),
+      Self::Optional => f.write_str("optional"),
+      Self::Variable => f.write_str("variable"),
+    }
+  }
+}
+
 #[derive(Clone, Debug)]
 pub enum ForeignFunctionError {
   AlreadyExisted { name: String },
   UnusedGenericType { id: usize },
   UnboundedReturnGenericType { id: usize },
   UnboundedReturnTypeFamily { family: TypeFamily },
+  UndeclaredGenericType { id: usize },
+  OptionalAndVariableArguments,
+  InconsistentArity { kind: ArgumentKind, index: usize },
+  ReturnTypeMismatch { expected: ValueType, found: ValueType },
 }
 
 impl std::fmt::Display for ForeignFunctionError {
@@ -131,6 +152,18 @@ impl std::fmt::Display for ForeignFunctionError {
       Self::UnboundedReturnTypeFamily { family } => {
         f.write_fmt(format_args!("Returning type family `{}` is disallowed", family))
       }
+      Self::UndeclaredGenericType { id } => f.write_fmt(format_args!("Generic type #{} is not declared", id)),
+      Self::OptionalAndVariableArguments => {
+        f.write_str("Optional arguments and variable arguments cannot co-exist")
+      }
+      Self::InconsistentArity { kind, index } => f.write_fmt(format_args!(
+        "The type of {} argument #{} cannot be obtained although it is within the declared arity",
+        kind, index
+      )),
+      Self::ReturnTypeMismatch { expected, found } => f.write_fmt(format_args!(
+        "Declared return type is `{}` but the function returns a value of type `{}`",
+        expected, found
+      )),
     }
   }
 }
@@ -239,6 +272,29 @@ pub trait ForeignFunction: DynClone {
   /// In case error happens, we return `None` as the result.
   fn execute(&self, args: Vec<Value>) -> Option<Value>;
 
+  /// Execute the function on a batch of argument rows, producing one result per row
+  ///
+  /// By default it calls `execute` on every row; functions that can be vectorized (e.g. a neural
+  /// scorer running on GPU) could override it to evaluate the whole batch at once.
+  fn execute_batch(&self, rows: &[Vec<Value>]) -> Vec<Option<Value>> {
+    rows.iter().map(|args| self.execute(args.clone())).collect()
+  }
+
+  /// Whether the function always produces the same output given the same input.
+  ///
+  /// Non-deterministic functions are skipped by compile time optimizations such as constant folding.
+  fn is_deterministic(&self) -> bool {
+    true
+  }
+
+  /// Execute the function given arguments and the random number generator of the runtime environment
+  ///
+  /// Only non-deterministic functions need to implement this; by default it falls back to `execute`.
+  #[allow(unused_variables)]
+  fn execute_with_rng(&self, args: Vec<Value>, rng: &mut SmallRng) -> Option<Value> {
+    self.execute(args)
+  }
+
   /// Get all the arguments
   fn arguments(&self) -> Vec<(ArgumentKind, ForeignFunctionParameterType)> {
     let mut args = vec![];
@@ -280,13 +336,46 @@ pub trait ForeignFunction: DynClone {
     }
   }
 
+  /// Check if the declared arities are consistent with the argument types; that is, the type
+  /// of every argument within the declared arity can be obtained without panicking
+  fn check_arity_consistent(&self) -> Result<(), ForeignFunctionError> {
+    for i in 0..self.num_static_arguments() {
+      catch_unwind(AssertUnwindSafe(|| self.static_argument_type(i)))
+        .map_err(|_| ForeignFunctionError::InconsistentArity { kind: ArgumentKind::Static, index: i })?;
+    }
+    for i in 0..self.num_optional_arguments() {
+      catch_unwind(AssertUnwindSafe(|| self.optional_argument_type(i)))
+        .map_err(|_| ForeignFunctionError::InconsistentArity { kind: ArgumentKind::Optional, index: i })?;
+    }
+    if self.has_variable_arguments() {
+      catch_unwind(AssertUnwindSafe(|| self.variable_argument_type()))
+        .map_err(|_| ForeignFunctionError::InconsistentArity { kind: ArgumentKind::Variable, index: 0 })?;
+    }
+    Ok(())
+  }
+
   /// Check if the function's type is well-formed; if not, a `ForeignFunctionError` is returned
   fn check_type_well_formed(&self) -> Result<(), ForeignFunctionError> {
+    // A function cannot have both optional and variable arguments
+    if self.has_variable_arguments() && self.num_optional_arguments() > 0 {
+      return Err(ForeignFunctionError::OptionalAndVariableArguments);
+    }
+
     let arg_types = self.arguments();
     let ret_type = self.return_type();
     let mut to_check_parameters: Vec<_> = arg_types.iter().map(|(_, t)| t).collect();
     to_check_parameters.push(&ret_type);
 
+    // Check that every generic type being referred to is declared
+    for param in &to_check_parameters {
+      match param {
+        ForeignFunctionParameterType::Generic(i) if *i >= self.num_generic_types() => {
+          return Err(ForeignFunctionError::UndeclaredGenericType { id: *i });
+        }
+        _ => {}
+      }
+    }
+
     // First check for each generic type, whether it is used
     for generic_type_id in 0..self.num_generic_types() {
       let mut used = false;
@@ -328,16 +417,135 @@ pub trait ForeignFunction: DynClone {
 
     Ok(())
   }
+
+  /// Best-effort check on the return type of non-generic functions: execute the function on
+  /// sample arguments and make sure that the returned value, if any, has the declared type.
+  fn check_return_type(&self) -> Result<(), ForeignFunctionError> {
+    let expected = match self.return_type() {
+      ForeignFunctionParameterType::BaseType(ty) if self.num_generic_types() == 0 => ty,
+      _ => return Ok(()),
+    };
+
+    // Only static arguments are supplied; all of them need to be of base types
+    let mut args = vec![];
+    for i in 0..self.num_static_arguments() {
+      match self.static_argument_type(i) {
+        ForeignFunctionParameterType::BaseType(ty) => args.push(sample_value(&ty)),
+        _ => return Ok(()),
+      }
+    }
+
+    // Execute the function; a panic or a failure on the sample arguments is not a type error
+    if let Ok(Some(result)) = catch_unwind(AssertUnwindSafe(|| self.execute(args))) {
+      let found = result.value_type();
+      if found != expected {
+        return Err(ForeignFunctionError::ReturnTypeMismatch { expected, found });
+      }
+    }
+    Ok(())
+  }
+}
+
+/// Get a sample value of the given type, used for self-checking foreign functions
+fn sample_value(ty: &ValueType) -> Value {
+  match ty {
+    ValueType::Char => Value::Char('a'),
+    ValueType::Bool => Value::Bool(true),
+    ValueType::Str => Value::Str(""),
+    ValueType::String => Value::String(String::new()),
+    ValueType::Symbol => Value::symbol(""),
+    ValueType::DateTime => Value::DateTime(chrono::DateTime::<chrono::Utc>::default()),
+    ValueType::Duration => Value::Duration(chrono::Duration::zero()),
+    ValueType::Tuple(tys) => Value::Tuple(tys.iter().map(sample_value).collect()),
+    ValueType::List(_) => Value::List(vec![]),
+    numeric => numeric.one(),
+  }
+}
+
+/// A monomorphic foreign function whose implementation is a plain Rust closure
+///
+/// All the arguments are static and of base types, e.g. `$my_sigmoid(f32) -> f32`.
+#[derive(Clone)]
+pub struct ClosureForeignFunction {
+  name: String,
+  arg_types: Vec<ValueType>,
+  return_type: ValueType,
+  closure_type: TypeId,
+  f: Arc<dyn Fn(Vec<Value>) -> Option<Value> + Send + Sync>,
+}
+
+impl ClosureForeignFunction {
+  pub fn new<F>(name: &str, arg_types: Vec<ValueType>, return_type: ValueType, f: F) -> Self
+  where
+    F: Fn(Vec<Value>) -> Option<Value> + Send + Sync + 'static,
+  {
+    Self {
+      name: name.to_string(),
+      arg_types,
+      return_type,
+      closure_type: TypeId::of::<F>(),
+      f: Arc::new(f),
+    }
+  }
+}
+
+impl ForeignFunction for ClosureForeignFunction {
+  fn name(&self) -> String {
+    self.name.clone()
+  }
+
+  fn num_static_arguments(&self) -> usize {
+    self.arg_types.len()
+  }
+
+  fn static_argument_type(&self, i: usize) -> ForeignFunctionParameterType {
+    ForeignFunctionParameterType::BaseType(self.arg_types[i].clone())
+  }
+
+  fn return_type(&self) -> ForeignFunctionParameterType {
+    ForeignFunctionParameterType::BaseType(self.return_type.clone())
+  }
+
+  fn execute(&self, args: Vec<Value>) -> Option<Value> {
+    (self.f)(args)
+  }
 }
 
 /// A dynamic foreign function that can hold any static foreign function
 pub struct DynamicForeignFunction {
   ff: Box<dyn ForeignFunction + Send + Sync>,
+
+  /// The type implementing the function; for closure foreign functions, the type of the closure
+  implementation: TypeId,
 }
 
 impl DynamicForeignFunction {
   pub fn new<F: ForeignFunction + Send + Sync + 'static>(f: F) -> Self {
-    Self { ff: Box::new(f) }
+    let any: &dyn Any = &f;
+    if let Some(dynamic) = any.downcast_ref::<Self>() {
+      dynamic.clone()
+    } else {
+      let implementation = match any.downcast_ref::<ClosureForeignFunction>() {
+        Some(closure) => closure.closure_type,
+        None => TypeId::of::<F>(),
+      };
+      Self {
+        ff: Box::new(f),
+        implementation,
+      }
+    }
+  }
+
+  /// Check whether the two functions are the same definition, i.e. they have the same name and signature,
+  /// and are implemented by the same type
+  pub fn is_same_definition(&self, other: &Self) -> bool {
+    self.implementation == other.implementation
+      && self.name() == other.name()
+      && self.generic_type_parameters() == other.generic_type_parameters()
+      && self.static_argument_types() == other.static_argument_types()
+      && self.optional_argument_types() == other.optional_argument_types()
+      && self.optional_variable_argument_type() == other.optional_variable_argument_type()
+      && self.return_type() == other.return_type()
   }
 }
 
@@ -419,6 +627,7 @@ impl Clone for DynamicForeignFunction {
   fn clone(&self) -> Self {
     Self {
       ff: dyn_clone::clone_box(&*self.ff),
+      implementation: self.implementation,
     }
   }
 }
@@ -467,6 +676,18 @@ impl ForeignFunction for DynamicForeignFunction {
   fn execute(&self, args: Vec<Value>) -> Option<Value> {
     self.ff.execute(args)
   }
+
+  fn execute_batch(&self, rows: &[Vec<Value>]) -> Vec<Option<Value>> {
+    self.ff.execute_batch(rows)
+  }
+
+  fn is_deterministic(&self) -> bool {
+    self.ff.is_deterministic()
+  }
+
+  fn execute_with_rng(&self, args: Vec<Value>, rng: &mut SmallRng) -> Option<Value> {
+    self.ff.execute_with_rng(args, rng)
+  }
 }
 
 /// Dynamic foreign function registry
@@ -500,6 +721,9 @@ impl ForeignFunctionRegistry {
     registry.register(ffs::Sin).unwrap();
     registry.register(ffs::Cos).unwrap();
     registry.register(ffs::Tan).unwrap();
+    registry.register(ffs::Sqrt).unwrap();
+    registry.register(ffs::Ln).unwrap();
+    registry.register(ffs::Exp).unwrap();
 
     // Min/Max
     registry.register(ffs::Max).unwrap();
@@ -507,19 +731,31 @@ impl ForeignFunctionRegistry {
 
     // String operations
     registry.register(ffs::StringConcat).unwrap();
+    registry.register(ffs::StringFormat).unwrap();
     registry.register(ffs::StringLength).unwrap();
     registry.register(ffs::StringCharAt).unwrap();
     registry.register(ffs::Substring).unwrap();
 
     // DateTime operations
+    registry.register(ffs::DateTimeParse).unwrap();
+    registry.register(ffs::DateTimeAdd).unwrap();
+    registry.register(ffs::DateTimeDiff).unwrap();
     registry.register(ffs::DateTimeDay).unwrap();
     registry.register(ffs::DateTimeMonth).unwrap();
     registry.register(ffs::DateTimeMonth0).unwrap();
     registry.register(ffs::DateTimeYear).unwrap();
+    registry.register(ffs::DurationSeconds).unwrap();
 
     // Hashing operation
     registry.register(ffs::Hash).unwrap();
 
+    // Coalescing operation
+    registry.register(ffs::Coalesce).unwrap();
+
+    // Random number generation
+    registry.register(ffs::Random).unwrap();
+    registry.register(ffs::RandomInt).unwrap();
+
     registry
   }
 
@@ -532,6 +768,7 @@ impl ForeignFunctionRegistry {
       Err(ForeignFunctionError::AlreadyExisted { name })
     } else {
       // Check if the function is well formed
+      f.check_arity_consistent()?;
       f.check_type_well_formed()?;
 
       // Insert it into the registry
@@ -542,6 +779,20 @@ impl ForeignFunctionRegistry {
     }
   }
 
+  /// Register a new foreign function in this registry, after self-checking it
+  ///
+  /// On top of the checks done by `register`, non-generic functions are executed on sample arguments to
+  /// check that they return values of the declared type. Since the function's code is run (and its panics are caught) during the
+  /// self-check, this should only be used on functions without side effects, and not under `panic = "abort"`.
+  pub fn register_with_self_check<F: ForeignFunction + Send + Sync + 'static>(
+    &mut self,
+    f: F,
+  ) -> Result<(), ForeignFunctionError> {
+    f.check_arity_consistent()?;
+    f.check_return_type()?;
+    self.register(f)
+  }
+
   pub fn contains(&self, name: &str) -> bool {
     self.registry.contains_key(name)
   }
@@ -566,6 +817,11 @@ impl<'a> IntoIterator for &'a ForeignFunctionRegistry {
 pub trait UnaryFloatFunction: Clone {
   fn name(&self) -> String;
 
+  /// Check if the argument is within the domain of the function; the function returns `None` otherwise
+  fn in_domain(&self, _arg: f64) -> bool {
+    true
+  }
+
   fn execute_f32(&self, arg: f32) -> f32;
 
   fn execute_f64(&self, arg: f64) -> f64;
@@ -600,8 +856,9 @@ impl<F: UnaryFloatFunction> ForeignFunction for F {
 
   fn execute(&self, args: Vec<Value>) -> Option<Value> {
     match args[0] {
-      Value::F32(f) => Some(Value::F32(self.execute_f32(f))),
-      Value::F64(f) => Some(Value::F64(self.execute_f64(f))),
+      Value::F32(f) if self.in_domain(f as f64) => Some(Value::F32(self.execute_f32(f))),
+      Value::F64(f) if self.in_domain(f) => Some(Value::F64(self.execute_f64(f))),
+      Value::F32(_) | Value::F64(_) => None,
       _ => panic!("Expect floating point input"),
     }
   }
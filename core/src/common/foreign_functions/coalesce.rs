@@ -0,0 +1,76 @@
+use super::*;
+
+/// Coalesce
+///
+/// ``` scl
+/// extern fn $coalesce<T: Any>(x: T...) -> T
+/// ```
+///
+/// Returns the first argument that is not the default value of its type, i.e. not
+/// the empty string, `0`, `false`, or `'\0'`. Date times are never considered to be
+/// default, a tuple is default if all of its elements are, and a list is default if it
+/// is empty. If every argument is a default value, the function fails and the fact is
+/// dropped.
+#[derive(Clone)]
+pub struct Coalesce;
+
+impl Coalesce {
+  fn is_default(value: &Value) -> bool {
+    match value {
+      Value::I8(i) => *i == 0,
+      Value::I16(i) => *i == 0,
+      Value::I32(i) => *i == 0,
+      Value::I64(i) => *i == 0,
+      Value::I128(i) => *i == 0,
+      Value::ISize(i) => *i == 0,
+      Value::U8(u) => *u == 0,
+      Value::U16(u) => *u == 0,
+      Value::U32(u) => *u == 0,
+      Value::U64(u) => *u == 0,
+      Value::U128(u) => *u == 0,
+      Value::USize(u) => *u == 0,
+      Value::F32(f) => *f == 0.0,
+      Value::F64(f) => *f == 0.0,
+      Value::Bool(b) => !*b,
+      Value::Char(c) => *c == '\0',
+      Value::Str(s) => s.is_empty(),
+      Value::String(s) => s.is_empty(),
+      Value::DateTime(_) => false,
+      Value::Duration(d) => d.is_zero(),
+      Value::Tuple(t) => t.iter().all(Self::is_default),
+      Value::List(l) => l.is_empty(),
+      Value::Symbol(_) => value.as_str().is_empty(),
+    }
+  }
+}
+
+impl ForeignFunction for Coalesce {
+  fn name(&self) -> String {
+    "coalesce".to_string()
+  }
+
+  fn num_generic_types(&self) -> usize {
+    1
+  }
+
+  fn generic_type_family(&self, i: usize) -> TypeFamily {
+    assert_eq!(i, 0);
+    TypeFamily::Any
+  }
+
+  fn has_variable_arguments(&self) -> bool {
+    true
+  }
+
+  fn variable_argument_type(&self) -> ForeignFunctionParameterType {
+    ForeignFunctionParameterType::Generic(0)
+  }
+
+  fn return_type(&self) -> ForeignFunctionParameterType {
+    ForeignFunctionParameterType::Generic(0)
+  }
+
+  fn execute(&self, args: Vec<Value>) -> Option<Value> {
+    args.into_iter().find(|arg| !Self::is_default(arg))
+  }
+}
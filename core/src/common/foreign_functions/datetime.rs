@@ -0,0 +1,37 @@
+use crate::utils;
+
+use super::*;
+
+/// Parse a string into a date time; fails if the string is not a valid date time
+///
+/// ``` scl
+/// extern fn $datetime(s: String) -> DateTime
+/// ```
+#[derive(Clone)]
+pub struct DateTimeParse;
+
+impl ForeignFunction for DateTimeParse {
+  fn name(&self) -> String {
+    "datetime".to_string()
+  }
+
+  fn num_static_arguments(&self) -> usize {
+    1
+  }
+
+  fn static_argument_type(&self, i: usize) -> ForeignFunctionParameterType {
+    assert_eq!(i, 0);
+    ForeignFunctionParameterType::BaseType(ValueType::String)
+  }
+
+  fn return_type(&self) -> ForeignFunctionParameterType {
+    ForeignFunctionParameterType::BaseType(ValueType::DateTime)
+  }
+
+  fn execute(&self, args: Vec<Value>) -> Option<Value> {
+    match &args[0] {
+      Value::String(s) => utils::parse_date_time_string(s).map(Value::DateTime),
+      _ => None,
+    }
+  }
+}
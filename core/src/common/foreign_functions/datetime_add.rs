@@ -0,0 +1,38 @@
+use super::*;
+
+/// Shift a date time by a duration
+///
+/// ``` scl
+/// extern fn $datetime_add(d: DateTime, dur: Duration) -> DateTime
+/// ```
+#[derive(Clone)]
+pub struct DateTimeAdd;
+
+impl ForeignFunction for DateTimeAdd {
+  fn name(&self) -> String {
+    "datetime_add".to_string()
+  }
+
+  fn num_static_arguments(&self) -> usize {
+    2
+  }
+
+  fn static_argument_type(&self, i: usize) -> ForeignFunctionParameterType {
+    match i {
+      0 => ForeignFunctionParameterType::BaseType(ValueType::DateTime),
+      1 => ForeignFunctionParameterType::BaseType(ValueType::Duration),
+      _ => panic!("No argument {}", i),
+    }
+  }
+
+  fn return_type(&self) -> ForeignFunctionParameterType {
+    ForeignFunctionParameterType::BaseType(ValueType::DateTime)
+  }
+
+  fn execute(&self, args: Vec<Value>) -> Option<Value> {
+    match (&args[0], &args[1]) {
+      (Value::DateTime(d), Value::Duration(dur)) => d.checked_add_signed(*dur).map(Value::DateTime),
+      _ => None,
+    }
+  }
+}
@@ -0,0 +1,35 @@
+use super::*;
+
+/// Get the number of seconds elapsed from the second date time to the first
+///
+/// ``` scl
+/// extern fn $datetime_diff(a: DateTime, b: DateTime) -> i64
+/// ```
+#[derive(Clone)]
+pub struct DateTimeDiff;
+
+impl ForeignFunction for DateTimeDiff {
+  fn name(&self) -> String {
+    "datetime_diff".to_string()
+  }
+
+  fn num_static_arguments(&self) -> usize {
+    2
+  }
+
+  fn static_argument_type(&self, i: usize) -> ForeignFunctionParameterType {
+    assert!(i < 2);
+    ForeignFunctionParameterType::BaseType(ValueType::DateTime)
+  }
+
+  fn return_type(&self) -> ForeignFunctionParameterType {
+    ForeignFunctionParameterType::BaseType(ValueType::I64)
+  }
+
+  fn execute(&self, args: Vec<Value>) -> Option<Value> {
+    match (&args[0], &args[1]) {
+      (Value::DateTime(a), Value::DateTime(b)) => Some(Value::I64(a.signed_duration_since(*b).num_seconds())),
+      _ => None,
+    }
+  }
+}
@@ -0,0 +1,35 @@
+use super::*;
+
+/// Get the number of whole seconds in a duration
+///
+/// ``` scl
+/// extern fn $duration_seconds(dur: Duration) -> i64
+/// ```
+#[derive(Clone)]
+pub struct DurationSeconds;
+
+impl ForeignFunction for DurationSeconds {
+  fn name(&self) -> String {
+    "duration_seconds".to_string()
+  }
+
+  fn num_static_arguments(&self) -> usize {
+    1
+  }
+
+  fn static_argument_type(&self, i: usize) -> ForeignFunctionParameterType {
+    assert_eq!(i, 0);
+    ForeignFunctionParameterType::BaseType(ValueType::Duration)
+  }
+
+  fn return_type(&self) -> ForeignFunctionParameterType {
+    ForeignFunctionParameterType::BaseType(ValueType::I64)
+  }
+
+  fn execute(&self, args: Vec<Value>) -> Option<Value> {
+    match &args[0] {
+      Value::Duration(dur) => Some(Value::I64(dur.num_seconds())),
+      _ => None,
+    }
+  }
+}
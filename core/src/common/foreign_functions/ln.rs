@@ -0,0 +1,27 @@
+use super::*;
+
+/// Natural logarithm foreign function; returns nothing on non-positive numbers
+///
+/// ``` scl
+/// extern fn $ln<T: Float>(x: T) -> T
+/// ```
+#[derive(Clone)]
+pub struct Ln;
+
+impl UnaryFloatFunction for Ln {
+  fn name(&self) -> String {
+    "ln".to_string()
+  }
+
+  fn in_domain(&self, arg: f64) -> bool {
+    arg > 0.0
+  }
+
+  fn execute_f32(&self, arg: f32) -> f32 {
+    arg.ln()
+  }
+
+  fn execute_f64(&self, arg: f64) -> f64 {
+    arg.ln()
+  }
+}
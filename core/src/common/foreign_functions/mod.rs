@@ -8,33 +8,53 @@ use super::foreign_function::*;
 use std::convert::*;
 
 mod abs;
+mod coalesce;
 mod cos;
+mod datetime;
+mod datetime_add;
 mod datetime_day;
+mod datetime_diff;
 mod datetime_month;
 mod datetime_month0;
 mod datetime_year;
+mod duration_seconds;
+mod exp;
 mod hash;
+mod ln;
 mod max;
 mod min;
+mod random;
 mod sin;
+mod sqrt;
 mod string_char_at;
 mod string_concat;
+mod string_format;
 mod string_length;
 mod substring;
 mod tan;
 
 pub use abs::*;
+pub use coalesce::*;
 pub use cos::*;
+pub use datetime::*;
+pub use datetime_add::*;
 pub use datetime_day::*;
+pub use datetime_diff::*;
 pub use datetime_month::*;
 pub use datetime_month0::*;
 pub use datetime_year::*;
+pub use duration_seconds::*;
+pub use exp::*;
 pub use hash::*;
+pub use ln::*;
 pub use max::*;
 pub use min::*;
+pub use random::*;
 pub use sin::*;
+pub use sqrt::*;
 pub use string_char_at::*;
 pub use string_concat::*;
+pub use string_format::*;
 pub use string_length::*;
 pub use substring::*;
 pub use tan::*;
@@ -0,0 +1,120 @@
+use rand::rngs::SmallRng;
+use rand::{Rng, SeedableRng};
+
+use super::*;
+
+/// Random floating point number uniformly drawn from `[0, 1)`
+///
+/// ``` scl
+/// extern fn $random() -> f64
+/// ```
+///
+/// The number is drawn from the random number generator of the runtime environment,
+/// so that two runs with the same random seed produce the same results.
+#[derive(Clone)]
+pub struct Random;
+
+impl ForeignFunction for Random {
+  fn name(&self) -> String {
+    "random".to_string()
+  }
+
+  fn return_type(&self) -> ForeignFunctionParameterType {
+    ForeignFunctionParameterType::BaseType(ValueType::F64)
+  }
+
+  fn is_deterministic(&self) -> bool {
+    false
+  }
+
+  fn execute(&self, args: Vec<Value>) -> Option<Value> {
+    self.execute_with_rng(args, &mut SmallRng::from_entropy())
+  }
+
+  fn execute_with_rng(&self, _: Vec<Value>, rng: &mut SmallRng) -> Option<Value> {
+    Some(Value::F64(rng.gen()))
+  }
+}
+
+/// Random integer uniformly drawn from `[lo, hi)`
+///
+/// ``` scl
+/// extern fn $random_int<T: Integer>(lo: T, hi: T) -> T
+/// ```
+///
+/// Fails when the range is empty, i.e. when `lo >= hi`.
+#[derive(Clone)]
+pub struct RandomInt;
+
+impl RandomInt {
+  fn dyn_random_int<T>(lo: Value, hi: Value, rng: &mut SmallRng) -> Option<T>
+  where
+    T: PartialOrd + rand::distributions::uniform::SampleUniform,
+    Value: TryInto<T>,
+  {
+    let lo: T = lo.try_into().ok()?;
+    let hi: T = hi.try_into().ok()?;
+    if lo < hi {
+      Some(rng.gen_range(lo..hi))
+    } else {
+      None
+    }
+  }
+}
+
+impl ForeignFunction for RandomInt {
+  fn name(&self) -> String {
+    "random_int".to_string()
+  }
+
+  fn num_generic_types(&self) -> usize {
+    1
+  }
+
+  fn generic_type_family(&self, i: usize) -> TypeFamily {
+    assert_eq!(i, 0);
+    TypeFamily::Integer
+  }
+
+  fn num_static_arguments(&self) -> usize {
+    2
+  }
+
+  fn static_argument_type(&self, i: usize) -> ForeignFunctionParameterType {
+    assert!(i < 2);
+    ForeignFunctionParameterType::Generic(0)
+  }
+
+  fn return_type(&self) -> ForeignFunctionParameterType {
+    ForeignFunctionParameterType::Generic(0)
+  }
+
+  fn is_deterministic(&self) -> bool {
+    false
+  }
+
+  fn execute(&self, args: Vec<Value>) -> Option<Value> {
+    self.execute_with_rng(args, &mut SmallRng::from_entropy())
+  }
+
+  fn execute_with_rng(&self, args: Vec<Value>, rng: &mut SmallRng) -> Option<Value> {
+    let rt = self.infer_return_type(&args);
+    let mut iter = args.into_iter();
+    let (lo, hi) = (iter.next()?, iter.next()?);
+    match rt {
+      ValueType::I8 => Self::dyn_random_int(lo, hi, rng).map(Value::I8),
+      ValueType::I16 => Self::dyn_random_int(lo, hi, rng).map(Value::I16),
+      ValueType::I32 => Self::dyn_random_int(lo, hi, rng).map(Value::I32),
+      ValueType::I64 => Self::dyn_random_int(lo, hi, rng).map(Value::I64),
+      ValueType::I128 => Self::dyn_random_int(lo, hi, rng).map(Value::I128),
+      ValueType::ISize => Self::dyn_random_int(lo, hi, rng).map(Value::ISize),
+      ValueType::U8 => Self::dyn_random_int(lo, hi, rng).map(Value::U8),
+      ValueType::U16 => Self::dyn_random_int(lo, hi, rng).map(Value::U16),
+      ValueType::U32 => Self::dyn_random_int(lo, hi, rng).map(Value::U32),
+      ValueType::U64 => Self::dyn_random_int(lo, hi, rng).map(Value::U64),
+      ValueType::U128 => Self::dyn_random_int(lo, hi, rng).map(Value::U128),
+      ValueType::USize => Self::dyn_random_int(lo, hi, rng).map(Value::USize),
+      _ => None,
+    }
+  }
+}
@@ -0,0 +1,27 @@
+use super::*;
+
+/// Square root foreign function; returns nothing on negative numbers
+///
+/// ``` scl
+/// extern fn $sqrt<T: Float>(x: T) -> T
+/// ```
+#[derive(Clone)]
+pub struct Sqrt;
+
+impl UnaryFloatFunction for Sqrt {
+  fn name(&self) -> String {
+    "sqrt".to_string()
+  }
+
+  fn in_domain(&self, arg: f64) -> bool {
+    arg >= 0.0
+  }
+
+  fn execute_f32(&self, arg: f32) -> f32 {
+    arg.sqrt()
+  }
+
+  fn execute_f64(&self, arg: f64) -> f64 {
+    arg.sqrt()
+  }
+}
@@ -0,0 +1,76 @@
+use super::*;
+
+/// String format
+///
+/// ``` scl
+/// extern fn $format(template: String, args: Any...) -> String
+/// ```
+///
+/// Each `{}` placeholder in the template is substituted by the next argument, from left to right;
+/// `{{` and `}}` stand for literal braces. Strings and characters are substituted as they are, while
+/// the other values are displayed as in the outputs. Fails when the number of placeholders does not
+/// match the number of arguments.
+#[derive(Clone)]
+pub struct StringFormat;
+
+impl ForeignFunction for StringFormat {
+  fn name(&self) -> String {
+    "format".to_string()
+  }
+
+  fn num_static_arguments(&self) -> usize {
+    1
+  }
+
+  fn static_argument_type(&self, i: usize) -> ForeignFunctionParameterType {
+    match i {
+      0 => ForeignFunctionParameterType::BaseType(ValueType::String),
+      _ => panic!("No argument {}", i),
+    }
+  }
+
+  fn has_variable_arguments(&self) -> bool {
+    true
+  }
+
+  fn variable_argument_type(&self) -> ForeignFunctionParameterType {
+    ForeignFunctionParameterType::TypeFamily(TypeFamily::Any)
+  }
+
+  fn return_type(&self) -> ForeignFunctionParameterType {
+    ForeignFunctionParameterType::BaseType(ValueType::String)
+  }
+
+  fn execute(&self, args: Vec<Value>) -> Option<Value> {
+    let mut args = args.into_iter();
+    let template = match args.next() {
+      Some(Value::String(s)) => s,
+      _ => panic!("Template is not string"),
+    };
+    let mut result = String::new();
+    let mut chars = template.chars().peekable();
+    while let Some(c) = chars.next() {
+      match (c, chars.peek()) {
+        ('{', Some('}')) => {
+          chars.next();
+          match args.next()? {
+            Value::Str(s) => result += s,
+            Value::String(s) => result += &s,
+            Value::Char(c) => result.push(c),
+            arg => result += &arg.to_string(),
+          }
+        }
+        ('{', Some('{')) | ('}', Some('}')) => {
+          chars.next();
+          result.push(c);
+        }
+        _ => result.push(c),
+      }
+    }
+    if args.next().is_some() {
+      None
+    } else {
+      Some(Value::String(result))
+    }
+  }
+}
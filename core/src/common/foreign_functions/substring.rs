@@ -5,6 +5,9 @@ use super::*;
 /// ``` scl
 /// extern fn $substring(s: String, begin: usize, end: usize?) -> String
 /// ```
+///
+/// The `end` index is clamped to the length of the string. Fails when `begin` is out of range,
+/// is greater than `end`, or when either index does not fall on a character boundary.
 #[derive(Clone)]
 pub struct Substring;
 
@@ -38,16 +41,15 @@ impl ForeignFunction for Substring {
   }
 
   fn execute(&self, args: Vec<Value>) -> Option<Value> {
-    if args.len() == 2 {
-      match (&args[0], &args[1]) {
-        (Value::String(s), Value::USize(i)) => Some(Value::String(s[*i..].to_string())),
-        _ => panic!("Invalid arguments"),
-      }
+    let (s, begin, end) = match (&args[0], &args[1], args.get(2)) {
+      (Value::String(s), Value::USize(i), None) => (s, *i, s.len()),
+      (Value::String(s), Value::USize(i), Some(Value::USize(j))) => (s, *i, (*j).min(s.len())),
+      _ => panic!("Invalid arguments"),
+    };
+    if begin > end {
+      None
     } else {
-      match (&args[0], &args[1], &args[2]) {
-        (Value::String(s), Value::USize(i), Value::USize(j)) => Some(Value::String(s[*i..*j].to_string())),
-        _ => panic!("Invalid arguments"),
-      }
+      s.get(begin..end).map(|s| Value::String(s.to_string()))
     }
   }
 }
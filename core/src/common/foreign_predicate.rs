@@ -1,3 +1,4 @@
+use std::any::{Any, TypeId};
 use std::collections::*;
 
 use dyn_clone::*;
@@ -22,6 +23,10 @@ impl std::fmt::Display for Binding {
   }
 }
 
+/// The mode of an argument of a foreign predicate: a bound argument needs to be known when evaluating the
+/// predicate, while a free argument is produced by the predicate
+pub type ArgMode = Binding;
+
 impl Binding {
   pub fn is_bound(&self) -> bool {
     match self {
@@ -84,6 +89,18 @@ impl BindingPattern {
     }
   }
 
+  /// Create a binding pattern from the binding of each argument
+  pub fn from_bindings<I: IntoIterator<Item = Binding>>(bindings: I) -> Self {
+    Self {
+      pattern: bindings.into_iter().collect(),
+    }
+  }
+
+  /// Check if the bound arguments are exactly the first `num_bounded` ones
+  pub fn is_bound_prefix(&self, num_bounded: usize) -> bool {
+    self.pattern.iter().enumerate().all(|(i, b)| b.is_bound() == (i < num_bounded))
+  }
+
   /// Check if all argument needs to be bounded
   pub fn is_bounded(&self) -> bool {
     self.pattern.iter().all(|p| p.is_bound())
@@ -130,6 +147,18 @@ pub trait ForeignPredicate: DynClone {
   /// The number of bounded arguments
   fn num_bounded(&self) -> usize;
 
+  /// The mode of the `i`-th argument
+  ///
+  /// By default, the first `num_bounded` arguments are bound and the others are free. Overriding it only
+  /// documents the modes; they have to agree with `num_bounded`, which is checked upon registration.
+  fn argument_mode(&self, i: usize) -> ArgMode {
+    if i < self.num_bounded() {
+      ArgMode::Bound
+    } else {
+      ArgMode::Free
+    }
+  }
+
   /// The number of free arguments
   fn num_free(&self) -> usize {
     self.arity() - self.num_bounded()
@@ -142,7 +171,16 @@ pub trait ForeignPredicate: DynClone {
 
   /// Get an identifier for this predicate
   fn binding_pattern(&self) -> BindingPattern {
-    BindingPattern::new(self.arity(), self.num_bounded())
+    BindingPattern::from_bindings((0..self.arity()).map(|i| self.argument_mode(i)))
+  }
+
+  /// Whether the predicate can be directly queried, e.g. `query range_i32(0, 5, x)`
+  ///
+  /// The tuples of a queried predicate are materialized by grounding it, so the bounded arguments of
+  /// the query need to be constants. This should only be enabled for predicates whose evaluation is
+  /// cheap and produces finitely many tuples.
+  fn is_queryable(&self) -> bool {
+    false
   }
 
   /// Evaluate the foreign predicate given a tuple containing bounded variables
@@ -150,16 +188,45 @@ pub trait ForeignPredicate: DynClone {
   /// The `bounded` tuple (`Vec<Value>`) should have arity (length) `self.num_bounded()`.
   /// The function returns a sequence of (dynamically) tagged-tuples where the arity is `self.num_free()`
   fn evaluate(&self, bounded: &[Value]) -> Vec<(DynamicInputTag, Vec<Value>)>;
+
+  /// Evaluate the foreign predicate on a batch of tuples containing bounded variables
+  ///
+  /// The result contains one sequence of tagged-tuples for each of the `inputs`, in the same order.
+  /// By default, `evaluate` is called on each input; predicates that can share work across the
+  /// inputs (e.g. issuing a single query) may override this for efficiency.
+  fn evaluate_batch(&self, inputs: &[Vec<Value>]) -> Vec<Vec<(DynamicInputTag, Vec<Value>)>> {
+    inputs.iter().map(|bounded| self.evaluate(bounded)).collect()
+  }
 }
 
 /// The dynamic foreign predicate
 pub struct DynamicForeignPredicate {
   fp: Box<dyn ForeignPredicate + Send + Sync>,
+
+  /// The type implementing the predicate
+  implementation: TypeId,
 }
 
 impl DynamicForeignPredicate {
   pub fn new<P: ForeignPredicate + Send + Sync + 'static>(fp: P) -> Self {
-    Self { fp: Box::new(fp) }
+    let any: &dyn Any = &fp;
+    if let Some(dynamic) = any.downcast_ref::<Self>() {
+      dynamic.clone()
+    } else {
+      Self {
+        fp: Box::new(fp),
+        implementation: TypeId::of::<P>(),
+      }
+    }
+  }
+
+  /// Check whether the two predicates are the same definition, i.e. they have the same name and signature,
+  /// and are implemented by the same type
+  pub fn is_same_definition(&self, other: &Self) -> bool {
+    self.implementation == other.implementation
+      && self.name() == other.name()
+      && self.argument_types() == other.argument_types()
+      && self.binding_pattern() == other.binding_pattern()
   }
 }
 
@@ -167,6 +234,7 @@ impl Clone for DynamicForeignPredicate {
   fn clone(&self) -> Self {
     Self {
       fp: dyn_clone::clone_box(&*self.fp),
+      implementation: self.implementation,
     }
   }
 }
@@ -188,9 +256,21 @@ impl ForeignPredicate for DynamicForeignPredicate {
     self.fp.num_bounded()
   }
 
+  fn argument_mode(&self, i: usize) -> ArgMode {
+    self.fp.argument_mode(i)
+  }
+
+  fn is_queryable(&self) -> bool {
+    self.fp.is_queryable()
+  }
+
   fn evaluate(&self, bounded: &[Value]) -> Vec<(DynamicInputTag, Vec<Value>)> {
     self.fp.evaluate(bounded)
   }
+
+  fn evaluate_batch(&self, inputs: &[Vec<Value>]) -> Vec<Vec<(DynamicInputTag, Vec<Value>)>> {
+    self.fp.evaluate_batch(inputs)
+  }
 }
 
 impl std::fmt::Debug for DynamicForeignPredicate {
@@ -212,11 +292,7 @@ impl std::fmt::Display for DynamicForeignPredicate {
         f.write_str(", ")?;
       }
       self.argument_type(i).fmt(f)?;
-      if i < self.num_bounded() {
-        f.write_str(" [b]")?;
-      } else {
-        f.write_str(" [f]")?;
-      }
+      f.write_fmt(format_args!(" [{}]", self.argument_mode(i)))?;
     }
     f.write_str(")")
   }
@@ -258,6 +334,7 @@ impl ForeignPredicateRegistry {
 
     // String operations
     reg.register(fps::StringCharsBFF::new()).unwrap();
+    reg.register(fps::StringSplitBBFF::new()).unwrap();
 
     reg
   }
@@ -267,6 +344,12 @@ impl ForeignPredicateRegistry {
     let id = p.name();
     if self.contains(&id) {
       Err(ForeignPredicateError::AlreadyExisted { id: format!("{}", id) })
+    } else if !p.binding_pattern().is_bound_prefix(p.num_bounded()) {
+      Err(ForeignPredicateError::InvalidArgumentModes {
+        id,
+        pattern: p.binding_pattern(),
+        num_bounded: p.num_bounded(),
+      })
     } else {
       let p = DynamicForeignPredicate::new(p);
       self.registry.insert(id, p);
@@ -303,12 +386,20 @@ impl<'a> IntoIterator for &'a ForeignPredicateRegistry {
 #[derive(Clone, Debug)]
 pub enum ForeignPredicateError {
   AlreadyExisted { id: String },
+  InvalidArgumentModes { id: String, pattern: BindingPattern, num_bounded: usize },
+  InvalidDefinition { id: String, msg: String },
 }
 
 impl std::fmt::Display for ForeignPredicateError {
   fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
     match self {
       Self::AlreadyExisted { id } => write!(f, "Foreign predicate `{}` already existed", id),
+      Self::InvalidArgumentModes { id, pattern, num_bounded } => write!(
+        f,
+        "Argument modes `{}` of foreign predicate `{}` do not match its {} bound argument(s), which need to come first",
+        pattern, id, num_bounded
+      ),
+      Self::InvalidDefinition { id, msg } => write!(f, "Invalid definition of foreign predicate `{}`: {}", id, msg),
     }
   }
 }
@@ -16,7 +16,10 @@ mod soft_eq;
 mod soft_gt;
 mod soft_lt;
 mod soft_neq;
+#[cfg(feature = "sqlite")]
+mod sql_query;
 mod string_chars;
+mod string_split;
 
 pub use float_eq::*;
 pub use range::*;
@@ -25,4 +28,7 @@ pub use soft_eq::*;
 pub use soft_gt::*;
 pub use soft_lt::*;
 pub use soft_neq::*;
+#[cfg(feature = "sqlite")]
+pub use sql_query::*;
 pub use string_chars::*;
+pub use string_split::*;
@@ -72,6 +72,10 @@ impl ForeignPredicate for RangeBBF {
     2
   }
 
+  fn is_queryable(&self) -> bool {
+    true
+  }
+
   fn evaluate(&self, bounded: &[Value]) -> Vec<(DynamicInputTag, Vec<Value>)> {
     assert_eq!(bounded.len(), 2);
     let begin = &bounded[0];
@@ -0,0 +1,197 @@
+use std::sync::{Arc, Mutex};
+
+use rusqlite::types::Value as SqlValue;
+use rusqlite::Connection;
+
+use super::*;
+
+/// SQL query foreign predicate, backed by a parameterized query over a SQLite database
+///
+/// ``` scl
+/// extern pred sql_employees(dept: i32, name: String)[bf]
+/// ```
+///
+/// The bounded arguments fill the `?` placeholders of the query, in order, and each row returned
+/// by the query grounds the free arguments. Rows failing to be read, containing `NULL`s, or
+/// containing values that cannot be converted to the declared argument types are skipped.
+/// Bounded integers that do not fit in SQLite's 64-bit integers match no row.
+#[derive(Clone)]
+pub struct SqlQuery {
+  name: String,
+  connection: Arc<Mutex<Connection>>,
+  query: String,
+  types: Vec<ValueType>,
+  num_bounded: usize,
+}
+
+impl SqlQuery {
+  /// Create a new SQL query predicate named `name` with the given argument types, the first
+  /// `num_bounded` of which are bounded
+  ///
+  /// The query is prepared against the connection, and its number of `?` placeholders and of
+  /// result columns are checked against the argument types.
+  pub fn new<S1: ToString, S2: ToString>(
+    name: S1,
+    connection: Connection,
+    query: S2,
+    types: Vec<ValueType>,
+    num_bounded: usize,
+  ) -> Result<Self, ForeignPredicateError> {
+    Self::with_shared_connection(name, Arc::new(Mutex::new(connection)), query, types, num_bounded)
+  }
+
+  /// Create a new SQL query predicate using a connection shared with other predicates
+  pub fn with_shared_connection<S1: ToString, S2: ToString>(
+    name: S1,
+    connection: Arc<Mutex<Connection>>,
+    query: S2,
+    types: Vec<ValueType>,
+    num_bounded: usize,
+  ) -> Result<Self, ForeignPredicateError> {
+    let name = name.to_string();
+    let query = query.to_string();
+    let error = |msg: String| ForeignPredicateError::InvalidDefinition { id: name.clone(), msg };
+    if num_bounded > types.len() {
+      return Err(error(format!(
+        "{} bounded argument(s) but only {} argument(s)",
+        num_bounded,
+        types.len()
+      )));
+    }
+    if let Some(ty) = types[..num_bounded]
+      .iter()
+      .find(|ty| matches!(ty, ValueType::Tuple(_) | ValueType::List(_)))
+    {
+      return Err(error(format!("SQL query cannot take a `{}` as parameter", ty)));
+    }
+
+    // Prepare the query once to validate it; the statement stays in the connection's cache
+    {
+      let conn = connection.lock().unwrap();
+      let stmt = conn
+        .prepare_cached(&query)
+        .map_err(|e| error(format!("cannot prepare SQL query: {}", e)))?;
+      if stmt.parameter_count() != num_bounded {
+        return Err(error(format!(
+          "SQL query takes {} parameter(s) but there are {} bounded argument(s)",
+          stmt.parameter_count(),
+          num_bounded
+        )));
+      }
+      if stmt.column_count() != types.len() - num_bounded {
+        return Err(error(format!(
+          "SQL query returns {} column(s) but there are {} free argument(s)",
+          stmt.column_count(),
+          types.len() - num_bounded
+        )));
+      }
+    }
+
+    Ok(Self {
+      name,
+      connection,
+      query,
+      types,
+      num_bounded,
+    })
+  }
+
+  fn value_to_sql(value: &Value) -> Option<SqlValue> {
+    let sql_value = match value {
+      Value::I8(i) => SqlValue::Integer(*i as i64),
+      Value::I16(i) => SqlValue::Integer(*i as i64),
+      Value::I32(i) => SqlValue::Integer(*i as i64),
+      Value::I64(i) => SqlValue::Integer(*i),
+      Value::I128(i) => SqlValue::Integer(i64::try_from(*i).ok()?),
+      Value::ISize(i) => SqlValue::Integer(i64::try_from(*i).ok()?),
+      Value::U8(u) => SqlValue::Integer(*u as i64),
+      Value::U16(u) => SqlValue::Integer(*u as i64),
+      Value::U32(u) => SqlValue::Integer(*u as i64),
+      Value::U64(u) => SqlValue::Integer(i64::try_from(*u).ok()?),
+      Value::U128(u) => SqlValue::Integer(i64::try_from(*u).ok()?),
+      Value::USize(u) => SqlValue::Integer(i64::try_from(*u).ok()?),
+      Value::F32(f) => SqlValue::Real(*f as f64),
+      Value::F64(f) => SqlValue::Real(*f),
+      Value::Bool(b) => SqlValue::Integer(*b as i64),
+      Value::Char(c) => SqlValue::Text(c.to_string()),
+      Value::Str(s) => SqlValue::Text(s.to_string()),
+      Value::String(s) => SqlValue::Text(s.clone()),
+      Value::DateTime(d) => SqlValue::Text(d.to_rfc3339()),
+      Value::Duration(d) => SqlValue::Text(d.to_string()),
+      Value::Symbol(_) => SqlValue::Text(value.as_str().to_string()),
+      Value::List(_) | Value::Tuple(_) => return None,
+    };
+    Some(sql_value)
+  }
+
+  fn sql_to_value(value: SqlValue, ty: &ValueType) -> Option<Value> {
+    match value {
+      SqlValue::Null | SqlValue::Blob(_) => None,
+      SqlValue::Integer(i) if ty.is_boolean() => Some(Value::Bool(i != 0)),
+      SqlValue::Integer(i) => ty.parse(&i.to_string()).ok(),
+      SqlValue::Real(f) => ty.parse(&f.to_string()).ok(),
+      SqlValue::Text(s) => ty.parse(&s).ok(),
+    }
+  }
+}
+
+impl ForeignPredicate for SqlQuery {
+  fn name(&self) -> String {
+    self.name.clone()
+  }
+
+  fn arity(&self) -> usize {
+    self.types.len()
+  }
+
+  fn argument_type(&self, i: usize) -> ValueType {
+    match self.types.get(i) {
+      Some(ty) => ty.clone(),
+      None => panic!("Invalid argument ID `{}`", i),
+    }
+  }
+
+  fn num_bounded(&self) -> usize {
+    self.num_bounded
+  }
+
+  fn evaluate(&self, bounded: &[Value]) -> Vec<(DynamicInputTag, Vec<Value>)> {
+    assert_eq!(bounded.len(), self.num_bounded);
+    let free_types = &self.types[self.num_bounded..];
+
+    // A bounded value which cannot be stored in SQLite matches no row
+    let params = match bounded.iter().map(Self::value_to_sql).collect::<Option<Vec<_>>>() {
+      Some(params) => params,
+      None => return vec![],
+    };
+
+    // Run the query; the connection cannot be used concurrently
+    let connection = self.connection.lock().unwrap();
+    let mut stmt = match connection.prepare_cached(&self.query) {
+      Ok(stmt) => stmt,
+      Err(_) => return vec![],
+    };
+    let rows = stmt.query_map(rusqlite::params_from_iter(params), |row| {
+      (0..free_types.len())
+        .map(|i| row.get::<_, SqlValue>(i))
+        .collect::<Result<Vec<_>, _>>()
+    });
+    let rows = match rows {
+      Ok(rows) => rows,
+      Err(_) => return vec![],
+    };
+
+    // Convert each row into the free arguments; rows failing to be read are skipped
+    rows
+      .filter_map(|row| {
+        row
+          .ok()?
+          .into_iter()
+          .zip(free_types)
+          .map(|(v, ty)| Self::sql_to_value(v, ty))
+          .collect::<Option<Vec<_>>>()
+      })
+      .map(|values| (DynamicInputTag::None, values))
+      .collect()
+  }
+}
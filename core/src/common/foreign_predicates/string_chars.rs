@@ -42,6 +42,10 @@ impl ForeignPredicate for StringCharsBFF {
     1
   }
 
+  fn is_queryable(&self) -> bool {
+    true
+  }
+
   fn evaluate(&self, bounded: &[Value]) -> Vec<(DynamicInputTag, Vec<Value>)> {
     assert_eq!(bounded.len(), 1);
     let s = &bounded[0];
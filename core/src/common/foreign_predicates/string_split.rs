@@ -0,0 +1,74 @@
+use super::*;
+
+/// String split foreign predicate
+///
+/// ``` scl
+/// extern pred string_split(s: String, delim: String, id: usize, piece: String)[bbff]
+/// ```
+///
+/// Splits the bounded string `s` on every occurrence of `delim`, grounding each piece along with
+/// its index. Consecutive delimiters produce empty pieces, while an empty string produces no piece
+/// at all. An empty delimiter does not split the string.
+#[derive(Clone)]
+pub struct StringSplitBBFF;
+
+impl Default for StringSplitBBFF {
+  fn default() -> Self {
+    Self
+  }
+}
+
+impl StringSplitBBFF {
+  pub fn new() -> Self {
+    Self
+  }
+}
+
+impl ForeignPredicate for StringSplitBBFF {
+  fn name(&self) -> String {
+    "string_split".to_string()
+  }
+
+  fn arity(&self) -> usize {
+    4
+  }
+
+  fn argument_type(&self, i: usize) -> ValueType {
+    match i {
+      0 | 1 | 3 => ValueType::String,
+      2 => ValueType::USize,
+      _ => panic!("Invalid argument ID `{}`", i),
+    }
+  }
+
+  fn num_bounded(&self) -> usize {
+    2
+  }
+
+  fn is_queryable(&self) -> bool {
+    true
+  }
+
+  fn evaluate(&self, bounded: &[Value]) -> Vec<(DynamicInputTag, Vec<Value>)> {
+    assert_eq!(bounded.len(), 2);
+    let (s, delim) = (bounded[0].as_str(), bounded[1].as_str());
+    if s.is_empty() {
+      vec![]
+    } else if delim.is_empty() {
+      vec![(
+        DynamicInputTag::None,
+        vec![Value::from(0usize), Value::from(s.to_string())],
+      )]
+    } else {
+      s.split(delim)
+        .enumerate()
+        .map(|(i, piece)| {
+          (
+            DynamicInputTag::None,
+            vec![Value::from(i), Value::from(piece.to_string())],
+          )
+        })
+        .collect()
+    }
+  }
+}
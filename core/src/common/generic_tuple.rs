@@ -1,6 +1,7 @@
 use super::tuple_access::TupleAccessor;
+use serde::{Deserialize, Serialize};
 
-#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub enum GenericTuple<T> {
   Value(T),
   Tuple(Box<[GenericTuple<T>]>),
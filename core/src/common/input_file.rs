@@ -1,36 +1,95 @@
+use serde::{Deserialize, Serialize};
 use std::path::*;
 
-#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+/// The way fields are separated within a line of an input file
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Delimiter {
+  /// A single byte, e.g. `b','`
+  Byte(u8),
+
+  /// Runs of spaces and tabs; consecutive separators are collapsed
+  Whitespace,
+
+  /// A regular expression matching the separators
+  Regex(String),
+}
+
+impl Default for Delimiter {
+  fn default() -> Self {
+    Self::Byte(b',')
+  }
+}
+
+/// The options of a CSV input file
+///
+/// `quote_char` falls back to the CSV reader's default (`"`), and when `empty_value` is given, empty
+/// fields are read as that value instead. When `column_mapping` is given, the columns are selected and
+/// ordered by their names in the header instead of by their positions. Lines starting with
+/// `comment_char` are skipped, as well as blank lines
+#[derive(Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct CsvOptions {
+  pub deliminator: Delimiter,
+  pub has_header: bool,
+  pub has_probability: bool,
+  pub quote_char: Option<u8>,
+  pub empty_value: Option<String>,
+  pub column_mapping: Option<Vec<String>>,
+  pub comment_char: Option<u8>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum InputFile {
   Csv {
     file_path: PathBuf,
-    deliminator: u8,
-    has_header: bool,
+    options: CsvOptions,
+  },
+  Txt {
+    file_path: PathBuf,
+    deliminator: Option<Delimiter>,
+  },
+  Json {
+    file_path: PathBuf,
+    fields: Option<Vec<String>>,
     has_probability: bool,
   },
-  Txt(PathBuf),
 }
 
 impl InputFile {
   pub fn csv(file_path: PathBuf) -> Self {
-    Self::Csv {
+    Self::csv_with_options(file_path, CsvOptions::default())
+  }
+
+  pub fn csv_with_options(file_path: PathBuf, options: CsvOptions) -> Self {
+    Self::Csv { file_path, options }
+  }
+
+  pub fn txt(file_path: PathBuf) -> Self {
+    Self::Txt {
+      file_path,
+      deliminator: None,
+    }
+  }
+
+  /// A text file; if a delimiter is given, each line is split into fields, otherwise
+  /// each line is taken as a whole
+  pub fn txt_with_options(file_path: PathBuf, deliminator: Option<Delimiter>) -> Self {
+    Self::Txt { file_path, deliminator }
+  }
+
+  pub fn json(file_path: PathBuf) -> Self {
+    Self::Json {
       file_path,
-      deliminator: b',',
-      has_header: false,
+      fields: None,
       has_probability: false,
     }
   }
 
-  pub fn csv_with_options(
-    file_path: PathBuf,
-    deliminator: Option<u8>,
-    has_header: Option<bool>,
-    has_probability: Option<bool>,
-  ) -> Self {
-    Self::Csv {
+  /// A JSON file holding an array of records; `fields` are the keys taken, in order, as the
+  /// columns of the records that are objects
+  pub fn json_with_options(file_path: PathBuf, fields: Option<Vec<String>>, has_probability: Option<bool>) -> Self {
+    Self::Json {
       file_path,
-      deliminator: deliminator.unwrap_or(b','),
-      has_header: has_header.unwrap_or(false),
+      fields,
       has_probability: has_probability.unwrap_or(false),
     }
   }
@@ -1,10 +1,13 @@
-#[derive(Clone, Debug, PartialEq, PartialOrd)]
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, PartialOrd, Serialize, Deserialize)]
 pub enum DynamicInputTag {
   None,
   Exclusive(usize),
   Bool(bool),
   Float(f64),
   ExclusiveFloat(f64, usize),
+  Interval(f64, f64),
 }
 
 impl DynamicInputTag {
@@ -40,6 +43,7 @@ impl std::fmt::Display for DynamicInputTag {
       Self::Bool(b) => b.fmt(f),
       Self::Float(n) => n.fmt(f),
       Self::ExclusiveFloat(n, i) => f.write_str(&format!("{} [ME({})]", n, i)),
+      Self::Interval(lo, hi) => f.write_str(&format!("{}:{}", lo, hi)),
     }
   }
 }
@@ -58,6 +62,20 @@ impl std::str::FromStr for DynamicInputTag {
       Ok(Self::Bool(true))
     } else if s == "false" {
       Ok(Self::Bool(false))
+    } else if let Some((lo, hi)) = s.split_once(':') {
+      // A probability interval `lo:hi`, where the lower bound cannot exceed the upper bound
+      let parse_bound = |b: &str| {
+        b.trim().parse::<f64>().map_err(|_| ParseInputTagError {
+          source_str: s.to_string(),
+        })
+      };
+      let (lo, hi) = (parse_bound(lo)?, parse_bound(hi)?);
+      if lo > hi {
+        return Err(ParseInputTagError {
+          source_str: s.to_string(),
+        });
+      }
+      Ok(Self::Interval(lo, hi))
     } else {
       let f = s.parse::<f64>().map_err(|_| ParseInputTagError {
         source_str: s.to_string(),
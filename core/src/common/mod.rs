@@ -14,6 +14,7 @@ pub mod input_file;
 pub mod input_tag;
 pub mod output_option;
 pub mod predicate_set;
+pub mod symbol;
 pub mod tuple;
 pub mod tuple_access;
 pub mod tuple_type;
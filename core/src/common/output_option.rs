@@ -1,6 +1,7 @@
+use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
-#[derive(Clone, Debug, PartialEq, Eq, PartialOrd)]
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Serialize, Deserialize)]
 pub enum OutputOption {
   /// Hidden output means that the relation would not be returned or printed by default
   Hidden,
@@ -48,23 +49,30 @@ impl std::fmt::Display for OutputOption {
   }
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, PartialOrd)]
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Serialize, Deserialize)]
 pub enum OutputFile {
   CSV(OutputCSVFile),
+  JSON(OutputJSONFile),
 }
 
 impl std::fmt::Display for OutputFile {
   fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
     match self {
       Self::CSV(c) => std::fmt::Display::fmt(c, f),
+      Self::JSON(j) => std::fmt::Display::fmt(j, f),
     }
   }
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, PartialOrd)]
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Serialize, Deserialize)]
 pub struct OutputCSVFile {
   pub file_path: PathBuf,
   pub deliminator: u8,
+
+  /// Whether to append the tuples to the end of an existing file instead of truncating it.
+  /// Output CSV files contain neither a header row nor a probability column, so the appended
+  /// rows line up with the existing ones as long as the relation type stays the same
+  pub append: bool,
 }
 
 impl OutputCSVFile {
@@ -72,13 +80,15 @@ impl OutputCSVFile {
     Self {
       file_path,
       deliminator: b',',
+      append: false,
     }
   }
 
-  pub fn new_with_options(file_path: PathBuf, deliminator: Option<u8>) -> Self {
+  pub fn new_with_options(file_path: PathBuf, deliminator: Option<u8>, append: Option<bool>) -> Self {
     Self {
       file_path,
       deliminator: deliminator.unwrap_or(b','),
+      append: append.unwrap_or(false),
     }
   }
 }
@@ -86,8 +96,50 @@ impl OutputCSVFile {
 impl std::fmt::Display for OutputCSVFile {
   fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
     f.write_fmt(format_args!(
-      "CSV(\"{:?}\", deliminator='{}')",
-      self.file_path, self.deliminator as char
+      "CSV(\"{:?}\", deliminator='{}'{})",
+      self.file_path,
+      self.deliminator as char,
+      if self.append { ", append" } else { "" }
     ))
   }
 }
+
+/// An output file holding one JSON record per line
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Serialize, Deserialize)]
+pub struct OutputJSONFile {
+  pub file_path: PathBuf,
+
+  /// The names of the fields; when given, each tuple is written as an object instead of an array
+  pub fields: Option<Vec<String>>,
+
+  /// Whether to append the records to the end of an existing file instead of truncating it
+  pub append: bool,
+}
+
+impl OutputJSONFile {
+  pub fn new(file_path: PathBuf) -> Self {
+    Self {
+      file_path,
+      fields: None,
+      append: false,
+    }
+  }
+
+  pub fn new_with_options(file_path: PathBuf, fields: Option<Vec<String>>, append: Option<bool>) -> Self {
+    Self {
+      file_path,
+      fields,
+      append: append.unwrap_or(false),
+    }
+  }
+}
+
+impl std::fmt::Display for OutputJSONFile {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.write_fmt(format_args!("JSON(\"{:?}\"", self.file_path))?;
+    if let Some(fields) = &self.fields {
+      f.write_fmt(format_args!(", fields=[{}]", fields.join(", ")))?;
+    }
+    f.write_str(if self.append { ", append)" } else { ")" })
+  }
+}
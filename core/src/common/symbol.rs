@@ -0,0 +1,51 @@
+//! # Interned symbols
+//!
+//! A symbol is a string stored once in a global interner, and represented by its id in the
+//! interner everywhere else, so that comparing and hashing symbols only involves integers.
+//! The interner is global instead of being attached to a context, since string constants of
+//! the programs are interned at compile time, and have to agree with the symbols loaded from
+//! files at runtime. Interned strings are never freed.
+
+use std::collections::*;
+use std::sync::*;
+
+use lazy_static::lazy_static;
+
+lazy_static! {
+  static ref SYMBOL_INTERNER: RwLock<SymbolInterner> = RwLock::new(SymbolInterner::default());
+}
+
+#[derive(Default)]
+struct SymbolInterner {
+  ids: HashMap<&'static str, u32>,
+  symbols: Vec<&'static str>,
+}
+
+/// Get the id of the symbol of a string, interning the string if it is not yet interned
+pub fn intern(s: &str) -> u32 {
+  if let Some(id) = SYMBOL_INTERNER.read().unwrap().ids.get(s) {
+    return *id;
+  }
+
+  // The string might have been interned by another thread since the read lock is released
+  let mut interner = SYMBOL_INTERNER.write().unwrap();
+  if let Some(id) = interner.ids.get(s) {
+    return *id;
+  }
+  let id = interner.symbols.len() as u32;
+  let s: &'static str = Box::leak(s.to_string().into_boxed_str());
+  interner.ids.insert(s, id);
+  interner.symbols.push(s);
+  id
+}
+
+/// Get the string of an interned symbol
+pub fn resolve(id: u32) -> &'static str {
+  SYMBOL_INTERNER
+    .read()
+    .unwrap()
+    .symbols
+    .get(id as usize)
+    .copied()
+    .unwrap_or_else(|| panic!("[Internal Error] Symbol #{} is not interned", id))
+}
@@ -1,6 +1,9 @@
 // use std::rc::Rc;
 
+use std::convert::*;
+
 use super::generic_tuple::GenericTuple;
+use super::symbol;
 use super::tuple_type::TupleType;
 use super::value::Value;
 
@@ -44,6 +47,44 @@ impl Tuple {
     }
   }
 
+  /// Create a flat tuple from a list of values
+  pub fn from_values(values: Vec<Value>) -> Self {
+    values.into()
+  }
+
+  /// Turn a flat tuple of exactly `N` values into an array of values
+  pub fn try_into_array<const N: usize>(self) -> Option<[Value; N]> {
+    match self {
+      Self::Tuple(ts) if ts.len() == N => {
+        let values = ts
+          .into_vec()
+          .into_iter()
+          .map(|t| match t {
+            Self::Value(v) => Some(v),
+            _ => None,
+          })
+          .collect::<Option<Vec<_>>>()?;
+        values.try_into().ok()
+      }
+      _ => None,
+    }
+  }
+
+  /// Get the `i`-th element as a concrete Rust type; returns `None` if the index
+  /// is out of range, or if the element is not a value of that type
+  pub fn get_as<T>(&self, i: usize) -> Option<T>
+  where
+    Value: TryInto<T>,
+  {
+    match self {
+      Self::Tuple(ts) => match ts.get(i)? {
+        Self::Value(v) => v.clone().try_into().ok(),
+        _ => None,
+      },
+      _ => None,
+    }
+  }
+
   pub fn as_value(&self) -> Value {
     match self {
       Self::Value(p) => p.clone(),
@@ -342,6 +383,7 @@ impl AsTuple<&'static str> for Tuple {
   fn as_tuple(&self) -> &'static str {
     match self {
       Self::Value(Value::Str(s)) => s,
+      Self::Value(Value::Symbol(s)) => symbol::resolve(*s),
       _ => panic!("Cannot perform as_tuple<&str>"),
     }
   }
@@ -351,6 +393,7 @@ impl AsTuple<String> for Tuple {
   fn as_tuple(&self) -> String {
     match self {
       Self::Value(Value::String(s)) => s.clone(),
+      Self::Value(Value::Symbol(s)) => symbol::resolve(*s).to_string(),
       _ => panic!("Cannot perform as_tuple<String>"),
     }
   }
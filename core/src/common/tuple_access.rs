@@ -1,6 +1,8 @@
+use serde::{Deserialize, Serialize};
+
 pub const TUPLE_ACCESSOR_DEPTH: usize = 3;
 
-#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub struct TupleAccessor {
   pub len: i8,
   pub indices: [i8; TUPLE_ACCESSOR_DEPTH],
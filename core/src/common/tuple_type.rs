@@ -45,7 +45,7 @@ impl TupleType {
           tys.iter().zip(vs.iter()).all(|(ty, v)| ty.matches(v))
         }
       }
-      (TupleType::Value(ty), Tuple::Value(v)) => &ValueType::type_of(v) == ty,
+      (TupleType::Value(ty), Tuple::Value(v)) => ty.matches(v),
       _ => false,
     }
   }
@@ -2,10 +2,12 @@
 
 use std::convert::*;
 
+use super::symbol;
 use super::value_type::*;
 use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, PartialEq, PartialOrd)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum Value {
   I8(i8),
   I16(i16),
@@ -23,13 +25,30 @@ pub enum Value {
   F64(f64),
   Char(char),
   Bool(bool),
-  Str(&'static str),
+  /// Static strings are never produced by the compiler, and cannot be serialized
+  Str(#[serde(serialize_with = "serialize_static_str", skip_deserializing)] &'static str),
   String(String),
   DateTime(DateTime<Utc>),
   Duration(Duration),
+  Tuple(Vec<Value>),
+  List(Vec<Value>),
+  /// An interned string; symbols are serialized as their strings since the ids are only valid in the process
+  Symbol(#[serde(serialize_with = "serialize_symbol", deserialize_with = "deserialize_symbol")] u32),
   // RcString(Rc<String>),
 }
 
+fn serialize_static_str<S: serde::Serializer>(_: &&'static str, _: S) -> Result<S::Ok, S::Error> {
+  Err(serde::ser::Error::custom("static strings cannot be serialized"))
+}
+
+fn serialize_symbol<S: serde::Serializer>(id: &u32, serializer: S) -> Result<S::Ok, S::Error> {
+  serializer.serialize_str(symbol::resolve(*id))
+}
+
+fn deserialize_symbol<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<u32, D::Error> {
+  String::deserialize(deserializer).map(|s| symbol::intern(&s))
+}
+
 impl Value {
   pub fn value_type(&self) -> ValueType {
     ValueType::type_of(self)
@@ -56,22 +75,106 @@ impl Value {
     }
   }
 
+  /// Create a symbol value, interning the string
+  pub fn symbol(s: &str) -> Self {
+    Self::Symbol(symbol::intern(s))
+  }
+
   pub fn as_str(&self) -> &str {
     match self {
       Self::Str(s) => s,
       Self::String(s) => &s,
+      Self::Symbol(id) => symbol::resolve(*id),
       v => panic!("Cannot get string from value {}", v),
     }
   }
 }
 
+impl Value {
+  /// The position of the variant in the declaration of `Value`, used to order values of different types
+  fn variant_index(&self) -> usize {
+    match self {
+      Self::I8(_) => 0,
+      Self::I16(_) => 1,
+      Self::I32(_) => 2,
+      Self::I64(_) => 3,
+      Self::I128(_) => 4,
+      Self::ISize(_) => 5,
+      Self::U8(_) => 6,
+      Self::U16(_) => 7,
+      Self::U32(_) => 8,
+      Self::U64(_) => 9,
+      Self::U128(_) => 10,
+      Self::USize(_) => 11,
+      Self::F32(_) => 12,
+      Self::F64(_) => 13,
+      Self::Char(_) => 14,
+      Self::Bool(_) => 15,
+      Self::Str(_) => 16,
+      Self::String(_) => 17,
+      Self::DateTime(_) => 18,
+      Self::Duration(_) => 19,
+      Self::Tuple(_) => 20,
+      Self::List(_) => 21,
+      Self::Symbol(_) => 22,
+    }
+  }
+}
+
+/// Total order on floats: `NaN` is equal to itself and greater than every other float
+fn float_cmp<F: PartialOrd>(f1: &F, f2: &F) -> std::cmp::Ordering {
+  #[allow(clippy::eq_op)]
+  let is_nan = |f: &F| f != f;
+  match f1.partial_cmp(f2) {
+    Some(o) => o,
+    None => is_nan(f1).cmp(&is_nan(f2)),
+  }
+}
+
+impl PartialEq for Value {
+  fn eq(&self, other: &Self) -> bool {
+    self.cmp(other) == std::cmp::Ordering::Equal
+  }
+}
+
 impl Eq for Value {}
 
+impl PartialOrd for Value {
+  fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+    Some(self.cmp(other))
+  }
+}
+
 impl Ord for Value {
+  /// A total order on values; values of different types are ordered by their type, and floating point
+  /// values are ordered such that `NaN` is equal to itself and greater than any other number. Symbols are
+  /// ordered by the order in which they are interned, not by their strings
   fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-    match self.partial_cmp(other) {
-      Some(o) => o,
-      None => std::cmp::Ordering::Equal,
+    match (self, other) {
+      (Self::I8(i1), Self::I8(i2)) => i1.cmp(i2),
+      (Self::I16(i1), Self::I16(i2)) => i1.cmp(i2),
+      (Self::I32(i1), Self::I32(i2)) => i1.cmp(i2),
+      (Self::I64(i1), Self::I64(i2)) => i1.cmp(i2),
+      (Self::I128(i1), Self::I128(i2)) => i1.cmp(i2),
+      (Self::ISize(i1), Self::ISize(i2)) => i1.cmp(i2),
+      (Self::U8(u1), Self::U8(u2)) => u1.cmp(u2),
+      (Self::U16(u1), Self::U16(u2)) => u1.cmp(u2),
+      (Self::U32(u1), Self::U32(u2)) => u1.cmp(u2),
+      (Self::U64(u1), Self::U64(u2)) => u1.cmp(u2),
+      (Self::U128(u1), Self::U128(u2)) => u1.cmp(u2),
+      (Self::USize(u1), Self::USize(u2)) => u1.cmp(u2),
+      (Self::F32(f1), Self::F32(f2)) => float_cmp(f1, f2),
+      (Self::F64(f1), Self::F64(f2)) => float_cmp(f1, f2),
+      (Self::Char(c1), Self::Char(c2)) => c1.cmp(c2),
+      (Self::Bool(b1), Self::Bool(b2)) => b1.cmp(b2),
+      (Self::Str(s1), Self::Str(s2)) => s1.cmp(s2),
+      (Self::String(s1), Self::String(s2)) => s1.cmp(s2),
+      (Self::DateTime(d1), Self::DateTime(d2)) => d1.cmp(d2),
+      (Self::Duration(d1), Self::Duration(d2)) => d1.cmp(d2),
+      (Self::Tuple(t1), Self::Tuple(t2)) => t1.cmp(t2),
+      (Self::List(l1), Self::List(l2)) => l1.cmp(l2),
+      (Self::Symbol(s1), Self::Symbol(s2)) => s1.cmp(s2),
+      _ => self.variant_index().cmp(&other.variant_index()),
     }
   }
 }
@@ -99,6 +202,9 @@ impl std::hash::Hash for Value {
       Self::String(s) => s.hash(state),
       Self::DateTime(d) => d.hash(state),
       Self::Duration(d) => d.hash(state),
+      Self::Tuple(t) => t.hash(state),
+      Self::List(l) => l.hash(state),
+      Self::Symbol(s) => s.hash(state),
     }
   }
 }
@@ -126,6 +232,27 @@ impl std::fmt::Display for Value {
       Self::String(i) => f.write_fmt(format_args!("{:?}", i)),
       Self::DateTime(i) => f.write_fmt(format_args!("t\"{}\"", i)),
       Self::Duration(i) => f.write_fmt(format_args!("d\"{}\"", i)),
+      Self::Tuple(t) => {
+        f.write_str("(")?;
+        for (i, v) in t.iter().enumerate() {
+          if i > 0 {
+            f.write_str(", ")?;
+          }
+          std::fmt::Display::fmt(v, f)?;
+        }
+        f.write_str(")")
+      }
+      Self::List(l) => {
+        f.write_str("[")?;
+        for (i, v) in l.iter().enumerate() {
+          if i > 0 {
+            f.write_str(", ")?;
+          }
+          std::fmt::Display::fmt(v, f)?;
+        }
+        f.write_str("]")
+      }
+      Self::Symbol(s) => f.write_fmt(format_args!("{:?}", symbol::resolve(*s))),
       // Self::RcString(i) => f.write_fmt(format_args!("{:?}", i)),
     }
   }
@@ -292,3 +419,6 @@ impl_try_into!(f64, F64);
 impl_try_into!(bool, Bool);
 impl_try_into!(char, Char);
 impl_try_into!(String, String);
+impl_try_into!(&'static str, Str);
+impl_try_into!(DateTime<Utc>, DateTime);
+impl_try_into!(Duration, Duration);
@@ -4,8 +4,9 @@ use crate::utils;
 
 use super::tuple::*;
 use super::value::*;
+use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub enum ValueType {
   I8,
   I16,
@@ -27,6 +28,9 @@ pub enum ValueType {
   String,
   DateTime,
   Duration,
+  Tuple(Vec<ValueType>),
+  List(Box<ValueType>),
+  Symbol,
   // RcString,
 }
 
@@ -54,10 +58,24 @@ impl ValueType {
       String(_) => Self::String,
       DateTime(_) => Self::DateTime,
       Duration(_) => Self::Duration,
+      Tuple(vs) => Self::Tuple(vs.iter().map(Self::type_of).collect()),
+      // The element type of an empty list cannot be recovered, so it is taken to be the unit type
+      List(vs) => Self::List(Box::new(vs.first().map(Self::type_of).unwrap_or(Self::Tuple(vec![])))),
+      Symbol(_) => Self::Symbol,
       // RcString(_) => Self::RcString,
     }
   }
 
+  /// Check whether the value is of this type; unlike comparing against `type_of`, an empty
+  /// list matches any list type
+  pub fn matches(&self, value: &Value) -> bool {
+    match (self, value) {
+      (Self::Tuple(tys), Value::Tuple(vs)) => tys.len() == vs.len() && tys.iter().zip(vs).all(|(t, v)| t.matches(v)),
+      (Self::List(ty), Value::List(vs)) => vs.iter().all(|v| ty.matches(v)),
+      (ty, v) => ty == &Self::type_of(v),
+    }
+  }
+
   pub fn zero(&self) -> Value {
     use Value as P;
     use ValueType::*;
@@ -166,6 +184,13 @@ impl ValueType {
     }
   }
 
+  pub fn is_symbol(&self) -> bool {
+    match self {
+      Self::Symbol => true,
+      _ => false,
+    }
+  }
+
   pub fn is_datetime(&self) -> bool {
     match self {
       Self::DateTime => true,
@@ -190,7 +215,7 @@ impl ValueType {
     } else if self.is_string() && target.is_numeric() {
       true
     } else {
-      self.is_string() && target.is_string()
+      (self.is_string() || self.is_symbol()) && (target.is_string() || target.is_symbol())
     }
   }
 
@@ -227,11 +252,56 @@ impl ValueType {
       // String
       Self::Str => panic!("Cannot parse into a static string"),
       Self::String => Ok(Value::String(s.to_string())),
+      Self::Symbol => Ok(Value::symbol(s)),
       // Self::RcString => Ok(Value::RcString(Rc::new(s.to_string()))),
 
       // DateTime and Duration
       Self::DateTime => Ok(Value::DateTime(utils::parse_date_time_string(s).ok_or_else(|| ValueParseError::new(s, self))?)),
       Self::Duration => Ok(Value::Duration(utils::parse_duration_string(s).ok_or_else(|| ValueParseError::new(s, self))?)),
+
+      // Nested tuple, written as a parenthesized group of comma separated values
+      Self::Tuple(tys) => {
+        let inner = s
+          .trim()
+          .strip_prefix('(')
+          .and_then(|s| s.strip_suffix(')'))
+          .ok_or_else(|| ValueParseError::new(s, self))?;
+        let parts = split_tuple_components(inner);
+        if parts.len() != tys.len() {
+          return Err(ValueParseError::new(s, self));
+        }
+        let values = tys
+          .iter()
+          .zip(parts)
+          .map(|(ty, part)| ty.parse_component(part))
+          .collect::<Result<Vec<_>, _>>()?;
+        Ok(Value::Tuple(values))
+      }
+
+      // List, written as a bracketed group of comma separated values
+      Self::List(ty) => {
+        let inner = s
+          .trim()
+          .strip_prefix('[')
+          .and_then(|s| s.strip_suffix(']'))
+          .ok_or_else(|| ValueParseError::new(s, self))?;
+        let values = split_tuple_components(inner)
+          .into_iter()
+          .map(|part| ty.parse_component(part))
+          .collect::<Result<Vec<_>, _>>()?;
+        Ok(Value::List(values))
+      }
+    }
+  }
+
+  /// Parse a component of a tuple or a list, where strings and symbols may be double quoted
+  fn parse_component(&self, s: &str) -> Result<Value, ValueParseError> {
+    let s = s.trim();
+    match self {
+      Self::String | Self::Symbol if s.len() >= 2 && s.starts_with('"') && s.ends_with('"') => {
+        self.parse(&unescape_string(&s[1..s.len() - 1]))
+      }
+      _ => self.parse(s),
     }
   }
 
@@ -287,6 +357,142 @@ impl ValueType {
     }
   }
 
+  /// Same as `sum`, except that floating points are added up with compensated summation (see `compensated_sum`)
+  pub fn stable_sum<'a, I: Iterator<Item = &'a Tuple>>(&self, i: I) -> Tuple {
+    match self {
+      Self::F32 => (compensated_sum(i.map(|v| v.as_f32() as f64)) as f32).into(),
+      Self::F64 => compensated_sum(i.map(|v| v.as_f64())).into(),
+      _ => self.sum(i),
+    }
+  }
+
+  /// Compute the population variance of the numbers in `i`; `None` if `i` is empty or this type is not numeric
+  ///
+  /// When `stable` is set, the mean and the squared deviations are added up with compensated summation.
+  pub fn variance<'a, I: Iterator<Item = &'a Tuple>>(&self, i: I, stable: bool) -> Option<f64> {
+    let xs = i.map(|v| self.tuple_as_f64(v)).collect::<Option<Vec<_>>>()?;
+    if xs.is_empty() {
+      return None;
+    }
+    let sum = |ys: &mut dyn Iterator<Item = f64>| {
+      if stable {
+        compensated_sum(ys)
+      } else {
+        ys.sum::<f64>()
+      }
+    };
+    let n = xs.len() as f64;
+    let mean = sum(&mut xs.iter().copied()) / n;
+    Some(sum(&mut xs.iter().map(|x| (x - mean) * (x - mean))) / n)
+  }
+
+  /// Compute the sum of `value * weight` over the `(value, weight)` pairs in `i`; `None` if `i` is empty
+  pub fn weighted_sum<'a, I: Iterator<Item = &'a Tuple>>(&self, i: I) -> Option<Tuple> {
+    let mut i = i.peekable();
+    i.peek()?;
+    let s = match self {
+      Self::I8 => i.fold(0, |a, v| a + v[0].as_i8() * v[1].as_i8()).into(),
+      Self::I16 => i.fold(0, |a, v| a + v[0].as_i16() * v[1].as_i16()).into(),
+      Self::I32 => i.fold(0, |a, v| a + v[0].as_i32() * v[1].as_i32()).into(),
+      Self::I64 => i.fold(0, |a, v| a + v[0].as_i64() * v[1].as_i64()).into(),
+      Self::I128 => i.fold(0, |a, v| a + v[0].as_i128() * v[1].as_i128()).into(),
+      Self::ISize => i.fold(0, |a, v| a + v[0].as_isize() * v[1].as_isize()).into(),
+
+      // Unsigned
+      Self::U8 => i.fold(0, |a, v| a + v[0].as_u8() * v[1].as_u8()).into(),
+      Self::U16 => i.fold(0, |a, v| a + v[0].as_u16() * v[1].as_u16()).into(),
+      Self::U32 => i.fold(0, |a, v| a + v[0].as_u32() * v[1].as_u32()).into(),
+      Self::U64 => i.fold(0, |a, v| a + v[0].as_u64() * v[1].as_u64()).into(),
+      Self::U128 => i.fold(0, |a, v| a + v[0].as_u128() * v[1].as_u128()).into(),
+      Self::USize => i.fold(0, |a, v| a + v[0].as_usize() * v[1].as_usize()).into(),
+
+      // Floating point
+      Self::F32 => i.fold(0.0, |a, v| a + v[0].as_f32() * v[1].as_f32()).into(),
+      Self::F64 => i.fold(0.0, |a, v| a + v[0].as_f64() * v[1].as_f64()).into(),
+
+      // Others
+      _ => panic!("Cannot perform weighted sum on type `{}`", self),
+    };
+    Some(s)
+  }
+
+  /// Compute the median of the numbers in `i`, averaging the two middle numbers when there is an even
+  /// amount of them; `None` if `i` is empty or this type is not numeric
+  pub fn median<'a, I: Iterator<Item = &'a Tuple>>(&self, i: I) -> Option<f64> {
+    let mut xs = i.map(|v| self.tuple_as_f64(v)).collect::<Option<Vec<_>>>()?;
+    if xs.is_empty() {
+      return None;
+    }
+    xs.sort_by(|a, b| a.total_cmp(b));
+    let mid = xs.len() / 2;
+    if xs.len() % 2 == 0 {
+      Some((xs[mid - 1] + xs[mid]) / 2.0)
+    } else {
+      Some(xs[mid])
+    }
+  }
+
+  /// Compute the geometric mean of the numbers in `i`; `None` if `i` is empty, contains a number that
+  /// is not positive, or if this type is not numeric
+  pub fn geometric_mean<'a, I: Iterator<Item = &'a Tuple>>(&self, i: I) -> Option<f64> {
+    let xs = i.map(|v| self.tuple_as_f64(v)).collect::<Option<Vec<_>>>()?;
+    if xs.is_empty() || xs.iter().any(|x| *x <= 0.0) {
+      return None;
+    }
+    if xs.len() == 1 {
+      return Some(xs[0]);
+    }
+
+    // Take the n-th root of the product, unless the product overflows or underflows; the mean of the
+    // logarithms is used then
+    let n = xs.len() as f64;
+    let product = xs.iter().product::<f64>();
+    if product.is_normal() {
+      Some(product.powf(1.0 / n))
+    } else {
+      Some((xs.iter().map(|x| x.ln()).sum::<f64>() / n).exp())
+    }
+  }
+
+  /// Compute the harmonic mean of the numbers in `i`; `None` if `i` is empty, contains a zero, if the
+  /// reciprocals of the numbers sum up to zero, or if this type is not numeric
+  pub fn harmonic_mean<'a, I: Iterator<Item = &'a Tuple>>(&self, i: I) -> Option<f64> {
+    let xs = i.map(|v| self.tuple_as_f64(v)).collect::<Option<Vec<_>>>()?;
+    if xs.is_empty() || xs.contains(&0.0) {
+      return None;
+    }
+    let n = xs.len() as f64;
+    Some(n / xs.iter().map(|x| 1.0 / x).sum::<f64>()).filter(|m| m.is_finite())
+  }
+
+  /// Convert a number of this type into a floating point number; `None` if this type is not numeric
+  pub fn tuple_as_f64(&self, v: &Tuple) -> Option<f64> {
+    let x = match self {
+      Self::I8 => v.as_i8() as f64,
+      Self::I16 => v.as_i16() as f64,
+      Self::I32 => v.as_i32() as f64,
+      Self::I64 => v.as_i64() as f64,
+      Self::I128 => v.as_i128() as f64,
+      Self::ISize => v.as_isize() as f64,
+
+      // Unsigned
+      Self::U8 => v.as_u8() as f64,
+      Self::U16 => v.as_u16() as f64,
+      Self::U32 => v.as_u32() as f64,
+      Self::U64 => v.as_u64() as f64,
+      Self::U128 => v.as_u128() as f64,
+      Self::USize => v.as_usize() as f64,
+
+      // Floating point
+      Self::F32 => v.as_f32() as f64,
+      Self::F64 => v.as_f64(),
+
+      // Others
+      _ => return None,
+    };
+    Some(x)
+  }
+
   /// Get all integer types
   pub fn integers() -> &'static [ValueType] {
     &[
@@ -384,8 +590,84 @@ impl std::fmt::Display for ValueType {
       // RcString => f.write_str("Rc<String>"),
       DateTime => f.write_str("DateTime"),
       Duration => f.write_str("Duration"),
+      Tuple(tys) => f.write_fmt(format_args!(
+        "({})",
+        tys.iter().map(|t| t.to_string()).collect::<Vec<_>>().join(", ")
+      )),
+      List(ty) => f.write_fmt(format_args!("List<{}>", ty)),
+      Symbol => f.write_str("Symbol"),
+    }
+  }
+}
+
+/// Add up floating points with the Kahan-Babuska (Neumaier) compensated summation, which keeps track of the
+/// low-order bits lost by each addition, so that the error does not grow with the number of summands
+fn compensated_sum<I: Iterator<Item = f64>>(i: I) -> f64 {
+  let (mut sum, mut compensation) = (0.0, 0.0);
+  for x in i {
+    let t = sum + x;
+    if f64::abs(sum) >= f64::abs(x) {
+      compensation += (sum - t) + x;
+    } else {
+      compensation += (x - t) + sum;
+    }
+    sum = t;
+  }
+  sum + compensation
+}
+
+/// Split the inside of a parenthesized or bracketed group by the commas that are not nested in inner groups
+/// or in double quoted strings
+fn split_tuple_components(s: &str) -> Vec<&str> {
+  if s.trim().is_empty() {
+    return vec![];
+  }
+  let mut parts = vec![];
+  let (mut depth, mut start) = (0, 0);
+  let (mut in_quote, mut escaped) = (false, false);
+  for (i, c) in s.char_indices() {
+    if in_quote {
+      match c {
+        _ if escaped => escaped = false,
+        '\\' => escaped = true,
+        '"' => in_quote = false,
+        _ => {}
+      }
+      continue;
+    }
+    match c {
+      '"' => in_quote = true,
+      '(' | '[' => depth += 1,
+      ')' | ']' => depth -= 1,
+      ',' if depth == 0 => {
+        parts.push(&s[start..i]);
+        start = i + 1;
+      }
+      _ => {}
+    }
+  }
+  parts.push(&s[start..]);
+  parts
+}
+
+/// Remove the escapes of the inside of a double quoted string
+fn unescape_string(s: &str) -> String {
+  let mut result = String::with_capacity(s.len());
+  let mut chars = s.chars();
+  while let Some(c) = chars.next() {
+    match c {
+      '\\' => match chars.next() {
+        Some('n') => result.push('\n'),
+        Some('t') => result.push('\t'),
+        Some('r') => result.push('\r'),
+        Some('0') => result.push('\0'),
+        Some(c) => result.push(c),
+        None => result.push('\\'),
+      },
+      c => result.push(c),
     }
   }
+  result
 }
 
 pub trait FromType<T> {
@@ -652,4 +652,39 @@ impl Reduce {
       .chain(self.arg_vars.iter())
       .chain(self.group_by_vars.iter())
   }
+
+  /// Whether each aggregator of the reduce produces a single result variable and has no argument variable
+  fn has_single_result_per_aggregator(&self) -> bool {
+    let num_aggregators = match &self.op {
+      AggregateOp::Multiple(ops) => ops.len(),
+      _ => 1,
+    };
+    self.arg_vars.is_empty() && self.left_vars.len() == num_aggregators
+  }
+
+  /// Whether the other reduce aggregates over the same tuples in the same groups, such that both of them
+  /// can be evaluated by a single reduce with multiple aggregators (see `add_aggregator`)
+  pub fn can_share_aggregation_with(&self, other: &Self) -> bool {
+    self.has_single_result_per_aggregator()
+      && other.has_single_result_per_aggregator()
+      && self.left_vars.iter().all(|v| !other.left_vars.contains(v))
+      && self.group_by_vars == other.group_by_vars
+      && self.other_group_by_vars == other.other_group_by_vars
+      && self.to_aggregate_vars == other.to_aggregate_vars
+      && self.body_formula == other.body_formula
+      && self.group_by_formula == other.group_by_formula
+  }
+
+  /// Evaluate the aggregators of the other reduce along with the ones of this reduce; their results come
+  /// after the results of this reduce
+  pub fn add_aggregator(&mut self, other: Self) {
+    let ops = match (std::mem::replace(&mut self.op, AggregateOp::Count), other.op) {
+      (AggregateOp::Multiple(ops1), AggregateOp::Multiple(ops2)) => vec![ops1, ops2].concat(),
+      (AggregateOp::Multiple(ops1), op2) => vec![ops1, vec![op2]].concat(),
+      (op1, AggregateOp::Multiple(ops2)) => vec![vec![op1], ops2].concat(),
+      (op1, op2) => vec![op1, op2],
+    };
+    self.op = AggregateOp::multiple(ops);
+    self.left_vars.extend(other.left_vars);
+  }
 }
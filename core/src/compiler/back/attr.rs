@@ -72,6 +72,16 @@ impl Attributes {
     }
     None
   }
+
+  pub fn doc_attr(&self) -> Option<&DocAttribute> {
+    for attr in &self.attrs {
+      match attr {
+        Attribute::Doc(d) => return Some(d),
+        _ => {}
+      }
+    }
+    None
+  }
 }
 
 impl<I> From<I> for Attributes
@@ -92,6 +102,7 @@ pub enum Attribute {
   Demand(DemandAttribute),
   MagicSet(MagicSetAttribute),
   InputFile(InputFileAttribute),
+  Doc(DocAttribute),
 }
 
 impl Attribute {
@@ -174,3 +185,9 @@ pub struct MagicSetAttribute;
 pub struct InputFileAttribute {
   pub input_file: InputFile,
 }
+
+/// Doc comment attached to a relation declaration
+#[derive(Clone, Debug, PartialEq)]
+pub struct DocAttribute {
+  pub doc: String,
+}
@@ -107,7 +107,11 @@ impl Program {
             perm_relations.insert(perm_pred.clone(), perm_relation);
 
             // Add permutation update
-            stratum.updates.push(self.perm_to_ram_update(perm_pred, pred, perm));
+            let arity = match &relation.tuple_type {
+              TupleType::Tuple(ts) => ts.len(),
+              TupleType::Value(_) => 1,
+            };
+            stratum.updates.push(self.perm_to_ram_update(perm_pred, pred, perm, arity));
           }
         }
       }
@@ -122,9 +126,10 @@ impl Program {
       accumulated_sources.extend(ram_stratum.relations.iter().map(|(n, _)| n.clone()));
 
       // Get the negative dataflows that can be computed at this stratum
-      let curr_neg_dfs = negative_dataflows
-        .extract_if(|ndf| ndf.sources.is_subset(&accumulated_sources))
-        .collect::<Vec<_>>();
+      let (curr_neg_dfs, rest_neg_dfs): (Vec<_>, Vec<_>) = std::mem::take(&mut negative_dataflows)
+        .into_iter()
+        .partition(|ndf| ndf.sources.is_subset(&accumulated_sources));
+      negative_dataflows = rest_neg_dfs;
 
       // Add the negative dataflow into the stratum
       for neg_df in curr_neg_dfs {
@@ -270,6 +275,9 @@ impl Program {
       None
     };
 
+    // Check doc comment
+    let doc = rel.attributes.doc_attr().map(|doc_attr| doc_attr.doc.clone());
+
     // Check output file
     let output = self.outputs.get(pred).cloned().unwrap_or(OutputOption::Hidden);
 
@@ -284,6 +292,7 @@ impl Program {
       input_file,
       output,
       immutable,
+      doc,
     };
 
     ram_relation
@@ -492,9 +501,10 @@ impl Program {
       } else {
         let perm = goal.permutation(atom);
         if let Some(filter) = Self::atom_filter(atom) {
-          let dataflow = ram::Dataflow::project(
+          let dataflow = Self::permute_dataflow(
             ram::Dataflow::filter(ram::Dataflow::Relation(atom.predicate.clone()), filter),
-            perm.expr(),
+            &perm,
+            atom.args.len(),
           );
           if prop.need_sorted && !perm.order_preserving() {
             ctx.add_permutation(atom.predicate.clone(), perm);
@@ -512,7 +522,7 @@ impl Program {
             ctx.add_permutation(atom.predicate.clone(), perm);
             ram::Dataflow::Relation(perm_name)
           } else {
-            ram::Dataflow::project(ram::Dataflow::Relation(atom.predicate.clone()), perm.expr())
+            Self::permute_dataflow(ram::Dataflow::Relation(atom.predicate.clone()), &perm, atom.args.len())
           }
         }
       }
@@ -1155,10 +1165,25 @@ impl Program {
     }
   }
 
-  fn perm_to_ram_update(&self, perm_pred_name: String, pred_name: &String, perm: &Permutation) -> ram::Update {
+  fn perm_to_ram_update(
+    &self,
+    perm_pred_name: String,
+    pred_name: &String,
+    perm: &Permutation,
+    arity: usize,
+  ) -> ram::Update {
     ram::Update {
       target: perm_pred_name,
-      dataflow: ram::Dataflow::project(ram::Dataflow::relation(pred_name.clone()), perm.expr()),
+      dataflow: Self::permute_dataflow(ram::Dataflow::relation(pred_name.clone()), perm, arity),
+    }
+  }
+
+  /// Permute the columns of a dataflow of the given arity; a pure permutation is a cheap `Rename`, while
+  /// the other permutations (e.g. the ones dropping or nesting columns) need a full projection
+  fn permute_dataflow(dataflow: ram::Dataflow, perm: &Permutation, arity: usize) -> ram::Dataflow {
+    match perm.pure_permutation(arity) {
+      Some(permutation) => dataflow.rename(permutation),
+      None => dataflow.project(perm.expr()),
     }
   }
 
@@ -19,11 +19,13 @@ impl Program {
       // First propagate equality
       optimizations::propagate_equality(rule);
 
-      // Enter the loop of constant folding/propagation
+      // Enter the loop of constant folding/propagation; folding an if-then-else with a constant condition
+      // yields an equality between variables, which needs to be propagated again
       loop {
         let cloned = rule.clone();
         optimizations::constant_fold(rule, &self.function_registry);
         optimizations::constant_prop(rule);
+        optimizations::propagate_equality(rule);
         if &cloned == rule {
           break;
         }
@@ -38,6 +40,9 @@ impl Program {
 
     // Demand Transformation
     if !opt.do_not_demand_transform {
+      if opt.demand_from_queries {
+        self.add_query_demand_attributes()?;
+      }
       self.demand_transform()?;
     }
 
@@ -71,7 +71,11 @@ pub fn constant_fold(rule: &mut Rule, function_registry: &ForeignFunctionRegistr
         AssignExpr::Call(c) => {
           let all_constant = c.args.iter().all(|a| a.is_constant());
           if all_constant {
-            if let Some(f) = runtime.function_registry.get(&c.function) {
+            if let Some(f) = runtime
+              .function_registry
+              .get(&c.function)
+              .filter(|f| f.is_deterministic())
+            {
               let args = c.args.iter().map(|a| a.as_constant().unwrap().clone()).collect();
               let maybe_value = f.execute(args);
               if let Some(value) = maybe_value {
@@ -30,6 +30,114 @@ impl Program {
     // Return ok
     Ok(())
   }
+
+  /// Add demand attributes to the relations which are only queried with some constant arguments, so that
+  /// the demand transformation only derives the tuples relevant to the queries (e.g. `query path(1, x)`)
+  ///
+  /// A relation is considered if it is derived by rules, is not an output relation, and is only used by
+  /// the rules of the output relations and by its own (possibly recursive) rules. The bound arguments are
+  /// the ones given as constants, and need to be the same in every use by the output rules. The attribute
+  /// is only added if the bound values can be passed to every use of the relation, so that no relevant
+  /// tuple is missed.
+  pub fn add_query_demand_attributes(&mut self) -> Result<(), BackCompileError> {
+    let outputs = self.output_relations();
+    let mut adornments = collect_adornments(&self.relations).map_err(BackCompileError::from)?;
+    let mut patterns = vec![];
+    for relation in &self.relations {
+      let is_candidate = !outputs.contains(&relation.predicate)
+        && relation.attributes.demand_attr().is_none()
+        && relation.attributes.magic_set_attr().is_none();
+      if let Some(pattern) = is_candidate
+        .then(|| self.query_pattern(&relation.predicate, &outputs))
+        .flatten()
+      {
+        let adornment = Adornment::try_new(relation, pattern).map_err(BackCompileError::from)?;
+        adornments.insert(relation.predicate.clone(), adornment);
+        if self.rules.iter().all(|rule| has_sips(rule, &adornments)) {
+          patterns.push((
+            relation.predicate.clone(),
+            adornments[&relation.predicate].pattern.to_string(),
+          ));
+        } else {
+          adornments.remove(&relation.predicate);
+        }
+      }
+    }
+
+    // Attach the demand attributes
+    for (predicate, pattern) in patterns {
+      let relation = self.relations.iter_mut().find(|r| r.predicate == predicate).unwrap();
+      relation
+        .attributes
+        .attrs
+        .push(Attribute::Demand(DemandAttribute { pattern }));
+    }
+    Ok(())
+  }
+
+  /// Get the demand pattern of a relation from the constant arguments it is used with in the output rules
+  fn query_pattern(&self, predicate: &String, outputs: &HashSet<String>) -> Option<Pattern> {
+    let mut has_rule = false;
+    let mut pattern: Option<Vec<bool>> = None;
+    for rule in &self.rules {
+      if rule.head_predicate() == predicate {
+        // Only the rules with a single head atom can be transformed
+        rule.head.get_atom()?;
+        has_rule = true;
+      } else if rule.body_literals().any(|l| literal_predicates(l).contains(&predicate)) {
+        if !outputs.contains(rule.head_predicate()) {
+          return None;
+        }
+        for lit in rule.body_literals() {
+          match lit {
+            Literal::Atom(a) if &a.predicate == predicate => {
+              let bound = a.args.iter().map(|t| t.is_constant()).collect::<Vec<_>>();
+              match &pattern {
+                Some(p) if p != &bound => return None,
+                _ => pattern = Some(bound),
+              }
+            }
+            l if literal_predicates(l).contains(&predicate) => return None,
+            _ => {}
+          }
+        }
+      }
+    }
+    let pattern = pattern.filter(|p| has_rule && p.iter().any(|b| *b))?;
+    let pattern = pattern
+      .into_iter()
+      .map(|b| if b { Boundness::Bound } else { Boundness::Free });
+    Some(Pattern(pattern.collect()))
+  }
+}
+
+fn literal_predicates(lit: &Literal) -> Vec<&String> {
+  match lit {
+    Literal::Atom(a) => vec![&a.predicate],
+    Literal::NegAtom(n) => vec![&n.atom.predicate],
+    Literal::Reduce(r) => std::iter::once(&r.body_formula.predicate)
+      .chain(r.group_by_formula.iter().map(|a| &a.predicate))
+      .collect(),
+    _ => vec![],
+  }
+}
+
+/// Check if the bound values of every on-demand atom in the rule can be passed from the rest of the rule
+fn has_sips(rule: &Rule, adornments: &HashMap<String, Adornment>) -> bool {
+  let transformed_rule;
+  let rule = match adornments.get(rule.head_predicate()) {
+    Some(adornment) if rule.head.get_atom().is_some() => {
+      transformed_rule = transform_on_demand_rule(rule, adornment);
+      &transformed_rule
+    }
+    _ => rule,
+  };
+  if contains_on_demand_predicate(rule, adornments) {
+    let (base, to_ground) = split_on_demand_atoms(rule, adornments);
+    generate_sips(&SIPSGenerator::MaxNumAtomsPerLayer, base, to_ground).is_some()
+  } else {
+    true
+  }
 }
 
 fn collect_adornments(relations: &Vec<Relation>) -> Result<HashMap<String, Adornment>, DemandTransformError> {
@@ -105,6 +213,31 @@ fn contains_on_demand_predicate(rule: &Rule, adornments: &HashMap<String, Adornm
 
 fn generate_demand_rules(rule: &Rule, adornments: &HashMap<String, Adornment>) -> Vec<Rule> {
   // First find all the atoms of demanded predicates
+  let (base, to_ground) = split_on_demand_atoms(rule, adornments);
+
+  // Invoke SIPS to find the information passing arcs.
+  // In case a SIPS fails, no demand rule will be generated.
+  let sips_gen = SIPSGenerator::MaxNumAtomsPerLayer;
+  let maybe_arcs = generate_sips(&sips_gen, base, to_ground);
+  if let Some(arcs) = maybe_arcs {
+    // Iterate through all generated arcs
+    let mut demand_rules = vec![];
+    for arc in arcs {
+      // For each arc and each of its rhs, generate a demand rule
+      for to_ground in arc.rhs {
+        let maybe_dr = generate_demand_rule(&arc.lhs, &to_ground.atom, &to_ground.demand);
+        if let Some(dr) = maybe_dr {
+          demand_rules.push(dr);
+        }
+      }
+    }
+    demand_rules
+  } else {
+    vec![]
+  }
+}
+
+fn split_on_demand_atoms(rule: &Rule, adornments: &HashMap<String, Adornment>) -> (Vec<Literal>, Vec<OnDemandAtom>) {
   let mut base = vec![];
   let mut to_ground = vec![];
 
@@ -140,26 +273,7 @@ fn generate_demand_rules(rule: &Rule, adornments: &HashMap<String, Adornment>) -
     }
   }
 
-  // Invoke SIPS to find the information passing arcs.
-  // In case a SIPS fails, no demand rule will be generated.
-  let sips_gen = SIPSGenerator::MaxNumAtomsPerLayer;
-  let maybe_arcs = generate_sips(&sips_gen, base, to_ground);
-  if let Some(arcs) = maybe_arcs {
-    // Iterate through all generated arcs
-    let mut demand_rules = vec![];
-    for arc in arcs {
-      // For each arc and each of its rhs, generate a demand rule
-      for to_ground in arc.rhs {
-        let maybe_dr = generate_demand_rule(&arc.lhs, &to_ground.atom, &to_ground.demand);
-        if let Some(dr) = maybe_dr {
-          demand_rules.push(dr);
-        }
-      }
-    }
-    demand_rules
-  } else {
-    vec![]
-  }
+  (base, to_ground)
 }
 
 fn generate_demand_rule(base: &Vec<Literal>, goal: &Atom, adm: &Adornment) -> Option<Rule> {
@@ -47,6 +47,28 @@ impl Permutation {
     }
   }
 
+  /// Get the column indices if this is a pure permutation of a flat tuple of `arity` columns, that is,
+  /// a flat tuple mentioning each of the columns exactly once
+  pub fn pure_permutation(&self, arity: usize) -> Option<Vec<usize>> {
+    match self {
+      Self::Tuple(ts) if ts.len() == arity => {
+        let mut indices = Vec::with_capacity(arity);
+        let mut visited = vec![false; arity];
+        for t in ts.iter() {
+          match t {
+            Self::Value(i) if *i < arity && !visited[*i] => {
+              visited[*i] = true;
+              indices.push(*i);
+            }
+            _ => return None,
+          }
+        }
+        Some(indices)
+      }
+      _ => None,
+    }
+  }
+
   pub fn order_preserving(&self) -> bool {
     self.order_preserving_helper(&mut 0)
   }
@@ -57,6 +57,7 @@ impl Display for Attribute {
       Self::Demand(d) => d.fmt(f),
       Self::MagicSet(d) => d.fmt(f),
       Self::InputFile(i) => i.fmt(f),
+      Self::Doc(d) => d.fmt(f),
     }
   }
 }
@@ -97,6 +98,12 @@ impl Display for InputFileAttribute {
   }
 }
 
+impl Display for DocAttribute {
+  fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+    f.write_fmt(format_args!("@doc({:?})", self.doc))
+  }
+}
+
 impl Display for Fact {
   fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
     if self.tag.is_some() {
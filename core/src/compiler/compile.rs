@@ -12,6 +12,7 @@ pub fn compile_source_to_ram_with_options<S: front::Source>(
 ) -> Result<ram::Program, CompileErrors> {
   // Construct the compilation context
   let mut front_context = front::FrontContext::new();
+  front_context.set_strict_relation_declarations(options.strict_relation_declarations);
   match front_context.compile_source(source) {
     Ok(_) => {}
     Err(error_ctx) => {
@@ -12,6 +12,7 @@ pub struct Analysis {
   pub input_files_analysis: InputFilesAnalysis,
   pub output_files_analysis: OutputFilesAnalysis,
   pub hidden_analysis: HiddenRelationAnalysis,
+  pub doc_comment_analysis: DocCommentAnalysis,
   pub aggregation_analysis: AggregationAnalysis,
   pub character_literal_analysis: CharacterLiteralAnalysis,
   pub constant_decl_analysis: ConstantDeclAnalysis,
@@ -19,6 +20,10 @@ pub struct Analysis {
   pub type_inference: TypeInference,
   pub boundness_analysis: BoundnessAnalysis,
   pub demand_attr_analysis: DemandAttributeAnalysis,
+  pub k_attr_analysis: KAttributeAnalysis,
+  pub unused_function_analysis: UnusedFunctionAnalysis,
+  pub dead_relation_analysis: DeadRelationAnalysis,
+  pub exclusive_relation_analysis: ExclusiveRelationAnalysis,
 }
 
 impl Analysis {
@@ -33,6 +38,7 @@ impl Analysis {
       input_files_analysis: InputFilesAnalysis::new(),
       output_files_analysis: OutputFilesAnalysis::new(),
       hidden_analysis: HiddenRelationAnalysis::new(),
+      doc_comment_analysis: DocCommentAnalysis::new(),
       aggregation_analysis: AggregationAnalysis::new(),
       character_literal_analysis: CharacterLiteralAnalysis::new(),
       constant_decl_analysis: ConstantDeclAnalysis::new(),
@@ -40,6 +46,10 @@ impl Analysis {
       type_inference: TypeInference::new(function_registry, predicate_registry),
       boundness_analysis: BoundnessAnalysis::new(predicate_registry),
       demand_attr_analysis: DemandAttributeAnalysis::new(),
+      k_attr_analysis: KAttributeAnalysis::new(),
+      unused_function_analysis: UnusedFunctionAnalysis::new(),
+      dead_relation_analysis: DeadRelationAnalysis::new(),
+      exclusive_relation_analysis: ExclusiveRelationAnalysis::new(),
     }
   }
 
@@ -47,12 +57,16 @@ impl Analysis {
     let mut analyzers = (
       &mut self.input_files_analysis,
       &mut self.hidden_analysis,
+      &mut self.doc_comment_analysis,
       &mut self.output_files_analysis,
       &mut self.aggregation_analysis,
       &mut self.character_literal_analysis,
       &mut self.constant_decl_analysis,
       &mut self.invalid_constant,
       &mut self.invalid_wildcard,
+      &mut self.dead_relation_analysis,
+      &mut self.exclusive_relation_analysis,
+      &mut self.k_attr_analysis,
     );
     analyzers.walk_items(items);
   }
@@ -66,12 +80,14 @@ impl Analysis {
       &mut self.type_inference,
       &mut self.demand_attr_analysis,
       &mut self.boundness_analysis,
+      &mut self.unused_function_analysis,
     );
     analyzers.walk_items(items);
   }
 
   pub fn post_analysis(&mut self) {
     self.head_relation_analysis.compute_errors();
+    self.exclusive_relation_analysis.compute_errors();
     self.type_inference.check_query_predicates();
     self.type_inference.infer_types();
     self.demand_attr_analysis.check_arity(&self.type_inference);
@@ -86,8 +102,10 @@ impl Analysis {
     error_ctx.extend(&mut self.character_literal_analysis.errors);
     error_ctx.extend(&mut self.constant_decl_analysis.errors);
     error_ctx.extend(&mut self.head_relation_analysis.errors);
+    error_ctx.extend(&mut self.exclusive_relation_analysis.errors);
     error_ctx.extend(&mut self.type_inference.errors);
     error_ctx.extend(&mut self.boundness_analysis.errors);
     error_ctx.extend(&mut self.demand_attr_analysis.errors);
+    error_ctx.extend(&mut self.k_attr_analysis.errors);
   }
 }
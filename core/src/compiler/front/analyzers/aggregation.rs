@@ -1,3 +1,5 @@
+use std::collections::*;
+
 use super::super::ast::*;
 use super::super::error::*;
 use super::super::source::*;
@@ -16,10 +18,63 @@ impl AggregationAnalysis {
 }
 
 impl NodeVisitor for AggregationAnalysis {
+  fn visit_rule(&mut self, rule: &Rule) {
+    // Collect all the variable occurrences in the body and all the aggregations in the rule; occurrences
+    // in the head are left to the boundness analysis, which reports them in more detail
+    let mut all_vars = VariableCollector::default();
+    all_vars.walk_formula(rule.body());
+    let mut reduces = ReduceCollector::default();
+    reduces.walk_rule(rule);
+
+    // Check that no binding variable of an aggregation occurs outside of that aggregation; sibling
+    // aggregations may bind the same name, e.g. `n := count(x: p(x)), s := sum(x: p(x))`
+    for (bindings, _) in &reduces.reduces {
+      for binding in bindings {
+        let binding_reduces = reduces.reduces.iter().filter(|(bs, _)| bs.contains(binding)).collect::<Vec<_>>();
+        let escaped = all_vars.vars.iter().find(|(name, loc)| {
+          name == binding && binding_reduces.iter().all(|(_, inside_locs)| !inside_locs.contains(loc))
+        });
+        if let Some((name, loc)) = escaped {
+          self.errors.push(AggregationAnalysisError::BindingVariableEscapes {
+            name: name.clone(),
+            loc: loc.clone(),
+          });
+        }
+      }
+    }
+  }
+
   fn visit_reduce(&mut self, reduce: &Reduce) {
     // Check max/min arg
     match &reduce.operator().node {
-      ReduceOperatorNode::Max | ReduceOperatorNode::Min => {}
+      ReduceOperatorNode::Max | ReduceOperatorNode::Min | ReduceOperatorNode::Rank => {}
+      ReduceOperatorNode::ArgMax | ReduceOperatorNode::ArgMin => {
+        // The only argument is the key to compare the tuples by
+        if reduce.args().len() != 1 {
+          self.errors.push(AggregationAnalysisError::ArgMinMaxNumArguments {
+            agg: reduce.operator().to_string(),
+            num_args: reduce.args().len(),
+            loc: reduce.location().clone(),
+          })
+        }
+      }
+      ReduceOperatorNode::TopK(_) => {
+        // Top-k aggregation ranks by the binding variable when there is an argument, which has to be unique
+        if reduce.args().len() > 1 {
+          self.errors.push(AggregationAnalysisError::TopKMultipleArguments {
+            num_args: reduce.args().len(),
+            loc: reduce.location().clone(),
+          })
+        }
+      }
+      ReduceOperatorNode::Window { .. } => {
+        // Window aggregation needs the arguments to order the tuples
+        if reduce.args().is_empty() {
+          self.errors.push(AggregationAnalysisError::WindowWithoutOrdering {
+            loc: reduce.location().clone(),
+          })
+        }
+      }
       ReduceOperatorNode::Forall => {
         // Check the body of forall expression
         match reduce.body() {
@@ -29,10 +84,17 @@ impl NodeVisitor for AggregationAnalysis {
           }),
         }
       }
-      ReduceOperatorNode::Unknown(a) => self.errors.push(AggregationAnalysisError::UnknownAggregator {
-        agg: a.clone(),
-        loc: reduce.location().clone(),
-      }),
+      ReduceOperatorNode::Unknown(a) => match suggest_aggregator(a) {
+        Some(suggestion) => self.errors.push(AggregationAnalysisError::MalformedAggregator {
+          agg: a.clone(),
+          suggestion,
+          loc: reduce.location().clone(),
+        }),
+        None => self.errors.push(AggregationAnalysisError::UnknownAggregator {
+          agg: a.clone(),
+          loc: reduce.location().clone(),
+        }),
+      },
       _ => {
         if !reduce.args().is_empty() {
           self
@@ -48,6 +110,9 @@ impl NodeVisitor for AggregationAnalysis {
     if reduce.bindings().is_empty() {
       match &reduce.operator().node {
         ReduceOperatorNode::Exists
+        | ReduceOperatorNode::Any
+        | ReduceOperatorNode::ArgMin
+        | ReduceOperatorNode::ArgMax
         | ReduceOperatorNode::Forall
         | ReduceOperatorNode::Unknown(_) => {}
         r => {
@@ -61,12 +126,153 @@ impl NodeVisitor for AggregationAnalysis {
   }
 }
 
+/// The aggregators that are written without parameters, e.g. `count`
+const PLAIN_AGGREGATORS: &[&str] = &[
+  "count",
+  "count_distinct",
+  "sum",
+  "prod",
+  "weighted_sum",
+  "variance",
+  "stddev",
+  "median",
+  "collect",
+  "list",
+  "geometric_mean",
+  "harmonic_mean",
+  "min",
+  "max",
+  "argmin",
+  "argmax",
+  "unique",
+  "any",
+  "rank",
+  "sample",
+  "normalize",
+  "exists",
+  "forall",
+];
+
+/// The aggregators that can be used inside of a window, e.g. `sum` in `window<3, sum>`
+const WINDOW_AGGREGATORS: &[&str] = &[
+  "count",
+  "count_distinct",
+  "sum",
+  "prod",
+  "variance",
+  "stddev",
+  "median",
+  "geometric_mean",
+  "harmonic_mean",
+  "min",
+  "max",
+  "unique",
+];
+
+/// Suggest the correct form of an unknown aggregator, given its raw spelling, if it looks like a known one
+/// with the wrong casing or parameters, e.g. `Count`, `top` or `count<3>`
+fn suggest_aggregator(raw: &str) -> Option<String> {
+  let (name, params) = match raw.split_once('<') {
+    Some((name, params)) => (name, Some(params.trim_end_matches('>'))),
+    None => (raw, None),
+  };
+  let lower = name.to_lowercase();
+  let params = params.map(|p| p.split(',').map(|p| p.trim()).collect::<Vec<_>>());
+  match (lower.as_str(), params.as_deref()) {
+    ("top", None | Some([_, _, ..])) => {
+      Some("`top` requires the number of tuples to keep, e.g. `top<3>`".to_string())
+    }
+    ("categorical", None | Some([_, _, ..])) => {
+      Some("`categorical` requires the number of samples to draw, e.g. `categorical<3>`".to_string())
+    }
+    ("window", None | Some([_]) | Some([_, _, _, ..])) => {
+      Some("`window` requires a size and an aggregator, e.g. `window<3, sum>`".to_string())
+    }
+    ("top" | "categorical", Some([k])) => match k.parse::<i64>() {
+      Ok(k) if k > 0 => Some(format!("did you mean `{}<{}>`?", lower, k)),
+      _ => Some(format!("the parameter of `{}` must be a positive integer, e.g. `{}<3>`", lower, lower)),
+    },
+    ("window", Some([k, op])) => {
+      let op = op.to_lowercase();
+      match k.parse::<i64>() {
+        Ok(k) if k <= 0 => Some(format!(
+          "the size of `window` must be a positive integer, e.g. `window<3, {}>`",
+          op
+        )),
+        _ if !WINDOW_AGGREGATORS.contains(&op.as_str()) => Some(format!(
+          "`{}` cannot be used inside of a window; expected one of {}",
+          op,
+          WINDOW_AGGREGATORS
+            .iter()
+            .map(|a| format!("`{}`", a))
+            .collect::<Vec<_>>()
+            .join(", ")
+        )),
+        _ => Some(format!("did you mean `window<{}, {}>`?", k, op)),
+      }
+    }
+    (n, None) if n != name && PLAIN_AGGREGATORS.contains(&n) => Some(format!("did you mean `{}`?", n)),
+    (n, Some(_)) if PLAIN_AGGREGATORS.contains(&n) => {
+      Some(format!("`{}` does not take parameters, did you mean `{}`?", n, n))
+    }
+    _ => None,
+  }
+}
+
+/// Collects all the variable occurrences, along with their locations
+#[derive(Default)]
+struct VariableCollector {
+  vars: Vec<(String, Loc)>,
+}
+
+impl NodeVisitor for VariableCollector {
+  fn visit_variable(&mut self, variable: &Variable) {
+    self.vars.push((variable.name().to_string(), variable.location().clone()));
+  }
+}
+
+/// Collects the binding variables of each aggregation, along with the locations of variables inside it
+#[derive(Default)]
+struct ReduceCollector {
+  reduces: Vec<(Vec<String>, HashSet<Loc>)>,
+}
+
+impl ReduceCollector {
+  fn add_reduce<'a, I: Iterator<Item = &'a str>>(&mut self, bindings: I, inside: VariableCollector) {
+    let bindings = bindings.map(|b| b.to_string()).collect();
+    let inside_locs = inside.vars.into_iter().map(|(_, loc)| loc).collect();
+    self.reduces.push((bindings, inside_locs));
+  }
+}
+
+impl NodeVisitor for ReduceCollector {
+  fn visit_reduce(&mut self, reduce: &Reduce) {
+    let mut inside = VariableCollector::default();
+    inside.walk_reduce(reduce);
+
+    // Binding variables that are also the result of the aggregation (e.g. `x = min(x: ...)`) are allowed to escape
+    let left = reduce.left().iter().filter_map(|l| l.name()).collect::<Vec<_>>();
+    self.add_reduce(reduce.binding_names().filter(|b| !left.contains(b)), inside);
+  }
+
+  fn visit_forall_exists_reduce(&mut self, reduce: &ForallExistsReduce) {
+    let mut inside = VariableCollector::default();
+    inside.walk_forall_exists_reduce(reduce);
+    self.add_reduce(reduce.binding_names(), inside);
+  }
+}
+
 #[derive(Debug, Clone)]
 pub enum AggregationAnalysisError {
   NonMinMaxAggregationHasArgument { op: ReduceOperator },
   UnknownAggregator { agg: String, loc: Loc },
+  MalformedAggregator { agg: String, suggestion: String, loc: Loc },
   ForallBodyNotImplies { loc: Loc },
   EmptyBinding { agg: String, loc: Loc },
+  BindingVariableEscapes { name: String, loc: Loc },
+  WindowWithoutOrdering { loc: Loc },
+  TopKMultipleArguments { num_args: usize, loc: Loc },
+  ArgMinMaxNumArguments { agg: String, num_args: usize, loc: Loc },
 }
 
 impl FrontCompileErrorTrait for AggregationAnalysisError {
@@ -86,6 +292,9 @@ impl FrontCompileErrorTrait for AggregationAnalysisError {
       Self::UnknownAggregator { agg, loc } => {
         format!("unknown aggregator `{}`\n{}", agg, loc.report(src))
       }
+      Self::MalformedAggregator { agg, suggestion, loc } => {
+        format!("unknown aggregator `{}`; {}\n{}", agg, suggestion, loc.report(src))
+      }
       Self::ForallBodyNotImplies { loc } => {
         format!(
           "the body of forall aggregation must be an `implies` formula\n{}",
@@ -99,6 +308,34 @@ impl FrontCompileErrorTrait for AggregationAnalysisError {
           loc.report(src),
         )
       }
+      Self::BindingVariableEscapes { name, loc } => {
+        format!(
+          "the binding variable `{}` of an aggregation cannot be used outside of the aggregation\n{}",
+          name,
+          loc.report(src),
+        )
+      }
+      Self::WindowWithoutOrdering { loc } => {
+        format!(
+          "window aggregation requires arguments to order the tuples, e.g. `window<3, sum>[t](x: ...)`\n{}",
+          loc.report(src)
+        )
+      }
+      Self::TopKMultipleArguments { num_args, loc } => {
+        format!(
+          "top-k aggregation expects at most one argument, found {}\n{}",
+          num_args,
+          loc.report(src)
+        )
+      }
+      Self::ArgMinMaxNumArguments { agg, num_args, loc } => {
+        format!(
+          "`{}` aggregation expects exactly one argument as the key, found {}\n{}",
+          agg,
+          num_args,
+          loc.report(src)
+        )
+      }
     }
   }
 }
@@ -8,14 +8,32 @@ use crate::compiler::front::visitor::*;
 #[derive(Debug, Clone)]
 pub struct RuleContext {
   pub head_vars: Vec<(String, Loc)>,
+
+  /// The location and the variables of each disjunct of a disjunctive head; empty for the other heads
+  pub head_disjuncts: Vec<(Loc, Vec<String>)>,
+
   pub body: DisjunctionContext,
 }
 
 impl RuleContext {
   pub fn from_rule(rule: &Rule) -> Self {
     let head_vars = collect_vars_in_head(rule.head());
+    let head_disjuncts = match &rule.head().node {
+      RuleHeadNode::Atom(_) => vec![],
+      RuleHeadNode::Disjunction(d) => d
+        .iter()
+        .map(|atom| {
+          let vars = collect_vars_in_atom(atom).into_iter().map(|(v, _)| v).collect();
+          (atom.location().clone(), vars)
+        })
+        .collect(),
+    };
     let body = DisjunctionContext::from_formula(rule.body());
-    Self { head_vars, body }
+    Self {
+      head_vars,
+      head_disjuncts,
+      body,
+    }
   }
 
   pub fn from_qualified(bindings: &Vec<VariableBinding>, args: &Vec<Variable>, body: &Formula) -> Self {
@@ -29,7 +47,11 @@ impl RuleContext {
       .collect();
     let head_vars = vec![bindings, args].concat();
     let body = DisjunctionContext::from_formula(body);
-    Self { head_vars, body }
+    Self {
+      head_vars,
+      head_disjuncts: vec![],
+      body,
+    }
   }
 
   pub fn compute_boundness(
@@ -38,13 +60,34 @@ impl RuleContext {
     bounded_exprs: &Vec<Expr>,
   ) -> Result<BTreeSet<String>, Vec<BoundnessAnalysisError>> {
     let bounded_vars = self.body.compute_boundness(predicate_bindings, bounded_exprs)?;
+    self.check_head_boundness(&bounded_vars)?;
+    Ok(bounded_vars)
+  }
+
+  /// Check that all the head variables are bounded by the given set of bounded variables
+  ///
+  /// For a disjunctive head, every variable of every disjunct has to be bounded, since each disjunct is
+  /// derived on its own
+  pub fn check_head_boundness(&self, bounded_vars: &BTreeSet<String>) -> Result<(), Vec<BoundnessAnalysisError>> {
     for (var_name, var_loc) in &self.head_vars {
       if !bounded_vars.contains(var_name) {
-        let err = BoundnessAnalysisError::HeadExprUnbound { loc: var_loc.clone() };
+        let disjunct = self.head_disjuncts.iter().position(|(_, vars)| vars.contains(var_name));
+        let err = match (self.body.aggregation_binding_var(var_name), disjunct) {
+          (Some(agg_loc), _) => BoundnessAnalysisError::HeadUsesAggregationInternal {
+            name: var_name.clone(),
+            agg_loc: agg_loc.clone(),
+            head_loc: var_loc.clone(),
+          },
+          (None, Some(disjunct_index)) => BoundnessAnalysisError::DisjunctHeadVarUnbound {
+            disjunct_index,
+            loc: self.head_disjuncts[disjunct_index].0.clone(),
+          },
+          (None, None) => BoundnessAnalysisError::HeadExprUnbound { loc: var_loc.clone() },
+        };
         return Err(vec![err]);
       }
     }
-    Ok(bounded_vars)
+    Ok(())
   }
 }
 
@@ -62,9 +105,7 @@ impl DisjunctionContext {
         let cp = ctxs.multi_cartesian_product();
         cp.map(ConjunctionContext::join).collect()
       }
-      Formula::Implies(_) => {
-        panic!("Unexpected `implies` visited during boundness analysis; implies should be rewritten by previous transformations")
-      }
+      Formula::Implies(i) => Self::from_formula(&i.to_disjunction()).conjuncts,
       Formula::Atom(a) => vec![ConjunctionContext::from_atom(a)],
       Formula::NegAtom(a) => vec![ConjunctionContext::from_neg_atom(a)],
       Formula::Constraint(a) => vec![ConjunctionContext::from_constraint(a)],
@@ -76,6 +117,16 @@ impl DisjunctionContext {
     Self { conjuncts }
   }
 
+  /// Find the location of an aggregation in which the variable is a binding variable
+  pub fn aggregation_binding_var(&self, var_name: &str) -> Option<&Loc> {
+    self
+      .conjuncts
+      .iter()
+      .flat_map(|c| c.agg_contexts.iter())
+      .find(|agg_ctx| agg_ctx.binding_vars.iter().any(|b| b == var_name))
+      .map(|agg_ctx| &agg_ctx.loc)
+  }
+
   pub fn compute_boundness(
     &self,
     predicate_bindings: &ForeignPredicateBindings,
@@ -187,6 +238,7 @@ impl ConjunctionContext {
 #[derive(Debug, Clone)]
 pub struct AggregationContext {
   pub result_vars: Vec<Variable>,
+  pub result_type: Option<Type>,
   pub binding_vars: Vec<String>,
   pub arg_vars: Vec<Variable>,
   pub body: Box<RuleContext>,
@@ -195,6 +247,7 @@ pub struct AggregationContext {
   pub joined_body_formula: Formula,
   pub group_by: Option<(Box<RuleContext>, Vec<Variable>, Formula)>,
   pub aggregate_op: ReduceOperatorNode,
+  pub loc: Loc,
 }
 
 impl AggregationContext {
@@ -210,6 +263,15 @@ impl AggregationContext {
     self.arg_vars.iter().map(|n| n.name().to_string()).collect()
   }
 
+  /// The binding variables which are also results of the aggregation, e.g. `x` in `w := normalize(c, x: ...)`
+  pub fn output_binding_variable_names(&self) -> Vec<String> {
+    if self.aggregate_op.has_output_bindings() {
+      self.binding_vars.iter().skip(1).cloned().collect()
+    } else {
+      vec![]
+    }
+  }
+
   pub fn group_by_head_variable_names(&self) -> BTreeSet<String> {
     if let Some((_, vars, _)) = &self.group_by {
       vars.iter().map(|n| n.name().to_string()).collect()
@@ -218,6 +280,38 @@ impl AggregationContext {
     }
   }
 
+  /// A key identifying the body and group-by of the aggregation; aggregations with the same key
+  /// aggregate over the same tuples in the same groups
+  pub fn shared_body_key(&self) -> String {
+    if self.aggregate_op.has_ordered_bindings() {
+      // The key and the binding variables are aggregated together, in order
+      format!(
+        "{} | {:?} | {:?}",
+        self.joined_body_formula,
+        self.group_by_head_variable_names(),
+        self.ordered_aggregated_variable_names(),
+      )
+    } else {
+      format!(
+        "{} | {:?} | {:?} | {:?}",
+        self.joined_body_formula,
+        self.group_by_head_variable_names(),
+        self.argument_variable_names(),
+        self.binding_variable_names(),
+      )
+    }
+  }
+
+  /// The key followed by the binding variables of an `argmin`/`argmax` aggregation, in the order of the output
+  pub fn ordered_aggregated_variable_names(&self) -> Vec<String> {
+    self
+      .arg_vars
+      .iter()
+      .map(|v| v.name().to_string())
+      .chain(self.binding_vars.iter().cloned())
+      .collect()
+  }
+
   pub fn from_reduce(reduce: &Reduce) -> Self {
     // Merge the body and the group_by formula if presented
     let body = RuleContext::from_qualified(reduce.bindings(), reduce.args(), reduce.body());
@@ -241,6 +335,7 @@ impl AggregationContext {
     // Construct self
     Self {
       result_vars: reduce.left_variables().cloned().collect(),
+      result_type: reduce.result_type().cloned(),
       binding_vars: reduce.binding_names().map(|n| n.to_string()).collect(),
       arg_vars: reduce.args().clone(),
       body: Box::new(body),
@@ -249,6 +344,7 @@ impl AggregationContext {
       joined_body_formula,
       group_by,
       aggregate_op: reduce.operator().node.clone(),
+      loc: reduce.location().clone(),
     }
   }
 
@@ -265,32 +361,69 @@ impl AggregationContext {
       group_by_ctx.compute_boundness(predicate_bindings, bounded_exprs)?;
     }
 
-    // Add all the bounded variables in the aggregation body
-    bounded.extend(self.joined_body.compute_boundness(predicate_bindings, bounded_exprs)?);
+    // Compute the bounded variables in the aggregation body
+    let body_bounded = self
+      .joined_body
+      .body
+      .compute_boundness(predicate_bindings, bounded_exprs)?;
 
-    // Remove the qualified variable
-    for binding_name in &self.binding_vars {
-      bounded.remove(binding_name);
-    }
-
-    // Check if the arguments are bounded
+    // Check if the arguments are bounded; this is done before checking the binding variables so that
+    // unbound arguments are reported with a dedicated error
     for arg_var in &self.arg_vars {
-      if !bounded.contains(arg_var.name()) {
+      if !body_bounded.contains(arg_var.name()) || self.binding_vars.iter().any(|b| b == arg_var.name()) {
+        let mut atoms = vec![];
+        collect_atoms_mentioning_var(&self.joined_body_formula, arg_var.name(), &mut atoms);
         let err = BoundnessAnalysisError::ReduceArgUnbound {
+          name: arg_var.name().to_string(),
           loc: arg_var.location().clone(),
+          atoms,
         };
         return Err(vec![err]);
       }
     }
 
-    // Add args and result variables
+    // Check if the binding variables are bounded
+    self.joined_body.check_head_boundness(&body_bounded)?;
+
+    // Add all the bounded variables in the aggregation body except for the qualified variables
+    bounded.extend(body_bounded.into_iter().filter(|v| !self.binding_vars.contains(v)));
+
+    // Add args and result variables; the key of `argmin`/`argmax` is part of the result instead
     bounded.extend(self.result_vars.iter().map(|v| v.name().to_string()));
-    bounded.extend(self.arg_vars.iter().map(|v| v.name().to_string()));
+    if !self.aggregate_op.is_arg_min_max() {
+      bounded.extend(self.arg_vars.iter().map(|v| v.name().to_string()));
+    }
+    bounded.extend(self.output_binding_variable_names());
 
     Ok(bounded)
   }
 }
 
+/// Collect the locations of the (possibly negated) atoms inside of a formula which mention the given variable.
+/// Nested aggregations are not visited since their variables are scoped.
+fn collect_atoms_mentioning_var(formula: &Formula, var_name: &str, atoms: &mut Vec<Loc>) {
+  let mentions = |atom: &Atom| collect_vars_in_atom(atom).iter().any(|(n, _)| n == var_name);
+  match formula {
+    Formula::Conjunction(c) => c.args().for_each(|a| collect_atoms_mentioning_var(a, var_name, atoms)),
+    Formula::Disjunction(d) => d.args().for_each(|a| collect_atoms_mentioning_var(a, var_name, atoms)),
+    Formula::Implies(i) => {
+      collect_atoms_mentioning_var(i.left(), var_name, atoms);
+      collect_atoms_mentioning_var(i.right(), var_name, atoms);
+    }
+    Formula::Atom(a) => {
+      if mentions(a) {
+        atoms.push(a.location().clone())
+      }
+    }
+    Formula::NegAtom(n) => {
+      if mentions(n.atom()) {
+        atoms.push(n.location().clone())
+      }
+    }
+    Formula::Constraint(_) | Formula::Reduce(_) | Formula::ForallExistsReduce(_) => {}
+  }
+}
+
 fn collect_vars_in_head(head: &RuleHead) -> Vec<(String, Loc)> {
   match &head.node {
     RuleHeadNode::Atom(atom) => collect_vars_in_atom(atom),
@@ -6,7 +6,10 @@ pub enum BoundnessAnalysisError {
   UnboundVariable { var_name: String, var_loc: Loc },
   HeadExprUnbound { loc: Loc },
   ConstraintUnbound { loc: Loc },
-  ReduceArgUnbound { loc: Loc },
+  ReduceArgUnbound { name: String, loc: Loc, atoms: Vec<Loc> },
+  HeadUsesAggregationInternal { name: String, agg_loc: Loc, head_loc: Loc },
+  DisjunctHeadVarUnbound { disjunct_index: usize, loc: Loc },
+  ForeignPredicateArgNotBound { pred: String, index: usize, loc: Loc },
 }
 
 impl FrontCompileErrorTrait for BoundnessAnalysisError {
@@ -25,8 +28,53 @@ impl FrontCompileErrorTrait for BoundnessAnalysisError {
       Self::ConstraintUnbound { loc } => {
         format!("Constraint unbound\n{}", loc.report(src))
       }
-      Self::ReduceArgUnbound { loc } => {
-        format!("The argument for the aggregation is unbounded\n{}", loc.report(src))
+      Self::ReduceArgUnbound { name, loc, atoms } => {
+        let msg = format!(
+          "The argument `{}` for the aggregation is unbounded\n{}",
+          name,
+          loc.report(src)
+        );
+        if atoms.is_empty() {
+          format!(
+            "{}\nVariable `{}` does not occur in any atom of the aggregation body; did you mean to join on `{}` inside of the aggregation?",
+            msg, name, name
+          )
+        } else {
+          let atoms = atoms.iter().map(|l| l.report(src)).collect::<Vec<_>>().join("\n");
+          format!(
+            "{}\nVariable `{}` occurs in the following atoms of the aggregation body, but none of them binds it:\n{}\nDid you mean to join on `{}` with a positive atom in every branch of the aggregation body?",
+            msg, name, atoms, name
+          )
+        }
+      }
+      Self::HeadUsesAggregationInternal {
+        name,
+        agg_loc,
+        head_loc,
+      } => {
+        format!(
+          "Variable `{}` in the head of the rule is only bound inside of an aggregation:\n{}\nwhile it is used here:\n{}\nBinding variables of an aggregation are not visible outside of it; consider using a result variable of the aggregation instead",
+          name,
+          agg_loc.report(src),
+          head_loc.report(src),
+        )
+      }
+      Self::DisjunctHeadVarUnbound { disjunct_index, loc } => {
+        format!(
+          "Argument of disjunct #{} in the head of the rule is unbounded\n{}\nEvery variable of every disjunct has to be bound by the body of the rule",
+          disjunct_index,
+          loc.report(src)
+        )
+      }
+      Self::ForeignPredicateArgNotBound { pred, index, loc } => {
+        format!(
+          "Argument #{} of foreign predicate `{}` is bound, but it is given an unbound value
+{}
+The bound arguments of a foreign predicate have to be bound by the rest of the rule before it is evaluated",
+          index,
+          pred,
+          loc.report(src)
+        )
       }
     }
   }
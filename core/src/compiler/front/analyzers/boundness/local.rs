@@ -2,6 +2,7 @@ use std::collections::*;
 
 use super::*;
 
+use crate::common::foreign_predicate::BindingPattern;
 use crate::compiler::front::ast::*;
 use crate::compiler::front::visitor::*;
 
@@ -12,6 +13,7 @@ pub struct LocalBoundnessAnalysisContext<'a> {
   pub dependencies: Vec<BoundnessDependency>,
   pub variable_locations: HashMap<String, Vec<Loc>>,
   pub constraints: Vec<Loc>,
+  pub foreign_predicate_atoms: Vec<(String, BindingPattern, Vec<Loc>)>,
   pub bounded_variables: BTreeSet<String>,
   pub errors: Vec<BoundnessAnalysisError>,
 }
@@ -23,6 +25,12 @@ impl<'a> NodeVisitor for LocalBoundnessAnalysisContext<'a> {
       let to_bound = atom.iter_arguments().enumerate().filter_map(|(i, a)| if binding[i].is_free() { Some(a.location().clone()) } else { None } ).collect();
       let dep = BoundnessDependency::ForeignPredicateArgs(bounded, to_bound);
       self.dependencies.push(dep);
+
+      // Remember the arguments, so that the unbound ones in bound positions are reported on their own
+      let arg_locs = atom.iter_arguments().map(|a| a.location().clone()).collect();
+      self
+        .foreign_predicate_atoms
+        .push((atom.predicate().to_string(), binding.clone(), arg_locs));
     } else {
       for arg in atom.iter_arguments() {
         let loc = arg.location().clone();
@@ -111,6 +119,7 @@ impl<'a> LocalBoundnessAnalysisContext<'a> {
       dependencies: Vec::new(),
       variable_locations: HashMap::new(),
       constraints: Vec::new(),
+      foreign_predicate_atoms: Vec::new(),
       bounded_variables: BTreeSet::new(),
       errors: Vec::new(),
     }
@@ -205,10 +214,32 @@ impl<'a> LocalBoundnessAnalysisContext<'a> {
       }
     }
 
+    // Check if the bound arguments of foreign predicates are bounded; when they are not, the free arguments
+    // cannot be bounded by the predicate either, which is a consequence not worth another error
+    let mut reported_locs = HashSet::new();
+    for (pred, binding, arg_locs) in &self.foreign_predicate_atoms {
+      let mut has_unbound_arg = false;
+      for (index, loc) in arg_locs.iter().enumerate() {
+        if binding[index].is_bound() && !get(&self.expr_boundness, loc) {
+          self.errors.push(BoundnessAnalysisError::ForeignPredicateArgNotBound {
+            pred: pred.clone(),
+            index,
+            loc: loc.clone(),
+          });
+          has_unbound_arg = true;
+        }
+      }
+      if has_unbound_arg {
+        reported_locs.extend(arg_locs.iter());
+      }
+    }
+
     // Check if all variables are bounded
     for (var, locs) in &self.variable_locations {
       let var_bounded = locs.iter().all(|loc| get(&self.expr_boundness, loc));
-      if !var_bounded {
+      if !var_bounded && locs.iter().any(|loc| reported_locs.contains(loc)) {
+        continue;
+      } else if !var_bounded {
         self.errors.push(BoundnessAnalysisError::UnboundVariable {
           var_name: var.clone(),
           var_loc: locs[0].clone(),
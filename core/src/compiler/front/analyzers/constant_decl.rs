@@ -60,16 +60,18 @@ impl ConstantDeclAnalysis {
   }
 
   pub fn process_enum_type_decl(&mut self, etd: &ast::EnumTypeDecl) -> Result<(), ConstantDeclError> {
+    // With `@allow_negative`, the IDs can be negative and non-monotonic, and only need to be unique
+    let allow_negative = etd.allow_negative();
     let extract_value = |member: &ast::EnumTypeMember, prev_max: Option<i64>| -> Result<i64, ConstantDeclError> {
       // First check if there is an integer number assignment to the enum
       match member.assigned_number() {
         Some(c) => match &c.node {
           // If there is, we check if the integer is greater than or equal to zero and greater than the previous maximum
-          ast::ConstantNode::Integer(i) if *i >= 0 => {
+          ast::ConstantNode::Integer(i) if *i >= 0 || allow_negative => {
             let i = *i;
             // Check if we have a previous number already
             if let Some(prev_max) = prev_max {
-              if i > prev_max {
+              if i > prev_max || allow_negative {
                 // If the number is greater than previous number, then ok to directly assign the number
                 return Ok(i);
               } else {
@@ -100,8 +102,16 @@ impl ConstantDeclAnalysis {
       }
     };
 
+    let id_type = etd.id_type();
+    let mut assigned_ids = HashSet::new();
     let mut process_member = |member: &ast::EnumTypeMember, id: i64| -> Result<(), ConstantDeclError> {
-      if let Some((first_decl_loc, _, _)) = self.variables.get(member.name()) {
+      if !assigned_ids.insert(id) {
+        Err(ConstantDeclError::EnumIDAlreadyAssigned {
+          curr_name: member.name().to_string(),
+          id,
+          loc: member.location().clone(),
+        })
+      } else if let Some((first_decl_loc, _, _)) = self.variables.get(member.name()) {
         Err(ConstantDeclError::DuplicatedConstant {
           name: member.name().to_string(),
           first_decl: first_decl_loc.clone(),
@@ -111,7 +121,7 @@ impl ConstantDeclAnalysis {
         // Then store the variable into the storage
         self.variables.insert(
           member.name().to_string(),
-          (member.location().clone(), Some(Type::usize()), Constant::integer(id as i64))
+          (member.location().clone(), Some(id_type.clone()), Constant::integer(id as i64))
         );
         Ok(())
       }
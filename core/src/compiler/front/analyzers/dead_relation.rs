@@ -0,0 +1,117 @@
+use std::collections::*;
+
+use super::super::utils::*;
+use super::super::*;
+use super::HiddenRelationAnalysis;
+
+/// Analysis finding the relations that are declared but never used
+///
+/// A relation is used if it occurs in the body of a rule or is queried. When the program has
+/// no query, every relation that is not hidden is an output, and is therefore also used.
+/// Since rules can be added incrementally, the warnings are only produced on demand through
+/// `warnings`, instead of after every analyzed source.
+#[derive(Clone, Debug, Default)]
+pub struct DeadRelationAnalysis {
+  pub declared_relations: BTreeMap<String, Loc>,
+  pub used_relations: HashSet<String>,
+  pub has_query: bool,
+  head_atoms: HashSet<Loc>,
+}
+
+impl DeadRelationAnalysis {
+  pub fn new() -> Self {
+    Self {
+      declared_relations: BTreeMap::new(),
+      used_relations: HashSet::new(),
+      has_query: false,
+      head_atoms: HashSet::new(),
+    }
+  }
+
+  fn declare(&mut self, predicate: &str, loc: &Loc) {
+    if !predicate.contains('#') && !self.declared_relations.contains_key(predicate) {
+      self.declared_relations.insert(predicate.to_string(), loc.clone());
+    }
+  }
+
+  /// Get the declared relations that are never used, along with their declaration locations
+  pub fn dead_relations(&self, hidden: &HiddenRelationAnalysis) -> Vec<(String, Loc)> {
+    self
+      .declared_relations
+      .iter()
+      .filter(|(r, _)| !self.used_relations.contains(*r))
+      .filter(|(r, _)| self.has_query || hidden.contains(r))
+      .map(|(r, loc)| (r.clone(), loc.clone()))
+      .collect()
+  }
+
+  pub fn warnings(&self, hidden: &HiddenRelationAnalysis) -> Vec<DeadRelationError> {
+    self
+      .dead_relations(hidden)
+      .into_iter()
+      .map(|(relation, loc)| DeadRelationError::DeadRelationWarning { relation, loc })
+      .collect()
+  }
+}
+
+impl NodeVisitor for DeadRelationAnalysis {
+  fn visit_relation_type(&mut self, rt: &ast::RelationType) {
+    self.declare(rt.predicate(), rt.location());
+  }
+
+  fn visit_fact_decl(&mut self, fd: &ast::FactDecl) {
+    self.declare(fd.predicate(), fd.location());
+  }
+
+  fn visit_constant_set_decl(&mut self, csd: &ast::ConstantSetDecl) {
+    self.declare(csd.predicate(), csd.location());
+  }
+
+  fn visit_rule_head(&mut self, head: &ast::RuleHead) {
+    let atoms = match &head.node {
+      ast::RuleHeadNode::Atom(a) => vec![a],
+      ast::RuleHeadNode::Disjunction(d) => d.iter().collect(),
+    };
+    for atom in atoms {
+      self.declare(atom.predicate(), atom.location());
+      self.head_atoms.insert(atom.location().clone());
+    }
+  }
+
+  fn visit_query(&mut self, q: &ast::Query) {
+    self.has_query = true;
+    self.used_relations.insert(q.predicate());
+  }
+
+  fn visit_atom(&mut self, a: &ast::Atom) {
+    // The atoms in rule heads are visited after the heads themselves, so they are already recorded
+    if !self.head_atoms.contains(a.location()) {
+      self.used_relations.insert(a.predicate().to_string());
+    }
+  }
+}
+
+#[derive(Debug, Clone)]
+pub enum DeadRelationError {
+  DeadRelationWarning { relation: String, loc: Loc },
+}
+
+impl FrontCompileErrorTrait for DeadRelationError {
+  fn error_type(&self) -> FrontCompileErrorType {
+    match self {
+      Self::DeadRelationWarning { .. } => FrontCompileErrorType::Warning,
+    }
+  }
+
+  fn report(&self, src: &Sources) -> String {
+    match self {
+      Self::DeadRelationWarning { relation, loc } => {
+        format!(
+          "relation `{}` is declared but never used or queried:\n{}",
+          relation,
+          loc.report_warning(src)
+        )
+      }
+    }
+  }
+}
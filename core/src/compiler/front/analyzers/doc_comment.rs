@@ -0,0 +1,48 @@
+use std::collections::*;
+
+use super::super::*;
+
+/// Collects the doc comments of relations; when a relation is documented at
+/// multiple declarations, the first one is kept
+#[derive(Clone, Debug)]
+pub struct DocCommentAnalysis {
+  pub docs: HashMap<String, String>,
+}
+
+impl DocCommentAnalysis {
+  pub fn new() -> Self {
+    Self { docs: HashMap::new() }
+  }
+
+  pub fn doc(&self, rela: &str) -> Option<&String> {
+    self.docs.get(rela)
+  }
+
+  pub fn process_doc(&mut self, pred: &str, doc: Option<&str>) {
+    if let Some(doc) = doc {
+      self.docs.entry(pred.to_string()).or_insert_with(|| doc.to_string());
+    }
+  }
+}
+
+impl NodeVisitor for DocCommentAnalysis {
+  fn visit_relation_type_decl(&mut self, rela_type_decl: &ast::RelationTypeDecl) {
+    for rela_type in rela_type_decl.relation_types() {
+      self.process_doc(rela_type.predicate(), rela_type_decl.doc());
+    }
+  }
+
+  fn visit_constant_set_decl(&mut self, decl: &ast::ConstantSetDecl) {
+    self.process_doc(decl.predicate(), decl.doc())
+  }
+
+  fn visit_fact_decl(&mut self, decl: &ast::FactDecl) {
+    self.process_doc(decl.predicate(), decl.doc())
+  }
+
+  fn visit_rule_decl(&mut self, rule_decl: &RuleDecl) {
+    for predicate in rule_decl.rule().head().iter_predicates() {
+      self.process_doc(predicate, rule_decl.doc())
+    }
+  }
+}
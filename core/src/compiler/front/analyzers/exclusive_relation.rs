@@ -0,0 +1,124 @@
+use std::collections::*;
+
+use super::super::utils::*;
+use super::super::*;
+
+/// Analysis finding the relations holding mutually exclusive tuples that are also derived by regular rules
+///
+/// The tuples of a disjunctive fact set (e.g. `rel color = {0.3::"red"; 0.7::"blue"}`) or of a rule with
+/// a disjunctive head are mutually exclusive, and their probabilities are supposed to form a distribution.
+/// Once the same relation is also derived by a rule with an atomic head, the derived tuples are independent
+/// of the exclusive ones, and the distribution no longer holds.
+#[derive(Clone, Debug, Default)]
+pub struct ExclusiveRelationAnalysis {
+  pub errors: Vec<ExclusiveRelationError>,
+  pub exclusive_relations: BTreeMap<String, Loc>,
+  pub derived_relations: BTreeMap<String, Loc>,
+  reported_relations: HashSet<String>,
+}
+
+impl ExclusiveRelationAnalysis {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Get the relations that hold mutually exclusive tuples and are also derived by regular rules
+  pub fn conflicting_relations(&self) -> Vec<&String> {
+    self
+      .exclusive_relations
+      .keys()
+      .filter(|r| self.derived_relations.contains_key(*r))
+      .collect()
+  }
+
+  /// Produce a warning for each conflicting relation; since rules can be added incrementally, every relation
+  /// is only reported once
+  pub fn compute_errors(&mut self) {
+    let new_errors = self
+      .conflicting_relations()
+      .into_iter()
+      .filter(|r| !self.reported_relations.contains(*r))
+      .map(|r| ExclusiveRelationError::ExclusiveAndDerivedWarning {
+        relation: r.clone(),
+        exclusive_loc: self.exclusive_relations[r].clone(),
+        derived_loc: self.derived_relations[r].clone(),
+      })
+      .collect::<Vec<_>>();
+    for e in new_errors {
+      self.reported_relations.insert(e.relation().to_string());
+      self.errors.push(e);
+    }
+  }
+}
+
+impl NodeVisitor for ExclusiveRelationAnalysis {
+  fn visit_constant_set_decl(&mut self, csd: &ast::ConstantSetDecl) {
+    if csd.is_disjunction() {
+      self
+        .exclusive_relations
+        .entry(csd.predicate().to_string())
+        .or_insert_with(|| csd.location().clone());
+    }
+  }
+
+  fn visit_rule_head(&mut self, head: &ast::RuleHead) {
+    match &head.node {
+      ast::RuleHeadNode::Atom(a) => {
+        self
+          .derived_relations
+          .entry(a.predicate().to_string())
+          .or_insert_with(|| a.location().clone());
+      }
+      ast::RuleHeadNode::Disjunction(d) => {
+        for a in d {
+          self
+            .exclusive_relations
+            .entry(a.predicate().to_string())
+            .or_insert_with(|| a.location().clone());
+        }
+      }
+    }
+  }
+}
+
+#[derive(Debug, Clone)]
+pub enum ExclusiveRelationError {
+  ExclusiveAndDerivedWarning {
+    relation: String,
+    exclusive_loc: Loc,
+    derived_loc: Loc,
+  },
+}
+
+impl ExclusiveRelationError {
+  pub fn relation(&self) -> &str {
+    match self {
+      Self::ExclusiveAndDerivedWarning { relation, .. } => relation,
+    }
+  }
+}
+
+impl FrontCompileErrorTrait for ExclusiveRelationError {
+  fn error_type(&self) -> FrontCompileErrorType {
+    match self {
+      Self::ExclusiveAndDerivedWarning { .. } => FrontCompileErrorType::Warning,
+    }
+  }
+
+  fn report(&self, src: &Sources) -> String {
+    match self {
+      Self::ExclusiveAndDerivedWarning {
+        relation,
+        exclusive_loc,
+        derived_loc,
+      } => {
+        format!(
+          "relation `{}` holds mutually exclusive tuples but is also derived by a rule, so its probabilities might not form a valid distribution:\n{}\nit is derived here:\n{}",
+          relation,
+          exclusive_loc.report_warning(src),
+          derived_loc.report_warning(src)
+        )
+      }
+    }
+  }
+}
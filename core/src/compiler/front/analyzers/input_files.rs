@@ -1,8 +1,10 @@
 use std::collections::*;
 use std::path::*;
 
+use regex::Regex;
+
 use super::super::*;
-use crate::common::input_file::InputFile;
+use crate::common::input_file::{CsvOptions, Delimiter, InputFile};
 
 #[derive(Clone, Debug)]
 pub struct InputFilesAnalysis {
@@ -54,6 +56,57 @@ impl InputFilesAnalysis {
     }
   }
 
+  pub fn process_deliminator_regex(&self, attr_arg: Option<&Constant>) -> Result<Option<String>, InputFilesError> {
+    match attr_arg {
+      Some(v) => match &v.node {
+        ConstantNode::String(s) => match Regex::new(s) {
+          Ok(_) => Ok(Some(s.clone())),
+          Err(e) => Err(InputFilesError::DeliminatorRegexInvalid {
+            error: e.to_string(),
+            loc: v.location().clone(),
+          }),
+        },
+        _ => Err(InputFilesError::DeliminatorNotString {
+          loc: v.location().clone(),
+        }),
+      },
+      None => Ok(None),
+    }
+  }
+
+  pub fn process_whitespace_separated(&self, attr_arg: Option<&Constant>) -> Result<Option<bool>, InputFilesError> {
+    match attr_arg {
+      Some(v) => match &v.node {
+        ConstantNode::Boolean(b) => Ok(Some(*b)),
+        _ => Err(InputFilesError::WhitespaceSeparatedNotBoolean {
+          loc: v.location().clone(),
+        }),
+      },
+      None => Ok(None),
+    }
+  }
+
+  /// Process the `deliminator`, `deliminator_regex`, and `whitespace_separated` arguments; at most one of
+  /// them can be specified
+  pub fn process_delimiter(&self, attr: &Attribute) -> Result<Option<Delimiter>, InputFilesError> {
+    let byte = self
+      .process_deliminator(attr.kw_arg("deliminator"))?
+      .map(Delimiter::Byte);
+    let regex = self
+      .process_deliminator_regex(attr.kw_arg("deliminator_regex"))?
+      .map(Delimiter::Regex);
+    let whitespace = self
+      .process_whitespace_separated(attr.kw_arg("whitespace_separated"))?
+      .and_then(|b| if b { Some(Delimiter::Whitespace) } else { None });
+    let mut delimiters = vec![byte, regex, whitespace].into_iter().flatten();
+    match (delimiters.next(), delimiters.next()) {
+      (Some(_), Some(_)) => Err(InputFilesError::ConflictingDeliminators {
+        attr_loc: attr.location().clone(),
+      }),
+      (delimiter, _) => Ok(delimiter),
+    }
+  }
+
   pub fn process_has_header(&self, attr_arg: Option<&Constant>) -> Result<Option<bool>, InputFilesError> {
     match attr_arg {
       Some(v) => match &v.node {
@@ -78,6 +131,84 @@ impl InputFilesAnalysis {
     }
   }
 
+  pub fn process_quote_char(&self, attr_arg: Option<&Constant>) -> Result<Option<u8>, InputFilesError> {
+    match attr_arg {
+      Some(v) => match &v.node {
+        ConstantNode::String(s) if s.len() == 1 => Ok(Some(s.as_bytes()[0])),
+        ConstantNode::String(_) => Err(InputFilesError::QuoteCharNotSingleASCIICharacter {
+          loc: v.location().clone(),
+        }),
+        _ => Err(InputFilesError::QuoteCharNotString {
+          loc: v.location().clone(),
+        }),
+      },
+      None => Ok(None),
+    }
+  }
+
+  /// Process the `comment_char` argument, the character starting the lines to be skipped
+  pub fn process_comment_char(&self, attr_arg: Option<&Constant>) -> Result<Option<u8>, InputFilesError> {
+    match attr_arg {
+      Some(v) => match &v.node {
+        ConstantNode::String(s) if s.len() == 1 => Ok(Some(s.as_bytes()[0])),
+        ConstantNode::String(_) => Err(InputFilesError::CommentCharNotSingleASCIICharacter {
+          loc: v.location().clone(),
+        }),
+        _ => Err(InputFilesError::CommentCharNotString {
+          loc: v.location().clone(),
+        }),
+      },
+      None => Ok(None),
+    }
+  }
+
+  /// Process the `empty_value` argument, the value read in place of empty fields
+  pub fn process_empty_value(&self, attr_arg: Option<&Constant>) -> Result<Option<String>, InputFilesError> {
+    match attr_arg {
+      Some(v) => match &v.node {
+        ConstantNode::String(s) => Ok(Some(s.clone())),
+        _ => Err(InputFilesError::EmptyValueNotString {
+          loc: v.location().clone(),
+        }),
+      },
+      None => Ok(None),
+    }
+  }
+
+  /// Process the `fields` argument, a comma separated list of the keys of JSON objects
+  pub fn process_fields(&self, attr_arg: Option<&Constant>) -> Result<Option<Vec<String>>, InputFilesError> {
+    match attr_arg {
+      Some(v) => match &v.node {
+        ConstantNode::String(s) => Ok(Some(s.split(',').map(|f| f.trim().to_string()).collect())),
+        _ => Err(InputFilesError::FieldsNotString {
+          loc: v.location().clone(),
+        }),
+      },
+      None => Ok(None),
+    }
+  }
+
+  /// Process the `columns` argument, a comma separated list of the names of the CSV columns to be
+  /// selected, which requires the CSV file to have a header
+  pub fn process_columns(
+    &self,
+    attr_arg: Option<&Constant>,
+    has_header: Option<bool>,
+  ) -> Result<Option<Vec<String>>, InputFilesError> {
+    match attr_arg {
+      Some(v) => match &v.node {
+        ConstantNode::String(_) if has_header != Some(true) => Err(InputFilesError::ColumnsWithoutHeader {
+          loc: v.location().clone(),
+        }),
+        ConstantNode::String(s) => Ok(Some(s.split(',').map(|c| c.trim().to_string()).collect())),
+        _ => Err(InputFilesError::ColumnsNotString {
+          loc: v.location().clone(),
+        }),
+      },
+      None => Ok(None),
+    }
+  }
+
   /// Assumption: Assumes attr is of `file`
   pub fn process_attr(&self, attr: &Attribute) -> Result<InputFile, InputFilesError> {
     if attr.num_pos_args() > 0 {
@@ -87,13 +218,28 @@ impl InputFilesAnalysis {
           let path = PathBuf::from(s);
           match path.extension() {
             Some(s) if s == "csv" => {
-              let deliminator = self.process_deliminator(attr.kw_arg("deliminator"))?;
+              let deliminator = self.process_delimiter(attr)?;
               let has_header = self.process_has_header(attr.kw_arg("has_header"))?;
+              let options = CsvOptions {
+                deliminator: deliminator.unwrap_or_default(),
+                has_header: has_header.unwrap_or(false),
+                has_probability: self.process_has_probability(attr.kw_arg("has_probability"))?.unwrap_or(false),
+                quote_char: self.process_quote_char(attr.kw_arg("quote_char"))?,
+                empty_value: self.process_empty_value(attr.kw_arg("empty_value"))?,
+                column_mapping: self.process_columns(attr.kw_arg("columns"), has_header)?,
+                comment_char: self.process_comment_char(attr.kw_arg("comment_char"))?,
+              };
+              Ok(InputFile::csv_with_options(path, options))
+            }
+            Some(s) if s == "txt" => {
+              let deliminator = self.process_delimiter(attr)?;
+              Ok(InputFile::txt_with_options(path, deliminator))
+            }
+            Some(s) if s == "json" => {
+              let fields = self.process_fields(attr.kw_arg("fields"))?;
               let has_probability = self.process_has_probability(attr.kw_arg("has_probability"))?;
-              let input_file = InputFile::csv_with_options(path, deliminator, has_header, has_probability);
-              Ok(input_file)
+              Ok(InputFile::json_with_options(path, fields, has_probability))
             }
-            Some(s) if s == "txt" => Ok(InputFile::Txt(path)),
             Some(s) => Err(InputFilesError::UnknownExtension {
               ext: String::from(s.to_str().unwrap()),
               attr_arg_loc: arg.location().clone(),
@@ -168,6 +314,40 @@ pub enum InputFilesError {
   DeliminatorNotASCII {
     loc: AstNodeLocation,
   },
+  DeliminatorRegexInvalid {
+    error: String,
+    loc: AstNodeLocation,
+  },
+  WhitespaceSeparatedNotBoolean {
+    loc: AstNodeLocation,
+  },
+  ConflictingDeliminators {
+    attr_loc: AstNodeLocation,
+  },
+  FieldsNotString {
+    loc: AstNodeLocation,
+  },
+  QuoteCharNotString {
+    loc: AstNodeLocation,
+  },
+  QuoteCharNotSingleASCIICharacter {
+    loc: AstNodeLocation,
+  },
+  CommentCharNotString {
+    loc: AstNodeLocation,
+  },
+  CommentCharNotSingleASCIICharacter {
+    loc: AstNodeLocation,
+  },
+  EmptyValueNotString {
+    loc: AstNodeLocation,
+  },
+  ColumnsNotString {
+    loc: AstNodeLocation,
+  },
+  ColumnsWithoutHeader {
+    loc: AstNodeLocation,
+  },
 }
 
 impl FrontCompileErrorTrait for InputFilesError {
@@ -201,7 +381,7 @@ impl FrontCompileErrorTrait for InputFilesError {
       }
       Self::UnknownExtension { ext, attr_arg_loc } => {
         format!(
-          "Unknown input file extension `.{}`. Expected one from [`.csv`, `.txt`]\n{}",
+          "Unknown input file extension `.{}`. Expected one from [`.csv`, `.txt`, `.json`]\n{}",
           ext,
           attr_arg_loc.report(src)
         )
@@ -224,6 +404,55 @@ impl FrontCompileErrorTrait for InputFilesError {
       Self::DeliminatorNotASCII { loc } => {
         format!("`deliminator` attribute is not an ASCII character\n{}", loc.report(src))
       }
+      Self::DeliminatorRegexInvalid { error, loc } => {
+        format!(
+          "`deliminator_regex` attribute is not a valid regular expression: {}\n{}",
+          error,
+          loc.report(src)
+        )
+      }
+      Self::WhitespaceSeparatedNotBoolean { loc } => {
+        format!("`whitespace_separated` attribute is not a boolean\n{}", loc.report(src))
+      }
+      Self::ConflictingDeliminators { attr_loc } => {
+        format!(
+          "At most one of `deliminator`, `deliminator_regex`, and `whitespace_separated` can be specified\n{}",
+          attr_loc.report(src)
+        )
+      }
+      Self::FieldsNotString { loc } => {
+        format!("`fields` attribute is not a string\n{}", loc.report(src))
+      }
+      Self::QuoteCharNotString { loc } => {
+        format!("`quote_char` attribute is not a string\n{}", loc.report(src))
+      }
+      Self::QuoteCharNotSingleASCIICharacter { loc } => {
+        format!(
+          "`quote_char` attribute is not a single ASCII character string\n{}",
+          loc.report(src)
+        )
+      }
+      Self::CommentCharNotString { loc } => {
+        format!("`comment_char` attribute is not a string\n{}", loc.report(src))
+      }
+      Self::CommentCharNotSingleASCIICharacter { loc } => {
+        format!(
+          "`comment_char` attribute is not a single ASCII character string\n{}",
+          loc.report(src)
+        )
+      }
+      Self::EmptyValueNotString { loc } => {
+        format!("`empty_value` attribute is not a string\n{}", loc.report(src))
+      }
+      Self::ColumnsNotString { loc } => {
+        format!("`columns` attribute is not a string\n{}", loc.report(src))
+      }
+      Self::ColumnsWithoutHeader { loc } => {
+        format!(
+          "`columns` attribute requires `has_header` to be true, as the columns are selected by their names in the header\n{}",
+          loc.report(src)
+        )
+      }
     }
   }
 }
@@ -0,0 +1,114 @@
+use super::super::*;
+
+/// Analysis collecting the `@k(K)` attribute, which requests the `k` of the top-k provenances
+///
+/// The attribute is program-wide and can annotate any item, e.g.
+///
+/// ``` scl
+/// @k(5)
+/// rel path(a, b) = edge(a, b) \/ path(a, c) /\ edge(c, b)
+/// ```
+///
+/// The compiler only records the requested `k`; it is up to the caller to configure the provenance with it.
+#[derive(Clone, Debug)]
+pub struct KAttributeAnalysis {
+  pub k: Option<(usize, AstNodeLocation)>,
+  pub errors: Vec<KAttributeError>,
+}
+
+impl KAttributeAnalysis {
+  pub fn new() -> Self {
+    Self {
+      k: None,
+      errors: Vec::new(),
+    }
+  }
+
+  /// Get the `k` requested by the program, if there is one
+  pub fn k(&self) -> Option<usize> {
+    self.k.as_ref().map(|(k, _)| *k)
+  }
+
+  pub fn process_attribute(&mut self, attr: &Attribute) {
+    if attr.name() != "k" {
+      return;
+    }
+
+    // Check the argument
+    let k = match (attr.num_pos_args(), attr.pos_arg(0).map(|a| &a.node)) {
+      (1, Some(ConstantNode::Integer(k))) if *k > 0 => *k as usize,
+      _ => {
+        self.errors.push(KAttributeError::InvalidArgument {
+          loc: attr.location().clone(),
+        });
+        return;
+      }
+    };
+
+    // Check that the program does not request another `k`
+    match &self.k {
+      Some((first_k, first_loc)) if *first_k != k => {
+        self.errors.push(KAttributeError::ConflictingK {
+          first_k: *first_k,
+          first_loc: first_loc.clone(),
+          second_k: k,
+          second_loc: attr.location().clone(),
+        });
+      }
+      Some(_) => {}
+      None => self.k = Some((k, attr.location().clone())),
+    }
+  }
+}
+
+impl NodeVisitor for KAttributeAnalysis {
+  fn visit_item(&mut self, item: &Item) {
+    for attr in item.attributes() {
+      self.process_attribute(attr);
+    }
+  }
+}
+
+#[derive(Clone, Debug)]
+pub enum KAttributeError {
+  InvalidArgument {
+    loc: AstNodeLocation,
+  },
+  ConflictingK {
+    first_k: usize,
+    first_loc: AstNodeLocation,
+    second_k: usize,
+    second_loc: AstNodeLocation,
+  },
+}
+
+impl FrontCompileErrorTrait for KAttributeError {
+  fn error_type(&self) -> FrontCompileErrorType {
+    FrontCompileErrorType::Error
+  }
+
+  fn report(&self, src: &Sources) -> String {
+    match self {
+      Self::InvalidArgument { loc } => {
+        format!(
+          "Invalid argument of @k attribute. Expected one positive integer\n{}",
+          loc.report(src)
+        )
+      }
+      Self::ConflictingK {
+        first_k,
+        first_loc,
+        second_k,
+        second_loc,
+      } => {
+        format!(
+          "Conflicting @k attributes. k is first set to {} here:\n{}and then set to {} here:\n{}",
+          first_k,
+          first_loc.report(src),
+          second_k,
+          second_loc.report(src)
+        )
+      }
+    }
+  }
+}
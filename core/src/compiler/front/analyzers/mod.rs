@@ -2,37 +2,51 @@ pub mod aggregation;
 pub mod boundness;
 pub mod character_literal;
 pub mod constant_decl;
+pub mod dead_relation;
 pub mod demand_attr;
+pub mod doc_comment;
+pub mod exclusive_relation;
 pub mod head_relation;
 pub mod hidden_relation;
 pub mod input_files;
 pub mod invalid_constant;
 pub mod invalid_wildcard;
+pub mod k_attr;
 pub mod output_files;
 pub mod type_inference;
+pub mod unused_function;
 
 pub use aggregation::AggregationAnalysis;
 pub use boundness::BoundnessAnalysis;
 pub use character_literal::CharacterLiteralAnalysis;
 pub use constant_decl::ConstantDeclAnalysis;
+pub use dead_relation::DeadRelationAnalysis;
 pub use demand_attr::DemandAttributeAnalysis;
+pub use doc_comment::DocCommentAnalysis;
+pub use exclusive_relation::ExclusiveRelationAnalysis;
 pub use head_relation::HeadRelationAnalysis;
 pub use hidden_relation::HiddenRelationAnalysis;
 pub use input_files::InputFilesAnalysis;
 pub use invalid_constant::InvalidConstantAnalyzer;
 pub use invalid_wildcard::InvalidWildcardAnalyzer;
+pub use k_attr::KAttributeAnalysis;
 pub use output_files::OutputFilesAnalysis;
 pub use type_inference::TypeInference;
+pub use unused_function::UnusedFunctionAnalysis;
 
 pub mod errors {
   pub use super::aggregation::AggregationAnalysisError;
   pub use super::boundness::BoundnessAnalysisError;
   pub use super::constant_decl::ConstantDeclError;
+  pub use super::dead_relation::DeadRelationError;
   pub use super::demand_attr::DemandAttributeError;
+  pub use super::exclusive_relation::ExclusiveRelationError;
   pub use super::head_relation::HeadRelationError;
   pub use super::input_files::InputFilesError;
   pub use super::invalid_constant::InvalidConstantError;
   pub use super::invalid_wildcard::InvalidWildcardError;
+  pub use super::k_attr::KAttributeError;
   pub use super::output_files::OutputFilesError;
   pub use super::type_inference::TypeInferenceError;
+  pub use super::unused_function::UnusedFunctionError;
 }
@@ -1,7 +1,7 @@
 use std::collections::*;
 use std::path::PathBuf;
 
-use crate::common::output_option::{OutputCSVFile, OutputFile};
+use crate::common::output_option::{OutputCSVFile, OutputFile, OutputJSONFile};
 use crate::compiler::front::*;
 
 #[derive(Clone, Debug)]
@@ -49,6 +49,31 @@ impl OutputFilesAnalysis {
     }
   }
 
+  pub fn process_append(&self, attr_arg: Option<&Constant>) -> Result<Option<bool>, OutputFilesError> {
+    match attr_arg {
+      Some(v) => match &v.node {
+        ConstantNode::Boolean(b) => Ok(Some(*b)),
+        _ => Err(OutputFilesError::AppendNotBoolean {
+          loc: v.location().clone(),
+        }),
+      },
+      None => Ok(None),
+    }
+  }
+
+  /// Process the `fields` argument, a comma separated list of the names of the fields
+  pub fn process_fields(&self, attr_arg: Option<&Constant>) -> Result<Option<Vec<String>>, OutputFilesError> {
+    match attr_arg {
+      Some(v) => match &v.node {
+        ConstantNode::String(s) => Ok(Some(s.split(',').map(|f| f.trim().to_string()).collect())),
+        _ => Err(OutputFilesError::FieldsNotString {
+          loc: v.location().clone(),
+        }),
+      },
+      None => Ok(None),
+    }
+  }
+
   pub fn process_attribute(&self, attr: &Attribute) -> Result<OutputFile, OutputFilesError> {
     if attr.num_pos_args() > 0 {
       let arg = attr.pos_arg(0).unwrap();
@@ -58,7 +83,14 @@ impl OutputFilesAnalysis {
           match path.extension() {
             Some(s) if s == "csv" => {
               let deliminator = self.process_deliminator(attr.kw_arg("deliminator"))?;
-              let output_file = OutputFile::CSV(OutputCSVFile::new_with_options(path, deliminator));
+              let append = self.process_append(attr.kw_arg("append"))?;
+              let output_file = OutputFile::CSV(OutputCSVFile::new_with_options(path, deliminator, append));
+              Ok(output_file)
+            }
+            Some(s) if s == "json" => {
+              let fields = self.process_fields(attr.kw_arg("fields"))?;
+              let append = self.process_append(attr.kw_arg("append"))?;
+              let output_file = OutputFile::JSON(OutputJSONFile::new_with_options(path, fields, append));
               Ok(output_file)
             }
             Some(s) => Err(OutputFilesError::UnknownExtension {
@@ -127,6 +159,12 @@ pub enum OutputFilesError {
   DeliminatorNotASCII {
     loc: AstNodeLocation,
   },
+  AppendNotBoolean {
+    loc: AstNodeLocation,
+  },
+  FieldsNotString {
+    loc: AstNodeLocation,
+  },
 }
 
 impl FrontCompileErrorTrait for OutputFilesError {
@@ -160,7 +198,7 @@ impl FrontCompileErrorTrait for OutputFilesError {
       }
       Self::UnknownExtension { ext, attr_arg_loc } => {
         format!(
-          "Unknown input file extension `.{}`. Expected one from [`.csv`, `.txt`]\n{}",
+          "Unknown input file extension `.{}`. Expected one from [`.csv`, `.json`]\n{}",
           ext,
           attr_arg_loc.report(src)
         )
@@ -177,6 +215,12 @@ impl FrontCompileErrorTrait for OutputFilesError {
       Self::DeliminatorNotASCII { loc } => {
         format!("`deliminator` attribute is not an ASCII character\n{}", loc.report(src))
       }
+      Self::AppendNotBoolean { loc } => {
+        format!("`append` attribute is not a boolean\n{}", loc.report(src))
+      }
+      Self::FieldsNotString { loc } => {
+        format!("`fields` attribute is not a string\n{}", loc.report(src))
+      }
     }
   }
 }
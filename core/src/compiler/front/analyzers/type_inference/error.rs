@@ -27,6 +27,10 @@ pub enum TypeInferenceError {
     predicate: String,
     loc: AstNodeLocation,
   },
+  UnknownRelation {
+    predicate: String,
+    loc: AstNodeLocation,
+  },
   UnknownFunctionType {
     function_name: String,
     loc: AstNodeLocation,
@@ -44,6 +48,8 @@ pub enum TypeInferenceError {
   },
   FunctionArityMismatch {
     function: String,
+    min_expected: usize,
+    max_expected: Option<usize>,
     actual: usize,
     loc: AstNodeLocation,
   },
@@ -190,6 +196,13 @@ impl FrontCompileErrorTrait for TypeInferenceError {
       Self::UnknownQueryRelationType { predicate, loc } => {
         format!("unknown relation `{}` used in query\n{}", predicate, loc.report(src))
       }
+      Self::UnknownRelation { predicate, loc } => {
+        format!(
+          "relation `{}` is used without a prior type declaration\n{}",
+          predicate,
+          loc.report(src)
+        )
+      }
       Self::UnknownFunctionType { function_name, loc } => {
         format!("unknown function `{}`\n{}", function_name, loc.report(src))
       }
@@ -211,10 +224,22 @@ impl FrontCompileErrorTrait for TypeInferenceError {
           mismatch_loc.report(src)
         )
       }
-      Self::FunctionArityMismatch { function, actual, loc } => {
+      Self::FunctionArityMismatch {
+        function,
+        min_expected,
+        max_expected,
+        actual,
+        loc,
+      } => {
+        let expected = match max_expected {
+          Some(max) if max == min_expected => format!("{}", min_expected),
+          Some(max) => format!("{} to {}", min_expected, max),
+          None => format!("at least {}", min_expected),
+        };
         format!(
-          "wrong number of arguments for function `{}`, found {}:\n{}",
+          "wrong number of arguments for function `{}`. Expected {}, found {}:\n{}",
           function,
+          expected,
           actual,
           loc.report(src)
         )
@@ -268,7 +293,7 @@ impl FrontCompileErrorTrait for TypeInferenceError {
       }
       Self::NegativeEnumValue { found, loc } => {
         format!(
-          "enum value `{}` found to be negative. Expected unsigned integers\n{}",
+          "enum value `{}` found to be negative. Expected unsigned integers, unless the enum is annotated with `@allow_negative`\n{}",
           found,
           loc.report(src),
         )
@@ -103,6 +103,15 @@ impl FunctionType {
     self.variable_argument_type.is_some()
   }
 
+  /// Get the maximum number of arguments accepted by the function type; `None` if it takes variable arguments
+  pub fn max_num_args(&self) -> Option<usize> {
+    if self.has_variable_arguments() {
+      None
+    } else {
+      Some(self.num_static_arguments() + self.num_optional_arguments())
+    }
+  }
+
   /// Check if the given `num_args` is acceptable by the function type
   pub fn is_valid_num_args(&self, num_args: usize) -> bool {
     // First, there should be at least `len(static_argument_types)` arguments
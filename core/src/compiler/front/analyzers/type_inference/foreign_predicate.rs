@@ -8,12 +8,14 @@ use crate::common::foreign_predicate::*;
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct PredicateType {
   pub arguments: Vec<ValueType>,
+  pub queryable: bool,
 }
 
 impl<P: ForeignPredicate> From<&P> for PredicateType {
   fn from(p: &P) -> Self {
     Self {
       arguments: p.argument_types(),
+      queryable: p.is_queryable(),
     }
   }
 }
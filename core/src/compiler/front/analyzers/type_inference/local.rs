@@ -11,6 +11,7 @@ pub struct LocalTypeInferenceContext {
   pub atom_arities: HashMap<String, Vec<(usize, Loc)>>,
   pub unifications: Vec<Unification>,
   pub vars_of_same_type: Vec<(String, String)>,
+  pub vars_of_list_type: Vec<(String, String)>,
   pub var_types: HashMap<String, (TypeSet, Loc)>,
   pub constraints: Vec<Loc>,
   pub errors: Vec<TypeInferenceError>,
@@ -23,6 +24,7 @@ impl LocalTypeInferenceContext {
       atom_arities: HashMap::new(),
       unifications: Vec::new(),
       vars_of_same_type: Vec::new(),
+      vars_of_list_type: Vec::new(),
       var_types: HashMap::new(),
       constraints: Vec::new(),
       errors: Vec::new(),
@@ -182,6 +184,28 @@ impl LocalTypeInferenceContext {
       }
     }
 
+    // Check list variables against the types of their element variables
+    for (list_var, elem_var) in &self.vars_of_list_type {
+      let elem_ty = TypeSet::List(Box::new(var_tys[elem_var].clone()), self.rule_loc.clone());
+      let list_ty = match elem_ty.unify(&var_tys[list_var]) {
+        Ok(list_ty) => list_ty,
+        Err(_) => {
+          return Err(TypeInferenceError::CannotUnifyVariables {
+            v1: list_var.clone(),
+            t1: var_tys[list_var].clone(),
+            v2: elem_var.clone(),
+            t2: elem_ty,
+            loc: self.rule_loc.clone(),
+          })
+        }
+      };
+      let new_elem_ty = list_ty
+        .element_type()
+        .expect("Unified list type should have an element type");
+      var_tys.insert(list_var.clone(), list_ty);
+      var_tys.insert(elem_var.clone(), new_elem_ty);
+    }
+
     // Check variable type constraints
     for (var, (ty, _)) in &self.var_types {
       let curr_ty = &var_tys[var];
@@ -322,9 +346,14 @@ impl NodeVisitor for LocalTypeInferenceContext {
       }
     }
 
-    // Then propagate the variables
-    match &r.operator().node {
-      ReduceOperatorNode::Count => {
+    // Then propagate the variables; a window aggregation follows the type rules of its inner aggregation
+    let (num_vars_of_same_type, num_vars_of_list_type) = (self.vars_of_same_type.len(), self.vars_of_list_type.len());
+    let op = match &r.operator().node {
+      ReduceOperatorNode::Window { op, .. } => &**op,
+      op => op,
+    };
+    match op {
+      ReduceOperatorNode::Count | ReduceOperatorNode::CountDistinct => {
         if let Some(n) = vars[0].name() {
           let loc = vars[0].location();
           let ty = TypeSet::BaseType(ValueType::USize, loc.clone());
@@ -343,6 +372,42 @@ impl NodeVisitor for LocalTypeInferenceContext {
             .push((n.to_string(), bindings[0].name().to_string()));
         }
       }
+      ReduceOperatorNode::WeightedSum => {
+        if let Some(n) = vars[0].name() {
+          let loc = vars[0].location();
+          let ty = TypeSet::Numeric(loc.clone());
+          self.var_types.insert(n.to_string(), (ty, loc.clone()));
+
+          // Result var, value var, and weight var should have the same type
+          for binding in bindings {
+            self.vars_of_same_type.push((n.to_string(), binding.name().to_string()));
+          }
+        }
+      }
+      ReduceOperatorNode::Variance
+      | ReduceOperatorNode::StdDev
+      | ReduceOperatorNode::Median
+      | ReduceOperatorNode::GeometricMean
+      | ReduceOperatorNode::HarmonicMean => {
+        if let Some(n) = vars[0].name() {
+          let loc = vars[0].location();
+          let ty = TypeSet::BaseType(ValueType::F64, loc.clone());
+          self.var_types.insert(n.to_string(), (ty, loc.clone()));
+        }
+
+        // The binding var could be of any numeric type
+        let loc = bindings[0].location();
+        let ty = TypeSet::Numeric(loc.clone());
+        self.var_types.insert(bindings[0].name().to_string(), (ty, loc.clone()));
+      }
+      ReduceOperatorNode::Collect => {
+        // The result var is a list of the binding var
+        if let Some(n) = vars[0].name() {
+          self
+            .vars_of_list_type
+            .push((n.to_string(), bindings[0].name().to_string()));
+        }
+      }
       ReduceOperatorNode::Prod => {
         if let Some(n) = vars[0].name() {
           let loc = vars[0].location();
@@ -386,6 +451,65 @@ impl NodeVisitor for LocalTypeInferenceContext {
           self.var_types.insert(n.to_string(), (ty, loc.clone()));
         }
       }
+      ReduceOperatorNode::Any => {
+        // The first result var tells whether there is any tuple, followed by the witness of each binding var
+        if vars.len() != bindings.len() + 1 {
+          self.errors.push(TypeInferenceError::InvalidReduceOutput {
+            op: r.operator().to_string().to_string(),
+            expected: bindings.len() + 1,
+            found: vars.len(),
+            loc: r.location().clone(),
+          });
+          return;
+        }
+        if let Some(n) = vars[0].name() {
+          let loc = vars[0].location();
+          let ty = TypeSet::BaseType(ValueType::Bool, loc.clone());
+          self.var_types.insert(n.to_string(), (ty, loc.clone()));
+        }
+        for (var, binding) in vars.iter().skip(1).zip(bindings.iter()) {
+          if let Some(n) = var.name() {
+            self.vars_of_same_type.push((n.to_string(), binding.name().to_string()));
+          }
+        }
+      }
+      ReduceOperatorNode::ArgMin | ReduceOperatorNode::ArgMax => {
+        // The result vars are the key followed by each binding var, i.e. the whole tuple achieving the extremum
+        let keys_and_bindings = r
+          .args()
+          .iter()
+          .map(|a| a.name())
+          .chain(bindings.iter().map(|b| b.name()))
+          .collect::<Vec<_>>();
+        if vars.len() != keys_and_bindings.len() {
+          self.errors.push(TypeInferenceError::InvalidReduceOutput {
+            op: r.operator().to_string().to_string(),
+            expected: keys_and_bindings.len(),
+            found: vars.len(),
+            loc: r.location().clone(),
+          });
+          return;
+        }
+        for (var, name) in vars.iter().zip(keys_and_bindings) {
+          if let Some(n) = var.name() {
+            self.vars_of_same_type.push((n.to_string(), name.to_string()));
+          }
+        }
+      }
+      ReduceOperatorNode::Rank => {
+        if let Some(n) = vars[0].name() {
+          let loc = vars[0].location();
+          let ty = TypeSet::BaseType(ValueType::USize, loc.clone());
+          self.var_types.insert(n.to_string(), (ty, loc.clone()));
+        }
+
+        // The second result var is the ranked binding var
+        if let Some(n) = vars[1].name() {
+          self
+            .vars_of_same_type
+            .push((n.to_string(), bindings[0].name().to_string()));
+        }
+      }
       ReduceOperatorNode::Forall => {
         if let Some(n) = vars[0].name() {
           let loc = vars[0].location();
@@ -409,7 +533,71 @@ impl NodeVisitor for LocalTypeInferenceContext {
           return;
         }
       }
-      ReduceOperatorNode::Unknown(_) => {}
+      ReduceOperatorNode::Sample => {
+        // With one more binding var than output vars, the first binding var is the weight of the tuple
+        let sampled = if bindings.len() == vars.len() + 1 {
+          let loc = bindings[0].location();
+          let ty = TypeSet::Numeric(loc.clone());
+          self.var_types.insert(bindings[0].name().to_string(), (ty, loc.clone()));
+          &bindings[1..]
+        } else {
+          &bindings[..]
+        };
+        if vars.len() == sampled.len() {
+          for (var, binding) in vars.iter().zip(sampled.iter()) {
+            if let Some(n) = var.name() {
+              self.vars_of_same_type.push((n.to_string(), binding.name().to_string()));
+            }
+          }
+        } else {
+          self.errors.push(TypeInferenceError::InvalidReduceBindingVar {
+            op: r.operator().to_string().to_string(),
+            expected: vars.len(),
+            found: bindings.len(),
+            loc: r.location().clone(),
+          });
+          return;
+        }
+      }
+      ReduceOperatorNode::Normalize => {
+        if let Some(n) = vars[0].name() {
+          let loc = vars[0].location();
+          let ty = TypeSet::BaseType(ValueType::F64, loc.clone());
+          self.var_types.insert(n.to_string(), (ty, loc.clone()));
+        }
+
+        // The first binding var is the value to normalize, and the other ones are passed through
+        let loc = bindings[0].location();
+        let ty = TypeSet::Numeric(loc.clone());
+        self.var_types.insert(bindings[0].name().to_string(), (ty, loc.clone()));
+      }
+      ReduceOperatorNode::Window { .. } | ReduceOperatorNode::Unknown(_) => {}
+    }
+
+    // With an annotated result type, e.g. `(c: f32) := count(...)`, the constraints above are about the raw result
+    // in the natural type of the aggregation, which needs to be castable into the annotated type
+    if let (Some(ty), Some(n)) = (r.result_type(), vars[0].name()) {
+      let raw_var = Reduce::raw_result_variable_name(n);
+      let raw_ty = self
+        .var_types
+        .remove(n)
+        .map_or(TypeSet::Any(ty.location().clone()), |(ty, _)| ty);
+      self.var_types.insert(raw_var.clone(), (raw_ty, ty.location().clone()));
+      for (v1, v2) in self.vars_of_same_type[num_vars_of_same_type..]
+        .iter_mut()
+        .chain(self.vars_of_list_type[num_vars_of_list_type..].iter_mut())
+      {
+        for v in [v1, v2] {
+          if v == n {
+            *v = raw_var.clone();
+          }
+        }
+      }
+      self.unifications.push(Unification::TypeCast(
+        ty.location().clone(),
+        vars[0].location().clone(),
+        ty.clone(),
+      ));
     }
   }
 
@@ -20,6 +20,10 @@ pub struct TypeInference {
   pub rule_local_contexts: Vec<LocalTypeInferenceContext>,
   pub query_relations: HashMap<String, Loc>,
   pub expr_types: HashMap<Loc, TypeSet>,
+
+  /// Whether the relations used in rules need to be declared by a prior `type` declaration,
+  /// instead of having their types inferred
+  pub strict_relation_declarations: bool,
   pub errors: Vec<TypeInferenceError>,
 }
 
@@ -39,6 +43,7 @@ impl TypeInference {
       rule_local_contexts: Vec::new(),
       query_relations: HashMap::new(),
       expr_types: HashMap::new(),
+      strict_relation_declarations: false,
       errors: vec![],
     }
   }
@@ -89,6 +94,26 @@ impl TypeInference {
       .map(|a| TupleType::from_types(&a, false))
   }
 
+  /// Get the inferred column types of all the user defined relations
+  pub fn inferred_relation_types(&self) -> HashMap<String, Vec<ValueType>> {
+    self
+      .inferred_relation_types
+      .iter()
+      .filter(|(n, _)| !n.contains("#"))
+      .map(|(n, (tys, _))| (n.clone(), tys.iter().map(TypeSet::to_default_value_type).collect()))
+      .collect()
+  }
+
+  /// Get the inferred types of all the variables in the rule at the given location
+  pub fn rule_variable_types(&self, rule_loc: &Loc) -> Option<HashMap<String, ValueType>> {
+    self.rule_variable_type.get(rule_loc).map(|var_tys| {
+      var_tys
+        .iter()
+        .map(|(v, ty)| (v.clone(), ty.to_default_value_type()))
+        .collect()
+    })
+  }
+
   pub fn variable_type(&self, rule_loc: &Loc, var: &str) -> ValueType {
     self.rule_variable_type[rule_loc][var].to_default_value_type()
   }
@@ -183,6 +208,30 @@ impl TypeInference {
     }
   }
 
+  /// Check that the relations used in a rule are all declared, skipping the foreign predicates and the
+  /// temporary relations generated by the compiler
+  fn check_relation_declarations(&mut self, ctx: &LocalTypeInferenceContext) {
+    let mut preds = ctx.atom_arities.iter().collect::<Vec<_>>();
+    preds.sort_by_key(|(pred, _)| *pred);
+    for (pred, arities) in preds {
+      self.check_relation_declaration(pred, &arities[0].1);
+    }
+  }
+
+  /// Check that a relation is declared, unless it is a foreign predicate or a temporary relation generated by
+  /// the compiler
+  fn check_relation_declaration(&mut self, pred: &str, loc: &Loc) {
+    if !pred.contains('#')
+      && !self.relation_type_decl_loc.contains_key(pred)
+      && !self.foreign_predicate_type_registry.contains_predicate(pred)
+    {
+      self.errors.push(TypeInferenceError::UnknownRelation {
+        predicate: pred.to_string(),
+        loc: loc.clone(),
+      });
+    }
+  }
+
   pub fn resolve_constant_type(&self, c: &Constant) -> Result<TypeSet, TypeInferenceError> {
     if let Some(ty) = self.constant_types.get(c.location()) {
       let val_ty = find_value_type(&self.custom_types, ty)?;
@@ -304,7 +353,7 @@ impl NodeVisitor for TypeInference {
 
   fn visit_enum_type_decl(&mut self, enum_type_decl: &ast::EnumTypeDecl) {
     // First add the enum type
-    let ty = Type::usize();
+    let ty = enum_type_decl.id_type();
     self.check_and_add_custom_type(enum_type_decl.name(), &ty, enum_type_decl.location());
 
     // And then declare all the constant types
@@ -312,7 +361,7 @@ impl NodeVisitor for TypeInference {
       match member.assigned_number() {
         Some(c) => match &c.node {
           ConstantNode::Integer(i) => {
-            if *i < 0 {
+            if *i < 0 && !enum_type_decl.allow_negative() {
               self.errors.push(TypeInferenceError::NegativeEnumValue {
                 found: *i,
                 loc: c.location().clone(),
@@ -359,6 +408,11 @@ impl NodeVisitor for TypeInference {
       return;
     }
 
+    // Check that the relation is declared if requested
+    if self.strict_relation_declarations {
+      self.check_relation_declaration(pred, constant_set_decl.location());
+    }
+
     // There's nothing we can check if there is no tuple inside the set
     if constant_set_decl.num_tuples() == 0 {
       return;
@@ -463,6 +517,11 @@ impl NodeVisitor for TypeInference {
       return;
     }
 
+    // Check that the relation is declared if requested
+    if self.strict_relation_declarations {
+      self.check_relation_declaration(pred, fact_decl.location());
+    }
+
     let maybe_curr_type_sets = fact_decl
       .iter_arguments()
       .map(|arg| match arg {
@@ -536,6 +595,11 @@ impl NodeVisitor for TypeInference {
       return;
     }
 
+    // Check that all the relations are declared if requested
+    if self.strict_relation_declarations {
+      self.check_relation_declarations(&ctx);
+    }
+
     // First unify atom arity
     if let Err(err) = ctx.unify_atom_arities(&mut self.inferred_relation_types) {
       self.errors.push(err);
@@ -547,14 +611,18 @@ impl NodeVisitor for TypeInference {
   }
 
   fn visit_query(&mut self, query: &Query) {
-    // Check if the relation is a foreign predicate
+    // Check if the relation is a foreign predicate; only the queryable ones can be queried, and only through
+    // an atom (e.g. `query range_i32(0, 5, x)`) providing the arguments to ground them with
     let pred = query.predicate();
-    if self.foreign_predicate_type_registry.contains_predicate(&pred) {
-      self.errors.push(TypeInferenceError::CannotQueryForeignPredicate {
-        pred: pred.to_string(),
-        loc: query.location().clone(),
-      });
-      return;
+    if let Some(pred_type) = self.foreign_predicate_type_registry.get(&pred) {
+      let is_atom_query = query.create_relation_name() != pred;
+      if !pred_type.queryable || !is_atom_query {
+        self.errors.push(TypeInferenceError::CannotQueryForeignPredicate {
+          pred: pred.to_string(),
+          loc: query.location().clone(),
+        });
+        return;
+      }
     }
 
     // Check the query
@@ -7,12 +7,14 @@ use crate::compiler::front::*;
 pub enum TypeSet {
   BaseType(ValueType, AstNodeLocation), // Concrete base type
   Numeric(AstNodeLocation),             // Contains integer and float, default i32
+  SignedNumeric(AstNodeLocation),       // Contains signed integer and float, default i32
   Arith(AstNodeLocation),               // Numeric but with arithmetics, default integer i32
   Integer(AstNodeLocation),             // integer, default `i32`
   SignedInteger(AstNodeLocation),       // signed integer, default `i32`
   UnsignedInteger(AstNodeLocation),     // unsigned integer, default `u32`
   Float(AstNodeLocation),               // float, default `f32`
-  String(AstNodeLocation),              // string, default `String`
+  String(AstNodeLocation),              // string or symbol, default `String`
+  List(Box<TypeSet>, AstNodeLocation),  // list of elements in the type set, default list of the element default
   Any(AstNodeLocation),                 // Any type, default i32
 }
 
@@ -42,12 +44,14 @@ impl std::cmp::PartialEq for TypeSet {
     match (self, other) {
       (Self::BaseType(b1, _), Self::BaseType(b2, _)) => b1 == b2,
       (Self::Numeric(_), Self::Numeric(_)) => true,
+      (Self::SignedNumeric(_), Self::SignedNumeric(_)) => true,
       (Self::Arith(_), Self::Arith(_)) => true,
       (Self::Integer(_), Self::Integer(_)) => true,
       (Self::SignedInteger(_), Self::SignedInteger(_)) => true,
       (Self::Float(_), Self::Float(_)) => true,
       (Self::String(_), Self::String(_)) => true,
       (Self::Any(_), Self::Any(_)) => true,
+      (Self::List(t1, _), Self::List(t2, _)) => t1 == t2,
       _ => false,
     }
   }
@@ -59,8 +63,8 @@ impl std::cmp::PartialOrd for TypeSet {
     match (self, other) {
       // Base type less than anything else
       (Self::BaseType(b1, _), Self::BaseType(b2, _)) if b1 == b2 => Some(Equal),
-      (Self::BaseType(b, _), Self::String(_)) if b.is_string() => Some(Less),
-      (Self::String(_), Self::BaseType(b, _)) if b.is_string() => Some(Greater),
+      (Self::BaseType(b, _), Self::String(_)) if b.is_string() || b.is_symbol() => Some(Less),
+      (Self::String(_), Self::BaseType(b, _)) if b.is_string() || b.is_symbol() => Some(Greater),
       (Self::BaseType(b, _), Self::Float(_)) if b.is_float() => Some(Less),
       (Self::Float(_), Self::BaseType(b, _)) if b.is_float() => Some(Greater),
       (Self::BaseType(b, _), Self::SignedInteger(_)) if b.is_signed_integer() => Some(Less),
@@ -69,6 +73,8 @@ impl std::cmp::PartialOrd for TypeSet {
       (Self::Integer(_), Self::BaseType(b, _)) if b.is_integer() => Some(Greater),
       (Self::BaseType(b, _), Self::Arith(_)) if b.is_numeric() => Some(Less),
       (Self::Arith(_), Self::BaseType(b, _)) if b.is_numeric() => Some(Greater),
+      (Self::BaseType(b, _), Self::SignedNumeric(_)) if b.is_signed_integer() || b.is_float() => Some(Less),
+      (Self::SignedNumeric(_), Self::BaseType(b, _)) if b.is_signed_integer() || b.is_float() => Some(Greater),
       (Self::BaseType(b, _), Self::Numeric(_)) if b.is_numeric() => Some(Less),
       (Self::Numeric(_), Self::BaseType(b, _)) if b.is_numeric() => Some(Greater),
       (Self::BaseType(_, _), Self::Any(_)) => Some(Less),
@@ -81,6 +87,8 @@ impl std::cmp::PartialOrd for TypeSet {
 
       // Float type less than Arith and Numeric and Any
       (Self::Float(_), Self::Float(_)) => Some(Equal),
+      (Self::Float(_), Self::SignedNumeric(_)) => Some(Less),
+      (Self::SignedNumeric(_), Self::Float(_)) => Some(Greater),
       (Self::Float(_), Self::Arith(_)) => Some(Less),
       (Self::Arith(_), Self::Float(_)) => Some(Greater),
       (Self::Float(_), Self::Numeric(_)) => Some(Less),
@@ -92,6 +100,8 @@ impl std::cmp::PartialOrd for TypeSet {
       (Self::SignedInteger(_), Self::SignedInteger(_)) => Some(Equal),
       (Self::SignedInteger(_), Self::Integer(_)) => Some(Less),
       (Self::Integer(_), Self::SignedInteger(_)) => Some(Greater),
+      (Self::SignedInteger(_), Self::SignedNumeric(_)) => Some(Less),
+      (Self::SignedNumeric(_), Self::SignedInteger(_)) => Some(Greater),
       (Self::SignedInteger(_), Self::Arith(_)) => Some(Less),
       (Self::Arith(_), Self::SignedInteger(_)) => Some(Greater),
       (Self::SignedInteger(_), Self::Numeric(_)) => Some(Less),
@@ -119,6 +129,15 @@ impl std::cmp::PartialOrd for TypeSet {
       (Self::Integer(_), Self::Any(_)) => Some(Less),
       (Self::Any(_), Self::Integer(_)) => Some(Greater),
 
+      // Signed Numeric less than Arith, Numeric, and Any
+      (Self::SignedNumeric(_), Self::SignedNumeric(_)) => Some(Equal),
+      (Self::SignedNumeric(_), Self::Arith(_)) => Some(Less),
+      (Self::Arith(_), Self::SignedNumeric(_)) => Some(Greater),
+      (Self::SignedNumeric(_), Self::Numeric(_)) => Some(Less),
+      (Self::Numeric(_), Self::SignedNumeric(_)) => Some(Greater),
+      (Self::SignedNumeric(_), Self::Any(_)) => Some(Less),
+      (Self::Any(_), Self::SignedNumeric(_)) => Some(Greater),
+
       // Arith less than Numeric and Any
       (Self::Arith(_), Self::Arith(_)) => Some(Equal),
       (Self::Arith(_), Self::Numeric(_)) => Some(Less),
@@ -131,6 +150,13 @@ impl std::cmp::PartialOrd for TypeSet {
       (Self::Numeric(_), Self::Any(_)) => Some(Less),
       (Self::Any(_), Self::Numeric(_)) => Some(Greater),
 
+      // List types are ordered by their element types, and are less than Any
+      (Self::List(t1, _), Self::List(t2, _)) => t1.partial_cmp(t2),
+      (Self::BaseType(ValueType::List(b), _), Self::List(t, _)) => Self::base((**b).clone()).partial_cmp(t),
+      (Self::List(t, _), Self::BaseType(ValueType::List(b), _)) => (**t).partial_cmp(&Self::base((**b).clone())),
+      (Self::List(_, _), Self::Any(_)) => Some(Less),
+      (Self::Any(_), Self::List(_, _)) => Some(Greater),
+
       // Any
       (Self::Any(_), Self::Any(_)) => Some(Greater),
 
@@ -145,6 +171,7 @@ impl std::fmt::Display for TypeSet {
     match self {
       Self::BaseType(t, _) => t.fmt(f),
       Self::Numeric(_) => f.write_str("numeric"),
+      Self::SignedNumeric(_) => f.write_str("signed numeric"),
       Self::Arith(_) => f.write_str("arithmetic"),
       Self::Integer(_) => f.write_str("integer"),
       Self::SignedInteger(_) => f.write_str("signed integer"),
@@ -152,6 +179,7 @@ impl std::fmt::Display for TypeSet {
       Self::Float(_) => f.write_str("float"),
       Self::String(_) => f.write_str("string"),
       Self::Any(_) => f.write_str("any"),
+      Self::List(t, _) => f.write_fmt(format_args!("List<{}>", t)),
     }
   }
 }
@@ -177,11 +205,20 @@ impl TypeSet {
     Self::String(AstNodeLocation::default())
   }
 
+  /// Get the type set of the elements, if this is a list type
+  pub fn element_type(&self) -> Option<Self> {
+    match self {
+      Self::List(t, _) => Some((**t).clone()),
+      Self::BaseType(ValueType::List(t), l) => Some(Self::BaseType((**t).clone(), l.clone())),
+      _ => None,
+    }
+  }
+
   pub fn from_constant(c: &Constant) -> Self {
     match &c.node {
       ConstantNode::Integer(i) => {
         if i < &0 {
-          Self::SignedInteger(c.location().clone())
+          Self::SignedNumeric(c.location().clone())
         } else {
           Self::Numeric(c.location().clone())
         }
@@ -206,13 +243,15 @@ impl TypeSet {
     match (self, base_ty) {
       (Self::BaseType(b1, _), base_ty) => b1.can_type_cast(base_ty),
       (Self::Numeric(_), base_ty) => base_ty.is_numeric(),
+      (Self::SignedNumeric(_), base_ty) => base_ty.is_numeric(),
       (Self::Arith(_), base_ty) => base_ty.is_numeric(),
       (Self::Integer(_), base_ty) => base_ty.is_numeric(),
       (Self::SignedInteger(_), base_ty) => base_ty.is_numeric(),
       (Self::UnsignedInteger(_), base_ty) => base_ty.is_numeric(),
       (Self::Float(_), base_ty) => base_ty.is_numeric(),
-      (Self::String(_), base_ty) => base_ty.is_string() || base_ty.is_numeric(),
+      (Self::String(_), base_ty) => base_ty.is_string() || base_ty.is_symbol() || base_ty.is_numeric(),
       (Self::Any(_), base_ty) => base_ty.is_numeric(),
+      (Self::List(_, _), _) => false,
     }
   }
 
@@ -227,6 +266,7 @@ impl TypeSet {
     match self {
       Self::BaseType(_, l) => l,
       Self::Numeric(l) => l,
+      Self::SignedNumeric(l) => l,
       Self::Arith(l) => l,
       Self::Integer(l) => l,
       Self::SignedInteger(l) => l,
@@ -234,6 +274,7 @@ impl TypeSet {
       Self::Float(l) => l,
       Self::String(l) => l,
       Self::Any(l) => l,
+      Self::List(_, l) => l,
     }
   }
 
@@ -241,6 +282,7 @@ impl TypeSet {
     match self {
       Self::BaseType(b, _) => b.clone(),
       Self::Numeric(_) => ValueType::I32,
+      Self::SignedNumeric(_) => ValueType::I32,
       Self::Arith(_) => ValueType::I32,
       Self::Integer(_) => ValueType::I32,
       Self::SignedInteger(_) => ValueType::I32,
@@ -248,6 +290,7 @@ impl TypeSet {
       Self::Float(_) => ValueType::F32,
       Self::String(_) => ValueType::String,
       Self::Any(_) => ValueType::I32,
+      Self::List(t, _) => ValueType::List(Box::new(t.to_default_value_type())),
     }
   }
 
@@ -255,6 +298,7 @@ impl TypeSet {
     match (self, other) {
       (Self::BaseType(_, _), Self::BaseType(_, _)) => true,
       (Self::Numeric(_), Self::Numeric(_)) => true,
+      (Self::SignedNumeric(_), Self::SignedNumeric(_)) => true,
       (Self::Arith(_), Self::Arith(_)) => true,
       (Self::Integer(_), Self::Integer(_)) => true,
       (Self::SignedInteger(_), Self::SignedInteger(_)) => true,
@@ -262,6 +306,7 @@ impl TypeSet {
       (Self::Float(_), Self::Float(_)) => true,
       (Self::String(_), Self::String(_)) => true,
       (Self::Any(_), Self::Any(_)) => true,
+      (Self::List(_, _), Self::List(_, _)) => true,
       _ => false,
     }
   }
@@ -279,6 +324,24 @@ impl TypeSet {
 
   pub fn unify(&self, other: &Self) -> Result<Self, TypeInferenceError> {
     use std::cmp::Ordering::*;
+
+    // Integers and signed numbers are incomparable, but they still intersect in the signed integers
+    if let (Self::Integer(l), Self::SignedNumeric(_)) | (Self::SignedNumeric(_), Self::Integer(l)) = (self, other) {
+      return Ok(Self::SignedInteger(l.clone()));
+    }
+
+    // Two list types unify when their element types do
+    if let (Self::List(t1, l), Self::List(t2, _)) = (self, other) {
+      return match t1.unify(t2) {
+        Ok(t) => Ok(Self::List(Box::new(t), l.clone())),
+        Err(_) => Err(TypeInferenceError::CannotUnifyTypes {
+          t1: self.clone(),
+          t2: other.clone(),
+          loc: None,
+        }),
+      };
+    }
+
     match self.partial_cmp(other) {
       Some(Equal) | Some(Less) => Ok(self.clone()),
       Some(Greater) => Ok(other.clone()),
@@ -290,17 +353,78 @@ impl TypeSet {
     }
   }
 
+  /// Find the smallest numeric base type that two different numeric base types can both be casted to
+  /// without overflowing; `None` if there is no such type, or if any of the type sets is not a numeric base type
+  ///
+  /// Integers are widened to the wider one of the two (e.g. `i32` and `i64` to `i64`, and `u32` and `i32` to
+  /// `i64`), while mixing integers and floating points, or floating points of different precisions, gives `f64`.
+  pub fn numeric_supertype(&self, other: &Self) -> Option<Self> {
+    let (t1, t2, l) = match (self, other) {
+      (Self::BaseType(t1, l), Self::BaseType(t2, _)) if t1 != t2 && t1.is_numeric() && t2.is_numeric() => (t1, t2, l),
+      _ => return None,
+    };
+    let sup = if t1.is_float() || t2.is_float() {
+      ValueType::F64
+    } else {
+      let (signed_1, bits_1) = integer_signedness_and_bits(t1);
+      let (signed_2, bits_2) = integer_signedness_and_bits(t2);
+      let (signed, bits) = if signed_1 == signed_2 {
+        (signed_1, bits_1.max(bits_2))
+      } else {
+        // A signed integer holds an unsigned one only if it is strictly wider
+        let (signed_bits, unsigned_bits) = if signed_1 { (bits_1, bits_2) } else { (bits_2, bits_1) };
+        (true, signed_bits.max(unsigned_bits * 2))
+      };
+      match (signed, bits) {
+        (true, 8) => ValueType::I8,
+        (true, 16) => ValueType::I16,
+        (true, 32) => ValueType::I32,
+        (true, 64) => ValueType::I64,
+        (true, 128) => ValueType::I128,
+        (false, 8) => ValueType::U8,
+        (false, 16) => ValueType::U16,
+        (false, 32) => ValueType::U32,
+        (false, 64) => ValueType::U64,
+        (false, 128) => ValueType::U128,
+        _ => return None,
+      }
+    };
+    Some(Self::BaseType(sup, l.clone()))
+  }
+
   pub fn contains_value_type(&self, value_type: &ValueType) -> bool {
     match self {
       Self::BaseType(b, _) => b == value_type,
       Self::Numeric(_) => value_type.is_numeric(),
+      Self::SignedNumeric(_) => value_type.is_signed_integer() || value_type.is_float(),
       Self::Arith(_) => value_type.is_numeric(),
       Self::Integer(_) => value_type.is_integer(),
       Self::SignedInteger(_) => value_type.is_signed_integer(),
       Self::UnsignedInteger(_) => value_type.is_unsigned_integer(),
       Self::Float(_) => value_type.is_float(),
-      Self::String(_) => value_type.is_string(),
+      Self::String(_) => value_type.is_string() || value_type.is_symbol(),
       Self::Any(_) => true,
+      Self::List(t, _) => match value_type {
+        ValueType::List(e) => t.contains_value_type(e),
+        _ => false,
+      },
     }
   }
 }
+
+/// Get the signedness and the number of bits of an integer type; pointer sized integers are regarded as 64 bits
+fn integer_signedness_and_bits(ty: &ValueType) -> (bool, usize) {
+  match ty {
+    ValueType::I8 => (true, 8),
+    ValueType::I16 => (true, 16),
+    ValueType::I32 => (true, 32),
+    ValueType::I64 | ValueType::ISize => (true, 64),
+    ValueType::I128 => (true, 128),
+    ValueType::U8 => (false, 8),
+    ValueType::U16 => (false, 16),
+    ValueType::U32 => (false, 32),
+    ValueType::U64 | ValueType::USize => (false, 64),
+    ValueType::U128 => (false, 128),
+    _ => panic!("[Internal Error] `{}` is not an integer type", ty),
+  }
+}
@@ -253,6 +253,13 @@ impl Unification {
             Ok(())
           }
           Err(mut err) => {
+            // Branches of different numeric types are implicitly casted to their common supertype
+            if let Some(sup_ty) = then_br_ty.numeric_supertype(&else_br_ty) {
+              if let Ok(new_ty) = e_ty.unify(&sup_ty) {
+                inferred_expr_types.insert(e.clone(), new_ty);
+                return Ok(());
+              }
+            }
             err.annotate_location(e);
             Err(err)
           }
@@ -348,6 +355,8 @@ impl Unification {
           } else {
             Err(TypeInferenceError::FunctionArityMismatch {
               function: function.clone(),
+              min_expected: function_type.num_static_arguments(),
+              max_expected: function_type.max_num_args(),
               actual: args.len(),
               loc: e.clone(),
             })
@@ -0,0 +1,70 @@
+use std::collections::*;
+
+use super::super::*;
+
+/// Analysis finding the registered foreign functions that are never called
+///
+/// Since rules can be added incrementally, the warnings are only produced on demand
+/// through `warnings`, instead of after every analyzed source.
+#[derive(Clone, Debug)]
+pub struct UnusedFunctionAnalysis {
+  pub registered_functions: BTreeSet<String>,
+  pub called_functions: HashSet<String>,
+}
+
+impl UnusedFunctionAnalysis {
+  pub fn new() -> Self {
+    Self {
+      registered_functions: BTreeSet::new(),
+      called_functions: HashSet::new(),
+    }
+  }
+
+  pub fn add_foreign_function(&mut self, name: String) {
+    self.registered_functions.insert(name);
+  }
+
+  pub fn unused_functions(&self) -> Vec<String> {
+    self
+      .registered_functions
+      .iter()
+      .filter(|f| !self.called_functions.contains(*f))
+      .cloned()
+      .collect()
+  }
+
+  pub fn warnings(&self) -> Vec<UnusedFunctionError> {
+    self
+      .unused_functions()
+      .into_iter()
+      .map(|function| UnusedFunctionError::UnusedFunctionWarning { function })
+      .collect()
+  }
+}
+
+impl NodeVisitor for UnusedFunctionAnalysis {
+  fn visit_call_expr(&mut self, c: &ast::CallExpr) {
+    self.called_functions.insert(c.function_identifier().name().to_string());
+  }
+}
+
+#[derive(Debug, Clone)]
+pub enum UnusedFunctionError {
+  UnusedFunctionWarning { function: String },
+}
+
+impl FrontCompileErrorTrait for UnusedFunctionError {
+  fn error_type(&self) -> FrontCompileErrorType {
+    match self {
+      Self::UnusedFunctionWarning { .. } => FrontCompileErrorType::Warning,
+    }
+  }
+
+  fn report(&self, _: &Sources) -> String {
+    match self {
+      Self::UnusedFunctionWarning { function } => {
+        format!("foreign function `${}` is registered but never called", function)
+      }
+    }
+  }
+}
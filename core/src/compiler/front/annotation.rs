@@ -83,3 +83,60 @@ impl NodeVisitorMut for LocationSpanAnnotator {
     loc.loc_span = Some(Span { start, end });
   }
 }
+
+/// Attaches the `///` comment lines directly above a relation or type declaration
+/// to the declaration as its doc comment
+#[derive(Clone, Debug)]
+pub struct DocCommentAnnotator {
+  pub content: String,
+}
+
+impl DocCommentAnnotator {
+  pub fn new<S: Source>(source: &S) -> Self {
+    Self {
+      content: source.content().to_string(),
+    }
+  }
+
+  /// Get the doc comment for a declaration starting at `offset`; the declaration
+  /// needs to start its line, and the comment lines need to be contiguous
+  pub fn doc_comment_at(&self, offset: usize) -> Option<String> {
+    let (above, line_prefix) = self.content.get(..offset)?.rsplit_once('\n')?;
+    if !line_prefix.trim().is_empty() {
+      return None;
+    }
+
+    let mut doc_lines = vec![];
+    for line in above.split('\n').rev() {
+      match line.trim().strip_prefix("///") {
+        Some(l) if !l.starts_with('/') => doc_lines.push(l.strip_prefix(' ').unwrap_or(l)),
+        _ => break,
+      }
+    }
+
+    if doc_lines.is_empty() {
+      None
+    } else {
+      doc_lines.reverse();
+      Some(doc_lines.join("\n"))
+    }
+  }
+}
+
+impl NodeVisitorMut for DocCommentAnnotator {
+  fn visit_relation_type_decl(&mut self, decl: &mut RelationTypeDecl) {
+    decl.node.doc = self.doc_comment_at(decl.location().offset_span.start);
+  }
+
+  fn visit_constant_set_decl(&mut self, decl: &mut ConstantSetDecl) {
+    decl.node.doc = self.doc_comment_at(decl.location().offset_span.start);
+  }
+
+  fn visit_fact_decl(&mut self, decl: &mut FactDecl) {
+    decl.node.doc = self.doc_comment_at(decl.location().offset_span.start);
+  }
+
+  fn visit_rule_decl(&mut self, decl: &mut RuleDecl) {
+    decl.node.doc = self.doc_comment_at(decl.location().offset_span.start);
+  }
+}
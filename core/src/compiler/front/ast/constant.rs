@@ -74,6 +74,7 @@ impl Constant {
       (String(_), ValueType::Str) => panic!("Cannot cast dynamic string into static string"),
       (String(s), ValueType::String) => Value::String(s.clone()),
       // (String(s), ValueType::RcString) => Value::RcString(Rc::new(s.clone())),
+      (String(s), ValueType::Symbol) => Value::symbol(s),
       (DateTime(d), ValueType::DateTime) => Value::DateTime(d.clone()),
       (Duration(d), ValueType::Duration) => Value::Duration(d.clone()),
       _ => panic!("Cannot convert front Constant `{:?}` to Type `{}`", self, ty),
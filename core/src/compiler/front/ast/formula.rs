@@ -148,6 +148,16 @@ impl Implies {
   pub fn right(&self) -> &Formula {
     &self.node.right
   }
+
+  /// Rewrite `A => B` into its equivalent disjunction `(not A) or B`
+  pub fn to_disjunction(&self) -> Formula {
+    Formula::Disjunction(Disjunction::new(
+      self.location().clone(),
+      DisjunctionNode {
+        args: vec![self.left().negate(), self.right().clone()],
+      },
+    ))
+  }
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -206,6 +216,7 @@ impl VariableOrWildcard {
 #[doc(hidden)]
 pub struct ReduceNode {
   pub left: Vec<VariableOrWildcard>,
+  pub result_type: Option<Type>,
   pub operator: ReduceOperator,
   pub args: Vec<Variable>,
   pub bindings: Vec<VariableBinding>,
@@ -225,6 +236,18 @@ impl Reduce {
     &self.node.left
   }
 
+  /// The type annotated on the result of the aggregation, e.g. `f32` in `c: f32 := count(...)`, into which
+  /// the result is cast
+  pub fn result_type(&self) -> Option<&Type> {
+    self.node.result_type.as_ref()
+  }
+
+  /// The name of the variable holding the result of an aggregation in its natural type, before it is cast
+  /// into the annotated result type
+  pub fn raw_result_variable_name(var: &str) -> String {
+    format!("{}#raw", var)
+  }
+
   pub fn left_variables(&self) -> impl Iterator<Item = &Variable> {
     self.node.left.iter().filter_map(|i| match i {
       VariableOrWildcard::Variable(v) => Some(v),
@@ -257,15 +280,32 @@ impl Reduce {
 #[doc(hidden)]
 pub enum ReduceOperatorNode {
   Count,
+  /// Counts the distinct tuples of the body. As the body of an aggregation is already a set of tuples, it
+  /// matches `count` at the language level; the two only differ on batches holding duplicate tuples
+  CountDistinct,
   Sum,
   Prod,
+  WeightedSum,
+  Variance,
+  StdDev,
+  Median,
+  Collect,
+  GeometricMean,
+  HarmonicMean,
   Min,
   Max,
+  ArgMin,
+  ArgMax,
   Exists,
+  Any,
   Forall,
   Unique,
   TopK(usize),
+  Rank,
   CategoricalK(usize),
+  Sample,
+  Normalize,
+  Window { op: Box<ReduceOperatorNode>, size: usize },
   Unknown(String),
 }
 
@@ -273,18 +313,54 @@ impl ReduceOperatorNode {
   pub fn to_string(&self) -> String {
     match self {
       Self::Count => "count".to_string(),
+      Self::CountDistinct => "count_distinct".to_string(),
       Self::Sum => "sum".to_string(),
       Self::Prod => "prod".to_string(),
+      Self::WeightedSum => "weighted_sum".to_string(),
+      Self::Variance => "variance".to_string(),
+      Self::StdDev => "stddev".to_string(),
+      Self::Median => "median".to_string(),
+      Self::Collect => "collect".to_string(),
+      Self::GeometricMean => "geometric_mean".to_string(),
+      Self::HarmonicMean => "harmonic_mean".to_string(),
       Self::Min => "min".to_string(),
       Self::Max => "max".to_string(),
+      Self::ArgMin => "argmin".to_string(),
+      Self::ArgMax => "argmax".to_string(),
       Self::Exists => "exists".to_string(),
+      Self::Any => "any".to_string(),
       Self::Forall => "forall".to_string(),
       Self::Unique => "unique".to_string(),
       Self::TopK(k) => format!("top<{}>", k),
+      Self::Rank => "rank".to_string(),
       Self::CategoricalK(k) => format!("categorical<{}>", k),
+      Self::Sample => "sample".to_string(),
+      Self::Normalize => "normalize".to_string(),
+      Self::Window { op, size } => format!("window<{}, {}>", size, op.to_string()),
       Self::Unknown(_) => "unknown".to_string(),
     }
   }
+
+  pub fn is_unknown(&self) -> bool {
+    matches!(self, Self::Unknown(_))
+  }
+
+  /// Whether this is `argmin` or `argmax`, which aggregate their argument (the key) along with the binding variables
+  pub fn is_arg_min_max(&self) -> bool {
+    matches!(self, Self::ArgMin | Self::ArgMax)
+  }
+
+  /// Whether the binding variables are aggregated in the order they are written, instead of by their names,
+  /// e.g. the first binding variable of `sample` is the weight
+  pub fn has_ordered_bindings(&self) -> bool {
+    matches!(self, Self::ArgMin | Self::ArgMax | Self::Sample | Self::Normalize)
+  }
+
+  /// Whether the binding variables after the first one are also results of the aggregation, e.g. `x` in
+  /// `w := normalize(c, x: counts(x, c))`, which produces one normalized weight for each of the tuples
+  pub fn has_output_bindings(&self) -> bool {
+    matches!(self, Self::Normalize)
+  }
 }
 
 /// A reduce opeartor, e.g. `count`
@@ -299,32 +375,68 @@ impl ReduceOperator {
   }
 
   pub fn output_arity(&self) -> Option<usize> {
-    match &self.node {
+    Self::node_output_arity(&self.node)
+  }
+
+  fn node_output_arity(node: &ReduceOperatorNode) -> Option<usize> {
+    match node {
       ReduceOperatorNode::Count => Some(1),
+      ReduceOperatorNode::CountDistinct => Some(1),
       ReduceOperatorNode::Sum => Some(1),
       ReduceOperatorNode::Prod => Some(1),
+      ReduceOperatorNode::WeightedSum => Some(1),
+      ReduceOperatorNode::Variance => Some(1),
+      ReduceOperatorNode::StdDev => Some(1),
+      ReduceOperatorNode::Median => Some(1),
+      ReduceOperatorNode::Collect => Some(1),
+      ReduceOperatorNode::GeometricMean => Some(1),
+      ReduceOperatorNode::HarmonicMean => Some(1),
       ReduceOperatorNode::Min => Some(1),
       ReduceOperatorNode::Max => Some(1),
+      ReduceOperatorNode::ArgMin => None,
+      ReduceOperatorNode::ArgMax => None,
       ReduceOperatorNode::Exists => Some(1),
+      ReduceOperatorNode::Any => None,
       ReduceOperatorNode::Forall => Some(1),
       ReduceOperatorNode::Unique => None,
       ReduceOperatorNode::TopK(_) => None,
+      ReduceOperatorNode::Rank => Some(2),
       ReduceOperatorNode::CategoricalK(_) => None,
+      ReduceOperatorNode::Sample => None,
+      ReduceOperatorNode::Normalize => Some(1),
+      ReduceOperatorNode::Window { op, .. } => Self::node_output_arity(op),
       ReduceOperatorNode::Unknown(_) => None,
     }
   }
 
   pub fn num_bindings(&self) -> Option<usize> {
-    match &self.node {
+    Self::node_num_bindings(&self.node)
+  }
+
+  fn node_num_bindings(node: &ReduceOperatorNode) -> Option<usize> {
+    match node {
       ReduceOperatorNode::Count => None,
+      ReduceOperatorNode::CountDistinct => None,
       ReduceOperatorNode::Sum => Some(1),
       ReduceOperatorNode::Prod => Some(1),
+      ReduceOperatorNode::WeightedSum => Some(2),
+      ReduceOperatorNode::Variance => Some(1),
+      ReduceOperatorNode::StdDev => Some(1),
+      ReduceOperatorNode::Median => Some(1),
+      ReduceOperatorNode::Collect => Some(1),
+      ReduceOperatorNode::GeometricMean => Some(1),
+      ReduceOperatorNode::HarmonicMean => Some(1),
       ReduceOperatorNode::Min => Some(1),
       ReduceOperatorNode::Max => Some(1),
+      ReduceOperatorNode::ArgMin => None,
+      ReduceOperatorNode::ArgMax => None,
       ReduceOperatorNode::Exists => None,
+      ReduceOperatorNode::Any => None,
       ReduceOperatorNode::Forall => None,
       ReduceOperatorNode::Unique => None,
       ReduceOperatorNode::TopK(_) => None,
+      ReduceOperatorNode::Rank => Some(1),
+      ReduceOperatorNode::Window { op, .. } => Self::node_num_bindings(op),
       _ => None,
     }
   }
@@ -58,6 +58,7 @@ pub struct ConstantSetDeclNode {
   pub attrs: Attributes,
   pub name: Identifier,
   pub set: ConstantSet,
+  pub doc: Option<String>,
 }
 
 pub type ConstantSetDecl = AstNode<ConstantSetDeclNode>;
@@ -71,6 +72,11 @@ impl ConstantSetDecl {
     &mut self.node.attrs
   }
 
+  /// The `///` doc comment preceding the declaration, if any
+  pub fn doc(&self) -> Option<&str> {
+    self.node.doc.as_deref()
+  }
+
   pub fn predicate(&self) -> &String {
     &self.node.name.node.name
   }
@@ -94,6 +100,7 @@ pub struct FactDeclNode {
   pub attrs: Attributes,
   pub tag: Tag,
   pub atom: Atom,
+  pub doc: Option<String>,
 }
 
 pub type FactDecl = AstNode<FactDeclNode>;
@@ -107,6 +114,11 @@ impl FactDecl {
     &mut self.node.attrs
   }
 
+  /// The `///` doc comment preceding the declaration, if any
+  pub fn doc(&self) -> Option<&str> {
+    self.node.doc.as_deref()
+  }
+
   pub fn predicate(&self) -> &String {
     self.node.atom.predicate()
   }
@@ -145,11 +157,17 @@ pub struct RuleDeclNode {
   pub attrs: Attributes,
   pub tag: Tag,
   pub rule: Rule,
+  pub doc: Option<String>,
 }
 
 impl RuleDeclNode {
   pub fn new(attrs: Attributes, tag: Tag, rule: Rule) -> Self {
-    Self { attrs, tag, rule }
+    Self {
+      attrs,
+      tag,
+      rule,
+      doc: None,
+    }
   }
 }
 
@@ -164,6 +182,11 @@ impl RuleDecl {
     &mut self.node.attrs
   }
 
+  /// The `///` doc comment preceding the declaration, if any
+  pub fn doc(&self) -> Option<&str> {
+    self.node.doc.as_deref()
+  }
+
   pub fn rule(&self) -> &Rule {
     &self.node.rule
   }
@@ -207,6 +230,14 @@ impl RelationDecl {
       RelationDeclNode::Rule(r) => r.attributes_mut(),
     }
   }
+
+  pub fn doc(&self) -> Option<&str> {
+    match &self.node {
+      RelationDeclNode::Set(s) => s.doc(),
+      RelationDeclNode::Fact(f) => f.doc(),
+      RelationDeclNode::Rule(r) => r.doc(),
+    }
+  }
 }
 
 impl From<RelationDecl> for Item {
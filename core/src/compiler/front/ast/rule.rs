@@ -33,6 +33,7 @@ impl Into<Vec<Item>> for Rule {
           attrs: Attributes::new(),
           tag: Tag::default_none(),
           rule: self,
+          doc: None,
         }
         .into(),
       )
@@ -122,6 +122,7 @@ impl Into<Vec<Item>> for RelationType {
         RelationTypeDeclNode {
           attrs: Attributes::new(),
           rel_types: vec![self],
+          doc: None,
         }
         .into(),
       )
@@ -145,6 +146,7 @@ impl RelationType {
 pub struct RelationTypeDeclNode {
   pub attrs: Attributes,
   pub rel_types: Vec<RelationType>,
+  pub doc: Option<String>,
 }
 
 pub type RelationTypeDecl = AstNode<RelationTypeDeclNode>;
@@ -169,6 +171,11 @@ impl RelationTypeDecl {
   pub fn attributes_mut(&mut self) -> &mut Attributes {
     &mut self.node.attrs
   }
+
+  /// The `///` doc comment preceding the declaration, if any
+  pub fn doc(&self) -> Option<&str> {
+    self.node.doc.as_deref()
+  }
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -194,6 +201,21 @@ impl EnumTypeDecl {
     self.node.name.name()
   }
 
+  /// Whether the enum is annotated with `@allow_negative`, in which case its IDs can be negative and do not
+  /// need to increase, as long as they are unique
+  pub fn allow_negative(&self) -> bool {
+    self.node.attrs.iter().any(|a| a.name() == "allow_negative")
+  }
+
+  /// The base type of the enum IDs; `isize` if negative IDs are allowed, and `usize` otherwise
+  pub fn id_type(&self) -> Type {
+    if self.allow_negative() {
+      Type::isize()
+    } else {
+      Type::usize()
+    }
+  }
+
   pub fn members(&self) -> &[EnumTypeMember] {
     &self.node.members
   }
@@ -23,6 +23,7 @@ pub enum TypeNode {
   Str,
   String,
   // RcString,
+  Symbol,
   DateTime,
   Duration,
   Named(Identifier),
@@ -50,6 +51,7 @@ impl std::fmt::Display for TypeNode {
       Self::Str => f.write_str("&str"),
       Self::String => f.write_str("String"),
       // Self::RcString => f.write_str("Rc<String>"),
+      Self::Symbol => f.write_str("Symbol"),
       Self::DateTime => f.write_str("DateTime"),
       Self::Duration => f.write_str("Duration"),
       Self::Named(i) => f.write_str(&i.node.name),
@@ -65,6 +67,11 @@ impl Type {
     Self::default(TypeNode::I8)
   }
 
+  /// Create a new `isize` type AST node
+  pub fn isize() -> Self {
+    Self::default(TypeNode::ISize)
+  }
+
   /// Create a new `usize` type AST node
   pub fn usize() -> Self {
     Self::default(TypeNode::USize)
@@ -95,6 +102,7 @@ impl Type {
       TypeNode::Str => Ok(ValueType::Str),
       TypeNode::String => Ok(ValueType::String),
       // TypeNode::RcString => Ok(ValueType::RcString),
+      TypeNode::Symbol => Ok(ValueType::Symbol),
       TypeNode::DateTime => Ok(ValueType::DateTime),
       TypeNode::Duration => Ok(ValueType::Duration),
       TypeNode::Named(s) => Err(s.name().to_string()),
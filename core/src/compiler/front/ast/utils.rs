@@ -83,6 +83,22 @@ impl AstNodeLocation {
     }
   }
 
+  /// Get the 1-based line and column where this location starts
+  pub fn line_col(&self, src: &Sources) -> (usize, usize) {
+    match &self.loc_span {
+      Some(loc_span) => (loc_span.start.row + 1, loc_span.start.col + 1),
+      None => {
+        let s = &src.sources[self.source_id];
+        let offset = self.offset_span.start;
+        let row = (0..s.num_rows())
+          .rev()
+          .find(|row| s.row_offset_length(*row).0 <= offset)
+          .unwrap_or(0);
+        (row + 1, offset - s.row_offset_length(row).0 + 1)
+      }
+    }
+  }
+
   pub fn report(&self, src: &Sources) -> String {
     self.report_with_marker_color(src, Color::Red)
   }
@@ -97,10 +113,11 @@ impl AstNodeLocation {
 
     // Print the title
     let s = &src.sources[self.source_id];
+    let (line, col) = self.line_col(src);
     let mut result = if let Some(name) = s.name() {
-      format!(" {} {}:\n", arrow, name)
+      format!(" {} {}:{}:{}\n", arrow, name, line, col)
     } else {
-      format!("")
+      format!(" {} {}:{}\n", arrow, line, col)
     };
 
     // Gather the lines to print
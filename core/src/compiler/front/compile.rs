@@ -87,7 +87,10 @@ impl FrontContext {
       analysis
         .type_inference
         .foreign_function_type_registry
-        .add_function_type(func_name, func_type)
+        .add_function_type(func_name.clone(), func_type);
+
+      // Track the function so that we can warn if it is never called
+      analysis.unused_function_analysis.add_foreign_function(func_name);
     });
 
     Ok(())
@@ -123,6 +126,13 @@ impl FrontContext {
     Ok(())
   }
 
+  /// Require the relations used in rules to be declared with `type` instead of inferring their types
+  pub fn set_strict_relation_declarations(&mut self, strict: bool) {
+    self.analysis.modify(|analysis| {
+      analysis.type_inference.strict_relation_declarations = strict;
+    });
+  }
+
   pub fn compile_source<S: Source>(&mut self, s: S) -> Result<SourceId, FrontCompileError> {
     self.compile_source_with_parser(s, parser::str_to_items)
   }
@@ -223,8 +233,9 @@ impl FrontContext {
     dup_ctx.add_import(&source);
     dup_ctx.process_imports(&source, &ast)?;
 
-    // Setup the location annotator
+    // Setup the location and doc comment annotators
     let mut loc_annotator = LocationSpanAnnotator::new(&source);
+    let mut doc_annotator = DocCommentAnnotator::new(&source);
 
     // Add the source to the context
     let source_id = dup_ctx.sources.add(source);
@@ -235,7 +246,12 @@ impl FrontContext {
 
     // Annotate it
     let node_id_annotator = &mut dup_ctx.node_id_annotator;
-    let mut annotators = (node_id_annotator, &mut loc_annotator, &mut source_id_annotator);
+    let mut annotators = (
+      node_id_annotator,
+      &mut loc_annotator,
+      &mut source_id_annotator,
+      &mut doc_annotator,
+    );
     annotators.walk_items(&mut ast);
 
     // Use external annotator to annotate each item
@@ -329,6 +345,37 @@ impl FrontContext {
     &self.analysis.borrow().type_inference
   }
 
+  /// Get the `k` of the top-k provenances requested by the `@k` attribute of the program
+  pub fn requested_k(&self) -> Option<usize> {
+    self.analysis.borrow().k_attr_analysis.k()
+  }
+
+  /// Get the names of the registered foreign functions that are not called by any rule
+  pub fn unused_foreign_functions(&self) -> Vec<String> {
+    self.analysis.borrow().unused_function_analysis.unused_functions()
+  }
+
+  /// Get the warnings of the registered foreign functions that are not called by any rule.
+  ///
+  /// This should be checked only after all the rules are added.
+  pub fn unused_foreign_function_warnings(&self) -> FrontCompileError {
+    let mut error_ctx = FrontCompileError::new();
+    error_ctx.set_sources(&self.sources);
+    error_ctx.extend(&mut self.analysis.borrow().unused_function_analysis.warnings());
+    error_ctx
+  }
+
+  /// Get the warnings of the relations that are declared but never used or queried.
+  ///
+  /// This should be checked only after all the rules are added.
+  pub fn dead_relation_warnings(&self) -> FrontCompileError {
+    let analysis = self.analysis.borrow();
+    let mut error_ctx = FrontCompileError::new();
+    error_ctx.set_sources(&self.sources);
+    error_ctx.extend(&mut analysis.dead_relation_analysis.warnings(&analysis.hidden_analysis));
+    error_ctx
+  }
+
   pub fn items_of_source_id(&self, source_id: SourceId) -> impl Iterator<Item = &Item> {
     self
       .items
@@ -400,4 +447,14 @@ impl FrontContext {
   pub fn relation_tuple_type(&self, relation: &str) -> Option<TupleType> {
     self.type_inference().relation_tuple_type(relation)
   }
+
+  pub fn inferred_relation_types(&self) -> HashMap<String, Vec<ValueType>> {
+    self.type_inference().inferred_relation_types()
+  }
+
+  /// Get the inferred variable types of the rule compiled from the given source
+  pub fn variable_types_in_rule(&self, source_id: SourceId) -> Option<HashMap<String, ValueType>> {
+    let rule_decl = self.rule_decl_of_source_id(source_id)?;
+    self.type_inference().rule_variable_types(rule_decl.rule().location())
+  }
 }
@@ -19,6 +19,11 @@ impl FrontContext {
       }));
     }
 
+    // Check doc comments
+    if let Some(doc) = self.analysis.borrow().doc_comment_analysis.doc(relation) {
+      attrs.add_attribute(back::Attribute::Doc(back::DocAttribute { doc: doc.clone() }));
+    }
+
     attrs
   }
 }
@@ -314,12 +314,15 @@ impl FrontContext {
 
     // First pull out the boundness analysis
     for (conj_idx, conj_ctx) in rule_ctx.body.conjuncts.iter().enumerate() {
+      // Aggregations over the same body and group-by share the relations computing them
+      let mut shared_body_predicates = HashMap::<String, String>::new();
+
       // Generate aggregations
       let reduce_formulas = conj_ctx
         .agg_contexts
         .iter()
         .enumerate()
-        .map(|(agg_idx, agg_ctx)| {
+        .flat_map(|(agg_idx, agg_ctx)| {
           let predicate = format!(
             "{}#{}#agg#{}#{}",
             parent_predicate,
@@ -327,17 +330,23 @@ impl FrontContext {
             conj_idx,
             agg_idx
           );
+          let (predicate, reuse_body) = match shared_body_predicates.entry(agg_ctx.shared_body_key()) {
+            hash_map::Entry::Occupied(e) => (e.get().clone(), true),
+            hash_map::Entry::Vacant(e) => (e.insert(predicate).clone(), false),
+          };
           self.reduce_to_back_literal(
             flatten_expr,
             src_rule_loc,
             rule_ctx,
             agg_ctx,
             predicate,
+            reuse_body,
             temp_relations,
             &mut temp_rules,
           )
         })
         .collect::<Vec<_>>();
+      let reduce_formulas = merge_shared_reduces(reduce_formulas);
 
       // Create a context and visit all atoms
       conj_ctx.pos_atoms.iter().for_each(|a| flatten_expr.walk_formula(a));
@@ -370,9 +379,10 @@ impl FrontContext {
     rule_ctx: &RuleContext,
     agg_ctx: &AggregationContext,
     predicate: String,
+    reuse_body: bool,
     temp_relations: &mut Vec<back::Relation>,
     temp_rules: &mut Vec<back::Rule>,
-  ) -> back::Literal {
+  ) -> Vec<back::Literal> {
     // unwrap is ok because the success of compute boundness is checked already
     let pred_bindings = ForeignPredicateBindings::from(&self.foreign_predicate_registry);
     let body_bounded_vars = agg_ctx.body.compute_boundness(&pred_bindings, &vec![]).unwrap();
@@ -384,9 +394,38 @@ impl FrontContext {
       .cloned()
       .collect::<HashSet<_>>();
 
-    // get the core variables
-    let to_agg_var_names = agg_ctx.binding_variable_names();
-    let arg_var_names = agg_ctx.argument_variable_names();
+    // get the core variables; `argmin`/`argmax` aggregate their key along with the binding variables, in order,
+    // so that the resulting tuples directly match the result variables, and so does `sample` after its weight;
+    // `normalize` produces the binding variables after its value followed by the normalized weight
+    let (to_agg_var_names, arg_var_names, left_var_names) = if agg_ctx.aggregate_op.has_ordered_bindings() {
+      let left_var_names: Vec<String> = agg_ctx
+        .output_binding_variable_names()
+        .into_iter()
+        .chain(agg_ctx.result_vars.iter().map(|v| v.name().to_string()))
+        .collect();
+      (agg_ctx.ordered_aggregated_variable_names(), vec![], left_var_names)
+    } else {
+      let to_agg_var_names = agg_ctx.binding_variable_names().into_iter().collect::<Vec<_>>();
+      let arg_var_names = agg_ctx.argument_variable_names().into_iter().collect::<Vec<_>>();
+      (
+        to_agg_var_names,
+        arg_var_names,
+        agg_ctx.left_variable_names().into_iter().collect(),
+      )
+    };
+
+    // the result with an annotated type is produced by the aggregation in its natural type, and cast afterwards
+    let result_cast = match (&agg_ctx.result_type, agg_ctx.result_vars.first()) {
+      (Some(ty), Some(v)) => Some((v.name().to_string(), self.type_inference().find_value_type(ty).unwrap())),
+      _ => None,
+    };
+    let left_var_names = left_var_names
+      .into_iter()
+      .map(|v| match &result_cast {
+        Some((n, _)) if n == &v => front::Reduce::raw_result_variable_name(&v),
+        _ => v,
+      })
+      .collect::<Vec<_>>();
 
     // check if there is group-by formula
     let (group_by_vars, other_group_by_vars, group_by_atom) = if let Some((group_by_ctx, _, _)) = &agg_ctx.group_by {
@@ -410,29 +449,30 @@ impl FrontContext {
       let group_by_types = self.type_inference().variable_types(src_rule_loc, group_by_vars.iter());
       let group_by_terms = self.back_terms_with_types(group_by_vars, group_by_types.clone());
 
-      // Create a temporary relation for group_by
-      let group_by_relation_attr = back::AggregateGroupByAttribute::new(joined_vars.len(), other_group_by_vars.len());
-      let group_by_relation_attrs = back::Attributes::singleton(group_by_relation_attr);
-      let group_by_relation = back::Relation::new_with_attrs(
-        group_by_relation_attrs,
-        group_by_predicate.clone(),
-        group_by_types.clone(),
-      );
-      temp_relations.push(group_by_relation);
-
-      // Create temporary rule(s) for group_by
-      let group_by_rule_head = back::Head::atom(group_by_predicate.clone(), group_by_terms.clone());
-      let group_by_rules = self.formula_to_back_rules(
-        flatten_expr,
-        src_rule_loc,
-        back::Attributes::new(),
-        group_by_predicate.clone(),
-        group_by_ctx,
-        group_by_rule_head.clone(),
-        vec![],
-        temp_relations,
-      );
-      temp_rules.extend(group_by_rules);
+      // Create a temporary relation and rule(s) for group_by, unless they are shared with a previous aggregation
+      if !reuse_body {
+        let group_by_relation_attr = back::AggregateGroupByAttribute::new(joined_vars.len(), other_group_by_vars.len());
+        let group_by_relation_attrs = back::Attributes::singleton(group_by_relation_attr);
+        let group_by_relation = back::Relation::new_with_attrs(
+          group_by_relation_attrs,
+          group_by_predicate.clone(),
+          group_by_types.clone(),
+        );
+        temp_relations.push(group_by_relation);
+
+        let group_by_rule_head = back::Head::atom(group_by_predicate.clone(), group_by_terms.clone());
+        let group_by_rules = self.formula_to_back_rules(
+          flatten_expr,
+          src_rule_loc,
+          back::Attributes::new(),
+          group_by_predicate.clone(),
+          group_by_ctx,
+          group_by_rule_head.clone(),
+          vec![],
+          temp_relations,
+        );
+        temp_rules.extend(group_by_rules);
+      }
 
       // Create group_by atom to be placed inside reduce literal
       let group_by_atom = back::Atom::new(group_by_predicate.clone(), group_by_terms.clone());
@@ -472,29 +512,31 @@ impl FrontContext {
     let body_tys = self.type_inference().variable_types(src_rule_loc, body_args.iter());
     let body_terms = self.back_terms_with_types(body_args.clone(), body_tys.clone());
 
-    // Get the body to-aggregate relation
-    let body_attr = back::AggregateBodyAttribute::new(group_by_vars.len(), arg_var_names.len(), to_agg_var_names.len());
-    let body_attrs = back::Attributes::singleton(body_attr);
-    let body_relation = back::Relation::new_with_attrs(body_attrs, body_predicate.clone(), body_tys.clone());
-    temp_relations.push(body_relation);
-
-    // Get the rules for body
-    let body_head = back::Head::atom(body_predicate.clone(), body_terms.clone());
-    let body_rules = self.formula_to_back_rules(
-      flatten_expr,
-      src_rule_loc,
-      back::Attributes::new(),
-      body_predicate.clone(),
-      &agg_ctx.body,
-      body_head,
-      vec![],
-      temp_relations,
-    );
-    temp_rules.extend(body_rules);
+    // Get the body to-aggregate relation and its rules, unless they are shared with a previous aggregation
+    if !reuse_body {
+      let body_attr =
+        back::AggregateBodyAttribute::new(group_by_vars.len(), arg_var_names.len(), to_agg_var_names.len());
+      let body_attrs = back::Attributes::singleton(body_attr);
+      let body_relation = back::Relation::new_with_attrs(body_attrs, body_predicate.clone(), body_tys.clone());
+      temp_relations.push(body_relation);
+
+      let body_head = back::Head::atom(body_predicate.clone(), body_terms.clone());
+      let body_rules = self.formula_to_back_rules(
+        flatten_expr,
+        src_rule_loc,
+        back::Attributes::new(),
+        body_predicate.clone(),
+        &agg_ctx.body,
+        body_head,
+        vec![],
+        temp_relations,
+      );
+      temp_rules.extend(body_rules);
+    }
 
     // Get the reduce literal
     let body_atom = back::Atom::new(body_predicate.clone(), body_terms);
-    let left_vars = self.back_vars(src_rule_loc, agg_ctx.left_variable_names().into_iter().collect());
+    let left_vars = self.back_vars(src_rule_loc, left_var_names);
     let group_by_vars = self.back_vars(src_rule_loc, group_by_vars.into_iter().collect());
     let other_group_by_vars = self.back_vars(src_rule_loc, other_group_by_vars);
     let arg_vars = self.back_vars(src_rule_loc, arg_var_names.into_iter().collect());
@@ -502,7 +544,54 @@ impl FrontContext {
 
     // Generate the internal aggregate operator
     let op = match &agg_ctx.aggregate_op {
+      front::ReduceOperatorNode::Window { op, size } => {
+        // The arguments of window aggregation are used for ordering, not passed to the inner aggregator
+        let op = self.aggregate_op(op, &left_vars, &to_agg_vars, false);
+        AggregateOp::window(op, size.clone())
+      }
+      op => self.aggregate_op(op, &left_vars, &to_agg_vars, !arg_vars.is_empty()),
+    };
+
+    // Get the literal
+    let reduce_literal = back::Reduce::new(
+      op,
+      left_vars,
+      group_by_vars,
+      other_group_by_vars,
+      arg_vars,
+      to_agg_vars,
+      body_atom,
+      group_by_atom,
+    );
+
+    // Cast the raw result into the annotated result type
+    let mut literals = vec![back::Literal::Reduce(reduce_literal)];
+    if let Some((result_var_name, result_ty)) = result_cast {
+      let raw_var_name = front::Reduce::raw_result_variable_name(&result_var_name);
+      let raw_var_ty = self.type_inference().variable_type(src_rule_loc, &raw_var_name);
+      literals.push(back::Literal::Assign(back::Assign {
+        left: back::Variable::new(result_var_name, result_ty.clone()),
+        right: back::AssignExpr::Unary(back::UnaryAssignExpr {
+          op: back::UnaryExprOp::TypeCast(result_ty),
+          op1: back::Term::variable(raw_var_name, raw_var_ty),
+        }),
+      }));
+    }
+
+    // Return
+    literals
+  }
+
+  fn aggregate_op(
+    &self,
+    op: &front::ReduceOperatorNode,
+    left_vars: &Vec<back::Variable>,
+    to_agg_vars: &Vec<back::Variable>,
+    has_arg: bool,
+  ) -> AggregateOp {
+    match op {
       front::ReduceOperatorNode::Count => AggregateOp::Count,
+      front::ReduceOperatorNode::CountDistinct => AggregateOp::CountDistinct,
       front::ReduceOperatorNode::Sum => {
         assert_eq!(left_vars.len(), 1, "There should be only one var for summation");
         AggregateOp::Sum(left_vars[0].ty.clone())
@@ -511,34 +600,75 @@ impl FrontContext {
         assert_eq!(left_vars.len(), 1, "There should be only one var for production");
         AggregateOp::Prod(left_vars[0].ty.clone())
       }
-      front::ReduceOperatorNode::Min => AggregateOp::min(!arg_vars.is_empty()),
-      front::ReduceOperatorNode::Max => AggregateOp::max(!arg_vars.is_empty()),
+      front::ReduceOperatorNode::WeightedSum => {
+        assert_eq!(
+          left_vars.len(),
+          1,
+          "There should be only one var for weighted summation"
+        );
+        AggregateOp::WeightedSum(left_vars[0].ty.clone())
+      }
+      front::ReduceOperatorNode::Variance => {
+        assert_eq!(to_agg_vars.len(), 1, "There should be only one var for variance");
+        AggregateOp::Variance(to_agg_vars[0].ty.clone())
+      }
+      front::ReduceOperatorNode::StdDev => {
+        assert_eq!(
+          to_agg_vars.len(),
+          1,
+          "There should be only one var for standard deviation"
+        );
+        AggregateOp::StdDev(to_agg_vars[0].ty.clone())
+      }
+      front::ReduceOperatorNode::Median => {
+        assert_eq!(to_agg_vars.len(), 1, "There should be only one var for median");
+        AggregateOp::Median(to_agg_vars[0].ty.clone())
+      }
+      front::ReduceOperatorNode::Collect => AggregateOp::Collect,
+      front::ReduceOperatorNode::GeometricMean => {
+        assert_eq!(to_agg_vars.len(), 1, "There should be only one var for geometric mean");
+        AggregateOp::GeometricMean(to_agg_vars[0].ty.clone())
+      }
+      front::ReduceOperatorNode::HarmonicMean => {
+        assert_eq!(to_agg_vars.len(), 1, "There should be only one var for harmonic mean");
+        AggregateOp::HarmonicMean(to_agg_vars[0].ty.clone())
+      }
+      front::ReduceOperatorNode::Min => AggregateOp::min(has_arg),
+      front::ReduceOperatorNode::Max => AggregateOp::max(has_arg),
+      front::ReduceOperatorNode::ArgMin => AggregateOp::ArgminTuple,
+      front::ReduceOperatorNode::ArgMax => AggregateOp::ArgmaxTuple,
       front::ReduceOperatorNode::Exists => AggregateOp::Exists,
+      front::ReduceOperatorNode::Any => {
+        // Without binding vars there is no witness, and `any` is the same as `exists`
+        if to_agg_vars.is_empty() {
+          AggregateOp::Exists
+        } else {
+          AggregateOp::Any
+        }
+      }
       front::ReduceOperatorNode::Unique => AggregateOp::top_k(1),
-      front::ReduceOperatorNode::TopK(k) => AggregateOp::top_k(k.clone()),
+      front::ReduceOperatorNode::TopK(k) => AggregateOp::top_k_with_arg(k.clone(), has_arg),
+      front::ReduceOperatorNode::Rank => AggregateOp::rank(has_arg),
       front::ReduceOperatorNode::CategoricalK(k) => AggregateOp::categorical_k(k.clone()),
+      front::ReduceOperatorNode::Sample => {
+        // The extra binding var, if any, is the weight of the tuple
+        if to_agg_vars.len() == left_vars.len() + 1 {
+          AggregateOp::Sample(Some(to_agg_vars[0].ty.clone()))
+        } else {
+          AggregateOp::Sample(None)
+        }
+      }
+      front::ReduceOperatorNode::Normalize => AggregateOp::Normalize(to_agg_vars[0].ty.clone()),
+      front::ReduceOperatorNode::Window { .. } => {
+        panic!("There should be no nested window aggregator op. This is a bug");
+      }
       front::ReduceOperatorNode::Forall => {
         panic!("There should be no forall aggregator op. This is a bug");
       }
       front::ReduceOperatorNode::Unknown(_) => {
         panic!("There should be no unknown aggregator op. This is a bug");
       }
-    };
-
-    // Get the literal
-    let reduce_literal = back::Reduce::new(
-      op,
-      left_vars,
-      group_by_vars,
-      other_group_by_vars,
-      arg_vars,
-      to_agg_vars,
-      body_atom,
-      group_by_atom,
-    );
-
-    // Return
-    back::Literal::Reduce(reduce_literal)
+    }
   }
 
   fn back_terms_with_types(&self, var_names: Vec<String>, var_tys: Vec<ValueType>) -> Vec<back::Term> {
@@ -567,3 +697,25 @@ impl FrontContext {
     self.back_vars_with_types(var_names, var_tys)
   }
 }
+
+/// Merge the reduces aggregating over the same tuples in the same groups into a single reduce with multiple
+/// aggregators, so that the groups are only computed once
+fn merge_shared_reduces(literals: Vec<back::Literal>) -> Vec<back::Literal> {
+  let mut merged: Vec<back::Literal> = vec![];
+  for literal in literals {
+    match literal {
+      back::Literal::Reduce(r) => {
+        let shared = merged.iter_mut().find_map(|l| match l {
+          back::Literal::Reduce(m) if m.can_share_aggregation_with(&r) => Some(m),
+          _ => None,
+        });
+        match shared {
+          Some(m) => m.add_aggregator(r),
+          None => merged.push(back::Literal::Reduce(r)),
+        }
+      }
+      literal => merged.push(literal),
+    }
+  }
+  merged
+}
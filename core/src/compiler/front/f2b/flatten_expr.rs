@@ -35,6 +35,8 @@ pub enum FlattenedNode {
     cond: Loc,
     then_br: Loc,
     else_br: Loc,
+    then_br_cast: Option<back::Variable>,
+    else_br_cast: Option<back::Variable>,
   },
   Call {
     left: back::Variable,
@@ -107,7 +109,9 @@ impl<'a> FlattenExprContext<'a> {
           cond,
           then_br,
           else_br,
-        } => self.collect_flattened_literals_of_if_then_else_op(left, cond, then_br, else_br),
+          then_br_cast,
+          else_br_cast,
+        } => self.collect_flattened_literals_of_if_then_else_op(left, cond, then_br, else_br, then_br_cast, else_br_cast),
         FlattenedNode::Call { left, function, args } => {
           self.collect_flattened_literals_of_call_op(left, function, args)
         }
@@ -185,13 +189,27 @@ impl<'a> FlattenExprContext<'a> {
     cond: &Loc,
     then_br: &Loc,
     else_br: &Loc,
+    then_br_cast: &Option<back::Variable>,
+    else_br_cast: &Option<back::Variable>,
   ) -> Vec<back::Literal> {
     let mut curr_literals = vec![];
 
+    // The branches of a different type than the expression are casted first
+    let mut branch_term = |br: &Loc, cast: &Option<back::Variable>| {
+      let term = self.get_loc_term(br);
+      if let Some(cast_var) = cast {
+        let op = back::UnaryExprOp::TypeCast(left.ty.clone());
+        curr_literals.push(back::Literal::unary_expr(cast_var.clone(), op, term));
+        back::Term::Variable(cast_var.clone())
+      } else {
+        term
+      }
+    };
+    let then_br_term = branch_term(then_br, then_br_cast);
+    let else_br_term = branch_term(else_br, else_br_cast);
+
     // The if-then-else expression literal
     let cond_term = self.get_loc_term(cond);
-    let then_br_term = self.get_loc_term(then_br);
-    let else_br_term = self.get_loc_term(else_br);
     let literal = back::Literal::if_then_else_expr(left.clone(), cond_term, then_br_term, else_br_term);
     curr_literals.push(literal);
 
@@ -343,6 +361,11 @@ impl<'a> FlattenExprContext<'a> {
     match c.expr() {
       Expr::Binary(b) => self.binary_constraint_to_back_literal(b),
       Expr::Unary(u) => self.unary_constraint_to_back_literal(u),
+      Expr::Constant(c) => match &c.node {
+        ConstantNode::Boolean(true) => vec![back::Literal::True],
+        ConstantNode::Boolean(false) => vec![back::Literal::False],
+        _ => panic!("[Internal Error] Cannot have non-boolean constant constraint expression"),
+      },
       _ => {
         panic!("[Internal Error] Cannot have non-binary/non-unary constraint expression");
       }
@@ -421,16 +444,28 @@ impl<'a> NodeVisitor for FlattenExprContext<'a> {
 
   fn visit_if_then_else_expr(&mut self, i: &ast::IfThenElseExpr) {
     let tmp_var_name = self.allocate_tmp_var();
+    let ty = self.type_inference.expr_value_type(i).unwrap();
+
+    // The branches could be of narrower numeric types, in which case they need to be casted
+    let mut branch_cast = |br: &Expr| match self.type_inference.expr_types.get(br.location()) {
+      Some(br_ty) if br_ty.to_default_value_type() != ty => Some(back::Variable {
+        name: self.allocate_tmp_var(),
+        ty: ty.clone(),
+      }),
+      _ => None,
+    };
+    let then_br_cast = branch_cast(i.then_br());
+    let else_br_cast = branch_cast(i.else_br());
+
     self.internal.insert(
       i.location().clone(),
       FlattenedNode::IfThenElse {
-        left: back::Variable {
-          name: tmp_var_name,
-          ty: self.type_inference.expr_value_type(i).unwrap(),
-        },
+        left: back::Variable { name: tmp_var_name, ty },
         cond: i.cond().location().clone(),
         then_br: i.then_br().location().clone(),
         else_br: i.else_br().location().clone(),
+        then_br_cast,
+        else_br_cast,
       },
     );
   }
@@ -401,6 +401,8 @@ impl Display for Reduce {
           .collect::<Vec<_>>()
           .join(", ")
       ))?;
+    } else if let Some(ty) = self.result_type() {
+      f.write_fmt(format_args!("({}: {})", self.left()[0], ty))?;
     } else {
       Display::fmt(self.left().iter().next().unwrap(), f)?;
     }
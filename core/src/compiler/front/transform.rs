@@ -29,7 +29,10 @@ pub fn apply_transformations(ast: &mut Vec<Item>, analysis: &Analysis) {
   transform_const_var_to_const.walk_items(&mut new_items);
 
   // Extend the ast to incorporate these new items
-  ast.extend(new_items)
+  ast.extend(new_items);
+
+  // Fold the constant expressions last, after the constant variables have been substituted
+  TransformConstantFold::new(&analysis.constant_decl_analysis).walk_items(ast);
 }
 
 pub trait Transformation {
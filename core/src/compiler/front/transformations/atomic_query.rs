@@ -78,6 +78,7 @@ impl Transformation for TransformAtomicQuery {
           attrs: vec![],
           tag: Tag::default_none(),
           rule: rule.clone(),
+          doc: None,
         };
         let rel_decl = RelationDeclNode::Rule(rule_decl.into());
         let item = Item::RelationDecl(rel_decl.into());
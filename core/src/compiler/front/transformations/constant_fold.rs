@@ -0,0 +1,128 @@
+use crate::common::binary_op::BinaryOp as BinOp;
+use crate::compiler::front::analyzers::ConstantDeclAnalysis;
+use crate::compiler::front::*;
+
+/// Folding the unary and binary expressions whose operands are all constants, e.g. `1 + 2` should be
+/// rewritten to `3` and `!true` to `false`
+///
+/// The folding happens before type inference, so only the operations whose results do not depend on
+/// the inferred types are folded. For example, integer division is only folded when it is exact,
+/// since the integers might turn out to be floating points, and floating point arithmetic is never
+/// folded, since it might be performed in single precision. Operations that would fail at runtime
+/// (e.g. division by zero) or that are ill-typed are left for the later stages to report, and so are
+/// the constant variables declared with a type annotation, as the annotation is tied to their uses.
+/// Function calls are never evaluated, as foreign functions might be non-deterministic. The folded
+/// constant takes the location of the whole expression, so that errors are still reported on the source.
+#[derive(Clone, Debug)]
+pub struct TransformConstantFold<'a> {
+  const_decl_analysis: &'a ConstantDeclAnalysis,
+}
+
+impl<'a> TransformConstantFold<'a> {
+  pub fn new(const_decl_analysis: &'a ConstantDeclAnalysis) -> Self {
+    Self { const_decl_analysis }
+  }
+
+  /// Get the constant of an expression, unless it is a use of a constant variable with a declared type
+  fn untyped_constant<'b>(&self, expr: &'b Expr) -> Option<&'b ConstantNode> {
+    match expr {
+      Expr::Constant(c) if self.const_decl_analysis.loc_of_const_type(c.location()).is_none() => Some(&c.node),
+      _ => None,
+    }
+  }
+
+  fn fold(&self, expr: &mut Expr) {
+    let folded = match expr {
+      Expr::Binary(b) => {
+        self.fold(&mut b.node.op1);
+        self.fold(&mut b.node.op2);
+        match (self.untyped_constant(b.op1()), self.untyped_constant(b.op2())) {
+          (Some(c1), Some(c2)) => fold_binary(&b.op().node, c1, c2),
+          _ => None,
+        }
+      }
+      Expr::Unary(u) => {
+        self.fold(&mut u.node.op1);
+        self.untyped_constant(u.op1()).and_then(|c| fold_unary(&u.op().node, c))
+      }
+      Expr::IfThenElse(i) => {
+        // Both branches are kept so that they are still type checked; the if-then-else with a constant
+        // condition is only reduced to the taken branch by the back compiler, after type inference
+        self.fold(&mut i.node.cond);
+        self.fold(&mut i.node.then_br);
+        self.fold(&mut i.node.else_br);
+        None
+      }
+      _ => None,
+    };
+    if let Some(node) = folded {
+      *expr = Expr::Constant(Constant::new(expr.location().clone(), node));
+    }
+  }
+}
+
+impl<'a> NodeVisitorMut for TransformConstantFold<'a> {
+  fn visit_expr(&mut self, expr: &mut Expr) {
+    self.fold(expr)
+  }
+}
+
+fn fold_binary(op: &BinOp, c1: &ConstantNode, c2: &ConstantNode) -> Option<ConstantNode> {
+  use ConstantNode::*;
+  match (op, c1, c2) {
+    // Integer arithmetic
+    (BinOp::Add, Integer(i1), Integer(i2)) => i1.checked_add(*i2).map(Integer),
+    (BinOp::Sub, Integer(i1), Integer(i2)) => i1.checked_sub(*i2).map(Integer),
+    (BinOp::Mul, Integer(i1), Integer(i2)) => i1.checked_mul(*i2).map(Integer),
+    (BinOp::Div, Integer(i1), Integer(i2)) if i1.checked_rem(*i2) == Some(0) => i1.checked_div(*i2).map(Integer),
+    (BinOp::Mod, Integer(i1), Integer(i2)) => i1.checked_rem(*i2).map(Integer),
+
+    // String concatenation
+    (BinOp::Add, String(s1), String(s2)) => Some(String(format!("{}{}", s1, s2))),
+
+    // Date and duration arithmetic
+    (BinOp::Add, DateTime(d1), Duration(d2)) => d1.checked_add_signed(*d2).map(DateTime),
+    (BinOp::Add, Duration(d1), DateTime(d2)) => d2.checked_add_signed(*d1).map(DateTime),
+    (BinOp::Add, Duration(d1), Duration(d2)) => d1.checked_add(d2).map(Duration),
+    (BinOp::Sub, DateTime(d1), Duration(d2)) => d1.checked_sub_signed(*d2).map(DateTime),
+    (BinOp::Sub, DateTime(d1), DateTime(d2)) => Some(Duration(d1.signed_duration_since(*d2))),
+    (BinOp::Sub, Duration(d1), Duration(d2)) => d1.checked_sub(d2).map(Duration),
+
+    // Boolean logic
+    (BinOp::And, Boolean(b1), Boolean(b2)) => Some(Boolean(*b1 && *b2)),
+    (BinOp::Or, Boolean(b1), Boolean(b2)) => Some(Boolean(*b1 || *b2)),
+    (BinOp::Xor, Boolean(b1), Boolean(b2)) => Some(Boolean(*b1 ^ *b2)),
+
+    // Comparisons
+    (BinOp::Eq | BinOp::Neq, Integer(_), Integer(_))
+    | (BinOp::Eq | BinOp::Neq, Boolean(_), Boolean(_))
+    | (BinOp::Eq | BinOp::Neq, Char(_), Char(_))
+    | (BinOp::Eq | BinOp::Neq, String(_), String(_))
+    | (BinOp::Eq | BinOp::Neq, DateTime(_), DateTime(_))
+    | (BinOp::Eq | BinOp::Neq, Duration(_), Duration(_)) => Some(Boolean((c1 == c2) == op.is_eq())),
+    (BinOp::Gt, Integer(i1), Integer(i2)) => Some(Boolean(i1 > i2)),
+    (BinOp::Geq, Integer(i1), Integer(i2)) => Some(Boolean(i1 >= i2)),
+    (BinOp::Lt, Integer(i1), Integer(i2)) => Some(Boolean(i1 < i2)),
+    (BinOp::Leq, Integer(i1), Integer(i2)) => Some(Boolean(i1 <= i2)),
+    (BinOp::Gt, DateTime(d1), DateTime(d2)) => Some(Boolean(d1 > d2)),
+    (BinOp::Geq, DateTime(d1), DateTime(d2)) => Some(Boolean(d1 >= d2)),
+    (BinOp::Lt, DateTime(d1), DateTime(d2)) => Some(Boolean(d1 < d2)),
+    (BinOp::Leq, DateTime(d1), DateTime(d2)) => Some(Boolean(d1 <= d2)),
+    (BinOp::Gt, Duration(d1), Duration(d2)) => Some(Boolean(d1 > d2)),
+    (BinOp::Geq, Duration(d1), Duration(d2)) => Some(Boolean(d1 >= d2)),
+    (BinOp::Lt, Duration(d1), Duration(d2)) => Some(Boolean(d1 < d2)),
+    (BinOp::Leq, Duration(d1), Duration(d2)) => Some(Boolean(d1 <= d2)),
+    _ => None,
+  }
+}
+
+fn fold_unary(op: &UnaryOpNode, c: &ConstantNode) -> Option<ConstantNode> {
+  use ConstantNode::*;
+  match (op, c) {
+    (UnaryOpNode::Neg, Integer(i)) => i.checked_neg().map(Integer),
+    (UnaryOpNode::Neg, Float(f)) => Some(Float(-f)),
+    (UnaryOpNode::Pos, Integer(_) | Float(_)) => Some(c.clone()),
+    (UnaryOpNode::Not, Boolean(b)) => Some(Boolean(!b)),
+    _ => None,
+  }
+}
@@ -39,6 +39,7 @@ impl NodeVisitorMut for DesugarForallExists {
           node: ReduceNode {
             operator: r.node.operator.clone(),
             left: vec![VariableOrWildcard::Variable(boolean_var.clone())],
+            result_type: None,
             args: vec![],
             bindings: r.node.bindings.clone(),
             body: r.node.body.clone(),
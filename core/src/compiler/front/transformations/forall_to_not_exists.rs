@@ -51,6 +51,7 @@ impl TransformForall {
               i.location().clone_without_id(),
               ReduceNode {
                 left: vec![VariableOrWildcard::Variable(temp_var)],
+                result_type: None,
                 operator: ReduceOperator::new(r.operator().location().clone_without_id(), ReduceOperatorNode::Exists),
                 args: r.node.args.clone(),
                 bindings: r.node.bindings.clone(),
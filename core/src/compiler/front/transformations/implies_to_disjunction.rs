@@ -8,13 +8,7 @@ impl NodeVisitorMut for TransformImplies {
   fn visit_formula(&mut self, formula: &mut Formula) {
     match formula {
       Formula::Implies(i) => {
-        let rewrite = Formula::Disjunction(Disjunction::new(
-          i.location().clone(),
-          DisjunctionNode {
-            args: vec![i.left().negate(), i.right().clone()],
-          },
-        ));
-        *formula = rewrite;
+        *formula = i.to_disjunction();
       }
       _ => {}
     }
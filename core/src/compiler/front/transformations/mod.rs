@@ -1,5 +1,6 @@
 mod atomic_query;
 mod const_var_to_const;
+mod constant_fold;
 mod desugar_forall_exists;
 mod forall_to_not_exists;
 mod implies_to_disjunction;
@@ -8,6 +9,7 @@ mod tagged_rule;
 
 pub use atomic_query::*;
 pub use const_var_to_const::*;
+pub use constant_fold::*;
 pub use desugar_forall_exists::*;
 pub use forall_to_not_exists::*;
 pub use implies_to_disjunction::*;
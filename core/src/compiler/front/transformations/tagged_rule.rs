@@ -72,6 +72,7 @@ impl Transformation for TransformTaggedRule {
           attrs: vec![],
           tag: TagNode(tag).into(),
           atom: fact.into(),
+          doc: None,
         };
         let rel_decl = RelationDeclNode::Fact(fact_decl.into());
         let item = Item::RelationDecl(rel_decl.into());
@@ -337,6 +337,7 @@ pub trait NodeVisitor {
         VariableOrWildcard::Wildcard(w) => self.walk_wildcard(w),
       }
     }
+    self.walk_option_type(&reduce.node.result_type);
     self.walk_reduce_op(&reduce.node.operator);
     for binding in &reduce.node.bindings {
       self.walk_variable_binding(binding);
@@ -578,3 +579,5 @@ impl_node_visitor_tuple!(A, B, C, D, E, F, G,);
 impl_node_visitor_tuple!(A, B, C, D, E, F, G, H,);
 impl_node_visitor_tuple!(A, B, C, D, E, F, G, H, I,);
 impl_node_visitor_tuple!(A, B, C, D, E, F, G, H, I, J,);
+impl_node_visitor_tuple!(A, B, C, D, E, F, G, H, I, J, K,);
+impl_node_visitor_tuple!(A, B, C, D, E, F, G, H, I, J, K, L,);
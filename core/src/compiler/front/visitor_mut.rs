@@ -337,6 +337,7 @@ pub trait NodeVisitorMut {
         VariableOrWildcard::Wildcard(w) => self.walk_wildcard(w),
       }
     }
+    self.walk_option_type(&mut reduce.node.result_type);
     self.walk_reduce_op(&mut reduce.node.operator);
     for binding in &mut reduce.node.bindings {
       self.walk_variable_binding(binding);
@@ -13,6 +13,9 @@ pub struct CompileOptions {
   // Back compile options
   pub do_not_remove_unused_relations: bool,
   pub do_not_demand_transform: bool,
+
+  /// Whether to demand-transform the relations queried with constant arguments, e.g. `query path(1, x)`
+  pub demand_from_queries: bool,
   pub do_not_optimize_ram: bool,
 
   /// Whether static relation type needs to be convertable to dynamic tuple
@@ -21,6 +24,9 @@ pub struct CompileOptions {
   /// Output including hidden things
   pub output_all: bool,
 
+  /// Whether the relations used in rules need to be declared with `type`, instead of having their types inferred
+  pub strict_relation_declarations: bool,
+
   // Allow probability
   pub allow_probability: bool,
 }
@@ -10,6 +10,7 @@ use crate::common::output_option::OutputOption;
 use crate::common::tuple::{AsTuple, Tuple};
 use crate::common::tuple_type::TupleType;
 use crate::common::value::Value;
+use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone)]
 pub struct Program {
@@ -79,7 +80,7 @@ impl Program {
   }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Serialize, Deserialize)]
 pub struct Stratum {
   pub is_recursive: bool,
   pub relations: BTreeMap<String, Relation>,
@@ -92,12 +93,13 @@ impl Stratum {
   }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Serialize, Deserialize)]
 pub struct Relation {
   /// The name of the relation
   pub predicate: String,
 
-  /// The tuple type of the relation; will be always a one level tuple with no nested tuples
+  /// The tuple type of the relation; will be always a one level tuple with no nested tuples, though the
+  /// value type of a column can itself be a tuple (`ValueType::Tuple`)
   pub tuple_type: TupleType,
 
   /// Whether there is a input file where this relation should be loaded from
@@ -111,6 +113,9 @@ pub struct Relation {
 
   /// Whether the relation is immutable, i.e., not being populated by any rule
   pub immutable: bool,
+
+  /// The doc comment attached to the declaration of the relation
+  pub doc: Option<String>,
 }
 
 impl Relation {
@@ -123,6 +128,7 @@ impl Relation {
       facts: vec![],
       output: OutputOption::Hidden,
       immutable: false,
+      doc: None,
     }
   }
 }
@@ -154,30 +160,61 @@ impl std::cmp::Ord for Relation {
   }
 }
 
-#[derive(Debug, Clone, PartialEq, PartialOrd)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Fact {
   pub tag: DynamicInputTag,
   pub tuple: Tuple,
 }
 
+impl std::cmp::PartialEq for Fact {
+  fn eq(&self, other: &Self) -> bool {
+    self.cmp(other) == std::cmp::Ordering::Equal
+  }
+}
+
 impl std::cmp::Eq for Fact {}
 
+impl std::cmp::PartialOrd for Fact {
+  fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+    Some(self.cmp(other))
+  }
+}
+
 impl std::cmp::Ord for Fact {
   fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-    match self.partial_cmp(other) {
-      Some(ord) => ord,
-      _ => panic!("[Internal Error] No ordering found between facts"),
-    }
+    tag_cmp(&self.tag, &other.tag).then_with(|| self.tuple.cmp(&other.tuple))
+  }
+}
+
+/// A total order on input tags; tags of different kinds are ordered by their kind, and probabilities are
+/// ordered with `f64::total_cmp` so that `NaN` is equal to itself
+fn tag_cmp(t1: &DynamicInputTag, t2: &DynamicInputTag) -> std::cmp::Ordering {
+  use DynamicInputTag::*;
+  let kind = |t: &DynamicInputTag| match t {
+    None => 0,
+    Exclusive(_) => 1,
+    Bool(_) => 2,
+    Float(_) => 3,
+    ExclusiveFloat(_, _) => 4,
+    Interval(_, _) => 5,
+  };
+  match (t1, t2) {
+    (Exclusive(e1), Exclusive(e2)) => e1.cmp(e2),
+    (Bool(b1), Bool(b2)) => b1.cmp(b2),
+    (Float(f1), Float(f2)) => f1.total_cmp(f2),
+    (ExclusiveFloat(f1, e1), ExclusiveFloat(f2, e2)) => f1.total_cmp(f2).then_with(|| e1.cmp(e2)),
+    (Interval(l1, u1), Interval(l2, u2)) => l1.total_cmp(l2).then_with(|| u1.total_cmp(u2)),
+    _ => kind(t1).cmp(&kind(t2)),
   }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct Update {
   pub target: String,
   pub dataflow: Dataflow,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum Dataflow {
   // Base relation
   Unit(TupleType),
@@ -186,8 +223,12 @@ pub enum Dataflow {
 
   // Unary operations
   Project(Box<Dataflow>, Expr),
+  Rename(Box<Dataflow>, Vec<usize>),
   Filter(Box<Dataflow>, Expr),
   Find(Box<Dataflow>, Tuple),
+  Sort(Box<Dataflow>, Vec<(usize, bool)>),
+  Limit(Box<Dataflow>, usize),
+  Distinct(Box<Dataflow>),
 
   // Binary operations
   Union(Box<Dataflow>, Box<Dataflow>),
@@ -247,6 +288,17 @@ impl Dataflow {
     Self::Project(Box::new(self), expr.into())
   }
 
+  /// Create a dataflow permuting the columns of its tuples, where the output column `i` is the input
+  /// column `permutation[i]`; it is equivalent to, but cheaper than, projecting with the access expressions
+  pub fn rename(self, permutation: Vec<usize>) -> Self {
+    Self::Rename(Box::new(self), permutation)
+  }
+
+  /// The projection expression equivalent to renaming with the given permutation
+  pub fn rename_expr(permutation: &[usize]) -> Expr {
+    Expr::Tuple(permutation.iter().map(|i| Expr::access(*i)).collect())
+  }
+
   pub fn filter<E: Into<Expr>>(self, expr: E) -> Self {
     Self::Filter(Box::new(self), expr.into())
   }
@@ -255,6 +307,25 @@ impl Dataflow {
     Self::Find(Box::new(self), AsTuple::as_tuple(&t))
   }
 
+  /// Create a dataflow whose batches are sorted by the given `(column, ascending)` pairs; ties are
+  /// broken by the remaining columns in ascending order. The columns are not checked against the
+  /// arity of the tuples; see [`super::DataflowBuilder::sort`] for a checked version
+  pub fn sort(self, order: Vec<(usize, bool)>) -> Self {
+    Self::Sort(Box::new(self), order)
+  }
+
+  /// Create a dataflow keeping only the first `n` elements of each batch, e.g. `limit(sort(scores), 3)`
+  /// takes the three best scores. The elements are taken by their order within the batch, regardless of
+  /// their tags; under a provenance, it is therefore not the top-`n` most likely tuples that are kept.
+  pub fn limit(self, n: usize) -> Self {
+    Self::Limit(Box::new(self), n)
+  }
+
+  /// Create a dataflow where the duplicated tuples of each batch are merged into one, with their tags added
+  pub fn distinct(self) -> Self {
+    Self::Distinct(Box::new(self))
+  }
+
   pub fn overwrite_one(self) -> Self {
     Self::OverwriteOne(Box::new(self))
   }
@@ -293,8 +364,12 @@ impl Dataflow {
       | Self::Antijoin(d1, d2)
       | Self::Difference(d1, d2) => d1.source_relations().union(&d2.source_relations()).cloned().collect(),
       Self::Project(d, _)
+      | Self::Rename(d, _)
       | Self::Filter(d, _)
       | Self::Find(d, _)
+      | Self::Sort(d, _)
+      | Self::Limit(d, _)
+      | Self::Distinct(d)
       | Self::OverwriteOne(d)
       | Self::ForeignPredicateConstraint(d, _, _)
       | Self::ForeignPredicateJoin(d, _, _)
@@ -307,10 +382,18 @@ impl Dataflow {
   }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+/// How the tuples of a reduce are grouped; this also determines the shape of the output tuples
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum ReduceGroupByType {
+  /// All the tuples form a single group; the reduce outputs the bare aggregation results
   None,
+
+  /// The input tuples are `(key, value)` pairs and are grouped by `key`; the reduce outputs
+  /// `(key, result)`, so the group key does not need to be joined back in
   Implicit,
+
+  /// The groups are the `(key, other)` tuples of the given relation, joined with the `(key, value)`
+  /// input tuples; the reduce outputs `(key, other, result)`, including groups that are empty
   Join(String),
 }
 
@@ -328,7 +411,8 @@ impl ReduceGroupByType {
   }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+/// An aggregation over the tuples of a relation; see [`ReduceGroupByType`] for the shape of its output
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct Reduce {
   pub op: AggregateOp,
   pub predicate: String,
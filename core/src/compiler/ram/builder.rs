@@ -0,0 +1,424 @@
+//! # Dataflow Builder
+//!
+//! A builder for RAM dataflows that keeps track of the tuple type flowing out of each operation,
+//! so that malformed expressions are caught while building instead of at runtime.
+
+use crate::common::binary_op::BinaryOp;
+use crate::common::expr::*;
+use crate::common::foreign_function::*;
+use crate::common::tuple::Tuple;
+use crate::common::tuple_access::TupleAccessor;
+use crate::common::tuple_type::TupleType;
+use crate::common::type_family::TypeFamily;
+use crate::common::unary_op::UnaryOp;
+use crate::common::value_type::ValueType;
+
+use super::*;
+
+/// A typed builder for [`Dataflow`].
+///
+/// ```
+/// # use scallop_core::common::expr::Expr;
+/// # use scallop_core::common::tuple_type::TupleType;
+/// # use scallop_core::common::value_type::ValueType;
+/// # use scallop_core::compiler::ram::DataflowBuilder;
+/// let edge = TupleType::from_types(&[ValueType::I32, ValueType::I32], false);
+/// let dataflow = DataflowBuilder::relation("edge", edge)
+///   .filter(Expr::access(0).lt(Expr::access(1)))
+///   .and_then(|b| b.project((Expr::access(1), Expr::access(0))))
+///   .map(|b| b.build());
+/// assert!(dataflow.is_ok());
+/// ```
+#[derive(Clone, Debug)]
+pub struct DataflowBuilder {
+  dataflow: Dataflow,
+  tuple_type: TupleType,
+  function_registry: ForeignFunctionRegistry,
+}
+
+impl DataflowBuilder {
+  /// Start building from a relation of the given tuple type
+  pub fn relation<S: ToString>(relation: S, tuple_type: TupleType) -> Self {
+    Self::new(Dataflow::relation(relation), tuple_type)
+  }
+
+  /// Start building from a relation of a RAM program, taking the tuple type declared in the program
+  pub fn relation_of_program(program: &Program, relation: &str) -> Result<Self, DataflowTypeError> {
+    match program.relation_tuple_type(relation) {
+      Some(tuple_type) => Ok(Self::relation(relation, tuple_type)),
+      None => Err(DataflowTypeError::UnknownRelation {
+        relation: relation.to_string(),
+      }),
+    }
+  }
+
+  /// Start building from a unit dataflow
+  pub fn unit(tuple_type: TupleType) -> Self {
+    Self::new(Dataflow::unit(tuple_type.clone()), tuple_type)
+  }
+
+  fn new(dataflow: Dataflow, tuple_type: TupleType) -> Self {
+    Self {
+      dataflow,
+      tuple_type,
+      function_registry: ForeignFunctionRegistry::std(),
+    }
+  }
+
+  /// Use the given registry to type check foreign function calls; the standard library is used by default
+  pub fn with_function_registry(mut self, function_registry: ForeignFunctionRegistry) -> Self {
+    self.function_registry = function_registry;
+    self
+  }
+
+  /// The tuple type of the dataflow built so far
+  pub fn tuple_type(&self) -> &TupleType {
+    &self.tuple_type
+  }
+
+  /// Keep the tuples satisfying the given boolean expression
+  pub fn filter<E: Into<Expr>>(self, expr: E) -> Result<Self, DataflowTypeError> {
+    let expr = expr.into();
+    match self.type_of_expr(&expr)? {
+      TupleType::Value(ValueType::Bool) => Ok(Self {
+        dataflow: self.dataflow.filter(expr),
+        ..self
+      }),
+      found => Err(DataflowTypeError::NonBooleanFilter { found }),
+    }
+  }
+
+  /// Map each tuple with the given expression
+  pub fn project<E: Into<Expr>>(self, expr: E) -> Result<Self, DataflowTypeError> {
+    let expr = expr.into();
+    let tuple_type = self.type_of_expr(&expr)?;
+    Ok(Self {
+      dataflow: self.dataflow.project(expr),
+      tuple_type,
+      ..self
+    })
+  }
+
+  /// Keep the `(key, value)` tuples whose key is the given tuple
+  pub fn find<T: Into<Tuple>>(self, key: T) -> Result<Self, DataflowTypeError> {
+    let key = key.into();
+    match &self.tuple_type {
+      TupleType::Tuple(ts) if ts.len() == 2 && ts[0].matches(&key) => Ok(Self {
+        dataflow: Dataflow::Find(Box::new(self.dataflow), key),
+        ..self
+      }),
+      _ => Err(DataflowTypeError::InvalidOperandTypes {
+        op: "find".to_string(),
+        operands: vec![self.tuple_type.clone(), TupleType::type_of(&key)],
+      }),
+    }
+  }
+
+  /// Sort the batches by the given `(column, ascending)` pairs; each column should be within the arity of the tuples
+  pub fn sort(self, order: Vec<(usize, bool)>) -> Result<Self, DataflowTypeError> {
+    let arity = match &self.tuple_type {
+      TupleType::Tuple(ts) => ts.len(),
+      TupleType::Value(_) => 0,
+    };
+    if let Some((column, _)) = order.iter().find(|(column, _)| *column >= arity) {
+      return Err(DataflowTypeError::SortColumnOutOfRange {
+        column: *column,
+        tuple_type: self.tuple_type,
+      });
+    }
+    Ok(Self {
+      dataflow: self.dataflow.sort(order),
+      ..self
+    })
+  }
+
+  /// Union with another dataflow of the same tuple type
+  pub fn union(self, other: Self) -> Result<Self, DataflowTypeError> {
+    if self.tuple_type == other.tuple_type {
+      Ok(Self {
+        dataflow: self.dataflow.union(other.dataflow),
+        ..self
+      })
+    } else {
+      Err(DataflowTypeError::InvalidOperandTypes {
+        op: "union".to_string(),
+        operands: vec![self.tuple_type, other.tuple_type],
+      })
+    }
+  }
+
+  /// Cartesian product with another dataflow, producing `(left, right)` tuples
+  pub fn product(self, other: Self) -> Self {
+    Self {
+      dataflow: self.dataflow.product(other.dataflow),
+      tuple_type: TupleType::Tuple(Box::new([self.tuple_type, other.tuple_type])),
+      ..self
+    }
+  }
+
+  /// Join `(key, left)` tuples with `(key, right)` tuples of another dataflow, producing `(key, left, right)` tuples
+  pub fn join(self, other: Self) -> Result<Self, DataflowTypeError> {
+    match (&self.tuple_type, &other.tuple_type) {
+      (TupleType::Tuple(t1), TupleType::Tuple(t2)) if t1.len() == 2 && t2.len() == 2 && t1[0] == t2[0] => {
+        let tuple_type = TupleType::Tuple(Box::new([t1[0].clone(), t1[1].clone(), t2[1].clone()]));
+        Ok(Self {
+          dataflow: self.dataflow.join(other.dataflow),
+          tuple_type,
+          ..self
+        })
+      }
+      _ => Err(DataflowTypeError::InvalidOperandTypes {
+        op: "join".to_string(),
+        operands: vec![self.tuple_type, other.tuple_type],
+      }),
+    }
+  }
+
+  /// Finish building and get the dataflow
+  pub fn build(self) -> Dataflow {
+    self.dataflow
+  }
+
+  /// Compute the type of an expression evaluated on the tuples of the current dataflow
+  pub fn type_of_expr(&self, expr: &Expr) -> Result<TupleType, DataflowTypeError> {
+    match expr {
+      Expr::Tuple(es) => Ok(TupleType::Tuple(
+        es.iter().map(|e| self.type_of_expr(e)).collect::<Result<_, _>>()?,
+      )),
+      Expr::Access(a) => type_of_access(&self.tuple_type, a),
+      Expr::Constant(c) => Ok(TupleType::Value(ValueType::type_of(c))),
+      Expr::Binary(b) => {
+        let op1 = self.type_of_expr(&b.op1)?;
+        let op2 = self.type_of_expr(&b.op2)?;
+        type_of_binary(&b.op, op1, op2)
+      }
+      Expr::Unary(u) => {
+        let op1 = self.type_of_expr(&u.op1)?;
+        type_of_unary(&u.op, op1)
+      }
+      Expr::IfThenElse(i) => {
+        let cond = self.type_of_expr(&i.cond)?;
+        let then_br = self.type_of_expr(&i.then_br)?;
+        let else_br = self.type_of_expr(&i.else_br)?;
+        if cond == TupleType::Value(ValueType::Bool) && then_br == else_br {
+          Ok(then_br)
+        } else {
+          Err(DataflowTypeError::InvalidOperandTypes {
+            op: "if-then-else".to_string(),
+            operands: vec![cond, then_br, else_br],
+          })
+        }
+      }
+      Expr::Call(c) => self.type_of_call(c),
+    }
+  }
+
+  fn type_of_call(&self, call: &CallExpr) -> Result<TupleType, DataflowTypeError> {
+    let function = self
+      .function_registry
+      .get(&call.function)
+      .ok_or_else(|| DataflowTypeError::UnknownFunction {
+        function: call.function.clone(),
+      })?;
+
+    // Check the number of arguments
+    let num_args = call.args.len();
+    if num_args < function.num_static_arguments() || (num_args > 0 && function.argument_type(num_args - 1).is_none()) {
+      return Err(DataflowTypeError::FunctionArityMismatch {
+        function: call.function.clone(),
+        actual: num_args,
+      });
+    }
+
+    // Check the type of each argument, instantiating the generic type parameters along the way
+    let arg_types = call
+      .args
+      .iter()
+      .map(|a| self.type_of_expr(a))
+      .collect::<Result<Vec<_>, _>>()?;
+    let mut generic_types = vec![None; function.num_generic_types()];
+    for (i, arg_type) in arg_types.iter().enumerate() {
+      let ok = match (function.argument_type(i).unwrap(), arg_type) {
+        (ForeignFunctionParameterType::BaseType(t), TupleType::Value(a)) => &t == a,
+        (ForeignFunctionParameterType::TypeFamily(f), TupleType::Value(a)) => family_contains(&f, a),
+        (ForeignFunctionParameterType::Generic(g), TupleType::Value(a)) => match &generic_types[g] {
+          Some(t) => t == a,
+          None => {
+            generic_types[g] = Some(a.clone());
+            family_contains(&function.generic_type_family(g), a)
+          }
+        },
+        _ => false,
+      };
+      if !ok {
+        return Err(DataflowTypeError::InvalidOperandTypes {
+          op: format!("${}", call.function),
+          operands: arg_types,
+        });
+      }
+    }
+
+    // Get the return type
+    match function.return_type() {
+      ForeignFunctionParameterType::BaseType(t) => Ok(TupleType::Value(t)),
+      ForeignFunctionParameterType::Generic(g) if generic_types[g].is_some() => {
+        Ok(TupleType::Value(generic_types[g].clone().unwrap()))
+      }
+      _ => Err(DataflowTypeError::CannotInferReturnType {
+        function: call.function.clone(),
+      }),
+    }
+  }
+}
+
+fn type_of_access(tuple_type: &TupleType, accessor: &TupleAccessor) -> Result<TupleType, DataflowTypeError> {
+  let mut curr = tuple_type;
+  for i in &accessor.indices[0..accessor.len as usize] {
+    curr = match curr {
+      TupleType::Tuple(ts) if *i >= 0 && (*i as usize) < ts.len() => &ts[*i as usize],
+      _ => {
+        return Err(DataflowTypeError::IndexOutOfRange {
+          accessor: *accessor,
+          tuple_type: tuple_type.clone(),
+        })
+      }
+    };
+  }
+  Ok(curr.clone())
+}
+
+fn type_of_binary(op: &BinaryOp, op1: TupleType, op2: TupleType) -> Result<TupleType, DataflowTypeError> {
+  use BinaryOp::*;
+  use ValueType as T;
+  let result = match (op, &op1, &op2) {
+    (op, TupleType::Value(t1), TupleType::Value(t2)) => match (op, t1, t2) {
+      // Arithmetics
+      (Add | Sub | Mul | Div, t1, t2) if t1 == t2 && t1.is_numeric() => Some(t1.clone()),
+      (Mod, t1, t2) if t1 == t2 && t1.is_integer() => Some(t1.clone()),
+      (Add, T::String, T::String) => Some(T::String),
+      (Add, T::DateTime, T::Duration) | (Add, T::Duration, T::DateTime) => Some(T::DateTime),
+      (Sub, T::DateTime, T::Duration) => Some(T::DateTime),
+      (Sub, T::DateTime, T::DateTime) => Some(T::Duration),
+      (Add | Sub, T::Duration, T::Duration) => Some(T::Duration),
+      (Mul, T::Duration, T::I32) | (Mul, T::I32, T::Duration) | (Div, T::Duration, T::I32) => Some(T::Duration),
+
+      // Logical
+      (And | Or | Xor, T::Bool, T::Bool) => Some(T::Bool),
+
+      // Comparisons
+      (Eq | Neq, t1, t2) if t1 == t2 => Some(T::Bool),
+      (Lt | Leq | Gt | Geq, t1, t2) if t1 == t2 && (t1.is_numeric() || t1.is_datetime() || t1.is_duration()) => {
+        Some(T::Bool)
+      }
+      _ => None,
+    },
+    _ => None,
+  };
+  result
+    .map(TupleType::Value)
+    .ok_or_else(|| DataflowTypeError::InvalidOperandTypes {
+      op: op.to_string(),
+      operands: vec![op1, op2],
+    })
+}
+
+fn type_of_unary(op: &UnaryOp, op1: TupleType) -> Result<TupleType, DataflowTypeError> {
+  let result = match (op, &op1) {
+    (UnaryOp::Neg, TupleType::Value(t)) if t.is_signed_integer() || t.is_float() => Some(t.clone()),
+    (UnaryOp::Pos, TupleType::Value(t)) if t.is_numeric() => Some(t.clone()),
+    (UnaryOp::Not, TupleType::Value(ValueType::Bool)) => Some(ValueType::Bool),
+    (UnaryOp::TypeCast(dst), TupleType::Value(t)) if t.can_type_cast(dst) => Some(dst.clone()),
+    _ => None,
+  };
+  result
+    .map(TupleType::Value)
+    .ok_or_else(|| DataflowTypeError::InvalidOperandTypes {
+      op: match op {
+        UnaryOp::Neg => "-".to_string(),
+        UnaryOp::Pos => "+".to_string(),
+        UnaryOp::Not => "!".to_string(),
+        UnaryOp::TypeCast(t) => format!("as {}", t),
+      },
+      operands: vec![op1],
+    })
+}
+
+fn family_contains(family: &TypeFamily, ty: &ValueType) -> bool {
+  match family {
+    TypeFamily::Any => true,
+    TypeFamily::Number => ty.is_numeric(),
+    TypeFamily::Integer => ty.is_integer(),
+    TypeFamily::SignedInteger => ty.is_signed_integer(),
+    TypeFamily::UnsignedInteger => ty.is_unsigned_integer(),
+    TypeFamily::Float => ty.is_float(),
+    TypeFamily::String => ty.is_string(),
+    TypeFamily::Bottom => false,
+  }
+}
+
+/// The errors that can occur when building a dataflow with [`DataflowBuilder`]
+#[derive(Clone, Debug, PartialEq)]
+pub enum DataflowTypeError {
+  UnknownRelation {
+    relation: String,
+  },
+  IndexOutOfRange {
+    accessor: TupleAccessor,
+    tuple_type: TupleType,
+  },
+  InvalidOperandTypes {
+    op: String,
+    operands: Vec<TupleType>,
+  },
+  SortColumnOutOfRange {
+    column: usize,
+    tuple_type: TupleType,
+  },
+  NonBooleanFilter {
+    found: TupleType,
+  },
+  UnknownFunction {
+    function: String,
+  },
+  FunctionArityMismatch {
+    function: String,
+    actual: usize,
+  },
+  CannotInferReturnType {
+    function: String,
+  },
+}
+
+impl std::fmt::Display for DataflowTypeError {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      Self::UnknownRelation { relation } => f.write_fmt(format_args!("Unknown relation `{}`", relation)),
+      Self::IndexOutOfRange { accessor, tuple_type } => f.write_fmt(format_args!(
+        "Tuple access {:?} is out of range for tuple type {}",
+        accessor, tuple_type
+      )),
+      Self::InvalidOperandTypes { op, operands } => f.write_fmt(format_args!(
+        "Cannot apply `{}` on operands of type {}",
+        op,
+        operands.iter().map(|t| t.to_string()).collect::<Vec<_>>().join(", ")
+      )),
+      Self::SortColumnOutOfRange { column, tuple_type } => f.write_fmt(format_args!(
+        "Sort column {} is out of range for tuple type {}",
+        column, tuple_type
+      )),
+      Self::NonBooleanFilter { found } => f.write_fmt(format_args!(
+        "Filter expression should be of type bool, found {}",
+        found
+      )),
+      Self::UnknownFunction { function } => f.write_fmt(format_args!("Unknown foreign function `${}`", function)),
+      Self::FunctionArityMismatch { function, actual } => f.write_fmt(format_args!(
+        "Wrong number of arguments for foreign function `${}`, found {}",
+        function, actual
+      )),
+      Self::CannotInferReturnType { function } => f.write_fmt(format_args!(
+        "Cannot infer the return type of foreign function `${}`",
+        function
+      )),
+    }
+  }
+}
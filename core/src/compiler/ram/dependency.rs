@@ -46,6 +46,99 @@ impl Program {
   }
 }
 
+/// A summary of a single stratum of a compiled program, meant for tooling and visualization
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StratumInfo {
+  /// The id of the stratum, i.e. its position in the evaluation schedule
+  pub id: usize,
+
+  /// The relations computed in this stratum
+  pub relations: Vec<String>,
+
+  /// Whether the stratum needs to be evaluated to a fixpoint
+  pub is_recursive: bool,
+
+  /// The ids of the (earlier) strata this stratum reads from
+  pub dependencies: BTreeSet<usize>,
+}
+
+impl Program {
+  /// Get the id of the stratum computing the given relation, along with whether that stratum is recursive
+  pub fn stratum_of(&self, relation: &str) -> Option<(usize, bool)> {
+    self
+      .relation_to_stratum
+      .get(relation)
+      .map(|id| (*id, self.strata[*id].is_recursive))
+  }
+
+  /// Summarize the stratification of the program, in evaluation order
+  pub fn strata_summary(&self) -> Vec<StratumInfo> {
+    self
+      .strata
+      .iter()
+      .enumerate()
+      .map(|(id, stratum)| StratumInfo {
+        id,
+        relations: stratum.relations.keys().cloned().collect(),
+        is_recursive: stratum.is_recursive,
+        dependencies: stratum
+          .dependency()
+          .iter()
+          .filter_map(|pred| self.relation_to_stratum.get(pred).cloned())
+          .collect(),
+      })
+      .collect()
+  }
+
+  /// Render the program as a graphviz dot graph, with one node per relation and an edge from each
+  /// relation used in the body of an update to its target relation
+  ///
+  /// Each stratum is drawn as a cluster labelled with its id, so that the evaluation order can be read
+  /// off the graph; the recursive strata, which are evaluated to a fixpoint, are highlighted.
+  pub fn to_dot(&self) -> String {
+    let cluster_strs = self.strata.iter().enumerate().map(|(id, stratum)| {
+      let (label, style) = if stratum.is_recursive {
+        (format!("stratum {} (recursive)", id), " style=bold; color=red;")
+      } else {
+        (format!("stratum {}", id), "")
+      };
+      let nodes = stratum
+        .relations
+        .keys()
+        .map(|r| format!("\"{}\";", escape_dot_label(r)))
+        .collect::<Vec<_>>();
+      format!(
+        "subgraph cluster_{} {{ label=\"{}\";{} {} }}",
+        id,
+        label,
+        style,
+        nodes.join(" ")
+      )
+    });
+    let edges = self
+      .strata
+      .iter()
+      .flat_map(|stratum| stratum.updates.iter())
+      .flat_map(|update| {
+        let target = &update.target;
+        update.dataflow.source_relations().into_iter().map(move |source| (source, target))
+      })
+      .collect::<BTreeSet<_>>();
+    let edge_strs = edges
+      .iter()
+      .map(|(source, target)| format!("\"{}\" -> \"{}\";", escape_dot_label(source), escape_dot_label(target)));
+    format!(
+      "digraph program {{ node [shape=box]; {} {} }}",
+      cluster_strs.collect::<Vec<_>>().join(" "),
+      edge_strs.collect::<Vec<_>>().join(" ")
+    )
+  }
+}
+
+fn escape_dot_label(s: &str) -> String {
+  s.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
 impl Stratum {
   pub fn dependency(&self) -> HashSet<String> {
     self
@@ -66,6 +159,12 @@ impl Update {
 }
 
 impl Dataflow {
+  pub fn dependency(&self) -> HashSet<String> {
+    let mut preds = HashSet::new();
+    self.collect_dependency(&mut preds);
+    preds
+  }
+
   fn collect_dependency(&self, preds: &mut HashSet<String>) {
     match self {
       Self::Unit(_)
@@ -88,12 +187,24 @@ impl Dataflow {
       Self::Find(d, _) => {
         d.collect_dependency(preds);
       }
+      Self::Sort(d, _) => {
+        d.collect_dependency(preds);
+      }
+      Self::Limit(d, _) => {
+        d.collect_dependency(preds);
+      }
+      Self::Distinct(d) => {
+        d.collect_dependency(preds);
+      }
       Self::Filter(d, _) => {
         d.collect_dependency(preds);
       }
       Self::Project(d, _) => {
         d.collect_dependency(preds);
       }
+      Self::Rename(d, _) => {
+        d.collect_dependency(preds);
+      }
       Self::Difference(d1, d2) => {
         d1.collect_dependency(preds);
         d2.collect_dependency(preds);
@@ -59,10 +59,12 @@ impl Program {
           }
         }
         std::cmp::Ordering::Greater => {
-          fringe_2.insert((s2, n2));
+          // The stratum `s2` is new, keep `s1` for the next strata of the new program
+          fringe_1.insert((s1, n1));
         }
         std::cmp::Ordering::Less => {
-          fringe_1.insert((s1, n1));
+          // The stratum `s1` is removed, keep `s2` for the next strata of the old program
+          fringe_2.insert((s2, n2));
         }
       }
     }
@@ -134,10 +136,12 @@ impl Program {
           }
         }
         std::cmp::Ordering::Greater => {
-          fringe_2.insert((s2, n2));
+          // The stratum `s2` is new, keep `s1` for the next strata of the new program
+          fringe_1.insert((s1, n1));
         }
         std::cmp::Ordering::Less => {
-          fringe_1.insert((s1, n1));
+          // The stratum `s1` is removed, keep `s2` for the next strata of the old program
+          fringe_2.insert((s2, n2));
         }
       }
     }
@@ -146,6 +150,44 @@ impl Program {
   }
 }
 
+impl Stratum {
+  /// Check whether the relations of the stratum only get new facts when the given relations get new facts
+  ///
+  /// This is the case if the given relations never occur under a negation, an aggregation, or any other
+  /// operation that could remove facts from its result when its input grows. The relations of the stratum
+  /// are considered to be growing as well.
+  pub fn is_monotonic_in(&self, growing: &HashSet<String>) -> bool {
+    let is_growing = |r: &String| growing.contains(r) || self.relations.contains_key(r);
+    self.updates.iter().all(|u| u.dataflow.is_monotonic_in(&is_growing))
+  }
+}
+
+impl Dataflow {
+  fn is_monotonic_in<F: Fn(&String) -> bool>(&self, is_growing: &F) -> bool {
+    match self {
+      Self::Unit(_) | Self::UntaggedVec(_) | Self::Relation(_) | Self::ForeignPredicateGround(_, _) => true,
+      Self::Project(d, _)
+      | Self::Rename(d, _)
+      | Self::Filter(d, _)
+      | Self::Find(d, _)
+      | Self::ForeignPredicateConstraint(d, _, _)
+      | Self::ForeignPredicateJoin(d, _, _) => d.is_monotonic_in(is_growing),
+      Self::Union(d1, d2) | Self::Join(d1, d2) | Self::Intersect(d1, d2) | Self::Product(d1, d2) => {
+        d1.is_monotonic_in(is_growing) && d2.is_monotonic_in(is_growing)
+      }
+      Self::Antijoin(d1, d2) | Self::Difference(d1, d2) => {
+        d1.is_monotonic_in(is_growing) && !d2.dependency().iter().any(is_growing)
+      }
+      Self::Sort(_, _)
+      | Self::Limit(_, _)
+      | Self::Distinct(_)
+      | Self::Reduce(_)
+      | Self::OverwriteOne(_)
+      | Self::Exclusion(_, _) => !self.dependency().iter().any(is_growing),
+    }
+  }
+}
+
 type Fringe<'a> = BTreeSet<(Vec<&'a Relation>, NodeIndex)>;
 
 type Visited = HashSet<NodeIndex>;
@@ -153,8 +195,10 @@ type Visited = HashSet<NodeIndex>;
 fn scc<'a>(p: &'a Program) -> (RamDependencySCCGraph<'a>, Fringe<'a>, Visited) {
   let scc = p.scc();
   let fringe = fringe(&scc);
-  let visited = visited_nodes(&fringe);
-  (scc, fringe, visited)
+
+  // A node is only visited once its relations are persisted, so that the nodes depending on it are not
+  // persisted otherwise
+  (scc, fringe, Visited::new())
 }
 
 fn fringe<'a>(g: &RamDependencySCCGraph<'a>) -> Fringe<'a> {
@@ -170,10 +214,6 @@ fn fringe<'a>(g: &RamDependencySCCGraph<'a>) -> Fringe<'a> {
     .collect()
 }
 
-fn visited_nodes(f: &BTreeSet<(Vec<&Relation>, NodeIndex)>) -> HashSet<NodeIndex> {
-  f.iter().map(|(_, n)| n.clone()).collect()
-}
-
 fn incoming_updates<'a>(n: NodeIndex, g: &RamDependencySCCGraph<'a>) -> BTreeSet<&'a Update> {
   g.edges_directed(n, EdgeDirection::Incoming)
     .flat_map(|e| e.weight().clone().into_iter())
@@ -0,0 +1,355 @@
+use petgraph::graph::DiGraph;
+use std::collections::*;
+
+use crate::common::tuple_type::TupleType;
+
+use super::*;
+
+/// The errors happening when merging two separately compiled RAM programs
+#[derive(Debug, Clone, PartialEq)]
+pub enum MergeError {
+  ConflictingRelationType {
+    relation: String,
+    t1: TupleType,
+    t2: TupleType,
+  },
+  ConflictingInputFile {
+    relation: String,
+  },
+  ConflictingFunction {
+    name: String,
+  },
+  ConflictingPredicate {
+    name: String,
+  },
+  NonStratifiable {
+    relations: Vec<String>,
+  },
+}
+
+impl std::fmt::Display for MergeError {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      Self::ConflictingRelationType { relation, t1, t2 } => f.write_fmt(format_args!(
+        "Conflicting types for relation `{}`: `{}` and `{}`",
+        relation, t1, t2
+      )),
+      Self::ConflictingInputFile { relation } => {
+        f.write_fmt(format_args!("Conflicting input files for relation `{}`", relation))
+      }
+      Self::ConflictingFunction { name } => {
+        f.write_fmt(format_args!("Conflicting definitions of foreign function `${}`", name))
+      }
+      Self::ConflictingPredicate { name } => {
+        f.write_fmt(format_args!("Conflicting definitions of foreign predicate `{}`", name))
+      }
+      Self::NonStratifiable { relations } => f.write_fmt(format_args!(
+        "Merged program is not stratifiable: negation or aggregation inside the recursive relations {}",
+        relations
+          .iter()
+          .map(|r| format!("`{}`", r))
+          .collect::<Vec<_>>()
+          .join(", ")
+      )),
+    }
+  }
+}
+
+impl Program {
+  /// Merge another separately compiled program into this one
+  ///
+  /// Relations declared in both programs need to have the same type; their facts and updates are
+  /// unioned. Foreign functions and predicates defined in both programs need to be the same definitions, i.e.
+  /// with the same signature and implementation. Compiler generated relations of `other` are renamed when they
+  /// clash with this program. The strata are recomputed afterwards so that the merged program has a consistent
+  /// evaluation order.
+  pub fn merge(&mut self, mut other: Program) -> Result<(), MergeError> {
+    // Merge the foreign functions and predicates first, so that nothing is modified on conflict
+    let mut function_registry = self.function_registry.clone();
+    for (name, function) in &other.function_registry {
+      if let Some(existing) = function_registry.get(name) {
+        if !existing.is_same_definition(function) {
+          return Err(MergeError::ConflictingFunction { name: name.clone() });
+        }
+      } else {
+        function_registry
+          .register(function.clone())
+          .map_err(|_| MergeError::ConflictingFunction { name: name.clone() })?;
+      }
+    }
+    let mut predicate_registry = self.predicate_registry.clone();
+    for (name, predicate) in other.predicate_registry.iter() {
+      if let Some(existing) = predicate_registry.get(name) {
+        if !existing.is_same_definition(predicate) {
+          return Err(MergeError::ConflictingPredicate { name: name.clone() });
+        }
+      } else {
+        predicate_registry
+          .register(predicate.clone())
+          .map_err(|_| MergeError::ConflictingPredicate { name: name.clone() })?;
+      }
+    }
+
+    // Rename the generated relations of the other program which clash with the ones of this program
+    other.rename_clashing_generated_relations(&self.relation_to_stratum);
+
+    // Merge the relations
+    let mut relations = self
+      .strata
+      .iter()
+      .flat_map(|s| s.relations.values().cloned())
+      .map(|r| (r.predicate.clone(), r))
+      .collect::<BTreeMap<_, _>>();
+    for other_relation in other.strata.iter().flat_map(|s| s.relations.values()) {
+      if let Some(relation) = relations.get_mut(&other_relation.predicate) {
+        relation.merge(other_relation)?;
+      } else {
+        relations.insert(other_relation.predicate.clone(), other_relation.clone());
+      }
+    }
+
+    // Merge the updates
+    let mut updates = self
+      .strata
+      .iter()
+      .flat_map(|s| s.updates.iter().cloned())
+      .collect::<Vec<_>>();
+    for update in other.strata.into_iter().flat_map(|s| s.updates) {
+      if !updates.contains(&update) {
+        updates.push(update);
+      }
+    }
+
+    // Recompute the strata
+    let (strata, relation_to_stratum) = stratify(relations, updates)?;
+    self.strata = strata;
+    self.relation_to_stratum = relation_to_stratum;
+    self.function_registry = function_registry;
+    self.predicate_registry = predicate_registry;
+    Ok(())
+  }
+
+  fn rename_clashing_generated_relations(&mut self, existing: &HashMap<String, usize>) {
+    // Compute fresh names for the clashing generated relations
+    let mut renaming = HashMap::new();
+    for predicate in self.relation_to_stratum.keys() {
+      if predicate.contains('#') && existing.contains_key(predicate) {
+        let fresh = (1..)
+          .map(|i| format!("{}#{}", predicate, i))
+          .find(|n| !existing.contains_key(n) && !self.relation_to_stratum.contains_key(n))
+          .unwrap();
+        renaming.insert(predicate.clone(), fresh);
+      }
+    }
+    if renaming.is_empty() {
+      return;
+    }
+
+    // Apply the renaming to all the relations and updates
+    for stratum in &mut self.strata {
+      stratum.relations = std::mem::take(&mut stratum.relations)
+        .into_iter()
+        .map(|(predicate, mut relation)| {
+          if let Some(fresh) = renaming.get(&predicate) {
+            relation.predicate = fresh.clone();
+          }
+          (relation.predicate.clone(), relation)
+        })
+        .collect();
+      for update in &mut stratum.updates {
+        if let Some(fresh) = renaming.get(&update.target) {
+          update.target = fresh.clone();
+        }
+        update.dataflow.rename_relations(&renaming);
+      }
+    }
+    self.relation_to_stratum = std::mem::take(&mut self.relation_to_stratum)
+      .into_iter()
+      .map(|(predicate, id)| (renaming.get(&predicate).cloned().unwrap_or(predicate), id))
+      .collect();
+  }
+}
+
+impl Relation {
+  fn merge(&mut self, other: &Relation) -> Result<(), MergeError> {
+    if self.tuple_type != other.tuple_type {
+      return Err(MergeError::ConflictingRelationType {
+        relation: self.predicate.clone(),
+        t1: self.tuple_type.clone(),
+        t2: other.tuple_type.clone(),
+      });
+    }
+
+    // Merge the input file
+    match (&self.input_file, &other.input_file) {
+      (Some(f1), Some(f2)) if f1 != f2 => {
+        return Err(MergeError::ConflictingInputFile {
+          relation: self.predicate.clone(),
+        })
+      }
+      (None, Some(f)) => self.input_file = Some(f.clone()),
+      _ => {}
+    }
+
+    // Union the facts
+    for fact in &other.facts {
+      if !self.facts.contains(fact) {
+        self.facts.push(fact.clone());
+      }
+    }
+
+    // Merge the rest of the attributes
+    if self.output.is_hidden() {
+      self.output = other.output.clone();
+    }
+    self.immutable = self.immutable && other.immutable;
+    if self.doc.is_none() {
+      self.doc = other.doc.clone();
+    }
+    Ok(())
+  }
+}
+
+impl Dataflow {
+  fn rename_relations(&mut self, renaming: &HashMap<String, String>) {
+    let rename = |r: &mut String| {
+      if let Some(fresh) = renaming.get(r) {
+        *r = fresh.clone();
+      }
+    };
+    match self {
+      Self::Unit(_) | Self::UntaggedVec(_) | Self::ForeignPredicateGround(_, _) => {}
+      Self::Relation(r) => rename(r),
+      Self::Reduce(r) => {
+        rename(&mut r.predicate);
+        if let ReduceGroupByType::Join(group_by_predicate) = &mut r.group_by {
+          rename(group_by_predicate);
+        }
+      }
+      Self::Project(d, _)
+      | Self::Rename(d, _)
+      | Self::Filter(d, _)
+      | Self::Find(d, _)
+      | Self::Sort(d, _)
+      | Self::Limit(d, _)
+      | Self::Distinct(d)
+      | Self::OverwriteOne(d)
+      | Self::ForeignPredicateConstraint(d, _, _)
+      | Self::ForeignPredicateJoin(d, _, _) => d.rename_relations(renaming),
+      Self::Union(d1, d2)
+      | Self::Join(d1, d2)
+      | Self::Intersect(d1, d2)
+      | Self::Product(d1, d2)
+      | Self::Antijoin(d1, d2)
+      | Self::Difference(d1, d2)
+      | Self::Exclusion(d1, d2) => {
+        d1.rename_relations(renaming);
+        d2.rename_relations(renaming);
+      }
+    }
+  }
+
+  /// Collect the relations which this dataflow depends on non-monotonically, i.e. through negation or aggregation
+  fn collect_negative_dependency(&self, negative: bool, preds: &mut HashSet<String>) {
+    match self {
+      Self::Unit(_) | Self::UntaggedVec(_) | Self::ForeignPredicateGround(_, _) => {}
+      Self::Relation(r) => {
+        if negative {
+          preds.insert(r.clone());
+        }
+      }
+      Self::Reduce(r) => {
+        preds.insert(r.predicate.clone());
+        if let ReduceGroupByType::Join(group_by_predicate) = &r.group_by {
+          preds.insert(group_by_predicate.clone());
+        }
+      }
+      Self::Project(d, _)
+      | Self::Rename(d, _)
+      | Self::Filter(d, _)
+      | Self::Find(d, _)
+      | Self::Sort(d, _)
+      | Self::Limit(d, _)
+      | Self::Distinct(d)
+      | Self::OverwriteOne(d)
+      | Self::ForeignPredicateConstraint(d, _, _)
+      | Self::ForeignPredicateJoin(d, _, _) => d.collect_negative_dependency(negative, preds),
+      Self::Union(d1, d2)
+      | Self::Join(d1, d2)
+      | Self::Intersect(d1, d2)
+      | Self::Product(d1, d2)
+      | Self::Exclusion(d1, d2) => {
+        d1.collect_negative_dependency(negative, preds);
+        d2.collect_negative_dependency(negative, preds);
+      }
+      Self::Antijoin(d1, d2) | Self::Difference(d1, d2) => {
+        d1.collect_negative_dependency(negative, preds);
+        d2.collect_negative_dependency(true, preds);
+      }
+    }
+  }
+}
+
+/// Group the relations into strata following the strongly connected components of their dependency graph,
+/// in topological order
+fn stratify(
+  mut relations: BTreeMap<String, Relation>,
+  mut updates: Vec<Update>,
+) -> Result<(Vec<Stratum>, HashMap<String, usize>), MergeError> {
+  // Build the dependency graph
+  let mut graph = DiGraph::<String, ()>::new();
+  let node_ids = relations
+    .keys()
+    .map(|r| (r.clone(), graph.add_node(r.clone())))
+    .collect::<HashMap<_, _>>();
+  for update in &updates {
+    for dep in update.dependency() {
+      graph.update_edge(node_ids[&dep], node_ids[&update.target], ());
+    }
+  }
+
+  // Tarjan's algorithm returns the components in reverse topological order
+  let mut sccs = petgraph::algo::tarjan_scc(&graph);
+  sccs.reverse();
+  let relation_to_stratum = sccs
+    .iter()
+    .enumerate()
+    .flat_map(|(id, scc)| scc.iter().map(|n| (graph[*n].clone(), id)).collect::<Vec<_>>())
+    .collect::<HashMap<_, _>>();
+
+  // Distribute the relations and updates into the strata
+  let mut strata = sccs
+    .iter()
+    .map(|_| Stratum {
+      is_recursive: false,
+      relations: BTreeMap::new(),
+      updates: Vec::new(),
+    })
+    .collect::<Vec<_>>();
+  for (predicate, relation) in std::mem::take(&mut relations) {
+    strata[relation_to_stratum[&predicate]]
+      .relations
+      .insert(predicate, relation);
+  }
+  for update in updates.drain(..) {
+    let stratum_id = relation_to_stratum[&update.target];
+    let dependency = update.dependency();
+    if dependency.iter().any(|d| relation_to_stratum[d] == stratum_id) {
+      // Negation and aggregation are only allowed on relations of earlier strata
+      let mut negative = HashSet::new();
+      update.dataflow.collect_negative_dependency(false, &mut negative);
+      let mut cyclic = negative
+        .into_iter()
+        .filter(|d| relation_to_stratum[d] == stratum_id)
+        .collect::<Vec<_>>();
+      if !cyclic.is_empty() {
+        cyclic.sort();
+        return Err(MergeError::NonStratifiable { relations: cyclic });
+      }
+      strata[stratum_id].is_recursive = true;
+    }
+    strata[stratum_id].updates.push(update);
+  }
+
+  Ok((strata, relation_to_stratum))
+}
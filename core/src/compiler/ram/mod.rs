@@ -1,14 +1,20 @@
 mod ast;
+mod builder;
 mod dependency;
 mod incremental;
+mod merge;
 pub mod optimizations;
 mod pretty;
 mod ram2rs;
+mod serialize;
 mod transform;
 
 pub use ast::*;
+pub use builder::*;
 pub use dependency::*;
 pub use incremental::*;
+pub use merge::*;
 pub use pretty::*;
 pub use ram2rs::*;
+pub use serialize::*;
 pub use transform::*;
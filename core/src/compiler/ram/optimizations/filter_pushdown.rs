@@ -0,0 +1,183 @@
+use crate::common::binary_op::BinaryOp;
+use crate::common::expr::*;
+
+use super::*;
+
+/// Push the filters applied on joined tuples down to the join operands
+///
+/// A join produces `(key, left, right)` tuples out of `(key, left)` and `(key, right)` tuples. Each
+/// conjunct of a filter on top of a join (possibly through a projection) that only accesses the key
+/// and one of the two sides is moved below the join, onto that side, so that fewer tuples are joined.
+/// The conjuncts accessing both sides (or the whole joined tuple) stay on top of the join.
+pub fn filter_pushdown(ram: &mut Program) -> bool {
+  let mut changed = false;
+
+  // Iterate through all the updates
+  for stratum in &mut ram.strata {
+    for update in &mut stratum.updates {
+      changed |= filter_pushdown_on_dataflow(&mut update.dataflow);
+    }
+  }
+
+  // Return whether this optimization has changed anything
+  changed
+}
+
+fn filter_pushdown_on_dataflow(d0: &mut Dataflow) -> bool {
+  match d0 {
+    Dataflow::Union(d1, d2)
+    | Dataflow::Join(d1, d2)
+    | Dataflow::Intersect(d1, d2)
+    | Dataflow::Product(d1, d2)
+    | Dataflow::Antijoin(d1, d2)
+    | Dataflow::Difference(d1, d2) => {
+      let r1 = filter_pushdown_on_dataflow(d1);
+      let r2 = filter_pushdown_on_dataflow(d2);
+      r1 || r2
+    }
+    Dataflow::Filter(d1, e) => {
+      let remaining = match &mut **d1 {
+        Dataflow::Join(left, right) => push_into_join(left, right, e, |c| c.clone()),
+        Dataflow::Project(d2, p) => match &mut **d2 {
+          Dataflow::Join(left, right) => push_into_join(left, right, e, |c| c.compose(p)),
+          _ => None,
+        },
+        _ => None,
+      };
+
+      match remaining {
+        Some(remaining) => {
+          *d0 = match remaining {
+            Some(e) => Dataflow::Filter(d1.clone(), e),
+            None => (**d1).clone(),
+          };
+          filter_pushdown_on_dataflow(d0);
+          true
+        }
+        None => filter_pushdown_on_dataflow(d1),
+      }
+    }
+    Dataflow::Project(d, _)
+    | Dataflow::Rename(d, _)
+    | Dataflow::Find(d, _)
+    | Dataflow::Sort(d, _)
+    | Dataflow::Limit(d, _)
+    | Dataflow::Distinct(d)
+    | Dataflow::OverwriteOne(d)
+    | Dataflow::Exclusion(d, _)
+    | Dataflow::ForeignPredicateConstraint(d, _, _)
+    | Dataflow::ForeignPredicateJoin(d, _, _) => filter_pushdown_on_dataflow(d),
+    Dataflow::ForeignPredicateGround(_, _)
+    | Dataflow::Unit(_)
+    | Dataflow::Relation(_)
+    | Dataflow::Reduce(_)
+    | Dataflow::UntaggedVec(_) => false,
+  }
+}
+
+/// Push the conjuncts of the filter `e` down to the operands of a join
+///
+/// `on_joined` turns a conjunct into an expression on the joined tuples, in case there is a projection between
+/// the filter and the join. Returns `None` if nothing could be pushed, and otherwise the conjunction of the
+/// conjuncts that have to stay on top of the join, if any.
+fn push_into_join<F>(
+  left: &mut Box<Dataflow>,
+  right: &mut Box<Dataflow>,
+  e: &Expr,
+  on_joined: F,
+) -> Option<Option<Expr>>
+where
+  F: Fn(&Expr) -> Expr,
+{
+  let mut remaining = vec![];
+  let mut pushed = false;
+  for conjunct in conjuncts(e) {
+    let joined_conjunct = on_joined(&conjunct);
+    let usage = JoinOperandUsage::of(&joined_conjunct);
+    if usage.whole || (usage.left && usage.right) {
+      remaining.push(conjunct);
+    } else if usage.right {
+      **right = (**right).clone().filter(access_right_operand(&joined_conjunct));
+      pushed = true;
+    } else {
+      // The conjunct only accesses the key and the left side, which have the same indices in the left operand
+      **left = (**left).clone().filter(joined_conjunct);
+      pushed = true;
+    }
+  }
+
+  if pushed {
+    Some(remaining.into_iter().reduce(|acc, c| acc & c))
+  } else {
+    None
+  }
+}
+
+/// Split a filter expression into its conjuncts
+fn conjuncts(e: &Expr) -> Vec<Expr> {
+  match e {
+    Expr::Binary(b) if b.op == BinaryOp::And => {
+      let mut result = conjuncts(&b.op1);
+      result.extend(conjuncts(&b.op2));
+      result
+    }
+    _ => vec![e.clone()],
+  }
+}
+
+/// The parts of a `(key, left, right)` joined tuple accessed by an expression
+#[derive(Default)]
+struct JoinOperandUsage {
+  left: bool,
+  right: bool,
+  whole: bool,
+}
+
+impl JoinOperandUsage {
+  fn of(e: &Expr) -> Self {
+    let mut usage = Self::default();
+    usage.collect(e);
+    usage
+  }
+
+  fn collect(&mut self, e: &Expr) {
+    match e {
+      Expr::Tuple(es) => es.iter().for_each(|e| self.collect(e)),
+      Expr::Access(a) => match a.iter().next() {
+        Some(0) => {}
+        Some(1) => self.left = true,
+        Some(2) => self.right = true,
+        _ => self.whole = true,
+      },
+      Expr::Constant(_) => {}
+      Expr::Binary(b) => {
+        self.collect(&b.op1);
+        self.collect(&b.op2);
+      }
+      Expr::Unary(u) => self.collect(&u.op1),
+      Expr::IfThenElse(i) => {
+        self.collect(&i.cond);
+        self.collect(&i.then_br);
+        self.collect(&i.else_br);
+      }
+      Expr::Call(c) => c.args.iter().for_each(|a| self.collect(a)),
+    }
+  }
+}
+
+/// Rewrite an expression on `(key, left, right)` joined tuples into one on `(key, right)` tuples
+fn access_right_operand(e: &Expr) -> Expr {
+  match e {
+    Expr::Tuple(es) => Expr::Tuple(es.iter().map(access_right_operand).collect()),
+    Expr::Access(a) if a.iter().next() == Some(2) => Expr::Access(a.shift().prepend(1)),
+    Expr::Access(_) | Expr::Constant(_) => e.clone(),
+    Expr::Binary(b) => Expr::binary(b.op.clone(), access_right_operand(&b.op1), access_right_operand(&b.op2)),
+    Expr::Unary(u) => Expr::unary(u.op.clone(), access_right_operand(&u.op1)),
+    Expr::IfThenElse(i) => Expr::ite(
+      access_right_operand(&i.cond),
+      access_right_operand(&i.then_br),
+      access_right_operand(&i.else_br),
+    ),
+    Expr::Call(c) => Expr::call(c.function.clone(), c.args.iter().map(access_right_operand).collect()),
+  }
+}
@@ -1,6 +1,8 @@
+mod filter_pushdown;
 mod optimizations;
 mod project_cascade;
 
+pub use filter_pushdown::*;
 pub use optimizations::*;
 pub use project_cascade::*;
 
@@ -81,6 +81,11 @@ impl Dataflow {
         f.write_fmt(format_args!("Project[{:?}]\n{}", project, padding))?;
         d.pretty_print(f, next_indent, indent_size)
       }
+      Self::Rename(d, permutation) => {
+        let permutation = permutation.iter().map(|i| format!("{}", i)).collect::<Vec<_>>();
+        f.write_fmt(format_args!("Rename[{}]\n{}", permutation.join(", "), padding))?;
+        d.pretty_print(f, next_indent, indent_size)
+      }
       Self::Filter(d, filter) => {
         f.write_fmt(format_args!("Filter[{:?}]\n{}", filter, padding))?;
         d.pretty_print(f, next_indent, indent_size)
@@ -89,6 +94,22 @@ impl Dataflow {
         f.write_fmt(format_args!("Find[{}]\n{}", tuple, padding))?;
         d.pretty_print(f, next_indent, indent_size)
       }
+      Self::Sort(d, order) => {
+        let order = order
+          .iter()
+          .map(|(i, asc)| format!("{} {}", i, if *asc { "asc" } else { "desc" }))
+          .collect::<Vec<_>>();
+        f.write_fmt(format_args!("Sort[{}]\n{}", order.join(", "), padding))?;
+        d.pretty_print(f, next_indent, indent_size)
+      }
+      Self::Limit(d, n) => {
+        f.write_fmt(format_args!("Limit[{}]\n{}", n, padding))?;
+        d.pretty_print(f, next_indent, indent_size)
+      }
+      Self::Distinct(d) => {
+        f.write_fmt(format_args!("Distinct\n{}", padding))?;
+        d.pretty_print(f, next_indent, indent_size)
+      }
 
       // Binary operations
       Self::Union(d1, d2) => {
@@ -313,6 +313,11 @@ impl ast::Dataflow {
         let rs_expr = expr_to_rs_expr(expr);
         quote! { dataflow::project(#rs_d1, |t| #rs_expr) }
       }
+      Self::Rename(d1, permutation) => {
+        let rs_d1 = d1.to_rs_dataflow(curr_strat_id, rel_to_strat_map);
+        let rs_expr = expr_to_rs_expr(&Dataflow::rename_expr(permutation));
+        quote! { dataflow::project(#rs_d1, |t| #rs_expr) }
+      }
       Self::Filter(d1, expr) => {
         let rs_d1 = d1.to_rs_dataflow(curr_strat_id, rel_to_strat_map);
         let rs_expr = expr_to_rs_expr(expr);
@@ -367,15 +372,29 @@ impl ast::Dataflow {
         // Get the aggregator
         let agg = match &r.op {
           AggregateOp::Count => quote! { CountAggregator::new() },
+          AggregateOp::CountDistinct => quote! { CountDistinctAggregator::new() },
           AggregateOp::Sum(_) => quote! { SumAggregator::new() },
           AggregateOp::Prod(_) => quote! { ProdAggregator::new() },
+          AggregateOp::WeightedSum(_)
+          | AggregateOp::Variance(_)
+          | AggregateOp::StdDev(_)
+          | AggregateOp::Median(_)
+          | AggregateOp::Collect
+          | AggregateOp::GeometricMean(_)
+          | AggregateOp::HarmonicMean(_) => unimplemented! {},
           AggregateOp::Max => quote! { MaxAggregator::new() },
           AggregateOp::Min => quote! { MinAggregator::new() },
           AggregateOp::Argmax => quote! { ArgmaxAggregator::new() },
           AggregateOp::Argmin => quote! { ArgminAggregator::new() },
           AggregateOp::Exists => quote! { ExistsAggregator::new() },
+          AggregateOp::Any => unimplemented! {},
+          AggregateOp::ArgminTuple => unimplemented! {},
+          AggregateOp::ArgmaxTuple => unimplemented! {},
           AggregateOp::TopK(k) => quote! { TopKAggregator::new(#k) },
-          AggregateOp::CategoricalK(_) => unimplemented! {},
+          AggregateOp::ArgTopK(_) => unimplemented! {},
+          AggregateOp::Rank | AggregateOp::ArgRank => unimplemented! {},
+          AggregateOp::CategoricalK(_) | AggregateOp::Sample(_) | AggregateOp::Normalize(_) => unimplemented! {},
+          AggregateOp::Window { .. } | AggregateOp::Multiple(_) => unimplemented! {},
         };
 
         // Get the dataflow
@@ -396,6 +415,9 @@ impl ast::Dataflow {
         let rs_d1 = d1.to_rs_dataflow(curr_strat_id, rel_to_strat_map);
         quote! { dataflow::overwrite_one(#rs_d1) }
       }
+      Self::Sort(_, _) => unimplemented!(),
+      Self::Limit(_, _) => unimplemented!(),
+      Self::Distinct(_) => unimplemented!(),
       Self::Exclusion(_, _) => unimplemented!(),
       Self::ForeignPredicateGround(_, _) => unimplemented!(),
       Self::ForeignPredicateConstraint(_, _, _) => unimplemented!(),
@@ -453,6 +475,15 @@ fn value_type_to_rs_type(ty: &ValueType) -> TokenStream {
     ValueType::String => quote! { String },
     ValueType::DateTime => quote! { DateTime<Utc> },
     ValueType::Duration => quote! { Duration },
+    ValueType::Tuple(tys) => {
+      let tys = tys.iter().map(value_type_to_rs_type);
+      quote! { (#(#tys,)*) }
+    }
+    ValueType::List(ty) => {
+      let ty = value_type_to_rs_type(ty);
+      quote! { Vec<#ty> }
+    }
+    ValueType::Symbol => unimplemented!(),
     // ValueType::RcString => quote! { Rc<String> },
   }
 }
@@ -513,6 +544,7 @@ fn input_tag_to_rs_input_tag(tag: &DynamicInputTag) -> TokenStream {
     DynamicInputTag::Bool(b) => quote! { DynamicInputTag::Bool(#b) },
     DynamicInputTag::Float(f) => quote! { DynamicInputTag::Float(#f) },
     DynamicInputTag::ExclusiveFloat(f, u) => quote! { DynamicInputTag::ExclusiveFloat(#f, #u) },
+    DynamicInputTag::Interval(lo, hi) => quote! { DynamicInputTag::Interval(#lo, #hi) },
   }
 }
 
@@ -571,6 +603,9 @@ fn value_to_rs_value(value: &Value) -> TokenStream {
     // RcString(s) => quote! { Rc::new(String::from(#s)) },
     DateTime(_) => unimplemented!(),
     Duration(_) => unimplemented!(),
+    Tuple(_) => unimplemented!(),
+    List(_) => unimplemented!(),
+    Symbol(_) => unimplemented!(),
   }
 }
 
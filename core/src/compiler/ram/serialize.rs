@@ -0,0 +1,123 @@
+use serde::{Deserialize, Serialize};
+use std::collections::*;
+
+use crate::common::foreign_function::*;
+use crate::common::foreign_predicate::*;
+
+use super::*;
+
+/// The errors happening when loading a serialized RAM program
+#[derive(Debug, Clone, PartialEq)]
+pub enum LoadError {
+  Decode { message: String },
+  UnregisteredFunction { name: String },
+  UnregisteredPredicate { name: String },
+}
+
+impl std::fmt::Display for LoadError {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      Self::Decode { message } => f.write_fmt(format_args!("Cannot decode the serialized program: {}", message)),
+      Self::UnregisteredFunction { name } => f.write_fmt(format_args!(
+        "Foreign function `${}` required by the program is not registered",
+        name
+      )),
+      Self::UnregisteredPredicate { name } => f.write_fmt(format_args!(
+        "Foreign predicate `{}` required by the program is not registered",
+        name
+      )),
+    }
+  }
+}
+
+/// The errors happening when serializing a RAM program
+#[derive(Debug, Clone, PartialEq)]
+pub enum SerializeError {
+  Encode { message: String },
+}
+
+impl std::fmt::Display for SerializeError {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      Self::Encode { message } => f.write_fmt(format_args!("Cannot serialize the program: {}", message)),
+    }
+  }
+}
+
+/// The serialized form of a program, where the foreign functions and predicates are only kept by their names
+#[derive(Serialize, Deserialize)]
+struct SerializedProgram {
+  strata: Vec<Stratum>,
+  function_names: Vec<String>,
+  predicate_names: Vec<String>,
+  relation_to_stratum: HashMap<String, usize>,
+}
+
+impl Program {
+  /// Serialize the program into bytes, so that it can be loaded later on without being compiled again
+  ///
+  /// The foreign functions and predicates cannot be serialized; only their names are stored, and their
+  /// implementations need to be registered again when loading the program. Programs holding static strings,
+  /// which can only be introduced by the embedder, cannot be serialized either.
+  pub fn serialize(&self) -> Result<Vec<u8>, SerializeError> {
+    let mut function_names = self
+      .function_registry
+      .into_iter()
+      .map(|(n, _)| n.clone())
+      .collect::<Vec<_>>();
+    function_names.sort();
+    let mut predicate_names = self
+      .predicate_registry
+      .iter()
+      .map(|(n, _)| n.clone())
+      .collect::<Vec<_>>();
+    predicate_names.sort();
+    let serialized = SerializedProgram {
+      strata: self.strata.clone(),
+      function_names,
+      predicate_names,
+      relation_to_stratum: self.relation_to_stratum.clone(),
+    };
+    bincode::serialize(&serialized).map_err(|e| SerializeError::Encode { message: e.to_string() })
+  }
+
+  /// Load a serialized program, taking the foreign functions and predicates from the standard library
+  pub fn deserialize(bytes: &[u8]) -> Result<Self, LoadError> {
+    Self::deserialize_with_registries(bytes, &ForeignFunctionRegistry::std(), &ForeignPredicateRegistry::std())
+  }
+
+  /// Load a serialized program, taking the foreign functions and predicates from the given registries
+  ///
+  /// Every foreign function and predicate the program was compiled with needs to be in the registries.
+  pub fn deserialize_with_registries(
+    bytes: &[u8],
+    function_registry: &ForeignFunctionRegistry,
+    predicate_registry: &ForeignPredicateRegistry,
+  ) -> Result<Self, LoadError> {
+    let serialized: SerializedProgram =
+      bincode::deserialize(bytes).map_err(|e| LoadError::Decode { message: e.to_string() })?;
+
+    // Check that all the foreign functions and predicates are registered
+    if let Some(name) = serialized
+      .function_names
+      .iter()
+      .find(|n| !function_registry.contains(n))
+    {
+      return Err(LoadError::UnregisteredFunction { name: name.clone() });
+    }
+    if let Some(name) = serialized
+      .predicate_names
+      .iter()
+      .find(|n| !predicate_registry.contains(n))
+    {
+      return Err(LoadError::UnregisteredPredicate { name: name.clone() });
+    }
+
+    Ok(Self {
+      strata: serialized.strata,
+      function_registry: function_registry.clone(),
+      predicate_registry: predicate_registry.clone(),
+      relation_to_stratum: serialized.relation_to_stratum,
+    })
+  }
+}
@@ -1,7 +1,12 @@
+use std::collections::*;
+use std::path::Path;
+
 use crate::common::foreign_function::*;
 use crate::common::foreign_predicate::*;
 use crate::common::tuple::*;
 use crate::common::tuple_type::*;
+use crate::common::value::*;
+use crate::common::value_type::*;
 
 use crate::compiler;
 use crate::runtime::database::extensional::*;
@@ -42,7 +47,7 @@ impl<Prov: Provenance, P: PointerFamily> IntegrateContext<Prov, P> {
       internal: InternalIntegrateContext {
         prov_ctx,
         runtime_env: RuntimeEnvironment::default(),
-        ram_program: compiler::ram::Program::new(),
+        ram_program: P::new_rc(compiler::ram::Program::new()),
         exec_ctx: dynamic::DynamicExecutionContext::new_with_options(dynamic::ExecutionOptions {
           type_check: false,
           ..Default::default()
@@ -59,7 +64,7 @@ impl<Prov: Provenance, P: PointerFamily> IntegrateContext<Prov, P> {
       internal: InternalIntegrateContext {
         prov_ctx,
         runtime_env: RuntimeEnvironment::default(),
-        ram_program: compiler::ram::Program::new(),
+        ram_program: P::new_rc(compiler::ram::Program::new()),
         exec_ctx: dynamic::DynamicExecutionContext::new_with_options(dynamic::ExecutionOptions {
           type_check: false,
           incremental_maintain: true,
@@ -71,13 +76,15 @@ impl<Prov: Provenance, P: PointerFamily> IntegrateContext<Prov, P> {
   }
 
   pub fn new_with_options(prov_ctx: Prov, options: IntegrateOptions) -> Self {
+    let mut front_ctx = compiler::front::FrontContext::new();
+    front_ctx.set_strict_relation_declarations(options.compiler_options.strict_relation_declarations);
     Self {
       options: options.compiler_options,
-      front_ctx: compiler::front::FrontContext::new(),
+      front_ctx,
       internal: InternalIntegrateContext {
         prov_ctx,
-        runtime_env: RuntimeEnvironment::default(),
-        ram_program: compiler::ram::Program::new(),
+        runtime_env: options.runtime_environment_options.build(),
+        ram_program: P::new_rc(compiler::ram::Program::new()),
         exec_ctx: dynamic::DynamicExecutionContext::new_with_options(dynamic::ExecutionOptions {
           type_check: false,
           ..options.execution_options
@@ -97,13 +104,24 @@ impl<Prov: Provenance, P: PointerFamily> IntegrateContext<Prov, P> {
       internal: InternalIntegrateContext {
         prov_ctx: new_prov,
         runtime_env: self.internal.runtime_env.clone(),
-        ram_program: self.internal.ram_program.clone(),
+        ram_program: P::clone_rc(&self.internal.ram_program),
         exec_ctx: self.internal.exec_ctx.clone_with_new_provenance::<Prov2>(),
       },
       front_has_changed: true,
     }
   }
 
+  /// Fork a snapshot of this context for running another query against the same facts
+  ///
+  /// The compiled RAM program, the internalized facts of the extensional relations, and the
+  /// computed relations are shared with the fork rather than copied. The facts of a relation
+  /// are only copied once the fork (or this context) updates them.
+  /// Rules and facts later added to the fork do not affect this context, and vice versa.
+  /// To share the compiled program and the facts, the context should be run before being forked.
+  pub fn clone_for_query(&self) -> Self {
+    self.clone()
+  }
+
   pub fn provenance_context(&self) -> &Prov {
     &self.internal.prov_ctx
   }
@@ -270,6 +288,40 @@ impl<Prov: Provenance, P: PointerFamily> IntegrateContext<Prov, P> {
     Ok(())
   }
 
+  /// Add a list of facts that are already converted into tuples of the given type to the given predicate
+  ///
+  /// The given type is checked against the type of the predicate only once, and the tuples are inserted
+  /// without being converted; see `ExtensionalDatabase::add_facts_checked`
+  pub fn add_facts_checked(
+    &mut self,
+    predicate: &str,
+    facts: Vec<Tuple>,
+    tuple_type: &TupleType,
+  ) -> Result<(), IntegrateError> {
+    let error = match self.front_ctx.relation_tuple_type(predicate) {
+      Some(pred_tuple_type) if &pred_tuple_type != tuple_type => Some(DatabaseError::RelationTypeMismatch {
+        relation: predicate.to_string(),
+        expected: pred_tuple_type,
+        found: tuple_type.clone(),
+      }),
+      Some(_) => None,
+      None => Some(DatabaseError::UnknownRelation {
+        relation: predicate.to_string(),
+      }),
+    };
+    if let Some(error) = error {
+      return Err(IntegrateError::Runtime(RuntimeError::Database(error)));
+    }
+
+    // Actually insert
+    self
+      .internal
+      .exec_ctx
+      .edb
+      .add_facts_checked(predicate, facts, tuple_type)
+      .map_err(|e| IntegrateError::Runtime(RuntimeError::Database(e)))
+  }
+
   /// Register a foreign function to the context
   pub fn register_foreign_function<F>(&mut self, ff: F) -> Result<(), IntegrateError>
   where
@@ -288,6 +340,33 @@ impl<Prov: Provenance, P: PointerFamily> IntegrateContext<Prov, P> {
     Ok(())
   }
 
+  /// Register a foreign function to the context, after executing it on sample arguments to check its
+  /// return type; see `ForeignFunctionRegistry::register_with_self_check`
+  pub fn register_foreign_function_with_self_check<F>(&mut self, ff: F) -> Result<(), IntegrateError>
+  where
+    F: ForeignFunction + Send + Sync + 'static,
+  {
+    ff.check_arity_consistent()
+      .and_then(|_| ff.check_return_type())
+      .map_err(|e| IntegrateError::Runtime(RuntimeError::ForeignFunction(e)))?;
+    self.register_foreign_function(ff)
+  }
+
+  /// Register a monomorphic foreign function implemented by a closure, taking the arguments of
+  /// the given types and returning a value of `return_type`; the closure returns `None` on failure
+  pub fn register_foreign_function_closure<F>(
+    &mut self,
+    name: &str,
+    arg_types: Vec<ValueType>,
+    return_type: ValueType,
+    f: F,
+  ) -> Result<(), IntegrateError>
+  where
+    F: Fn(Vec<Value>) -> Option<Value> + Send + Sync + 'static,
+  {
+    self.register_foreign_function(ClosureForeignFunction::new(name, arg_types, return_type, f))
+  }
+
   /// Register a foreign predicate to the context
   pub fn register_foreign_predicate<F>(&mut self, fp: F) -> Result<(), IntegrateError>
   where
@@ -316,6 +395,24 @@ impl<Prov: Provenance, P: PointerFamily> IntegrateContext<Prov, P> {
     self.internal.runtime_env.set_early_discard(early_discard)
   }
 
+  /// Set whether the tags of the facts are printed as they are tagged, derived and recovered when running
+  pub fn set_debug_tags(&mut self, debug_tags: bool) {
+    self.internal.runtime_env.set_debug_tags(debug_tags)
+  }
+
+  /// Set whether the groundings of foreign predicates are sorted, making runs reproducible
+  pub fn set_deterministic(&mut self, deterministic: bool) {
+    self.internal.runtime_env.set_deterministic(deterministic)
+  }
+
+  /// Set whether the floating point sums and variances of aggregations use compensated summation
+  pub fn set_stable_float_aggregation(&mut self, stable_float_aggregation: bool) {
+    self
+      .internal
+      .runtime_env
+      .set_stable_float_aggregation(stable_float_aggregation)
+  }
+
   /// Set the iteration limit
   pub fn set_iter_limit(&mut self, k: usize) {
     self.internal.runtime_env.set_iter_limit(k)
@@ -326,11 +423,36 @@ impl<Prov: Provenance, P: PointerFamily> IntegrateContext<Prov, P> {
     self.internal.runtime_env.remove_iter_limit()
   }
 
+  /// Set the maximum number of facts a relation may hold; the execution fails when it is exceeded
+  pub fn set_relation_size_limit(&mut self, k: usize) {
+    self.internal.runtime_env.set_relation_size_limit(k)
+  }
+
+  /// Remove the relation size limit
+  pub fn remove_relation_size_limit(&mut self) {
+    self.internal.runtime_env.remove_relation_size_limit()
+  }
+
+  /// Set the random seed used by non-deterministic foreign functions and sampling
+  pub fn set_random_seed(&mut self, seed: u64) {
+    self.internal.runtime_env.set_random_seed(seed)
+  }
+
   /// Get a mutable refernce to the Extensional Database (EDB)
-  pub fn edb(&mut self) -> &mut ExtensionalDatabase<Prov> {
+  pub fn edb(&mut self) -> &mut ExtensionalDatabase<Prov, P> {
     &mut self.internal.exec_ctx.edb
   }
 
+  /// Get the warnings of the registered foreign functions that are not called by any rule added so far
+  pub fn unused_foreign_function_warnings(&self) -> compiler::front::FrontCompileError {
+    self.front_ctx.unused_foreign_function_warnings()
+  }
+
+  /// Get the warnings of the relations that are declared but not used by any rule added so far, nor queried
+  pub fn dead_relation_warnings(&self) -> compiler::front::FrontCompileError {
+    self.front_ctx.dead_relation_warnings()
+  }
+
   /// Compile the front context into back
   pub fn compile(&mut self) -> Result<(), IntegrateError> {
     self.compile_with_output_relations(None)?;
@@ -365,7 +487,7 @@ impl<Prov: Provenance, P: PointerFamily> IntegrateContext<Prov, P> {
       compiler::ram::optimizations::optimize_ram(&mut ram);
 
       // Store the ram
-      self.internal.ram_program = ram;
+      self.internal.ram_program = P::new_rc(ram);
 
       // Set front_has_changed to false
       self.front_has_changed = false;
@@ -376,7 +498,7 @@ impl<Prov: Provenance, P: PointerFamily> IntegrateContext<Prov, P> {
   }
 
   pub fn ram(&self) -> &compiler::ram::Program {
-    &self.internal.ram_program
+    P::get_rc(&self.internal.ram_program)
   }
 
   /// Execute the program in its current state, with a limit set on iteration count
@@ -400,11 +522,60 @@ impl<Prov: Provenance, P: PointerFamily> IntegrateContext<Prov, P> {
     self.internal.run()
   }
 
+  /// Execute the program in its current state, and hand every tuple of the non-hidden computed relations to
+  /// the callback, along with its relation and output tag, as soon as the tuple is recovered. The recovered
+  /// tuples are not kept in the context
+  pub fn run_with_output_callback<F>(&mut self, f: F) -> Result<(), IntegrateError>
+  where
+    F: FnMut(&str, &Tuple, &Prov::OutputTag),
+  {
+    self.run()?;
+    let relations = self.relations();
+    self.internal.stream_with_output_callback(&relations, f);
+    Ok(())
+  }
+
+  /// Execute the program in its current state, and return the tuples of each non-hidden derived relation
+  /// that are newly computed by this run, i.e. that were not present after the previous run
+  pub fn run_incremental(&mut self) -> Result<BTreeMap<String, BTreeSet<Tuple>>, IntegrateError> {
+    // First compile the code
+    self.compile()?;
+
+    // Collect the relations derived by rules
+    let ram = self.ram();
+    let relations = ram
+      .strata
+      .iter()
+      .flat_map(|s| s.updates.iter().map(|u| u.target.clone()))
+      .filter(|r| ram.output_option(r).map_or(false, |o| o.is_not_hidden()))
+      .collect::<BTreeSet<_>>();
+
+    // Finally execute the ram
+    self.internal.run_incremental(&relations)
+  }
+
   /// Get the relation type
   pub fn relation_type(&self, relation: &str) -> Option<TupleType> {
     self.front_ctx.relation_tuple_type(relation)
   }
 
+  /// Get the inferred column types of all the user defined relations
+  pub fn inferred_relation_types(&self) -> HashMap<String, Vec<ValueType>> {
+    self.front_ctx.inferred_relation_types()
+  }
+
+  /// Get the `k` requested by the `@k(K)` attribute of the program, so that the top-k provenance can be
+  /// configured accordingly (e.g. through `TopBottomKClausesProvenance::set_k`)
+  pub fn requested_k(&self) -> Option<usize> {
+    self.front_ctx.requested_k()
+  }
+
+  /// Get the inferred types of the variables in a rule, identified by the id returned from `add_rule`;
+  /// the map is empty if there is no such rule
+  pub fn variable_types_in_rule(&self, rule_id: compiler::front::SourceId) -> HashMap<String, ValueType> {
+    self.front_ctx.variable_types_in_rule(rule_id).unwrap_or_default()
+  }
+
   /// Has relation
   pub fn has_relation(&self, relation: &str) -> bool {
     self.front_ctx.has_relation(relation)
@@ -445,6 +616,16 @@ impl<Prov: Provenance, P: PointerFamily> IntegrateContext<Prov, P> {
     self.internal.computed_relation(relation)
   }
 
+  /// Get the facts of a computed relation matching the pattern, where `None` is a wildcard and `Some(v)`
+  /// constrains the corresponding column to be `v`; returns `None` if the relation is not computed
+  pub fn query(
+    &self,
+    relation: &str,
+    pattern: Vec<Option<Value>>,
+  ) -> Result<Option<dynamic::DynamicOutputCollection<Prov>>, IntegrateError> {
+    self.internal.query(relation, pattern)
+  }
+
   /// Get the relation output collection of a given relation
   pub fn computed_relation_with_monitor<M>(
     &mut self,
@@ -456,6 +637,11 @@ impl<Prov: Provenance, P: PointerFamily> IntegrateContext<Prov, P> {
   {
     self.internal.computed_relation_with_monitor(relation, m)
   }
+
+  /// Store every non-hidden computed relation into its own file `dir/<relation>.csv`
+  pub fn store_all_outputs(&self, dir: &Path) -> Result<(), IntegrateError> {
+    self.internal.store_all_outputs(&self.relations(), dir)
+  }
 }
 
 pub struct InternalIntegrateContext<Prov: Provenance, P: PointerFamily> {
@@ -466,7 +652,7 @@ pub struct InternalIntegrateContext<Prov: Provenance, P: PointerFamily> {
   pub runtime_env: RuntimeEnvironment,
 
   /// The ram program to be evaluated
-  pub ram_program: compiler::ram::Program,
+  pub ram_program: P::Rc<compiler::ram::Program>,
 
   /// The dynamic execution context; within which there are EDB, IDB, and a program
   ///
@@ -481,7 +667,7 @@ impl<Prov: Provenance, P: PointerFamily> Clone for InternalIntegrateContext<Prov
     Self {
       prov_ctx: self.prov_ctx.clone(),
       runtime_env: self.runtime_env.clone(),
-      ram_program: self.ram_program.clone(),
+      ram_program: P::clone_rc(&self.ram_program),
       exec_ctx: self.exec_ctx.clone(),
     }
   }
@@ -533,6 +719,17 @@ impl<Prov: Provenance, P: PointerFamily> InternalIntegrateContext<Prov, P> {
 
   /// Execute the program in its current state, with a limit set on iteration count
   pub fn run_with_monitor<M>(&mut self, m: &M) -> Result<(), IntegrateError>
+  where
+    M: Monitor<Prov>,
+  {
+    if self.runtime_env.debug_tags {
+      self.run_with_monitor_helper(&(DebugTagsMonitor, m))
+    } else {
+      self.run_with_monitor_helper(m)
+    }
+  }
+
+  fn run_with_monitor_helper<M>(&mut self, m: &M) -> Result<(), IntegrateError>
   where
     M: Monitor<Prov>,
   {
@@ -543,7 +740,12 @@ impl<Prov: Provenance, P: PointerFamily> InternalIntegrateContext<Prov, P> {
     // Finally execute the ram
     self
       .exec_ctx
-      .incremental_execute_with_monitor(self.ram_program.clone(), &mut self.runtime_env, &mut self.prov_ctx, m)
+      .incremental_execute_shared_with_monitor(
+        P::clone_rc(&self.ram_program),
+        &mut self.runtime_env,
+        &mut self.prov_ctx,
+        m,
+      )
       .map_err(IntegrateError::Runtime)?;
 
     // Success
@@ -552,6 +754,11 @@ impl<Prov: Provenance, P: PointerFamily> InternalIntegrateContext<Prov, P> {
 
   /// Execute the program in its current state, with a limit set on iteration count
   pub fn run(&mut self) -> Result<(), IntegrateError> {
+    // The tags are debugged through a monitor
+    if self.runtime_env.debug_tags {
+      return self.run_with_monitor(&());
+    }
+
     // Populate the runtime foreign function/predicate registry
     self.runtime_env.function_registry = self.ram_program.function_registry.clone();
     self.runtime_env.predicate_registry = self.ram_program.predicate_registry.clone();
@@ -559,13 +766,58 @@ impl<Prov: Provenance, P: PointerFamily> InternalIntegrateContext<Prov, P> {
     // Finally execute the ram
     self
       .exec_ctx
-      .incremental_execute(self.ram_program.clone(), &mut self.runtime_env, &mut self.prov_ctx)
+      .incremental_execute_shared(
+        P::clone_rc(&self.ram_program),
+        &mut self.runtime_env,
+        &mut self.prov_ctx,
+      )
       .map_err(IntegrateError::Runtime)?;
 
     // Success
     Ok(())
   }
 
+  /// Execute the program, and return the tuples of the given relations that are newly computed by this run
+  ///
+  /// The strata that can be maintained (under an idempotent provenance, and with the changed relations only used
+  /// monotonically) are seeded with their facts from the previous run, and only the facts that the semi-naive
+  /// evaluation derives from the new facts are checked against them; the facts derived from retracted facts are
+  /// first deleted from the seeds, and rederived if they are still supported. The other strata are recomputed from
+  /// scratch, and all of their facts are checked. The strata which do not depend on the changed relations are not
+  /// executed at all.
+  pub fn run_incremental(
+    &mut self,
+    relations: &BTreeSet<String>,
+  ) -> Result<BTreeMap<String, BTreeSet<Tuple>>, IntegrateError> {
+    // Populate the runtime foreign function/predicate registry
+    self.runtime_env.function_registry = self.ram_program.function_registry.clone();
+    self.runtime_env.predicate_registry = self.ram_program.predicate_registry.clone();
+
+    // Execute the ram, getting the new facts of the computed relations
+    let mut new_facts = self
+      .exec_ctx
+      .incremental_execute_shared_with_new_facts(
+        P::clone_rc(&self.ram_program),
+        &mut self.runtime_env,
+        &mut self.prov_ctx,
+      )
+      .map_err(IntegrateError::Runtime)?;
+
+    // Collect the tuples of the requested relations
+    let delta = relations
+      .iter()
+      .filter(|relation| self.exec_ctx.is_computed(relation))
+      .map(|relation| {
+        let tuples = new_facts
+          .remove(relation)
+          .map(|collection| collection.into_iter().into_iter().map(|elem| elem.tuple).collect())
+          .unwrap_or_default();
+        (relation.clone(), tuples)
+      })
+      .collect();
+    Ok(delta)
+  }
+
   /// Get the number of all relations
   pub fn num_all_relations(&self) -> usize {
     self.exec_ctx.num_relations()
@@ -592,6 +844,18 @@ impl<Prov: Provenance, P: PointerFamily> InternalIntegrateContext<Prov, P> {
     self.exec_ctx.relation(relation)
   }
 
+  /// Get the facts of a computed relation matching the pattern
+  pub fn query(
+    &self,
+    relation: &str,
+    pattern: Vec<Option<Value>>,
+  ) -> Result<Option<dynamic::DynamicOutputCollection<Prov>>, IntegrateError> {
+    self
+      .exec_ctx
+      .query(relation, &pattern, &self.runtime_env, &self.prov_ctx)
+      .map_err(|e| IntegrateError::Runtime(RuntimeError::Database(e)))
+  }
+
   /// Get the RC'ed output collection of a given relation
   pub fn computed_relation_with_monitor<M: Monitor<Prov>>(
     &mut self,
@@ -601,4 +865,49 @@ impl<Prov: Provenance, P: PointerFamily> InternalIntegrateContext<Prov, P> {
     self.exec_ctx.recover_with_monitor(relation, &self.prov_ctx, m);
     self.exec_ctx.relation(relation)
   }
+
+  /// Hand every recovered tuple of the given relations to the callback, skipping the hidden and uncomputed
+  /// ones. The recovered tuples are not stored, so the relations that are not recovered yet stay unrecovered;
+  /// the relations already recovered are streamed from their output collections instead
+  pub fn stream_with_output_callback<F>(&self, relations: &[String], mut f: F)
+  where
+    F: FnMut(&str, &Tuple, &Prov::OutputTag),
+  {
+    let mut relations = relations
+      .iter()
+      .filter(|r| self.ram_program.output_option(r).map_or(false, |o| o.is_not_hidden()))
+      .collect::<Vec<_>>();
+    relations.sort();
+
+    for relation in relations {
+      if let Some(collection) = self.exec_ctx.relation_ref(relation) {
+        for (tag, tuple) in collection.iter() {
+          f(relation, tuple, tag);
+        }
+      } else {
+        let monitor = OutputCallbackMonitor::new(&mut f);
+        self.exec_ctx.stream_with_monitor(relation, &self.prov_ctx, &monitor);
+      }
+    }
+  }
+
+  /// Store the given relations into `dir/<relation>.csv`, skipping the hidden and uncomputed ones. The tuples
+  /// of the relations that are not recovered yet are written from their internal facts, without recovering them
+  pub fn store_all_outputs(&self, relations: &[String], dir: &Path) -> Result<(), IntegrateError> {
+    let mut relations = relations
+      .iter()
+      .filter(|r| self.ram_program.output_option(r).map_or(false, |o| o.is_not_hidden()))
+      .collect::<Vec<_>>();
+    relations.sort();
+
+    let collections = relations.iter().filter_map(|r| {
+      let tuples: Box<dyn Iterator<Item = &Tuple>> = if let Some(c) = self.exec_ctx.relation_ref(r) {
+        Box::new(c.iter().map(|(_, t)| t))
+      } else {
+        Box::new(self.exec_ctx.internal_relation(r)?.iter().map(|e| &e.tuple))
+      };
+      Some((r.as_str(), tuples))
+    });
+    dynamic::io::store_csv_in_directory(dir, collections).map_err(|e| IntegrateError::Runtime(RuntimeError::IO(e)))
+  }
 }
@@ -13,6 +13,26 @@ pub fn interpret_string(program_string: String) -> Result<IntentionalDatabase<un
   Ok(interpret_ctx.idb())
 }
 
+/// Interpret a program string, additionally returning statistics of the run
+///
+/// The statistics include the number of iterations and the time spent in each
+/// stratum, as well as the final size of each output relation.
+pub fn interpret_string_with_stats(
+  program_string: String,
+) -> Result<(IntentionalDatabase<unit::UnitProvenance>, RunStats), IntegrateError> {
+  let prov = unit::UnitProvenance::default();
+  let monitor = StatisticsMonitor::new();
+  let mut interpret_ctx = InterpretContext::new(program_string, prov)?;
+  interpret_ctx.run_with_monitor(&monitor)?;
+  let idb = interpret_ctx.idb();
+  let mut stats = monitor.run_stats();
+  stats.relation_sizes = (&idb)
+    .into_iter()
+    .map(|(predicate, relation)| (predicate.clone(), relation.len()))
+    .collect();
+  Ok((idb, stats))
+}
+
 pub fn interpret_string_with_ctx<Prov: Provenance>(
   program_string: String,
   prov: Prov,
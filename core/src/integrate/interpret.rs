@@ -61,11 +61,16 @@ impl<Prov: provenance::Provenance, Ptr: PointerFamily> InterpretContext<Prov, Pt
     })
   }
 
-  pub fn edb(&mut self) -> &mut database::extensional::ExtensionalDatabase<Prov> {
+  pub fn edb(&mut self) -> &mut database::extensional::ExtensionalDatabase<Prov, Ptr> {
     &mut self.execution_context.edb
   }
 
   pub fn run(&mut self) -> Result<(), IntegrateError> {
+    // The tags are debugged through a monitor
+    if self.runtime_env.debug_tags {
+      return self.run_with_monitor(&());
+    }
+
     // Execute the program
     self
       .execution_context
@@ -102,6 +107,14 @@ impl<Prov: provenance::Provenance, Ptr: PointerFamily> InterpretContext<Prov, Pt
   }
 
   pub fn run_with_monitor<M: monitor::Monitor<Prov>>(&mut self, m: &M) -> Result<(), IntegrateError> {
+    if self.runtime_env.debug_tags {
+      self.run_with_monitor_helper(&(monitor::DebugTagsMonitor, m))
+    } else {
+      self.run_with_monitor_helper(m)
+    }
+  }
+
+  fn run_with_monitor_helper<M: monitor::Monitor<Prov>>(&mut self, m: &M) -> Result<(), IntegrateError> {
     // Execute the program
     self
       .execution_context
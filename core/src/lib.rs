@@ -1,5 +1,4 @@
-#![feature(min_specialization)]
-#![feature(extract_if)]
+#![cfg_attr(not(feature = "stable"), feature(min_specialization))]
 
 pub mod common;
 pub mod compiler;
@@ -1,5 +1,6 @@
 use crate::common::tuple::*;
 use crate::common::tuple_type::*;
+use crate::common::value::*;
 
 #[derive(Clone, Debug)]
 pub enum DatabaseError {
@@ -8,12 +9,28 @@ pub enum DatabaseError {
     relation_type: TupleType,
     tuple: Tuple,
   },
+  TypeMismatch {
+    relation: String,
+    index: usize,
+    relation_type: TupleType,
+    tuple: Tuple,
+  },
+  RelationTypeMismatch {
+    relation: String,
+    expected: TupleType,
+    found: TupleType,
+  },
   UnknownRelation {
     relation: String,
   },
   NewProgramFacts {
     relation: String,
   },
+  QueryPatternMismatch {
+    relation: String,
+    relation_type: TupleType,
+    pattern: Vec<Option<Value>>,
+  },
 }
 
 impl std::fmt::Display for DatabaseError {
@@ -27,11 +44,42 @@ impl std::fmt::Display for DatabaseError {
         "Type mismatch on tuple `{}` for relation `{}`. Expected tuple type `{}`",
         tuple, relation, relation_type
       )),
+      Self::TypeMismatch {
+        relation,
+        index,
+        relation_type,
+        tuple,
+      } => f.write_str(&format!(
+        "Type mismatch on tuple #{} `{}` for relation `{}`. Expected tuple type `{}`",
+        index, tuple, relation, relation_type
+      )),
+      Self::RelationTypeMismatch {
+        relation,
+        expected,
+        found,
+      } => f.write_str(&format!(
+        "Type mismatch for relation `{}`. Expected tuple type `{}`, found `{}`",
+        relation, expected, found
+      )),
       Self::UnknownRelation { relation } => f.write_str(&format!("Unknown relation `{}`", relation)),
       Self::NewProgramFacts { relation } => f.write_str(&format!(
         "New facts in program declared for relation `{}`; cannot incrementally compute",
         relation
       )),
+      Self::QueryPatternMismatch {
+        relation,
+        relation_type,
+        pattern,
+      } => f.write_str(&format!(
+        "Query pattern `({})` does not match relation `{}`. Expected tuple type `{}`",
+        pattern
+          .iter()
+          .map(|v| v.as_ref().map_or("_".to_string(), |v| v.to_string()))
+          .collect::<Vec<_>>()
+          .join(", "),
+        relation,
+        relation_type
+      )),
     }
   }
 }
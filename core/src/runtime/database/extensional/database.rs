@@ -8,11 +8,12 @@ use crate::runtime::dynamic::*;
 use crate::runtime::monitor::*;
 use crate::runtime::provenance::*;
 use crate::runtime::statics::*;
+use crate::utils::*;
 
 use super::*;
 
 #[derive(Clone, Debug)]
-pub struct ExtensionalDatabase<Prov: Provenance> {
+pub struct ExtensionalDatabase<Prov: Provenance, Ptr: PointerFamily = RcFamily> {
   /// Configuration of whether we perform type check when adding facts
   pub type_check: bool,
 
@@ -23,13 +24,13 @@ pub struct ExtensionalDatabase<Prov: Provenance> {
   pub relation_types: HashMap<String, TupleType>,
 
   /// Extensional relations
-  pub extensional_relations: HashMap<String, ExtensionalRelation<Prov>>,
+  pub extensional_relations: HashMap<String, ExtensionalRelation<Prov, Ptr>>,
 
   /// Flag for whether is internalized
   pub internalized: bool,
 }
 
-impl<Prov: Provenance> ExtensionalDatabase<Prov> {
+impl<Prov: Provenance, Ptr: PointerFamily> ExtensionalDatabase<Prov, Ptr> {
   /// Create a new extensional database
   pub fn new() -> Self {
     Self {
@@ -51,7 +52,7 @@ impl<Prov: Provenance> ExtensionalDatabase<Prov> {
     }
   }
 
-  pub fn clone_with_new_provenance<Prov2: Provenance>(&self) -> ExtensionalDatabase<Prov2>
+  pub fn clone_with_new_provenance<Prov2: Provenance>(&self) -> ExtensionalDatabase<Prov2, Ptr>
   where
     Prov2::InputTag: ConvertFromInputTag<Prov::InputTag>,
   {
@@ -133,6 +134,97 @@ impl<Prov: Provenance> ExtensionalDatabase<Prov> {
     Ok(())
   }
 
+  /// Retract facts from a relation, removing every occurrence of the given tuples regardless of their tags
+  ///
+  /// The relation is then marked as updated, so that the next execution updates every stratum that depends on
+  /// it; the derived tuples which are no longer supported are therefore removed as well. When the strata are
+  /// maintained (see `ExecutionOptions::incremental_maintain`), the deletions are propagated by deletion and
+  /// rederivation under an idempotent provenance, as long as the retracted facts are only used monotonically.
+  /// Otherwise the strata are recomputed from scratch, since the tags of the remaining derivations cannot be
+  /// obtained by subtracting the tags of the retracted facts for an arbitrary provenance.
+  pub fn retract_facts<T>(&mut self, relation: &str, facts: Vec<T>) -> Result<(), DatabaseError>
+  where
+    T: Into<Tuple>,
+  {
+    let facts: BTreeSet<Tuple> = facts.into_iter().map(|tup| tup.into()).collect();
+    self.check_tuples_type(relation, facts.iter())?;
+    self
+      .extensional_relations
+      .entry(relation.to_string())
+      .or_default()
+      .retract_facts(&facts);
+    Ok(())
+  }
+
+  /// Get the facts of a relation along with their input tags, including the facts that are not yet internalized
+  ///
+  /// The facts declared in the program are only loaded into the database when the program is executed. The
+  /// tags are the input tags of the provenance rather than the dynamic input tags of `ram::Fact`, since the
+  /// facts added with static input tags cannot be converted back into dynamic ones.
+  pub fn facts_of(&self, relation: &str) -> &[(Option<Prov::InputTag>, Tuple)] {
+    self
+      .extensional_relations
+      .get(relation)
+      .map(|r| r.facts())
+      .unwrap_or_default()
+  }
+
+  /// Remove all the facts of a relation, e.g. to load different facts into it before the next execution
+  ///
+  /// The strata depending on the relation are recomputed, and the program facts of the relation are not
+  /// populated anymore, even if the relation is cleared before the program is first executed.
+  pub fn clear_relation(&mut self, relation: &str) -> Result<(), DatabaseError> {
+    self.check_tuples_type(relation, std::iter::empty())?;
+    self
+      .extensional_relations
+      .entry(relation.to_string())
+      .or_default()
+      .clear();
+    Ok(())
+  }
+
+  /// Add facts that are already converted into tuples of the given type, e.g. when loading a large number of facts
+  ///
+  /// The given type is checked against the type of the relation only once, and the tuples are inserted without
+  /// being converted. A `TypeMismatch` error identifies the first tuple that does not match the type, in which
+  /// case no fact is added.
+  pub fn add_facts_checked(
+    &mut self,
+    relation: &str,
+    facts: Vec<Tuple>,
+    tuple_type: &TupleType,
+  ) -> Result<(), DatabaseError> {
+    match self.relation_types.get(relation) {
+      Some(relation_type) if relation_type != tuple_type => {
+        return Err(DatabaseError::RelationTypeMismatch {
+          relation: relation.to_string(),
+          expected: relation_type.clone(),
+          found: tuple_type.clone(),
+        })
+      }
+      None if self.type_check => {
+        return Err(DatabaseError::UnknownRelation {
+          relation: relation.to_string(),
+        })
+      }
+      _ => {}
+    }
+    if let Some(index) = facts.iter().position(|tuple| !tuple_type.matches(tuple)) {
+      return Err(DatabaseError::TypeMismatch {
+        relation: relation.to_string(),
+        index,
+        relation_type: tuple_type.clone(),
+        tuple: facts[index].clone(),
+      });
+    }
+    self
+      .extensional_relations
+      .entry(relation.to_string())
+      .or_default()
+      .add_facts(facts);
+    Ok(())
+  }
+
   pub fn add_static_input_facts(
     &mut self,
     relation: &str,
@@ -163,11 +255,14 @@ impl<Prov: Provenance> ExtensionalDatabase<Prov> {
   }
 
   pub fn get_dynamic_collection(&self, relation: &str) -> Option<&DynamicCollection<Prov>> {
-    self.extensional_relations.get(relation).map(|r| &r.internal)
+    self.extensional_relations.get(relation).map(|r| Ptr::get_rc(&r.internal))
   }
 
   pub fn pop_dynamic_collection(&mut self, relation: &str) -> Option<DynamicCollection<Prov>> {
-    self.extensional_relations.remove(relation).map(|r| r.internal)
+    self
+      .extensional_relations
+      .remove(relation)
+      .map(|mut r| std::mem::replace(Ptr::make_rc_mut(&mut r.internal), DynamicCollection::empty()))
   }
 
   pub fn load_into_static_relation<Tup>(&self, relation: &str, ctx: &Prov, rela: &StaticRelation<Tup, Prov>)
@@ -191,15 +286,11 @@ impl<Prov: Provenance> ExtensionalDatabase<Prov> {
           .extensional_relations
           .entry(relation.predicate.clone())
           .or_default();
-        if edb_relation.internalized_program_facts
-          && edb_relation.has_program_facts()
-          && edb_relation.num_program_facts() < relation.facts.len()
-        {
+        if !edb_relation.populate_program_facts(&relation.facts) {
           return Err(DatabaseError::NewProgramFacts {
             relation: relation.predicate.clone(),
           });
         }
-        edb_relation.add_program_facts(relation.facts.iter().map(|f| (f.tag.clone(), f.tuple.clone())));
       }
 
       // Check if we need to load external facts (from files or databases)
@@ -268,9 +359,10 @@ impl<Prov: Provenance> ExtensionalDatabase<Prov> {
   }
 }
 
-impl<Prov> ExtensionalDatabase<Prov>
+impl<Prov, Ptr> ExtensionalDatabase<Prov, Ptr>
 where
   Prov: Provenance<InputTag = InputExclusiveProb>,
+  Ptr: PointerFamily,
 {
   pub fn add_exclusive_probabilistic_facts<T>(
     &mut self,
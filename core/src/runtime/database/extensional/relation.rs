@@ -1,79 +1,182 @@
+use std::collections::*;
+
 use crate::common::input_tag::*;
 use crate::common::tuple::*;
+use crate::compiler::ram;
 use crate::runtime::dynamic::*;
 use crate::runtime::monitor::*;
 use crate::runtime::provenance::*;
+use crate::utils::*;
+
+pub struct ExtensionalRelation<Prov: Provenance, Ptr: PointerFamily = RcFamily> {
+  /// The facts of the relation with their input tags, whether they are from the program or added later;
+  /// the first `num_internalized_facts` of them are already internalized
+  facts: Vec<(Option<Prov::InputTag>, Tuple)>,
+
+  /// The number of facts that are already internalized
+  num_internalized_facts: usize,
+
+  /// Whether the program facts are populated; we only allow a single round of population of program facts
+  populated_program_facts: bool,
+
+  /// The number of program facts that are populated
+  num_program_facts: usize,
 
-#[derive(Clone, Debug)]
-pub struct ExtensionalRelation<Prov: Provenance> {
-  /// The facts from the program
-  program_facts: Vec<(DynamicInputTag, Tuple)>,
+  /// Whether the relation is cleared; the program facts of a cleared relation are not populated anymore
+  cleared: bool,
 
-  /// Whether we have internalized the program facts; we only allow a single
-  /// round of internalization of program facts
-  pub internalized_program_facts: bool,
+  /// The tuples retracted since the last internalization; the program facts with these tuples are not populated
+  retracted_facts: BTreeSet<Tuple>,
 
-  /// Dynamically tagged input facts
-  dynamic_input: Vec<(DynamicInputTag, Tuple)>,
+  /// Internalized facts; shared with the clones of the relation until either of them updates the facts
+  pub internal: Ptr::Rc<DynamicCollection<Prov>>,
 
-  /// Statically tagged input facts
-  static_input: Vec<(Option<Prov::InputTag>, Tuple)>,
+  /// The facts added by the last internalization, used to maintain the relations derived from this one
+  pub delta: Ptr::Rc<DynamicCollection<Prov>>,
 
-  /// Internalized facts
-  pub internal: DynamicCollection<Prov>,
+  /// The facts retracted before the last internalization, used to maintain the relations derived from this one
+  pub deleted: Ptr::Rc<DynamicCollection<Prov>>,
 
   /// Internalized flag
   pub internalized: bool,
 }
 
-impl<Prov: Provenance> Default for ExtensionalRelation<Prov> {
+impl<Prov: Provenance, Ptr: PointerFamily> Default for ExtensionalRelation<Prov, Ptr> {
   fn default() -> Self {
     Self::new()
   }
 }
 
-impl<Prov: Provenance> ExtensionalRelation<Prov> {
+impl<Prov: Provenance, Ptr: PointerFamily> Clone for ExtensionalRelation<Prov, Ptr> {
+  fn clone(&self) -> Self {
+    Self {
+      facts: self.facts.clone(),
+      num_internalized_facts: self.num_internalized_facts,
+      populated_program_facts: self.populated_program_facts,
+      num_program_facts: self.num_program_facts,
+      cleared: self.cleared,
+      retracted_facts: self.retracted_facts.clone(),
+      internal: Ptr::clone_rc(&self.internal),
+      delta: Ptr::clone_rc(&self.delta),
+      deleted: Ptr::clone_rc(&self.deleted),
+      internalized: self.internalized,
+    }
+  }
+}
+
+impl<Prov: Provenance, Ptr: PointerFamily> std::fmt::Debug for ExtensionalRelation<Prov, Ptr> {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.debug_struct("ExtensionalRelation")
+      .field("facts", &self.facts)
+      .field("num_internalized_facts", &self.num_internalized_facts)
+      .field("populated_program_facts", &self.populated_program_facts)
+      .field("cleared", &self.cleared)
+      .field("internal", Ptr::get_rc::<DynamicCollection<Prov>>(&self.internal))
+      .field("internalized", &self.internalized)
+      .finish()
+  }
+}
+
+impl<Prov: Provenance, Ptr: PointerFamily> ExtensionalRelation<Prov, Ptr> {
   pub fn new() -> Self {
     Self {
-      program_facts: vec![],
-      internalized_program_facts: false,
-      dynamic_input: vec![],
-      static_input: vec![],
-      internal: DynamicCollection::empty(),
+      facts: vec![],
+      num_internalized_facts: 0,
+      populated_program_facts: false,
+      num_program_facts: 0,
+      cleared: false,
+      retracted_facts: BTreeSet::new(),
+      internal: Ptr::new_rc(DynamicCollection::empty()),
+      delta: Ptr::new_rc(DynamicCollection::empty()),
+      deleted: Ptr::new_rc(DynamicCollection::empty()),
       internalized: false,
     }
   }
 
-  pub fn clone_with_new_provenance<Prov2: Provenance>(&self) -> ExtensionalRelation<Prov2>
+  pub fn clone_with_new_provenance<Prov2: Provenance>(&self) -> ExtensionalRelation<Prov2, Ptr>
   where
     Prov2::InputTag: ConvertFromInputTag<Prov::InputTag>,
   {
     ExtensionalRelation {
-      program_facts: self.program_facts.clone(),
-      internalized_program_facts: false,
-      dynamic_input: self.dynamic_input.clone(),
-      static_input: self.static_input.iter().map(|(tag, tuple)| {
+      facts: self.facts.iter().map(|(tag, tuple)| {
         let new_tag = tag.as_ref().and_then(|tag| ConvertFromInputTag::from_input_tag(tag.clone()));
         (new_tag, tuple.clone())
       }).collect(),
-      internal: DynamicCollection::empty(),
+      num_internalized_facts: 0,
+      populated_program_facts: self.populated_program_facts,
+      num_program_facts: self.num_program_facts,
+      cleared: self.cleared,
+      retracted_facts: self.retracted_facts.clone(),
+      internal: Ptr::new_rc(DynamicCollection::empty()),
+      delta: Ptr::new_rc(DynamicCollection::empty()),
+      deleted: Ptr::new_rc(DynamicCollection::empty()),
       internalized: false,
     }
   }
 
-  pub fn has_program_facts(&self) -> bool {
-    !self.program_facts.is_empty()
+  /// Populate the facts declared in the program, unless the relation is cleared
+  ///
+  /// The program facts are only populated once. Returns `false` if the program declares more facts than the
+  /// ones populated before, since the new program facts cannot be added anymore.
+  pub fn populate_program_facts(&mut self, facts: &[ram::Fact]) -> bool {
+    if self.cleared {
+      true
+    } else if self.populated_program_facts {
+      facts.len() <= self.num_program_facts
+    } else {
+      let retracted = &self.retracted_facts;
+      let program_facts = facts.iter().filter(|f| !retracted.contains(&f.tuple)).map(|f| {
+        let input_tag = StaticInputTag::from_dynamic_input_tag(&f.tag);
+        (input_tag, f.tuple.clone())
+      });
+      self.facts.extend(program_facts);
+      self.populated_program_facts = true;
+      self.num_program_facts = facts.len();
+      self.internalized = false;
+      true
+    }
   }
 
-  pub fn num_program_facts(&self) -> usize {
-    self.program_facts.len()
+  /// Get all the facts of the relation along with their input tags, whether they are internalized or not
+  pub fn facts(&self) -> &[(Option<Prov::InputTag>, Tuple)] {
+    &self.facts
   }
 
-  pub fn add_program_facts<I>(&mut self, i: I)
-  where
-    I: Iterator<Item = (DynamicInputTag, Tuple)>,
-  {
-    self.program_facts.extend(i)
+  /// Remove every occurrence of the given tuples from the relation, whether they are internalized or not
+  pub fn retract_facts(&mut self, facts: &BTreeSet<Tuple>) {
+    if facts.is_empty() {
+      return;
+    }
+    self.internalized = false;
+
+    // Remove the facts, keeping track of how many of the internalized ones are left
+    let num_previously_internalized_facts = self.num_internalized_facts;
+    let (mut index, mut num_internalized_facts) = (0, 0);
+    self.facts.retain(|(_, tup)| {
+      let keep = !facts.contains(tup);
+      if keep && index < num_previously_internalized_facts {
+        num_internalized_facts += 1;
+      }
+      index += 1;
+      keep
+    });
+    self.num_internalized_facts = num_internalized_facts;
+    Ptr::make_rc_mut(&mut self.internal)
+      .elements
+      .retain(|elem| !facts.contains(&elem.tuple));
+
+    // The program facts that are not populated yet should not be populated later
+    self.retracted_facts.extend(facts.iter().cloned());
+  }
+
+  /// Remove all the facts of the relation; its program facts are not populated anymore
+  pub fn clear(&mut self) {
+    let tuples = self.facts.drain(..).map(|(_, tup)| tup);
+    self.retracted_facts.extend(tuples.chain(self.internal.iter().map(|elem| elem.tuple.clone())));
+    self.num_internalized_facts = 0;
+    self.cleared = true;
+    self.internal = Ptr::new_rc(DynamicCollection::empty());
+    self.internalized = false;
   }
 
   pub fn add_facts(&mut self, facts: Vec<Tuple>) {
@@ -81,7 +184,7 @@ impl<Prov: Provenance> ExtensionalRelation<Prov> {
       self.internalized = false;
     }
 
-    self.static_input.extend(facts.into_iter().map(|tup| (None, tup)))
+    self.facts.extend(facts.into_iter().map(|tup| (None, tup)))
   }
 
   pub fn add_dynamic_input_facts(&mut self, facts: Vec<(DynamicInputTag, Tuple)>) {
@@ -89,7 +192,10 @@ impl<Prov: Provenance> ExtensionalRelation<Prov> {
       self.internalized = false;
     }
 
-    self.dynamic_input.extend(facts)
+    self.facts.extend(facts.into_iter().map(|(tag, tup)| {
+      let input_tag = StaticInputTag::from_dynamic_input_tag(&tag);
+      (input_tag, tup)
+    }))
   }
 
   pub fn add_static_input_facts(&mut self, facts: Vec<(Option<Prov::InputTag>, Tuple)>) {
@@ -97,92 +203,85 @@ impl<Prov: Provenance> ExtensionalRelation<Prov> {
       self.internalized = false;
     }
 
-    self.static_input.extend(facts)
+    self.facts.extend(facts)
   }
 
-  pub fn internalize(&mut self, ctx: &mut Prov) {
-    let mut elems: Vec<DynamicElement<Prov>> = Vec::new();
-
-    // First internalize program facts, only if there is program facts
-    if !self.program_facts.is_empty() {
-      // Iterate (not drain) the program facts
-      elems.extend(self.program_facts.iter().map(|(tag, tup)| {
-        let maybe_input_tag = StaticInputTag::from_dynamic_input_tag(&tag);
-        let tag = ctx.tagging_optional_fn(maybe_input_tag);
-        DynamicElement::new(tup.clone(), tag)
-      }));
+  /// Whether there is no fact to be internalized; the internal facts are then kept as is, and stay shared
+  /// with the clones of the relation
+  fn nothing_to_internalize(&self) -> bool {
+    self.num_internalized_facts == self.facts.len()
+  }
 
-      // Set the internalization to `true`
-      self.internalized_program_facts = true;
+  /// Take the retracted tuples as the deleted facts; their tags do not matter, as the facts are removed
+  fn take_retracted_facts(&mut self, ctx: &Prov) {
+    if !self.retracted_facts.is_empty() || !self.deleted.is_empty() {
+      let retracted_facts = std::mem::take(&mut self.retracted_facts);
+      let elems = retracted_facts.into_iter().map(|tup| DynamicElement::new(tup, ctx.one()));
+      self.deleted = Ptr::new_rc(DynamicCollection::from_vec_unchecked(elems.collect()));
     }
+  }
 
-    // First internalize dynamic input facts
-    elems.extend(self.dynamic_input.drain(..).map(|(tag, tup)| {
-      let maybe_input_tag = StaticInputTag::from_dynamic_input_tag(&tag);
-      let tag = ctx.tagging_optional_fn(maybe_input_tag);
-      DynamicElement::new(tup, tag)
-    }));
+  pub fn internalize(&mut self, ctx: &mut Prov) {
+    self.take_retracted_facts(ctx);
+    if self.nothing_to_internalize() {
+      if !self.delta.is_empty() {
+        self.delta = Ptr::new_rc(DynamicCollection::empty());
+      }
+      self.internalized = true;
+      return;
+    }
 
-    // Then internalize static input facts
-    elems.extend(self.static_input.drain(..).map(|(tag, tup)| {
-      let tag = ctx.tagging_optional_fn(tag);
-      DynamicElement::new(tup, tag)
-    }));
+    // Tag the facts that are not yet internalized
+    let elems: Vec<DynamicElement<Prov>> = self.facts[self.num_internalized_facts..]
+      .iter()
+      .map(|(tag, tup)| {
+        let tag = ctx.tagging_optional_fn(tag.clone());
+        DynamicElement::new(tup.clone(), tag)
+      })
+      .collect();
 
-    // Add existed facts
-    elems.extend(self.internal.elements.drain(..));
+    // Sort the new facts; note that we need to merge possibly duplicated tags
+    let delta = DynamicCollection::from_vec(elems, ctx);
 
-    // Finally sort the internal facts; note that we need to merge possibly duplicated tags
-    self.internal = DynamicCollection::from_vec(elems, ctx);
+    // Merge them with the existed facts; they are copied if shared with other relations
+    let internal = std::mem::replace(Ptr::make_rc_mut(&mut self.internal), DynamicCollection::empty());
+    self.internal = Ptr::new_rc(internal.merge(delta.clone(), ctx));
+    self.delta = Ptr::new_rc(delta);
+    self.num_internalized_facts = self.facts.len();
     self.internalized = true;
   }
 
   pub fn internalize_with_monitor<M: Monitor<Prov>>(&mut self, ctx: &mut Prov, m: &M) {
-    let mut elems: Vec<DynamicElement<Prov>> = Vec::new();
+    self.take_retracted_facts(ctx);
+    if self.nothing_to_internalize() {
+      if !self.delta.is_empty() {
+        self.delta = Ptr::new_rc(DynamicCollection::empty());
+      }
+      self.internalized = true;
+      return;
+    }
 
-    // First internalize program facts, only if there is program facts
-    if !self.program_facts.is_empty() {
-      // Iterate (not drain) the program facts
-      elems.extend(self.program_facts.iter().map(|(tag, tup)| {
-        let maybe_input_tag = StaticInputTag::from_dynamic_input_tag(&tag);
-        let tag = ctx.tagging_optional_fn(maybe_input_tag.clone());
+    // Tag the facts that are not yet internalized
+    let elems: Vec<DynamicElement<Prov>> = self.facts[self.num_internalized_facts..]
+      .iter()
+      .map(|(input_tag, tup)| {
+        let tag = ctx.tagging_optional_fn(input_tag.clone());
 
         // !SPECIAL MONITORING!
-        m.observe_tagging(tup, &maybe_input_tag, &tag);
+        m.observe_tagging(tup, input_tag, &tag);
 
         DynamicElement::new(tup.clone(), tag)
-      }));
-
-      // Set the internalization to `true`
-      self.internalized_program_facts = true;
-    }
-
-    // First internalize dynamic input facts
-    elems.extend(self.dynamic_input.drain(..).map(|(tag, tup)| {
-      let maybe_input_tag = StaticInputTag::from_dynamic_input_tag(&tag);
-      let tag = ctx.tagging_optional_fn(maybe_input_tag.clone());
-
-      // !SPECIAL MONITORING!
-      m.observe_tagging(&tup, &maybe_input_tag, &tag);
-
-      DynamicElement::new(tup, tag)
-    }));
-
-    // Then internalize static input facts
-    elems.extend(self.static_input.drain(..).map(|(input_tag, tup)| {
-      let tag = ctx.tagging_optional_fn(input_tag.clone());
-
-      // !SPECIAL MONITORING!
-      m.observe_tagging(&tup, &input_tag, &tag);
-
-      DynamicElement::new(tup, tag)
-    }));
+      })
+      .collect();
 
-    // Add existed facts
-    elems.extend(self.internal.elements.drain(..));
+    // Sort the new facts; note that we need to merge possibly duplicated tags
+    let delta = DynamicCollection::from_vec(elems, ctx);
 
-    // Finally sort the internal facts; note that we need to merge possibly duplicated tags
-    self.internal = DynamicCollection::from_vec(elems, ctx);
+    // Merge them with the existed facts; they are copied if shared with other relations
+    let internal = std::mem::replace(Ptr::make_rc_mut(&mut self.internal), DynamicCollection::empty());
+    self.internal = Ptr::new_rc(internal.merge(delta.clone(), ctx));
+    self.delta = Ptr::new_rc(delta);
+    self.num_internalized_facts = self.facts.len();
     self.internalized = true;
   }
 }
@@ -61,6 +61,13 @@ impl<Prov: Provenance, Ptr: PointerFamily> IntentionalDatabase<Prov, Ptr> {
       .insert(relation, IntentionalRelation::from_dynamic_collection(collection));
   }
 
+  /// Insert a dynamic collection that is shared with other relations, without copying it
+  pub fn insert_shared_dynamic_collection(&mut self, relation: String, collection: Ptr::Rc<DynamicCollection<Prov>>) {
+    self
+      .intentional_relations
+      .insert(relation, IntentionalRelation::from_shared_dynamic_collection(collection));
+  }
+
   /// Insert dynamic output collection
   pub fn insert_dynamic_output_collection(&mut self, relation: String, collection: DynamicOutputCollection<Prov>) {
     self.intentional_relations.insert(
@@ -74,12 +81,12 @@ impl<Prov: Provenance, Ptr: PointerFamily> IntentionalDatabase<Prov, Ptr> {
     self.intentional_relations.contains_key(relation)
   }
 
-  pub fn recover_from_edb(&mut self, relation: &str, ctx: &Prov, edb_relation: &ExtensionalRelation<Prov>) {
+  pub fn recover_from_edb(&mut self, relation: &str, ctx: &Prov, edb_relation: &ExtensionalRelation<Prov, Ptr>) {
     self.intentional_relations.insert(
       relation.to_string(),
       IntentionalRelation {
         recovered: true,
-        internal_facts: DynamicCollection::empty(),
+        internal_facts: Ptr::new_rc(DynamicCollection::empty()),
         recovered_facts: Ptr::new_rc(DynamicOutputCollection::from(
           edb_relation
             .internal
@@ -106,9 +113,18 @@ impl<Prov: Provenance, Ptr: PointerFamily> IntentionalDatabase<Prov, Ptr> {
     }
   }
 
+  /// Hand the recovered facts of a relation to a monitor without storing them
+  pub fn stream_with_monitor<M: Monitor<Prov>>(&self, relation: &str, ctx: &Prov, m: &M) {
+    if let Some(r) = self.intentional_relations.get(relation) {
+      // !SPECIAL MONITORING!
+      m.observe_recovering_relation(relation);
+      r.stream_with_monitor(ctx, m);
+    }
+  }
+
   /// Get internal collection
   pub fn get_internal_collection(&self, relation: &str) -> Option<&DynamicCollection<Prov>> {
-    self.intentional_relations.get(relation).map(|r| &r.internal_facts)
+    self.intentional_relations.get(relation).map(|r| Ptr::get_rc(&r.internal_facts))
   }
 
   /// Get recovered collection
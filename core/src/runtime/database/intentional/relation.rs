@@ -7,8 +7,8 @@ pub struct IntentionalRelation<Prov: Provenance, Ptr: PointerFamily> {
   /// Recovered
   pub recovered: bool,
 
-  /// Internal facts
-  pub internal_facts: DynamicCollection<Prov>,
+  /// Internal facts; shared with the clones of the relation until either of them updates the facts
+  pub internal_facts: Ptr::Rc<DynamicCollection<Prov>>,
 
   /// Recovered facts
   pub recovered_facts: Ptr::Rc<DynamicOutputCollection<Prov>>,
@@ -24,7 +24,7 @@ impl<Prov: Provenance, Ptr: PointerFamily> Clone for IntentionalRelation<Prov, P
   fn clone(&self) -> Self {
     Self {
       recovered: self.recovered,
-      internal_facts: self.internal_facts.clone(),
+      internal_facts: Ptr::clone_rc(&self.internal_facts),
       recovered_facts: Ptr::clone_rc(&self.recovered_facts),
     }
   }
@@ -33,7 +33,7 @@ impl<Prov: Provenance, Ptr: PointerFamily> Clone for IntentionalRelation<Prov, P
 impl<Prov: Provenance, Ptr: PointerFamily> std::fmt::Debug for IntentionalRelation<Prov, Ptr> {
   fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
     f.debug_struct("IDBRelation")
-      .field("internal", &self.internal_facts)
+      .field("internal", Ptr::get_rc::<DynamicCollection<Prov>>(&self.internal_facts))
       .field("recovered", &Ptr::get_rc(&self.recovered_facts))
       .finish()
   }
@@ -49,12 +49,21 @@ impl<Prov: Provenance, Ptr: PointerFamily> IntentionalRelation<Prov, Ptr> {
   pub fn new() -> Self {
     Self {
       recovered: false,
-      internal_facts: DynamicCollection::empty(),
+      internal_facts: Ptr::new_rc(DynamicCollection::empty()),
       recovered_facts: Ptr::new_rc(DynamicOutputCollection::empty()),
     }
   }
 
   pub fn from_dynamic_collection(collection: DynamicCollection<Prov>) -> Self {
+    Self {
+      recovered: false,
+      internal_facts: Ptr::new_rc(collection),
+      recovered_facts: Ptr::new_rc(DynamicOutputCollection::empty()),
+    }
+  }
+
+  /// Create a relation whose internal facts are shared with another relation, e.g. an extensional one
+  pub fn from_shared_dynamic_collection(collection: Ptr::Rc<DynamicCollection<Prov>>) -> Self {
     Self {
       recovered: false,
       internal_facts: collection,
@@ -65,24 +74,35 @@ impl<Prov: Provenance, Ptr: PointerFamily> IntentionalRelation<Prov, Ptr> {
   pub fn from_dynamic_output_collection(collection: DynamicOutputCollection<Prov>) -> Self {
     Self {
       recovered: true,
-      internal_facts: DynamicCollection::empty(),
+      internal_facts: Ptr::new_rc(DynamicCollection::empty()),
       recovered_facts: Ptr::new_rc(collection),
     }
   }
 
+  /// Get the number of recovered facts in this relation
+  pub fn len(&self) -> usize {
+    Ptr::get_rc(&self.recovered_facts).len()
+  }
+
+  /// Check if there is no recovered fact in this relation
+  pub fn is_empty(&self) -> bool {
+    Ptr::get_rc(&self.recovered_facts).is_empty()
+  }
+
   pub fn recover_with_monitor<M: Monitor<Prov>>(&mut self, ctx: &Prov, m: &M, drain: bool) {
     // Only recover if it is not recovered
     if !self.recovered && !self.internal_facts.is_empty() {
       if drain {
-        // Add internal facts to recovered facts, and remove the internal facts
-        Ptr::get_rc_mut(&mut self.recovered_facts).extend(self.internal_facts.drain().map(|elem| {
+        // Add internal facts to recovered facts, and remove the internal facts; they are copied if shared
+        let internal_facts = Ptr::make_rc_mut(&mut self.internal_facts);
+        Ptr::make_rc_mut(&mut self.recovered_facts).extend(internal_facts.drain().map(|elem| {
           let output_tag = ctx.recover_fn(&elem.tag);
           m.observe_recover(&elem.tuple, &elem.tag, &output_tag);
           (output_tag, elem.tuple)
         }));
       } else {
         // Add internal facts to recover facts, do not remove the internal facts
-        Ptr::get_rc_mut(&mut self.recovered_facts).extend(self.internal_facts.iter().map(|elem| {
+        Ptr::make_rc_mut(&mut self.recovered_facts).extend(self.internal_facts.iter().map(|elem| {
           let output_tag = ctx.recover_fn(&elem.tag);
           m.observe_recover(&elem.tuple, &elem.tag, &output_tag);
           (output_tag, elem.tuple.clone())
@@ -94,6 +114,14 @@ impl<Prov: Provenance, Ptr: PointerFamily> IntentionalRelation<Prov, Ptr> {
     }
   }
 
+  /// Hand the recovered facts to the monitor without storing them; the relation stays unrecovered
+  pub fn stream_with_monitor<M: Monitor<Prov>>(&self, ctx: &Prov, m: &M) {
+    for elem in self.internal_facts.iter() {
+      let output_tag = ctx.recover_fn(&elem.tag);
+      m.observe_recover(&elem.tuple, &elem.tag, &output_tag);
+    }
+  }
+
   pub fn recover(&mut self, ctx: &Prov, drain: bool) {
     // Only recover if it is not recovered
     if !self.recovered {
@@ -105,14 +133,15 @@ impl<Prov: Provenance, Ptr: PointerFamily> IntentionalRelation<Prov, Ptr> {
 
       // Check if we need to drain the internal facts
       if drain {
-        // Add internal facts to recovered facts, and remove the internal facts
-        Ptr::get_rc_mut(&mut self.recovered_facts).extend(self.internal_facts.drain().map(|elem| {
+        // Add internal facts to recovered facts, and remove the internal facts; they are copied if shared
+        let internal_facts = Ptr::make_rc_mut(&mut self.internal_facts);
+        Ptr::make_rc_mut(&mut self.recovered_facts).extend(internal_facts.drain().map(|elem| {
           let output_tag = ctx.recover_fn(&elem.tag);
           (output_tag, elem.tuple)
         }));
       } else {
         // Add internal facts to recover facts, do not remove the internal facts
-        Ptr::get_rc_mut(&mut self.recovered_facts).extend(self.internal_facts.iter().map(|elem| {
+        Ptr::make_rc_mut(&mut self.recovered_facts).extend(self.internal_facts.iter().map(|elem| {
           let output_tag = ctx.recover_fn(&elem.tag);
           (output_tag, elem.tuple.clone())
         }));
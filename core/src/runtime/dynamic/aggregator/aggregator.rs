@@ -8,30 +8,66 @@ use super::*;
 #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
 pub enum DynamicAggregator {
   Count(DynamicCount),
+  CountDistinct(DynamicCountDistinct),
   Sum(DynamicSum),
   Prod(DynamicProd),
+  WeightedSum(DynamicWeightedSum),
+  Variance(DynamicVariance),
+  StdDev(DynamicStdDev),
+  Median(DynamicMedian),
+  Collect(DynamicCollect),
+  GeometricMean(DynamicGeometricMean),
+  HarmonicMean(DynamicHarmonicMean),
   Min(DynamicMin),
   Max(DynamicMax),
   Argmin(DynamicArgmin),
   Argmax(DynamicArgmax),
+  ArgminTuple(DynamicArgminTuple),
+  ArgmaxTuple(DynamicArgmaxTuple),
   Exists(DynamicExists),
+  Any(DynamicAny),
   TopK(DynamicTopK),
+  ArgTopK(DynamicArgTopK),
+  Rank(DynamicRank),
+  ArgRank(DynamicArgRank),
   CategoricalK(DynamicCategoricalK),
+  Sample(DynamicSample),
+  Normalize(DynamicNormalize),
+  Window(DynamicWindow),
+  Multiple(DynamicMultiple),
 }
 
 impl From<AggregateOp> for DynamicAggregator {
   fn from(o: AggregateOp) -> Self {
     match o {
       AggregateOp::Count => Self::count(),
+      AggregateOp::CountDistinct => Self::count_distinct(),
       AggregateOp::Sum(t) => Self::sum(t),
       AggregateOp::Prod(t) => Self::prod(t),
+      AggregateOp::WeightedSum(t) => Self::weighted_sum(t),
+      AggregateOp::Variance(t) => Self::variance(t),
+      AggregateOp::StdDev(t) => Self::std_dev(t),
+      AggregateOp::Median(t) => Self::median(t),
+      AggregateOp::Collect => Self::collect(),
+      AggregateOp::GeometricMean(t) => Self::geometric_mean(t),
+      AggregateOp::HarmonicMean(t) => Self::harmonic_mean(t),
       AggregateOp::Min => Self::min(),
       AggregateOp::Max => Self::max(),
       AggregateOp::Argmin => Self::argmin(),
       AggregateOp::Argmax => Self::argmax(),
+      AggregateOp::ArgminTuple => Self::argmin_tuple(),
+      AggregateOp::ArgmaxTuple => Self::argmax_tuple(),
       AggregateOp::Exists => Self::exists(),
+      AggregateOp::Any => Self::any(),
       AggregateOp::TopK(k) => Self::top_k(k),
+      AggregateOp::ArgTopK(k) => Self::arg_top_k(k),
+      AggregateOp::Rank => Self::rank(),
+      AggregateOp::ArgRank => Self::arg_rank(),
       AggregateOp::CategoricalK(k) => Self::categorical_k(k),
+      AggregateOp::Sample(t) => Self::sample(t),
+      AggregateOp::Normalize(t) => Self::normalize(t),
+      AggregateOp::Window { op, size } => Self::window((*op).into(), size),
+      AggregateOp::Multiple(ops) => Self::multiple(ops.into_iter().map(Self::from).collect()),
     }
   }
 }
@@ -41,6 +77,10 @@ impl DynamicAggregator {
     Self::Count(DynamicCount)
   }
 
+  pub fn count_distinct() -> Self {
+    Self::CountDistinct(DynamicCountDistinct)
+  }
+
   pub fn sum(ty: ValueType) -> Self {
     Self::Sum(DynamicSum(ty))
   }
@@ -63,6 +103,34 @@ impl DynamicAggregator {
     Self::Prod(DynamicProd(<ValueType as FromType<T>>::from_type()))
   }
 
+  pub fn weighted_sum(ty: ValueType) -> Self {
+    Self::WeightedSum(DynamicWeightedSum(ty))
+  }
+
+  pub fn variance(ty: ValueType) -> Self {
+    Self::Variance(DynamicVariance(ty))
+  }
+
+  pub fn std_dev(ty: ValueType) -> Self {
+    Self::StdDev(DynamicStdDev(ty))
+  }
+
+  pub fn median(ty: ValueType) -> Self {
+    Self::Median(DynamicMedian(ty))
+  }
+
+  pub fn collect() -> Self {
+    Self::Collect(DynamicCollect)
+  }
+
+  pub fn geometric_mean(ty: ValueType) -> Self {
+    Self::GeometricMean(DynamicGeometricMean(ty))
+  }
+
+  pub fn harmonic_mean(ty: ValueType) -> Self {
+    Self::HarmonicMean(DynamicHarmonicMean(ty))
+  }
+
   pub fn min() -> Self {
     Self::Min(DynamicMin)
   }
@@ -79,18 +147,58 @@ impl DynamicAggregator {
     Self::Argmax(DynamicArgmax)
   }
 
+  pub fn argmin_tuple() -> Self {
+    Self::ArgminTuple(DynamicArgminTuple)
+  }
+
+  pub fn argmax_tuple() -> Self {
+    Self::ArgmaxTuple(DynamicArgmaxTuple)
+  }
+
   pub fn exists() -> Self {
     Self::Exists(DynamicExists)
   }
 
+  pub fn any() -> Self {
+    Self::Any(DynamicAny)
+  }
+
   pub fn top_k(k: usize) -> Self {
     Self::TopK(DynamicTopK(k))
   }
 
+  pub fn arg_top_k(k: usize) -> Self {
+    Self::ArgTopK(DynamicArgTopK(k))
+  }
+
+  pub fn rank() -> Self {
+    Self::Rank(DynamicRank)
+  }
+
+  pub fn arg_rank() -> Self {
+    Self::ArgRank(DynamicArgRank)
+  }
+
   pub fn categorical_k(k: usize) -> Self {
     Self::CategoricalK(DynamicCategoricalK(k))
   }
 
+  pub fn sample(weight_ty: Option<ValueType>) -> Self {
+    Self::Sample(DynamicSample(weight_ty))
+  }
+
+  pub fn normalize(ty: ValueType) -> Self {
+    Self::Normalize(DynamicNormalize(ty))
+  }
+
+  pub fn window(op: DynamicAggregator, size: usize) -> Self {
+    Self::Window(DynamicWindow { op: Box::new(op), size })
+  }
+
+  pub fn multiple(ops: Vec<DynamicAggregator>) -> Self {
+    Self::Multiple(DynamicMultiple(ops))
+  }
+
   pub fn aggregate<Prov: Provenance>(
     &self,
     batch: DynamicElements<Prov>,
@@ -99,15 +207,33 @@ impl DynamicAggregator {
   ) -> DynamicElements<Prov> {
     match self {
       Self::Count(c) => c.aggregate(batch, ctx),
-      Self::Sum(s) => s.aggregate(batch, ctx),
+      Self::CountDistinct(c) => c.aggregate(batch, ctx),
+      Self::Sum(s) => s.aggregate(batch, ctx, rt),
       Self::Prod(p) => p.aggregate(batch, ctx),
+      Self::WeightedSum(w) => w.aggregate(batch, ctx),
+      Self::Variance(v) => v.aggregate(batch, ctx, rt),
+      Self::StdDev(s) => s.aggregate(batch, ctx, rt),
+      Self::Median(m) => m.aggregate(batch, ctx),
+      Self::Collect(c) => c.aggregate(batch, ctx),
+      Self::GeometricMean(m) => m.aggregate(batch, ctx),
+      Self::HarmonicMean(m) => m.aggregate(batch, ctx),
       Self::Min(m) => m.aggregate(batch, ctx),
       Self::Max(m) => m.aggregate(batch, ctx),
       Self::Argmin(m) => m.aggregate(batch, ctx),
       Self::Argmax(m) => m.aggregate(batch, ctx),
+      Self::ArgminTuple(m) => m.aggregate(batch, ctx),
+      Self::ArgmaxTuple(m) => m.aggregate(batch, ctx),
       Self::Exists(e) => e.aggregate(batch, ctx),
+      Self::Any(a) => a.aggregate(batch, ctx),
       Self::TopK(t) => t.aggregate(batch, ctx),
+      Self::ArgTopK(t) => t.aggregate(batch, ctx),
+      Self::Rank(r) => r.aggregate(batch, ctx),
+      Self::ArgRank(r) => r.aggregate(batch, ctx),
       Self::CategoricalK(c) => c.aggregate(batch, ctx, rt),
+      Self::Sample(s) => s.aggregate(batch, ctx, rt),
+      Self::Normalize(n) => n.aggregate(batch, ctx),
+      Self::Window(w) => w.aggregate(batch, ctx, rt),
+      Self::Multiple(m) => m.aggregate(batch, ctx, rt),
     }
   }
 }
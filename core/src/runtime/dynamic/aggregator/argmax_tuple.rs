@@ -0,0 +1,14 @@
+use crate::runtime::provenance::*;
+
+use super::argmin_tuple::{key_first, key_last};
+use super::*;
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct DynamicArgmaxTuple;
+
+impl DynamicArgmaxTuple {
+  pub fn aggregate<Prov: Provenance>(&self, batch: DynamicElements<Prov>, ctx: &Prov) -> DynamicElements<Prov> {
+    let batch = batch.into_iter().map(key_last).collect();
+    ctx.dynamic_argmax(batch).into_iter().map(key_first).collect()
+  }
+}
@@ -0,0 +1,43 @@
+use crate::common::tuple::*;
+use crate::runtime::provenance::*;
+
+use super::*;
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct DynamicArgminTuple;
+
+impl DynamicArgminTuple {
+  pub fn aggregate<Prov: Provenance>(&self, batch: DynamicElements<Prov>, ctx: &Prov) -> DynamicElements<Prov> {
+    let batch = batch.into_iter().map(key_last).collect();
+    ctx.dynamic_argmin(batch).into_iter().map(key_first).collect()
+  }
+}
+
+/// Turn a tuple `(key, x...)` into `((x...), key)`, the shape of the tuples aggregated by `argmin`/`argmax`;
+/// a tuple without any `x` is the key itself, turned into `((), key)`
+pub(super) fn key_last<Prov: Provenance>(elem: DynamicElement<Prov>) -> DynamicElement<Prov> {
+  let tuple = match elem.tuple {
+    Tuple::Tuple(ts) if ts.len() > 1 => {
+      let mut ts = ts.into_vec();
+      let key = ts.remove(0);
+      Tuple::Tuple(Box::new([Tuple::Tuple(ts.into()), key]))
+    }
+    key => Tuple::Tuple(Box::new([Tuple::Tuple(Box::new([])), key])),
+  };
+  DynamicElement::new(tuple, elem.tag)
+}
+
+/// Turn a tuple `((x...), key)` back into `(key, x...)`
+pub(super) fn key_first<Prov: Provenance>(elem: DynamicElement<Prov>) -> DynamicElement<Prov> {
+  let mut ts = match elem.tuple {
+    Tuple::Tuple(ts) => ts.into_vec(),
+    t => vec![t],
+  };
+  let (key, args) = (ts.pop().unwrap(), ts.pop().unwrap());
+  let tuple = match args {
+    Tuple::Tuple(args) if args.is_empty() => key,
+    Tuple::Tuple(args) => Tuple::Tuple(std::iter::once(key).chain(args.into_vec()).collect()),
+    arg => Tuple::Tuple(Box::new([key, arg])),
+  };
+  DynamicElement::new(tuple, elem.tag)
+}
@@ -0,0 +1,12 @@
+use crate::runtime::provenance::*;
+
+use super::*;
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct DynamicCollect;
+
+impl DynamicCollect {
+  pub fn aggregate<Prov: Provenance>(&self, batch: DynamicElements<Prov>, ctx: &Prov) -> DynamicElements<Prov> {
+    ctx.dynamic_collect(batch)
+  }
+}
@@ -0,0 +1,13 @@
+use crate::common::value_type::*;
+use crate::runtime::provenance::*;
+
+use super::*;
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct DynamicHarmonicMean(pub ValueType);
+
+impl DynamicHarmonicMean {
+  pub fn aggregate<Prov: Provenance>(&self, batch: DynamicElements<Prov>, ctx: &Prov) -> DynamicElements<Prov> {
+    ctx.dynamic_harmonic_mean(&self.0, batch)
+  }
+}
@@ -1,25 +1,59 @@
 mod aggregator;
+mod any;
+mod arg_top_k;
 mod argmax;
+mod argmax_tuple;
 mod argmin;
+mod argmin_tuple;
 mod categorical_k;
+mod collect;
 mod count;
+mod count_distinct;
 mod exists;
+mod geometric_mean;
+mod harmonic_mean;
 mod max;
+mod median;
 mod min;
+mod multiple;
+mod normalize;
 mod prod;
+mod rank;
+mod sample;
+mod std_dev;
 mod sum;
 mod top_k;
+mod variance;
+mod weighted_sum;
+mod window;
 
 pub use aggregator::*;
+pub use any::*;
+pub use arg_top_k::*;
 pub use argmax::*;
+pub use argmax_tuple::*;
 pub use argmin::*;
+pub use argmin_tuple::*;
 pub use categorical_k::*;
+pub use collect::*;
 pub use count::*;
+pub use count_distinct::*;
 pub use exists::*;
+pub use geometric_mean::*;
+pub use harmonic_mean::*;
 pub use max::*;
+pub use median::*;
 pub use min::*;
+pub use multiple::*;
+pub use normalize::*;
 pub use prod::*;
+pub use rank::*;
+pub use sample::*;
+pub use std_dev::*;
 pub use sum::*;
 pub use top_k::*;
+pub use variance::*;
+pub use weighted_sum::*;
+pub use window::*;
 
 use super::*;
@@ -0,0 +1,39 @@
+use crate::common::tuple::*;
+use crate::runtime::env::*;
+use crate::runtime::provenance::*;
+
+use super::*;
+
+/// Multiple aggregator
+///
+/// Applies every inner aggregator on the same batch. Each combination of their results produces
+/// the tuple of the results, in the order of the aggregators, tagged with the product of their tags.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct DynamicMultiple(pub Vec<DynamicAggregator>);
+
+impl DynamicMultiple {
+  pub fn aggregate<Prov: Provenance>(
+    &self,
+    batch: DynamicElements<Prov>,
+    ctx: &Prov,
+    rt: &RuntimeEnvironment,
+  ) -> DynamicElements<Prov> {
+    let mut result = vec![(vec![], ctx.one())];
+    for op in &self.0 {
+      let elems = op.aggregate(batch.clone(), ctx, rt);
+      result = result
+        .into_iter()
+        .flat_map(|(tuples, tag)| {
+          elems.iter().map(move |e| {
+            let tuples = tuples.iter().cloned().chain(std::iter::once(e.tuple.clone())).collect::<Vec<_>>();
+            (tuples, ctx.mult(&tag, &e.tag))
+          })
+        })
+        .collect();
+    }
+    result
+      .into_iter()
+      .map(|(tuples, tag)| DynamicElement::new(Tuple::Tuple(tuples.into()), tag))
+      .collect()
+  }
+}
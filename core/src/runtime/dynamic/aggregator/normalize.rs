@@ -0,0 +1,18 @@
+use crate::common::value_type::*;
+use crate::runtime::provenance::*;
+
+use super::*;
+
+/// Normalize aggregator
+///
+/// Each element is of the form `(value, tuple...)`, where the value is of the given type; one element
+/// `(tuple..., weight)` is produced for each of the elements, where the weight is the value divided by
+/// the sum of the values of the batch.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct DynamicNormalize(pub ValueType);
+
+impl DynamicNormalize {
+  pub fn aggregate<Prov: Provenance>(&self, batch: DynamicElements<Prov>, ctx: &Prov) -> DynamicElements<Prov> {
+    ctx.dynamic_normalize(&self.0, batch)
+  }
+}
@@ -0,0 +1,25 @@
+use crate::common::value_type::*;
+use crate::runtime::env::*;
+use crate::runtime::provenance::*;
+
+use super::*;
+
+/// Weighted random sample aggregator
+///
+/// Selects one element of the batch with probability proportional to its weight. When the weight type
+/// is given, each element is of the form `(weight, tuple...)` and the weight is dropped from the result;
+/// otherwise the weights are the ones of the provenance tags. The random number generator of the
+/// runtime environment is used, so that the samples are reproducible under the same seed.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct DynamicSample(pub Option<ValueType>);
+
+impl DynamicSample {
+  pub fn aggregate<Prov: Provenance>(
+    &self,
+    batch: DynamicElements<Prov>,
+    ctx: &Prov,
+    rt: &RuntimeEnvironment,
+  ) -> DynamicElements<Prov> {
+    ctx.dynamic_sample(self.0.as_ref(), batch, rt)
+  }
+}
@@ -0,0 +1,19 @@
+use crate::common::value_type::*;
+use crate::runtime::env::*;
+use crate::runtime::provenance::*;
+
+use super::*;
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct DynamicStdDev(pub ValueType);
+
+impl DynamicStdDev {
+  pub fn aggregate<Prov: Provenance>(
+    &self,
+    batch: DynamicElements<Prov>,
+    ctx: &Prov,
+    rt: &RuntimeEnvironment,
+  ) -> DynamicElements<Prov> {
+    ctx.dynamic_std_dev(&self.0, batch, rt)
+  }
+}
@@ -1,4 +1,5 @@
 use crate::common::value_type::*;
+use crate::runtime::env::*;
 use crate::runtime::provenance::*;
 
 use super::*;
@@ -7,7 +8,12 @@ use super::*;
 pub struct DynamicSum(pub ValueType);
 
 impl DynamicSum {
-  pub fn aggregate<Prov: Provenance>(&self, batch: DynamicElements<Prov>, ctx: &Prov) -> DynamicElements<Prov> {
-    ctx.dynamic_sum(&self.0, batch)
+  pub fn aggregate<Prov: Provenance>(
+    &self,
+    batch: DynamicElements<Prov>,
+    ctx: &Prov,
+    rt: &RuntimeEnvironment,
+  ) -> DynamicElements<Prov> {
+    ctx.dynamic_sum(&self.0, batch, rt)
   }
 }
@@ -0,0 +1,19 @@
+use crate::common::value_type::*;
+use crate::runtime::env::*;
+use crate::runtime::provenance::*;
+
+use super::*;
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct DynamicVariance(pub ValueType);
+
+impl DynamicVariance {
+  pub fn aggregate<Prov: Provenance>(
+    &self,
+    batch: DynamicElements<Prov>,
+    ctx: &Prov,
+    rt: &RuntimeEnvironment,
+  ) -> DynamicElements<Prov> {
+    ctx.dynamic_variance(&self.0, batch, rt)
+  }
+}
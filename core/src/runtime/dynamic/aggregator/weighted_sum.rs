@@ -0,0 +1,13 @@
+use crate::common::value_type::*;
+use crate::runtime::provenance::*;
+
+use super::*;
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct DynamicWeightedSum(pub ValueType);
+
+impl DynamicWeightedSum {
+  pub fn aggregate<Prov: Provenance>(&self, batch: DynamicElements<Prov>, ctx: &Prov) -> DynamicElements<Prov> {
+    ctx.dynamic_weighted_sum(&self.0, batch)
+  }
+}
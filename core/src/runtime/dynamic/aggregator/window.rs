@@ -0,0 +1,49 @@
+use crate::runtime::env::*;
+use crate::runtime::provenance::*;
+
+use super::*;
+
+/// Window aggregator
+///
+/// Each element in the batch is of the form `(ordering, value)`. Elements are sorted by their
+/// ordering, and the inner aggregator is applied on the values of every window of `size`
+/// contiguous elements; windows at the beginning of the batch are shorter. Each window produces
+/// its results tupled with the ordering of the element ending the window.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct DynamicWindow {
+  pub op: Box<DynamicAggregator>,
+  pub size: usize,
+}
+
+impl DynamicWindow {
+  pub fn aggregate<Prov: Provenance>(
+    &self,
+    mut batch: DynamicElements<Prov>,
+    ctx: &Prov,
+    rt: &RuntimeEnvironment,
+  ) -> DynamicElements<Prov> {
+    batch.sort_by(|e1, e2| e1.tuple.cmp(&e2.tuple));
+
+    let mut result = vec![];
+    for end in 0..batch.len() {
+      let start = (end + 1).saturating_sub(self.size);
+
+      // The inner aggregator expects its batch to be sorted
+      let mut window = batch[start..=end]
+        .iter()
+        .map(|e| DynamicElement::new(e.tuple[1].clone(), e.tag.clone()))
+        .collect::<DynamicElements<Prov>>();
+      window.sort_by(|e1, e2| e1.tuple.cmp(&e2.tuple));
+
+      let ordering = &batch[end].tuple[0];
+      result.extend(
+        self
+          .op
+          .aggregate(window, ctx, rt)
+          .into_iter()
+          .map(|e| DynamicElement::new((ordering.clone(), e.tuple), e.tag)),
+      );
+    }
+    result
+  }
+}
@@ -84,6 +84,15 @@ impl<Prov: Provenance> DynamicCollection<Prov> {
     self.elements.get(i)
   }
 
+  /// Find the element of the given tuple
+  pub fn find(&self, tuple: &Tuple) -> Option<&DynamicElement<Prov>> {
+    self
+      .elements
+      .binary_search_by(|elem| elem.tuple.cmp(tuple))
+      .ok()
+      .map(|i| &self.elements[i])
+  }
+
   pub fn iter(&self) -> impl Iterator<Item = &DynamicElement<Prov>> {
     self.elements.iter()
   }
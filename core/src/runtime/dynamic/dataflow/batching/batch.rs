@@ -13,6 +13,7 @@ pub enum DynamicBatch<'a, Prov: Provenance> {
   DynamicRelationRecent(DynamicRelationRecentBatch<'a, Prov>),
   OverwriteOne(DynamicOverwriteOneBatch<'a, Prov>),
   Project(DynamicProjectBatch<'a, Prov>),
+  Rename(DynamicRenameBatch<'a, Prov>),
   Filter(DynamicFilterBatch<'a, Prov>),
   Find(DynamicFindBatch<'a, Prov>),
   Intersect(DynamicIntersectBatch<'a, Prov>),
@@ -118,6 +119,7 @@ impl<'a, Prov: Provenance> Iterator for DynamicBatch<'a, Prov> {
       Self::DynamicRelationRecent(b) => b.next(),
       Self::OverwriteOne(o) => o.next(),
       Self::Project(p) => p.next(),
+      Self::Rename(r) => r.next(),
       Self::Filter(f) => f.next(),
       Self::Find(f) => f.next(),
       Self::Intersect(i) => i.next(),
@@ -12,8 +12,12 @@ pub enum DynamicBatches<'a, Prov: Provenance> {
   Chain(DynamicBatchesChain<'a, Prov>),
   DynamicRelationStable(DynamicRelationStableBatches<'a, Prov>),
   Project(DynamicProjectBatches<'a, Prov>),
+  Rename(DynamicRenameBatches<'a, Prov>),
   Filter(DynamicFilterBatches<'a, Prov>),
   Find(DynamicFindBatches<'a, Prov>),
+  Sort(DynamicSortBatches<'a, Prov>),
+  Limit(DynamicLimitBatches<'a, Prov>),
+  Distinct(DynamicDistinctBatches<'a, Prov>),
   OverwriteOne(DynamicOverwriteOneBatches<'a, Prov>),
   Binary(DynamicBatchesBinary<'a, Prov>),
   ForeignPredicateConstraint(ForeignPredicateConstraintBatches<'a, Prov>),
@@ -45,6 +49,13 @@ impl<'a, Prov: Provenance> DynamicBatches<'a, Prov> {
     })
   }
 
+  pub fn rename(source: DynamicBatches<'a, Prov>, permutation: Vec<usize>) -> Self {
+    Self::Rename(DynamicRenameBatches {
+      source: Box::new(source),
+      permutation,
+    })
+  }
+
   pub fn filter(runtime: &'a RuntimeEnvironment, source: DynamicBatches<'a, Prov>, filter: Expr) -> Self {
     Self::Filter(DynamicFilterBatches {
       runtime,
@@ -76,8 +87,12 @@ impl<'a, Prov: Provenance> Iterator for DynamicBatches<'a, Prov> {
       Self::Chain(c) => c.next(),
       Self::DynamicRelationStable(drs) => drs.next(),
       Self::Project(m) => m.next(),
+      Self::Rename(r) => r.next(),
       Self::Filter(f) => f.next(),
       Self::Find(f) => f.next(),
+      Self::Sort(s) => s.next(),
+      Self::Limit(l) => l.next(),
+      Self::Distinct(d) => d.next(),
       Self::OverwriteOne(o) => o.next(),
       Self::Binary(b) => b.next(),
       Self::ForeignPredicateConstraint(b) => b.next(),
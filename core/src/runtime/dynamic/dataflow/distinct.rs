@@ -0,0 +1,62 @@
+use super::*;
+
+/// A dataflow which merges the duplicated tuples within each of the batches of its source.
+///
+/// The tags of the duplicated tuples are combined using `Prov::add`, and the resulting batches are
+/// in tuple order. Duplicates spanning different batches are not merged.
+pub struct DynamicDistinctDataflow<'a, Prov: Provenance> {
+  pub source: Box<DynamicDataflow<'a, Prov>>,
+  pub ctx: &'a Prov,
+}
+
+impl<'a, Prov: Provenance> Clone for DynamicDistinctDataflow<'a, Prov> {
+  fn clone(&self) -> Self {
+    Self {
+      source: self.source.clone(),
+      ctx: self.ctx,
+    }
+  }
+}
+
+impl<'a, Prov: Provenance> DynamicDistinctDataflow<'a, Prov> {
+  pub fn iter_stable(&self, runtime: &'a RuntimeEnvironment) -> DynamicBatches<'a, Prov> {
+    DynamicBatches::Distinct(DynamicDistinctBatches {
+      source: Box::new(self.source.iter_stable(runtime)),
+      ctx: self.ctx,
+    })
+  }
+
+  pub fn iter_recent(&self, runtime: &'a RuntimeEnvironment) -> DynamicBatches<'a, Prov> {
+    DynamicBatches::Distinct(DynamicDistinctBatches {
+      source: Box::new(self.source.iter_recent(runtime)),
+      ctx: self.ctx,
+    })
+  }
+}
+
+#[derive(Clone)]
+pub struct DynamicDistinctBatches<'a, Prov: Provenance> {
+  pub source: Box<DynamicBatches<'a, Prov>>,
+  pub ctx: &'a Prov,
+}
+
+impl<'a, Prov: Provenance> Iterator for DynamicDistinctBatches<'a, Prov> {
+  type Item = DynamicBatch<'a, Prov>;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    self.source.next().map(|batch| {
+      let mut elems = batch.collect::<Vec<_>>();
+      elems.sort_by(|e1, e2| e1.tuple.cmp(&e2.tuple));
+
+      // Merge the adjacent elements sharing the same tuple
+      let mut result: Vec<DynamicElement<Prov>> = Vec::with_capacity(elems.len());
+      for elem in elems {
+        match result.last_mut() {
+          Some(last) if last.tuple == elem.tuple => last.tag = self.ctx.add(&last.tag, &elem.tag),
+          _ => result.push(elem),
+        }
+      }
+      DynamicBatch::source_vec(result)
+    })
+  }
+}
@@ -17,8 +17,12 @@ pub enum DynamicDataflow<'a, Prov: Provenance> {
   DynamicRelation(DynamicRelationDataflow<'a, Prov>),
   OverwriteOne(DynamicOverwriteOneDataflow<'a, Prov>),
   Project(DynamicProjectDataflow<'a, Prov>),
+  Rename(DynamicRenameDataflow<'a, Prov>),
   Filter(DynamicFilterDataflow<'a, Prov>),
   Find(DynamicFindDataflow<'a, Prov>),
+  Sort(DynamicSortDataflow<'a, Prov>),
+  Limit(DynamicLimitDataflow<'a, Prov>),
+  Distinct(DynamicDistinctDataflow<'a, Prov>),
   Intersect(DynamicIntersectDataflow<'a, Prov>),
   Join(DynamicJoinDataflow<'a, Prov>),
   Product(DynamicProductDataflow<'a, Prov>),
@@ -82,6 +86,13 @@ impl<'a, Prov: Provenance> DynamicDataflow<'a, Prov> {
     })
   }
 
+  pub fn rename(self, permutation: Vec<usize>) -> Self {
+    Self::Rename(DynamicRenameDataflow {
+      source: Box::new(self),
+      permutation,
+    })
+  }
+
   pub fn filter(self, filter: Expr) -> Self {
     Self::Filter(DynamicFilterDataflow {
       source: Box::new(self),
@@ -96,6 +107,27 @@ impl<'a, Prov: Provenance> DynamicDataflow<'a, Prov> {
     })
   }
 
+  pub fn sort(self, order: Vec<(usize, bool)>) -> Self {
+    Self::Sort(DynamicSortDataflow {
+      source: Box::new(self),
+      order,
+    })
+  }
+
+  pub fn limit(self, n: usize) -> Self {
+    Self::Limit(DynamicLimitDataflow {
+      source: Box::new(self),
+      n,
+    })
+  }
+
+  pub fn distinct(self, ctx: &'a Prov) -> Self {
+    Self::Distinct(DynamicDistinctDataflow {
+      source: Box::new(self),
+      ctx,
+    })
+  }
+
   pub fn intersect(self, d2: Self, ctx: &'a Prov) -> Self {
     Self::Intersect(DynamicIntersectDataflow {
       d1: Box::new(self),
@@ -204,8 +236,12 @@ impl<'a, Prov: Provenance> DynamicDataflow<'a, Prov> {
 
       // Unary operations
       Self::Project(p) => p.iter_stable(runtime),
+      Self::Rename(r) => r.iter_stable(runtime),
       Self::Filter(f) => f.iter_stable(runtime),
       Self::Find(f) => f.iter_stable(runtime),
+      Self::Sort(s) => s.iter_stable(runtime),
+      Self::Limit(l) => l.iter_stable(runtime),
+      Self::Distinct(d) => d.iter_stable(runtime),
 
       // Binary operations
       Self::Union(u) => u.iter_stable(runtime),
@@ -244,8 +280,12 @@ impl<'a, Prov: Provenance> DynamicDataflow<'a, Prov> {
 
       // Unary operations
       Self::Project(p) => p.iter_recent(runtime),
+      Self::Rename(r) => r.iter_recent(runtime),
       Self::Filter(f) => f.iter_recent(runtime),
       Self::Find(f) => f.iter_recent(runtime),
+      Self::Sort(s) => s.iter_recent(runtime),
+      Self::Limit(l) => l.iter_recent(runtime),
+      Self::Distinct(d) => d.iter_recent(runtime),
 
       // Binary operations
       Self::Union(u) => u.iter_recent(runtime),
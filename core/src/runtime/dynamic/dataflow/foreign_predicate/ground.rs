@@ -30,9 +30,12 @@ impl<'a, Prov: Provenance> ForeignPredicateGroundDataflow<'a, Prov> {
       .get(&self.foreign_predicate)
       .expect("Foreign predicate not found");
 
-    // Evaluate the foreign predicate
-    let elements = foreign_predicate
-      .evaluate(&self.bounded_constants)
+    // Evaluate the foreign predicate, sorting the groundings if the execution needs to be deterministic
+    let mut groundings = foreign_predicate.evaluate(&self.bounded_constants);
+    if runtime.deterministic {
+      groundings.sort_by(|(_, v1), (_, v2)| v1.cmp(v2));
+    }
+    let elements = groundings
       .into_iter()
       .map(|(input_tag, values)| {
         let input_tag = StaticInputTag::from_dynamic_input_tag(&input_tag);
@@ -37,6 +37,7 @@ impl<'a, Prov: Provenance> ForeignPredicateJoinDataflow<'a, Prov> {
       batches: Box::new(self.left.iter_stable(runtime)),
       foreign_predicate: runtime.predicate_registry.get(&self.foreign_predicate).expect("Foreign predicate not found").clone(),
       args: self.args.clone(),
+      deterministic: runtime.deterministic,
       ctx: self.ctx,
     })
   }
@@ -46,6 +47,7 @@ impl<'a, Prov: Provenance> ForeignPredicateJoinDataflow<'a, Prov> {
       batches: Box::new(self.left.iter_recent(runtime)),
       foreign_predicate: runtime.predicate_registry.get(&self.foreign_predicate).expect("Foreign predicate not found").clone(),
       args: self.args.clone(),
+      deterministic: runtime.deterministic,
       ctx: self.ctx,
     })
   }
@@ -56,6 +58,7 @@ pub struct ForeignPredicateJoinBatches<'a, Prov: Provenance> {
   pub batches: Box<DynamicBatches<'a, Prov>>,
   pub foreign_predicate: DynamicForeignPredicate,
   pub args: Vec<Expr>,
+  pub deterministic: bool,
   pub ctx: &'a Prov,
 }
 
@@ -64,18 +67,15 @@ impl<'a, Prov: Provenance> Iterator for ForeignPredicateJoinBatches<'a, Prov> {
 
   fn next(&mut self) -> Option<Self::Item> {
     // First, try to get a batch from the set of batches
-    self.batches.next().map(|mut batch| {
-      // Then, try to get the first element inside of this batch;
-      // if there is an element, we need to evaluate the foreign predicate and produce a current output batch
-      let first_output_batch = batch.next().map(|elem| {
-        eval_foreign_predicate(elem, &self.foreign_predicate, &self.args, self.ctx)
-      });
+    self.batches.next().map(|batch| {
+      // Then, evaluate the foreign predicate on all the elements inside of this batch at once
+      let elems = batch.collect();
+      let mut outputs = eval_foreign_predicate_batch(elems, &self.foreign_predicate, &self.args, self.deterministic, self.ctx).into_iter();
+      let first_output_batch = outputs.next();
 
       // Generate a new batch
       DynamicBatch::ForeignPredicateJoin(ForeignPredicateJoinBatch {
-        batch: Box::new(batch),
-        foreign_predicate: self.foreign_predicate.clone(),
-        args: self.args.clone(),
+        outputs,
         current_output_batch: first_output_batch,
         ctx: self.ctx,
       })
@@ -85,9 +85,7 @@ impl<'a, Prov: Provenance> Iterator for ForeignPredicateJoinBatches<'a, Prov> {
 
 #[derive(Clone)]
 pub struct ForeignPredicateJoinBatch<'a, Prov: Provenance> {
-  pub batch: Box<DynamicBatch<'a, Prov>>,
-  pub foreign_predicate: DynamicForeignPredicate,
-  pub args: Vec<Expr>,
+  pub outputs: std::vec::IntoIter<(DynamicElement<Prov>, std::vec::IntoIter<DynamicElement<Prov>>)>,
   pub current_output_batch: Option<(DynamicElement<Prov>, std::vec::IntoIter<DynamicElement<Prov>>)>,
   pub ctx: &'a Prov,
 }
@@ -102,44 +100,53 @@ impl<'a, Prov: Provenance> Iterator for ForeignPredicateJoinBatch<'a, Prov> {
         let new_tag = self.ctx.mult(&left_elem.tag, &right_elem.tag);
         return Some(DynamicElement::new(tuple, new_tag))
       } else {
-        self.current_output_batch = self.batch.next().map(|elem| {
-          eval_foreign_predicate(elem, &self.foreign_predicate, &self.args, self.ctx)
-        });
+        self.current_output_batch = self.outputs.next();
       }
     }
     None
   }
 }
 
-/// Evaluate the foreign predicate on the given element
-fn eval_foreign_predicate<Prov: Provenance>(
-  elem: DynamicElement<Prov>,
+/// Evaluate the foreign predicate on all the given elements, pairing each element with its outputs;
+/// the outputs of each element are sorted when `deterministic` is set
+fn eval_foreign_predicate_batch<Prov: Provenance>(
+  elems: Vec<DynamicElement<Prov>>,
   fp: &DynamicForeignPredicate,
   args: &Vec<Expr>,
+  deterministic: bool,
   ctx: &Prov,
-) -> (DynamicElement<Prov>, std::vec::IntoIter<DynamicElement<Prov>>) {
+) -> Vec<(DynamicElement<Prov>, std::vec::IntoIter<DynamicElement<Prov>>)> {
   // First get the arguments to pass to the foreign predicate
-  let args_to_fp: Vec<Value> = args.iter().map(|arg| {
-    match arg {
-      Expr::Access(a) => elem.tuple[a].as_value(),
-      Expr::Constant(c) => c.clone(),
-      _ => panic!("Foreign predicate join only supports constant and access arguments"),
-    }
+  let args_to_fp: Vec<Vec<Value>> = elems.iter().map(|elem| {
+    args.iter().map(|arg| {
+      match arg {
+        Expr::Access(a) => elem.tuple[a].as_value(),
+        Expr::Constant(c) => c.clone(),
+        _ => panic!("Foreign predicate join only supports constant and access arguments"),
+      }
+    }).collect()
   }).collect();
 
   // Then evaluate the foreign predicate on these arguments
-  let outputs: Vec<_> = fp.evaluate(&args_to_fp).into_iter().map(|(tag, values)| {
-    // Make sure to tag the output elements
-    let input_tag = Prov::InputTag::from_dynamic_input_tag(&tag);
-    let new_tag = ctx.tagging_optional_fn(input_tag);
+  let outputs = fp.evaluate_batch(&args_to_fp);
+  assert_eq!(outputs.len(), elems.len(), "Foreign predicate should produce outputs for every input");
 
-    // Generate a tuple from the values produced by the foreign predicate
-    let tuple = Tuple::from(values);
-
-    // Generate the output element
-    DynamicElement::new(tuple, new_tag)
-  }).collect();
-
-  // Return the input element and output elements pair
-  (elem, outputs.into_iter())
+  // Return the input element and output elements pairs
+  elems.into_iter().zip(outputs).map(|(elem, mut outputs)| {
+    if deterministic {
+      outputs.sort_by(|(_, v1), (_, v2)| v1.cmp(v2));
+    }
+    let outputs: Vec<_> = outputs.into_iter().map(|(tag, values)| {
+      // Make sure to tag the output elements
+      let input_tag = Prov::InputTag::from_dynamic_input_tag(&tag);
+      let new_tag = ctx.tagging_optional_fn(input_tag);
+
+      // Generate a tuple from the values produced by the foreign predicate
+      let tuple = Tuple::from(values);
+
+      // Generate the output element
+      DynamicElement::new(tuple, new_tag)
+    }).collect();
+    (elem, outputs.into_iter())
+  }).collect()
 }
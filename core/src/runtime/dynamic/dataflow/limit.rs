@@ -0,0 +1,45 @@
+use super::*;
+
+/// A dataflow which only keeps the first `n` elements of each of the batches of its source.
+///
+/// The elements are taken by their order within the batch, so this dataflow is typically placed on top
+/// of a sort. The tags play no part in which elements are kept: under a probabilistic provenance, the
+/// result is not the `n` most likely tuples, and the tags of the kept elements are left untouched.
+#[derive(Clone)]
+pub struct DynamicLimitDataflow<'a, Prov: Provenance> {
+  pub source: Box<DynamicDataflow<'a, Prov>>,
+  pub n: usize,
+}
+
+impl<'a, Prov: Provenance> DynamicLimitDataflow<'a, Prov> {
+  pub fn iter_stable(&self, runtime: &'a RuntimeEnvironment) -> DynamicBatches<'a, Prov> {
+    DynamicBatches::Limit(DynamicLimitBatches {
+      source: Box::new(self.source.iter_stable(runtime)),
+      n: self.n,
+    })
+  }
+
+  pub fn iter_recent(&self, runtime: &'a RuntimeEnvironment) -> DynamicBatches<'a, Prov> {
+    DynamicBatches::Limit(DynamicLimitBatches {
+      source: Box::new(self.source.iter_recent(runtime)),
+      n: self.n,
+    })
+  }
+}
+
+#[derive(Clone)]
+pub struct DynamicLimitBatches<'a, Prov: Provenance> {
+  pub source: Box<DynamicBatches<'a, Prov>>,
+  pub n: usize,
+}
+
+impl<'a, Prov: Provenance> Iterator for DynamicLimitBatches<'a, Prov> {
+  type Item = DynamicBatch<'a, Prov>;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    self
+      .source
+      .next()
+      .map(|batch| DynamicBatch::source_vec(batch.take(self.n).collect()))
+  }
+}
@@ -4,6 +4,7 @@ mod utils;
 
 mod antijoin;
 mod difference;
+mod distinct;
 mod dynamic_collection;
 mod dynamic_exclusion;
 mod dynamic_dataflow;
@@ -13,9 +14,12 @@ mod find;
 mod foreign_predicate;
 mod intersect;
 mod join;
+mod limit;
 mod overwrite_one;
 mod product;
 mod project;
+mod rename;
+mod sort;
 mod static_relation;
 mod union;
 mod unit;
@@ -33,6 +37,7 @@ use batching::*;
 // Dataflows
 use antijoin::*;
 use difference::*;
+use distinct::*;
 use dynamic_collection::*;
 pub use dynamic_dataflow::*;
 use dynamic_exclusion::*;
@@ -42,9 +47,12 @@ use find::*;
 use foreign_predicate::*;
 use intersect::*;
 use join::*;
+use limit::*;
 use overwrite_one::*;
 use product::*;
 use project::*;
+use rename::*;
+use sort::*;
 use union::*;
 use unit::*;
 use untagged_vec::*;
@@ -1,5 +1,5 @@
 use super::*;
-use crate::common::expr::Expr;
+use crate::common::expr::{CallExpr, Expr};
 
 #[derive(Clone)]
 pub struct DynamicProjectDataflow<'a, Prov: Provenance> {
@@ -29,16 +29,45 @@ impl<'a, Prov: Provenance> Iterator for DynamicProjectBatches<'a, Prov> {
 
   fn next(&mut self) -> Option<Self::Item> {
     match self.source.next() {
-      Some(next_batch) => Some(DynamicBatch::Project(DynamicProjectBatch {
-        runtime: self.runtime,
-        source: Box::new(next_batch),
-        expression: self.expression.clone(),
-      })),
+      Some(next_batch) => {
+        if let Some(call) = self.expression.single_call() {
+          // The foreign function is executed on the whole batch at once
+          let elems = batched_project(self.runtime, next_batch, &self.expression, call);
+          Some(DynamicBatch::source_vec(elems))
+        } else {
+          Some(DynamicBatch::Project(DynamicProjectBatch {
+            runtime: self.runtime,
+            source: Box::new(next_batch),
+            expression: self.expression.clone(),
+          }))
+        }
+      }
       None => None,
     }
   }
 }
 
+/// Project a batch with an expression containing a single function call, which is evaluated on all the
+/// elements of the batch through one `eval_call_batch`
+fn batched_project<'a, Prov: Provenance>(
+  runtime: &'a RuntimeEnvironment,
+  batch: DynamicBatch<'a, Prov>,
+  expression: &Expr,
+  call: &CallExpr,
+) -> Vec<DynamicElement<Prov>> {
+  let elems = batch.collect::<Vec<_>>();
+  let results = runtime.eval_call_batch(call, elems.iter().map(|e| &e.tuple));
+  elems
+    .into_iter()
+    .zip(results)
+    .filter_map(|(elem, result)| {
+      let expr = expression.substitute_call(&result?);
+      let tup = runtime.eval(&expr, &elem.tuple)?;
+      Some(DynamicElement::new(tup, elem.tag))
+    })
+    .collect()
+}
+
 #[derive(Clone)]
 pub struct DynamicProjectBatch<'a, Prov: Provenance> {
   pub runtime: &'a RuntimeEnvironment,
@@ -0,0 +1,58 @@
+use super::*;
+use crate::common::tuple::Tuple;
+
+/// A dataflow which permutes the columns of the tuples of its source, so that the output column `i`
+/// is the input column `permutation[i]`.
+///
+/// This is a cheap index shuffle on the top-level tuple; unlike a projection, no expression is evaluated.
+#[derive(Clone)]
+pub struct DynamicRenameDataflow<'a, Prov: Provenance> {
+  pub source: Box<DynamicDataflow<'a, Prov>>,
+  pub permutation: Vec<usize>,
+}
+
+impl<'a, Prov: Provenance> DynamicRenameDataflow<'a, Prov> {
+  pub fn iter_stable(&self, runtime: &'a RuntimeEnvironment) -> DynamicBatches<'a, Prov> {
+    DynamicBatches::rename(self.source.iter_stable(runtime), self.permutation.clone())
+  }
+
+  pub fn iter_recent(&self, runtime: &'a RuntimeEnvironment) -> DynamicBatches<'a, Prov> {
+    DynamicBatches::rename(self.source.iter_recent(runtime), self.permutation.clone())
+  }
+}
+
+#[derive(Clone)]
+pub struct DynamicRenameBatches<'a, Prov: Provenance> {
+  pub source: Box<DynamicBatches<'a, Prov>>,
+  pub permutation: Vec<usize>,
+}
+
+impl<'a, Prov: Provenance> Iterator for DynamicRenameBatches<'a, Prov> {
+  type Item = DynamicBatch<'a, Prov>;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    self.source.next().map(|next_batch| {
+      DynamicBatch::Rename(DynamicRenameBatch {
+        source: Box::new(next_batch),
+        permutation: self.permutation.clone(),
+      })
+    })
+  }
+}
+
+#[derive(Clone)]
+pub struct DynamicRenameBatch<'a, Prov: Provenance> {
+  pub source: Box<DynamicBatch<'a, Prov>>,
+  pub permutation: Vec<usize>,
+}
+
+impl<'a, Prov: Provenance> Iterator for DynamicRenameBatch<'a, Prov> {
+  type Item = DynamicElement<Prov>;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    self.source.next().map(|elem| {
+      let tuple = Tuple::Tuple(self.permutation.iter().map(|i| elem.tuple[*i].clone()).collect());
+      DynamicElement::new(tuple, elem.tag)
+    })
+  }
+}
@@ -0,0 +1,66 @@
+use std::cmp::Ordering;
+
+use super::*;
+
+/// A dataflow which sorts each of the batches of its source by the given `(column, ascending)` pairs.
+///
+/// Elements agreeing on all the given columns are ordered by their whole tuple in ascending order.
+/// Note that the resulting batches are no longer in tuple order, so this dataflow should only be used
+/// where the order of the elements is observed, e.g. when materializing a relation for output.
+#[derive(Clone)]
+pub struct DynamicSortDataflow<'a, Prov: Provenance> {
+  pub source: Box<DynamicDataflow<'a, Prov>>,
+  pub order: Vec<(usize, bool)>,
+}
+
+impl<'a, Prov: Provenance> DynamicSortDataflow<'a, Prov> {
+  pub fn iter_stable(&self, runtime: &'a RuntimeEnvironment) -> DynamicBatches<'a, Prov> {
+    DynamicBatches::Sort(DynamicSortBatches {
+      source: Box::new(self.source.iter_stable(runtime)),
+      order: self.order.clone(),
+    })
+  }
+
+  pub fn iter_recent(&self, runtime: &'a RuntimeEnvironment) -> DynamicBatches<'a, Prov> {
+    DynamicBatches::Sort(DynamicSortBatches {
+      source: Box::new(self.source.iter_recent(runtime)),
+      order: self.order.clone(),
+    })
+  }
+}
+
+#[derive(Clone)]
+pub struct DynamicSortBatches<'a, Prov: Provenance> {
+  pub source: Box<DynamicBatches<'a, Prov>>,
+  pub order: Vec<(usize, bool)>,
+}
+
+impl<'a, Prov: Provenance> DynamicSortBatches<'a, Prov> {
+  fn compare(&self, e1: &DynamicElement<Prov>, e2: &DynamicElement<Prov>) -> Ordering {
+    self
+      .order
+      .iter()
+      .map(|(i, asc)| {
+        let ord = e1.tuple[*i].cmp(&e2.tuple[*i]);
+        if *asc {
+          ord
+        } else {
+          ord.reverse()
+        }
+      })
+      .find(|ord| ord.is_ne())
+      .unwrap_or_else(|| e1.tuple.cmp(&e2.tuple))
+  }
+}
+
+impl<'a, Prov: Provenance> Iterator for DynamicSortBatches<'a, Prov> {
+  type Item = DynamicBatch<'a, Prov>;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    self.source.next().map(|batch| {
+      let mut elems = batch.collect::<Vec<_>>();
+      elems.sort_by(|e1, e2| self.compare(e1, e2));
+      DynamicBatch::source_vec(elems)
+    })
+  }
+}
@@ -1,7 +1,11 @@
 use std::collections::*;
 
 use super::*;
+use crate::common::binary_op::*;
+use crate::common::expr::*;
 use crate::common::tuple::*;
+use crate::common::tuple_type::*;
+use crate::common::value::*;
 use crate::compiler::ram;
 use crate::runtime::database::extensional::*;
 use crate::runtime::database::intentional::*;
@@ -31,8 +35,8 @@ impl Default for ExecutionOptions {
 
 pub struct DynamicExecutionContext<Prov: Provenance, Ptr: PointerFamily = RcFamily> {
   pub options: ExecutionOptions,
-  pub program: ram::Program,
-  pub edb: ExtensionalDatabase<Prov>,
+  pub program: Ptr::Rc<ram::Program>,
+  pub edb: ExtensionalDatabase<Prov, Ptr>,
   pub idb: IntentionalDatabase<Prov, Ptr>,
 }
 
@@ -40,7 +44,7 @@ impl<Prov: Provenance, Ptr: PointerFamily> Clone for DynamicExecutionContext<Pro
   fn clone(&self) -> Self {
     Self {
       options: self.options.clone(),
-      program: self.program.clone(),
+      program: Ptr::clone_rc(&self.program),
       edb: self.edb.clone(),
       idb: self.idb.clone(),
     }
@@ -56,7 +60,7 @@ impl<Prov: Provenance, Ptr: PointerFamily> DynamicExecutionContext<Prov, Ptr> {
   pub fn new_with_options(options: ExecutionOptions) -> Self {
     let edb = ExtensionalDatabase::new_with_options(options.type_check.clone());
     let idb = IntentionalDatabase::default();
-    let program = ram::Program::new();
+    let program = Ptr::new_rc(ram::Program::new());
     Self {
       options,
       program,
@@ -71,7 +75,7 @@ impl<Prov: Provenance, Ptr: PointerFamily> DynamicExecutionContext<Prov, Ptr> {
     let idb = IntentionalDatabase::default();
     Self {
       options,
-      program,
+      program: Ptr::new_rc(program),
       edb,
       idb,
     }
@@ -83,7 +87,7 @@ impl<Prov: Provenance, Ptr: PointerFamily> DynamicExecutionContext<Prov, Ptr> {
   {
     DynamicExecutionContext {
       options: self.options.clone(),
-      program: self.program.clone(),
+      program: Ptr::clone_rc(&self.program),
       edb: self.edb.clone_with_new_provenance::<Prov2>(),
       idb: self.idb.clone_with_new_provenance::<Prov2>(),
     }
@@ -95,7 +99,7 @@ impl<Prov: Provenance, Ptr: PointerFamily> DynamicExecutionContext<Prov, Ptr> {
 
   /// Directly execute the program stored in the file
   pub fn execute(&mut self, runtime: &RuntimeEnvironment, ctx: &mut Prov) -> Result<(), RuntimeError> {
-    self.incremental_execute_helper(None, runtime, ctx)
+    self.incremental_execute_helper(None, runtime, ctx, false).map(|_| ())
   }
 
   pub fn incremental_execute(
@@ -104,55 +108,55 @@ impl<Prov: Provenance, Ptr: PointerFamily> DynamicExecutionContext<Prov, Ptr> {
     runtime: &RuntimeEnvironment,
     ctx: &mut Prov,
   ) -> Result<(), RuntimeError> {
-    self.incremental_execute_helper(Some(program), runtime, ctx)
+    self
+      .incremental_execute_helper(Some(Ptr::new_rc(program)), runtime, ctx, false)
+      .map(|_| ())
   }
 
-  fn incremental_execute_helper(
+  /// Execute a program that is shared with other execution contexts, without cloning it
+  pub fn incremental_execute_shared(
     &mut self,
-    maybe_new_program: Option<ram::Program>,
+    program: Ptr::Rc<ram::Program>,
     runtime: &RuntimeEnvironment,
     ctx: &mut Prov,
   ) -> Result<(), RuntimeError> {
-    // Pull the IDB
-    let mut incremental_result = IntentionalDatabase::default();
-    std::mem::swap(&mut self.idb, &mut incremental_result);
-
-    // Persistent relations
-    let mut temp_program = ram::Program::new();
-    std::mem::swap(&mut self.program, &mut temp_program);
-    let program_ref = if let Some(new_program) = &maybe_new_program {
-      // Process the EDB; populate using program facts
-      self.edb.populate_program_facts(new_program)?;
-
-      // If need to incrementalize, remove such computed results
-      let edb_need_update_relations = self.edb.need_update_relations();
-
-      // If a new program is provided, compute the persistent relations that we can re-use
-      let pers = if edb_need_update_relations.is_empty() {
-        self.program.persistent_relations(new_program)
-      } else {
-        self
-          .program
-          .persistent_relations_with_need_update_relations(new_program, &edb_need_update_relations)
-      };
+    self
+      .incremental_execute_helper(Some(program), runtime, ctx, false)
+      .map(|_| ())
+  }
 
-      // Keep the relations only in the set of persistent relations
-      incremental_result.retain_relations(&pers);
+  /// Execute a program that is shared with other execution contexts, and get the facts of each relation computed
+  /// by this execution that were not computed by the previous one
+  ///
+  /// The relations that are maintained (see `ExecutionOptions::incremental_maintain`) only derive the facts
+  /// following from the new facts of their inputs, which are then their new facts. The relations that are
+  /// recomputed are compared with their facts from the previous execution instead.
+  pub fn incremental_execute_shared_with_new_facts(
+    &mut self,
+    program: Ptr::Rc<ram::Program>,
+    runtime: &RuntimeEnvironment,
+    ctx: &mut Prov,
+  ) -> Result<HashMap<String, DynamicCollection<Prov>>, RuntimeError> {
+    self.incremental_execute_helper(Some(program), runtime, ctx, true)
+  }
 
-      // Return this new program
-      &new_program
-    } else {
-      self
-        .edb
-        .populate_program_facts(&temp_program)
-        .expect("Since there is no new program, no error should be raised during program facts population");
+  fn incremental_execute_helper(
+    &mut self,
+    maybe_new_program: Option<Ptr::Rc<ram::Program>>,
+    runtime: &RuntimeEnvironment,
+    ctx: &mut Prov,
+    need_new_facts: bool,
+  ) -> Result<HashMap<String, DynamicCollection<Prov>>, RuntimeError> {
+    // If there is no new program, we directly take our current program
+    let program = maybe_new_program.unwrap_or_else(|| Ptr::clone_rc(&self.program));
+    let program_ref = Ptr::get_rc(&program);
 
-      // If there is no new program, we directly take our current program
-      &temp_program
-    };
+    // Pull the IDB, keeping the relations that can be reused
+    let (mut incremental_result, mut maintenance) = self.prepare_execution(program_ref, ctx, need_new_facts)?;
 
     // Internalize EDB relations
     self.edb.internalize(ctx);
+    maintenance.add_edb_deltas(&self.edb);
 
     // Generate stratum information
     let strata_info = stratum_inputs_outputs(program_ref);
@@ -167,12 +171,22 @@ impl<Prov: Provenance, Ptr: PointerFamily> DynamicExecutionContext<Prov, Ptr> {
     // Go through each stratum
     for (i, stratum) in program_ref.strata.iter().enumerate() {
       // Run the stratum to get the result
-      let result = self.execute_stratum(i, stratum, &incremental_result, program_ref, &strata_info, runtime, ctx)?;
+      let result = self.execute_stratum(
+        i,
+        stratum,
+        &incremental_result,
+        &mut maintenance,
+        program_ref,
+        &strata_info,
+        runtime,
+        ctx,
+      )?;
 
       // Extend the incremental result with the relations within their lifespan
+      let keep_hidden = self.options.incremental_maintain;
       incremental_result.extend(result.into_iter().filter(|(name, _)| {
-        // Check if the relation is a hidden one
-        if program_ref.relation_unchecked(&name).output.is_hidden() {
+        // Check if the relation is a hidden one; they are all kept if they are to be maintained
+        if program_ref.relation_unchecked(&name).output.is_hidden() && !keep_hidden {
           // If it is hidden, additionally check if it is going to be used later
           if let Some(last_stratum) = strata_info.relation_lifespan.get(name) {
             // If the last stratum that this relation is used is later than current, then keep; otherwise drop
@@ -185,58 +199,116 @@ impl<Prov: Provenance, Ptr: PointerFamily> DynamicExecutionContext<Prov, Ptr> {
 
     // Store the result
     self.idb = incremental_result;
+    self.share_edb_relations_into_idb(program_ref);
 
-    // See if any EDB relations need to directly go into IDB
-    for relation in program_ref.relations() {
-      if relation.output.is_not_hidden() && !self.idb.has_relation(&relation.predicate) {
-        if self.options.incremental_maintain {
-          if let Some(edb_collection) = self.edb.get_dynamic_collection(&relation.predicate) {
-            self
-              .idb
-              .insert_dynamic_collection(relation.predicate.clone(), edb_collection.clone());
-          }
-        } else {
-          if let Some(edb_collection) = self.edb.pop_dynamic_collection(&relation.predicate) {
-            self
-              .idb
-              .insert_dynamic_collection(relation.predicate.clone(), edb_collection.clone());
-          }
+    // Update the program
+    self.program = program;
+
+    // Success!
+    Ok(maintenance.new_facts(&self.idb))
+  }
+
+  /// Prepare the execution of a program: populate the program facts, and pull the relations of the IDB that can
+  /// be reused from the previous execution, along with the state to maintain the other ones
+  fn prepare_execution(
+    &mut self,
+    program: &ram::Program,
+    ctx: &Prov,
+    need_new_facts: bool,
+  ) -> Result<(IntentionalDatabase<Prov, Ptr>, Maintenance<Prov, Ptr>), RuntimeError> {
+    // Pull the IDB
+    let mut incremental_result = IntentionalDatabase::default();
+    std::mem::swap(&mut self.idb, &mut incremental_result);
+
+    // Process the EDB; populate using program facts
+    self.edb.populate_program_facts(program)?;
+
+    // If need to incrementalize, remove such computed results
+    let edb_need_update_relations = self.edb.need_update_relations();
+
+    // Compute the persistent relations that we can re-use
+    let same_rules = self.program.persistent_relations(program);
+    let pers = if edb_need_update_relations.is_empty() {
+      same_rules.clone()
+    } else {
+      self
+        .program
+        .persistent_relations_with_need_update_relations(program, &edb_need_update_relations)
+    };
+
+    // The other relations are maintained rather than recomputed when deriving a fact once more does not change
+    // its tag; the internal facts need to be retained for them to be updated
+    let can_maintain =
+      self.options.incremental_maintain && self.options.retain_internal_when_recover && ctx.is_idempotent();
+    let mut maintenance = Maintenance::new(can_maintain, need_new_facts);
+    if can_maintain || need_new_facts {
+      maintenance.same_rules = same_rules;
+      for (name, relation) in &incremental_result {
+        if !pers.contains(name) {
+          maintenance
+            .stale
+            .insert(name.clone(), Ptr::clone_rc(&relation.internal_facts));
         }
       }
     }
 
-    // Update the program
-    if let Some(new_program) = maybe_new_program {
-      self.program = new_program;
-    } else {
-      self.program = temp_program;
-    }
+    // Keep the relations only in the set of persistent relations
+    incremental_result.retain_relations(&pers);
+    Ok((incremental_result, maintenance))
+  }
 
-    // Success!
-    Ok(())
+  /// Put the EDB relations that are not computed into the IDB; the facts are shared with the EDB, which keeps them
+  /// for the later executions
+  fn share_edb_relations_into_idb(&mut self, program: &ram::Program) {
+    for relation in program.relations() {
+      if relation.output.is_not_hidden() && !self.idb.has_relation(&relation.predicate) {
+        if let Some(edb_relation) = self.edb.extensional_relations.get(&relation.predicate) {
+          self
+            .idb
+            .insert_shared_dynamic_collection(relation.predicate.clone(), Ptr::clone_rc(&edb_relation.internal));
+        }
+      }
+    }
   }
 
   fn execute_stratum(
-    &mut self,
+    &self,
     stratum_id: usize,
     stratum: &ram::Stratum,
     current_idb: &IntentionalDatabase<Prov, Ptr>,
+    maintenance: &mut Maintenance<Prov, Ptr>,
     ram_program: &ram::Program,
     strata_info: &StrataInformation,
     runtime: &RuntimeEnvironment,
-    ctx: &mut Prov,
+    ctx: &Prov,
   ) -> Result<IntentionalDatabase<Prov, Ptr>, RuntimeError> {
-    let dyn_relas = stratum
-      .relations
-      .iter()
-      .filter(|(r, _)| !current_idb.has_relation(*r))
-      .map(|(r, _)| r.clone())
-      .collect::<HashSet<_>>();
-
-    // Check if we need to compute anything new
-    if dyn_relas.is_empty() {
+    // Check if we need to compute anything new; the relations of a stratum are computed together, so the
+    // stratum is only reused if all of its relations are kept
+    let is_kept = stratum.relations.keys().all(|r| current_idb.has_relation(r));
+    if is_kept {
       return Ok(IntentionalDatabase::new());
     }
+    let dyn_relas = stratum.relations.keys().cloned().collect::<HashSet<_>>();
+
+    // Check if the relations computed by the previous execution are to be maintained; their facts are then
+    // taken as the seeds of the relations
+    let mut seeds = maintenance.take_seeds(stratum, &dyn_relas);
+
+    // If facts are deleted, the seeds are first over-deleted, and the remaining facts are then completed
+    let mut remaining = None;
+    if seeds.is_some() && maintenance.has_deletions(stratum) {
+      let seeds = seeds.take().unwrap();
+      remaining = Some(self.over_delete(
+        stratum,
+        &dyn_relas,
+        seeds,
+        current_idb,
+        maintenance,
+        ram_program,
+        runtime,
+        ctx,
+      )?);
+    }
 
     // Otherwise, do computation
     let mut iter = DynamicIteration::<Prov>::new();
@@ -249,7 +321,14 @@ impl<Prov: Provenance, Ptr: PointerFamily> DynamicExecutionContext<Prov, Ptr> {
         }
       }
       for (rel, col) in current_idb {
-        iter.add_input_dynamic_collection(&rel, &col.internal_facts);
+        iter.add_input_dynamic_collection(&rel, Ptr::get_rc(&col.internal_facts));
+      }
+
+      // The seeded relations only need the new facts of their inputs
+      if seeds.is_some() {
+        for (rel, delta) in &maintenance.deltas {
+          iter.add_input_dynamic_delta(rel, Ptr::get_rc(delta));
+        }
       }
     } else {
       // Non-incremental version:
@@ -277,11 +356,23 @@ impl<Prov: Provenance, Ptr: PointerFamily> DynamicExecutionContext<Prov, Ptr> {
 
     // Create dynamic relations; all of them will be in the output
     // Note: Unwrap is ok since the relation in stratum must be in the ram program
+    let mut seeded_relations = Vec::new();
     for rela in dyn_relas
       .iter()
       .filter(|r| !ram_program.relation_unchecked(r).immutable)
     {
-      iter.create_dynamic_relation(rela);
+      if let Some(seeds) = &mut seeds {
+        iter.create_seeded_dynamic_relation(rela, seeds.remove(rela).unwrap());
+        seeded_relations.push((rela.clone(), iter.get_dynamic_relation_unsafe(rela).clone()));
+      } else {
+        iter.create_dynamic_relation(rela);
+        if let Some(remaining) = &remaining {
+          let dataflow = dataflow::DynamicDataflow::dynamic_recent_collection(&remaining[rela]);
+          iter
+            .get_dynamic_relation_unsafe(rela)
+            .insert_dataflow_recent(ctx, &dataflow, runtime);
+        }
+      }
 
       // Check if we need it to be output
       if self.options.incremental_maintain
@@ -291,8 +382,13 @@ impl<Prov: Provenance, Ptr: PointerFamily> DynamicExecutionContext<Prov, Ptr> {
         iter.add_output_relation(rela);
       }
 
-      // Load external facts
-      if let Some(facts) = self.edb.get_dynamic_collection(rela) {
+      // Load external facts; a seeded relation only needs the ones added since the previous execution
+      let facts = if seeds.is_some() {
+        maintenance.deltas.get(rela).map(|delta| Ptr::get_rc(delta))
+      } else {
+        self.edb.get_dynamic_collection(rela)
+      };
+      if let Some(facts) = facts {
         // Mutable relations need their EDB facts to go into dynamic relation
         let dataflow = dataflow::DynamicDataflow::dynamic_recent_collection(facts);
         iter
@@ -309,12 +405,78 @@ impl<Prov: Provenance, Ptr: PointerFamily> DynamicExecutionContext<Prov, Ptr> {
     }
 
     // Run!
-    let result = iter.run(ctx, runtime);
+    let result = iter.run(ctx, runtime)?;
+    maintenance.record_execution(&dyn_relas, seeded_relations, remaining, &result, ctx);
 
     // Success!
     Ok(IntentionalDatabase::from_dynamic_collections(result.into_iter()))
   }
 
+  /// Over-delete the facts of the seeds that are derived from the deleted facts, and get the remaining facts
+  ///
+  /// The seeds are the stable facts of the over-deleting relations, while the deleted facts of the inputs and of
+  /// the relations themselves are recent; the facts of the seeds that are then derived are over-deleted.
+  fn over_delete(
+    &self,
+    stratum: &ram::Stratum,
+    dyn_relas: &HashSet<String>,
+    seeds: HashMap<String, DynamicCollection<Prov>>,
+    current_idb: &IntentionalDatabase<Prov, Ptr>,
+    maintenance: &mut Maintenance<Prov, Ptr>,
+    ram_program: &ram::Program,
+    runtime: &RuntimeEnvironment,
+    ctx: &Prov,
+  ) -> Result<HashMap<String, DynamicCollection<Prov>>, RuntimeError> {
+    let mut iter = DynamicIteration::<Prov>::new();
+
+    // Add input collections along with their deleted facts
+    for (rel, col) in &self.edb.extensional_relations {
+      if ram_program.relation(rel).map(|r| r.immutable).unwrap_or(false) {
+        iter.add_input_dynamic_collection(&rel, &col.internal);
+      }
+    }
+    for (rel, col) in current_idb {
+      iter.add_input_dynamic_collection(&rel, Ptr::get_rc(&col.internal_facts));
+    }
+    for (rel, deleted) in &maintenance.deletions {
+      iter.add_input_dynamic_delta(rel, Ptr::get_rc(deleted));
+    }
+
+    // Create the over-deleting relations
+    let mut relations = Vec::new();
+    for (rela, seed) in seeds {
+      iter.create_over_deleting_dynamic_relation(&rela, seed);
+      if let Some(deleted) = maintenance.deletions.get(&rela) {
+        let dataflow = dataflow::DynamicDataflow::dynamic_recent_collection(Ptr::get_rc(deleted));
+        iter
+          .get_dynamic_relation_unsafe(&rela)
+          .insert_dataflow_recent(ctx, &dataflow, runtime);
+      }
+      relations.push((rela.clone(), iter.get_dynamic_relation_unsafe(&rela).clone()));
+    }
+
+    // Add updates
+    for update in &stratum.updates {
+      if dyn_relas.contains(&update.target) {
+        iter.add_update(update.clone());
+      }
+    }
+
+    // Run!
+    iter.run(ctx, runtime)?;
+
+    // Remove the over-deleted facts from the seeds
+    let mut remaining = HashMap::new();
+    for (rela, relation) in relations {
+      let over_deleted = relation.delta(ctx);
+      let mut seed = relation.complete(ctx);
+      seed.elements.retain(|elem| over_deleted.find(&elem.tuple).is_none());
+      maintenance.set_deletion(rela.clone(), over_deleted);
+      remaining.insert(rela, seed);
+    }
+    Ok(remaining)
+  }
+
   /// Directly execute the program stored in the file
   pub fn execute_with_monitor<M>(
     &mut self,
@@ -335,6 +497,20 @@ impl<Prov: Provenance, Ptr: PointerFamily> DynamicExecutionContext<Prov, Ptr> {
     ctx: &mut Prov,
     m: &M,
   ) -> Result<(), RuntimeError>
+  where
+    M: Monitor<Prov>,
+  {
+    self.incremental_execute_with_monitor_helper(Some(Ptr::new_rc(program)), runtime, ctx, m)
+  }
+
+  /// Execute a program that is shared with other execution contexts, without cloning it
+  pub fn incremental_execute_shared_with_monitor<M>(
+    &mut self,
+    program: Ptr::Rc<ram::Program>,
+    runtime: &RuntimeEnvironment,
+    ctx: &mut Prov,
+    m: &M,
+  ) -> Result<(), RuntimeError>
   where
     M: Monitor<Prov>,
   {
@@ -343,7 +519,7 @@ impl<Prov: Provenance, Ptr: PointerFamily> DynamicExecutionContext<Prov, Ptr> {
 
   fn incremental_execute_with_monitor_helper<M>(
     &mut self,
-    maybe_new_program: Option<ram::Program>,
+    maybe_new_program: Option<Ptr::Rc<ram::Program>>,
     runtime: &RuntimeEnvironment,
     ctx: &mut Prov,
     m: &M,
@@ -351,47 +527,17 @@ impl<Prov: Provenance, Ptr: PointerFamily> DynamicExecutionContext<Prov, Ptr> {
   where
     M: Monitor<Prov>,
   {
-    // Pull the IDB
-    let mut incremental_result = IntentionalDatabase::default();
-    std::mem::swap(&mut self.idb, &mut incremental_result);
+    // If there is no new program, we directly take our current program
+    let program = maybe_new_program.unwrap_or_else(|| Ptr::clone_rc(&self.program));
+    let program_ref = Ptr::get_rc(&program);
 
-    // Persistent relations
-    let mut temp_program = ram::Program::new();
-    std::mem::swap(&mut self.program, &mut temp_program);
-    let program_ref = if let Some(new_program) = &maybe_new_program {
-      // Process the EDB; populate using program facts
-      self.edb.populate_program_facts(new_program)?;
-
-      // If need to incrementalize, remove such computed results
-      let edb_need_update_relations = self.edb.need_update_relations();
-
-      // If a new program is provided, compute the persistent relations that we can re-use
-      let pers = if edb_need_update_relations.is_empty() {
-        self.program.persistent_relations(new_program)
-      } else {
-        self
-          .program
-          .persistent_relations_with_need_update_relations(new_program, &edb_need_update_relations)
-      };
-
-      // Keep the relations only in the set of persistent relations
-      incremental_result.retain_relations(&pers);
-
-      // Return this new program
-      &new_program
-    } else {
-      self
-        .edb
-        .populate_program_facts(&temp_program)
-        .expect("Since there is no new program, no error should be raised during program facts population");
-
-      // If there is no new program, we directly take our current program
-      &temp_program
-    };
+    // Pull the IDB, keeping the relations that can be reused
+    let (mut incremental_result, mut maintenance) = self.prepare_execution(program_ref, ctx, false)?;
 
     // Internalize EDB relations
     // !SPECIAL MONITORING!
     self.edb.internalize_with_monitor(ctx, m);
+    maintenance.add_edb_deltas(&self.edb);
 
     // Generate stratum information
     let strata_info = stratum_inputs_outputs(program_ref);
@@ -410,6 +556,7 @@ impl<Prov: Provenance, Ptr: PointerFamily> DynamicExecutionContext<Prov, Ptr> {
         i,
         stratum,
         &incremental_result,
+        &mut maintenance,
         program_ref,
         &strata_info,
         runtime,
@@ -418,9 +565,10 @@ impl<Prov: Provenance, Ptr: PointerFamily> DynamicExecutionContext<Prov, Ptr> {
       )?;
 
       // Extend the incremental result with the relations within their lifespan
+      let keep_hidden = self.options.incremental_maintain;
       incremental_result.extend(result.into_iter().filter(|(name, _)| {
-        // Check if the relation is a hidden one
-        if program_ref.relation_unchecked(&name).output.is_hidden() {
+        // Check if the relation is a hidden one; they are all kept if they are to be maintained
+        if program_ref.relation_unchecked(&name).output.is_hidden() && !keep_hidden {
           // If it is hidden, additionally check if it is going to be used later
           if let Some(last_stratum) = strata_info.relation_lifespan.get(name) {
             // If the last stratum that this relation is used is later than current, then keep; otherwise drop
@@ -433,46 +581,25 @@ impl<Prov: Provenance, Ptr: PointerFamily> DynamicExecutionContext<Prov, Ptr> {
 
     // Store the result
     self.idb = incremental_result;
-
-    // See if any EDB relations need to directly go into IDB
-    for relation in program_ref.relations() {
-      if relation.output.is_not_hidden() && !self.idb.has_relation(&relation.predicate) {
-        if self.options.incremental_maintain {
-          if let Some(edb_collection) = self.edb.get_dynamic_collection(&relation.predicate) {
-            self
-              .idb
-              .insert_dynamic_collection(relation.predicate.clone(), edb_collection.clone());
-          }
-        } else {
-          if let Some(edb_collection) = self.edb.pop_dynamic_collection(&relation.predicate) {
-            self
-              .idb
-              .insert_dynamic_collection(relation.predicate.clone(), edb_collection.clone());
-          }
-        }
-      }
-    }
+    self.share_edb_relations_into_idb(program_ref);
 
     // Update the program
-    if let Some(new_program) = maybe_new_program {
-      self.program = new_program;
-    } else {
-      self.program = temp_program;
-    }
+    self.program = program;
 
     // Success!
     Ok(())
   }
 
   fn execute_stratum_with_monitor<M>(
-    &mut self,
+    &self,
     stratum_id: usize,
     stratum: &ram::Stratum,
     current_idb: &IntentionalDatabase<Prov, Ptr>,
+    maintenance: &mut Maintenance<Prov, Ptr>,
     ram_program: &ram::Program,
     strata_info: &StrataInformation,
     runtime: &RuntimeEnvironment,
-    ctx: &mut Prov,
+    ctx: &Prov,
     m: &M,
   ) -> Result<IntentionalDatabase<Prov, Ptr>, RuntimeError>
   where
@@ -481,17 +608,33 @@ impl<Prov: Provenance, Ptr: PointerFamily> DynamicExecutionContext<Prov, Ptr> {
     // !SPECIAL MONITORING!
     m.observe_executing_stratum(stratum_id);
 
-    let dyn_relas = stratum
-      .relations
-      .iter()
-      .filter(|(r, _)| !current_idb.has_relation(*r))
-      .map(|(r, _)| r.clone())
-      .collect::<HashSet<_>>();
-
-    // Check if we need to compute anything new
-    if dyn_relas.is_empty() {
+    // Check if we need to compute anything new; the relations of a stratum are computed together, so the
+    // stratum is only reused if all of its relations are kept
+    let is_kept = stratum.relations.keys().all(|r| current_idb.has_relation(r));
+    if is_kept {
       return Ok(IntentionalDatabase::new());
     }
+    let dyn_relas = stratum.relations.keys().cloned().collect::<HashSet<_>>();
+
+    // Check if the relations computed by the previous execution are to be maintained; their facts are then
+    // taken as the seeds of the relations
+    let mut seeds = maintenance.take_seeds(stratum, &dyn_relas);
+
+    // If facts are deleted, the seeds are first over-deleted, and the remaining facts are then completed
+    let mut remaining = None;
+    if seeds.is_some() && maintenance.has_deletions(stratum) {
+      let seeds = seeds.take().unwrap();
+      remaining = Some(self.over_delete(
+        stratum,
+        &dyn_relas,
+        seeds,
+        current_idb,
+        maintenance,
+        ram_program,
+        runtime,
+        ctx,
+      )?);
+    }
 
     // Otherwise, do computation
     let mut iter = DynamicIteration::<Prov>::new();
@@ -512,7 +655,14 @@ impl<Prov: Provenance, Ptr: PointerFamily> DynamicExecutionContext<Prov, Ptr> {
         m.observe_loading_relation(rel);
         m.observe_loading_relation_from_idb(rel);
 
-        iter.add_input_dynamic_collection(&rel, &col.internal_facts);
+        iter.add_input_dynamic_collection(&rel, Ptr::get_rc(&col.internal_facts));
+      }
+
+      // The seeded relations only need the new facts of their inputs
+      if seeds.is_some() {
+        for (rel, delta) in &maintenance.deltas {
+          iter.add_input_dynamic_delta(rel, Ptr::get_rc(delta));
+        }
       }
     } else {
       // Non-incremental version:
@@ -552,11 +702,23 @@ impl<Prov: Provenance, Ptr: PointerFamily> DynamicExecutionContext<Prov, Ptr> {
 
     // Create dynamic relations; all of them will be in the output
     // Note: Unwrap is ok since the relation in stratum must be in the ram program
+    let mut seeded_relations = Vec::new();
     for rela in dyn_relas
       .iter()
       .filter(|r| !ram_program.relation_unchecked(r).immutable)
     {
-      iter.create_dynamic_relation(rela);
+      if let Some(seeds) = &mut seeds {
+        iter.create_seeded_dynamic_relation(rela, seeds.remove(rela).unwrap());
+        seeded_relations.push((rela.clone(), iter.get_dynamic_relation_unsafe(rela).clone()));
+      } else {
+        iter.create_dynamic_relation(rela);
+        if let Some(remaining) = &remaining {
+          let dataflow = dataflow::DynamicDataflow::dynamic_recent_collection(&remaining[rela]);
+          iter
+            .get_dynamic_relation_unsafe(rela)
+            .insert_dataflow_recent(ctx, &dataflow, runtime);
+        }
+      }
 
       // Check if we need it to be output
       if self.options.incremental_maintain
@@ -566,8 +728,13 @@ impl<Prov: Provenance, Ptr: PointerFamily> DynamicExecutionContext<Prov, Ptr> {
         iter.add_output_relation(rela);
       }
 
-      // Load external facts
-      if let Some(facts) = self.edb.get_dynamic_collection(rela) {
+      // Load external facts; a seeded relation only needs the ones added since the previous execution
+      let facts = if seeds.is_some() {
+        maintenance.deltas.get(rela).map(|delta| Ptr::get_rc(delta))
+      } else {
+        self.edb.get_dynamic_collection(rela)
+      };
+      if let Some(facts) = facts {
         // !SPECIAL MONITOR!
         m.observe_loading_relation(rela);
         m.observe_loading_relation_from_edb(rela);
@@ -589,7 +756,8 @@ impl<Prov: Provenance, Ptr: PointerFamily> DynamicExecutionContext<Prov, Ptr> {
 
     // Run!
     // !SPECIAL MONITORING!
-    let result = iter.run_with_monitor(ctx, runtime, m);
+    let result = iter.run_with_monitor(ctx, runtime, m)?;
+    maintenance.record_execution(&dyn_relas, seeded_relations, remaining, &result, ctx);
 
     // Success!
     Ok(IntentionalDatabase::from_dynamic_collections(result.into_iter()))
@@ -613,6 +781,78 @@ impl<Prov: Provenance, Ptr: PointerFamily> DynamicExecutionContext<Prov, Ptr> {
     self.idb.get_internal_collection(r)
   }
 
+  /// Get the facts of a computed relation matching the pattern, where a `None` matches any value in its column
+  ///
+  /// The collection is sorted, so a bound first column is searched with a `Find`; the other bound columns are
+  /// checked with a `Filter`. Returns `None` if the relation is not computed.
+  pub fn query(
+    &self,
+    r: &str,
+    pattern: &[Option<Value>],
+    runtime: &RuntimeEnvironment,
+    ctx: &Prov,
+  ) -> Result<Option<DynamicOutputCollection<Prov>>, DatabaseError> {
+    // Check the pattern against the type of the relation
+    let relation_type = self
+      .program
+      .relation_tuple_type(r)
+      .ok_or_else(|| DatabaseError::UnknownRelation {
+        relation: r.to_string(),
+      })?;
+    let matches = match &relation_type {
+      TupleType::Tuple(tys) => {
+        tys.len() == pattern.len()
+          && tys
+            .iter()
+            .zip(pattern)
+            .all(|(ty, v)| v.as_ref().is_none_or(|v| ty.matches(&Tuple::Value(v.clone()))))
+      }
+      _ => false,
+    };
+    if !matches {
+      return Err(DatabaseError::QueryPatternMismatch {
+        relation: r.to_string(),
+        relation_type,
+        pattern: pattern.to_vec(),
+      });
+    }
+
+    // The relations recovered directly from the EDB do not keep their internal facts in the IDB
+    let collection = match (
+      self.idb.get_internal_collection(r),
+      self.edb.extensional_relations.get(r),
+    ) {
+      (Some(c), Some(edb_relation)) if c.is_empty() => &edb_relation.internal,
+      (Some(c), _) => c,
+      (None, Some(edb_relation)) => &edb_relation.internal,
+      (None, None) => return Ok(None),
+    };
+
+    // Build the dataflow finding the matching facts
+    let mut dataflow = dataflow::DynamicDataflow::dynamic_stable_collection(collection);
+    let mut bound_columns = pattern
+      .iter()
+      .enumerate()
+      .filter_map(|(i, v)| v.as_ref().map(|v| (i, v)));
+    if let Some(Some(v)) = pattern.first() {
+      bound_columns.next();
+      dataflow = dataflow.find(Tuple::Value(v.clone()));
+    }
+    let filter = bound_columns
+      .map(|(i, v)| Expr::binary(BinaryOp::Eq, Expr::access(i), Expr::constant(v.clone())))
+      .reduce(|e1, e2| Expr::binary(BinaryOp::And, e1, e2));
+    if let Some(filter) = filter {
+      dataflow = dataflow.filter(filter);
+    }
+
+    // Collect and recover the matching facts
+    let elements = dataflow
+      .iter_stable(runtime)
+      .flatten()
+      .map(|elem| (ctx.recover_fn(&elem.tag), elem.tuple));
+    Ok(Some(DynamicOutputCollection::from(elements)))
+  }
+
   pub fn recover(&mut self, r: &str, ctx: &Prov) {
     if self.idb.has_relation(r) {
       self.idb.recover(r, ctx, !self.options.retain_internal_when_recover);
@@ -627,6 +867,11 @@ impl<Prov: Provenance, Ptr: PointerFamily> DynamicExecutionContext<Prov, Ptr> {
       .recover_with_monitor(r, ctx, m, !self.options.retain_internal_when_recover)
   }
 
+  /// Hand the recovered facts of a relation to the monitor without storing them
+  pub fn stream_with_monitor<M: Monitor<Prov>>(&self, r: &str, ctx: &Prov, m: &M) {
+    self.idb.stream_with_monitor(r, ctx, m)
+  }
+
   pub fn relation_ref(&self, r: &str) -> Option<&DynamicOutputCollection<Prov>> {
     self.idb.get_output_collection_ref(r)
   }
@@ -705,6 +950,221 @@ fn stratum_inputs_outputs(ram: &ram::Program) -> StrataInformation {
   }
 }
 
+/// The state of an execution maintaining the relations computed by the previous execution of the context
+///
+/// A stratum whose relations are not reused is either recomputed from scratch, or maintained: its relations are
+/// seeded with their facts from the previous execution, and the semi-naive evaluation then only derives the facts
+/// following from the new facts of the relations it depends on. The facts derived anew by a maintained stratum
+/// are in turn the new facts that the later strata are maintained with.
+///
+/// When facts are deleted from the relations a stratum depends on, the stratum is maintained by deletion and
+/// rederivation (DRed): the facts derived from the deleted ones are over-deleted from the seeds, and the remaining
+/// facts are then completed with the ones that can still be derived. The over-deleted facts are in turn the
+/// deleted facts that the later strata are maintained with.
+struct Maintenance<Prov: Provenance, Ptr: PointerFamily> {
+  /// Whether the strata can be maintained at all
+  enabled: bool,
+
+  /// Whether the facts computed by the previous execution are to be kept, to get the new facts of the execution
+  need_new_facts: bool,
+
+  /// The relations whose rules are the same as in the previous execution
+  same_rules: HashSet<String>,
+
+  /// The facts of the relations computed by the previous execution that are not reused as is
+  stale: HashMap<String, Ptr::Rc<DynamicCollection<Prov>>>,
+
+  /// The new facts of the relations, since the previous execution
+  deltas: HashMap<String, Ptr::Rc<DynamicCollection<Prov>>>,
+
+  /// The deleted facts of the relations, since the previous execution
+  deletions: HashMap<String, Ptr::Rc<DynamicCollection<Prov>>>,
+
+  /// The relations whose facts are recomputed from scratch
+  recomputed: HashSet<String>,
+
+  /// The relations computed by this execution
+  computed: HashSet<String>,
+}
+
+impl<Prov: Provenance, Ptr: PointerFamily> Maintenance<Prov, Ptr> {
+  fn new(enabled: bool, need_new_facts: bool) -> Self {
+    Self {
+      enabled,
+      need_new_facts,
+      same_rules: HashSet::new(),
+      stale: HashMap::new(),
+      deltas: HashMap::new(),
+      deletions: HashMap::new(),
+      recomputed: HashSet::new(),
+      computed: HashSet::new(),
+    }
+  }
+
+  /// Record the facts added to and retracted from the EDB relations by their internalization
+  fn add_edb_deltas(&mut self, edb: &ExtensionalDatabase<Prov, Ptr>) {
+    if self.enabled {
+      for (name, relation) in &edb.extensional_relations {
+        if !relation.delta.is_empty() {
+          self.deltas.insert(name.clone(), Ptr::clone_rc(&relation.delta));
+        }
+        if !relation.deleted.is_empty() {
+          self.deletions.insert(name.clone(), Ptr::clone_rc(&relation.deleted));
+        }
+      }
+    }
+  }
+
+  /// Check whether the given relations of the stratum can be maintained
+  fn can_maintain(&self, stratum: &ram::Stratum, relations: &HashSet<String>) -> bool {
+    let changed = self.deltas.keys().chain(self.deletions.keys()).cloned().collect();
+    self.enabled
+      && relations
+        .iter()
+        .filter(|r| !stratum.relations[*r].immutable)
+        .all(|r| self.same_rules.contains(r) && self.stale.contains_key(r))
+      && !stratum.relations.keys().any(|r| self.recomputed.contains(r))
+      && !stratum.dependency().iter().any(|r| self.recomputed.contains(r))
+      && stratum.is_monotonic_in(&changed)
+  }
+
+  /// Check whether facts are deleted from the relations of the stratum or from the ones it depends on
+  fn has_deletions(&self, stratum: &ram::Stratum) -> bool {
+    stratum.relations.keys().any(|r| self.deletions.contains_key(r))
+      || stratum.dependency().iter().any(|r| self.deletions.contains_key(r))
+  }
+
+  /// Take the facts of the given relations computed by the previous execution if the stratum can be maintained;
+  /// otherwise the relations are recomputed
+  fn take_seeds(
+    &mut self,
+    stratum: &ram::Stratum,
+    relations: &HashSet<String>,
+  ) -> Option<HashMap<String, DynamicCollection<Prov>>> {
+    let mutable_relations = relations.iter().filter(|r| !stratum.relations[*r].immutable);
+    if mutable_relations.clone().next().is_none() {
+      return None;
+    }
+    self.computed.extend(mutable_relations.clone().cloned());
+    if !self.can_maintain(stratum, relations) {
+      for r in mutable_relations {
+        self.recomputed.insert(r.clone());
+        if !self.need_new_facts {
+          self.stale.remove(r);
+        }
+      }
+      return None;
+    }
+
+    // The stale facts are only copied when they are needed to get the new facts of the execution
+    let seeds = mutable_relations
+      .map(|r| {
+        let seed = if self.need_new_facts {
+          Ptr::get_rc(&self.stale[r]).clone()
+        } else {
+          let mut stale = self.stale.remove(r).unwrap();
+          std::mem::replace(Ptr::make_rc_mut(&mut stale), DynamicCollection::empty())
+        };
+        (r.clone(), seed)
+      })
+      .collect();
+    Some(seeds)
+  }
+
+  /// Record the facts of a relation that are derived anew
+  fn set_delta(&mut self, relation: String, delta: DynamicCollection<Prov>) {
+    if delta.is_empty() {
+      self.deltas.remove(&relation);
+    } else {
+      self.deltas.insert(relation, Ptr::new_rc(delta));
+    }
+  }
+
+  /// Record the facts of a relation that are over-deleted
+  fn set_deletion(&mut self, relation: String, deletion: DynamicCollection<Prov>) {
+    if deletion.is_empty() {
+      self.deletions.remove(&relation);
+    } else {
+      self.deletions.insert(relation, Ptr::new_rc(deletion));
+    }
+  }
+
+  /// Record the facts that the maintained relations derived anew as their deltas
+  fn record_execution(
+    &mut self,
+    relations: &HashSet<String>,
+    seeded_relations: Vec<(String, DynamicRelation<Prov>)>,
+    remaining: Option<HashMap<String, DynamicCollection<Prov>>>,
+    result: &HashMap<String, DynamicCollection<Prov>>,
+    ctx: &Prov,
+  ) {
+    if let Some(remaining) = remaining {
+      // The facts that do not remain after the over-deletion, or whose tag is updated, are derived anew
+      for (r, remaining) in remaining {
+        let delta = result[&r]
+          .iter()
+          .filter(|elem| match remaining.find(&elem.tuple) {
+            Some(old_elem) => !ctx.saturated(&old_elem.tag, &elem.tag),
+            None => true,
+          })
+          .cloned()
+          .collect();
+        self.set_delta(r, DynamicCollection::from_vec_unchecked(delta));
+      }
+      return;
+    }
+
+    // Otherwise no fact is deleted from the computed relations
+    let computed = &self.computed;
+    self
+      .deletions
+      .retain(|r, _| !relations.contains(r) || !computed.contains(r));
+    if seeded_relations.is_empty() {
+      // The recomputed relations have no delta; the dependent strata are recomputed as well
+      let recomputed = &self.recomputed;
+      self
+        .deltas
+        .retain(|r, _| !relations.contains(r) || !recomputed.contains(r));
+    } else {
+      for (r, relation) in seeded_relations {
+        self.set_delta(r, relation.delta(ctx));
+      }
+    }
+  }
+
+  /// Get the facts of each relation computed by this execution that were not computed by the previous one
+  fn new_facts(&self, idb: &IntentionalDatabase<Prov, Ptr>) -> HashMap<String, DynamicCollection<Prov>> {
+    if !self.need_new_facts {
+      return HashMap::new();
+    }
+    self
+      .computed
+      .iter()
+      .filter_map(|r| {
+        // A maintained relation only needs its delta to be checked; a recomputed one needs all of its facts
+        let candidates = if self.recomputed.contains(r) {
+          idb.get_internal_collection(r)?
+        } else {
+          Ptr::get_rc(self.deltas.get(r)?)
+        };
+        let new_facts = match self.stale.get(r) {
+          Some(stale) => {
+            let stale = Ptr::get_rc(stale);
+            let elements = candidates
+              .iter()
+              .filter(|elem| stale.find(&elem.tuple).is_none())
+              .cloned()
+              .collect();
+            DynamicCollection::from_vec_unchecked(elements)
+          }
+          None => candidates.clone(),
+        };
+        Some((r.clone(), new_facts))
+      })
+      .collect()
+  }
+}
+
 // impl StrataInformation {
 //   pub fn is_input(&self, stratum_id: usize, rel: &str) -> bool {
 //     if let Some(stratum_input) = self.stratum_inputs.get(&stratum_id) {
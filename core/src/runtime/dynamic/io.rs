@@ -1,85 +1,403 @@
 use csv::{ReaderBuilder, WriterBuilder};
-use std::fs::File;
-use std::path::PathBuf;
+use regex::Regex;
+use serde_json::Value as JsonValue;
+use std::borrow::Borrow;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
 
-use crate::common::input_file::InputFile;
+use crate::common::input_file::{CsvOptions, Delimiter, InputFile};
 use crate::common::input_tag::DynamicInputTag;
 use crate::common::output_option::OutputFile;
 use crate::common::tuple::Tuple;
 use crate::common::tuple_type::TupleType;
+use crate::common::value::Value;
 use crate::common::value_type::ValueType;
 
 use crate::runtime::error::*;
 
 pub fn load(input_file: &InputFile, types: &TupleType) -> Result<Vec<(DynamicInputTag, Tuple)>, IOError> {
   match input_file {
-    InputFile::Csv {
+    InputFile::Csv { file_path, options } => load_csv(file_path, options, types),
+    InputFile::Txt {
       file_path,
-      deliminator,
-      has_header,
+      deliminator: Some(deliminator),
+    } => {
+      let options = CsvOptions {
+        deliminator: deliminator.clone(),
+        ..Default::default()
+      };
+      load_lines(file_path, &options, types)
+    }
+    InputFile::Txt {
+      file_path,
+      deliminator: None,
+    } => load_txt(file_path, types),
+    InputFile::Json {
+      file_path,
+      fields,
       has_probability,
-    } => load_csv(file_path, *deliminator, *has_header, *has_probability, types),
-    InputFile::Txt(_) => unimplemented!(),
+    } => load_json(file_path, fields.as_ref(), *has_probability, types),
   }
 }
 
 pub fn load_csv(
   file_path: &PathBuf,
-  deliminator: u8,
-  has_header: bool,
-  has_probability: bool,
+  options: &CsvOptions,
   types: &TupleType,
 ) -> Result<Vec<(DynamicInputTag, Tuple)>, IOError> {
+  // Only single byte delimiters are understood by the CSV reader; quoting is not supported otherwise
+  let deliminator = match &options.deliminator {
+    Delimiter::Byte(b) => *b,
+    _ => return load_lines(file_path, options, types),
+  };
+
   // First parse the value types
   let value_types = get_value_types(types)?;
 
-  // Setup probability offset
-  let probability_offset = if has_probability { 1 } else { 0 };
-
-  // Then load the file
-  let file = File::open(file_path).map_err(|e| IOError::CannotOpenFile {
+  // Then load the file; its bytes are kept to tell quoted empty fields from unquoted ones
+  let bytes = fs::read(file_path).map_err(|e| IOError::CannotOpenFile {
     file_path: file_path.clone(),
     error: format!("{}", e),
   })?;
+  let quote_char = options.quote_char.unwrap_or(b'"');
 
   let mut result = vec![];
-  let mut csv_rdr = ReaderBuilder::new()
+  let mut csv_rdr_builder = ReaderBuilder::new();
+  csv_rdr_builder
     .delimiter(deliminator)
-    .has_headers(has_header)
-    .from_reader(file);
+    .has_headers(options.has_header);
+  csv_rdr_builder.quote(quote_char);
+  csv_rdr_builder.comment(options.comment_char);
+
+  // Records of different lengths are accepted by the reader so that empty records can be skipped; the
+  // length of the other records is checked below
+  csv_rdr_builder.flexible(true);
+  let mut csv_rdr = csv_rdr_builder.from_reader(&bytes[..]);
+
+  // Find the columns to be selected by their names in the header
+  let selected_columns = match &options.column_mapping {
+    Some(mapping) if options.has_header => {
+      let header = csv_rdr
+        .headers()
+        .map_err(|e| IOError::CannotParseCSV { error: e.to_string() })?;
+      let columns = select_columns(header.iter(), mapping, options.has_probability)?;
+      Some((header.len(), columns))
+    }
+    _ => None,
+  };
 
   for row in csv_rdr.records() {
     let record = row.map_err(|e| IOError::CannotParseCSV { error: e.to_string() })?;
+    if is_empty_record(&record, &bytes, quote_char, options) {
+      continue;
+    }
+    let line = record.position().map_or(0, |p| p.line() as usize);
+    result.push(parse_selected_record(
+      line,
+      record.iter().collect(),
+      selected_columns.as_ref(),
+      options,
+      &value_types,
+    )?);
+  }
 
-    if record.len() - probability_offset != value_types.len() {
-      return Err(IOError::ArityMismatch {
-        expected: value_types.len(),
-        found: record.len(),
-      });
+  Ok(result)
+}
+
+/// Whether a CSV record carries no data: it either has no field, or it is a single unquoted empty
+/// field which would not be filled by `empty_value`. A quoted `""` is kept as an empty string
+fn is_empty_record(record: &csv::StringRecord, bytes: &[u8], quote_char: u8, options: &CsvOptions) -> bool {
+  if record.is_empty() {
+    true
+  } else if record.len() == 1 && record[0].is_empty() && options.empty_value.is_none() {
+    let start = record.position().map_or(0, |p| p.byte() as usize);
+    bytes.get(start) != Some(&quote_char)
+  } else {
+    false
+  }
+}
+
+/// Load a file line by line, splitting each line into fields with the delimiter of the options
+///
+/// Blank lines and lines starting with `comment_char` are skipped, and empty fields are read as
+/// `empty_value` when it is given.
+pub fn load_lines(
+  file_path: &PathBuf,
+  options: &CsvOptions,
+  types: &TupleType,
+) -> Result<Vec<(DynamicInputTag, Tuple)>, IOError> {
+  // First parse the value types
+  let value_types = get_value_types(types)?;
+
+  // Prepare the splitter
+  let deliminator = &options.deliminator;
+  let regex = match deliminator {
+    Delimiter::Regex(r) => Some(Regex::new(r).map_err(|e| IOError::InvalidDeliminatorRegex {
+      regex: r.clone(),
+      error: e.to_string(),
+    })?),
+    _ => None,
+  };
+  let split = |line: &'_ str| -> Vec<String> {
+    match deliminator {
+      Delimiter::Byte(b) => line.split(*b as char).map(String::from).collect(),
+      Delimiter::Whitespace => line.split_whitespace().map(String::from).collect(),
+      Delimiter::Regex(_) => regex.as_ref().unwrap().split(line).map(String::from).collect(),
     }
+  };
 
-    let tag = if has_probability {
-      let s = record.get(0).unwrap();
-      s.parse::<DynamicInputTag>()
-        .map_err(|_| IOError::CannotParseProbability { value: s.to_string() })?
-    } else {
-      DynamicInputTag::None
-    };
+  // Then load the file
+  let file = open_file(file_path)?;
 
-    let values = record
-      .into_iter()
-      .skip(probability_offset)
-      .zip(value_types.iter())
-      .map(|(r, t)| t.parse(r).map_err(|e| IOError::ValueParseError { error: e }))
-      .collect::<Result<Vec<_>, _>>()?;
+  let mut result = vec![];
+  let is_skipped = |line: &str| {
+    line.trim().is_empty() || options.comment_char.map_or(false, |c| line.as_bytes().first() == Some(&c))
+  };
+  let mut lines = BufReader::new(file)
+    .lines()
+    .enumerate()
+    .map(|(i, l)| (i + 1, l))
+    .filter(|(_, l)| l.as_ref().map_or(true, |l| !is_skipped(l)));
 
-    let tuple = Tuple::from(values);
-    result.push((tag, tuple));
+  // Skip the header, finding the columns to be selected by their names if needed
+  let mut selected_columns = None;
+  if options.has_header {
+    if let Some((_, line)) = lines.next() {
+      let line = line.map_err(|e| IOError::CannotReadFile { error: e.to_string() })?;
+      if let Some(mapping) = &options.column_mapping {
+        let header = split(&line);
+        let columns = select_columns(header.iter().map(String::as_str), mapping, options.has_probability)?;
+        selected_columns = Some((header.len(), columns));
+      }
+    }
+  }
+
+  for (line_num, line) in lines {
+    let line = line.map_err(|e| IOError::CannotReadFile { error: e.to_string() })?;
+    let fields = split(&line);
+    result.push(parse_selected_record(
+      line_num,
+      fields.iter().map(String::as_str).collect(),
+      selected_columns.as_ref(),
+      options,
+      &value_types,
+    )?);
+  }
+
+  Ok(result)
+}
+
+/// Load a text file as a relation with a single string column, one tuple per line
+///
+/// The line endings are trimmed, while all the other whitespaces are preserved.
+pub fn load_txt(file_path: &PathBuf, types: &TupleType) -> Result<Vec<(DynamicInputTag, Tuple)>, IOError> {
+  // The relation needs to have exactly one string column
+  match get_value_types(types)?.as_slice() {
+    [ValueType::String] => {}
+    _ => return Err(IOError::InvalidType { types: types.clone() }),
+  }
+
+  // Then load the file
+  let file = open_file(file_path)?;
+
+  let mut result = vec![];
+  for line in BufReader::new(file).lines() {
+    let line = line.map_err(|e| IOError::CannotReadFile { error: e.to_string() })?;
+    result.push((DynamicInputTag::None, Tuple::from((line,))));
+  }
+
+  Ok(result)
+}
+
+/// Load a JSON file holding an array of records
+///
+/// A record is either an array of values, or an object whose `fields` are taken, in order, as
+/// the columns. When `has_probability` is set, the probability is the first element of an array
+/// record, or the `probability` field of an object record.
+pub fn load_json(
+  file_path: &PathBuf,
+  fields: Option<&Vec<String>>,
+  has_probability: bool,
+  types: &TupleType,
+) -> Result<Vec<(DynamicInputTag, Tuple)>, IOError> {
+  // First parse the value types
+  let value_types = get_value_types(types)?;
+
+  // Then load the file
+  let file = open_file(file_path)?;
+  let json: JsonValue =
+    serde_json::from_reader(BufReader::new(file)).map_err(|e| IOError::CannotParseJSON { error: e.to_string() })?;
+  let records = match json {
+    JsonValue::Array(records) => records,
+    _ => {
+      return Err(IOError::CannotParseJSON {
+        error: "expected an array of records".to_string(),
+      })
+    }
+  };
+
+  let mut result = vec![];
+  for (i, record) in records.into_iter().enumerate() {
+    let record = match record {
+      JsonValue::Array(values) => values.iter().map(json_to_field).collect::<Result<Vec<_>, _>>()?,
+      JsonValue::Object(object) => {
+        let fields = fields.ok_or_else(|| IOError::CannotParseJSON {
+          error: "`fields` need to be specified to load records that are objects".to_string(),
+        })?;
+        let probability_field = if has_probability { Some("probability") } else { None };
+        probability_field
+          .into_iter()
+          .chain(fields.iter().map(String::as_str))
+          .map(|field| match object.get(field) {
+            Some(value) => json_to_field(value),
+            None => Err(IOError::CannotParseJSON {
+              error: format!("missing field `{}` in record", field),
+            }),
+          })
+          .collect::<Result<Vec<_>, _>>()?
+      }
+      r => {
+        return Err(IOError::CannotParseJSON {
+          error: format!("expected a record to be an array or an object, found `{}`", r),
+        })
+      }
+    };
+    result.push(parse_record(
+      i + 1,
+      record.iter().map(String::as_str).collect(),
+      None,
+      has_probability,
+      None,
+      &value_types,
+    )?);
   }
 
   Ok(result)
 }
 
+/// Turn a JSON value into a field to be parsed as a value
+fn json_to_field(value: &JsonValue) -> Result<String, IOError> {
+  match value {
+    JsonValue::String(s) => Ok(s.clone()),
+    JsonValue::Number(n) => Ok(n.to_string()),
+    JsonValue::Bool(b) => Ok(b.to_string()),
+    _ => Err(IOError::CannotParseJSON {
+      error: format!("expected a string, number, or boolean, found `{}`", value),
+    }),
+  }
+}
+
+fn open_file(file_path: &PathBuf) -> Result<File, IOError> {
+  File::open(file_path).map_err(|e| IOError::CannotOpenFile {
+    file_path: file_path.clone(),
+    error: format!("{}", e),
+  })
+}
+
+/// Find the indices of the columns named by `mapping` in the header. The probability, if any, is
+/// always taken from the first column
+fn select_columns<'a, I: Iterator<Item = &'a str>>(
+  header: I,
+  mapping: &[String],
+  has_probability: bool,
+) -> Result<Vec<usize>, IOError> {
+  let header = header.map(|h| h.trim().to_string()).collect::<Vec<_>>();
+  let probability_column = if has_probability { Some(0) } else { None };
+  let value_columns = mapping.iter().map(|column| {
+    header
+      .iter()
+      .position(|h| h == column)
+      .ok_or_else(|| IOError::MissingCSVColumn {
+        column: column.clone(),
+        header: header.clone(),
+      })
+  });
+  probability_column.into_iter().map(Ok).chain(value_columns).collect()
+}
+
+/// Parse a record whose columns may be selected by the header, given as the number of columns of the header
+/// along with the indices of the selected ones. A record needs to have as many fields as the header
+fn parse_selected_record(
+  row: usize,
+  record: Vec<&str>,
+  selected_columns: Option<&(usize, Vec<usize>)>,
+  options: &CsvOptions,
+  value_types: &[&ValueType],
+) -> Result<(DynamicInputTag, Tuple), IOError> {
+  match selected_columns {
+    Some((num_columns, columns)) => {
+      if record.len() != *num_columns {
+        let probability_offset = if options.has_probability { 1 } else { 0 };
+        return Err(IOError::ArityMismatch {
+          row,
+          expected: num_columns.saturating_sub(probability_offset),
+          found: record.len().saturating_sub(probability_offset),
+        });
+      }
+      let fields = columns.iter().map(|i| record[*i]).collect();
+      let empty_value = options.empty_value.as_deref();
+      parse_record(row, fields, Some(columns), options.has_probability, empty_value, value_types)
+    }
+    None => {
+      let empty_value = options.empty_value.as_deref();
+      parse_record(row, record, None, options.has_probability, empty_value, value_types)
+    }
+  }
+}
+
+/// Parse a record into a tagged tuple. The `row` and the 1-based column of each field, taken from
+/// `selected_columns` when the fields are selected by the header, are used to locate the errors
+fn parse_record(
+  row: usize,
+  record: Vec<&str>,
+  selected_columns: Option<&[usize]>,
+  has_probability: bool,
+  empty_value: Option<&str>,
+  value_types: &[&ValueType],
+) -> Result<(DynamicInputTag, Tuple), IOError> {
+  // Setup probability offset
+  let probability_offset = if has_probability { 1 } else { 0 };
+
+  // The probability column is not counted in the arity; a record missing it is not long enough either
+  match record.len().checked_sub(probability_offset) {
+    Some(found) if found == value_types.len() => {}
+    found => {
+      return Err(IOError::ArityMismatch {
+        row,
+        expected: value_types.len(),
+        found: found.unwrap_or(0),
+      })
+    }
+  }
+
+  let tag = if has_probability {
+    let s = record[0];
+    s.parse::<DynamicInputTag>()
+      .map_err(|_| IOError::CannotParseProbability { value: s.to_string() })?
+  } else {
+    DynamicInputTag::None
+  };
+
+  let values = record
+    .into_iter()
+    .enumerate()
+    .skip(probability_offset)
+    .zip(value_types.iter())
+    .map(|((i, r), t)| {
+      let r = if r.is_empty() { empty_value.unwrap_or(r) } else { r };
+      t.parse(r).map_err(|error| {
+        let col = selected_columns.map_or(i, |columns| columns[i]) + 1;
+        IOError::ValueParseError { row, col, error }
+      })
+    })
+    .collect::<Result<Vec<_>, _>>()?;
+
+  Ok((tag, Tuple::from(values)))
+}
+
+/// Get the value type of each column of a relation. The tuple type has to be flat, but a column can hold
+/// nested tuple values, in which case its value type is a `ValueType::Tuple`
 fn get_value_types(types: &TupleType) -> Result<Vec<&ValueType>, IOError> {
   match types {
     TupleType::Tuple(ts) => ts
@@ -94,21 +412,31 @@ fn get_value_types(types: &TupleType) -> Result<Vec<&ValueType>, IOError> {
   }
 }
 
-pub fn store<'a, I>(output_file: &OutputFile, tuples: I) -> Result<(), IOError>
+pub fn store<I, T>(output_file: &OutputFile, tuples: I) -> Result<(), IOError>
 where
-  I: Iterator<Item = &'a Tuple>,
+  I: Iterator<Item = T>,
+  T: Borrow<Tuple>,
 {
   match output_file {
-    OutputFile::CSV(f) => store_csv(&f.file_path, f.deliminator, tuples),
+    OutputFile::CSV(f) => store_csv(&f.file_path, f.deliminator, f.append, tuples),
+    OutputFile::JSON(f) => store_json(&f.file_path, f.fields.as_ref(), f.append, tuples),
   }
 }
 
-pub fn store_csv<'a, I>(file_path: &PathBuf, deliminator: u8, tuples: I) -> Result<(), IOError>
+/// Store the tuples into a CSV file; when `append` is set, the tuples are added to the end of the
+/// file (creating it if needed) instead of replacing its content
+pub fn store_csv<I, T>(file_path: &PathBuf, deliminator: u8, append: bool, tuples: I) -> Result<(), IOError>
 where
-  I: Iterator<Item = &'a Tuple>,
+  I: Iterator<Item = T>,
+  T: Borrow<Tuple>,
 {
   // Then load the file
-  let file = File::create(file_path).map_err(|e| IOError::CannotOpenFile {
+  let file = if append {
+    OpenOptions::new().append(true).create(true).open(file_path)
+  } else {
+    File::create(file_path)
+  };
+  let file = file.map_err(|e| IOError::CannotOpenFile {
     file_path: file_path.clone(),
     error: format!("{}", e),
   })?;
@@ -116,7 +444,7 @@ where
   // Write the tuples
   let mut wtr = WriterBuilder::new().delimiter(deliminator).from_writer(file);
   for tuple in tuples {
-    let record = tuple.as_ref_values().into_iter().map(|v| format!("{}", v));
+    let record = tuple.borrow().as_ref_values().into_iter().map(|v| format!("{}", v));
     wtr
       .write_record(record)
       .map_err(|e| IOError::CannotWriteRecord { error: e.to_string() })?;
@@ -124,3 +452,159 @@ where
 
   Ok(())
 }
+
+/// Store the tuples into a JSON file holding an array of records, one record per line, as read by
+/// `load_json`; each tuple is written as an object if the `fields` are given, and as an array otherwise.
+/// When `append` is set, the records are added to the end of the array of the existing file
+pub fn store_json<I, T>(
+  file_path: &PathBuf,
+  fields: Option<&Vec<String>>,
+  append: bool,
+  tuples: I,
+) -> Result<(), IOError>
+where
+  I: Iterator<Item = T>,
+  T: Borrow<Tuple>,
+{
+  // Serialize the tuples first, so that the file is left untouched if one cannot be written
+  let records = tuples
+    .map(|tuple| {
+      let values = tuple
+        .borrow()
+        .as_ref_values()
+        .into_iter()
+        .map(value_to_json)
+        .collect::<Result<Vec<_>, _>>()?;
+      match fields {
+        Some(fields) if fields.len() == values.len() => {
+          let entries = fields
+            .iter()
+            .zip(values)
+            .map(|(field, value)| serde_json::to_string(field).map(|field| format!("{}:{}", field, value)))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| IOError::CannotWriteRecord { error: e.to_string() })?;
+          Ok(format!("{{{}}}", entries.join(",")))
+        }
+        Some(fields) => Err(IOError::CannotWriteRecord {
+          error: format!(
+            "expected {} fields, found a tuple of arity {}",
+            fields.len(),
+            values.len()
+          ),
+        }),
+        None => Ok(format!("[{}]", values.join(","))),
+      }
+    })
+    .collect::<Result<Vec<_>, _>>()?;
+
+  // Take the records of the existing file, without the closing bracket of the array
+  let existing_records = match fs::read_to_string(file_path) {
+    Ok(content) if append && !content.trim().is_empty() => {
+      let existing_records = content
+        .trim_end()
+        .strip_suffix(']')
+        .filter(|existing_records| existing_records.trim_start().starts_with('['))
+        .ok_or_else(|| IOError::CannotWriteRecord {
+          error: "expected the file to hold an array of records".to_string(),
+        })?;
+      Some(existing_records.trim_end().to_string())
+    }
+    _ => None,
+  };
+
+  let file = File::create(file_path).map_err(|e| IOError::CannotOpenFile {
+    file_path: file_path.clone(),
+    error: format!("{}", e),
+  })?;
+
+  // Write the records
+  let mut wtr = BufWriter::new(file);
+  let write_err = |e: std::io::Error| IOError::CannotWriteRecord { error: e.to_string() };
+  let mut is_first = match &existing_records {
+    Some(existing_records) => {
+      write!(wtr, "{}", existing_records).map_err(write_err)?;
+      existing_records.ends_with('[')
+    }
+    None => {
+      write!(wtr, "[").map_err(write_err)?;
+      true
+    }
+  };
+  for record in records {
+    let separator = if is_first { "" } else { "," };
+    write!(wtr, "{}\n{}", separator, record).map_err(write_err)?;
+    is_first = false;
+  }
+  writeln!(wtr, "\n]").map_err(write_err)?;
+  wtr.flush().map_err(write_err)?;
+
+  Ok(())
+}
+
+/// Serialize a value into JSON. Floats are written in their shortest lossless form, except for the
+/// non-finite ones, which JSON cannot represent and are written as strings such as `"NaN"`
+fn value_to_json(value: &Value) -> Result<String, IOError> {
+  let result = match value {
+    Value::I8(i) => serde_json::to_string(i),
+    Value::I16(i) => serde_json::to_string(i),
+    Value::I32(i) => serde_json::to_string(i),
+    Value::I64(i) => serde_json::to_string(i),
+    Value::I128(i) => serde_json::to_string(i),
+    Value::ISize(i) => serde_json::to_string(i),
+    Value::U8(u) => serde_json::to_string(u),
+    Value::U16(u) => serde_json::to_string(u),
+    Value::U32(u) => serde_json::to_string(u),
+    Value::U64(u) => serde_json::to_string(u),
+    Value::U128(u) => serde_json::to_string(u),
+    Value::USize(u) => serde_json::to_string(u),
+    Value::F32(f) if f.is_finite() => serde_json::to_string(f),
+    Value::F64(f) if f.is_finite() => serde_json::to_string(f),
+    Value::F32(f) => serde_json::to_string(&f.to_string()),
+    Value::F64(f) => serde_json::to_string(&f.to_string()),
+    Value::Bool(b) => serde_json::to_string(b),
+    Value::Char(c) => serde_json::to_string(c),
+    Value::Str(s) => serde_json::to_string(s),
+    Value::String(s) => serde_json::to_string(s),
+    Value::Symbol(_) => serde_json::to_string(value.as_str()),
+    Value::DateTime(d) => serde_json::to_string(&d.to_rfc3339()),
+    Value::Duration(d) => serde_json::to_string(&d.to_string()),
+    Value::Tuple(vs) => {
+      let vs = vs.iter().map(value_to_json).collect::<Result<Vec<_>, _>>()?;
+      return Ok(format!("[{}]", vs.join(",")));
+    }
+    Value::List(vs) => {
+      let vs = vs.iter().map(value_to_json).collect::<Result<Vec<_>, _>>()?;
+      return Ok(format!("[{}]", vs.join(",")));
+    }
+  };
+  result.map_err(|e| IOError::CannotWriteRecord { error: e.to_string() })
+}
+
+/// Store each of the given relations into its own CSV file `dir/<relation>.csv`
+///
+/// The directory is created if it does not exist yet. Failing to store one relation does
+/// not stop the rest from being stored; all the failures are reported together.
+pub fn store_csv_in_directory<'a, I, J>(dir: &Path, relations: I) -> Result<(), IOError>
+where
+  I: Iterator<Item = (&'a str, J)>,
+  J: Iterator<Item = &'a Tuple>,
+{
+  fs::create_dir_all(dir).map_err(|e| IOError::CannotCreateDirectory {
+    dir_path: dir.to_path_buf(),
+    error: format!("{}", e),
+  })?;
+
+  let mut errors = vec![];
+  for (relation, tuples) in relations {
+    let file_path = dir.join(format!("{}.csv", relation));
+    if let Err(e) = store_csv(&file_path, b',', false, tuples) {
+      errors.push((relation.to_string(), e));
+    }
+  }
+
+  if errors.is_empty() {
+    Ok(())
+  } else {
+    Err(IOError::CannotStoreRelations { errors })
+  }
+}
@@ -2,6 +2,7 @@ use std::collections::*;
 
 use crate::compiler::ram::*;
 use crate::runtime::env::*;
+use crate::runtime::error::*;
 use crate::runtime::monitor::*;
 use crate::runtime::provenance::*;
 
@@ -11,9 +12,11 @@ use super::*;
 pub struct DynamicIteration<'a, Prov: Provenance> {
   pub iter_num: usize,
   pub input_dynamic_collections: HashMap<String, &'a DynamicCollection<Prov>>,
+  pub input_dynamic_deltas: HashMap<String, &'a DynamicCollection<Prov>>,
   pub dynamic_relations: HashMap<String, DynamicRelation<Prov>>,
   pub output_relations: Vec<String>,
   pub updates: Vec<Update>,
+  pub seeded: bool,
 }
 
 impl<'a, Prov: Provenance> DynamicIteration<'a, Prov> {
@@ -21,9 +24,11 @@ impl<'a, Prov: Provenance> DynamicIteration<'a, Prov> {
     Self {
       iter_num: 0,
       input_dynamic_collections: HashMap::new(),
+      input_dynamic_deltas: HashMap::new(),
       dynamic_relations: HashMap::new(),
       output_relations: Vec::new(),
       updates: Vec::new(),
+      seeded: false,
     }
   }
 
@@ -31,6 +36,12 @@ impl<'a, Prov: Provenance> DynamicIteration<'a, Prov> {
     self.iter_num == 0
   }
 
+  /// Whether the base facts (inputs, units, and foreign predicate groundings) are new to this iteration; they
+  /// are not when the relations are seeded, as the facts derived from them are already in the seeds
+  fn has_recent_base_facts(&self) -> bool {
+    self.is_first_iteration() && !self.seeded
+  }
+
   pub fn step(&mut self) {
     self.iter_num += 1;
   }
@@ -45,6 +56,30 @@ impl<'a, Prov: Provenance> DynamicIteration<'a, Prov> {
       .insert(name.to_string(), DynamicRelation::<Prov>::new());
   }
 
+  /// Create a dynamic relation seeded with the facts computed by a previous execution; the iteration then only
+  /// derives the facts following from the deltas of the inputs and from the recent facts of the relations
+  pub fn create_seeded_dynamic_relation(&mut self, name: &str, collection: DynamicCollection<Prov>) {
+    self
+      .dynamic_relations
+      .insert(name.to_string(), DynamicRelation::<Prov>::new_seeded(collection));
+    self.seeded = true;
+  }
+
+  /// Create a dynamic relation holding the facts computed by a previous execution, to find the ones derived from
+  /// the deltas of the inputs, which are then the deleted facts of the inputs
+  pub fn create_over_deleting_dynamic_relation(&mut self, name: &str, collection: DynamicCollection<Prov>) {
+    self
+      .dynamic_relations
+      .insert(name.to_string(), DynamicRelation::<Prov>::new_over_deleting(collection));
+    self.seeded = true;
+  }
+
+  /// Add the facts that an input collection gained (or lost, when over-deleting) since the previous execution;
+  /// only used when seeded
+  pub fn add_input_dynamic_delta(&mut self, name: &str, delta: &'a DynamicCollection<Prov>) {
+    self.input_dynamic_deltas.insert(name.to_string(), delta);
+  }
+
   pub fn can_access_relation(&self, name: &str) -> bool {
     self.dynamic_relations.contains_key(name) || self.input_dynamic_collections.contains_key(name)
   }
@@ -72,9 +107,15 @@ impl<'a, Prov: Provenance> DynamicIteration<'a, Prov> {
     self.output_relations.push(name.to_string())
   }
 
-  pub fn run(&'a mut self, ctx: &Prov, runtime: &RuntimeEnvironment) -> HashMap<String, DynamicCollection<Prov>> {
+  pub fn run(
+    &'a mut self,
+    ctx: &Prov,
+    runtime: &RuntimeEnvironment,
+  ) -> Result<HashMap<String, DynamicCollection<Prov>>, RuntimeError> {
     // Iterate until fixpoint
     while self.need_to_iterate(ctx, &runtime.iter_limit) {
+      self.check_relation_size_limit(runtime)?;
+
       // Perform updates
       for update in &self.updates {
         let dyn_update = self.build_dynamic_update(ctx, update);
@@ -87,13 +128,16 @@ impl<'a, Prov: Provenance> DynamicIteration<'a, Prov> {
       self.step();
     }
 
+    // The facts merged when stopping could also exceed the limit
+    self.check_relation_size_limit(runtime)?;
+
     // Generate result
     let mut result = HashMap::new();
     for name in &self.output_relations {
       let col = self.dynamic_relations.remove(name).unwrap().complete(ctx);
       result.insert(name.clone(), col);
     }
-    result
+    Ok(result)
   }
 
   fn need_to_iterate(&mut self, ctx: &Prov, iter_limit: &Option<usize>) -> bool {
@@ -127,14 +171,23 @@ impl<'a, Prov: Provenance> DynamicIteration<'a, Prov> {
     ctx: &Prov,
     runtime: &RuntimeEnvironment,
     m: &M,
-  ) -> HashMap<String, DynamicCollection<Prov>>
+  ) -> Result<HashMap<String, DynamicCollection<Prov>>, RuntimeError>
   where
     M: Monitor<Prov>,
   {
     // Iterate until fixpoint
     while self.need_to_iterate_with_monitor(ctx, &runtime.iter_limit, m) {
+      self.check_relation_size_limit(runtime)?;
+
       // !SPECIAL MONITORING!
       m.observe_stratum_iteration(self.iter_num);
+      self.observe_derived_tuples(m);
+
+      // Stop early if requested by the monitor; merge the recent facts so that the relations can be completed
+      if m.should_stop_iteration(self.iter_num) {
+        self.changed(ctx);
+        break;
+      }
 
       // Perform updates
       for update in &self.updates {
@@ -148,13 +201,30 @@ impl<'a, Prov: Provenance> DynamicIteration<'a, Prov> {
       self.step();
     }
 
+    // The facts merged when stopping could also exceed the limit
+    self.check_relation_size_limit(runtime)?;
+
     // Generate result
     let mut result = HashMap::new();
     for name in &self.output_relations {
       let col = self.dynamic_relations.remove(name).unwrap().complete(ctx);
       result.insert(name.clone(), col);
     }
-    result
+    Ok(result)
+  }
+
+  /// Report the facts that were derived (or had their tag updated) in the last iteration
+  fn observe_derived_tuples<M>(&self, m: &M)
+  where
+    M: Monitor<Prov>,
+  {
+    let mut names = self.dynamic_relations.keys().collect::<Vec<_>>();
+    names.sort();
+    for name in names {
+      for elem in self.dynamic_relations[name].recent.borrow().iter() {
+        m.observe_derived_tuple(name, &elem.tuple, &elem.tag);
+      }
+    }
   }
 
   fn need_to_iterate_with_monitor<M>(&mut self, ctx: &Prov, iter_limit: &Option<usize>, m: &M) -> bool
@@ -192,6 +262,23 @@ impl<'a, Prov: Provenance> DynamicIteration<'a, Prov> {
     return false;
   }
 
+  /// Check that no relation holds more facts than the relation size limit of the runtime, if there is one
+  fn check_relation_size_limit(&self, runtime: &RuntimeEnvironment) -> Result<(), RuntimeError> {
+    if let Some(limit) = runtime.relation_size_limit {
+      let mut names = self.dynamic_relations.keys().collect::<Vec<_>>();
+      names.sort();
+      for name in names {
+        if self.dynamic_relations[name].num_facts() > limit {
+          return Err(RuntimeError::RelationSizeLimitExceeded {
+            relation: name.clone(),
+            limit,
+          });
+        }
+      }
+    }
+    Ok(())
+  }
+
   fn changed(&mut self, ctx: &Prov) -> bool {
     let mut changed = false;
     for (_, relation) in &mut self.dynamic_relations {
@@ -228,7 +315,7 @@ impl<'a, Prov: Provenance> DynamicIteration<'a, Prov> {
   fn build_dynamic_dataflow(&'a self, ctx: &'a Prov, dataflow: &'a Dataflow) -> DynamicDataflow<'a, Prov> {
     match dataflow {
       Dataflow::Unit(t) => {
-        if self.is_first_iteration() {
+        if self.has_recent_base_facts() {
           DynamicDataflow::recent_unit(ctx, t.clone())
         } else {
           DynamicDataflow::stable_unit(ctx, t.clone())
@@ -245,7 +332,7 @@ impl<'a, Prov: Provenance> DynamicIteration<'a, Prov> {
         }
       }
       Dataflow::ForeignPredicateGround(p, a) => {
-        DynamicDataflow::foreign_predicate_ground(p.clone(), a.clone(), self.is_first_iteration(), ctx)
+        DynamicDataflow::foreign_predicate_ground(p.clone(), a.clone(), self.has_recent_base_facts(), ctx)
       }
       Dataflow::ForeignPredicateConstraint(d, p, a) => {
         self.build_dynamic_dataflow(ctx, d).foreign_predicate_constraint(p.clone(), a.clone(), ctx)
@@ -257,7 +344,11 @@ impl<'a, Prov: Provenance> DynamicIteration<'a, Prov> {
       Dataflow::Exclusion(d1, d2) => self.build_dynamic_dataflow(ctx, d1).dynamic_exclusion(self.build_dynamic_dataflow(ctx, d2), ctx),
       Dataflow::Filter(d, e) => self.build_dynamic_dataflow(ctx, d).filter(e.clone()),
       Dataflow::Find(d, k) => self.build_dynamic_dataflow(ctx, d).find(k.clone()),
+      Dataflow::Sort(d, o) => self.build_dynamic_dataflow(ctx, d).sort(o.clone()),
+      Dataflow::Limit(d, n) => self.build_dynamic_dataflow(ctx, d).limit(*n),
+      Dataflow::Distinct(d) => self.build_dynamic_dataflow(ctx, d).distinct(ctx),
       Dataflow::Project(d, e) => self.build_dynamic_dataflow(ctx, d).project(e.clone()),
+      Dataflow::Rename(d, p) => self.build_dynamic_dataflow(ctx, d).rename(p.clone()),
       Dataflow::Intersect(d1, d2) => {
         let r1 = self.build_dynamic_dataflow(ctx, d1);
         let r2 = self.build_dynamic_dataflow(ctx, d2);
@@ -311,7 +402,14 @@ impl<'a, Prov: Provenance> DynamicIteration<'a, Prov> {
 
   fn build_dynamic_collection(&self, r: &str) -> DynamicDataflow<Prov> {
     let col = self.unsafe_get_input_dynamic_collection(r);
-    DynamicDataflow::dynamic_collection(col, self.is_first_iteration())
+    match self.input_dynamic_deltas.get(r) {
+      // The delta of the input is recent in the first iteration; it is also part of the stable collection, which
+      // only leads to deriving some facts twice
+      Some(delta) if self.seeded && self.is_first_iteration() => {
+        DynamicDataflow::dynamic_stable_collection(col).union(DynamicDataflow::dynamic_recent_collection(delta))
+      }
+      _ => DynamicDataflow::dynamic_collection(col, self.has_recent_base_facts()),
+    }
   }
 }
 
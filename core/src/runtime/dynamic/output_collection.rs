@@ -51,6 +51,7 @@ where
   }
 }
 
+#[cfg(not(feature = "stable"))]
 impl<Prov: Provenance> std::fmt::Debug for DynamicOutputCollection<Prov> {
   default fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
     f.write_str("{")?;
@@ -64,6 +65,7 @@ impl<Prov: Provenance> std::fmt::Debug for DynamicOutputCollection<Prov> {
   }
 }
 
+#[cfg(not(feature = "stable"))]
 impl std::fmt::Debug for DynamicOutputCollection<unit::UnitProvenance> {
   fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
     f.write_str("{")?;
@@ -77,6 +79,26 @@ impl std::fmt::Debug for DynamicOutputCollection<unit::UnitProvenance> {
   }
 }
 
+#[cfg(feature = "stable")]
+impl<Prov: Provenance> std::fmt::Debug for DynamicOutputCollection<Prov> {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    let omit_tag = unit::is_unit_provenance::<Prov>();
+    f.write_str("{")?;
+    for (i, (tag, tuple)) in self.elements.iter().enumerate() {
+      if i > 0 {
+        f.write_str(", ")?;
+      }
+      if omit_tag {
+        f.write_fmt(format_args!("{:?}", tuple))?;
+      } else {
+        f.write_fmt(format_args!("{:?}::{:?}", tag, tuple))?;
+      }
+    }
+    f.write_str("}")
+  }
+}
+
+#[cfg(not(feature = "stable"))]
 impl<Prov: Provenance> std::fmt::Display for DynamicOutputCollection<Prov> {
   default fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
     f.write_str("{")?;
@@ -90,6 +112,7 @@ impl<Prov: Provenance> std::fmt::Display for DynamicOutputCollection<Prov> {
   }
 }
 
+#[cfg(not(feature = "stable"))]
 impl std::fmt::Display for DynamicOutputCollection<unit::UnitProvenance> {
   fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
     f.write_str("{")?;
@@ -102,3 +125,22 @@ impl std::fmt::Display for DynamicOutputCollection<unit::UnitProvenance> {
     f.write_str("}")
   }
 }
+
+#[cfg(feature = "stable")]
+impl<Prov: Provenance> std::fmt::Display for DynamicOutputCollection<Prov> {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    let omit_tag = unit::is_unit_provenance::<Prov>();
+    f.write_str("{")?;
+    for (i, (tag, tuple)) in self.elements.iter().enumerate() {
+      if i > 0 {
+        f.write_str(", ")?;
+      }
+      if omit_tag {
+        f.write_fmt(format_args!("{}", tuple))?;
+      } else {
+        f.write_fmt(format_args!("{}::{}", tag, tuple))?;
+      }
+    }
+    f.write_str("}")
+  }
+}
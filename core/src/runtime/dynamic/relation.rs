@@ -16,6 +16,8 @@ pub struct DynamicRelation<Prov: Provenance> {
   pub stable: Rc<RefCell<Vec<DynamicCollection<Prov>>>>,
   pub recent: Rc<RefCell<DynamicCollection<Prov>>>,
   to_add: Rc<RefCell<Vec<DynamicCollection<Prov>>>>,
+  delta: Option<Rc<RefCell<Vec<DynamicCollection<Prov>>>>>,
+  over_deleting: bool,
 }
 
 impl<Prov: Provenance> DynamicRelation<Prov> {
@@ -24,6 +26,30 @@ impl<Prov: Provenance> DynamicRelation<Prov> {
       stable: Rc::new(RefCell::new(Vec::new())),
       recent: Rc::new(RefCell::new(DynamicCollection::empty())),
       to_add: Rc::new(RefCell::new(Vec::new())),
+      delta: None,
+      over_deleting: false,
+    }
+  }
+
+  /// Create a relation seeded with the facts computed by a previous execution; they are stable from the start,
+  /// and the facts that are then derived anew or have their tag updated are tracked as the delta of the relation
+  pub fn new_seeded(collection: DynamicCollection<Prov>) -> Self {
+    Self {
+      stable: Rc::new(RefCell::new(vec![collection])),
+      recent: Rc::new(RefCell::new(DynamicCollection::empty())),
+      to_add: Rc::new(RefCell::new(Vec::new())),
+      delta: Some(Rc::new(RefCell::new(Vec::new()))),
+      over_deleting: false,
+    }
+  }
+
+  /// Create a relation holding the facts computed by a previous execution, to find the ones that are derived from
+  /// deleted facts; the facts stay stable, and the ones that are derived again are tracked as the delta of the
+  /// relation, being recent once
+  pub fn new_over_deleting(collection: DynamicCollection<Prov>) -> Self {
+    Self {
+      over_deleting: true,
+      ..Self::new_seeded(collection)
     }
   }
 
@@ -131,7 +157,16 @@ impl<Prov: Provenance> DynamicRelation<Prov> {
     self.recent.borrow().len()
   }
 
+  /// The number of facts in the relation, not counting the ones that are yet to be merged by `changed`
+  pub fn num_facts(&self) -> usize {
+    self.num_stable() + self.num_recent()
+  }
+
   pub fn changed(&mut self, ctx: &Prov) -> bool {
+    if self.over_deleting {
+      return self.over_deleting_changed(ctx);
+    }
+
     // 1. Merge self.recent into self.stable.
     if !self.recent.borrow().is_empty() {
       let mut recent = ::std::mem::replace(&mut (*self.recent.borrow_mut()), DynamicCollection::empty());
@@ -218,9 +253,46 @@ impl<Prov: Provenance> DynamicRelation<Prov> {
       *self.recent.borrow_mut() = to_add;
     }
 
+    // Track the recent facts of a seeded relation
+    if let Some(delta) = &self.delta {
+      if !self.recent.borrow().is_empty() {
+        delta.borrow_mut().push(self.recent.borrow().clone());
+      }
+    }
+
+    !self.recent.borrow().is_empty()
+  }
+
+  fn over_deleting_changed(&mut self, ctx: &Prov) -> bool {
+    let mut to_add = DynamicCollection::empty();
+    while let Some(to_add_more) = self.to_add.borrow_mut().pop() {
+      to_add = to_add.merge(to_add_more, ctx);
+    }
+
+    // Only the facts of the relation that are not yet derived again are recent
+    let delta = self.delta.as_ref().unwrap();
+    to_add.elements.retain(|elem| {
+      self.stable.borrow().iter().any(|batch| batch.find(&elem.tuple).is_some())
+        && delta.borrow().iter().all(|batch| batch.find(&elem.tuple).is_none())
+    });
+    if !to_add.is_empty() {
+      delta.borrow_mut().push(to_add.clone());
+    }
+    *self.recent.borrow_mut() = to_add;
     !self.recent.borrow().is_empty()
   }
 
+  /// Get the facts of a seeded relation that are derived anew or have their tag updated since it is seeded
+  pub fn delta(&self, ctx: &Prov) -> DynamicCollection<Prov> {
+    let mut result = DynamicCollection::empty();
+    if let Some(delta) = &self.delta {
+      while let Some(batch) = delta.borrow_mut().pop() {
+        result = result.merge(batch, ctx);
+      }
+    }
+    result
+  }
+
   pub fn insert_dataflow_recent<'a>(&self, ctx: &Prov, d: &DynamicDataflow<'a, Prov>, runtime: &'a RuntimeEnvironment) {
     for batch in d.iter_recent(runtime) {
       let data = if runtime.early_discard {
@@ -243,13 +315,73 @@ impl<Prov: Provenance> DynamicRelation<Prov> {
     }
   }
 
+  /// Complete the relation into a single collection, merging all the stable batches at once (see
+  /// `complete_streaming`)
   pub fn complete(&self, ctx: &Prov) -> DynamicCollection<Prov> {
+    let elems = self.complete_streaming(ctx).map(|(tag, tup)| DynamicElement::new(tup, tag));
+    DynamicCollection::from_vec_unchecked(elems.collect())
+  }
+
+  /// Complete the relation lazily; the stable batches are merged while iterating, so that the
+  /// completed relation is never materialized as a whole
+  pub fn complete_streaming<'a>(&self, ctx: &'a Prov) -> DynamicCompleteStream<'a, Prov> {
     assert!(self.recent.borrow().is_empty());
     assert!(self.to_add.borrow().is_empty());
-    let mut result = DynamicCollection::empty();
-    while let Some(batch) = self.stable.borrow_mut().pop() {
-      result = result.merge(batch, ctx);
+    let batches = std::mem::take(&mut *self.stable.borrow_mut());
+    DynamicCompleteStream::new(batches, ctx)
+  }
+}
+
+/// An iterator over the completed elements of a relation, in order, produced by a k-way merge
+/// of its sorted stable batches; tags of the same tuple from different batches are added together
+pub struct DynamicCompleteStream<'a, Prov: Provenance> {
+  ctx: &'a Prov,
+  batches: Vec<std::vec::IntoIter<DynamicElement<Prov>>>,
+  heads: BinaryHeap<std::cmp::Reverse<(Tuple, usize)>>,
+  head_tags: Vec<Option<Prov::Tag>>,
+}
+
+impl<'a, Prov: Provenance> DynamicCompleteStream<'a, Prov> {
+  fn new(batches: Vec<DynamicCollection<Prov>>, ctx: &'a Prov) -> Self {
+    let mut stream = Self {
+      ctx,
+      head_tags: batches.iter().map(|_| None).collect(),
+      batches: batches.into_iter().map(|b| b.elements.into_iter()).collect(),
+      heads: BinaryHeap::new(),
+    };
+    for i in 0..stream.batches.len() {
+      stream.advance(i);
     }
-    result
+    stream
+  }
+
+  /// Move the next element of the `i`-th batch into the heads
+  fn advance(&mut self, i: usize) {
+    if let Some(elem) = self.batches[i].next() {
+      self.head_tags[i] = Some(elem.tag);
+      self.heads.push(std::cmp::Reverse((elem.tuple, i)));
+    }
+  }
+}
+
+impl<'a, Prov: Provenance> Iterator for DynamicCompleteStream<'a, Prov> {
+  type Item = (Prov::Tag, Tuple);
+
+  fn next(&mut self) -> Option<Self::Item> {
+    let std::cmp::Reverse((tuple, i)) = self.heads.pop()?;
+    let mut tag = self.head_tags[i].take().unwrap();
+    self.advance(i);
+
+    // Merge the same tuple coming from other batches
+    while let Some(std::cmp::Reverse((next_tuple, _))) = self.heads.peek() {
+      if next_tuple != &tuple {
+        break;
+      }
+      let std::cmp::Reverse((_, j)) = self.heads.pop().unwrap();
+      tag = self.ctx.add(&tag, &self.head_tags[j].take().unwrap());
+      self.advance(j);
+    }
+
+    Some((tag, tuple))
   }
 }
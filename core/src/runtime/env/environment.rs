@@ -7,7 +7,9 @@ use crate::common::constants::*;
 use crate::common::expr::*;
 use crate::common::foreign_function::*;
 use crate::common::foreign_predicate::*;
+use crate::common::symbol;
 use crate::common::tuple::*;
+use crate::common::value::Value;
 use crate::common::value_type::*;
 use crate::utils::*;
 
@@ -25,6 +27,19 @@ pub struct RuntimeEnvironment {
   /// Iteration count
   pub iter_limit: Option<usize>,
 
+  /// The maximum number of facts a relation may hold during execution; exceeding it aborts the execution
+  pub relation_size_limit: Option<usize>,
+
+  /// Whether to print the tags of the facts as they are tagged, derived and recovered, through a `DebugTagsMonitor`
+  /// running along with the given monitor, for debugging provenances
+  pub debug_tags: bool,
+
+  /// Whether to sort the groundings of foreign predicates, so that the facts they produce are tagged in a reproducible order
+  pub deterministic: bool,
+
+  /// Whether to use compensated (Kahan) summation in the floating point `sum`, `variance` and `stddev` aggregations
+  pub stable_float_aggregation: bool,
+
   /// Foreign function registry
   pub function_registry: ForeignFunctionRegistry,
 
@@ -48,6 +63,10 @@ impl RuntimeEnvironment {
       rng: Arc::new(Mutex::new(SmallRng::seed_from_u64(DEFAULT_RANDOM_SEED))),
       early_discard: true,
       iter_limit: None,
+      relation_size_limit: None,
+      debug_tags: false,
+      deterministic: false,
+      stable_float_aggregation: false,
       function_registry: ForeignFunctionRegistry::std(),
       predicate_registry: ForeignPredicateRegistry::std(),
       exclusion_id_allocator: Arc::new(Mutex::new(IdAllocator::new())),
@@ -60,6 +79,10 @@ impl RuntimeEnvironment {
       rng: Arc::new(Mutex::new(SmallRng::seed_from_u64(seed))),
       early_discard: true,
       iter_limit: None,
+      relation_size_limit: None,
+      debug_tags: false,
+      deterministic: false,
+      stable_float_aggregation: false,
       function_registry: ForeignFunctionRegistry::std(),
       predicate_registry: ForeignPredicateRegistry::std(),
       exclusion_id_allocator: Arc::new(Mutex::new(IdAllocator::new())),
@@ -75,6 +98,10 @@ impl RuntimeEnvironment {
       rng: Arc::new(Mutex::new(SmallRng::seed_from_u64(DEFAULT_RANDOM_SEED))),
       early_discard: true,
       iter_limit: None,
+      relation_size_limit: None,
+      debug_tags: false,
+      deterministic: false,
+      stable_float_aggregation: false,
       function_registry: ffr,
       predicate_registry: fpr,
       exclusion_id_allocator: Arc::new(Mutex::new(IdAllocator::new())),
@@ -87,6 +114,10 @@ impl RuntimeEnvironment {
       rng: Arc::new(Mutex::new(SmallRng::seed_from_u64(DEFAULT_RANDOM_SEED))),
       early_discard: true,
       iter_limit: None,
+      relation_size_limit: None,
+      debug_tags: false,
+      deterministic: false,
+      stable_float_aggregation: false,
       function_registry: ffr,
       predicate_registry: ForeignPredicateRegistry::std(),
       exclusion_id_allocator: Arc::new(Mutex::new(IdAllocator::new())),
@@ -97,6 +128,18 @@ impl RuntimeEnvironment {
     self.early_discard = early_discard
   }
 
+  pub fn set_debug_tags(&mut self, debug_tags: bool) {
+    self.debug_tags = debug_tags
+  }
+
+  pub fn set_deterministic(&mut self, deterministic: bool) {
+    self.deterministic = deterministic
+  }
+
+  pub fn set_stable_float_aggregation(&mut self, stable_float_aggregation: bool) {
+    self.stable_float_aggregation = stable_float_aggregation
+  }
+
   pub fn set_iter_limit(&mut self, k: usize) {
     self.iter_limit = Some(k);
   }
@@ -105,6 +148,38 @@ impl RuntimeEnvironment {
     self.iter_limit = None;
   }
 
+  pub fn set_relation_size_limit(&mut self, k: usize) {
+    self.relation_size_limit = Some(k);
+  }
+
+  pub fn remove_relation_size_limit(&mut self) {
+    self.relation_size_limit = None;
+  }
+
+  /// Intern a string as a symbol value
+  ///
+  /// The symbols are shared by all the environments, so that the string constants interned when
+  /// compiling a program are equal to the same strings interned while loading its input files.
+  pub fn intern_symbol(&self, s: &str) -> Value {
+    Value::symbol(s)
+  }
+
+  /// Get the string of an interned symbol
+  pub fn symbol_string(&self, id: u32) -> &'static str {
+    symbol::resolve(id)
+  }
+
+  /// Set the random seed and reset the random number generator accordingly
+  pub fn set_random_seed(&mut self, seed: u64) {
+    self.random_seed = seed;
+    self.rng = Arc::new(Mutex::new(SmallRng::seed_from_u64(seed)));
+  }
+
+  /// Get exclusive access to the random number generator
+  pub fn rng(&self) -> MutexGuard<'_, SmallRng> {
+    self.rng.lock().unwrap()
+  }
+
   pub fn allocate_new_exclusion_id(&self) -> usize {
     self.exclusion_id_allocator.lock().unwrap().alloc()
   }
@@ -125,7 +200,10 @@ impl RuntimeEnvironment {
 
   pub fn eval_binary(&self, expr: &BinaryExpr, v: &Tuple) -> Option<Tuple> {
     use crate::common::binary_op::BinaryOp::*;
-    use crate::common::value::Value::*;
+    use crate::common::value::Value::{
+      Bool, Char, DateTime, Duration, ISize, Str, String, Symbol, USize, F32, F64, I128, I16, I32, I64, I8, U128, U16,
+      U32, U64, U8,
+    };
 
     // Recursively evaluate sub-expressions
     let lhs_v = self.eval(&expr.op1, v)?;
@@ -267,6 +345,7 @@ impl RuntimeEnvironment {
       (Eq, Tuple::Value(Bool(i1)), Tuple::Value(Bool(i2))) => Tuple::Value(Bool(i1 == i2)),
       (Eq, Tuple::Value(Str(i1)), Tuple::Value(Str(i2))) => Tuple::Value(Bool(i1 == i2)),
       (Eq, Tuple::Value(String(i1)), Tuple::Value(String(i2))) => Tuple::Value(Bool(i1 == i2)),
+      (Eq, Tuple::Value(Symbol(i1)), Tuple::Value(Symbol(i2))) => Tuple::Value(Bool(i1 == i2)),
       // (Eq, Tuple::Value(RcString(i1)), Tuple::Value(RcString(i2))) => Tuple::Value(Bool(i1 == i2)),
       (Eq, Tuple::Value(DateTime(i1)), Tuple::Value(DateTime(i2))) => Tuple::Value(Bool(i1 == i2)),
       (Eq, Tuple::Value(Duration(i1)), Tuple::Value(Duration(i2))) => Tuple::Value(Bool(i1 == i2)),
@@ -291,6 +370,7 @@ impl RuntimeEnvironment {
       (Neq, Tuple::Value(Bool(i1)), Tuple::Value(Bool(i2))) => Tuple::Value(Bool(i1 != i2)),
       (Neq, Tuple::Value(Str(i1)), Tuple::Value(Str(i2))) => Tuple::Value(Bool(i1 != i2)),
       (Neq, Tuple::Value(String(i1)), Tuple::Value(String(i2))) => Tuple::Value(Bool(i1 != i2)),
+      (Neq, Tuple::Value(Symbol(i1)), Tuple::Value(Symbol(i2))) => Tuple::Value(Bool(i1 != i2)),
       // (Neq, Tuple::Value(RcString(i1)), Tuple::Value(RcString(i2))) => Tuple::Value(Bool(i1 != i2)),
       (Neq, Tuple::Value(DateTime(i1)), Tuple::Value(DateTime(i2))) => Tuple::Value(Bool(i1 != i2)),
       (Neq, Tuple::Value(Duration(i1)), Tuple::Value(Duration(i2))) => Tuple::Value(Bool(i1 != i2)),
@@ -377,7 +457,9 @@ impl RuntimeEnvironment {
 
   pub fn eval_unary(&self, expr: &UnaryExpr, v: &Tuple) -> Option<Tuple> {
     use crate::common::unary_op::UnaryOp::*;
-    use crate::common::value::Value::*;
+    use crate::common::value::Value::{
+      Bool, Char, ISize, Str, String, Symbol, USize, F32, F64, I128, I16, I32, I64, I8, U128, U16, U32, U64, U8,
+    };
 
     let arg_v = self.eval(&expr.op1, v)?;
     match (&expr.op, arg_v) {
@@ -448,6 +530,44 @@ impl RuntimeEnvironment {
           (Tuple::Value(USize(i)), T::F32) => Some(Tuple::Value(F32(i as f32))),
           (Tuple::Value(USize(i)), T::F64) => Some(Tuple::Value(F64(i as f64))),
 
+          // Widening casts, needed when numeric values of different types are coerced to a common type
+          (Tuple::Value(I16(i)), T::I32) => Some(Tuple::Value(I32(i as i32))),
+          (Tuple::Value(I16(i)), T::I64) => Some(Tuple::Value(I64(i as i64))),
+          (Tuple::Value(I16(i)), T::I128) => Some(Tuple::Value(I128(i as i128))),
+          (Tuple::Value(I16(i)), T::F64) => Some(Tuple::Value(F64(i as f64))),
+          (Tuple::Value(I64(i)), T::I128) => Some(Tuple::Value(I128(i as i128))),
+          (Tuple::Value(I64(i)), T::F64) => Some(Tuple::Value(F64(i as f64))),
+          (Tuple::Value(ISize(i)), T::I64) => Some(Tuple::Value(I64(i as i64))),
+          (Tuple::Value(ISize(i)), T::I128) => Some(Tuple::Value(I128(i as i128))),
+          (Tuple::Value(ISize(i)), T::F64) => Some(Tuple::Value(F64(i as f64))),
+          (Tuple::Value(I128(i)), T::F64) => Some(Tuple::Value(F64(i as f64))),
+          (Tuple::Value(U8(i)), T::I16) => Some(Tuple::Value(I16(i as i16))),
+          (Tuple::Value(U8(i)), T::I32) => Some(Tuple::Value(I32(i as i32))),
+          (Tuple::Value(U8(i)), T::I64) => Some(Tuple::Value(I64(i as i64))),
+          (Tuple::Value(U8(i)), T::I128) => Some(Tuple::Value(I128(i as i128))),
+          (Tuple::Value(U8(i)), T::U16) => Some(Tuple::Value(U16(i as u16))),
+          (Tuple::Value(U8(i)), T::U32) => Some(Tuple::Value(U32(i as u32))),
+          (Tuple::Value(U8(i)), T::U64) => Some(Tuple::Value(U64(i as u64))),
+          (Tuple::Value(U8(i)), T::U128) => Some(Tuple::Value(U128(i as u128))),
+          (Tuple::Value(U8(i)), T::F64) => Some(Tuple::Value(F64(i as f64))),
+          (Tuple::Value(U16(i)), T::I32) => Some(Tuple::Value(I32(i as i32))),
+          (Tuple::Value(U16(i)), T::I64) => Some(Tuple::Value(I64(i as i64))),
+          (Tuple::Value(U16(i)), T::I128) => Some(Tuple::Value(I128(i as i128))),
+          (Tuple::Value(U16(i)), T::U32) => Some(Tuple::Value(U32(i as u32))),
+          (Tuple::Value(U16(i)), T::U64) => Some(Tuple::Value(U64(i as u64))),
+          (Tuple::Value(U16(i)), T::U128) => Some(Tuple::Value(U128(i as u128))),
+          (Tuple::Value(U16(i)), T::F64) => Some(Tuple::Value(F64(i as f64))),
+          (Tuple::Value(U32(i)), T::I64) => Some(Tuple::Value(I64(i as i64))),
+          (Tuple::Value(U32(i)), T::I128) => Some(Tuple::Value(I128(i as i128))),
+          (Tuple::Value(U32(i)), T::U64) => Some(Tuple::Value(U64(i as u64))),
+          (Tuple::Value(U32(i)), T::U128) => Some(Tuple::Value(U128(i as u128))),
+          (Tuple::Value(U32(i)), T::F64) => Some(Tuple::Value(F64(i as f64))),
+          (Tuple::Value(U64(i)), T::I128) => Some(Tuple::Value(I128(i as i128))),
+          (Tuple::Value(U64(i)), T::U128) => Some(Tuple::Value(U128(i as u128))),
+          (Tuple::Value(U64(i)), T::F64) => Some(Tuple::Value(F64(i as f64))),
+          (Tuple::Value(U128(i)), T::F64) => Some(Tuple::Value(F64(i as f64))),
+          (Tuple::Value(F32(f)), T::F64) => Some(Tuple::Value(F64(f as f64))),
+
           (Tuple::Value(Char(s)), T::I8) => s.to_digit(10).map(|i| Tuple::Value(I8(i as i8))),
           (Tuple::Value(Char(s)), T::I16) => s.to_digit(10).map(|i| Tuple::Value(I16(i as i16))),
           (Tuple::Value(Char(s)), T::I32) => s.to_digit(10).map(|i| Tuple::Value(I32(i as i32))),
@@ -495,6 +615,12 @@ impl RuntimeEnvironment {
           (Tuple::Value(Str(s)), T::String) => Some(Tuple::Value(String(s.to_string()))),
           (Tuple::Value(String(s)), T::String) => Some(Tuple::Value(String(s.clone()))),
 
+          // Symbols are interned from strings, and recovered as strings
+          (Tuple::Value(Str(s)), T::Symbol) => Some(Tuple::Value(self.intern_symbol(s))),
+          (Tuple::Value(String(s)), T::Symbol) => Some(Tuple::Value(self.intern_symbol(&s))),
+          (Tuple::Value(Symbol(s)), T::Symbol) => Some(Tuple::Value(Symbol(s))),
+          (Tuple::Value(Symbol(s)), T::String) => Some(Tuple::Value(String(self.symbol_string(s).to_string()))),
+
           // Not implemented
           (v, t) => unimplemented!("Unimplemented type cast from `{:?}` to `{}`", v.tuple_type(), t),
         }
@@ -520,11 +646,45 @@ impl RuntimeEnvironment {
         .map(|a| self.eval(a, v).map(|t| t.as_value()))
         .collect::<Option<Vec<_>>>()?;
 
-      // Run the function
-      let result = f.execute(args)?;
+      // Run the function; non-deterministic functions draw from the environment's random number generator
+      let result = if f.is_deterministic() {
+        f.execute(args)?
+      } else {
+        f.execute_with_rng(args, &mut *self.rng())?
+      };
 
       // Turn result into tuple
       Some(Tuple::Value(result))
     })
   }
+
+  /// Evaluate a function call on a batch of tuples, producing one result per tuple
+  ///
+  /// Deterministic functions are executed once on the whole batch through `execute_batch`;
+  /// the tuples whose arguments cannot be evaluated are not passed to the function.
+  pub fn eval_call_batch<'t, I: Iterator<Item = &'t Tuple>>(&self, expr: &CallExpr, tuples: I) -> Vec<Option<Value>> {
+    let f = match self.function_registry.get(&expr.function) {
+      Some(f) if f.is_deterministic() => f,
+      _ => return tuples.map(|t| self.eval_call(expr, t).map(|r| r.as_value())).collect(),
+    };
+
+    // Evaluate the arguments of every tuple
+    let args = tuples
+      .map(|t| {
+        expr
+          .args
+          .iter()
+          .map(|a| self.eval(a, t).map(|t| t.as_value()))
+          .collect::<Option<Vec<_>>>()
+      })
+      .collect::<Vec<_>>();
+
+    // Run the function on the rows whose arguments are all successfully evaluated
+    let mut results = vec![None; args.len()];
+    let (ids, rows): (Vec<_>, Vec<_>) = args.into_iter().enumerate().filter_map(|(i, a)| Some((i, a?))).unzip();
+    for (i, result) in ids.into_iter().zip(f.execute_batch(&rows)) {
+      results[i] = result;
+    }
+    results
+  }
 }
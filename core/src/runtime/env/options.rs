@@ -16,6 +16,10 @@ pub struct RuntimeEnvironmentOptions {
   pub random_seed: u64,
   pub early_discard: bool,
   pub iter_limit: Option<usize>,
+  pub relation_size_limit: Option<usize>,
+  pub debug_tags: bool,
+  pub deterministic: bool,
+  pub stable_float_aggregation: bool,
 }
 
 impl Default for RuntimeEnvironmentOptions {
@@ -30,6 +34,10 @@ impl RuntimeEnvironmentOptions {
       random_seed: DEFAULT_RANDOM_SEED,
       early_discard: true,
       iter_limit: None,
+      relation_size_limit: None,
+      debug_tags: false,
+      deterministic: false,
+      stable_float_aggregation: false,
     }
   }
 
@@ -41,6 +49,10 @@ impl RuntimeEnvironmentOptions {
       rng: Arc::new(Mutex::new(rng)),
       early_discard: self.early_discard,
       iter_limit: self.iter_limit,
+      relation_size_limit: self.relation_size_limit,
+      debug_tags: self.debug_tags,
+      deterministic: self.deterministic,
+      stable_float_aggregation: self.stable_float_aggregation,
       function_registry: ForeignFunctionRegistry::std(),
       predicate_registry: ForeignPredicateRegistry::std(),
       exclusion_id_allocator: Arc::new(Mutex::new(IdAllocator::new())),
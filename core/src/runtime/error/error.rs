@@ -9,6 +9,7 @@ pub enum RuntimeError {
   ForeignFunction(ForeignFunctionError),
   ForeignPredicate(ForeignPredicateError),
   Database(DatabaseError),
+  RelationSizeLimitExceeded { relation: String, limit: usize },
 }
 
 impl std::fmt::Display for RuntimeError {
@@ -18,6 +19,10 @@ impl std::fmt::Display for RuntimeError {
       Self::ForeignFunction(e) => e.fmt(f),
       Self::ForeignPredicate(e) => e.fmt(f),
       Self::Database(e) => e.fmt(f),
+      Self::RelationSizeLimitExceeded { relation, limit } => f.write_fmt(format_args!(
+        "Relation `{}` exceeds the size limit of {} facts",
+        relation, limit
+      )),
     }
   }
 }
@@ -5,14 +5,55 @@ use crate::common::value_type::ValueParseError;
 
 #[derive(Clone, Debug)]
 pub enum IOError {
-  CannotOpenFile { file_path: PathBuf, error: String },
-  CannotReadFile { error: String },
-  CannotParseCSV { error: String },
-  InvalidType { types: TupleType },
-  ValueParseError { error: ValueParseError },
-  CannotParseProbability { value: String },
-  ArityMismatch { expected: usize, found: usize },
-  CannotWriteRecord { error: String },
+  CannotOpenFile {
+    file_path: PathBuf,
+    error: String,
+  },
+  CannotReadFile {
+    error: String,
+  },
+  CannotParseCSV {
+    error: String,
+  },
+  MissingCSVColumn {
+    column: String,
+    header: Vec<String>,
+  },
+  CannotParseJSON {
+    error: String,
+  },
+  InvalidDeliminatorRegex {
+    regex: String,
+    error: String,
+  },
+  InvalidType {
+    types: TupleType,
+  },
+  /// A field cannot be parsed as a value; `row` and `col` are 1-based, with `row` counting the lines
+  /// of the file (header included) for CSV files and the records for JSON files
+  ValueParseError {
+    row: usize,
+    col: usize,
+    error: ValueParseError,
+  },
+  CannotParseProbability {
+    value: String,
+  },
+  ArityMismatch {
+    row: usize,
+    expected: usize,
+    found: usize,
+  },
+  CannotWriteRecord {
+    error: String,
+  },
+  CannotCreateDirectory {
+    dir_path: PathBuf,
+    error: String,
+  },
+  CannotStoreRelations {
+    errors: Vec<(String, IOError)>,
+  },
 }
 
 impl std::fmt::Display for IOError {
@@ -25,14 +66,37 @@ impl std::fmt::Display for IOError {
       )),
       Self::CannotReadFile { error } => f.write_fmt(format_args!("IO: Cannot read file: {}", error)),
       Self::CannotParseCSV { error } => f.write_fmt(format_args!("IO: Cannot parse CSV: {}", error)),
+      Self::MissingCSVColumn { column, header } => f.write_fmt(format_args!(
+        "IO: Column `{}` is not found in the CSV header [{}]",
+        column,
+        header.join(", ")
+      )),
+      Self::CannotParseJSON { error } => f.write_fmt(format_args!("IO: Cannot parse JSON: {}", error)),
+      Self::InvalidDeliminatorRegex { regex, error } => {
+        f.write_fmt(format_args!("IO: Invalid deliminator regex `{}`: {}", regex, error))
+      }
       Self::InvalidType { types } => f.write_fmt(format_args!("IO: Invalid tuple type: `{}`", types)),
-      Self::ValueParseError { error } => std::fmt::Display::fmt(error, f),
+      Self::ValueParseError { row, col, error } => {
+        f.write_fmt(format_args!("IO: Row {}, column {}: {}", row, col, error))
+      }
       Self::CannotParseProbability { value } => f.write_fmt(format_args!("IO: Cannot parse probability `{}`", value)),
-      Self::ArityMismatch { expected, found } => f.write_fmt(format_args!(
-        "IO: Arity mismatch; expected {}, found {}",
-        expected, found
+      Self::ArityMismatch { row, expected, found } => f.write_fmt(format_args!(
+        "IO: Arity mismatch at row {}; expected {}, found {}",
+        row, expected, found
       )),
       Self::CannotWriteRecord { error } => f.write_fmt(format_args!("IO: Cannot write record: {}", error)),
+      Self::CannotCreateDirectory { dir_path, error } => f.write_fmt(format_args!(
+        "IO: Cannot create directory `{}`: {}",
+        dir_path.as_os_str().to_string_lossy(),
+        error
+      )),
+      Self::CannotStoreRelations { errors } => {
+        f.write_fmt(format_args!("IO: Cannot store {} relation(s):", errors.len()))?;
+        for (relation, error) in errors {
+          f.write_fmt(format_args!("\n  `{}`: {}", relation, error))?;
+        }
+        Ok(())
+      }
     }
   }
 }
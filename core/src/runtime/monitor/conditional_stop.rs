@@ -0,0 +1,29 @@
+use std::cell::RefCell;
+
+use crate::runtime::provenance::Provenance;
+
+use super::*;
+
+/// Conditional Stop Monitor
+///
+/// A monitor which gracefully stops the fixpoint iteration of a stratum as soon as the given
+/// predicate on the iteration count returns `true`. Different from `IterationCheckingMonitor`,
+/// no error is raised; the facts derived so far are kept as the result of the stratum, and
+/// the execution continues with the next stratum.
+pub struct ConditionalStopMonitor<F: FnMut(usize) -> bool> {
+  predicate: RefCell<F>,
+}
+
+impl<F: FnMut(usize) -> bool> ConditionalStopMonitor<F> {
+  pub fn new(predicate: F) -> Self {
+    Self {
+      predicate: RefCell::new(predicate),
+    }
+  }
+}
+
+impl<F: FnMut(usize) -> bool, Prov: Provenance> Monitor<Prov> for ConditionalStopMonitor<F> {
+  fn should_stop_iteration(&self, iteration_count: usize) -> bool {
+    (self.predicate.borrow_mut())(iteration_count)
+  }
+}
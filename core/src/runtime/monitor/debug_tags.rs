@@ -14,6 +14,10 @@ impl<Prov: Provenance> Monitor<Prov> for DebugTagsMonitor {
     println!("[Tagging] Tuple: {}, Input Tag: {:?} -> Tag: {:?}", tup, input_tag, tag)
   }
 
+  fn observe_derived_tuple(&self, relation: &str, tup: &Tuple, tag: &Prov::Tag) {
+    println!("[Derived] Relation: {}, Tuple: {} -> Tag: {:?}", relation, tup, tag)
+  }
+
   fn observe_recovering_relation(&self, relation: &str) {
     println!("[Recover Relation] {}", relation)
   }
@@ -40,6 +40,15 @@ macro_rules! dynamic_monitors_observe_event {
 impl<Prov: Provenance> Monitor<Prov> for DynamicMonitors<Prov> {
   dynamic_monitors_observe_event!(observe_executing_stratum, (stratum_id: usize));
   dynamic_monitors_observe_event!(observe_stratum_iteration, (iteration_count: usize));
+
+  fn should_stop_iteration(&self, iteration_count: usize) -> bool {
+    // Every monitor is asked, so that stateful predicates observe all iterations
+    self
+      .monitors
+      .iter()
+      .fold(false, |stop, m| m.should_stop_iteration(iteration_count) | stop)
+  }
+
   dynamic_monitors_observe_event!(observe_hitting_iteration_limit, ());
   dynamic_monitors_observe_event!(observe_converging, ());
   dynamic_monitors_observe_event!(observe_loading_relation, (relation: &str));
@@ -49,6 +58,10 @@ impl<Prov: Provenance> Monitor<Prov> for DynamicMonitors<Prov> {
     observe_tagging,
     (tup: &Tuple, input_tag: &Option<Prov::InputTag>, tag: &Prov::Tag)
   );
+  dynamic_monitors_observe_event!(
+    observe_derived_tuple,
+    (relation: &str, tup: &Tuple, tag: &Prov::Tag)
+  );
   dynamic_monitors_observe_event!(observe_recovering_relation, (relation: &str));
   dynamic_monitors_observe_event!(
     observe_recover,
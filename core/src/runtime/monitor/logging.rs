@@ -1,5 +1,6 @@
 use colored::*;
 
+use crate::common::tuple::Tuple;
 use crate::runtime::provenance::Provenance;
 
 use super::*;
@@ -37,6 +38,10 @@ impl<Prov: Provenance> Monitor<Prov> for LoggingMonitor {
     self.info(&format!("loading relation `{}` from IDB", relation))
   }
 
+  fn observe_derived_tuple(&self, relation: &str, tup: &Tuple, tag: &Prov::Tag) {
+    self.info(&format!("derived `{}{}` with tag {:?}", relation, tup, tag))
+  }
+
   fn observe_recovering_relation(&self, relation: &str) {
     self.info(&format!("recovering relation `{}`", relation))
   }
@@ -1,13 +1,21 @@
+mod conditional_stop;
 mod debug_runtime;
 mod debug_tags;
 mod dynamic_monitors;
 mod iteration_checker;
 mod logging;
 mod monitor;
+mod output_callback;
+mod statistics;
+mod tuple_trace;
 
+pub use conditional_stop::*;
 pub use debug_runtime::*;
 pub use debug_tags::*;
 pub use dynamic_monitors::*;
 pub use iteration_checker::*;
 pub use logging::*;
 pub use monitor::*;
+pub use output_callback::*;
+pub use statistics::*;
+pub use tuple_trace::*;
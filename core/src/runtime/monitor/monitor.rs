@@ -10,6 +10,13 @@ pub trait Monitor<Prov: Provenance> {
   #[allow(unused_variables)]
   fn observe_stratum_iteration(&self, iteration_count: usize) {}
 
+  /// Decide whether the fixpoint iteration of the current stratum should stop early; checked right
+  /// after `observe_stratum_iteration`. When stopped, the facts computed so far are kept as the result
+  #[allow(unused_variables)]
+  fn should_stop_iteration(&self, iteration_count: usize) -> bool {
+    false
+  }
+
   /// Observe hitting iteration limit
   #[allow(unused_variables)]
   fn observe_hitting_iteration_limit(&self) {}
@@ -34,6 +41,11 @@ pub trait Monitor<Prov: Provenance> {
   #[allow(unused_variables)]
   fn observe_tagging(&self, tup: &Tuple, input_tag: &Option<Prov::InputTag>, tag: &Prov::Tag) {}
 
+  /// Observe a tuple being inserted into a relation during the fixpoint iteration of a stratum, either as
+  /// a new fact or as an existing fact whose tag is updated
+  #[allow(unused_variables)]
+  fn observe_derived_tuple(&self, relation: &str, tup: &Tuple, tag: &Prov::Tag) {}
+
   /// Observe recovering output tags of a relation
   #[allow(unused_variables)]
   fn observe_recovering_relation(&self, relation: &str) {}
@@ -45,6 +57,31 @@ pub trait Monitor<Prov: Provenance> {
 
 impl<Prov: Provenance> Monitor<Prov> for () {}
 
+macro_rules! monitor_forward_event {
+  ($func:ident, ($($arg:ident: $ty:ty),*)) => {
+    fn $func(&self, $($arg : $ty,)*) {
+      (**self).$func($($arg),*)
+    }
+  };
+}
+
+impl<'a, M: Monitor<Prov>, Prov: Provenance> Monitor<Prov> for &'a M {
+  monitor_forward_event!(observe_executing_stratum, (stratum_id: usize));
+  monitor_forward_event!(observe_stratum_iteration, (iteration_count: usize));
+  fn should_stop_iteration(&self, iteration_count: usize) -> bool {
+    (**self).should_stop_iteration(iteration_count)
+  }
+  monitor_forward_event!(observe_hitting_iteration_limit, ());
+  monitor_forward_event!(observe_converging, ());
+  monitor_forward_event!(observe_loading_relation, (relation: &str));
+  monitor_forward_event!(observe_loading_relation_from_edb, (relation: &str));
+  monitor_forward_event!(observe_loading_relation_from_idb, (relation: &str));
+  monitor_forward_event!(observe_tagging, (tup: &Tuple, input_tag: &Option<Prov::InputTag>, tag: &Prov::Tag));
+  monitor_forward_event!(observe_derived_tuple, (relation: &str, tup: &Tuple, tag: &Prov::Tag));
+  monitor_forward_event!(observe_recovering_relation, (relation: &str));
+  monitor_forward_event!(observe_recover, (tup: &Tuple, tag: &Prov::Tag, output_tag: &Prov::OutputTag));
+}
+
 macro_rules! monitor_observe_event {
   ($func:ident, ($($arg:ident),*), $elem:ident) => {
     $elem.$func( $($arg),* );
@@ -71,12 +108,18 @@ macro_rules! impl_monitor {
     {
       monitor_observe_event!(observe_executing_stratum, ($($elem),*), (stratum_id: usize));
       monitor_observe_event!(observe_stratum_iteration, ($($elem),*), (iteration_count: usize));
+      fn should_stop_iteration(&self, iteration_count: usize) -> bool {
+        #[allow(non_snake_case)]
+        let ($( $elem,)*) = self;
+        false $( | $elem.should_stop_iteration(iteration_count) )*
+      }
       monitor_observe_event!(observe_hitting_iteration_limit, ($($elem),*), ());
       monitor_observe_event!(observe_converging, ($($elem),*), ());
       monitor_observe_event!(observe_loading_relation, ($($elem),*), (relation: &str));
       monitor_observe_event!(observe_loading_relation_from_edb, ($($elem),*), (relation: &str));
       monitor_observe_event!(observe_loading_relation_from_idb, ($($elem),*), (relation: &str));
       monitor_observe_event!(observe_tagging, ($($elem),*), (tup: &Tuple, input_tag: &Option<Prov::InputTag>, tag: &Prov::Tag));
+      monitor_observe_event!(observe_derived_tuple, ($($elem),*), (relation: &str, tup: &Tuple, tag: &Prov::Tag));
       monitor_observe_event!(observe_recovering_relation, ($($elem),*), (relation: &str));
       monitor_observe_event!(observe_recover, ($($elem),*), (tup: &Tuple, tag: &Prov::Tag, output_tag: &Prov::OutputTag));
     }
@@ -0,0 +1,39 @@
+use std::cell::RefCell;
+
+use crate::common::tuple::Tuple;
+use crate::runtime::provenance::Provenance;
+
+use super::*;
+
+/// Output Callback Monitor
+///
+/// A monitor which hands every recovered output tuple, along with the name of its relation and
+/// its output tag, to the given callback as soon as the tuple is recovered. This allows to consume
+/// the outputs (e.g. writing them into a file) while the relations are being recovered.
+pub struct OutputCallbackMonitor<F> {
+  relation: RefCell<String>,
+  callback: RefCell<F>,
+}
+
+impl<F> OutputCallbackMonitor<F> {
+  pub fn new(callback: F) -> Self {
+    Self {
+      relation: RefCell::new(String::new()),
+      callback: RefCell::new(callback),
+    }
+  }
+}
+
+impl<F, Prov> Monitor<Prov> for OutputCallbackMonitor<F>
+where
+  F: FnMut(&str, &Tuple, &Prov::OutputTag),
+  Prov: Provenance,
+{
+  fn observe_recovering_relation(&self, relation: &str) {
+    *self.relation.borrow_mut() = relation.to_string();
+  }
+
+  fn observe_recover(&self, tup: &Tuple, _: &Prov::Tag, output_tag: &Prov::OutputTag) {
+    (self.callback.borrow_mut())(&self.relation.borrow(), tup, output_tag)
+  }
+}
@@ -0,0 +1,121 @@
+use std::cell::RefCell;
+use std::collections::*;
+use std::time::{Duration, Instant};
+
+use crate::runtime::provenance::Provenance;
+
+use super::*;
+
+/// Statistics of a single stratum
+#[derive(Clone, Debug)]
+pub struct StratumStats {
+  /// The id of the stratum
+  pub stratum_id: usize,
+
+  /// The number of fixpoint iterations performed in this stratum
+  pub iterations: usize,
+
+  /// The time spent executing this stratum
+  pub time: Duration,
+}
+
+/// Statistics of a whole program run
+#[derive(Clone, Debug, Default)]
+pub struct RunStats {
+  /// The total number of iterations across all strata
+  pub total_iterations: usize,
+
+  /// The statistics of each executed stratum, in execution order
+  pub strata: Vec<StratumStats>,
+
+  /// The final number of facts in each output relation
+  pub relation_sizes: BTreeMap<String, usize>,
+}
+
+impl RunStats {
+  /// Total time spent executing the strata
+  pub fn total_time(&self) -> Duration {
+    self.strata.iter().map(|s| s.time).sum()
+  }
+}
+
+/// Statistics Monitor
+///
+/// A monitor recording the number of iterations and the time spent in each
+/// stratum. Relation sizes are not observable through the monitor and are
+/// left empty in the produced `RunStats`.
+#[derive(Default)]
+pub struct StatisticsMonitor {
+  strata: RefCell<Vec<StratumStats>>,
+  current: RefCell<Option<(StratumStats, Instant)>>,
+}
+
+impl StatisticsMonitor {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Get the statistics collected so far
+  pub fn run_stats(&self) -> RunStats {
+    self.finish_current_stratum();
+    let strata = self.strata.borrow().clone();
+    RunStats {
+      total_iterations: strata.iter().map(|s| s.iterations).sum(),
+      strata,
+      relation_sizes: BTreeMap::new(),
+    }
+  }
+
+  /// Get the statistics accumulated per stratum, sorted by stratum id
+  ///
+  /// Different from `run_stats`, a stratum executed multiple times (e.g. when a
+  /// context is run again after adding new facts) is reported only once, with the
+  /// iterations and time of all of its executions summed up.
+  pub fn report(&self) -> Vec<StratumStats> {
+    self.finish_current_stratum();
+    let mut report = BTreeMap::<usize, StratumStats>::new();
+    for stats in self.strata.borrow().iter() {
+      let entry = report.entry(stats.stratum_id).or_insert_with(|| StratumStats {
+        stratum_id: stats.stratum_id,
+        iterations: 0,
+        time: Duration::ZERO,
+      });
+      entry.iterations += stats.iterations;
+      entry.time += stats.time;
+    }
+    report.into_values().collect()
+  }
+
+  fn finish_current_stratum(&self) {
+    if let Some((mut stats, start)) = self.current.borrow_mut().take() {
+      stats.time = start.elapsed();
+      self.strata.borrow_mut().push(stats);
+    }
+  }
+}
+
+impl<Prov: Provenance> Monitor<Prov> for StatisticsMonitor {
+  fn observe_executing_stratum(&self, stratum_id: usize) {
+    self.finish_current_stratum();
+    let stats = StratumStats {
+      stratum_id,
+      iterations: 0,
+      time: Duration::ZERO,
+    };
+    *self.current.borrow_mut() = Some((stats, Instant::now()));
+  }
+
+  fn observe_stratum_iteration(&self, _: usize) {
+    if let Some((stats, _)) = &mut *self.current.borrow_mut() {
+      stats.iterations += 1;
+    }
+  }
+
+  fn observe_hitting_iteration_limit(&self) {
+    self.finish_current_stratum();
+  }
+
+  fn observe_converging(&self) {
+    self.finish_current_stratum();
+  }
+}
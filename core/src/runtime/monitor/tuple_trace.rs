@@ -0,0 +1,40 @@
+use std::collections::*;
+
+use crate::common::tuple::Tuple;
+use crate::runtime::provenance::Provenance;
+
+use super::*;
+
+/// Tuple Trace Monitor
+///
+/// A monitor printing the tuples derived into the traced relations during the fixpoint
+/// iterations, along with their tags; useful to find out when a rule produces an
+/// unexpected tuple.
+pub struct TupleTraceMonitor {
+  relations: HashSet<String>,
+}
+
+impl TupleTraceMonitor {
+  pub fn new<I, S>(relations: I) -> Self
+  where
+    I: IntoIterator<Item = S>,
+    S: ToString,
+  {
+    Self {
+      relations: relations.into_iter().map(|r| r.to_string()).collect(),
+    }
+  }
+
+  /// Check if the tuples of the given relation are traced
+  pub fn is_traced(&self, relation: &str) -> bool {
+    self.relations.contains(relation)
+  }
+}
+
+impl<Prov: Provenance> Monitor<Prov> for TupleTraceMonitor {
+  fn observe_derived_tuple(&self, relation: &str, tup: &Tuple, tag: &Prov::Tag) {
+    if self.is_traced(relation) {
+      println!("[Derived Tuple] Relation: {}, Tuple: {}, Tag: {:?}", relation, tup, tag)
+    }
+  }
+}
@@ -0,0 +1,217 @@
+use std::collections::*;
+
+use super::super::*;
+
+/// The id of the BDD node representing `false`
+pub const BDD_FALSE: usize = 0;
+
+/// The id of the BDD node representing `true`
+pub const BDD_TRUE: usize = 1;
+
+/// A reference to a node stored inside of a `BDDManager`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct BDDTag(pub usize);
+
+impl BDDTag {
+  pub fn is_false(&self) -> bool {
+    self.0 == BDD_FALSE
+  }
+
+  pub fn is_true(&self) -> bool {
+    self.0 == BDD_TRUE
+  }
+}
+
+impl std::fmt::Display for BDDTag {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self.0 {
+      BDD_FALSE => f.write_str("bdd(false)"),
+      BDD_TRUE => f.write_str("bdd(true)"),
+      n => f.write_fmt(format_args!("bdd({})", n)),
+    }
+  }
+}
+
+impl Tag for BDDTag {}
+
+/// An internal node of a BDD, branching on variable `var`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+struct BDDNode {
+  var: usize,
+  low: usize,
+  high: usize,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+enum BDDOp {
+  And,
+  Or,
+}
+
+/// A manager of reduced ordered binary decision diagrams (ROBDD)
+///
+/// Variables are ordered by their id. Nodes are hash-consed in a unique table so that two equivalent
+/// formulas are always represented by the same node, and the results of `apply` are cached.
+#[derive(Clone, Debug)]
+pub struct BDDManager {
+  nodes: Vec<BDDNode>,
+  unique_table: HashMap<BDDNode, usize>,
+  apply_cache: HashMap<(BDDOp, usize, usize), usize>,
+  negate_cache: HashMap<usize, usize>,
+}
+
+impl Default for BDDManager {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl BDDManager {
+  pub fn new() -> Self {
+    // The two terminal nodes do not branch on any variable
+    let terminal = BDDNode {
+      var: usize::MAX,
+      low: BDD_FALSE,
+      high: BDD_TRUE,
+    };
+    Self {
+      nodes: vec![terminal, terminal],
+      unique_table: HashMap::new(),
+      apply_cache: HashMap::new(),
+      negate_cache: HashMap::new(),
+    }
+  }
+
+  /// The number of nodes stored in the manager, including the two terminals
+  pub fn num_nodes(&self) -> usize {
+    self.nodes.len()
+  }
+
+  pub fn zero(&self) -> BDDTag {
+    BDDTag(BDD_FALSE)
+  }
+
+  pub fn one(&self) -> BDDTag {
+    BDDTag(BDD_TRUE)
+  }
+
+  /// The BDD of the positive literal of variable `var`
+  pub fn var(&mut self, var: usize) -> BDDTag {
+    BDDTag(self.make_node(var, BDD_FALSE, BDD_TRUE))
+  }
+
+  /// The BDD of the negative literal of variable `var`
+  pub fn neg_var(&mut self, var: usize) -> BDDTag {
+    BDDTag(self.make_node(var, BDD_TRUE, BDD_FALSE))
+  }
+
+  pub fn and(&mut self, t1: &BDDTag, t2: &BDDTag) -> BDDTag {
+    BDDTag(self.apply(BDDOp::And, t1.0, t2.0))
+  }
+
+  pub fn or(&mut self, t1: &BDDTag, t2: &BDDTag) -> BDDTag {
+    BDDTag(self.apply(BDDOp::Or, t1.0, t2.0))
+  }
+
+  pub fn not(&mut self, t: &BDDTag) -> BDDTag {
+    BDDTag(self.negate(t.0))
+  }
+
+  /// Weighted model count of the BDD, where `prob(v)` is the probability of variable `v` being true
+  pub fn wmc<F: Fn(usize) -> f64>(&self, t: &BDDTag, prob: F) -> f64 {
+    let mut memo = HashMap::new();
+    self.wmc_helper(t.0, &prob, &mut memo)
+  }
+
+  fn wmc_helper<F: Fn(usize) -> f64>(&self, n: usize, prob: &F, memo: &mut HashMap<usize, f64>) -> f64 {
+    match n {
+      BDD_FALSE => 0.0,
+      BDD_TRUE => 1.0,
+      _ => {
+        if let Some(w) = memo.get(&n) {
+          return *w;
+        }
+        let node = self.nodes[n];
+        let p = prob(node.var);
+        let w = (1.0 - p) * self.wmc_helper(node.low, prob, memo) + p * self.wmc_helper(node.high, prob, memo);
+        memo.insert(n, w);
+        w
+      }
+    }
+  }
+
+  fn make_node(&mut self, var: usize, low: usize, high: usize) -> usize {
+    if low == high {
+      return low;
+    }
+    let node = BDDNode { var, low, high };
+    if let Some(id) = self.unique_table.get(&node) {
+      *id
+    } else {
+      let id = self.nodes.len();
+      self.nodes.push(node);
+      self.unique_table.insert(node, id);
+      id
+    }
+  }
+
+  fn is_terminal(n: usize) -> bool {
+    n == BDD_FALSE || n == BDD_TRUE
+  }
+
+  fn apply(&mut self, op: BDDOp, a: usize, b: usize) -> usize {
+    // Terminal cases
+    match op {
+      BDDOp::And => {
+        if a == BDD_FALSE || b == BDD_FALSE {
+          return BDD_FALSE;
+        } else if a == BDD_TRUE || a == b {
+          return b;
+        } else if b == BDD_TRUE {
+          return a;
+        }
+      }
+      BDDOp::Or => {
+        if a == BDD_TRUE || b == BDD_TRUE {
+          return BDD_TRUE;
+        } else if a == BDD_FALSE || a == b {
+          return b;
+        } else if b == BDD_FALSE {
+          return a;
+        }
+      }
+    }
+
+    // Both operations are commutative, so the cache key is normalized
+    let key = (op, a.min(b), a.max(b));
+    if let Some(r) = self.apply_cache.get(&key) {
+      return *r;
+    }
+
+    // Shannon expansion on the smallest variable of the two nodes
+    let (na, nb) = (self.nodes[a], self.nodes[b]);
+    let var = na.var.min(nb.var);
+    let (a_low, a_high) = if na.var == var { (na.low, na.high) } else { (a, a) };
+    let (b_low, b_high) = if nb.var == var { (nb.low, nb.high) } else { (b, b) };
+    let low = self.apply(op, a_low, b_low);
+    let high = self.apply(op, a_high, b_high);
+    let r = self.make_node(var, low, high);
+    self.apply_cache.insert(key, r);
+    r
+  }
+
+  fn negate(&mut self, n: usize) -> usize {
+    if Self::is_terminal(n) {
+      return if n == BDD_FALSE { BDD_TRUE } else { BDD_FALSE };
+    }
+    if let Some(r) = self.negate_cache.get(&n) {
+      return *r;
+    }
+    let node = self.nodes[n];
+    let low = self.negate(node.low);
+    let high = self.negate(node.high);
+    let r = self.make_node(node.var, low, high);
+    self.negate_cache.insert(n, r);
+    r
+  }
+}
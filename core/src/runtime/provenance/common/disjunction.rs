@@ -7,6 +7,10 @@ pub struct Disjunction {
 }
 
 impl Disjunction {
+  pub fn facts(&self) -> &BTreeSet<usize> {
+    &self.facts
+  }
+
   pub fn add_fact_id(&mut self, fact_id: usize) {
     self.facts.insert(fact_id);
   }
@@ -90,6 +94,11 @@ impl Disjunctions {
     self.disjunctions.len()
   }
 
+  /// Get the disjunction of the given (external) disjunction id, if there is one
+  pub fn disjunction(&self, disj_id: usize) -> Option<&Disjunction> {
+    self.id_map.get(&disj_id).map(|i| &self.disjunctions[*i])
+  }
+
   pub fn has_conflict(&self, facts: &BTreeSet<usize>) -> bool {
     // Short hand
     if facts.len() < 2 {
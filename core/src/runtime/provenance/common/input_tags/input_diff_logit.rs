@@ -0,0 +1,111 @@
+use crate::common::input_tag::*;
+
+use super::*;
+
+/// An input differentiable logit.
+///
+/// Facts sharing the same `group` form a categorical distribution, and their probabilities
+/// are obtained by normalizing the logits of the group with a softmax.
+/// A fact without a group is treated as a binary categorical distribution, so that its
+/// probability is the sigmoid of its logit.
+/// Similar to [`InputDiffProb`], a `None` external tag means that we do not need to
+/// back-propagate gradients into the logit.
+#[derive(Clone)]
+pub struct InputDiffLogit<T: Clone + 'static> {
+  /// The (unnormalized) logit of the tag
+  pub logit: f64,
+
+  /// The external tag for differentiability
+  pub external_tag: Option<T>,
+
+  /// An optional identifier of the categorical group
+  pub group: Option<usize>,
+}
+
+impl<T: Clone + 'static> InputDiffLogit<T> {
+  pub fn new(logit: f64, tag: T, group: Option<usize>) -> Self {
+    Self {
+      logit,
+      external_tag: Some(tag),
+      group,
+    }
+  }
+
+  /// Create a constant logit from a probability, such that the normalized probability is `prob`
+  /// as long as the probabilities within the group sum up to one
+  pub fn from_prob(prob: f64, group: Option<usize>) -> Self {
+    let logit = if group.is_some() {
+      prob.ln()
+    } else {
+      (prob / (1.0 - prob)).ln()
+    };
+    Self {
+      logit,
+      external_tag: None,
+      group,
+    }
+  }
+}
+
+impl<T: Clone + 'static> std::fmt::Debug for InputDiffLogit<T> {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    self.logit.fmt(f)
+  }
+}
+
+impl<T: Clone + 'static> From<(f64, T, Option<usize>)> for InputDiffLogit<T> {
+  fn from((logit, tag, group): (f64, T, Option<usize>)) -> Self {
+    Self::new(logit, tag, group)
+  }
+}
+
+impl<T: Clone + 'static> StaticInputTag for InputDiffLogit<T> {
+  fn from_dynamic_input_tag(t: &DynamicInputTag) -> Option<Self> {
+    match t {
+      DynamicInputTag::None => None,
+      DynamicInputTag::Bool(b) => Some(Self::from_prob(if *b { 1.0 } else { 0.0 }, None)),
+      DynamicInputTag::Exclusive(i) => Some(Self {
+        logit: 0.0,
+        external_tag: None,
+        group: Some(*i),
+      }),
+      DynamicInputTag::Float(prob) => Some(Self::from_prob(*prob, None)),
+      DynamicInputTag::ExclusiveFloat(prob, i) => Some(Self::from_prob(*prob, Some(*i))),
+      DynamicInputTag::Interval(_, _) => None,
+    }
+  }
+}
+
+impl<T: Clone + 'static> ConvertFromInputTag<()> for InputDiffLogit<T> {
+  fn from_input_tag(_: ()) -> Option<Self> {
+    None
+  }
+}
+
+impl<T: Clone + 'static> ConvertFromInputTag<bool> for InputDiffLogit<T> {
+  fn from_input_tag(b: bool) -> Option<Self> {
+    if b {
+      None
+    } else {
+      Some(Self::from_prob(0.0, None))
+    }
+  }
+}
+
+impl<T: Clone + 'static> ConvertFromInputTag<f64> for InputDiffLogit<T> {
+  fn from_input_tag(t: f64) -> Option<Self> {
+    Some(Self::from_prob(t, None))
+  }
+}
+
+impl<T: Clone + 'static> ConvertFromInputTag<InputExclusiveProb> for InputDiffLogit<T> {
+  fn from_input_tag(t: InputExclusiveProb) -> Option<Self> {
+    Some(Self::from_prob(t.prob, t.exclusion))
+  }
+}
+
+impl<T: Clone + 'static> ConvertFromInputTag<InputDiffLogit<T>> for InputDiffLogit<T> {
+  fn from_input_tag(t: InputDiffLogit<T>) -> Option<Self> {
+    Some(t.clone())
+  }
+}
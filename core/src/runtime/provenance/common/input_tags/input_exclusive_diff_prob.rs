@@ -44,6 +44,7 @@ impl<T: Clone + 'static> StaticInputTag for InputExclusiveDiffProb<T> {
       DynamicInputTag::Exclusive(i) => Some(Self { prob: 1.0, external_tag: None, exclusion: Some(i.clone()) }),
       DynamicInputTag::Float(prob) => Some(Self { prob: prob.clone(), external_tag: None, exclusion: None }),
       DynamicInputTag::ExclusiveFloat(prob, i) => Some(Self { prob: prob.clone(), external_tag: None, exclusion: Some(i.clone()) }),
+      DynamicInputTag::Interval(_, _) => None,
     }
   }
 }
@@ -0,0 +1,98 @@
+use crate::common::input_tag::*;
+
+use super::*;
+
+/// An input probability interval, bounding an unknown probability from below and above
+#[derive(Clone, Copy, PartialEq)]
+pub struct InputProbInterval {
+  /// The lower bound of the probability
+  pub lo: f64,
+
+  /// The upper bound of the probability
+  pub hi: f64,
+}
+
+impl InputProbInterval {
+  pub fn new(lo: f64, hi: f64) -> Self {
+    Self { lo, hi }
+  }
+
+  /// Create a degenerate interval holding a single point probability
+  pub fn point(prob: f64) -> Self {
+    Self { lo: prob, hi: prob }
+  }
+}
+
+impl std::fmt::Debug for InputProbInterval {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.write_fmt(format_args!("[{}, {}]", self.lo, self.hi))
+  }
+}
+
+impl From<f64> for InputProbInterval {
+  fn from(p: f64) -> Self {
+    Self::point(p)
+  }
+}
+
+impl From<(f64, f64)> for InputProbInterval {
+  fn from((lo, hi): (f64, f64)) -> Self {
+    Self::new(lo, hi)
+  }
+}
+
+impl StaticInputTag for InputProbInterval {
+  fn from_dynamic_input_tag(t: &DynamicInputTag) -> Option<Self> {
+    match t {
+      DynamicInputTag::Bool(b) => Some(Self::point(if *b { 1.0 } else { 0.0 })),
+      DynamicInputTag::Float(f) => Some(Self::point(*f)),
+      DynamicInputTag::ExclusiveFloat(f, _) => Some(Self::point(*f)),
+      DynamicInputTag::Interval(lo, hi) => Some(Self::new(*lo, *hi)),
+      _ => None,
+    }
+  }
+}
+
+impl ConvertFromInputTag<()> for InputProbInterval {
+  fn from_input_tag(_: ()) -> Option<Self> {
+    None
+  }
+}
+
+impl ConvertFromInputTag<bool> for InputProbInterval {
+  fn from_input_tag(t: bool) -> Option<Self> {
+    if t {
+      None
+    } else {
+      Some(Self::point(0.0))
+    }
+  }
+}
+
+impl ConvertFromInputTag<usize> for InputProbInterval {
+  fn from_input_tag(t: usize) -> Option<Self> {
+    if t > 0 {
+      None
+    } else {
+      Some(Self::point(0.0))
+    }
+  }
+}
+
+impl ConvertFromInputTag<f64> for InputProbInterval {
+  fn from_input_tag(t: f64) -> Option<Self> {
+    Some(Self::point(t))
+  }
+}
+
+impl ConvertFromInputTag<InputExclusiveProb> for InputProbInterval {
+  fn from_input_tag(t: InputExclusiveProb) -> Option<Self> {
+    Some(Self::point(t.prob))
+  }
+}
+
+impl ConvertFromInputTag<InputProbInterval> for InputProbInterval {
+  fn from_input_tag(t: InputProbInterval) -> Option<Self> {
+    Some(t)
+  }
+}
@@ -1,20 +1,24 @@
 mod boolean;
 mod convert_input_tag;
 mod float;
+mod input_diff_logit;
 mod input_diff_prob;
 mod input_exclusion;
 mod input_exclusive_diff_prob;
 mod input_exclusive_prob;
+mod input_prob_interval;
 mod natural;
 mod static_input_tag;
 mod unit;
 
 pub use boolean::*;
 pub use convert_input_tag::*;
+pub use input_diff_logit::*;
 pub use input_diff_prob::*;
 pub use input_exclusion::*;
 pub use input_exclusive_diff_prob::*;
 pub use input_exclusive_prob::*;
+pub use input_prob_interval::*;
 pub use natural::*;
 pub use static_input_tag::*;
 pub use unit::*;
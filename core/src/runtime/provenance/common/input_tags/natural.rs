@@ -10,6 +10,7 @@ impl StaticInputTag for usize {
       DynamicInputTag::Bool(b) => Some(if *b { 1 } else { 0 }),
       DynamicInputTag::Float(f) => Some(if *f > 0.0 { 1 } else { 0 }),
       DynamicInputTag::ExclusiveFloat(_, _) => Some(1),
+      DynamicInputTag::Interval(_, hi) => Some(if *hi > 0.0 { 1 } else { 0 }),
     }
   }
 }
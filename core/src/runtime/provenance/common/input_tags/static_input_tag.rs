@@ -4,6 +4,9 @@ pub trait StaticInputTag: Sized {
   fn from_dynamic_input_tag(_: &DynamicInputTag) -> Option<Self>;
 }
 
+/// On nightly, every type is a static input tag that cannot be converted from
+/// a dynamic input tag unless it specializes this implementation
+#[cfg(not(feature = "stable"))]
 impl<T> StaticInputTag for T {
   default fn from_dynamic_input_tag(_: &DynamicInputTag) -> Option<Self> {
     None
@@ -1,4 +1,5 @@
 mod as_boolean_formula;
+mod bdd;
 mod chosen_elements;
 mod clause;
 mod cnf_dnf_context;
@@ -17,6 +18,7 @@ mod real;
 mod top_k_aggregation;
 
 pub use as_boolean_formula::*;
+pub use bdd::*;
 pub use chosen_elements::*;
 pub use clause::*;
 pub use cnf_dnf_context::*;
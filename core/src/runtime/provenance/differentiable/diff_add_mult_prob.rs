@@ -120,6 +120,10 @@ impl<T: Clone + 'static, P: PointerFamily> Provenance for DiffAddMultProbProvena
     t.real
   }
 
+  fn chosen_set_tag(&self, batch: &DynamicElements<Self>, chosen_set: &Vec<usize>) -> Option<Self::Tag> {
+    Some(self.tag_of_chosen_set(batch, chosen_set))
+  }
+
   fn dynamic_count(&self, batch: DynamicElements<Self>) -> DynamicElements<Self> {
     let mut result = vec![];
     if batch.is_empty() {
@@ -4,6 +4,7 @@ use super::*;
 use crate::common::element::*;
 use crate::common::value_type::*;
 use crate::runtime::dynamic::*;
+use crate::runtime::env::*;
 use crate::runtime::statics::*;
 use crate::utils::*;
 
@@ -197,6 +198,10 @@ impl<T: Clone + 'static, P: PointerFamily> Provenance for DiffMinMaxProbProvenan
     t.0
   }
 
+  fn chosen_set_tag(&self, batch: &DynamicElements<Self>, chosen_set: &Vec<usize>) -> Option<Self::Tag> {
+    Some(self.min_tag_of_chosen_set(batch, chosen_set))
+  }
+
   fn dynamic_count(&self, mut batch: DynamicElements<Self>) -> DynamicElements<Self> {
     if batch.is_empty() {
       vec![DynamicElement::new(0usize, self.one())]
@@ -211,11 +216,15 @@ impl<T: Clone + 'static, P: PointerFamily> Provenance for DiffMinMaxProbProvenan
     }
   }
 
-  fn dynamic_sum(&self, ty: &ValueType, batch: DynamicElements<Self>) -> DynamicElements<Self> {
+  fn dynamic_sum(&self, ty: &ValueType, batch: DynamicElements<Self>, rt: &RuntimeEnvironment) -> DynamicElements<Self> {
     let mut elems = vec![];
     for chosen_set in (0..batch.len()).powerset() {
       let chosen_elements = self.collect_chosen_elements(&batch, &chosen_set);
-      let sum = ty.sum(chosen_elements.iter_tuples());
+      let sum = if rt.stable_float_aggregation {
+        ty.stable_sum(chosen_elements.iter_tuples())
+      } else {
+        ty.sum(chosen_elements.iter_tuples())
+      };
       let prob = self.min_tag_of_chosen_set(&batch, &chosen_set);
       elems.push(DynamicElement::new(sum, prob));
     }
@@ -0,0 +1,314 @@
+use std::collections::*;
+
+use itertools::Itertools;
+use sprs::CsVec;
+
+use super::*;
+use crate::runtime::dynamic::*;
+use crate::runtime::statics::*;
+use crate::utils::*;
+
+/// The categorical groups of the input facts
+#[derive(Clone, Default)]
+pub struct SoftmaxGroups {
+  /// The group of each fact, indexed by fact id
+  fact_groups: Vec<Option<usize>>,
+
+  /// The facts of each group, in ascending order of fact id
+  members: HashMap<usize, Vec<usize>>,
+}
+
+impl SoftmaxGroups {
+  pub fn add_fact(&mut self, fact_id: usize, group: Option<usize>) {
+    self.fact_groups.push(group);
+    if let Some(group_id) = group {
+      self.members.entry(group_id).or_default().push(fact_id);
+    }
+  }
+
+  pub fn group_of(&self, fact_id: &usize) -> Option<&Vec<usize>> {
+    self.fact_groups[*fact_id].and_then(|g| self.members.get(&g))
+  }
+}
+
+/// A differentiable provenance where the input tags are logits forming categorical groups.
+///
+/// The probability of a fact is the softmax of its logit within its group (or the sigmoid of its
+/// logit if it is not in any group), and the facts of a group are mutually exclusive. Proofs are
+/// kept top-k, and the recovered gradients are taken with respect to the logits.
+pub struct DiffSoftmaxProvenance<T: Clone, P: PointerFamily> {
+  pub k: usize,
+  pub storage: DiffProbStorage<T, P>,
+  pub groups: P::Cell<SoftmaxGroups>,
+  pub disjunctions: P::Cell<Disjunctions>,
+}
+
+impl<T: Clone, P: PointerFamily> Clone for DiffSoftmaxProvenance<T, P> {
+  fn clone(&self) -> Self {
+    Self {
+      k: self.k,
+      storage: self.storage.clone_internal(),
+      groups: P::clone_cell(&self.groups),
+      disjunctions: P::clone_cell(&self.disjunctions),
+    }
+  }
+}
+
+impl<T: Clone, P: PointerFamily> DiffSoftmaxProvenance<T, P> {
+  pub fn new(k: usize) -> Self {
+    Self {
+      k,
+      storage: DiffProbStorage::new(),
+      groups: P::new_cell(SoftmaxGroups::default()),
+      disjunctions: P::new_cell(Disjunctions::new()),
+    }
+  }
+
+  pub fn input_tags(&self) -> Vec<T> {
+    self.storage.input_tags()
+  }
+
+  pub fn set_k(&mut self, k: usize) {
+    self.k = k;
+  }
+
+  /// Get the normalized probability of a fact, along with its derivatives with respect to the logits
+  /// of the facts in the same group, in ascending order of fact id
+  pub fn normalized_prob(&self, id: &usize) -> (f64, Vec<(usize, f64)>) {
+    P::get_cell(&self.groups, |groups| match groups.group_of(id) {
+      Some(members) => {
+        let logits = members.iter().map(|j| self.storage.get_prob(j)).collect::<Vec<_>>();
+        let max = logits.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+        let exps = logits.iter().map(|l| (l - max).exp()).collect::<Vec<_>>();
+        let sum = exps.iter().sum::<f64>();
+        let prob = (self.storage.get_prob(id) - max).exp() / sum;
+        let jacobian = members
+          .iter()
+          .zip(exps.iter())
+          .map(|(j, e)| {
+            if j == id {
+              (*j, prob * (1.0 - prob))
+            } else {
+              (*j, -prob * e / sum)
+            }
+          })
+          .collect();
+        (prob, jacobian)
+      }
+      None => {
+        let prob = 1.0 / (1.0 + (-self.storage.get_prob(id)).exp());
+        (prob, vec![(*id, prob * (1.0 - prob))])
+      }
+    })
+  }
+}
+
+impl<T: Clone, P: PointerFamily> DNFContextTrait for DiffSoftmaxProvenance<T, P> {
+  fn fact_probability(&self, id: &usize) -> f64 {
+    self.normalized_prob(id).0
+  }
+
+  fn has_disjunction_conflict(&self, pos_facts: &std::collections::BTreeSet<usize>) -> bool {
+    P::get_cell(&self.disjunctions, |d| d.has_conflict(pos_facts))
+  }
+}
+
+impl<T: Clone + 'static, P: PointerFamily> Provenance for DiffSoftmaxProvenance<T, P> {
+  type Tag = DNFFormula;
+
+  type InputTag = InputDiffLogit<T>;
+
+  type OutputTag = OutputDiffProb;
+
+  fn name() -> &'static str {
+    "diff-softmax"
+  }
+
+  fn tagging_fn(&self, input_tag: Self::InputTag) -> Self::Tag {
+    let InputDiffLogit {
+      logit,
+      external_tag,
+      group,
+    } = input_tag;
+
+    // First store the logit and generate the id
+    let fact_id = self.storage.add_prob(logit, external_tag);
+
+    // Store the categorical group, whose facts are mutually exclusive
+    P::get_cell_mut(&self.groups, |g| g.add_fact(fact_id, group));
+    if let Some(group_id) = group {
+      P::get_cell_mut(&self.disjunctions, |d| d.add_disjunction(group_id, fact_id));
+    }
+
+    // Finally return the formula
+    DNFFormula::singleton(fact_id)
+  }
+
+  fn recover_fn(&self, t: &Self::Tag) -> Self::OutputTag {
+    // The derivatives are taken with respect to the logits of all the facts
+    let num_facts = P::get_rc_cell(&self.storage.storage, |s| s.len());
+    let s = DualNumberSemiring::new(num_facts);
+    let v = |i: &usize| {
+      let (prob, jacobian) = self.normalized_prob(i);
+      let (indices, data) = jacobian
+        .into_iter()
+        .filter(|(j, _)| self.storage.get_diff_prob(j).1.is_some())
+        .unzip();
+      DualNumber {
+        real: prob,
+        deriv: CsVec::new(num_facts, indices, data),
+      }
+    };
+    let wmc_result = t.wmc(&s, &v);
+    let prob = wmc_result.real;
+    let deriv = wmc_result
+      .deriv
+      .iter()
+      .map(|(id, weight)| (id, *weight))
+      .collect::<Vec<_>>();
+    OutputDiffProb(prob, deriv)
+  }
+
+  fn discard(&self, t: &Self::Tag) -> bool {
+    t.is_empty()
+  }
+
+  fn zero(&self) -> Self::Tag {
+    self.base_zero()
+  }
+
+  fn one(&self) -> Self::Tag {
+    self.base_one()
+  }
+
+  fn add(&self, t1: &Self::Tag, t2: &Self::Tag) -> Self::Tag {
+    self.top_k_add(t1, t2, self.k)
+  }
+
+  fn saturated(&self, t_old: &Self::Tag, t_new: &Self::Tag) -> bool {
+    t_old == t_new
+  }
+
+  fn mult(&self, t1: &Self::Tag, t2: &Self::Tag) -> Self::Tag {
+    self.top_k_mult(t1, t2, self.k)
+  }
+
+  fn negate(&self, t: &Self::Tag) -> Option<Self::Tag> {
+    Some(self.top_k_negate(t, self.k))
+  }
+
+  fn weight(&self, t: &Self::Tag) -> f64 {
+    let v = |i: &usize| self.fact_probability(i);
+    t.wmc(&RealSemiring::new(), &v)
+  }
+
+  fn chosen_set_tag(&self, batch: &DynamicElements<Self>, chosen_set: &Vec<usize>) -> Option<Self::Tag> {
+    Some(self.top_k_tag_of_chosen_set(batch.iter().map(|e| &e.tag), chosen_set, self.k))
+  }
+
+  fn dynamic_count(&self, batch: DynamicElements<Self>) -> DynamicElements<Self> {
+    if batch.is_empty() {
+      vec![DynamicElement::new(0usize, self.one())]
+    } else {
+      let mut elems = vec![];
+      for chosen_set in (0..batch.len()).powerset() {
+        let count = chosen_set.len();
+        let tag = self.top_k_tag_of_chosen_set(batch.iter().map(|e| &e.tag), &chosen_set, self.k);
+        elems.push(DynamicElement::new(count, tag));
+      }
+      elems
+    }
+  }
+
+  fn dynamic_min(&self, batch: DynamicElements<Self>) -> DynamicElements<Self> {
+    let mut elems = vec![];
+    for i in 0..batch.len() {
+      let min_elem = batch[i].tuple.clone();
+      let mut agg_tag = self.one();
+      for elem in &batch[..i] {
+        agg_tag = self.mult(&agg_tag, &self.negate(&elem.tag).unwrap());
+      }
+      agg_tag = self.mult(&agg_tag, &batch[i].tag);
+      elems.push(DynamicElement::new(min_elem, agg_tag));
+    }
+    elems
+  }
+
+  fn dynamic_max(&self, batch: DynamicElements<Self>) -> DynamicElements<Self> {
+    let mut elems = vec![];
+    for i in 0..batch.len() {
+      let max_elem = batch[i].tuple.clone();
+      let mut agg_tag = batch[i].tag.clone();
+      for elem in &batch[i + 1..] {
+        agg_tag = self.mult(&agg_tag, &self.negate(&elem.tag).unwrap());
+      }
+      elems.push(DynamicElement::new(max_elem, agg_tag));
+    }
+    elems
+  }
+
+  fn dynamic_exists(&self, batch: DynamicElements<Self>) -> DynamicElements<Self> {
+    let mut exists_tag = self.zero();
+    let mut not_exists_tag = self.one();
+    for elem in batch {
+      exists_tag = self.add(&exists_tag, &elem.tag);
+      not_exists_tag = self.mult(&not_exists_tag, &self.negate(&elem.tag).unwrap());
+    }
+    let t = DynamicElement::new(true, exists_tag);
+    let f = DynamicElement::new(false, not_exists_tag);
+    vec![t, f]
+  }
+
+  fn static_count<Tup: StaticTupleTrait>(&self, batch: StaticElements<Tup, Self>) -> StaticElements<usize, Self> {
+    if batch.is_empty() {
+      vec![StaticElement::new(0, self.one())]
+    } else {
+      let mut elems = vec![];
+      for chosen_set in (0..batch.len()).powerset() {
+        let count = chosen_set.len();
+        let tag = self.top_k_tag_of_chosen_set(batch.iter().map(|e| &e.tag), &chosen_set, self.k);
+        elems.push(StaticElement::new(count, tag));
+      }
+      elems
+    }
+  }
+
+  fn static_min<Tup: StaticTupleTrait>(&self, batch: StaticElements<Tup, Self>) -> StaticElements<Tup, Self> {
+    let mut elems = vec![];
+    for i in 0..batch.len() {
+      let min_elem = batch[i].tuple.get().clone();
+      let mut agg_tag = self.one();
+      for elem in &batch[..i] {
+        agg_tag = self.mult(&agg_tag, &self.negate(&elem.tag).unwrap());
+      }
+      agg_tag = self.mult(&agg_tag, &batch[i].tag);
+      elems.push(StaticElement::new(min_elem, agg_tag));
+    }
+    elems
+  }
+
+  fn static_max<Tup: StaticTupleTrait>(&self, batch: StaticElements<Tup, Self>) -> StaticElements<Tup, Self> {
+    let mut elems = vec![];
+    for i in 0..batch.len() {
+      let max_elem = batch[i].tuple.get().clone();
+      let mut agg_tag = batch[i].tag.clone();
+      for elem in &batch[i + 1..] {
+        agg_tag = self.mult(&agg_tag, &self.negate(&elem.tag).unwrap());
+      }
+      elems.push(StaticElement::new(max_elem, agg_tag));
+    }
+    elems
+  }
+
+  fn static_exists<Tup: StaticTupleTrait>(&self, batch: StaticElements<Tup, Self>) -> StaticElements<bool, Self> {
+    let mut exists_tag = self.zero();
+    let mut not_exists_tag = self.one();
+    for elem in batch {
+      exists_tag = self.add(&exists_tag, &elem.tag);
+      not_exists_tag = self.mult(&not_exists_tag, &self.negate(&elem.tag).unwrap());
+    }
+    let t = StaticElement::new(true, exists_tag);
+    let f = StaticElement::new(false, not_exists_tag);
+    vec![t, f]
+  }
+}
@@ -3,12 +3,17 @@ use std::collections::*;
 use itertools::Itertools;
 
 use super::*;
+use crate::common::tuple::Tuple;
 use crate::runtime::dynamic::*;
 use crate::runtime::statics::*;
 use crate::utils::*;
 
 pub struct DiffTopBottomKClausesProvenance<T: Clone + 'static, P: PointerFamily = RcFamily> {
   pub k: usize,
+
+  /// Tags whose probabilities differ by at most this epsilon are considered saturated; when it is
+  /// zero, the formulas themselves need to be equal
+  pub saturation_epsilon: f64,
   pub storage: DiffProbStorage<T, P>,
   pub disjunctions: P::Cell<Disjunctions>,
 }
@@ -17,6 +22,7 @@ impl<T: Clone + 'static, P: PointerFamily> Clone for DiffTopBottomKClausesProven
   fn clone(&self) -> Self {
     Self {
       k: self.k,
+      saturation_epsilon: self.saturation_epsilon,
       storage: self.storage.clone_internal(),
       disjunctions: P::clone_cell(&self.disjunctions),
     }
@@ -27,6 +33,7 @@ impl<T: Clone + 'static, P: PointerFamily> DiffTopBottomKClausesProvenance<T, P>
   pub fn new(k: usize) -> Self {
     Self {
       k,
+      saturation_epsilon: 0.0,
       storage: DiffProbStorage::new(),
       disjunctions: P::new_cell(Disjunctions::new()),
     }
@@ -36,9 +43,47 @@ impl<T: Clone + 'static, P: PointerFamily> DiffTopBottomKClausesProvenance<T, P>
     self.k = k;
   }
 
+  pub fn set_saturation_epsilon(&mut self, saturation_epsilon: f64) {
+    self.saturation_epsilon = saturation_epsilon;
+  }
+
   pub fn input_tags(&self) -> Vec<T> {
     self.storage.input_tags()
   }
+
+  /// Compute the binary cross-entropy loss of the recovered outputs against the target probabilities,
+  /// along with its gradient with respect to each of the input tags
+  ///
+  /// The loss is averaged over the targets. A target tuple that is not among the outputs is predicted
+  /// with probability zero, and the outputs without a target are ignored. Predicted probabilities are
+  /// clamped away from zero and one so that the loss stays finite.
+  pub fn cross_entropy(&self, outputs: &[(Tuple, OutputDiffProb)], targets: &[(Tuple, f64)]) -> (f64, Vec<f64>) {
+    const EPSILON: f64 = 1e-7;
+    let mut grad = vec![0.0; self.storage.num_input_tags()];
+    if targets.is_empty() {
+      return (0.0, grad);
+    }
+
+    let outputs = outputs.iter().map(|(t, o)| (t, o)).collect::<HashMap<_, _>>();
+    let n = targets.len() as f64;
+    let mut loss = 0.0;
+    for (tuple, y) in targets {
+      let (p, deriv) = match outputs.get(tuple) {
+        Some(OutputDiffProb(p, deriv)) => (p.clamp(EPSILON, 1.0 - EPSILON), &deriv[..]),
+        None => (EPSILON, &[][..]),
+      };
+      loss -= (y * p.ln() + (1.0 - y) * (1.0 - p).ln()) / n;
+
+      // Chain the derivative of the loss w.r.t. the predicted probability with the one of the probability
+      let d_loss_d_p = ((1.0 - y) / (1.0 - p) - y / p) / n;
+      for (id, weight) in deriv {
+        if let Some(g) = grad.get_mut(*id) {
+          *g += d_loss_d_p * weight;
+        }
+      }
+    }
+    (loss, grad)
+  }
 }
 
 impl<T: Clone + 'static, P: PointerFamily> CNFDNFContextTrait for DiffTopBottomKClausesProvenance<T, P> {
@@ -122,7 +167,11 @@ impl<T: Clone + 'static, P: PointerFamily> Provenance for DiffTopBottomKClausesP
   }
 
   fn saturated(&self, t_old: &Self::Tag, t_new: &Self::Tag) -> bool {
-    t_old == t_new
+    if self.saturation_epsilon > 0.0 {
+      (self.weight(t_old) - self.weight(t_new)).abs() <= self.saturation_epsilon
+    } else {
+      t_old == t_new
+    }
   }
 
   fn negate(&self, t: &Self::Tag) -> Option<Self::Tag> {
@@ -134,6 +183,10 @@ impl<T: Clone + 'static, P: PointerFamily> Provenance for DiffTopBottomKClausesP
     t.wmc(&RealSemiring::new(), &v)
   }
 
+  fn chosen_set_tag(&self, batch: &DynamicElements<Self>, chosen_set: &Vec<usize>) -> Option<Self::Tag> {
+    Some(self.top_bottom_k_tag_of_chosen_set(batch.iter().map(|e| &e.tag), chosen_set, self.k))
+  }
+
   fn dynamic_count(&self, batch: DynamicElements<Self>) -> DynamicElements<Self> {
     if batch.is_empty() {
       vec![DynamicElement::new(0usize, self.one())]
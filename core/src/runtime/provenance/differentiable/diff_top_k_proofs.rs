@@ -132,6 +132,10 @@ impl<T: Clone + 'static, P: PointerFamily> Provenance for DiffTopKProofsProvenan
     t.wmc(&RealSemiring::new(), &v)
   }
 
+  fn chosen_set_tag(&self, batch: &DynamicElements<Self>, chosen_set: &Vec<usize>) -> Option<Self::Tag> {
+    Some(self.top_k_tag_of_chosen_set(batch.iter().map(|e| &e.tag), chosen_set, self.k))
+  }
+
   fn dynamic_count(&self, batch: DynamicElements<Self>) -> DynamicElements<Self> {
     if batch.is_empty() {
       vec![DynamicElement::new(0usize, self.one())]
@@ -4,6 +4,7 @@ pub mod diff_min_max_prob;
 pub mod diff_nand_min_prob;
 pub mod diff_nand_mult_prob;
 pub mod diff_sample_k_proofs;
+pub mod diff_softmax;
 pub mod diff_top_bottom_k_clauses;
 pub mod diff_top_k_proofs;
 pub mod diff_top_k_proofs_indiv;
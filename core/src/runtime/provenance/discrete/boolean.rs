@@ -54,6 +54,10 @@ impl Provenance for BooleanProvenance {
     t_old == t_new
   }
 
+  fn is_idempotent(&self) -> bool {
+    true
+  }
+
   fn dynamic_count(&self, batch: DynamicElements<Self>) -> DynamicElements<Self> {
     let count = batch
       .into_iter()
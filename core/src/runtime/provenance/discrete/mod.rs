@@ -1,5 +1,6 @@
 pub mod boolean;
 pub mod natural;
+pub mod proof_tree;
 pub mod proofs;
 pub mod unit;
 
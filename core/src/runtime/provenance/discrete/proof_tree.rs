@@ -0,0 +1,269 @@
+use std::collections::*;
+
+use crate::runtime::dynamic::*;
+use crate::runtime::statics::*;
+use crate::utils::*;
+
+use super::*;
+
+/// A derivation tree explaining why a tuple holds
+///
+/// The leaves are the ids of the input facts, allocated in the order the facts are tagged.
+/// An `And` node holds when all of its sub-proofs hold (e.g. the atoms in the body of a rule),
+/// and an `Or` node holds when any of its alternative sub-proofs holds (e.g. different rules).
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ProofTree {
+  /// An input fact
+  Fact(usize),
+
+  /// A conjunction of sub-proofs; the empty conjunction is the trivial proof
+  And(Vec<ProofTree>),
+
+  /// A set of alternative sub-proofs; the empty disjunction means there is no proof
+  Or(BTreeSet<ProofTree>),
+
+  /// A sub-proof that is omitted since the tree exceeds the maximum size
+  Truncated,
+}
+
+impl ProofTree {
+  pub fn zero() -> Self {
+    Self::Or(BTreeSet::new())
+  }
+
+  pub fn one() -> Self {
+    Self::And(vec![])
+  }
+
+  pub fn is_zero(&self) -> bool {
+    matches!(self, Self::Or(alts) if alts.is_empty())
+  }
+
+  pub fn is_one(&self) -> bool {
+    matches!(self, Self::And(conjs) if conjs.is_empty())
+  }
+
+  /// The number of nodes in the tree
+  pub fn size(&self) -> usize {
+    match self {
+      Self::Fact(_) | Self::Truncated => 1,
+      Self::And(conjs) => 1 + conjs.iter().map(Self::size).sum::<usize>(),
+      Self::Or(alts) => 1 + alts.iter().map(Self::size).sum::<usize>(),
+    }
+  }
+
+  /// The ids of all the input facts occurring in the tree
+  pub fn facts(&self) -> BTreeSet<usize> {
+    let mut facts = BTreeSet::new();
+    self.collect_facts(&mut facts);
+    facts
+  }
+
+  fn collect_facts(&self, facts: &mut BTreeSet<usize>) {
+    match self {
+      Self::Fact(f) => {
+        facts.insert(*f);
+      }
+      Self::And(conjs) => conjs.iter().for_each(|c| c.collect_facts(facts)),
+      Self::Or(alts) => alts.iter().for_each(|a| a.collect_facts(facts)),
+      Self::Truncated => {}
+    }
+  }
+
+  /// Check if every proof represented by this tree is also represented by `other`, e.g. `and(1, 2)` is
+  /// subsumed by `and(1, or(2, 3))`
+  pub fn is_subsumed_by(&self, other: &Self) -> bool {
+    match (self, other) {
+      (t1, t2) if t1 == t2 => true,
+      (Self::Or(alts1), t2) => alts1.iter().all(|a| a.is_subsumed_by(t2)),
+      (Self::And(conjs1), Self::And(conjs2)) => {
+        conjs1.len() == conjs2.len() && conjs1.iter().zip(conjs2).all(|(c1, c2)| c1.is_subsumed_by(c2))
+      }
+      (t1, Self::Or(alts2)) => alts2.iter().any(|a| t1.is_subsumed_by(a)),
+      _ => false,
+    }
+  }
+
+  /// Combine two sets of alternative proofs; nested disjunctions are flattened, and the alternatives
+  /// subsumed by others (e.g. the ones derived from earlier versions of a recursive tuple) are dropped
+  pub fn union(t1: &Self, t2: &Self) -> Self {
+    let mut all_alts = vec![];
+    for t in [t1, t2] {
+      match t {
+        Self::Or(ts) => all_alts.extend(ts.iter().cloned()),
+        t => all_alts.push(t.clone()),
+      }
+    }
+    let mut alts = BTreeSet::new();
+    for (i, alt) in all_alts.iter().enumerate() {
+      let subsumed = all_alts
+        .iter()
+        .enumerate()
+        .any(|(j, other)| i != j && alt != other && alt.is_subsumed_by(other));
+      if !subsumed {
+        alts.insert(alt.clone());
+      }
+    }
+    if alts.len() == 1 {
+      alts.into_iter().next().unwrap()
+    } else {
+      Self::Or(alts)
+    }
+  }
+
+  /// Conjoin two proofs; nested conjunctions are flattened and the trivial proof is dropped
+  pub fn conjunction(t1: &Self, t2: &Self) -> Self {
+    if t1.is_zero() || t2.is_zero() {
+      return Self::zero();
+    }
+    let mut conjs = vec![];
+    for t in [t1, t2] {
+      match t {
+        Self::And(ts) => conjs.extend(ts.iter().cloned()),
+        t => conjs.push(t.clone()),
+      }
+    }
+    if conjs.len() == 1 {
+      conjs.pop().unwrap()
+    } else {
+      Self::And(conjs)
+    }
+  }
+
+  /// Convert the tree into JSON, where the facts are numbers, the conjunctions and disjunctions are
+  /// `{"and": [...]}` and `{"or": [...]}` objects, and the truncated sub-proofs are `null`
+  pub fn to_json(&self) -> serde_json::Value {
+    match self {
+      Self::Fact(f) => serde_json::json!(f),
+      Self::And(conjs) => serde_json::json!({ "and": conjs.iter().map(Self::to_json).collect::<Vec<_>>() }),
+      Self::Or(alts) => serde_json::json!({ "or": alts.iter().map(Self::to_json).collect::<Vec<_>>() }),
+      Self::Truncated => serde_json::Value::Null,
+    }
+  }
+}
+
+impl std::fmt::Display for ProofTree {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      Self::Fact(id) => f.write_fmt(format_args!("{}", id)),
+      Self::And(conjs) => f.write_fmt(format_args!(
+        "and({})",
+        conjs.iter().map(|c| format!("{}", c)).collect::<Vec<_>>().join(", ")
+      )),
+      Self::Or(alts) => f.write_fmt(format_args!(
+        "or({})",
+        alts.iter().map(|a| format!("{}", a)).collect::<Vec<_>>().join(", ")
+      )),
+      Self::Truncated => f.write_str("..."),
+    }
+  }
+}
+
+impl std::fmt::Debug for ProofTree {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    std::fmt::Display::fmt(self, f)
+  }
+}
+
+impl Tag for ProofTree {}
+
+/// The provenance tracking the full derivation trees of the tuples (why-provenance)
+///
+/// Unlike the proofs provenance, which only keeps the sets of facts used by each proof, the trees
+/// also keep how the facts are combined. Since a recursive program can have infinitely many proofs,
+/// the size of each tree is capped by `max_size`: alternative proofs are no longer added once the
+/// cap is reached, and conjunctions exceeding the cap are truncated.
+pub struct ProofTreeProvenance<P: PointerFamily> {
+  id_allocator: P::Cell<IdAllocator>,
+  max_size: usize,
+}
+
+impl<P: PointerFamily> ProofTreeProvenance<P> {
+  pub fn new(max_size: usize) -> Self {
+    Self {
+      id_allocator: P::new_cell(IdAllocator::default()),
+      max_size,
+    }
+  }
+
+  pub fn max_size(&self) -> usize {
+    self.max_size
+  }
+}
+
+impl<P: PointerFamily> Default for ProofTreeProvenance<P> {
+  fn default() -> Self {
+    Self::new(1000)
+  }
+}
+
+impl<P: PointerFamily> Clone for ProofTreeProvenance<P> {
+  fn clone(&self) -> Self {
+    Self {
+      id_allocator: P::clone_cell(&self.id_allocator),
+      max_size: self.max_size,
+    }
+  }
+}
+
+impl<P: PointerFamily> Provenance for ProofTreeProvenance<P> {
+  type Tag = ProofTree;
+
+  type InputTag = ();
+
+  type OutputTag = ProofTree;
+
+  fn name() -> &'static str {
+    "prooftree"
+  }
+
+  fn tagging_fn(&self, _: Self::InputTag) -> Self::Tag {
+    ProofTree::Fact(P::get_cell_mut(&self.id_allocator, |a| a.alloc()))
+  }
+
+  fn recover_fn(&self, t: &Self::Tag) -> Self::OutputTag {
+    t.clone()
+  }
+
+  fn discard(&self, t: &Self::Tag) -> bool {
+    t.is_zero()
+  }
+
+  fn zero(&self) -> Self::Tag {
+    ProofTree::zero()
+  }
+
+  fn one(&self) -> Self::Tag {
+    ProofTree::one()
+  }
+
+  fn add(&self, t1: &Self::Tag, t2: &Self::Tag) -> Self::Tag {
+    let sum = ProofTree::union(t1, t2);
+    if sum.size() > self.max_size {
+      t1.clone()
+    } else {
+      sum
+    }
+  }
+
+  fn mult(&self, t1: &Self::Tag, t2: &Self::Tag) -> Self::Tag {
+    let prod = ProofTree::conjunction(t1, t2);
+    if prod.size() > self.max_size {
+      ProofTree::Truncated
+    } else {
+      prod
+    }
+  }
+
+  fn saturated(&self, t_old: &Self::Tag, t_new: &Self::Tag) -> bool {
+    t_old == t_new
+  }
+
+  fn dynamic_top_k(&self, k: usize, batch: DynamicElements<Self>) -> DynamicElements<Self> {
+    unweighted_aggregate_top_k_helper(batch, k)
+  }
+
+  fn static_top_k<T: StaticTupleTrait>(&self, k: usize, batch: StaticElements<T, Self>) -> StaticElements<T, Self> {
+    unweighted_aggregate_top_k_helper(batch, k)
+  }
+}
@@ -216,6 +216,10 @@ impl<P: PointerFamily> Provenance for ProofsProvenance<P> {
     t_old == t_new
   }
 
+  fn is_idempotent(&self) -> bool {
+    true
+  }
+
   fn dynamic_top_k(&self, k: usize, batch: DynamicElements<Self>) -> DynamicElements<Self> {
     unweighted_aggregate_top_k_helper(batch, k)
   }
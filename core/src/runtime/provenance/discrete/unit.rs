@@ -23,6 +23,11 @@ impl UnitProvenance {
   }
 }
 
+/// Check whether `Prov` is the unit provenance, whose tags are omitted when printing
+pub fn is_unit_provenance<Prov: Provenance>() -> bool {
+  std::any::TypeId::of::<Prov>() == std::any::TypeId::of::<UnitProvenance>()
+}
+
 impl Provenance for UnitProvenance {
   type Tag = Unit;
 
@@ -70,6 +75,10 @@ impl Provenance for UnitProvenance {
     true
   }
 
+  fn is_idempotent(&self) -> bool {
+    true
+  }
+
   fn dynamic_top_k(&self, k: usize, batch: DynamicElements<Self>) -> DynamicElements<Self> {
     unweighted_aggregate_top_k_helper(batch, k)
   }
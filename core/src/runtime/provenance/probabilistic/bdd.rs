@@ -0,0 +1,285 @@
+use itertools::Itertools;
+
+use super::*;
+use crate::runtime::dynamic::*;
+use crate::runtime::statics::*;
+use crate::utils::{PointerFamily, RcFamily};
+
+/// Exact probabilistic reasoning, where every tag is a binary decision diagram (BDD) over the input facts
+///
+/// `add`, `mult`, and `negate` are performed as BDD apply operations, and the probability of a tag is
+/// obtained exactly by a weighted model count over its BDD.
+/// Facts of the same disjunction are mutually exclusive: the `i`-th fact of a disjunction is tagged with
+/// `x_i /\ ~x_1 /\ ... /\ ~x_(i - 1)`, and its variable is given the probability of `x_i` conditioned on
+/// none of the previous facts being chosen.
+///
+/// Note that while the result is exact, the size of a BDD can be exponential in the number of facts in
+/// the worst case, so this provenance is only suited for programs with moderate amount of uncertainty.
+/// All the BDD nodes ever created are kept alive by the provenance.
+#[derive(Debug)]
+pub struct BDDProvenance<P: PointerFamily = RcFamily> {
+  /// The probability of each BDD variable
+  pub probs: P::Cell<Vec<f64>>,
+  pub disjunctions: P::Cell<Disjunctions>,
+  pub bdd: P::Cell<BDDManager>,
+}
+
+impl<P: PointerFamily> Clone for BDDProvenance<P> {
+  fn clone(&self) -> Self {
+    Self {
+      probs: P::clone_cell(&self.probs),
+      disjunctions: P::clone_cell(&self.disjunctions),
+      bdd: P::clone_cell(&self.bdd),
+    }
+  }
+}
+
+impl<P: PointerFamily> Default for BDDProvenance<P> {
+  fn default() -> Self {
+    Self {
+      probs: P::new_cell(Vec::new()),
+      disjunctions: P::new_cell(Disjunctions::new()),
+      bdd: P::new_cell(BDDManager::new()),
+    }
+  }
+}
+
+impl<P: PointerFamily> BDDProvenance<P> {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// The number of BDD nodes created so far
+  pub fn num_bdd_nodes(&self) -> usize {
+    P::get_cell(&self.bdd, |b| b.num_nodes())
+  }
+
+  pub fn soft_cmp(fst: &f64, snd: &f64) -> bool {
+    (fst - snd).abs() < 0.001
+  }
+
+  fn tag_of_chosen_set<'a, I>(&self, all: I, chosen_ids: &Vec<usize>) -> BDDTag
+  where
+    I: Iterator<Item = &'a BDDTag>,
+  {
+    let mut tag = self.one();
+    for (id, t) in all.enumerate() {
+      if chosen_ids.contains(&id) {
+        tag = self.mult(&tag, t);
+      } else {
+        tag = self.mult(&tag, &self.negate(t).unwrap());
+      }
+    }
+    tag
+  }
+}
+
+impl<P: PointerFamily> Provenance for BDDProvenance<P> {
+  type Tag = BDDTag;
+
+  type InputTag = InputExclusiveProb;
+
+  type OutputTag = f64;
+
+  fn name() -> &'static str {
+    "bdd"
+  }
+
+  fn tagging_fn(&self, input_tag: Self::InputTag) -> Self::Tag {
+    let fact_id = P::get_cell(&self.probs, |p| p.len());
+
+    // Facts of the same disjunction that are tagged before this one
+    let previous_facts = if let Some(disj_id) = input_tag.exclusion {
+      let previous_facts = P::get_cell(&self.disjunctions, |d| {
+        d.disjunction(disj_id)
+          .map(|disj| disj.facts().iter().cloned().collect::<Vec<_>>())
+          .unwrap_or_default()
+      });
+      P::get_cell_mut(&self.disjunctions, |d| d.add_disjunction(disj_id, fact_id));
+      previous_facts
+    } else {
+      vec![]
+    };
+
+    // The variable probability is conditioned on none of the previous facts being chosen; the probability
+    // of the `j`-th previous fact is recovered from the conditional probabilities of its own variable and
+    // of the variables before it
+    let previous_prob: f64 = P::get_cell(&self.probs, |p| {
+      previous_facts
+        .iter()
+        .map(|i| {
+          p[*i]
+            * previous_facts
+              .iter()
+              .filter(|j| *j < i)
+              .map(|j| 1.0 - p[*j])
+              .product::<f64>()
+        })
+        .sum()
+    });
+    let remaining_prob = 1.0 - previous_prob;
+    let var_prob = if remaining_prob > 0.0 {
+      (input_tag.prob / remaining_prob).clamp(0.0, 1.0)
+    } else {
+      0.0
+    };
+    P::get_cell_mut(&self.probs, |p| p.push(var_prob));
+
+    // The fact is chosen while none of the previous facts are
+    P::get_cell_mut(&self.bdd, |b| {
+      let mut tag = b.var(fact_id);
+      for previous_fact_id in previous_facts {
+        let neg = b.neg_var(previous_fact_id);
+        tag = b.and(&tag, &neg);
+      }
+      tag
+    })
+  }
+
+  fn recover_fn(&self, t: &Self::Tag) -> Self::OutputTag {
+    P::get_cell(&self.probs, |p| P::get_cell(&self.bdd, |b| b.wmc(t, |v| p[v])))
+  }
+
+  fn discard(&self, t: &Self::Tag) -> bool {
+    t.is_false()
+  }
+
+  fn zero(&self) -> Self::Tag {
+    BDDTag(BDD_FALSE)
+  }
+
+  fn one(&self) -> Self::Tag {
+    BDDTag(BDD_TRUE)
+  }
+
+  fn add(&self, t1: &Self::Tag, t2: &Self::Tag) -> Self::Tag {
+    P::get_cell_mut(&self.bdd, |b| b.or(t1, t2))
+  }
+
+  fn saturated(&self, t_old: &Self::Tag, t_new: &Self::Tag) -> bool {
+    // BDDs are canonical, so equivalent formulas are the same node
+    t_old == t_new
+  }
+
+  fn mult(&self, t1: &Self::Tag, t2: &Self::Tag) -> Self::Tag {
+    P::get_cell_mut(&self.bdd, |b| b.and(t1, t2))
+  }
+
+  fn negate(&self, t: &Self::Tag) -> Option<Self::Tag> {
+    Some(P::get_cell_mut(&self.bdd, |b| b.not(t)))
+  }
+
+  fn weight(&self, t: &Self::Tag) -> f64 {
+    self.recover_fn(t)
+  }
+
+  fn chosen_set_tag(&self, batch: &DynamicElements<Self>, chosen_set: &Vec<usize>) -> Option<Self::Tag> {
+    Some(self.tag_of_chosen_set(batch.iter().map(|e| &e.tag), chosen_set))
+  }
+
+  fn dynamic_count(&self, batch: DynamicElements<Self>) -> DynamicElements<Self> {
+    if batch.is_empty() {
+      vec![DynamicElement::new(0usize, self.one())]
+    } else {
+      let mut elems = vec![];
+      for chosen_set in (0..batch.len()).powerset() {
+        let count = chosen_set.len();
+        let tag = self.tag_of_chosen_set(batch.iter().map(|e| &e.tag), &chosen_set);
+        elems.push(DynamicElement::new(count, tag));
+      }
+      elems
+    }
+  }
+
+  fn dynamic_min(&self, batch: DynamicElements<Self>) -> DynamicElements<Self> {
+    let mut elems = vec![];
+    for i in 0..batch.len() {
+      let min_elem = batch[i].tuple.clone();
+      let mut agg_tag = self.one();
+      for j in 0..i {
+        agg_tag = self.mult(&agg_tag, &self.negate(&batch[j].tag).unwrap());
+      }
+      agg_tag = self.mult(&agg_tag, &batch[i].tag);
+      elems.push(DynamicElement::new(min_elem, agg_tag));
+    }
+    elems
+  }
+
+  fn dynamic_max(&self, batch: DynamicElements<Self>) -> DynamicElements<Self> {
+    let mut elems = vec![];
+    for i in 0..batch.len() {
+      let max_elem = batch[i].tuple.clone();
+      let mut agg_tag = batch[i].tag.clone();
+      for j in i + 1..batch.len() {
+        agg_tag = self.mult(&agg_tag, &self.negate(&batch[j].tag).unwrap());
+      }
+      elems.push(DynamicElement::new(max_elem, agg_tag));
+    }
+    elems
+  }
+
+  fn dynamic_exists(&self, batch: DynamicElements<Self>) -> DynamicElements<Self> {
+    let mut exists_tag = self.zero();
+    let mut not_exists_tag = self.one();
+    for elem in batch {
+      exists_tag = self.add(&exists_tag, &elem.tag);
+      not_exists_tag = self.mult(&not_exists_tag, &self.negate(&elem.tag).unwrap());
+    }
+    let t = DynamicElement::new(true, exists_tag);
+    let f = DynamicElement::new(false, not_exists_tag);
+    vec![t, f]
+  }
+
+  fn static_count<Tup: StaticTupleTrait>(&self, batch: StaticElements<Tup, Self>) -> StaticElements<usize, Self> {
+    if batch.is_empty() {
+      vec![StaticElement::new(0, self.one())]
+    } else {
+      let mut elems = vec![];
+      for chosen_set in (0..batch.len()).powerset() {
+        let count = chosen_set.len();
+        let tag = self.tag_of_chosen_set(batch.iter().map(|e| &e.tag), &chosen_set);
+        elems.push(StaticElement::new(count, tag));
+      }
+      elems
+    }
+  }
+
+  fn static_min<Tup: StaticTupleTrait>(&self, batch: StaticElements<Tup, Self>) -> StaticElements<Tup, Self> {
+    let mut elems = vec![];
+    for i in 0..batch.len() {
+      let min_elem = batch[i].tuple.get().clone();
+      let mut agg_tag = self.one();
+      for j in 0..i {
+        agg_tag = self.mult(&agg_tag, &self.negate(&batch[j].tag).unwrap());
+      }
+      agg_tag = self.mult(&agg_tag, &batch[i].tag);
+      elems.push(StaticElement::new(min_elem, agg_tag));
+    }
+    elems
+  }
+
+  fn static_max<Tup: StaticTupleTrait>(&self, batch: StaticElements<Tup, Self>) -> StaticElements<Tup, Self> {
+    let mut elems = vec![];
+    for i in 0..batch.len() {
+      let max_elem = batch[i].tuple.get().clone();
+      let mut agg_tag = batch[i].tag.clone();
+      for j in i + 1..batch.len() {
+        agg_tag = self.mult(&agg_tag, &self.negate(&batch[j].tag).unwrap());
+      }
+      elems.push(StaticElement::new(max_elem, agg_tag));
+    }
+    elems
+  }
+
+  fn static_exists<Tup: StaticTupleTrait>(&self, batch: StaticElements<Tup, Self>) -> StaticElements<bool, Self> {
+    let mut exists_tag = self.zero();
+    let mut not_exists_tag = self.one();
+    for elem in batch {
+      exists_tag = self.add(&exists_tag, &elem.tag);
+      not_exists_tag = self.mult(&not_exists_tag, &self.negate(&elem.tag).unwrap());
+    }
+    let t = StaticElement::new(true, exists_tag);
+    let f = StaticElement::new(false, not_exists_tag);
+    vec![t, f]
+  }
+}
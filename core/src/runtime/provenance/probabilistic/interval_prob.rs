@@ -0,0 +1,188 @@
+use itertools::Itertools;
+
+use super::*;
+use crate::common::element::*;
+use crate::runtime::dynamic::*;
+use crate::runtime::statics::*;
+
+/// A probability known only up to its lower and upper bounds
+#[derive(Clone, Copy, PartialEq)]
+pub struct ProbInterval {
+  pub lo: f64,
+  pub hi: f64,
+}
+
+impl ProbInterval {
+  pub fn new(lo: f64, hi: f64) -> Self {
+    Self { lo, hi }
+  }
+
+  pub fn point(prob: f64) -> Self {
+    Self { lo: prob, hi: prob }
+  }
+}
+
+impl std::fmt::Display for ProbInterval {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.write_fmt(format_args!("[{}, {}]", self.lo, self.hi))
+  }
+}
+
+impl std::fmt::Debug for ProbInterval {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.write_fmt(format_args!("[{:?}, {:?}]", self.lo, self.hi))
+  }
+}
+
+impl From<InputProbInterval> for ProbInterval {
+  fn from(i: InputProbInterval) -> Self {
+    Self::new(i.lo, i.hi)
+  }
+}
+
+impl Tag for ProbInterval {}
+
+/// The interval extension of the add-mult probability provenance
+///
+/// Each fact is tagged with a `[lo, hi]` probability interval. Addition and multiplication are
+/// monotonic on probabilities, so they are applied on the two bounds separately, while negation
+/// swaps the bounds. The resulting interval therefore contains every result the add-mult
+/// probability provenance would compute on point probabilities picked within the input intervals.
+#[derive(Clone, Debug)]
+pub struct IntervalProbProvenance {
+  valid_threshold: f64,
+}
+
+impl IntervalProbProvenance {
+  fn tag_of_chosen_set<E: Element<Self>>(&self, all: &[E], chosen_ids: &[usize]) -> ProbInterval {
+    all
+      .iter()
+      .enumerate()
+      .map(|(id, elem)| {
+        if chosen_ids.contains(&id) {
+          *elem.tag()
+        } else {
+          self.negate(elem.tag()).unwrap()
+        }
+      })
+      .fold(self.one(), |a, b| self.mult(&a, &b))
+  }
+
+  fn tag_of_exists<'b, I: Iterator<Item = &'b ProbInterval>>(&self, tags: I) -> (ProbInterval, ProbInterval) {
+    let mut exists_tag = self.zero();
+    let mut not_exists_tag = self.one();
+    for tag in tags {
+      exists_tag = self.add(&exists_tag, tag);
+      not_exists_tag = self.mult(&not_exists_tag, &self.negate(tag).unwrap());
+    }
+    (exists_tag, not_exists_tag)
+  }
+}
+
+impl Default for IntervalProbProvenance {
+  fn default() -> Self {
+    Self {
+      valid_threshold: 0.0000,
+    }
+  }
+}
+
+impl Provenance for IntervalProbProvenance {
+  type Tag = ProbInterval;
+
+  type InputTag = InputProbInterval;
+
+  type OutputTag = ProbInterval;
+
+  fn name() -> &'static str {
+    "intervalprob"
+  }
+
+  fn tagging_fn(&self, p: Self::InputTag) -> Self::Tag {
+    p.into()
+  }
+
+  fn recover_fn(&self, t: &Self::Tag) -> Self::OutputTag {
+    *t
+  }
+
+  fn discard(&self, t: &Self::Tag) -> bool {
+    t.hi <= self.valid_threshold
+  }
+
+  fn zero(&self) -> Self::Tag {
+    ProbInterval::point(0.0)
+  }
+
+  fn one(&self) -> Self::Tag {
+    ProbInterval::point(1.0)
+  }
+
+  fn add(&self, t1: &Self::Tag, t2: &Self::Tag) -> Self::Tag {
+    ProbInterval::new((t1.lo + t2.lo).min(1.0), (t1.hi + t2.hi).min(1.0))
+  }
+
+  fn mult(&self, t1: &Self::Tag, t2: &Self::Tag) -> Self::Tag {
+    ProbInterval::new(t1.lo * t2.lo, t1.hi * t2.hi)
+  }
+
+  fn negate(&self, t: &Self::Tag) -> Option<Self::Tag> {
+    Some(ProbInterval::new(1.0 - t.hi, 1.0 - t.lo))
+  }
+
+  fn saturated(&self, _: &Self::Tag, _: &Self::Tag) -> bool {
+    true
+  }
+
+  fn weight(&self, t: &Self::Tag) -> f64 {
+    (t.lo + t.hi) / 2.0
+  }
+
+  fn chosen_set_tag(&self, batch: &DynamicElements<Self>, chosen_set: &Vec<usize>) -> Option<Self::Tag> {
+    Some(self.tag_of_chosen_set(batch, chosen_set))
+  }
+
+  fn dynamic_count(&self, batch: DynamicElements<Self>) -> DynamicElements<Self> {
+    if batch.is_empty() {
+      vec![DynamicElement::new(0usize, self.one())]
+    } else {
+      let mut result = vec![];
+      for chosen_set in (0..batch.len()).powerset() {
+        let count = chosen_set.len();
+        let tag = self.tag_of_chosen_set(&batch, &chosen_set);
+        result.push(DynamicElement::new(count, tag));
+      }
+      result
+    }
+  }
+
+  fn dynamic_exists(&self, batch: DynamicElements<Self>) -> DynamicElements<Self> {
+    let (exists_tag, not_exists_tag) = self.tag_of_exists(batch.iter().map(|e| &e.tag));
+    vec![
+      DynamicElement::new(false, not_exists_tag),
+      DynamicElement::new(true, exists_tag),
+    ]
+  }
+
+  fn static_count<Tup: StaticTupleTrait>(&self, batch: StaticElements<Tup, Self>) -> StaticElements<usize, Self> {
+    let mut result = vec![];
+    if batch.is_empty() {
+      result.push(StaticElement::new(0usize, self.one()));
+    } else {
+      for chosen_set in (0..batch.len()).powerset() {
+        let count = chosen_set.len();
+        let tag = self.tag_of_chosen_set(&batch, &chosen_set);
+        result.push(StaticElement::new(count, tag));
+      }
+    }
+    result
+  }
+
+  fn static_exists<Tup: StaticTupleTrait>(&self, batch: StaticElements<Tup, Self>) -> StaticElements<bool, Self> {
+    let (exists_tag, not_exists_tag) = self.tag_of_exists(batch.iter().map(|e| &e.tag));
+    vec![
+      StaticElement::new(false, not_exists_tag),
+      StaticElement::new(true, exists_tag),
+    ]
+  }
+}
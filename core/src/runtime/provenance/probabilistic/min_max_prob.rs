@@ -3,6 +3,7 @@ use itertools::Itertools;
 use crate::common::element::*;
 use crate::common::value_type::*;
 use crate::runtime::dynamic::*;
+use crate::runtime::env::*;
 use crate::runtime::statics::*;
 
 use super::*;
@@ -69,6 +70,10 @@ impl Provenance for MinMaxProbProvenance {
     t_old == t_new
   }
 
+  fn is_idempotent(&self) -> bool {
+    true
+  }
+
   fn mult(&self, t1: &Self::Tag, t2: &Self::Tag) -> Self::Tag {
     t1.min(*t2)
   }
@@ -81,6 +86,10 @@ impl Provenance for MinMaxProbProvenance {
     *t
   }
 
+  fn chosen_set_tag(&self, batch: &DynamicElements<Self>, chosen_set: &Vec<usize>) -> Option<Self::Tag> {
+    Some(min_prob_of_chosen_set(batch, chosen_set))
+  }
+
   fn dynamic_count(&self, mut batch: DynamicElements<Self>) -> DynamicElements<Self> {
     if batch.is_empty() {
       vec![DynamicElement::new(0usize, self.one())]
@@ -95,11 +104,20 @@ impl Provenance for MinMaxProbProvenance {
     }
   }
 
-  fn dynamic_sum(&self, ty: &ValueType, batch: DynamicElements<Self>) -> DynamicElements<Self> {
+  fn dynamic_sum(
+    &self,
+    ty: &ValueType,
+    batch: DynamicElements<Self>,
+    rt: &RuntimeEnvironment,
+  ) -> DynamicElements<Self> {
     let mut elems = vec![];
     for chosen_set in (0..batch.len()).powerset() {
       let chosen_elements = collect_chosen_elements(&batch, &chosen_set);
-      let sum = ty.sum(chosen_elements.iter_tuples());
+      let sum = if rt.stable_float_aggregation {
+        ty.stable_sum(chosen_elements.iter_tuples())
+      } else {
+        ty.sum(chosen_elements.iter_tuples())
+      };
       let prob = min_prob_of_chosen_set(&batch, &chosen_set);
       elems.push(DynamicElement::new(sum, prob));
     }
@@ -1,4 +1,6 @@
+pub mod bdd;
 pub mod add_mult_prob;
+pub mod interval_prob;
 pub mod min_max_prob;
 pub mod prob_proofs;
 pub mod sample_k_proofs;
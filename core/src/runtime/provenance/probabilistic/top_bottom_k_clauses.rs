@@ -10,6 +10,10 @@ use crate::utils::{PointerFamily, RcFamily};
 #[derive(Debug)]
 pub struct TopBottomKClausesProvenance<P: PointerFamily = RcFamily> {
   pub k: usize,
+
+  /// Tags whose probabilities differ by at most this epsilon are considered saturated; when it is
+  /// zero, the formulas themselves need to be equal
+  pub saturation_epsilon: f64,
   pub probs: P::Cell<Vec<f64>>,
   pub disjunctions: P::Cell<Disjunctions>,
 }
@@ -18,6 +22,7 @@ impl<P: PointerFamily> Clone for TopBottomKClausesProvenance<P> {
   fn clone(&self) -> Self {
     Self {
       k: self.k,
+      saturation_epsilon: self.saturation_epsilon,
       probs: P::clone_cell(&self.probs),
       disjunctions: P::clone_cell(&self.disjunctions),
     }
@@ -28,6 +33,7 @@ impl<P: PointerFamily> TopBottomKClausesProvenance<P> {
   pub fn new(k: usize) -> Self {
     Self {
       k,
+      saturation_epsilon: 0.0,
       probs: P::new_cell(Vec::new()),
       disjunctions: P::new_cell(Disjunctions::new()),
     }
@@ -36,6 +42,10 @@ impl<P: PointerFamily> TopBottomKClausesProvenance<P> {
   pub fn set_k(&mut self, k: usize) {
     self.k = k;
   }
+
+  pub fn set_saturation_epsilon(&mut self, saturation_epsilon: f64) {
+    self.saturation_epsilon = saturation_epsilon;
+  }
 }
 
 impl<P: PointerFamily> CNFDNFContextTrait for TopBottomKClausesProvenance<P> {
@@ -96,7 +106,11 @@ impl<P: PointerFamily> Provenance for TopBottomKClausesProvenance<P> {
   }
 
   fn saturated(&self, t_old: &Self::Tag, t_new: &Self::Tag) -> bool {
-    t_old == t_new
+    if self.saturation_epsilon > 0.0 {
+      (self.weight(t_old) - self.weight(t_new)).abs() <= self.saturation_epsilon
+    } else {
+      t_old == t_new
+    }
   }
 
   fn mult(&self, t1: &Self::Tag, t2: &Self::Tag) -> Self::Tag {
@@ -113,6 +127,10 @@ impl<P: PointerFamily> Provenance for TopBottomKClausesProvenance<P> {
     t.wmc(&s, &v)
   }
 
+  fn chosen_set_tag(&self, batch: &DynamicElements<Self>, chosen_set: &Vec<usize>) -> Option<Self::Tag> {
+    Some(self.top_bottom_k_tag_of_chosen_set(batch.iter().map(|e| &e.tag), chosen_set, self.k))
+  }
+
   fn dynamic_count(&self, batch: DynamicElements<Self>) -> DynamicElements<Self> {
     if batch.is_empty() {
       vec![DynamicElement::new(0usize, self.one())]
@@ -1,12 +1,15 @@
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashSet};
 use std::fmt::{Debug, Display};
 
+use itertools::Itertools;
 use rand::distributions::WeightedIndex;
 use rand::prelude::*;
 
 use super::*;
 
+use crate::common::tuple::Tuple;
 use crate::common::tuples::*;
+use crate::common::value::Value;
 use crate::common::value_type::*;
 use crate::runtime::dynamic::*;
 use crate::runtime::env::*;
@@ -42,6 +45,12 @@ pub trait Provenance: Clone + 'static {
 
   fn saturated(&self, t_old: &Self::Tag, t_new: &Self::Tag) -> bool;
 
+  /// Whether adding a tag to itself leaves the tag unchanged; the facts derived under such a provenance can be
+  /// maintained incrementally, since deriving a fact once more does not change its tag
+  fn is_idempotent(&self) -> bool {
+    false
+  }
+
   fn mult(&self, t1: &Self::Tag, t2: &Self::Tag) -> Self::Tag;
 
   fn negate(&self, _: &Self::Tag) -> Option<Self::Tag> {
@@ -56,12 +65,62 @@ pub trait Provenance: Clone + 'static {
     1.0
   }
 
+  /// The tag of the chosen elements of the batch being present while the other elements are absent; `None` if
+  /// the provenance does not reason about the absence of elements
+  fn chosen_set_tag(&self, _: &DynamicElements<Self>, _: &Vec<usize>) -> Option<Self::Tag> {
+    None
+  }
+
+  /// Aggregate every chosen set of the batch with `f`, tagging the results with the tag of the chosen set;
+  /// `None` if the provenance does not reason about the absence of elements (see `chosen_set_tag`)
+  fn aggregate_chosen_sets<F>(&self, batch: &DynamicElements<Self>, f: F) -> Option<DynamicElements<Self>>
+  where
+    F: Fn(&Vec<&DynamicElement<Self>>) -> Vec<Tuple>,
+  {
+    let mut elems = vec![];
+    for chosen_set in (0..batch.len()).powerset() {
+      let tag = self.chosen_set_tag(batch, &chosen_set)?;
+      let chosen_elements = collect_chosen_elements(batch, &chosen_set);
+      elems.extend(f(&chosen_elements).into_iter().map(|t| DynamicElement::new(t, tag.clone())));
+    }
+    Some(elems)
+  }
+
   fn dynamic_count(&self, batch: DynamicElements<Self>) -> DynamicElements<Self> {
     vec![DynamicElement::new(batch.len(), self.one())]
   }
 
-  fn dynamic_sum(&self, ty: &ValueType, batch: DynamicElements<Self>) -> DynamicElements<Self> {
-    let s = ty.sum(batch.iter_tuples());
+  /// Count the distinct tuples of the batch; the tags of duplicated tuples are added
+  /// together before counting
+  fn dynamic_count_distinct(&self, batch: DynamicElements<Self>) -> DynamicElements<Self> {
+    let mut distinct = BTreeMap::<Tuple, Self::Tag>::new();
+    for elem in batch {
+      let tag = match distinct.remove(&elem.tuple) {
+        Some(tag) => self.add(&tag, &elem.tag),
+        None => elem.tag,
+      };
+      distinct.insert(elem.tuple, tag);
+    }
+    let batch = distinct
+      .into_iter()
+      .map(|(tup, tag)| DynamicElement::new(tup, tag))
+      .collect();
+    self.dynamic_count(batch)
+  }
+
+  /// Sum up the batch; the floating points are added up with compensated summation under the
+  /// `stable_float_aggregation` runtime option
+  fn dynamic_sum(
+    &self,
+    ty: &ValueType,
+    batch: DynamicElements<Self>,
+    rt: &RuntimeEnvironment,
+  ) -> DynamicElements<Self> {
+    let s = if rt.stable_float_aggregation {
+      ty.stable_sum(batch.iter_tuples())
+    } else {
+      ty.sum(batch.iter_tuples())
+    };
     vec![DynamicElement::new(s, self.one())]
   }
 
@@ -70,6 +129,90 @@ pub trait Provenance: Clone + 'static {
     vec![DynamicElement::new(p, self.one())]
   }
 
+  fn dynamic_weighted_sum(&self, ty: &ValueType, batch: DynamicElements<Self>) -> DynamicElements<Self> {
+    ty.weighted_sum(batch.iter_tuples())
+      .into_iter()
+      .map(|s| DynamicElement::new(s, self.one()))
+      .collect()
+  }
+
+  /// Compute the variance of the batch, conditioned on each chosen set of the batch when the provenance reasons
+  /// about the absence of elements
+  fn dynamic_variance(
+    &self,
+    ty: &ValueType,
+    batch: DynamicElements<Self>,
+    rt: &RuntimeEnvironment,
+  ) -> DynamicElements<Self> {
+    let variance = |elems: &Vec<&DynamicElement<Self>>| {
+      ty.variance(elems.iter_tuples(), rt.stable_float_aggregation)
+        .into_iter()
+        .map(Tuple::from)
+        .collect::<Vec<_>>()
+    };
+    self.aggregate_chosen_sets(&batch, variance).unwrap_or_else(|| {
+      variance(&batch.iter().collect())
+        .into_iter()
+        .map(|v| DynamicElement::new(v, self.one()))
+        .collect()
+    })
+  }
+
+  /// Compute the standard deviation as the square root of each variance given by `dynamic_variance`
+  fn dynamic_std_dev(
+    &self,
+    ty: &ValueType,
+    batch: DynamicElements<Self>,
+    rt: &RuntimeEnvironment,
+  ) -> DynamicElements<Self> {
+    self
+      .dynamic_variance(ty, batch, rt)
+      .into_iter()
+      .map(|elem| DynamicElement::new(elem.tuple.as_f64().sqrt(), elem.tag))
+      .collect()
+  }
+
+  fn dynamic_median(&self, ty: &ValueType, batch: DynamicElements<Self>) -> DynamicElements<Self> {
+    ty.median(batch.iter_tuples())
+      .into_iter()
+      .map(|m| DynamicElement::new(m, self.one()))
+      .collect()
+  }
+
+  fn dynamic_geometric_mean(&self, ty: &ValueType, batch: DynamicElements<Self>) -> DynamicElements<Self> {
+    ty.geometric_mean(batch.iter_tuples())
+      .into_iter()
+      .map(|m| DynamicElement::new(m, self.one()))
+      .collect()
+  }
+
+  fn dynamic_harmonic_mean(&self, ty: &ValueType, batch: DynamicElements<Self>) -> DynamicElements<Self> {
+    ty.harmonic_mean(batch.iter_tuples())
+      .into_iter()
+      .map(|m| DynamicElement::new(m, self.one()))
+      .collect()
+  }
+
+  /// Collect the values of the batch into a single sorted list; an empty batch yields an empty list. The list
+  /// is conditioned on each chosen set of the batch when the provenance reasons about the absence of elements
+  fn dynamic_collect(&self, batch: DynamicElements<Self>) -> DynamicElements<Self> {
+    let collect = |elems: &Vec<&DynamicElement<Self>>| {
+      let mut values = elems
+        .iter_tuples()
+        .map(|t| match t {
+          Tuple::Value(v) => v.clone(),
+          Tuple::Tuple(ts) if ts.len() == 1 => ts[0].as_value(),
+          _ => Value::Tuple(t.as_values()),
+        })
+        .collect::<Vec<_>>();
+      values.sort();
+      Tuple::from(Value::List(values))
+    };
+    self
+      .aggregate_chosen_sets(&batch, |elems| vec![collect(elems)])
+      .unwrap_or_else(|| vec![DynamicElement::new(collect(&batch.iter().collect()), self.one())])
+  }
+
   fn dynamic_min(&self, batch: DynamicElements<Self>) -> DynamicElements<Self> {
     batch.first().into_iter().cloned().collect()
   }
@@ -100,11 +243,65 @@ pub trait Provenance: Clone + 'static {
     vec![DynamicElement::new(!batch.is_empty(), self.one())]
   }
 
+  /// Whether there is any tuple in the batch, along with a witness; the witness is the minimum tuple, and
+  /// is flattened into the result so that each tuple is of the form `(true, witness...)`
+  ///
+  /// An empty batch has no witness to populate the witness columns with, so nothing is derived; `any` without
+  /// witness columns is compiled into `exists`, which yields `(false,)` in that case
+  fn dynamic_any(&self, batch: DynamicElements<Self>) -> DynamicElements<Self> {
+    if batch.is_empty() {
+      return vec![];
+    }
+    self
+      .dynamic_min(batch)
+      .into_iter()
+      .map(|elem| {
+        let witness = match elem.tuple {
+          Tuple::Tuple(ts) => ts.into_vec(),
+          t => vec![t],
+        };
+        let tuple = Tuple::Tuple(std::iter::once(true.into()).chain(witness).collect());
+        DynamicElement::new(tuple, elem.tag)
+      })
+      .collect()
+  }
+
+  /// The k tuples with the highest tag weights, e.g. the most likely ones under probabilistic provenances; the
+  /// values of the tuples are not compared, except for breaking ties in favor of the smaller tuples
   fn dynamic_top_k(&self, k: usize, batch: DynamicElements<Self>) -> DynamicElements<Self> {
     let ids = aggregate_top_k_helper(batch.len(), k, |id| self.weight(&batch[id].tag));
     ids.into_iter().map(|id| batch[id].clone()).collect()
   }
 
+  fn dynamic_arg_top_k(&self, k: usize, mut batch: DynamicElements<Self>) -> DynamicElements<Self> {
+    // Each tuple is of the form `(arg, value)`; rank by value in descending order and break ties by tuple ordering
+    batch.sort_by(|a, b| b.tuple[1].cmp(&a.tuple[1]).then_with(|| a.tuple.cmp(&b.tuple)));
+    batch.truncate(k);
+    batch
+  }
+
+  fn dynamic_rank(&self, mut batch: DynamicElements<Self>) -> DynamicElements<Self> {
+    batch.sort_by(|a, b| a.tuple.cmp(&b.tuple));
+    batch
+      .into_iter()
+      .enumerate()
+      .map(|(i, elem)| DynamicElement::new((i, elem.tuple), elem.tag))
+      .collect()
+  }
+
+  fn dynamic_arg_rank(&self, mut batch: DynamicElements<Self>) -> DynamicElements<Self> {
+    // Each tuple is of the form `(arg, value)`; rank by value and break ties by the argument
+    batch.sort_by(|a, b| a.tuple[1].cmp(&b.tuple[1]).then_with(|| a.tuple[0].cmp(&b.tuple[0])));
+    batch
+      .into_iter()
+      .enumerate()
+      .map(|(i, elem)| {
+        let (arg, value) = (elem.tuple[0].clone(), elem.tuple[1].clone());
+        DynamicElement::new((arg, (i, value)), elem.tag)
+      })
+      .collect()
+  }
+
   fn dynamic_categorical_k(
     &self,
     k: usize,
@@ -127,10 +324,114 @@ pub trait Provenance: Clone + 'static {
     }
   }
 
+  /// Sample one element of the batch with probability proportional to its weight, which is either the
+  /// first element of the tuple (of type `weight_ty`) or the weight of the tag. The sampled element keeps
+  /// its tag, and nothing is sampled if no element has a positive weight; weights that are not numeric count
+  /// as zero
+  fn dynamic_sample(
+    &self,
+    weight_ty: Option<&ValueType>,
+    batch: DynamicElements<Self>,
+    rt: &RuntimeEnvironment,
+  ) -> DynamicElements<Self> {
+    let weights = batch
+      .iter()
+      .map(|e| match weight_ty {
+        Some(ty) => ty.tuple_as_f64(&e.tuple[0]).map_or(0.0, |w| w.max(0.0)),
+        None => self.weight(&e.tag),
+      })
+      .collect::<Vec<_>>();
+    let dist = match WeightedIndex::new(&weights) {
+      Ok(dist) => dist,
+      Err(_) => return vec![],
+    };
+    let id = dist.sample(&mut *rt.rng());
+    let elem = batch.into_iter().nth(id).unwrap();
+    match (weight_ty, elem.tuple) {
+      (Some(_), Tuple::Tuple(ts)) => {
+        let mut rest = ts.into_vec().split_off(1);
+        let tuple = if rest.len() == 1 {
+          rest.pop().unwrap()
+        } else {
+          Tuple::Tuple(rest.into())
+        };
+        vec![DynamicElement::new(tuple, elem.tag)]
+      }
+      (_, tuple) => vec![DynamicElement::new(tuple, elem.tag)],
+    }
+  }
+
+  /// Divide the value of each element, i.e. the first element of its tuple (of type `ty`), by the sum of the
+  /// values of the batch. The value is replaced by the normalized weight at the end of the tuple, and each
+  /// element keeps its tag; nothing is produced when the values are not numeric or sum up to zero. When the
+  /// provenance reasons about the absence of elements, the values are normalized within each chosen set of the
+  /// batch and tagged with the tag of the chosen set instead
+  fn dynamic_normalize(&self, ty: &ValueType, batch: DynamicElements<Self>) -> DynamicElements<Self> {
+    let normalize = |elems: &Vec<&DynamicElement<Self>>| {
+      let values = elems
+        .iter()
+        .map(|e| match &e.tuple {
+          Tuple::Tuple(ts) => ty.tuple_as_f64(&ts[0]),
+          t => ty.tuple_as_f64(t),
+        })
+        .collect::<Option<Vec<_>>>();
+      let values = match values {
+        Some(values) => values,
+        None => return vec![],
+      };
+      let sum = values.iter().sum::<f64>();
+      if sum == 0.0 {
+        return vec![];
+      }
+      elems
+        .iter()
+        .zip(values)
+        .map(|(elem, value)| {
+          let weight = Tuple::from(value / sum);
+          let mut rest = match &elem.tuple {
+            Tuple::Tuple(ts) => ts[1..].to_vec(),
+            _ => vec![],
+          };
+          if rest.is_empty() {
+            weight
+          } else {
+            rest.push(weight);
+            Tuple::Tuple(rest.into())
+          }
+        })
+        .collect::<Vec<_>>()
+    };
+    self.aggregate_chosen_sets(&batch, normalize).unwrap_or_else(|| {
+      normalize(&batch.iter().collect())
+        .into_iter()
+        .zip(batch.iter())
+        .map(|(tuple, elem)| DynamicElement::new(tuple, elem.tag.clone()))
+        .collect()
+    })
+  }
+
   fn static_count<T: StaticTupleTrait>(&self, batch: StaticElements<T, Self>) -> StaticElements<usize, Self> {
     vec![StaticElement::new(batch.len(), self.one())]
   }
 
+  /// Count the distinct tuples of the batch; the tags of duplicated tuples are added
+  /// together before counting
+  fn static_count_distinct<T: StaticTupleTrait>(
+    &self,
+    mut batch: StaticElements<T, Self>,
+  ) -> StaticElements<usize, Self> {
+    batch.sort();
+    batch.dedup_by(|dup, kept| {
+      if dup == kept {
+        kept.tag = self.add(&kept.tag, &dup.tag);
+        true
+      } else {
+        false
+      }
+    });
+    self.static_count(batch)
+  }
+
   fn static_sum<T: StaticTupleTrait + SumType>(&self, batch: StaticElements<T, Self>) -> StaticElements<T, Self> {
     vec![StaticElement::new(
       <T as SumType>::sum(batch.iter_tuples().cloned()),
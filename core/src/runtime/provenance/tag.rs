@@ -30,6 +30,7 @@ impl<Tup: Clone + Ord + Sized, Prov: Provenance> Ord for Tagged<Tup, Prov> {
   }
 }
 
+#[cfg(not(feature = "stable"))]
 impl<Tup, Prov> std::fmt::Debug for Tagged<Tup, Prov>
 where
   Tup: Clone + Ord + Sized + std::fmt::Debug,
@@ -40,12 +41,29 @@ where
   }
 }
 
+#[cfg(not(feature = "stable"))]
 impl<Tup: Clone + Ord + Sized + std::fmt::Debug> std::fmt::Debug for Tagged<Tup, unit::UnitProvenance> {
   fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
     std::fmt::Debug::fmt(&self.tuple, f)
   }
 }
 
+#[cfg(feature = "stable")]
+impl<Tup, Prov> std::fmt::Debug for Tagged<Tup, Prov>
+where
+  Tup: Clone + Ord + Sized + std::fmt::Debug,
+  Prov: Provenance,
+{
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    if unit::is_unit_provenance::<Prov>() {
+      std::fmt::Debug::fmt(&self.tuple, f)
+    } else {
+      f.debug_tuple("").field(&self.tuple).field(&self.tag).finish()
+    }
+  }
+}
+
+#[cfg(not(feature = "stable"))]
 impl<Tup, Prov> std::fmt::Display for Tagged<Tup, Prov>
 where
   Tup: Clone + Ord + Sized + std::fmt::Display,
@@ -56,12 +74,28 @@ where
   }
 }
 
+#[cfg(not(feature = "stable"))]
 impl<Tup: Clone + Ord + Sized + std::fmt::Display> std::fmt::Display for Tagged<Tup, unit::UnitProvenance> {
   fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
     std::fmt::Display::fmt(&self.tuple, f)
   }
 }
 
+#[cfg(feature = "stable")]
+impl<Tup, Prov> std::fmt::Display for Tagged<Tup, Prov>
+where
+  Tup: Clone + Ord + Sized + std::fmt::Display,
+  Prov: Provenance,
+{
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    if unit::is_unit_provenance::<Prov>() {
+      std::fmt::Display::fmt(&self.tuple, f)
+    } else {
+      f.write_fmt(format_args!("{}::{}", self.tag, self.tuple))
+    }
+  }
+}
+
 impl Tag for bool {}
 
 impl Tag for usize {}
@@ -2,6 +2,7 @@ mod aggregator;
 mod argmax;
 mod argmin;
 mod count;
+mod count_distinct;
 mod exists;
 mod max;
 mod min;
@@ -13,6 +14,7 @@ pub use aggregator::*;
 pub use argmax::*;
 pub use argmin::*;
 pub use count::*;
+pub use count_distinct::*;
 pub use exists::*;
 pub use max::*;
 pub use min::*;
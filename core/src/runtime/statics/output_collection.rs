@@ -53,6 +53,7 @@ where
   }
 }
 
+#[cfg(not(feature = "stable"))]
 impl<Prov: Provenance, Tup: StaticTupleTrait> std::fmt::Debug for StaticOutputCollection<Tup, Prov> {
   default fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
     f.write_str("{")?;
@@ -66,6 +67,7 @@ impl<Prov: Provenance, Tup: StaticTupleTrait> std::fmt::Debug for StaticOutputCo
   }
 }
 
+#[cfg(not(feature = "stable"))]
 impl<Tup: StaticTupleTrait> std::fmt::Debug for StaticOutputCollection<Tup, unit::UnitProvenance> {
   fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
     f.write_str("{")?;
@@ -79,6 +81,26 @@ impl<Tup: StaticTupleTrait> std::fmt::Debug for StaticOutputCollection<Tup, unit
   }
 }
 
+#[cfg(feature = "stable")]
+impl<Prov: Provenance, Tup: StaticTupleTrait> std::fmt::Debug for StaticOutputCollection<Tup, Prov> {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    let omit_tag = unit::is_unit_provenance::<Prov>();
+    f.write_str("{")?;
+    for (i, (tag, tuple)) in self.elements.iter().enumerate() {
+      if i > 0 {
+        f.write_str(", ")?;
+      }
+      if omit_tag {
+        f.write_fmt(format_args!("{:?}", tuple))?;
+      } else {
+        f.write_fmt(format_args!("{:?}::{:?}", tag, tuple))?;
+      }
+    }
+    f.write_str("}")
+  }
+}
+
+#[cfg(not(feature = "stable"))]
 impl<Prov: Provenance, Tup: StaticTupleTrait> std::fmt::Display for StaticOutputCollection<Tup, Prov> {
   default fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
     f.write_str("{")?;
@@ -92,6 +114,7 @@ impl<Prov: Provenance, Tup: StaticTupleTrait> std::fmt::Display for StaticOutput
   }
 }
 
+#[cfg(not(feature = "stable"))]
 impl<Tup: StaticTupleTrait> std::fmt::Display for StaticOutputCollection<Tup, unit::UnitProvenance> {
   fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
     f.write_str("{")?;
@@ -104,3 +127,22 @@ impl<Tup: StaticTupleTrait> std::fmt::Display for StaticOutputCollection<Tup, un
     f.write_str("}")
   }
 }
+
+#[cfg(feature = "stable")]
+impl<Prov: Provenance, Tup: StaticTupleTrait> std::fmt::Display for StaticOutputCollection<Tup, Prov> {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    let omit_tag = unit::is_unit_provenance::<Prov>();
+    f.write_str("{")?;
+    for (i, (tag, tuple)) in self.elements.iter().enumerate() {
+      if i > 0 {
+        f.write_str(", ")?;
+      }
+      if omit_tag {
+        f.write_fmt(format_args!("{:?}", tuple))?;
+      } else {
+        f.write_fmt(format_args!("{}::{:?}", tag, tuple))?;
+      }
+    }
+    f.write_str("}")
+  }
+}
@@ -3,8 +3,11 @@ pub fn parse_date_time_string(d: &str) -> Option<chrono::DateTime<chrono::Utc>>
   dateparser::parse(d).ok()
 }
 
-/// Parse a string into a chrono Duration
+/// Parse a string into a chrono Duration; a plain (possibly negative) integer is a number of seconds
 pub fn parse_duration_string(d: &str) -> Option<chrono::Duration> {
+  if let Ok(secs) = d.trim().parse::<i64>() {
+    return chrono::Duration::try_seconds(secs);
+  }
   let d1 = parse_duration::parse(d).ok()?;
   chrono::Duration::from_std(d1).ok()
 }
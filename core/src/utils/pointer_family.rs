@@ -29,6 +29,10 @@ pub trait PointerFamily: Clone + PartialEq + 'static {
   /// Note that the `Rc` itself needs to be mutable here
   fn get_rc_mut<T>(ptr: &mut Self::Rc<T>) -> &mut T;
 
+  /// Get a mutable reference to the content pointed by the `Rc`, cloning the
+  /// content first if it is shared with other `Rc`s (copy-on-write)
+  fn make_rc_mut<T: Clone>(ptr: &mut Self::Rc<T>) -> &mut T;
+
   /* ==================== Cell ==================== */
 
   /// Cell
@@ -101,6 +105,10 @@ impl PointerFamily for ArcFamily {
     Arc::get_mut(ptr).unwrap()
   }
 
+  fn make_rc_mut<T: Clone>(ptr: &mut Self::Rc<T>) -> &mut T {
+    Arc::make_mut(ptr)
+  }
+
   type Cell<T> = Mutex<T>;
 
   fn new_cell<T>(value: T) -> Self::Cell<T> {
@@ -172,6 +180,10 @@ impl PointerFamily for RcFamily {
     Rc::get_mut(ptr).unwrap()
   }
 
+  fn make_rc_mut<T: Clone>(ptr: &mut Self::Rc<T>) -> &mut T {
+    Rc::make_mut(ptr)
+  }
+
   type Cell<T> = RefCell<T>;
 
   fn new_cell<T>(value: T) -> Self::Cell<T> {
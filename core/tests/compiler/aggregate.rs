@@ -0,0 +1,44 @@
+use scallop_core::common::tuple_type::TupleType;
+use scallop_core::common::value_type::ValueType;
+use scallop_core::compiler::compile_string_to_ram;
+
+#[test]
+fn shared_aggregate_body_1() {
+  let ram = compile_string_to_ram(
+    r#"
+    rel purchase = {("alice", 3), ("alice", 5), ("bob", 4)}
+    rel stats(c, n, s) = n := count(p: purchase(c, p)), s := sum(p: purchase(c, p))
+    rel other(c, n, s) = n := count(p: purchase(c, p)), s := sum(p: purchase(c, p) and p > 3)
+  "#
+    .to_string(),
+  )
+  .unwrap();
+  let num_bodies = |prefix: &str| {
+    ram
+      .relations()
+      .filter(|r| r.predicate.starts_with(prefix) && r.predicate.ends_with("#body"))
+      .count()
+  };
+  assert_eq!(num_bodies("stats#"), 1);
+  assert_eq!(num_bodies("other#"), 2);
+
+  // The aggregations sharing their body are evaluated by a single reduce
+  let program = ram.to_string();
+  assert_eq!(program.matches("Aggregation (count, sum<i32>)").count(), 1);
+  assert_eq!(program.matches("Aggregation count").count(), 1);
+  assert_eq!(program.matches("Aggregation sum<i32>").count(), 1);
+}
+
+#[test]
+fn collect_list_type_1() {
+  let ram = compile_string_to_ram(
+    r#"
+    type items(g: usize, x: i64)
+    rel seq(g, lst) = lst := collect(x: items(g, x))
+  "#
+    .to_string(),
+  )
+  .unwrap();
+  let expected = TupleType::from_types(&[ValueType::USize, ValueType::List(Box::new(ValueType::I64))], false);
+  assert_eq!(ram.relation_tuple_type("seq"), Some(expected));
+}
@@ -0,0 +1,46 @@
+use scallop_core::compiler::compile_string_to_ram;
+
+#[test]
+fn constant_fold_arith_1() {
+  let ram = compile_string_to_ram(
+    r#"
+    const N = 3
+    rel num = {1, 2, 3, 4}
+    rel big(x + 2 * 5) = num(x), x > N - 1
+  "#
+    .to_string(),
+  )
+  .unwrap();
+  let printed = format!("{}", ram);
+  assert!(printed.contains("Constant(I32(10))"));
+  assert!(printed.contains("Constant(I32(2))"));
+  assert!(!printed.contains("Mul"));
+  assert!(!printed.contains("Sub"));
+}
+
+#[test]
+fn constant_fold_if_then_else_1() {
+  let ram = compile_string_to_ram(
+    r#"
+    rel num = {1, 2, 3, 4}
+    rel big(if 3 > 2 then x + 1 else x) = num(x)
+  "#
+    .to_string(),
+  )
+  .unwrap();
+  let printed = format!("{}", ram);
+  assert!(printed.contains("Add"));
+  assert!(!printed.contains("IfThenElse"));
+}
+
+#[test]
+fn constant_fold_div_overflow_1() {
+  // The overflowing division is left unfolded instead of panicking the compiler
+  let ram = compile_string_to_ram(
+    r#"
+    rel r(x) = x == (-9223372036854775807 - 1) / -1
+  "#
+    .to_string(),
+  );
+  assert!(ram.is_ok());
+}
@@ -0,0 +1,50 @@
+use scallop_core::compiler::compile_string_to_ram;
+
+#[test]
+fn relation_doc_1() {
+  let ram = compile_string_to_ram(
+    r#"
+    /// Directed edges of the graph
+    /// given as (from, to)
+    type edge(i32, i32)
+
+    rel edge = {(0, 1), (1, 2)}
+
+    // A normal comment
+    /// The transitive closure of `edge`
+    @hidden
+    rel path(a, c) = edge(a, c) \/ path(a, b) /\ edge(b, c)
+
+    rel reach(b) = path(0, b)
+  "#
+    .to_string(),
+  )
+  .unwrap();
+  assert_eq!(
+    ram.relation("edge").unwrap().doc.as_deref(),
+    Some("Directed edges of the graph\ngiven as (from, to)")
+  );
+  assert_eq!(
+    ram.relation("path").unwrap().doc.as_deref(),
+    Some("The transitive closure of `edge`")
+  );
+  assert_eq!(ram.relation("reach").unwrap().doc, None);
+}
+
+#[test]
+fn relation_doc_2() {
+  let ram = compile_string_to_ram(
+    r#"
+    /// Separated from the declaration by an empty line
+
+    type edge(i32, i32)
+
+    ////////// Banner //////////
+    rel path(a, b) = edge(a, b)
+  "#
+    .to_string(),
+  )
+  .unwrap();
+  assert_eq!(ram.relation("edge").unwrap().doc, None);
+  assert_eq!(ram.relation("path").unwrap().doc, None);
+}
@@ -1,3 +1,4 @@
+use scallop_core::compiler;
 use scallop_core::testing::*;
 
 #[test]
@@ -54,6 +55,17 @@ fn cannot_cast_type_1() {
   )
 }
 
+#[test]
+fn cannot_cast_reduce_result_type_1() {
+  expect_front_compile_failure(
+    r#"
+    type A(i32)
+    rel B(c) = (c: bool) := count(x: A(x))
+    "#,
+    |e| e.contains("cannot cast type from `usize` to `bool`"),
+  )
+}
+
 #[test]
 fn duplicated_relation_decl_1() {
   expect_front_compile_failure(
@@ -129,6 +141,28 @@ fn bad_enum_type_decl() {
   )
 }
 
+#[test]
+fn bad_enum_type_decl_negative_1() {
+  expect_front_compile_failure(
+    r#"
+    type K = A = -1 | B
+    "#,
+    |e| e.contains("enum value `-1` found to be negative"),
+  )
+}
+
+#[test]
+fn bad_enum_type_decl_negative_2() {
+  // Negative and non-monotonic IDs are allowed, but they still need to be unique
+  expect_front_compile_failure(
+    r#"
+    @allow_negative
+    type K = A = -1 | B | C = 0
+    "#,
+    |e| e.contains("the enum ID `0` for variant `C` has already been assigned"),
+  )
+}
+
 #[test]
 fn bad_no_binding_agg_1() {
   expect_front_compile_failure(
@@ -138,3 +172,478 @@ fn bad_no_binding_agg_1() {
     |e| e.contains("binding variables of `count` aggregation cannot be empty"),
   )
 }
+
+#[test]
+fn bad_no_binding_agg_2() {
+  expect_front_compile_failure(
+    r#"
+    rel r() = x := count_distinct(edge(1, 3))
+    "#,
+    |e| e.contains("binding variables of `count_distinct` aggregation cannot be empty"),
+  )
+}
+
+#[test]
+fn implies_unbound_variable_1() {
+  expect_front_compile_failure(
+    r#"
+    rel p = {1, 2, 3}
+    rel q = {1, 3}
+    rel bad(x) = (p(x) => q(x))
+    "#,
+    |e| e.contains("Argument of the head of a rule is unbounded"),
+  )
+}
+
+#[test]
+fn disjunct_head_var_unbound_1() {
+  expect_front_compile_failure(
+    r#"
+    rel r = {1, 2, 3}
+    rel { a(x); b(y) } = r(x)
+    "#,
+    |e| e.contains("Argument of disjunct #1 in the head of the rule is unbounded"),
+  )
+}
+
+#[test]
+fn foreign_predicate_arg_not_bound_1() {
+  expect_front_compile_failure(
+    r#"
+    rel piece(i, p) = string_split(s, ",", i, p)
+    "#,
+    |e| {
+      e.contains("Argument #0 of foreign predicate `string_split` is bound, but it is given an unbound value")
+        && !e.contains("Unbound variable")
+    },
+  )
+}
+
+#[test]
+fn binding_variable_sibling_aggregations_1() {
+  // Sibling aggregations may bind variables of the same name
+  expect_compile(
+    r#"
+    rel a = {1, 2}
+    rel b = {3}
+    rel r(n, m) = n = count(o: a(o)), m = count(o: b(o))
+    "#,
+  )
+}
+
+#[test]
+fn binding_variable_escapes_1() {
+  expect_front_compile_failure(
+    r#"
+    rel color = {(0, "red"), (1, "green")}
+    rel num_colors(n) = n = count(o: color(o, _)), o > 0
+    "#,
+    |e| e.contains("binding variable `o` of an aggregation cannot be used outside"),
+  )
+}
+
+#[test]
+fn conflicting_deliminators_1() {
+  expect_front_compile_failure(
+    r#"
+    @file("edge.csv", deliminator = "\t", whitespace_separated = true)
+    type edge(i32, i32)
+    "#,
+    |e| e.contains("At most one of `deliminator`, `deliminator_regex`, and `whitespace_separated`"),
+  )
+}
+
+#[test]
+fn window_without_ordering_1() {
+  expect_front_compile_failure(
+    r#"
+    rel price = {(1, 5), (2, 3)}
+    rel rolling_sum(s) = s := window<2, sum>(p: price(_, p))
+    "#,
+    |e| e.contains("window aggregation requires arguments to order the tuples"),
+  )
+}
+
+#[test]
+fn head_uses_aggregation_internal_1() {
+  expect_front_compile_failure(
+    r#"
+    rel color = {(0, "red"), (1, "green")}
+    rel num_colors(o, n) = n := count(o: color(o, _))
+    "#,
+    |e| e.contains("Variable `o` in the head of the rule is only bound inside of an aggregation"),
+  )
+}
+
+#[test]
+fn negative_literal_unsigned_1() {
+  expect_front_compile_failure(
+    r#"
+    type data(u32)
+    rel r(x) = data(x), x > -1
+    "#,
+    |e| e.contains("`u32` and `signed numeric`"),
+  )
+}
+
+#[test]
+fn bad_variance_bindings_1() {
+  expect_front_compile_failure(
+    r#"
+    rel r(v) = v := variance(x, y: edge(x, y))
+    "#,
+    |e| e.contains("invalid amount of binding variables for `variance`"),
+  )
+}
+
+#[test]
+fn bad_weighted_sum_bindings_1() {
+  expect_front_compile_failure(
+    r#"
+    rel r(s) = s := weighted_sum(v: data(v))
+    "#,
+    |e| e.contains("invalid amount of binding variables for `weighted_sum`"),
+  )
+}
+
+#[test]
+fn top_k_multiple_arguments_1() {
+  expect_front_compile_failure(
+    r#"
+    rel r(a, b) = _ := top<2>[a, b](c: s(a, b, c))
+    "#,
+    |e| e.contains("top-k aggregation expects at most one argument"),
+  )
+}
+
+#[test]
+fn top_k_zero_1() {
+  expect_front_compile_failure(
+    r#"
+    rel r(a) = a := top<0>(a: s(a))
+    "#,
+    |e| e.contains("unknown aggregator `top<0>`"),
+  )
+}
+
+#[test]
+fn aggregator_wrong_casing_1() {
+  expect_front_compile_failure(
+    r#"
+    rel r(n) = n := Count(a: s(a))
+    "#,
+    |e| e.contains("unknown aggregator `Count`; did you mean `count`?"),
+  )
+}
+
+#[test]
+fn aggregator_missing_parameter_1() {
+  expect_front_compile_failure(
+    r#"
+    rel r(a) = a := top(a: s(a))
+    "#,
+    |e| e.contains("`top` requires the number of tuples to keep, e.g. `top<3>`"),
+  )
+}
+
+#[test]
+fn aggregator_unexpected_parameter_1() {
+  expect_front_compile_failure(
+    r#"
+    rel r(n) = n := count<3>(a: s(a))
+    "#,
+    |e| e.contains("unknown aggregator `count<3>`; `count` does not take parameters"),
+  )
+}
+
+#[test]
+fn aggregator_invalid_window_1() {
+  expect_front_compile_failure(
+    r#"
+    rel r(n) = n := window<3, argmax>[t](a: s(t, a))
+    "#,
+    |e| e.contains("`argmax` cannot be used inside of a window"),
+  )
+}
+
+#[test]
+fn quote_char_not_single_character_1() {
+  expect_front_compile_failure(
+    r#"
+    @file("edge.csv", quote_char = "''")
+    type edge(i32, i32)
+    "#,
+    |e| e.contains("`quote_char` attribute is not a single ASCII character string"),
+  )
+}
+
+#[test]
+fn rank_output_arity_1() {
+  expect_front_compile_failure(
+    r#"
+    rel data = {1, 2}
+    rel ranked(i) = i := rank(x: data(x))
+    "#,
+    |e| e.contains("invalid amount of output for `rank`. Expected 2, found 1"),
+  )
+}
+
+#[test]
+fn reduce_arg_unbound_no_atom_1() {
+  expect_front_compile_failure(
+    r#"
+      rel p = {(0, 1), (1, 2)}
+      rel r(x, m) = m := max[x](y: p(_, y))
+    "#,
+    |e| e.contains("argument `x` for the aggregation is unbounded") && e.contains("did you mean to join on `x`"),
+  )
+}
+
+#[test]
+fn reduce_arg_unbound_neg_atom_1() {
+  expect_front_compile_failure(
+    r#"
+      rel p = {(0, 1), (1, 2)}
+      rel q = {0}
+      rel r(x, m) = m := max[x](y: p(_, y) and ~q(x))
+    "#,
+    |e| e.contains("argument `x` for the aggregation is unbounded") && e.contains("occurs in the following atoms"),
+  )
+}
+
+#[test]
+fn function_arity_mismatch_1() {
+  expect_front_compile_failure(r#"rel r($substring("hello")) = 1 == 1"#, |e| {
+    e.contains("wrong number of arguments for function `substring`. Expected 2 to 3, found 1")
+  });
+  expect_front_compile_failure(r#"rel r($abs(1, 2)) = 1 == 1"#, |e| {
+    e.contains("wrong number of arguments for function `abs`. Expected 1, found 2")
+  });
+}
+
+#[test]
+fn any_output_arity_1() {
+  expect_front_compile_failure(
+    r#"
+    rel data = {1, 2}
+    rel r(b) = b := any(x: data(x))
+    "#,
+    |e| e.contains("invalid amount of output for `any`. Expected 2, found 1"),
+  )
+}
+
+#[test]
+fn argmax_num_arguments_1() {
+  expect_front_compile_failure(
+    r#"
+    rel score = {(0, 1, 90)}
+    rel r(s, p, c) = (s, p, c) := argmax[s, p](c: score(p, c, s))
+    "#,
+    |e| e.contains("`argmax` aggregation expects exactly one argument as the key, found 2"),
+  )
+}
+
+#[test]
+fn dead_relation_warning_1() {
+  let mut ctx = compiler::front::FrontContext::new();
+  ctx
+    .compile_source(compiler::front::StringSource::new(
+      r#"
+      rel edge = {(0, 1), (1, 2)}
+      rel edeg = {(2, 3)}
+      rel path(a, b) = edge(a, b) or (path(a, c) and edge(c, b))
+      query path
+      "#
+      .to_string(),
+    ))
+    .unwrap();
+  let warnings = ctx.dead_relation_warnings();
+  assert!(warnings.has_warning() && !warnings.has_error());
+  let report = format!("{}", warnings);
+  assert!(report.contains("relation `edeg` is declared but never used or queried"));
+  assert!(!report.contains("`edge`") && !report.contains("`path`"));
+}
+
+#[test]
+fn dead_relation_warning_2() {
+  // Without any query, all the relations which are not hidden are outputs
+  let mut ctx = compiler::front::FrontContext::new();
+  ctx
+    .compile_source(compiler::front::StringSource::new(
+      r#"
+      rel edge = {(0, 1), (1, 2)}
+      @hidden rel scratch = {(2, 3)}
+      "#
+      .to_string(),
+    ))
+    .unwrap();
+  let report = format!("{}", ctx.dead_relation_warnings());
+  assert!(report.contains("relation `scratch` is declared but never used or queried"));
+  assert!(!report.contains("`edge`"));
+
+  // Once the hidden relation is used by a later rule, there is no more warning
+  ctx
+    .compile_source(compiler::front::StringSource::new(
+      "rel path(a, b) = scratch(a, b)".to_string(),
+    ))
+    .unwrap();
+  assert!(!ctx.dead_relation_warnings().has_warning());
+}
+
+#[test]
+fn exclusive_relation_warning_1() {
+  let mut ctx = compiler::front::FrontContext::new();
+  ctx
+    .compile_source(compiler::front::StringSource::new(
+      r#"
+      rel color = {0.3::"red"; 0.7::"blue"}
+      rel var = {0, 1}
+      rel { assign(x, true); assign(x, false) } = var(x)
+      rel fav = {"green"}
+      "#
+      .to_string(),
+    ))
+    .unwrap();
+  assert!(ctx
+    .analysis
+    .borrow()
+    .exclusive_relation_analysis
+    .conflicting_relations()
+    .is_empty());
+
+  // Deriving the exclusive relations with regular rules is only a warning
+  ctx
+    .compile_source(compiler::front::StringSource::new(
+      r#"
+      rel color(c) = fav(c)
+      rel assign(2, true)
+      rel assign(x, true) = var(x), x > 0
+      "#
+      .to_string(),
+    ))
+    .unwrap();
+  assert_eq!(
+    ctx
+      .analysis
+      .borrow()
+      .exclusive_relation_analysis
+      .conflicting_relations(),
+    vec!["assign", "color"]
+  );
+}
+
+#[test]
+fn strict_relation_declarations_1() {
+  let options = compiler::CompileOptions {
+    strict_relation_declarations: true,
+    ..Default::default()
+  };
+  let result = compiler::compile_string_to_ram_with_options(
+    r#"
+    type edge(i32, i32)
+    type path(i32, i32)
+    rel edge = {(0, 1), (1, 2)}
+    rel path(a, b) = edge(a, b) or (path(a, c) and egde(c, b))
+    "#
+    .to_string(),
+    &options,
+  );
+  let report = match result {
+    Err(es) => es.iter().map(|e| format!("{}", e)).collect::<Vec<_>>().join("\n"),
+    Ok(_) => panic!("Compilation passed; expected failure"),
+  };
+  assert!(report.contains("relation `egde` is used without a prior type declaration"));
+  assert!(!report.contains("`edge`") && !report.contains("`path`"));
+}
+
+#[test]
+fn strict_relation_declarations_2() {
+  // Foreign predicates and the temporary relations generated for aggregations are exempt
+  let options = compiler::CompileOptions {
+    strict_relation_declarations: true,
+    ..Default::default()
+  };
+  let program = r#"
+    type edge(i32, i32)
+    type num_out(i32, usize)
+    rel edge = {(0, 1), (1, 2), (0, 2)}
+    rel num_out(a, n) = n := count(b: edge(a, b) and range_i32(0, 3, b))
+  "#;
+  compiler::compile_string_to_ram_with_options(program.to_string(), &options).expect("Compile Failure");
+
+  // Without the option, the undeclared relations are inferred
+  expect_compile("rel path(a, b) = edge(a, b)\nrel edge = {(0, 1)}");
+}
+
+#[test]
+fn strict_relation_declarations_3() {
+  // The relations defined by fact sets and facts should be declared as well
+  let options = compiler::CompileOptions {
+    strict_relation_declarations: true,
+    ..Default::default()
+  };
+  let result = compiler::compile_string_to_ram_with_options(
+    r#"
+    type edge(i32, i32)
+    rel edge = {(0, 1), (1, 2)}
+    rel foo = {(1, 2)}
+    rel bar(3, 4)
+    "#
+    .to_string(),
+    &options,
+  );
+  let report = match result {
+    Err(es) => es.iter().map(|e| format!("{}", e)).collect::<Vec<_>>().join("\n"),
+    Ok(_) => panic!("Compilation passed; expected failure"),
+  };
+  assert!(report.contains("relation `foo` is used without a prior type declaration"));
+  assert!(report.contains("relation `bar` is used without a prior type declaration"));
+  assert!(!report.contains("`edge`"));
+}
+
+#[test]
+fn k_attr_invalid_argument_1() {
+  expect_front_compile_failure(
+    r#"
+    @k("five")
+    rel path(a, b) = edge(a, b)
+    "#,
+    |e| e.contains("Invalid argument of @k attribute"),
+  )
+}
+
+#[test]
+fn k_attr_conflicting_1() {
+  expect_front_compile_failure(
+    r#"
+    @k(3)
+    rel path(a, b) = edge(a, b)
+
+    @k(5)
+    rel path(a, c) = path(a, b) and edge(b, c)
+    "#,
+    |e| e.contains("Conflicting @k attributes"),
+  )
+}
+
+#[test]
+fn if_then_else_incompatible_branches_1() {
+  expect_front_compile_failure(
+    r#"
+    type a(bool, String)
+    rel b(if x then x else y) = a(x, y)
+    "#,
+    |e| e.contains("cannot unify"),
+  )
+}
+
+#[test]
+fn if_then_else_incompatible_branches_2() {
+  expect_front_compile_failure(
+    r#"
+    rel a = {1, 2}
+    rel b(y) = a(x), y == (if true then x else "hello")
+    "#,
+    |e| e.contains("cannot unify"),
+  )
+}
@@ -1,4 +1,13 @@
+mod aggregate;
+mod constant_fold;
+mod doc;
 mod errors;
 mod incremental;
 mod parse;
 mod ram2rs;
+mod ram_builder;
+mod ram_merge;
+mod ram_optimizations;
+mod ram_serialize;
+mod rename;
+mod strata;
@@ -1,4 +1,5 @@
 use scallop_core::compiler::front::parser::*;
+use scallop_core::compiler::front::{AstNodeLocation, Sources, StringSource};
 
 #[test]
 fn parse_type_decl() {
@@ -29,3 +30,18 @@ fn parse_rule() {
   assert!(str_to_item(r#"rel path(a, b) :- path(a, c) /\ edge(c, b)"#).is_ok());
   assert!(str_to_item(r#"rel path(a, b) :- edge(a, b) \/ path(a, c) /\ edge(c, b)"#).is_ok());
 }
+
+#[test]
+fn location_line_col() {
+  let mut sources = Sources::new();
+  let source_id = sources.add(StringSource::new(
+    "rel edge = {(0, 1)}\nrel path(a, b) = edge(a, b)".to_string(),
+  ));
+  let mut loc = AstNodeLocation::from_offset_span(37, 41);
+  loc.source_id = source_id;
+  assert_eq!(loc.line_col(&sources), (2, 18));
+  assert!(loc.report(&sources).contains("2:18"));
+
+  let loc = AstNodeLocation::from_offset_span(0, 3);
+  assert_eq!(loc.line_col(&sources), (1, 1));
+}
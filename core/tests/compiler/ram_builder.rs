@@ -0,0 +1,145 @@
+use scallop_core::common::binary_op::BinaryOp;
+use scallop_core::common::expr::Expr;
+use scallop_core::common::tuple_type::TupleType;
+use scallop_core::common::value_type::ValueType;
+use scallop_core::compiler::compile_string_to_ram;
+use scallop_core::compiler::ram::*;
+
+fn edge_type() -> TupleType {
+  TupleType::from_types(&[ValueType::I32, ValueType::I32], false)
+}
+
+#[test]
+fn build_filter_project_1() {
+  let builder = DataflowBuilder::relation("edge", edge_type())
+    .filter(Expr::access(0).lt(Expr::access(1)))
+    .unwrap()
+    .project((
+      Expr::access(1),
+      Expr::binary(BinaryOp::Add, Expr::access(0), Expr::constant(1i32)),
+    ))
+    .unwrap();
+  assert_eq!(builder.tuple_type(), &edge_type());
+  assert_eq!(
+    builder.build(),
+    Dataflow::relation("edge")
+      .filter(Expr::access(0).lt(Expr::access(1)))
+      .project((
+        Expr::access(1),
+        Expr::binary(BinaryOp::Add, Expr::access(0), Expr::constant(1i32))
+      ))
+  );
+}
+
+#[test]
+fn build_join_1() {
+  let left = DataflowBuilder::relation("edge", edge_type());
+  let right = DataflowBuilder::relation("edge", edge_type())
+    .project((Expr::access(1), Expr::access(0)))
+    .unwrap();
+  let joined = left.join(right).unwrap();
+  assert_eq!(
+    joined.tuple_type(),
+    &TupleType::from_types(&[ValueType::I32, ValueType::I32, ValueType::I32], false)
+  );
+}
+
+#[test]
+fn build_from_program_1() {
+  let ram = compile_string_to_ram("type name(usize, String)".to_string()).unwrap();
+  let builder = DataflowBuilder::relation_of_program(&ram, "name")
+    .unwrap()
+    .project(Expr::call("string_length".to_string(), vec![Expr::access(1)]))
+    .unwrap();
+  assert_eq!(builder.tuple_type(), &TupleType::Value(ValueType::USize));
+  assert!(matches!(
+    DataflowBuilder::relation_of_program(&ram, "nome"),
+    Err(DataflowTypeError::UnknownRelation { .. })
+  ));
+}
+
+#[test]
+fn bad_access_out_of_range_1() {
+  let result = DataflowBuilder::relation("edge", edge_type()).project((Expr::access(0), Expr::access(2)));
+  assert!(matches!(result, Err(DataflowTypeError::IndexOutOfRange { .. })));
+}
+
+#[test]
+fn bad_access_into_value_1() {
+  let result = DataflowBuilder::relation("edge", edge_type()).project(Expr::access((0, 0)));
+  assert!(matches!(result, Err(DataflowTypeError::IndexOutOfRange { .. })));
+}
+
+#[test]
+fn bad_binary_operands_1() {
+  let result =
+    DataflowBuilder::relation("edge", edge_type()).filter(Expr::access(0).lt(Expr::constant("a".to_string())));
+  match result {
+    Err(e @ DataflowTypeError::InvalidOperandTypes { .. }) => {
+      assert_eq!(e.to_string(), "Cannot apply `<` on operands of type I32, String")
+    }
+    r => panic!("Expected invalid operand types, found {:?}", r),
+  }
+}
+
+#[test]
+fn bad_filter_non_boolean_1() {
+  let result = DataflowBuilder::relation("edge", edge_type()).filter(Expr::access(0));
+  assert!(matches!(result, Err(DataflowTypeError::NonBooleanFilter { .. })));
+}
+
+#[test]
+fn bad_function_call_1() {
+  let builder = DataflowBuilder::relation("edge", edge_type());
+  assert!(matches!(
+    builder.type_of_expr(&Expr::call("string_length".to_string(), vec![Expr::access(0)])),
+    Err(DataflowTypeError::InvalidOperandTypes { .. })
+  ));
+  assert!(matches!(
+    builder.type_of_expr(&Expr::call("abs".to_string(), vec![])),
+    Err(DataflowTypeError::FunctionArityMismatch { .. })
+  ));
+  assert!(matches!(
+    builder.type_of_expr(&Expr::call("not_a_function".to_string(), vec![])),
+    Err(DataflowTypeError::UnknownFunction { .. })
+  ));
+}
+
+#[test]
+fn bad_join_key_1() {
+  let left = DataflowBuilder::relation("edge", edge_type());
+  let right = DataflowBuilder::relation(
+    "name",
+    TupleType::from_types(&[ValueType::String, ValueType::I32], false),
+  );
+  assert!(matches!(
+    left.join(right),
+    Err(DataflowTypeError::InvalidOperandTypes { .. })
+  ));
+}
+
+#[test]
+fn build_sort_1() {
+  let builder = DataflowBuilder::relation("edge", edge_type())
+    .sort(vec![(1, false), (0, true)])
+    .unwrap();
+  assert_eq!(builder.tuple_type(), &edge_type());
+  assert_eq!(
+    builder.build(),
+    Dataflow::relation("edge").sort(vec![(1, false), (0, true)])
+  );
+}
+
+#[test]
+fn bad_sort_column_out_of_range_1() {
+  match DataflowBuilder::relation("edge", edge_type()).sort(vec![(0, true), (2, false)]) {
+    Err(e @ DataflowTypeError::SortColumnOutOfRange { .. }) => {
+      assert_eq!(e.to_string(), "Sort column 2 is out of range for tuple type (I32, I32)")
+    }
+    r => panic!("Expected sort column out of range, found {:?}", r),
+  }
+  assert!(matches!(
+    DataflowBuilder::relation("value", TupleType::Value(ValueType::I32)).sort(vec![(0, true)]),
+    Err(DataflowTypeError::SortColumnOutOfRange { .. })
+  ));
+}
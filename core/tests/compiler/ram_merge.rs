@@ -0,0 +1,154 @@
+use scallop_core::common::foreign_function::ClosureForeignFunction;
+use scallop_core::common::tuple::Tuple;
+use scallop_core::common::value::Value;
+use scallop_core::common::value_type::ValueType;
+use scallop_core::compiler::compile_string_to_ram;
+use scallop_core::compiler::ram::MergeError;
+use scallop_core::runtime::dynamic;
+use scallop_core::runtime::env;
+use scallop_core::runtime::provenance::*;
+use scallop_core::utils::RcFamily;
+
+#[test]
+fn merge_edge_and_path_libraries() {
+  let mut lib1 = compile_string_to_ram(
+    r#"
+    rel edge = {(0, 1), (1, 2)}
+    rel node(a) = edge(a, _) or edge(_, a)
+  "#
+    .to_string(),
+  )
+  .unwrap();
+  let lib2 = compile_string_to_ram(
+    r#"
+    type edge(i32, i32)
+    rel edge = {(1, 2), (2, 3)}
+    rel path(a, c) = edge(a, c) or path(a, b) and edge(b, c)
+    rel unreachable(a, b) = node(a) and node(b) and ~path(a, b)
+    type node(i32)
+  "#
+    .to_string(),
+  )
+  .unwrap();
+  lib1.merge(lib2).unwrap();
+
+  // The facts of `edge` are merged without duplicates, and the strata are consistently ordered
+  assert_eq!(lib1.relation("edge").unwrap().facts.len(), 3);
+  let (path_stratum, path_recursive) = lib1.stratum_of("path").unwrap();
+  let (unreachable_stratum, _) = lib1.stratum_of("unreachable").unwrap();
+  assert!(path_recursive);
+  assert!(lib1.stratum_of("edge").unwrap().0 < path_stratum);
+  assert!(lib1.stratum_of("node").unwrap().0 < unreachable_stratum);
+  assert!(path_stratum < unreachable_stratum);
+
+  // Execute the merged program
+  let mut ctx = unit::UnitProvenance::default();
+  let runtime = env::RuntimeEnvironment::default();
+  let mut exec_ctx = dynamic::DynamicExecutionContext::<_, RcFamily>::new();
+  exec_ctx.incremental_execute(lib1, &runtime, &mut ctx).unwrap();
+  for r in ["path", "node", "unreachable"] {
+    exec_ctx.recover(r, &ctx);
+  }
+  assert_eq!(exec_ctx.relation_ref("path").unwrap().len(), 6);
+  assert_eq!(exec_ctx.relation_ref("node").unwrap().len(), 4);
+  assert_eq!(exec_ctx.relation_ref("unreachable").unwrap().len(), 10);
+}
+
+#[test]
+fn merge_renames_generated_relations() {
+  let mut lib1 = compile_string_to_ram(
+    r#"
+    rel edge = {(0, 1), (1, 2)}
+    rel path(a, c) = edge(a, c) or path(a, b) and edge(b, c)
+  "#
+    .to_string(),
+  )
+  .unwrap();
+  let lib2 = compile_string_to_ram(
+    r#"
+    rel edge = {(0, 1), (1, 2)}
+    rel path(a, c) = edge(a, c) or edge(a, b) and path(b, c)
+  "#
+    .to_string(),
+  )
+  .unwrap();
+  let num_relations = lib1.relations().count() + lib2.relations().filter(|r| r.predicate.contains('#')).count();
+  lib1.merge(lib2).unwrap();
+  assert_eq!(lib1.relations().count(), num_relations);
+
+  let mut ctx = unit::UnitProvenance::default();
+  let runtime = env::RuntimeEnvironment::default();
+  let mut exec_ctx = dynamic::DynamicExecutionContext::<_, RcFamily>::new();
+  exec_ctx.incremental_execute(lib1, &runtime, &mut ctx).unwrap();
+  exec_ctx.recover("path", &ctx);
+  let path = exec_ctx.relation_ref("path").unwrap();
+  assert_eq!(path.len(), 3);
+  assert!(path.iter().any(|(_, t)| t == &Tuple::from((0i32, 2i32))));
+}
+
+#[test]
+fn merge_conflicting_relation_type() {
+  let mut lib1 = compile_string_to_ram("rel edge = {(0, 1)}".to_string()).unwrap();
+  let lib2 = compile_string_to_ram("rel edge = {(0, \"a\")}".to_string()).unwrap();
+  match lib1.merge(lib2) {
+    Err(MergeError::ConflictingRelationType { relation, .. }) => assert_eq!(relation, "edge"),
+    r => panic!("Expected conflicting relation type, found {:?}", r),
+  }
+}
+
+#[test]
+fn merge_non_stratifiable() {
+  let mut lib1 = compile_string_to_ram(
+    r#"
+    type a(i32), b(i32)
+    rel a = {1, 2}
+    rel b(x) = a(x) and ~c(x)
+    rel c(x) = a(x) and x > 5
+  "#
+    .to_string(),
+  )
+  .unwrap();
+  let lib2 = compile_string_to_ram(
+    r#"
+    type a(i32), b(i32), c(i32)
+    rel c(x) = b(x)
+  "#
+    .to_string(),
+  )
+  .unwrap();
+  match lib1.merge(lib2) {
+    Err(MergeError::NonStratifiable { relations }) => assert_eq!(relations, vec!["c".to_string()]),
+    r => panic!("Expected non-stratifiable error, found {:?}", r),
+  }
+}
+
+#[test]
+fn merge_conflicting_function() {
+  let id = |args: Vec<Value>| args.into_iter().next();
+  let mut lib1 = compile_string_to_ram("rel r = {1}".to_string()).unwrap();
+  let mut lib2 = compile_string_to_ram("rel s = {2}".to_string()).unwrap();
+  let f = ClosureForeignFunction::new("f", vec![ValueType::I32], ValueType::I32, id);
+  lib1.function_registry.register(f.clone()).unwrap();
+  lib2.function_registry.register(f).unwrap();
+
+  // The same function and the standard library are merged
+  lib1.merge(lib2).unwrap();
+
+  // A function with the same name and signature but another implementation is rejected
+  let mut lib4 = compile_string_to_ram("rel t = {3}".to_string()).unwrap();
+  let g = ClosureForeignFunction::new("f", vec![ValueType::I32], ValueType::I32, |_| Some(Value::I32(0)));
+  lib4.function_registry.register(g).unwrap();
+  match lib1.clone().merge(lib4) {
+    Err(MergeError::ConflictingFunction { name }) => assert_eq!(name, "f"),
+    r => panic!("Expected conflicting function, found {:?}", r),
+  }
+
+  // So is a function with the same implementation but another signature
+  let mut lib5 = compile_string_to_ram("rel t = {3}".to_string()).unwrap();
+  let h = ClosureForeignFunction::new("f", vec![ValueType::I64], ValueType::I64, id);
+  lib5.function_registry.register(h).unwrap();
+  match lib1.clone().merge(lib5) {
+    Err(MergeError::ConflictingFunction { name }) => assert_eq!(name, "f"),
+    r => panic!("Expected conflicting function, found {:?}", r),
+  }
+}
@@ -0,0 +1,37 @@
+use scallop_core::common::expr::Expr;
+use scallop_core::compiler::compile_string_to_ram;
+use scallop_core::compiler::ram::*;
+
+fn update_of<'a>(ram: &'a Program, target: &str) -> &'a Dataflow {
+  ram
+    .strata
+    .iter()
+    .flat_map(|s| s.updates.iter())
+    .find(|u| u.target == target)
+    .map(|u| &u.dataflow)
+    .unwrap()
+}
+
+#[test]
+fn filter_pushdown_1() {
+  let ram = compile_string_to_ram(
+    r#"
+    type a(i32, i32)
+    type b(i32, i32)
+    rel c(x, z) = a(x, y), b(y, z), z < 3, x + z > 4
+    "#
+    .to_string(),
+  )
+  .unwrap();
+
+  // `z < 3` only accesses the right operand of the join and is evaluated before joining; `x + z > 4` stays
+  let join =
+    Dataflow::relation("a#perm#(1,0)").join(Dataflow::relation("b").filter(Expr::access(1).lt(Expr::constant(3i32))));
+  let projected = join.project((Expr::access(1), Expr::access(2), Expr::access(1) + Expr::access(2)));
+  assert_eq!(
+    update_of(&ram, "c"),
+    &projected
+      .filter(Expr::access(2).gt(Expr::constant(4i32)))
+      .project((Expr::access(0), Expr::access(1)))
+  );
+}
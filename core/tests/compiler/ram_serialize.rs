@@ -0,0 +1,94 @@
+use scallop_core::common::foreign_function::ForeignFunctionRegistry;
+use scallop_core::common::foreign_predicate::ForeignPredicateRegistry;
+use scallop_core::compiler::compile_string_to_ram;
+use scallop_core::common::input_tag::DynamicInputTag;
+use scallop_core::common::tuple::Tuple;
+use scallop_core::common::value::Value;
+use scallop_core::compiler::ram::{Fact, LoadError, Program, SerializeError};
+use scallop_core::runtime::dynamic;
+use scallop_core::runtime::env;
+use scallop_core::runtime::provenance::*;
+use scallop_core::utils::RcFamily;
+
+#[test]
+fn serialize_round_trip_1() {
+  let program = compile_string_to_ram(
+    r#"
+    rel edge = {(0, 1), (1, 2), (2, 3)}
+    rel path(a, c) = edge(a, c) or path(a, b) and edge(b, c)
+    rel num_paths(n) = n := count(a, b: path(a, b))
+    rel label(a, $format("node {}", a)) = edge(a, _) and range_i32(0, 2, a)
+  "#
+    .to_string(),
+  )
+  .unwrap();
+  let loaded = Program::deserialize(&program.serialize().unwrap()).unwrap();
+  assert_eq!(loaded.strata, program.strata);
+  assert_eq!(loaded.relation_to_stratum, program.relation_to_stratum);
+
+  // Execute the loaded program
+  let mut ctx = unit::UnitProvenance::default();
+  let runtime = env::RuntimeEnvironment::default();
+  let mut exec_ctx = dynamic::DynamicExecutionContext::<_, RcFamily>::new();
+  exec_ctx.incremental_execute(loaded, &runtime, &mut ctx).unwrap();
+  for r in ["path", "num_paths", "label"] {
+    exec_ctx.recover(r, &ctx);
+  }
+  assert_eq!(exec_ctx.relation_ref("path").unwrap().len(), 6);
+  assert_eq!(exec_ctx.relation_ref("num_paths").unwrap().len(), 1);
+  assert_eq!(exec_ctx.relation_ref("label").unwrap().len(), 2);
+}
+
+#[test]
+fn deserialize_unregistered_foreign_function() {
+  let program = compile_string_to_ram("rel r($abs(-1))".to_string()).unwrap();
+  let result = Program::deserialize_with_registries(
+    &program.serialize().unwrap(),
+    &ForeignFunctionRegistry::new(),
+    &ForeignPredicateRegistry::std(),
+  );
+  assert!(matches!(result, Err(LoadError::UnregisteredFunction { .. })));
+  let result = Program::deserialize_with_registries(
+    &program.serialize().unwrap(),
+    &ForeignFunctionRegistry::std(),
+    &ForeignPredicateRegistry::new(),
+  );
+  assert!(matches!(result, Err(LoadError::UnregisteredPredicate { .. })));
+}
+
+#[test]
+fn deserialize_bad_bytes() {
+  assert!(matches!(
+    Program::deserialize(&[1, 2, 3]),
+    Err(LoadError::Decode { .. })
+  ));
+}
+
+#[test]
+fn serialize_static_string() {
+  let mut program = compile_string_to_ram("rel name = {\"alice\"}".to_string()).unwrap();
+  for stratum in &mut program.strata {
+    if let Some(relation) = stratum.relations.get_mut("name") {
+      relation.facts.push(Fact {
+        tag: DynamicInputTag::None,
+        tuple: Tuple::from_values(vec![Value::Str("bob")]),
+      });
+    }
+  }
+  assert!(matches!(program.serialize(), Err(SerializeError::Encode { .. })));
+}
+
+#[test]
+fn fact_nan_tag_order() {
+  let fact = |p: f64, i: i32| Fact {
+    tag: DynamicInputTag::Float(p),
+    tuple: Tuple::from_values(vec![Value::I32(i)]),
+  };
+
+  // Facts with `NaN` probabilities are equal exactly when their order says so
+  assert_eq!(fact(f64::NAN, 0), fact(f64::NAN, 0));
+  assert_eq!(fact(f64::NAN, 0).cmp(&fact(f64::NAN, 0)), std::cmp::Ordering::Equal);
+  assert_ne!(fact(f64::NAN, 0), fact(f64::NAN, 1));
+  assert_ne!(fact(f64::NAN, 0), fact(0.5, 0));
+  assert!(fact(0.5, 1) < fact(f64::NAN, 0));
+}
@@ -0,0 +1,30 @@
+use scallop_core::compiler::compile_string_to_ram;
+
+#[test]
+fn rename_pure_permutation_1() {
+  let ram = compile_string_to_ram(
+    r#"
+    rel edge = {(0, 1), (1, 2)}
+    rel path(a, c) = edge(a, b), edge(b, c)
+  "#
+    .to_string(),
+  )
+  .unwrap();
+  let printed = format!("{}", ram);
+  assert!(printed.contains("edge#perm#(1,0) <- Rename[1, 0]"));
+}
+
+#[test]
+fn rename_not_pure_permutation_1() {
+  let ram = compile_string_to_ram(
+    r#"
+    rel edge = {(0, 1, 2), (1, 2, 3)}
+    rel second(b) = edge(_, b, _)
+  "#
+    .to_string(),
+  )
+  .unwrap();
+  let printed = format!("{}", ram);
+  assert!(!printed.contains("Rename"));
+  assert!(printed.contains("Project"));
+}
@@ -0,0 +1,42 @@
+use scallop_core::compiler::compile_string_to_ram;
+
+#[test]
+fn strata_summary_1() {
+  let ram = compile_string_to_ram(
+    r#"
+    rel node = {0, 1, 2}
+    rel edge = {(0, 1), (1, 2)}
+    rel path(a, b) = edge(a, b) or path(a, c) and edge(c, b)
+    rel unreachable(a, b) = node(a), node(b), ~path(a, b)
+  "#
+    .to_string(),
+  )
+  .unwrap();
+  let summary = ram.strata_summary();
+
+  let stratum_of = |r: &str| summary.iter().find(|s| s.relations.iter().any(|n| n == r)).unwrap();
+  let path = stratum_of("path");
+  let unreachable = stratum_of("unreachable");
+  assert!(path.is_recursive);
+  assert!(!unreachable.is_recursive);
+  assert!(unreachable.dependencies.contains(&path.id));
+  assert!(unreachable.dependencies.contains(&stratum_of("node").id));
+  assert!(path.dependencies.iter().all(|d| *d < path.id));
+
+  assert_eq!(ram.stratum_of("path"), Some((path.id, true)));
+  assert_eq!(ram.stratum_of("unreachable"), Some((unreachable.id, false)));
+  assert_eq!(ram.stratum_of("unknown"), None);
+
+  let dot = ram.to_dot();
+  assert!(dot.starts_with("digraph program"));
+  assert!(dot.contains(&format!(
+    "subgraph cluster_{} {{ label=\"stratum {} (recursive)\";",
+    path.id, path.id
+  )));
+  assert!(dot.contains(&format!(
+    "subgraph cluster_{} {{ label=\"stratum {}\";",
+    unreachable.id, unreachable.id
+  )));
+  assert!(dot.contains("\"edge\" -> \"path\";"));
+  assert!(dot.contains("\"path\" -> \"unreachable\";"));
+}
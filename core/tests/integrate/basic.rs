@@ -1,3 +1,6 @@
+use scallop_core::common::tuple::Tuple;
+use scallop_core::common::value::Value;
+use scallop_core::common::value_type::ValueType;
 use scallop_core::runtime::provenance::*;
 use scallop_core::utils::*;
 use scallop_core::testing::*;
@@ -163,6 +166,47 @@ fn count_test_2() {
   );
 }
 
+#[test]
+fn count_result_type_1() {
+  expect_interpret_result(
+    r#"
+      rel R = {(0, "a"), (1, "b"), (1, "a"), (0, "c"), (0, "d")}
+      rel O(o, c) = (c: f32) := count(s: R(o, s))
+    "#,
+    ("O", vec![(0i32, 3.0f32), (1, 2.0)]),
+  );
+}
+
+#[test]
+fn count_result_type_2() {
+  expect_interpret_multi_result(
+    r#"
+      rel R = {(0, "a"), (1, "b"), (1, "a"), (0, "c"), (0, "d")}
+      rel O(o, c) = c: f32 := count(s: R(o, s))
+      rel P(o, c) = R(o, _), c: f64 := count(s: R(o, s))
+      rel Q(n) = n := count(o: O(o, c) and c > 2.5)
+    "#,
+    vec![
+      ("O", vec![(0i32, 3.0f32), (1, 2.0)].into()),
+      ("P", vec![(0i32, 3.0f64), (1, 2.0)].into()),
+      ("Q", vec![(1usize,)].into()),
+    ],
+  );
+}
+
+#[test]
+fn sum_result_type_1() {
+  expect_interpret_result(
+    r#"
+      type R(i32)
+      rel R = {1, 2, 3}
+      rel total(t) = (t: f64) := sum(x: R(x))
+      rel is_fraction(t / 4.0) = total(t)
+    "#,
+    ("is_fraction", vec![(1.5f64,)]),
+  );
+}
+
 #[test]
 fn topk_test_1() {
   expect_interpret_result(
@@ -660,6 +704,22 @@ fn test_count_with_where_clause() {
   )
 }
 
+#[test]
+fn test_count_with_where_clause_empty_body() {
+  expect_interpret_multi_result(
+    r#"
+      rel groups = {0, 1, 2}
+      type items(i32, i32)
+      rel num_items(g, c) = c := count(x: items(g, x) where g: groups(g))
+      rel sum_items(g, s) = s := sum(x: items(g, x) where g: groups(g))
+    "#,
+    vec![
+      ("num_items", vec![(0, 0usize), (1, 0), (2, 0)].into()),
+      ("sum_items", vec![(0, 0), (1, 0), (2, 0)].into()),
+    ],
+  )
+}
+
 #[test]
 fn test_exists_path_1() {
   expect_interpret_multi_result(
@@ -1081,6 +1141,18 @@ fn const_variable_10() {
   )
 }
 
+#[test]
+fn const_variable_11() {
+  expect_interpret_result(
+    r#"
+    @allow_negative
+    type Status = UNKNOWN = -1 | OK = 2 | FAILED = 0 | PENDING
+    rel r(UNKNOWN, OK, FAILED, PENDING)
+    "#,
+    ("r", vec![(-1isize, 2isize, 0isize, 1isize)]),
+  )
+}
+
 #[test]
 fn sat_1() {
   let ctx = proofs::ProofsProvenance::<RcFamily>::default();
@@ -1196,3 +1268,642 @@ fn disjunctive_1() {
     proofs::Proofs::eq,
   )
 }
+
+#[test]
+fn implies_in_rule_body_1() {
+  expect_interpret_result(
+    r#"
+      rel dom = {1, 2, 3, 4}
+      rel p = {1, 2, 3}
+      rel q = {1, 3}
+      rel ok(x) = dom(x), (p(x) => q(x))
+      query ok
+    "#,
+    ("ok", vec![(1,), (3,), (4,)]),
+  );
+}
+
+#[test]
+fn window_sum_1() {
+  expect_interpret_result(
+    r#"
+      rel event = {
+        ("a", 1, 10),
+        ("a", 2, 20),
+        ("a", 3, 30),
+        ("a", 4, 40),
+        ("b", 1, 5),
+        ("b", 5, 7),
+      }
+      rel rolling_sum(k, t, s) = s := window<3, sum>[t](x: event(k, t, x))
+    "#,
+    (
+      "rolling_sum",
+      vec![
+        ("a".to_string(), 1, 10),
+        ("a".to_string(), 2, 30),
+        ("a".to_string(), 3, 60),
+        ("a".to_string(), 4, 90),
+        ("b".to_string(), 1, 5),
+        ("b".to_string(), 5, 12),
+      ],
+    ),
+  )
+}
+
+#[test]
+fn window_max_1() {
+  expect_interpret_result(
+    r#"
+      rel price = {(1, 5), (2, 3), (3, 1), (4, 4)}
+      rel rolling_max(t, m) = m := window<2, max>[t](p: price(t, p))
+    "#,
+    ("rolling_max", vec![(1, 5), (2, 5), (3, 3), (4, 4)]),
+  )
+}
+
+#[test]
+fn shared_group_by_aggregates_1() {
+  expect_interpret_result(
+    r#"
+      rel purchase = {("alice", 3), ("alice", 5), ("bob", 4)}
+      rel stats(c, n, s, m) = n := count(p: purchase(c, p)), s := sum(p: purchase(c, p)), m := max(p: purchase(c, p))
+    "#,
+    (
+      "stats",
+      vec![
+        ("alice".to_string(), 2usize, 8i32, 5i32),
+        ("bob".to_string(), 1usize, 4i32, 4i32),
+      ],
+    ),
+  )
+}
+
+#[test]
+fn shared_group_by_aggregates_2() {
+  expect_interpret_result(
+    r#"
+      rel customer = {"alice", "bob", "carol"}
+      rel purchase = {("alice", 3), ("alice", 5), ("bob", 4)}
+      rel stats(c, n, s) = n := count(p: purchase(c, p) where c: customer(c)), s := sum(p: purchase(c, p) where c: customer(c))
+    "#,
+    (
+      "stats",
+      vec![
+        ("alice".to_string(), 2usize, 8i32),
+        ("bob".to_string(), 1usize, 4i32),
+        ("carol".to_string(), 0usize, 0i32),
+      ],
+    ),
+  )
+}
+
+#[test]
+fn numeric_literal_inference_1() {
+  expect_interpret_result(
+    r#"
+      type data(i64)
+      rel data = {3, -2}
+      rel r(x) = data(x), x > 0
+    "#,
+    ("r", vec![(3i64,)]),
+  );
+  expect_interpret_result(
+    r#"
+      type data(f32)
+      rel data = {1.5, -2.0}
+      rel r(x) = data(x), x > 0
+    "#,
+    ("r", vec![(1.5f32,)]),
+  );
+}
+
+#[test]
+fn negative_literal_inference_1() {
+  expect_interpret_result(
+    r#"
+      type data(f32)
+      rel data = {1.5, -2, -0.5}
+      rel r(x) = data(x), x > -1
+    "#,
+    ("r", vec![(-0.5f32,), (1.5,)]),
+  );
+}
+
+#[test]
+fn variance_stddev_1() {
+  expect_interpret_result(
+    r#"
+      rel data = {0, 2, 4, 6, 8}
+      rel var(v) = v := variance(x: data(x))
+    "#,
+    ("var", vec![(8.0f64,)]),
+  );
+  expect_interpret_result(
+    r#"
+      rel data = {1, 5}
+      rel std(s) = s := stddev(x: data(x))
+    "#,
+    ("std", vec![(2.0f64,)]),
+  );
+}
+
+#[test]
+fn variance_single_element_1() {
+  expect_interpret_result(
+    r#"
+      rel data = {("a", 3.5), ("b", 1.0), ("b", 2.0)}
+      rel var(k, v) = v := variance(x: data(k, x))
+    "#,
+    ("var", vec![("a".to_string(), 0.0f64), ("b".to_string(), 0.25f64)]),
+  )
+}
+
+#[test]
+fn median_1() {
+  expect_interpret_result(
+    r#"
+      rel scores = {("a", 3), ("a", 9), ("a", 1), ("b", 4), ("b", 1), ("b", 10), ("b", 2)}
+      rel m(k, v) = v := median(x: scores(k, x))
+    "#,
+    ("m", vec![("a".to_string(), 3.0f64), ("b".to_string(), 3.0f64)]),
+  )
+}
+
+#[test]
+fn arg_top_k_1() {
+  expect_interpret_multi_result(
+    r#"
+      rel score = {("alice", 90), ("bob", 80), ("carol", 90), ("dave", 70)}
+      rel top_3(n, s) = s := top<3>[n](s: score(n, s))
+      rel top_1(n) = _ := top<1>[n](s: score(n, s))
+    "#,
+    vec![
+      (
+        "top_3",
+        vec![
+          ("alice".to_string(), 90),
+          ("bob".to_string(), 80),
+          ("carol".to_string(), 90),
+        ]
+        .into(),
+      ),
+      ("top_1", vec![("alice".to_string(),)].into()),
+    ],
+  )
+}
+
+#[test]
+fn weighted_sum_1() {
+  expect_interpret_multi_result(
+    r#"
+      rel data = {("a", 2, 3), ("a", 4, 5), ("b", 7, 1)}
+      rel ws(s) = s := weighted_sum(v, w: data(_, v, w))
+      rel ws_by_key(k, s) = s := weighted_sum(v, w: data(k, v, w))
+      rel ws_none(s) = s := weighted_sum(v, w: data("c", v, w))
+    "#,
+    vec![
+      ("ws", vec![(33i32,)].into()),
+      (
+        "ws_by_key",
+        vec![("a".to_string(), 26i32), ("b".to_string(), 7i32)].into(),
+      ),
+      ("ws_none", TestCollection::empty()),
+    ],
+  )
+}
+
+#[test]
+fn sample_1() {
+  expect_interpret_multi_result(
+    r#"
+      rel data = {("a", 1, 0), ("a", 2, 5), ("b", 3, 2), ("b", 4, 0)}
+      rel s_by_key(k, x) = x := sample(w, x: data(k, x, w))
+      rel data_b(k, x, w) = data(k, x, w) and k == "b"
+      rel s_pair(k, x) = (k, x) := sample(w, k, x: data_b(k, x, w))
+      rel s_single(x) = x := sample(x: data("a", x, 0))
+    "#,
+    vec![
+      (
+        "s_by_key",
+        vec![("a".to_string(), 2i32), ("b".to_string(), 3i32)].into(),
+      ),
+      ("s_pair", vec![("b".to_string(), 3i32)].into()),
+      ("s_single", vec![(1i32,)].into()),
+    ],
+  )
+}
+
+#[test]
+fn sample_prob_1() {
+  expect_interpret_result_with_tag(
+    r#"
+      rel data = {0.3::(1, 2), 0.6::(2, 0)}
+      rel s(x) = x := sample(w, x: data(x, w))
+    "#,
+    add_mult_prob::AddMultProbProvenance::default(),
+    ("s", vec![(0.3, (1i32,))]),
+    |t1, t2| (t1 - t2).abs() < 0.001,
+  )
+}
+
+#[test]
+fn normalize_1() {
+  expect_interpret_multi_result(
+    r#"
+      rel counts = {("a", "x", 1), ("a", "y", 3), ("b", "z", 2)}
+      rel p(g, x, w) = w := normalize(c, x: counts(g, x, c))
+      rel total(w) = w := normalize(c: counts(_, _, c))
+      rel empty(w) = w := normalize(c: counts("c", _, c))
+    "#,
+    vec![
+      (
+        "p",
+        vec![
+          ("a".to_string(), "x".to_string(), 0.25f64),
+          ("a".to_string(), "y".to_string(), 0.75),
+          ("b".to_string(), "z".to_string(), 1.0),
+        ]
+        .into(),
+      ),
+      ("total", vec![(1.0f64 / 6.0,), (0.5,), (1.0 / 3.0,)].into()),
+      ("empty", TestCollection::empty()),
+    ],
+  )
+}
+
+#[test]
+fn symbol_1() {
+  let symbols = |ss: &[(&str, &str)]| {
+    ss.iter()
+      .map(|(a, b)| Tuple::from_values(vec![Value::symbol(a), Value::symbol(b)]))
+      .collect::<Vec<_>>()
+  };
+  expect_interpret_multi_result(
+    r#"
+      type knows(a: Symbol, b: Symbol)
+      rel knows = {("alice", "bob"), ("bob", "carol"), ("carol", "alice")}
+      rel two_hops(a, c) = knows(a, b), knows(b, c), a != c
+      rel from_alice(n) = two_hops("alice", c), n == c as String
+    "#,
+    vec![
+      (
+        "two_hops",
+        symbols(&[("alice", "carol"), ("bob", "alice"), ("carol", "bob")]).into(),
+      ),
+      ("from_alice", vec![("carol".to_string(),)].into()),
+    ],
+  )
+}
+
+#[test]
+fn geometric_harmonic_mean_1() {
+  expect_interpret_multi_result(
+    r#"
+      rel measurements = {1, 2, 4}
+      rel g(v) = v := geometric_mean(x: measurements(x))
+      rel h(v) = v := harmonic_mean(x: measurements(x))
+    "#,
+    vec![("g", vec![(2.0f64,)].into()), ("h", vec![(12.0f64 / 7.0,)].into())],
+  )
+}
+
+#[test]
+fn geometric_harmonic_mean_2() {
+  expect_interpret_multi_result(
+    r#"
+      rel measurements = {("a", 0.0), ("a", 2.0), ("b", -1.0), ("b", 1.0), ("c", -2.0), ("c", 4.0)}
+      rel g(k, v) = v := geometric_mean(x: measurements(k, x))
+      rel h(k, v) = v := harmonic_mean(x: measurements(k, x))
+    "#,
+    vec![
+      ("g", TestCollection::empty()),
+      ("h", vec![("c".to_string(), -8.0f64)].into()),
+    ],
+  )
+}
+
+#[test]
+fn rank_1() {
+  expect_interpret_result(
+    r#"
+      rel data = {30, 10, 20}
+      rel ranked(i, x) = (i, x) := rank(x: data(x))
+    "#,
+    ("ranked", vec![(0usize, 10), (1, 20), (2, 30)]),
+  )
+}
+
+#[test]
+fn rank_2() {
+  expect_interpret_result(
+    r#"
+      rel score = {("carol", 90), ("alice", 90), ("bob", 70)}
+      rel ranked(i, n, s) = (i, s) := rank[n](s: score(n, s))
+    "#,
+    (
+      "ranked",
+      vec![
+        (0usize, "bob".to_string(), 70),
+        (1, "alice".to_string(), 90),
+        (2, "carol".to_string(), 90),
+      ],
+    ),
+  )
+}
+
+#[test]
+fn any_with_witness_1() {
+  expect_interpret_multi_result(
+    r#"
+      rel edge = {(2, 3), (0, 1), (1, 2)}
+      rel some_edge(b, x, y) = (b, x, y) := any(x, y: edge(x, y))
+      rel to_five(b) = b := any(edge(_, 5))
+    "#,
+    vec![
+      ("some_edge", vec![(true, 0, 1)].into()),
+      ("to_five", vec![(false,)].into()),
+    ],
+  )
+}
+
+#[test]
+fn any_with_witness_2() {
+  expect_interpret_result(
+    r#"
+      rel friend = {("alice", "carol"), ("alice", "bob"), ("bob", "alice")}
+      rel person = {"alice", "bob", "carol"}
+      rel some_friend(p, b, f) = person(p), (b, f) := any(f: friend(p, f))
+    "#,
+    (
+      "some_friend",
+      vec![
+        ("alice".to_string(), true, "bob".to_string()),
+        ("bob".to_string(), true, "alice".to_string()),
+      ],
+    ),
+  )
+}
+
+#[test]
+fn any_with_witness_empty_body_1() {
+  expect_interpret_multi_result(
+    r#"
+      rel num = {1, 2, 3}
+      rel big(b, x) = (b, x) := any(x: num(x) and x > 10)
+      rel has_big(b) = b := any(num(x) and x > 10)
+      rel has_small(b) = b := any(num(x) and x < 3)
+    "#,
+    vec![
+      ("big", TestCollection::empty()),
+      ("has_big", vec![(false,)].into()),
+      ("has_small", vec![(true,)].into()),
+    ],
+  )
+}
+
+#[test]
+fn any_with_witness_empty_body_2() {
+  expect_interpret_multi_result(
+    r#"
+      rel friend = {("alice", "bob")}
+      rel person = {"alice", "carol"}
+      rel some_friend(p, b, f) = (b, f) := any(f: friend(p, f) where p: person(p))
+      rel has_friend(p, b) = b := any(friend(p, _) where p: person(p))
+    "#,
+    vec![
+      (
+        "some_friend",
+        vec![("alice".to_string(), true, "bob".to_string())].into(),
+      ),
+      (
+        "has_friend",
+        vec![("alice".to_string(), true), ("carol".to_string(), false)].into(),
+      ),
+    ],
+  )
+}
+
+#[test]
+fn argmax_argmin_1() {
+  expect_interpret_multi_result(
+    r#"
+      rel score = {(0, 1, 90), (1, 1, 95), (2, 1, 95), (0, 2, 80), (1, 2, 70)}
+      rel best(c, s, p) = (s, p) := argmax[s](p: score(p, c, s))
+      rel worst(c, s, p) = (s, p) := argmin[s](p: score(p, c, s))
+      rel top(s) = s := argmax[s](score(_, _, s))
+    "#,
+    vec![
+      ("best", vec![(1, 95, 1), (1, 95, 2), (2, 80, 0)].into()),
+      ("worst", vec![(1, 90, 0), (2, 70, 1)].into()),
+      ("top", vec![(95,)].into()),
+    ],
+  )
+}
+
+#[test]
+fn argmax_argmin_2() {
+  expect_interpret_multi_result(
+    r#"
+      rel score = {(0, 1, 90), (1, 1, 95), (2, 1, 95), (0, 2, 80), (1, 2, 70)}
+      rel best(m, who, course) = (m, who, course) := argmax[s](p, c: score(p, c, s))
+      rel worst(m, who, course) = (m, who, course) := argmin[s](p, c: score(p, c, s))
+    "#,
+    vec![
+      ("best", vec![(95, 1, 1), (95, 2, 1)].into()),
+      ("worst", vec![(70, 1, 2)].into()),
+    ],
+  )
+}
+
+#[test]
+fn collect_1() {
+  expect_interpret_multi_result(
+    r#"
+      rel groups = {0, 1, 2}
+      rel items = {(0, 3), (0, 1), (0, 2), (1, 5)}
+      rel seq(g, lst) = lst := collect(x: items(g, x) where g: groups(g))
+      rel all(lst) = lst := list(x: items(_, x))
+    "#,
+    vec![
+      (
+        "seq",
+        vec![
+          (0, Value::List(vec![1.into(), 2.into(), 3.into()])),
+          (1, Value::List(vec![5.into()])),
+          (2, Value::List(vec![])),
+        ]
+        .into(),
+      ),
+      (
+        "all",
+        vec![(Value::List(vec![1.into(), 2.into(), 3.into(), 5.into()]),)].into(),
+      ),
+    ],
+  )
+}
+
+#[test]
+fn inferred_types_1() {
+  let prov = unit::UnitProvenance::default();
+  let mut ctx = scallop_core::integrate::IntegrateContext::<_, RcFamily>::new(prov);
+  ctx.add_relation("edge(usize, usize)").unwrap();
+  let rule_id = ctx
+    .add_rule(r#"path(a, d) = edge(a, b) and edge(b, c) and d == c + 1"#)
+    .unwrap();
+  ctx.add_rule(r#"label(a, "node") = edge(a, _)"#).unwrap();
+
+  // Relation types
+  let rel_tys = ctx.inferred_relation_types();
+  assert_eq!(rel_tys["path"], vec![ValueType::USize, ValueType::USize]);
+  assert_eq!(rel_tys["label"], vec![ValueType::USize, ValueType::String]);
+  assert!(rel_tys.keys().all(|r| !r.contains('#')));
+
+  // Variable types
+  let var_tys = ctx.variable_types_in_rule(rule_id);
+  for var in ["a", "b", "c", "d"] {
+    assert_eq!(var_tys[var], ValueType::USize);
+  }
+}
+
+#[test]
+fn constant_fold_constraints_1() {
+  expect_interpret_multi_result(
+    r#"
+      rel num = {1, 2, 3}
+      rel kept(x) = num(x), !false, "a" == "a", 1 + 2 == 3
+      rel dropped(x) = num(x), !true
+      rel shifted(x + (2 - 1)) = num(x)
+    "#,
+    vec![
+      ("kept", vec![(1i32,), (2,), (3,)].into()),
+      ("dropped", TestCollection::empty()),
+      ("shifted", vec![(2i32,), (3,), (4,)].into()),
+    ],
+  );
+}
+
+#[test]
+fn constant_fold_float_1() {
+  expect_interpret_result(
+    r#"
+      type half(f32)
+      rel half(1 / 2)
+    "#,
+    ("half", vec![(0.5f32,)]),
+  );
+}
+
+#[test]
+fn filter_pushdown_join_1() {
+  expect_interpret_result(
+    r#"
+      rel a = {(1, 2), (6, 2), (7, 3)}
+      rel b = {(2, 1), (2, 5), (3, 0)}
+      rel c(x, z) = a(x, y), b(y, z), x > 5, z < 3, x + z > 6
+    "#,
+    ("c", vec![(6i32, 1i32), (7, 0)]),
+  );
+}
+
+#[test]
+fn stable_float_aggregation_1() {
+  use scallop_core::integrate::IntegrateContext;
+
+  let sum_of = |stable: bool| {
+    let mut ctx = IntegrateContext::<_, RcFamily>::new(unit::UnitProvenance::default());
+    ctx.set_stable_float_aggregation(stable);
+    ctx
+      .add_program(
+        r#"
+          type data(f64)
+          rel total(t) = t := sum(x: data(x))
+        "#,
+      )
+      .unwrap();
+    ctx
+      .edb()
+      .add_facts("data", vec![(-1e100f64,), (1.0,), (2.0,), (1e100,)])
+      .unwrap();
+    ctx.run().unwrap();
+    let total = ctx.computed_relation_ref("total").unwrap();
+    let result = total.iter().map(|(_, t)| t[0].as_f64()).collect::<Vec<_>>();
+    result
+  };
+
+  // The small numbers are lost when naively added to the large ones
+  assert_eq!(sum_of(false), vec![0.0]);
+  assert_eq!(sum_of(true), vec![3.0]);
+}
+
+#[test]
+fn k_attr_1() {
+  use scallop_core::integrate::IntegrateContext;
+
+  let mut ctx =
+    IntegrateContext::<_, RcFamily>::new(top_bottom_k_clauses::TopBottomKClausesProvenance::<RcFamily>::new(3));
+  assert_eq!(ctx.requested_k(), None);
+  ctx
+    .add_program(
+      r#"
+        @k(1)
+        rel path(a, b) = edge(a, b) or (path(a, c) and edge(c, b))
+        rel edge = {0.9::(0, 1), 0.8::(1, 2), 0.5::(0, 2)}
+      "#,
+    )
+    .unwrap();
+  assert_eq!(ctx.requested_k(), Some(1));
+
+  // Configure the provenance with the requested k
+  let mut prov = top_bottom_k_clauses::TopBottomKClausesProvenance::<RcFamily>::new(3);
+  prov.set_k(ctx.requested_k().unwrap());
+  let mut ctx = ctx.clone_with_new_provenance(prov);
+  ctx.run().unwrap();
+
+  // Only the most likely proof of `path(0, 2)` is kept
+  let path = ctx.computed_relation_ref("path").unwrap();
+  let (prob, _) = path.iter().find(|(_, t)| t == &Tuple::from((0i32, 2i32))).unwrap();
+  assert!((prob - 0.72).abs() < 0.0001);
+}
+
+#[test]
+fn if_then_else_numeric_branches_1() {
+  expect_interpret_result(
+    r#"
+      type a(i32, i64)
+      rel a = {(1, 10), (-2, 20)}
+      rel b(if x > 0 then x else y) = a(x, y)
+    "#,
+    ("b", vec![(1i64,), (20i64,)]),
+  );
+}
+
+#[test]
+fn if_then_else_numeric_branches_2() {
+  expect_interpret_multi_result(
+    r#"
+      type a(u32, i32, f32)
+      rel a = {(3, -4, 0.5)}
+      rel b(if true then x else y) = a(x, y, _)
+      rel c(x > 5 ? x : y) = a(x, y, _)
+      rel d(y < 0 ? y : z) = a(_, y, z)
+    "#,
+    vec![
+      ("b", vec![(3i64,)].into()),
+      ("c", vec![(-4i64,)].into()),
+      ("d", vec![(-4.0f64,)].into()),
+    ],
+  );
+}
+
+#[test]
+fn top_k_without_argument_1() {
+  // Without argument, the tuples are ranked by their tags; as the tags all tie here, the smallest tuples are kept
+  expect_interpret_result(
+    r#"
+      rel score = {90, 70, 80, 60}
+      rel best(s) = s := top<2>(s: score(s))
+    "#,
+    ("best", vec![(60,), (70,)]),
+  )
+}
@@ -1,4 +1,7 @@
+use scallop_core::integrate::*;
+use scallop_core::runtime::provenance::*;
 use scallop_core::testing::*;
+use scallop_core::utils::*;
 
 #[test]
 fn dt_fib_1() {
@@ -51,3 +54,72 @@ fn dt_edge_path_2() {
     ("path(0, _)", vec![(0, 1), (0, 2), (0, 3)]),
   );
 }
+
+fn run_with_query_demand(program: &str, demand_from_queries: bool) -> IntegrateContext<unit::UnitProvenance, RcFamily> {
+  let mut options = IntegrateOptions::default();
+  options.compiler_options.demand_from_queries = demand_from_queries;
+  let mut ctx = IntegrateContext::new_with_options(unit::UnitProvenance::default(), options);
+  ctx.add_program(program).expect("Compile error");
+  ctx.run().expect("Runtime error");
+  ctx
+}
+
+fn computed_tuples(ctx: &mut IntegrateContext<unit::UnitProvenance, RcFamily>, relation: &str) -> Vec<String> {
+  let collection = ctx.computed_relation_ref(relation).expect("Cannot get relation");
+  collection.iter().map(|(_, t)| t.to_string()).collect()
+}
+
+#[test]
+fn dt_query_demand_edge_path_1() {
+  let program = r#"
+    rel edge = {(0, 1), (1, 2), (2, 3), (3, 4), (5, 6)}
+    rel path(a, c) = edge(a, c) or (path(a, b) and edge(b, c))
+    query path(1, x)
+  "#;
+
+  // Only the paths starting from 1 are derived
+  let mut ctx = run_with_query_demand(program, true);
+  assert_eq!(
+    computed_tuples(&mut ctx, "path(1, x)"),
+    vec!["(1, 2)", "(1, 3)", "(1, 4)"]
+  );
+  assert_eq!(computed_tuples(&mut ctx, "path").len(), 3);
+
+  // Without the option, every path is derived
+  let mut ctx = run_with_query_demand(program, false);
+  assert_eq!(
+    computed_tuples(&mut ctx, "path(1, x)"),
+    vec!["(1, 2)", "(1, 3)", "(1, 4)"]
+  );
+  assert_eq!(computed_tuples(&mut ctx, "path").len(), 11);
+}
+
+#[test]
+fn dt_query_demand_conflicting_patterns_1() {
+  let mut ctx = run_with_query_demand(
+    r#"
+    rel edge = {(0, 1), (1, 2), (2, 3)}
+    rel path(a, c) = edge(a, c) or (path(a, b) and edge(b, c))
+    query path(1, x)
+    query path(x, 1)
+    "#,
+    true,
+  );
+  assert_eq!(computed_tuples(&mut ctx, "path(1, x)"), vec!["(1, 2)", "(1, 3)"]);
+  assert_eq!(computed_tuples(&mut ctx, "path(x, 1)"), vec!["(0, 1)"]);
+  assert_eq!(computed_tuples(&mut ctx, "path").len(), 6);
+}
+
+#[test]
+fn dt_query_demand_without_sips_1() {
+  // The bound first argument of `sym(b, a)` cannot be passed from the demanded `sym(a, b)`
+  let mut ctx = run_with_query_demand(
+    r#"
+    rel edge = {(0, 1), (1, 2)}
+    rel sym(a, b) = edge(a, b) or sym(b, a)
+    query sym(1, x)
+    "#,
+    true,
+  );
+  assert_eq!(computed_tuples(&mut ctx, "sym(1, x)"), vec!["(1, 0)", "(1, 2)"]);
+}
@@ -227,3 +227,122 @@ fn edb_edge_path_persistent_relation() {
     vec![(0usize, 1usize), (0, 2), (0, 3), (1, 2), (1, 3), (2, 3)],
   );
 }
+
+#[test]
+fn edb_add_facts_checked_1() {
+  use scallop_core::common::tuple::Tuple;
+  use scallop_core::common::tuple_type::TupleType;
+  use scallop_core::common::value_type::FromType;
+  use scallop_core::runtime::database::DatabaseError;
+  use scallop_core::runtime::error::RuntimeError;
+
+  let prov = unit::UnitProvenance::default();
+  let mut ctx = IntegrateContext::<_, RcFamily>::new(prov);
+  ctx
+    .add_program(
+      r#"
+      type edge(usize, usize)
+      rel path(a, b) = edge(a, b) or (path(a, c) and edge(c, b))
+    "#,
+    )
+    .expect("Compilation error");
+
+  // The type of the tuples has to be the one of the relation
+  let edge_type = <TupleType as FromType<(usize, usize)>>::from_type();
+  let wrong_type = <TupleType as FromType<(i32, i32)>>::from_type();
+  match ctx.add_facts_checked("edge", vec![], &wrong_type) {
+    Err(IntegrateError::Runtime(RuntimeError::Database(DatabaseError::RelationTypeMismatch { .. }))) => {}
+    r => panic!("Expected relation type mismatch, found {:?}", r),
+  }
+
+  // The first tuple not matching the type is reported, and no fact is added
+  let facts: Vec<Tuple> = vec![(0usize, 1usize).into(), (1usize, 2usize).into(), (2i32, 3i32).into()];
+  match ctx.edb().add_facts_checked("edge", facts, &edge_type) {
+    Err(DatabaseError::TypeMismatch { index, .. }) => assert_eq!(index, 2),
+    r => panic!("Expected type mismatch, found {:?}", r),
+  }
+
+  // Well typed tuples are added
+  let facts: Vec<Tuple> = vec![(0usize, 1usize).into(), (1usize, 2usize).into()];
+  ctx
+    .add_facts_checked("edge", facts, &edge_type)
+    .expect("Cannot add facts");
+  ctx.run().expect("Runtime error");
+  expect_output_collection(
+    "path",
+    ctx.computed_relation_ref("path").unwrap(),
+    vec![(0usize, 1usize), (0, 2), (1, 2)],
+  );
+}
+
+#[test]
+fn edb_query_1() {
+  use scallop_core::common::value::Value;
+
+  let prov = unit::UnitProvenance::default();
+  let mut ctx = IntegrateContext::<_, RcFamily>::new_incremental(prov);
+  ctx
+    .add_program(
+      r#"
+      type edge(usize, usize)
+      rel path(a, b) = edge(a, b) or (path(a, c) and edge(c, b))
+    "#,
+    )
+    .expect("Compilation error");
+  ctx
+    .edb()
+    .add_facts("edge", vec![(0usize, 1usize), (1, 2), (2, 3)])
+    .expect("Cannot add facts");
+  ctx.run().expect("Runtime error");
+
+  // Bound first column, second column, or both
+  let from_one = ctx.query("path", vec![Some(Value::USize(1)), None]).unwrap().unwrap();
+  expect_output_collection("path", &from_one, vec![(1usize, 2usize), (1, 3)]);
+  let to_two = ctx.query("path", vec![None, Some(Value::USize(2))]).unwrap().unwrap();
+  expect_output_collection("path", &to_two, vec![(0usize, 2usize), (1, 2)]);
+  let exact = ctx
+    .query("path", vec![Some(Value::USize(0)), Some(Value::USize(3))])
+    .unwrap()
+    .unwrap();
+  expect_output_collection("path", &exact, vec![(0usize, 3usize)]);
+
+  // All wildcards return the whole relation, including for the relations only in the EDB
+  let all = ctx.query("path", vec![None, None]).unwrap().unwrap();
+  assert_eq!(all.len(), 6);
+  let edges = ctx.query("edge", vec![Some(Value::USize(2)), None]).unwrap().unwrap();
+  expect_output_collection("edge", &edges, vec![(2usize, 3usize)]);
+}
+
+#[test]
+fn edb_query_pattern_mismatch_1() {
+  use scallop_core::common::value::Value;
+  use scallop_core::runtime::database::DatabaseError;
+  use scallop_core::runtime::error::RuntimeError;
+
+  let prov = unit::UnitProvenance::default();
+  let mut ctx = IntegrateContext::<_, RcFamily>::new(prov);
+  ctx
+    .add_program(
+      r#"
+      rel edge = {(0, 1), (1, 2)}
+    "#,
+    )
+    .expect("Compilation error");
+  ctx.run().expect("Runtime error");
+  let edges = ctx.query("edge", vec![Some(Value::I32(1)), None]).unwrap().unwrap();
+  expect_output_collection("edge", &edges, vec![(1i32, 2i32)]);
+
+  // Wrong arity and wrong value type
+  for pattern in [vec![None], vec![Some(Value::String("0".to_string())), None]] {
+    match ctx.query("edge", pattern) {
+      Err(IntegrateError::Runtime(RuntimeError::Database(DatabaseError::QueryPatternMismatch { .. }))) => {}
+      r => panic!("Expected query pattern mismatch, found {:?}", r.map(|_| ())),
+    }
+  }
+
+  // Unknown relation
+  match ctx.query("path", vec![None, None]) {
+    Err(IntegrateError::Runtime(RuntimeError::Database(DatabaseError::UnknownRelation { .. }))) => {}
+    r => panic!("Expected unknown relation, found {:?}", r.map(|_| ())),
+  }
+}
@@ -4,7 +4,9 @@ use scallop_core::utils::*;
 use scallop_core::common::value::*;
 use scallop_core::common::foreign_function::*;
 use scallop_core::common::type_family::*;
+use scallop_core::common::value_type::*;
 use scallop_core::runtime::provenance;
+use scallop_core::compiler;
 use scallop_core::integrate;
 use scallop_core::testing::*;
 
@@ -100,6 +102,137 @@ fn test_fib_ff() {
   );
 }
 
+/// Sum of one or more integers
+#[derive(Clone)]
+pub struct SumAll;
+
+impl ForeignFunction for SumAll {
+  fn name(&self) -> String {
+    "sum_all".to_string()
+  }
+
+  fn num_static_arguments(&self) -> usize {
+    1
+  }
+
+  fn static_argument_type(&self, i: usize) -> ForeignFunctionParameterType {
+    assert_eq!(i, 0);
+    ForeignFunctionParameterType::BaseType(ValueType::I32)
+  }
+
+  fn has_variable_arguments(&self) -> bool {
+    true
+  }
+
+  fn variable_argument_type(&self) -> ForeignFunctionParameterType {
+    ForeignFunctionParameterType::BaseType(ValueType::I32)
+  }
+
+  fn return_type(&self) -> ForeignFunctionParameterType {
+    ForeignFunctionParameterType::BaseType(ValueType::I32)
+  }
+
+  fn execute(&self, args: Vec<Value>) -> Option<Value> {
+    args
+      .iter()
+      .try_fold(0, |acc, arg| match arg {
+        Value::I32(i) => Some(acc + i),
+        _ => None,
+      })
+      .map(Value::I32)
+  }
+}
+
+#[test]
+fn ff_variable_arguments_1() {
+  let prov_ctx = provenance::unit::UnitProvenance::default();
+  let mut ctx = integrate::IntegrateContext::<_, RcFamily>::new(prov_ctx);
+  ctx.register_foreign_function(SumAll).unwrap();
+  ctx.add_relation("R(i32)").unwrap();
+  ctx
+    .add_rule(r#"S($sum_all(x), $sum_all(x, x), $sum_all(x, 1, 2, 3)) = R(x)"#)
+    .unwrap();
+  ctx.edb().add_facts("R", vec![(10i32,)]).unwrap();
+  ctx.run().unwrap();
+  expect_output_collection(
+    "S",
+    ctx.computed_relation_ref("S").unwrap(),
+    vec![(10i32, 20i32, 16i32)],
+  );
+
+  // Fewer arguments than the static ones is an arity mismatch
+  let err = ctx.add_rule(r#"T($sum_all()) = R(_)"#).unwrap_err();
+  assert!(format!("{}", err).contains("wrong number of arguments for function `sum_all`. Expected at least 1, found 0"));
+}
+
+#[test]
+fn ff_closure_1() {
+  let prov_ctx = provenance::unit::UnitProvenance::default();
+  let mut ctx = integrate::IntegrateContext::<_, RcFamily>::new(prov_ctx);
+  ctx
+    .register_foreign_function_closure("my_sigmoid", vec![ValueType::F32], ValueType::F32, |args| {
+      match &args[0] {
+        Value::F32(x) => Some(Value::F32(1.0 / (1.0 + (-x).exp()))),
+        _ => None,
+      }
+    })
+    .unwrap();
+  ctx
+    .register_foreign_function_closure(
+      "safe_div",
+      vec![ValueType::I32, ValueType::I32],
+      ValueType::I32,
+      |args| match (&args[0], &args[1]) {
+        (Value::I32(a), Value::I32(b)) => a.checked_div(*b).map(Value::I32),
+        _ => None,
+      },
+    )
+    .unwrap();
+  ctx.add_relation("R(f32, i32)").unwrap();
+  ctx.add_rule(r#"S($my_sigmoid(x)) = R(x, _)"#).unwrap();
+  ctx.add_rule(r#"T($safe_div(6, y)) = R(_, y)"#).unwrap();
+  ctx.edb().add_facts("R", vec![(0.0f32, 2i32), (0.0f32, 0i32)]).unwrap();
+  ctx.run().unwrap();
+  expect_output_collection("S", ctx.computed_relation_ref("S").unwrap(), vec![(0.5f32,)]);
+  expect_output_collection("T", ctx.computed_relation_ref("T").unwrap(), vec![(3i32,)]);
+
+  // The closure function is type checked like any other foreign function
+  let err = ctx.add_rule(r#"U($my_sigmoid("a")) = R(_, _)"#).unwrap_err();
+  assert!(format!("{}", err).contains("cannot unify types `string` and `f32`"));
+}
+
+#[test]
+fn ff_unused_foreign_function_1() {
+  let mut ctx = compiler::front::FrontContext::new();
+  ctx.register_foreign_function(Fib).unwrap();
+  ctx
+    .compile_source(compiler::front::StringSource::new("rel R = {1, 2}".to_string()))
+    .unwrap();
+  assert_eq!(ctx.unused_foreign_functions(), vec!["fib".to_string()]);
+  let warnings = ctx.unused_foreign_function_warnings();
+  assert!(warnings.has_warning() && !warnings.has_error());
+  assert!(format!("{}", warnings).contains("foreign function `$fib` is registered but never called"));
+
+  // Once the function is called by a later rule, there is no more warning
+  ctx
+    .compile_source(compiler::front::StringSource::new(
+      "rel S(x, $fib(x)) = R(x)".to_string(),
+    ))
+    .unwrap();
+  assert!(ctx.unused_foreign_functions().is_empty());
+  assert!(!ctx.unused_foreign_function_warnings().has_warning());
+}
+
+#[test]
+fn ff_unused_foreign_function_2() {
+  let prov_ctx = provenance::unit::UnitProvenance::default();
+  let mut ctx = integrate::IntegrateContext::<_, RcFamily>::new(prov_ctx);
+  ctx.register_foreign_function(Fib).unwrap();
+  ctx.add_program("rel R = {1, 2}").unwrap();
+  ctx.run().unwrap();
+  assert!(format!("{}", ctx.unused_foreign_function_warnings()).contains("`$fib` is registered but never called"));
+}
+
 #[test]
 fn ff_string_length_1() {
   expect_interpret_result(
@@ -142,6 +275,37 @@ fn ff_string_concat_2() {
   );
 }
 
+#[test]
+fn ff_string_format_1() {
+  expect_interpret_result(
+    r#"
+      rel edge = {("a", "b"), ("a", "c"), ("b", "c")}
+      rel num_edges(n, c) = c := count(m: edge(n, m))
+      rel desc(s) = num_edges(n, c), s == $format("{} has {} edges", n, c)
+    "#,
+    (
+      "desc",
+      vec![("a has 2 edges".to_string(),), ("b has 1 edges".to_string(),)],
+    ),
+  );
+}
+
+#[test]
+fn ff_string_format_2() {
+  expect_interpret_result(
+    r#"
+      rel result($format("{{{}}} = {}", 'x', 1.5))
+    "#,
+    ("result", vec![("{x} = 1.5".to_string(),)]),
+  );
+}
+
+#[test]
+fn ff_string_format_mismatch_1() {
+  expect_interpret_empty_result(r#"rel result($format("{} and {}", 1))"#, "result");
+  expect_interpret_empty_result(r#"rel result($format("{}", 1, true))"#, "result");
+}
+
 #[test]
 fn ff_hash_1() {
   expect_interpret_result(
@@ -184,6 +348,44 @@ fn ff_abs_2() {
   );
 }
 
+#[test]
+fn ff_sqrt_1() {
+  expect_interpret_result(
+    r#"
+      rel data = {4.0, 9.0, -1.0}
+      rel r($sqrt(x)) = data(x)
+    "#,
+    ("r", vec![(2.0f32,), (3.0,)]),
+  );
+}
+
+#[test]
+fn ff_ln_exp_1() {
+  expect_interpret_multi_result(
+    r#"
+      type data(f64)
+      rel data = {0.0, 1.0, -1.0}
+      rel r(x, $ln($exp(x))) = data(x)
+      rel s(x, $ln(x)) = data(x)
+    "#,
+    vec![
+      ("r", vec![(-1.0f64, -1.0f64), (0.0, 0.0), (1.0, 1.0)].into()),
+      ("s", vec![(1.0f64, 0.0f64)].into()),
+    ],
+  );
+}
+
+#[test]
+fn ff_sqrt_integer_argument_1() {
+  expect_front_compile_failure(
+    r#"
+      type data(i32)
+      rel r($sqrt(x)) = data(x)
+    "#,
+    |e| e.contains("cannot unify types `float` and `i32`"),
+  )
+}
+
 #[test]
 fn ff_substring_1() {
   expect_interpret_result(
@@ -205,3 +407,306 @@ fn ff_substring_2() {
     ("result", vec![("world!".to_string(),)]),
   );
 }
+
+#[test]
+fn ff_substring_out_of_range_1() {
+  expect_interpret_multi_result(
+    r#"
+      rel my_rel = {"abc"}
+      rel start_eq_len($substring(x, 3)) = my_rel(x)
+      rel start_gt_len($substring(x, 6)) = my_rel(x)
+      rel start_gt_end($substring(x, 2, 1)) = my_rel(x)
+      rel end_gt_len($substring(x, 1, 10)) = my_rel(x)
+    "#,
+    vec![
+      ("start_eq_len", vec![("".to_string(),)].into()),
+      ("start_gt_len", TestCollection::empty()),
+      ("start_gt_end", TestCollection::empty()),
+      ("end_gt_len", vec![("bc".to_string(),)].into()),
+    ],
+  );
+}
+
+#[test]
+fn ff_coalesce_1() {
+  expect_interpret_result(
+    r#"
+      rel person = {(1, "", "Alice"), (2, "Bob", "Robert"), (3, "", "")}
+      rel display_name(id, $coalesce(nick, name, "unknown")) = person(id, nick, name)
+    "#,
+    (
+      "display_name",
+      vec![
+        (1, "Alice".to_string()),
+        (2, "Bob".to_string()),
+        (3, "unknown".to_string()),
+      ],
+    ),
+  );
+}
+
+#[test]
+fn ff_coalesce_2() {
+  expect_interpret_result(
+    r#"
+      rel score = {(1, 0, 3), (2, 5, 7), (3, 0, 0)}
+      rel result(id, $coalesce(a, b)) = score(id, a, b)
+    "#,
+    ("result", vec![(1, 3), (2, 5)]),
+  );
+}
+
+fn run_random_program(seed: u64) -> Vec<(i32, f64, i32)> {
+  let prov_ctx = provenance::unit::UnitProvenance::default();
+  let mut ctx = integrate::IntegrateContext::<_, RcFamily>::new(prov_ctx);
+  ctx.set_random_seed(seed);
+  ctx
+    .add_program(
+      r#"
+      rel dom = {1, 2, 3, 4, 5}
+      rel sample(i, r, d) = dom(i), r == $random(), d == $random_int(1, 7)
+    "#,
+    )
+    .unwrap();
+  ctx.run().unwrap();
+  ctx
+    .computed_relation_ref("sample")
+    .unwrap()
+    .iter()
+    .map(|(_, t)| (t[0].as_i32(), t[1].as_f64(), t[2].as_i32()))
+    .collect()
+}
+
+#[test]
+fn ff_random_1() {
+  let result = run_random_program(42);
+  assert_eq!(result.len(), 5);
+  for (_, r, d) in &result {
+    assert!(0.0 <= *r && *r < 1.0);
+    assert!(1 <= *d && *d < 7);
+  }
+}
+
+#[test]
+fn ff_random_same_seed_1() {
+  assert_eq!(run_random_program(42), run_random_program(42));
+}
+
+#[derive(Clone)]
+pub struct WrongReturnType;
+
+impl ForeignFunction for WrongReturnType {
+  fn name(&self) -> String {
+    "wrong_return_type".to_string()
+  }
+
+  fn num_static_arguments(&self) -> usize {
+    1
+  }
+
+  fn static_argument_type(&self, i: usize) -> ForeignFunctionParameterType {
+    assert_eq!(i, 0);
+    ForeignFunctionParameterType::BaseType(ValueType::I32)
+  }
+
+  fn return_type(&self) -> ForeignFunctionParameterType {
+    ForeignFunctionParameterType::BaseType(ValueType::I32)
+  }
+
+  fn execute(&self, args: Vec<Value>) -> Option<Value> {
+    Some(Value::String(format!("{:?}", args[0])))
+  }
+}
+
+#[test]
+fn ff_register_wrong_return_type() {
+  let mut registry = ForeignFunctionRegistry::new();
+  match registry.register_with_self_check(WrongReturnType) {
+    Err(ForeignFunctionError::ReturnTypeMismatch { expected, found }) => {
+      assert_eq!(expected, ValueType::I32);
+      assert_eq!(found, ValueType::String);
+    }
+    r => panic!("Expected return type mismatch, found {:?}", r),
+  }
+  assert!(!registry.contains("wrong_return_type"));
+}
+
+#[test]
+fn ff_register_without_self_check() {
+  // Without the self-check, the function is not executed at registration
+  let mut registry = ForeignFunctionRegistry::new();
+  registry.register(WrongReturnType).unwrap();
+  assert!(registry.contains("wrong_return_type"));
+}
+
+#[test]
+fn ff_register_wrong_return_type_in_context() {
+  use scallop_core::runtime::error::RuntimeError;
+  let prov_ctx = provenance::unit::UnitProvenance::default();
+  let mut ctx = integrate::IntegrateContext::<_, RcFamily>::new(prov_ctx);
+  match ctx.register_foreign_function_with_self_check(WrongReturnType) {
+    Err(integrate::IntegrateError::Runtime(RuntimeError::ForeignFunction(
+      ForeignFunctionError::ReturnTypeMismatch { .. },
+    ))) => {}
+    r => panic!("Expected return type mismatch, found {:?}", r),
+  }
+  ctx.register_foreign_function_with_self_check(SymbolLength).unwrap();
+}
+
+#[derive(Clone)]
+pub struct InconsistentArity;
+
+impl ForeignFunction for InconsistentArity {
+  fn name(&self) -> String {
+    "inconsistent_arity".to_string()
+  }
+
+  fn num_static_arguments(&self) -> usize {
+    2
+  }
+
+  fn static_argument_type(&self, i: usize) -> ForeignFunctionParameterType {
+    assert_eq!(i, 0);
+    ForeignFunctionParameterType::BaseType(ValueType::I32)
+  }
+
+  fn return_type(&self) -> ForeignFunctionParameterType {
+    ForeignFunctionParameterType::BaseType(ValueType::I32)
+  }
+
+  fn execute(&self, args: Vec<Value>) -> Option<Value> {
+    Some(args[0].clone())
+  }
+}
+
+#[test]
+fn ff_register_inconsistent_arity() {
+  let mut registry = ForeignFunctionRegistry::new();
+  match registry.register(InconsistentArity) {
+    Err(ForeignFunctionError::InconsistentArity { index, .. }) => assert_eq!(index, 1),
+    r => panic!("Expected inconsistent arity, found {:?}", r),
+  }
+}
+
+#[derive(Clone)]
+pub struct SymbolLength;
+
+impl ForeignFunction for SymbolLength {
+  fn name(&self) -> String {
+    "symbol_length".to_string()
+  }
+
+  fn num_static_arguments(&self) -> usize {
+    1
+  }
+
+  fn static_argument_type(&self, i: usize) -> ForeignFunctionParameterType {
+    assert_eq!(i, 0);
+    ForeignFunctionParameterType::BaseType(ValueType::Symbol)
+  }
+
+  fn return_type(&self) -> ForeignFunctionParameterType {
+    ForeignFunctionParameterType::BaseType(ValueType::USize)
+  }
+
+  fn execute(&self, args: Vec<Value>) -> Option<Value> {
+    Some(Value::USize(args[0].as_str().len()))
+  }
+}
+
+#[test]
+fn ff_register_symbol_argument_1() {
+  let mut registry = ForeignFunctionRegistry::new();
+  registry.register_with_self_check(SymbolLength).unwrap();
+  assert!(registry.contains("symbol_length"));
+}
+
+#[test]
+fn ff_register_symbol_argument_2() {
+  let prov_ctx = provenance::unit::UnitProvenance::default();
+  let mut ctx = integrate::IntegrateContext::<_, RcFamily>::new(prov_ctx);
+  ctx.register_foreign_function(SymbolLength).unwrap();
+  ctx
+    .add_program(
+      r#"
+    type name(Symbol)
+    rel name = {"alice", "bob"}
+    rel len(n, $symbol_length(n)) = name(n)
+    "#,
+    )
+    .unwrap();
+  ctx.run().unwrap();
+  expect_output_collection(
+    "len",
+    ctx.computed_relation_ref("len").unwrap(),
+    vec![(Value::symbol("alice"), 5usize), (Value::symbol("bob"), 3usize)],
+  );
+}
+
+/// Doubles an integer; records the size of every batch it is executed on
+#[derive(Clone)]
+pub struct BatchedDouble {
+  batch_sizes: std::sync::Arc<std::sync::Mutex<Vec<usize>>>,
+}
+
+impl ForeignFunction for BatchedDouble {
+  fn name(&self) -> String {
+    "batched_double".to_string()
+  }
+
+  fn num_static_arguments(&self) -> usize {
+    1
+  }
+
+  fn static_argument_type(&self, i: usize) -> ForeignFunctionParameterType {
+    assert_eq!(i, 0);
+    ForeignFunctionParameterType::BaseType(ValueType::I32)
+  }
+
+  fn return_type(&self) -> ForeignFunctionParameterType {
+    ForeignFunctionParameterType::BaseType(ValueType::I32)
+  }
+
+  fn execute(&self, args: Vec<Value>) -> Option<Value> {
+    self.execute_batch(&[args]).pop().unwrap()
+  }
+
+  fn execute_batch(&self, rows: &[Vec<Value>]) -> Vec<Option<Value>> {
+    self.batch_sizes.lock().unwrap().push(rows.len());
+    rows
+      .iter()
+      .map(|args| match &args[0] {
+        Value::I32(i) if *i >= 0 => Some(Value::I32(i * 2)),
+        _ => None,
+      })
+      .collect()
+  }
+}
+
+#[test]
+fn ff_execute_batch_1() {
+  let batch_sizes = std::sync::Arc::new(std::sync::Mutex::new(vec![]));
+  let prov_ctx = provenance::unit::UnitProvenance::default();
+  let mut ctx = integrate::IntegrateContext::<_, RcFamily>::new(prov_ctx);
+  ctx
+    .register_foreign_function(BatchedDouble {
+      batch_sizes: batch_sizes.clone(),
+    })
+    .unwrap();
+  ctx.add_relation("R(i32)").unwrap();
+  ctx.add_rule(r#"S(x, $batched_double(x) + 1) = R(x)"#).unwrap();
+
+  ctx
+    .edb()
+    .add_facts("R", vec![(-1i32,), (1,), (2,), (3,), (4,)])
+    .unwrap();
+  ctx.run().unwrap();
+  expect_output_collection(
+    "S",
+    ctx.computed_relation_ref("S").unwrap(),
+    vec![(1i32, 3i32), (2, 5), (3, 7), (4, 9)],
+  );
+
+  // The function is executed once on the whole relation
+  assert_eq!(*batch_sizes.lock().unwrap(), vec![5]);
+}
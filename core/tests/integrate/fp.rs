@@ -1,3 +1,9 @@
+use std::sync::*;
+
+use scallop_core::common::foreign_predicate::*;
+use scallop_core::common::input_tag::DynamicInputTag;
+use scallop_core::common::value::Value;
+use scallop_core::common::value_type::ValueType;
 use scallop_core::testing::*;
 
 #[test]
@@ -21,6 +27,36 @@ fn range_constraint_1() {
   );
 }
 
+#[test]
+fn query_range_1() {
+  expect_interpret_result(
+    r#"
+      query range_usize(0, 3, y)
+    "#,
+    ("range_usize(0, 3, y)", vec![(0usize, 3usize, 0usize), (0, 3, 1), (0, 3, 2)]),
+  );
+}
+
+#[test]
+fn query_range_without_arguments_1() {
+  expect_front_compile_failure(
+    r#"
+      query range_usize
+    "#,
+    |e| e.contains("the foreign predicate `range_usize` cannot be queried"),
+  );
+}
+
+#[test]
+fn query_non_queryable_1() {
+  expect_front_compile_failure(
+    r#"
+      query soft_eq_f32(3.0, 3.0)
+    "#,
+    |e| e.contains("the foreign predicate `soft_eq_f32` cannot be queried"),
+  );
+}
+
 #[test]
 fn range_join_1() {
   expect_interpret_result(
@@ -76,6 +112,34 @@ fn string_chars_1() {
   );
 }
 
+#[test]
+fn string_split_1() {
+  expect_interpret_result(
+    r#"
+      rel sentence = {"hello big world"}
+      rel token(i, t) = sentence(s), string_split(s, " ", i, t)
+    "#,
+    (
+      "token",
+      vec![(0usize, "hello".to_string()), (1, "big".to_string()), (2, "world".to_string())],
+    ),
+  );
+}
+
+#[test]
+fn string_split_2() {
+  expect_interpret_result(
+    r#"
+      rel sentence = {"a,,b", ""}
+      rel token(i, t) = sentence(s), string_split(s, ",", i, t)
+    "#,
+    (
+      "token",
+      vec![(0usize, "a".to_string()), (1, "".to_string()), (2, "b".to_string())],
+    ),
+  );
+}
+
 #[test]
 fn floating_point_eq_1() {
   expect_interpret_multi_result(
@@ -89,3 +153,377 @@ fn floating_point_eq_1() {
     ],
   )
 }
+
+#[cfg(feature = "sqlite")]
+#[test]
+fn sql_query_join_1() {
+  use scallop_core::common::foreign_predicates::SqlQuery;
+  use scallop_core::common::value_type::ValueType;
+  use scallop_core::integrate;
+  use scallop_core::runtime::provenance;
+  use scallop_core::utils::RcFamily;
+
+  let conn = rusqlite::Connection::open_in_memory().unwrap();
+  conn
+    .execute_batch(
+      r#"
+        CREATE TABLE employee (dept INTEGER, name TEXT);
+        INSERT INTO employee VALUES (1, 'alice'), (1, 'bob'), (2, 'carol'), (3, NULL);
+      "#,
+    )
+    .unwrap();
+  let sql_employees = SqlQuery::new(
+    "sql_employees",
+    conn,
+    "SELECT name FROM employee WHERE dept = ?",
+    vec![ValueType::I32, ValueType::String],
+    1,
+  )
+  .unwrap();
+
+  let prov_ctx = provenance::unit::UnitProvenance::default();
+  let mut ctx = integrate::IntegrateContext::<_, RcFamily>::new(prov_ctx);
+  ctx.register_foreign_predicate(sql_employees).unwrap();
+  ctx
+    .add_program(
+      r#"
+        rel dept = {1, 3}
+        rel emp(id, name) = dept(id), sql_employees(id, name)
+      "#,
+    )
+    .unwrap();
+  ctx.run().unwrap();
+
+  expect_output_collection(
+    "emp",
+    ctx.computed_relation_ref("emp").unwrap(),
+    vec![(1i32, "alice".to_string()), (1i32, "bob".to_string())],
+  );
+}
+
+#[cfg(feature = "sqlite")]
+#[test]
+fn sql_query_invalid_1() {
+  use scallop_core::common::foreign_predicates::SqlQuery;
+  use scallop_core::common::value_type::ValueType;
+
+  let create = || {
+    let conn = rusqlite::Connection::open_in_memory().unwrap();
+    conn
+      .execute_batch("CREATE TABLE employee (dept INTEGER, name TEXT);")
+      .unwrap();
+    conn
+  };
+  let types = vec![ValueType::I32, ValueType::String];
+
+  // Syntax error, wrong number of parameters, and wrong number of columns
+  assert!(SqlQuery::new("q", create(), "SELEC name FROM employee", types.clone(), 1).is_err());
+  assert!(SqlQuery::new("q", create(), "SELECT name FROM employee", types.clone(), 1).is_err());
+  assert!(SqlQuery::new(
+    "q",
+    create(),
+    "SELECT dept, name FROM employee WHERE dept = ?",
+    types.clone(),
+    1
+  )
+  .is_err());
+  assert!(SqlQuery::new("q", create(), "SELECT name FROM employee WHERE dept = ?", types, 1).is_ok());
+
+  // Tuples cannot be bound to the parameters
+  let types = vec![ValueType::Tuple(vec![ValueType::I32]), ValueType::String];
+  assert!(SqlQuery::new("q", create(), "SELECT name FROM employee WHERE dept = ?", types, 1).is_err());
+}
+
+#[cfg(feature = "sqlite")]
+#[test]
+fn sql_query_out_of_range_1() {
+  use scallop_core::common::foreign_predicates::SqlQuery;
+  use scallop_core::common::value_type::ValueType;
+  use scallop_core::integrate;
+  use scallop_core::runtime::provenance;
+  use scallop_core::utils::RcFamily;
+
+  let conn = rusqlite::Connection::open_in_memory().unwrap();
+  conn
+    .execute_batch(
+      r#"
+        CREATE TABLE employee (dept INTEGER, name TEXT);
+        INSERT INTO employee VALUES (-1, 'alice'), (1, 'bob');
+      "#,
+    )
+    .unwrap();
+  let sql_employees = SqlQuery::new(
+    "sql_employees",
+    conn,
+    "SELECT name FROM employee WHERE dept = ?",
+    vec![ValueType::U64, ValueType::String],
+    1,
+  )
+  .unwrap();
+
+  // `u64::MAX` does not fit in SQLite and must not be truncated into -1
+  let prov_ctx = provenance::unit::UnitProvenance::default();
+  let mut ctx = integrate::IntegrateContext::<_, RcFamily>::new(prov_ctx);
+  ctx.register_foreign_predicate(sql_employees).unwrap();
+  ctx.add_relation("dept(u64)").unwrap();
+  ctx
+    .add_facts("dept", vec![(None, (1u64,).into()), (None, (u64::MAX,).into())], false)
+    .unwrap();
+  ctx
+    .add_rule("emp(id, name) = dept(id), sql_employees(id, name)")
+    .unwrap();
+  ctx.run().unwrap();
+
+  expect_output_collection(
+    "emp",
+    ctx.computed_relation_ref("emp").unwrap(),
+    vec![(1u64, "bob".to_string())],
+  );
+}
+
+#[cfg(feature = "sqlite")]
+#[test]
+fn sql_query_symbol_1() {
+  use scallop_core::common::foreign_predicates::SqlQuery;
+  use scallop_core::common::value_type::ValueType;
+  use scallop_core::integrate;
+  use scallop_core::runtime::provenance;
+  use scallop_core::utils::RcFamily;
+
+  let conn = rusqlite::Connection::open_in_memory().unwrap();
+  conn
+    .execute_batch(
+      r#"
+        CREATE TABLE employee (name TEXT, dept INTEGER);
+        INSERT INTO employee VALUES ('alice', 1), ('bob', 2);
+      "#,
+    )
+    .unwrap();
+  let sql_dept = SqlQuery::new(
+    "sql_dept",
+    conn,
+    "SELECT dept FROM employee WHERE name = ?",
+    vec![ValueType::Symbol, ValueType::I32],
+    1,
+  )
+  .unwrap();
+
+  // Symbols are bound to the query as their text
+  let prov_ctx = provenance::unit::UnitProvenance::default();
+  let mut ctx = integrate::IntegrateContext::<_, RcFamily>::new(prov_ctx);
+  ctx.register_foreign_predicate(sql_dept).unwrap();
+  ctx
+    .add_program(
+      r#"
+        type name(Symbol)
+        rel name = {"bob"}
+        rel dept(d) = name(n), sql_dept(n, d)
+      "#,
+    )
+    .unwrap();
+  ctx.run().unwrap();
+
+  expect_output_collection("dept", ctx.computed_relation_ref("dept").unwrap(), vec![(2i32,)]);
+}
+
+/// A foreign predicate producing the divisors of a number, evaluated a batch at a time
+#[derive(Clone)]
+struct Divisors {
+  batch_sizes: Arc<Mutex<Vec<usize>>>,
+}
+
+impl ForeignPredicate for Divisors {
+  fn name(&self) -> String {
+    "divisors".to_string()
+  }
+
+  fn arity(&self) -> usize {
+    2
+  }
+
+  fn argument_type(&self, _: usize) -> ValueType {
+    ValueType::I32
+  }
+
+  fn num_bounded(&self) -> usize {
+    1
+  }
+
+  fn evaluate(&self, _: &[Value]) -> Vec<(DynamicInputTag, Vec<Value>)> {
+    panic!("Divisors should only be evaluated in batches")
+  }
+
+  fn evaluate_batch(&self, inputs: &[Vec<Value>]) -> Vec<Vec<(DynamicInputTag, Vec<Value>)>> {
+    self.batch_sizes.lock().unwrap().push(inputs.len());
+    inputs
+      .iter()
+      .map(|bounded| match &bounded[0] {
+        Value::I32(n) => (1..=*n)
+          .filter(|d| n % d == 0)
+          .map(|d| (DynamicInputTag::None, vec![Value::I32(d)]))
+          .collect(),
+        _ => panic!("Bounded argument is not i32"),
+      })
+      .collect()
+  }
+}
+
+#[test]
+fn evaluate_batch_join_1() {
+  use scallop_core::integrate;
+  use scallop_core::runtime::provenance;
+  use scallop_core::utils::RcFamily;
+
+  let batch_sizes = Arc::new(Mutex::new(vec![]));
+  let prov_ctx = provenance::unit::UnitProvenance::default();
+  let mut ctx = integrate::IntegrateContext::<_, RcFamily>::new(prov_ctx);
+  ctx
+    .register_foreign_predicate(Divisors {
+      batch_sizes: batch_sizes.clone(),
+    })
+    .unwrap();
+  ctx
+    .add_program(
+      r#"
+        rel number = {4, 5, 6}
+        rel divisor(n, d) = number(n), divisors(n, d)
+      "#,
+    )
+    .unwrap();
+  ctx.run().unwrap();
+
+  expect_output_collection(
+    "divisor",
+    ctx.computed_relation_ref("divisor").unwrap(),
+    vec![
+      (4i32, 1i32),
+      (4, 2),
+      (4, 4),
+      (5, 1),
+      (5, 5),
+      (6, 1),
+      (6, 2),
+      (6, 3),
+      (6, 6),
+    ],
+  );
+
+  // All the numbers are evaluated within a single batch
+  assert!(batch_sizes.lock().unwrap().contains(&3));
+}
+
+/// A foreign predicate producing its groundings out of order
+#[derive(Clone)]
+struct UnorderedDigits;
+
+impl ForeignPredicate for UnorderedDigits {
+  fn name(&self) -> String {
+    "unordered_digits".to_string()
+  }
+
+  fn arity(&self) -> usize {
+    1
+  }
+
+  fn argument_type(&self, _: usize) -> ValueType {
+    ValueType::I32
+  }
+
+  fn num_bounded(&self) -> usize {
+    0
+  }
+
+  fn evaluate(&self, _: &[Value]) -> Vec<(DynamicInputTag, Vec<Value>)> {
+    vec![7, 3, 9, 1, 5]
+      .into_iter()
+      .map(|d| (DynamicInputTag::None, vec![Value::I32(d)]))
+      .collect()
+  }
+}
+
+#[test]
+fn deterministic_groundings_1() {
+  use scallop_core::integrate;
+  use scallop_core::runtime::provenance;
+  use scallop_core::utils::RcFamily;
+
+  let prov_ctx = provenance::proofs::ProofsProvenance::<RcFamily>::default();
+  let mut ctx = integrate::IntegrateContext::<_, RcFamily>::new(prov_ctx);
+  ctx.register_foreign_predicate(UnorderedDigits).unwrap();
+  ctx.set_deterministic(true);
+  ctx
+    .add_program(
+      r#"
+        rel digit(d) = unordered_digits(d)
+      "#,
+    )
+    .unwrap();
+  ctx.run().unwrap();
+
+  // The groundings are tagged in the order of their tuples, so the fact ids follow the digits
+  let digit = ctx
+    .computed_relation_ref("digit")
+    .unwrap()
+    .iter()
+    .map(|(tag, t)| (tag.clone(), t[0].as_i32()))
+    .collect::<Vec<_>>();
+  let expected = vec![1, 3, 5, 7, 9]
+    .into_iter()
+    .enumerate()
+    .map(|(id, d)| (provenance::proofs::Proofs::singleton(id), d))
+    .collect::<Vec<_>>();
+  assert_eq!(digit, expected);
+}
+
+/// A foreign predicate declaring its bound argument after the free one
+#[derive(Clone)]
+struct MisdeclaredModes;
+
+impl ForeignPredicate for MisdeclaredModes {
+  fn name(&self) -> String {
+    "misdeclared_modes".to_string()
+  }
+
+  fn arity(&self) -> usize {
+    2
+  }
+
+  fn argument_type(&self, _: usize) -> ValueType {
+    ValueType::I32
+  }
+
+  fn num_bounded(&self) -> usize {
+    1
+  }
+
+  fn argument_mode(&self, i: usize) -> ArgMode {
+    if i == 1 {
+      ArgMode::Bound
+    } else {
+      ArgMode::Free
+    }
+  }
+
+  fn evaluate(&self, _: &[Value]) -> Vec<(DynamicInputTag, Vec<Value>)> {
+    vec![]
+  }
+}
+
+#[test]
+fn argument_modes_1() {
+  let registry = ForeignPredicateRegistry::std();
+  let string_split = registry.get("string_split").unwrap();
+  let modes = (0..string_split.arity())
+    .map(|i| string_split.argument_mode(i))
+    .collect::<Vec<_>>();
+  assert_eq!(modes, vec![ArgMode::Bound, ArgMode::Bound, ArgMode::Free, ArgMode::Free]);
+}
+
+#[test]
+fn bad_argument_modes_1() {
+  let mut registry = ForeignPredicateRegistry::new();
+  match registry.register(MisdeclaredModes) {
+    Err(ForeignPredicateError::InvalidArgumentModes { id, .. }) => assert_eq!(id, "misdeclared_modes"),
+    r => panic!("Expected the argument modes to be rejected, found {:?}", r),
+  }
+}
@@ -1,3 +1,4 @@
+use scallop_core::common::tuple::Tuple;
 use scallop_core::integrate;
 use scallop_core::runtime::provenance;
 use scallop_core::testing::*;
@@ -134,3 +135,446 @@ fn incr_fib_test_1() {
     vec![(0i32, 1i32), (1, 1), (2, 2), (3, 3), (4, 5), (5, 8)],
   );
 }
+
+#[test]
+fn incr_clone_for_query_1() {
+  let prov_ctx = provenance::unit::UnitProvenance::default();
+  let mut ctx = integrate::IntegrateContext::<_, RcFamily>::new_incremental(prov_ctx);
+  ctx.add_relation("edge(usize, usize)").unwrap();
+  ctx
+    .add_rule(r#"path(a, c) = edge(a, c) \/ path(a, b) /\ edge(b, c)"#)
+    .unwrap();
+  ctx
+    .add_facts(
+      "edge",
+      vec![(None, (0usize, 1usize).into()), (None, (1usize, 2usize).into())],
+      false,
+    )
+    .unwrap();
+  ctx.run().unwrap();
+
+  // The forks share the compiled program and the facts
+  let mut fork_1 = ctx.clone_for_query();
+  let mut fork_2 = ctx.clone_for_query();
+  assert!(std::ptr::eq(ctx.ram(), fork_1.ram()));
+  assert!(std::ptr::eq(ctx.ram(), fork_2.ram()));
+  let edge_facts = |c: &mut integrate::IntegrateContext<_, RcFamily>| {
+    std::rc::Rc::as_ptr(&c.edb().extensional_relations["edge"].internal)
+  };
+  assert_eq!(edge_facts(&mut ctx), edge_facts(&mut fork_1));
+  assert_eq!(edge_facts(&mut ctx), edge_facts(&mut fork_2));
+  let path_facts = |c: &integrate::IntegrateContext<_, RcFamily>| {
+    std::rc::Rc::as_ptr(&c.internal_context().exec_ctx.idb.intentional_relations["path"].internal_facts)
+  };
+  assert_eq!(path_facts(&ctx), path_facts(&fork_1));
+  assert_eq!(path_facts(&ctx), path_facts(&fork_2));
+
+  // Each fork runs its own query; the facts are only copied by the fork adding new facts
+  fork_1.add_rule(r#"from_zero(b) = path(0, b)"#).unwrap();
+  fork_1.run().unwrap();
+  fork_2
+    .add_facts("edge", vec![(None, (2usize, 3usize).into())], false)
+    .unwrap();
+  fork_2.run().unwrap();
+  assert_eq!(edge_facts(&mut ctx), edge_facts(&mut fork_1));
+  assert_ne!(edge_facts(&mut ctx), edge_facts(&mut fork_2));
+
+  expect_output_collection(
+    "from_zero",
+    fork_1.computed_relation_ref("from_zero").unwrap(),
+    vec![(1usize,), (2,)],
+  );
+  expect_output_collection(
+    "path",
+    fork_2.computed_relation_ref("path").unwrap(),
+    vec![(0usize, 1usize), (0, 2), (0, 3), (1, 2), (1, 3), (2, 3)],
+  );
+
+  // The original context is left untouched
+  assert!(!ctx.has_relation("from_zero"));
+  expect_output_collection(
+    "path",
+    ctx.computed_relation_ref("path").unwrap(),
+    vec![(0usize, 1usize), (0, 2), (1, 2)],
+  );
+}
+
+#[test]
+fn incr_run_incremental_1() {
+  let prov_ctx = provenance::unit::UnitProvenance::default();
+  let mut ctx = integrate::IntegrateContext::<_, RcFamily>::new_incremental(prov_ctx);
+  ctx.add_relation("edge(usize, usize)").unwrap();
+  ctx
+    .add_rule(r#"path(a, c) = edge(a, c) \/ path(a, b) /\ edge(b, c)"#)
+    .unwrap();
+  ctx
+    .add_facts(
+      "edge",
+      vec![(None, (0usize, 1usize).into()), (None, (1usize, 2usize).into())],
+      false,
+    )
+    .unwrap();
+
+  // The first run derives everything
+  let delta = ctx.run_incremental().unwrap();
+  assert_eq!(delta.keys().collect::<Vec<_>>(), vec!["path"]);
+  assert_eq!(delta["path"].len(), 3);
+
+  // Only the newly derived paths are returned after adding an edge
+  ctx
+    .add_facts("edge", vec![(None, (2usize, 3usize).into())], false)
+    .unwrap();
+  let delta = ctx.run_incremental().unwrap();
+  let expected: Vec<Tuple> = vec![
+    (0usize, 3usize).into(),
+    (1usize, 3usize).into(),
+    (2usize, 3usize).into(),
+  ];
+  assert_eq!(delta["path"].iter().cloned().collect::<Vec<_>>(), expected);
+
+  // The prior state is preserved
+  expect_output_collection(
+    "path",
+    ctx.computed_relation_ref("path").unwrap(),
+    vec![(0usize, 1usize), (0, 2), (0, 3), (1, 2), (1, 3), (2, 3)],
+  );
+
+  // Nothing is new when nothing changes
+  assert!(ctx.run_incremental().unwrap()["path"].is_empty());
+}
+
+#[test]
+fn incr_run_incremental_2() {
+  let prov_ctx = provenance::unit::UnitProvenance::default();
+  let mut ctx = integrate::IntegrateContext::<_, RcFamily>::new_incremental(prov_ctx);
+  ctx.add_relation("edge(usize, usize)").unwrap();
+  ctx.add_relation("node(usize)").unwrap();
+  ctx
+    .add_rule(r#"path(a, c) = edge(a, c) \/ path(a, b) /\ edge(b, c)"#)
+    .unwrap();
+  ctx.add_rule(r#"succ(a, a + 1) = node(a)"#).unwrap();
+  ctx
+    .add_facts("edge", vec![(None, (0usize, 1usize).into())], false)
+    .unwrap();
+  ctx.add_facts("node", vec![(None, (0usize,).into())], false).unwrap();
+  ctx.run_incremental().unwrap();
+  let path_facts = |c: &integrate::IntegrateContext<_, RcFamily>| {
+    std::rc::Rc::as_ptr(&c.internal_context().exec_ctx.idb.intentional_relations["path"].internal_facts)
+  };
+  let path_facts_before = path_facts(&ctx);
+
+  // The relations whose strata are not affected by the new facts have no new tuple, and are not recomputed
+  ctx.add_facts("node", vec![(None, (5usize,).into())], false).unwrap();
+  let delta = ctx.run_incremental().unwrap();
+  assert!(delta["path"].is_empty());
+  assert_eq!(path_facts(&ctx), path_facts_before);
+  let expected: Vec<Tuple> = vec![(5usize, 6usize).into()];
+  assert_eq!(delta["succ"].iter().cloned().collect::<Vec<_>>(), expected);
+}
+
+#[test]
+fn incr_run_incremental_3() {
+  let prov_ctx = provenance::min_max_prob::MinMaxProbProvenance::default();
+  let mut ctx = integrate::IntegrateContext::<_, RcFamily>::new_incremental(prov_ctx);
+  ctx
+    .add_program(
+      r#"
+      rel node = {0, 1, 2, 3}
+      type edge(i32, i32)
+      rel path(a, c) = edge(a, c) or path(a, b) and edge(b, c)
+      rel unreachable(a, b) = node(a), node(b), a != b, ~path(a, b)
+      "#,
+    )
+    .unwrap();
+  ctx
+    .add_facts("edge", vec![(Some(0.5), (0i32, 1i32).into())], false)
+    .unwrap();
+  ctx.run_incremental().unwrap();
+
+  // The paths are maintained from the new edges, with the probabilities of the paths updated
+  ctx
+    .add_facts(
+      "edge",
+      vec![(Some(0.8), (1i32, 2i32).into()), (Some(0.9), (0i32, 1i32).into())],
+      false,
+    )
+    .unwrap();
+  let delta = ctx.run_incremental().unwrap();
+  let expected: Vec<Tuple> = vec![(0i32, 2i32).into(), (1i32, 2i32).into()];
+  assert_eq!(delta["path"].iter().cloned().collect::<Vec<_>>(), expected);
+  let path = ctx.computed_relation_ref("path").unwrap();
+  let path = path.iter().map(|(p, t)| (t.clone(), *p)).collect::<Vec<_>>();
+  assert_eq!(
+    path,
+    vec![
+      ((0i32, 1i32).into(), 0.9),
+      ((0i32, 2i32).into(), 0.8),
+      ((1i32, 2i32).into(), 0.8)
+    ]
+  );
+
+  // The negation over the paths is recomputed, and only has the facts that are not derived before as new
+  assert!(delta["unreachable"].is_empty());
+  ctx
+    .add_facts("edge", vec![(Some(1.0), (3i32, 0i32).into())], false)
+    .unwrap();
+  let delta = ctx.run_incremental().unwrap();
+  let expected: Vec<Tuple> = vec![(3i32, 0i32).into(), (3i32, 1i32).into(), (3i32, 2i32).into()];
+  assert_eq!(delta["path"].iter().cloned().collect::<Vec<_>>(), expected);
+  assert!(delta["unreachable"].is_empty());
+  let unreachable = ctx.computed_relation_ref("unreachable").unwrap();
+  let unreachable = unreachable.iter().map(|(p, t)| (t.clone(), *p)).collect::<Vec<_>>();
+  assert!(unreachable.contains(&((3i32, 1i32).into(), 0.09999999999999998)));
+}
+
+#[test]
+fn incr_retract_facts_1() {
+  let prov_ctx = provenance::unit::UnitProvenance::default();
+  let mut ctx = integrate::IntegrateContext::<_, RcFamily>::new_incremental(prov_ctx);
+  ctx.add_relation("edge(usize, usize)").unwrap();
+  ctx
+    .add_rule(r#"path(a, c) = edge(a, c) \/ path(a, b) /\ edge(b, c)"#)
+    .unwrap();
+  ctx
+    .edb()
+    .add_facts("edge", vec![(0usize, 1usize), (1, 2), (2, 3)])
+    .unwrap();
+  ctx.run().unwrap();
+
+  // The paths going through the retracted edge are removed
+  ctx.edb().retract_facts("edge", vec![(1usize, 2usize)]).unwrap();
+  ctx.run().unwrap();
+  expect_output_collection(
+    "edge",
+    ctx.computed_relation_ref("edge").unwrap(),
+    vec![(0usize, 1usize), (2, 3)],
+  );
+  expect_output_collection(
+    "path",
+    ctx.computed_relation_ref("path").unwrap(),
+    vec![(0usize, 1usize), (2, 3)],
+  );
+
+  // The retracted edge can be added back
+  ctx.edb().add_facts("edge", vec![(1usize, 2usize)]).unwrap();
+  ctx.run().unwrap();
+  expect_output_collection(
+    "path",
+    ctx.computed_relation_ref("path").unwrap(),
+    vec![(0usize, 1usize), (0, 2), (0, 3), (1, 2), (1, 3), (2, 3)],
+  );
+}
+
+#[test]
+fn incr_retract_program_facts_1() {
+  let prov_ctx = provenance::unit::UnitProvenance::default();
+  let mut ctx = integrate::IntegrateContext::<_, RcFamily>::new(prov_ctx);
+  ctx
+    .add_program(
+      r#"
+      rel node = {0, 1, 2}
+      rel edge = {(0, 1), (1, 2)}
+      rel path(a, c) = edge(a, c) or path(a, b) and edge(b, c)
+      rel unreachable(a, b) = node(a), node(b), a != b, ~path(a, b)
+      "#,
+    )
+    .unwrap();
+  ctx.run().unwrap();
+  expect_output_collection(
+    "unreachable",
+    ctx.computed_relation_ref("unreachable").unwrap(),
+    vec![(1i32, 0i32), (2, 0), (2, 1)],
+  );
+
+  // Retracting a program fact also updates the strata depending on it non-monotonically
+  ctx.edb().retract_facts("edge", vec![(0i32, 1i32)]).unwrap();
+  ctx.run().unwrap();
+  expect_output_collection("path", ctx.computed_relation_ref("path").unwrap(), vec![(1i32, 2i32)]);
+  expect_output_collection(
+    "unreachable",
+    ctx.computed_relation_ref("unreachable").unwrap(),
+    vec![(0i32, 1i32), (0, 2), (1, 0), (2, 0), (2, 1)],
+  );
+
+  // The retracted program fact is not populated again by later runs
+  ctx.add_rule("from_one(b) = path(1, b)").unwrap();
+  ctx.run().unwrap();
+  expect_output_collection("edge", ctx.computed_relation_ref("edge").unwrap(), vec![(1i32, 2i32)]);
+  expect_output_collection(
+    "from_one",
+    ctx.computed_relation_ref("from_one").unwrap(),
+    vec![(2i32,)],
+  );
+}
+
+#[test]
+fn incr_retract_probabilistic_facts_1() {
+  let prov_ctx = provenance::add_mult_prob::AddMultProbProvenance::default();
+  let mut ctx = integrate::IntegrateContext::<_, RcFamily>::new_incremental(prov_ctx);
+  ctx.add_relation("edge(usize, usize)").unwrap();
+  ctx
+    .add_rule(r#"path(a, c) = edge(a, c) \/ path(a, b) /\ edge(b, c)"#)
+    .unwrap();
+  ctx
+    .add_facts(
+      "edge",
+      vec![
+        (Some(0.5), (0usize, 1usize).into()),
+        (Some(0.5), (1usize, 2usize).into()),
+        (Some(0.2), (0usize, 2usize).into()),
+      ],
+      false,
+    )
+    .unwrap();
+  ctx.run().unwrap();
+
+  // The probability of the remaining derivation is recomputed instead of being left stale
+  ctx.edb().retract_facts("edge", vec![(0usize, 1usize)]).unwrap();
+  ctx.run().unwrap();
+  let path = ctx.computed_relation_ref("path").unwrap();
+  let probs = path.iter().map(|(p, t)| (t.clone(), *p)).collect::<Vec<_>>();
+  let expected: Vec<(Tuple, f64)> = vec![((0usize, 2usize).into(), 0.2), ((1usize, 2usize).into(), 0.5)];
+  assert_eq!(probs, expected);
+}
+
+#[test]
+fn incr_retract_facts_2() {
+  let prov_ctx = provenance::unit::UnitProvenance::default();
+  let mut ctx = integrate::IntegrateContext::<_, RcFamily>::new_incremental(prov_ctx);
+  ctx.add_relation("edge(usize, usize)").unwrap();
+  ctx.add_relation("node(usize)").unwrap();
+  ctx
+    .add_rule(r#"path(a, c) = edge(a, c) \/ path(a, b) /\ edge(b, c)"#)
+    .unwrap();
+  ctx.add_rule(r#"succ(a, a + 1) = node(a)"#).unwrap();
+  ctx
+    .edb()
+    .add_facts("edge", vec![(0usize, 1usize), (1, 2), (0, 2), (2, 3), (3, 4)])
+    .unwrap();
+  ctx.edb().add_facts("node", vec![(0usize,)]).unwrap();
+  ctx.run().unwrap();
+  let succ_facts = |c: &integrate::IntegrateContext<_, RcFamily>| {
+    std::rc::Rc::as_ptr(&c.internal_context().exec_ctx.idb.intentional_relations["succ"].internal_facts)
+  };
+  let succ_facts_before = succ_facts(&ctx);
+
+  // The paths derived from the retracted edges are deleted, unless they can be derived otherwise
+  ctx.edb().retract_facts("edge", vec![(1usize, 2usize), (3, 4)]).unwrap();
+  ctx.edb().add_facts("edge", vec![(3usize, 0usize)]).unwrap();
+  ctx.run().unwrap();
+  expect_output_collection(
+    "path",
+    ctx.computed_relation_ref("path").unwrap(),
+    vec![
+      (0usize, 0usize),
+      (0, 1),
+      (0, 2),
+      (0, 3),
+      (2, 0),
+      (2, 1),
+      (2, 2),
+      (2, 3),
+      (3, 0),
+      (3, 1),
+      (3, 2),
+      (3, 3),
+    ],
+  );
+  assert_eq!(succ_facts(&ctx), succ_facts_before);
+}
+
+#[test]
+fn incr_retract_probabilistic_facts_2() {
+  let prov_ctx = provenance::min_max_prob::MinMaxProbProvenance::default();
+  let mut ctx = integrate::IntegrateContext::<_, RcFamily>::new_incremental(prov_ctx);
+  ctx.add_relation("edge(usize, usize)").unwrap();
+  ctx
+    .add_rule(r#"path(a, c) = edge(a, c) \/ path(a, b) /\ edge(b, c)"#)
+    .unwrap();
+  ctx
+    .add_facts(
+      "edge",
+      vec![
+        (Some(0.9), (0usize, 1usize).into()),
+        (Some(0.8), (1usize, 2usize).into()),
+        (Some(0.3), (0usize, 2usize).into()),
+      ],
+      false,
+    )
+    .unwrap();
+  ctx.run().unwrap();
+
+  // The path derived from the retracted edge is rederived with the probability of the remaining derivation
+  ctx.edb().retract_facts("edge", vec![(1usize, 2usize)]).unwrap();
+  ctx.run().unwrap();
+  let path = ctx.computed_relation_ref("path").unwrap();
+  let probs = path.iter().map(|(p, t)| (t.clone(), *p)).collect::<Vec<_>>();
+  let expected: Vec<(Tuple, f64)> = vec![((0usize, 1usize).into(), 0.9), ((0usize, 2usize).into(), 0.3)];
+  assert_eq!(probs, expected);
+}
+
+#[test]
+fn incr_clear_relation_1() {
+  let prov_ctx = provenance::unit::UnitProvenance::default();
+  let mut ctx = integrate::IntegrateContext::<_, RcFamily>::new_incremental(prov_ctx);
+  ctx.add_relation("edge(usize, usize)").unwrap();
+  ctx.add_relation("start(usize)").unwrap();
+  ctx
+    .add_rule(r#"reach(b) = start(b) \/ reach(a) /\ edge(a, b)"#)
+    .unwrap();
+  ctx
+    .edb()
+    .add_facts("edge", vec![(0usize, 1usize), (1, 2), (3, 4)])
+    .unwrap();
+  ctx.edb().add_facts("start", vec![(0usize,)]).unwrap();
+
+  // The added facts can be inspected before running
+  assert_eq!(ctx.edb().facts_of("start"), &[(None, Tuple::from((0usize,)))]);
+  ctx.run().unwrap();
+  expect_output_collection(
+    "reach",
+    ctx.computed_relation_ref("reach").unwrap(),
+    vec![(0usize,), (1,), (2,)],
+  );
+
+  // Only the cleared relation changes between the runs
+  ctx.edb().clear_relation("start").unwrap();
+  assert!(ctx.edb().facts_of("start").is_empty());
+  ctx.edb().add_facts("start", vec![(3usize,)]).unwrap();
+  ctx.run().unwrap();
+  expect_output_collection(
+    "reach",
+    ctx.computed_relation_ref("reach").unwrap(),
+    vec![(3usize,), (4,)],
+  );
+  assert_eq!(ctx.edb().facts_of("edge").len(), 3);
+}
+
+#[test]
+fn incr_clear_relation_2() {
+  let prov_ctx = provenance::min_max_prob::MinMaxProbProvenance::default();
+  let mut ctx = integrate::IntegrateContext::<_, RcFamily>::new_incremental(prov_ctx);
+  ctx
+    .add_program(
+      r#"
+      rel start = {0.5::0, 0.2::1}
+      rel edge = {(0, 1), (1, 2)}
+      rel reach(b) = start(b) or reach(a) and edge(a, b)
+      "#,
+    )
+    .unwrap();
+  ctx.compile().unwrap();
+
+  // The program facts of a relation cleared before the first run are not populated
+  ctx.edb().clear_relation("start").unwrap();
+  ctx
+    .add_facts("start", vec![(Some(0.9), (1i32,).into())], false)
+    .unwrap();
+  assert_eq!(ctx.edb().facts_of("start"), &[(Some(0.9), Tuple::from((1i32,)))]);
+  ctx.run().unwrap();
+  let reach = ctx.computed_relation_ref("reach").unwrap();
+  let probs = reach.iter().map(|(p, t)| (t.clone(), *p)).collect::<Vec<_>>();
+  let expected: Vec<(Tuple, f64)> = vec![((1i32,).into(), 0.9), ((2i32,).into(), 0.9)];
+  assert_eq!(probs, expected);
+  assert_eq!(ctx.edb().facts_of("edge").len(), 2);
+}
@@ -0,0 +1,836 @@
+use std::fs;
+use std::path::PathBuf;
+
+use scallop_core::common::input_file::*;
+use scallop_core::common::input_tag::DynamicInputTag;
+use scallop_core::common::output_option::*;
+use scallop_core::common::tuple::Tuple;
+use scallop_core::common::tuple_type::*;
+use scallop_core::common::value::Value;
+use scallop_core::common::value_type::{FromType, ValueType};
+use scallop_core::compiler::compile_string_to_ram;
+use scallop_core::integrate::*;
+use scallop_core::runtime::dynamic::io;
+use scallop_core::runtime::error::*;
+use scallop_core::runtime::provenance::*;
+use scallop_core::utils::*;
+
+#[test]
+fn store_all_outputs_1() {
+  let prov = unit::UnitProvenance::default();
+  let mut ctx = IntegrateContext::<_, RcFamily>::new(prov);
+  ctx
+    .add_program(
+      r#"
+      rel edge = {(0, 1), (1, 2)}
+      rel path(a, c) = edge(a, c) or (path(a, b) and edge(b, c))
+      @hidden rel tmp(a) = edge(a, _)
+    "#,
+    )
+    .expect("Compilation error");
+  ctx.run().expect("Runtime error");
+
+  // Store all the relations into a fresh directory
+  let dir = std::env::temp_dir().join("scallop_test_store_all_outputs_1");
+  let _ = fs::remove_dir_all(&dir);
+  ctx.store_all_outputs(&dir).expect("Cannot store outputs");
+
+  // Check the stored files
+  let path = fs::read_to_string(dir.join("path.csv")).expect("Cannot read file");
+  assert_eq!(path.lines().collect::<Vec<_>>(), vec!["0,1", "0,2", "1,2"]);
+  let edge = fs::read_to_string(dir.join("edge.csv")).expect("Cannot read file");
+  assert_eq!(edge.lines().collect::<Vec<_>>(), vec!["0,1", "1,2"]);
+  assert!(!dir.join("tmp.csv").exists());
+  let _ = fs::remove_dir_all(&dir);
+
+  // The stored relations are written without being recovered
+  assert!(ctx.internal_context().exec_ctx.relation_ref("path").is_none());
+}
+
+fn write_temp_file(name: &str, content: &str) -> PathBuf {
+  let path = std::env::temp_dir().join(name);
+  fs::write(&path, content).expect("Cannot write file");
+  path
+}
+
+fn loaded_tuples(input_file: &InputFile) -> Vec<(i32, i32)> {
+  let types = <TupleType as FromType<(i32, i32)>>::from_type();
+  let tuples = io::load(input_file, &types).expect("Cannot load file");
+  tuples
+    .into_iter()
+    .map(|(_, t)| (t[0].as_i32(), t[1].as_i32()))
+    .collect()
+}
+
+#[test]
+fn load_txt_whitespace_separated_1() {
+  let path = write_temp_file("scallop_test_load_txt_1.txt", "0   1\n1\t\t2\n\n  2 3  \n");
+  let input_file = InputFile::txt_with_options(path, Some(Delimiter::Whitespace));
+  assert_eq!(loaded_tuples(&input_file), vec![(0, 1), (1, 2), (2, 3)]);
+}
+
+#[test]
+fn load_csv_whitespace_separated_1() {
+  let path = write_temp_file("scallop_test_load_csv_ws_1.csv", "a b\n0.5   0 1\n0.9 1   2\n");
+  let input_file = InputFile::csv_with_options(
+    path,
+    CsvOptions {
+      deliminator: Delimiter::Whitespace,
+      has_header: true,
+      has_probability: true,
+      ..Default::default()
+    },
+  );
+  assert_eq!(loaded_tuples(&input_file), vec![(0, 1), (1, 2)]);
+}
+
+#[test]
+fn load_csv_deliminator_regex_1() {
+  let path = write_temp_file("scallop_test_load_csv_regex_1.csv", "0 ; 1\n1;2\n2 ;  3\n");
+  let input_file = InputFile::csv_with_options(
+    path,
+    CsvOptions {
+      deliminator: Delimiter::Regex(r"\s*;\s*".to_string()),
+      ..Default::default()
+    },
+  );
+  assert_eq!(loaded_tuples(&input_file), vec![(0, 1), (1, 2), (2, 3)]);
+}
+
+#[test]
+fn load_csv_invalid_deliminator_regex_1() {
+  let path = write_temp_file("scallop_test_load_csv_regex_2.csv", "0,1\n");
+  let input_file = InputFile::csv_with_options(
+    path,
+    CsvOptions {
+      deliminator: Delimiter::Regex("(".to_string()),
+      ..Default::default()
+    },
+  );
+  let types = <TupleType as FromType<(i32, i32)>>::from_type();
+  match io::load(&input_file, &types) {
+    Err(IOError::InvalidDeliminatorRegex { .. }) => {}
+    r => panic!("Expected invalid regex error, found {:?}", r),
+  }
+}
+
+#[test]
+fn load_csv_quote_char_1() {
+  let path = write_temp_file(
+    "scallop_test_load_csv_quote_1.csv",
+    "0\t'hello\tworld'\n1\t'it''s'\n2\t\"quoted\"\n",
+  );
+  let input_file = InputFile::csv_with_options(
+    path,
+    CsvOptions {
+      deliminator: Delimiter::Byte(b'\t'),
+      quote_char: Some(b'\''),
+      ..Default::default()
+    },
+  );
+  let types = <TupleType as FromType<(i32, String)>>::from_type();
+  let tuples = io::load(&input_file, &types).expect("Cannot load file");
+  let strings = tuples.into_iter().map(|(_, t)| t[1].as_string()).collect::<Vec<_>>();
+  assert_eq!(strings, vec!["hello\tworld", "it's", "\"quoted\""]);
+}
+
+#[test]
+fn load_csv_empty_value_1() {
+  let path = write_temp_file("scallop_test_load_csv_empty_1.csv", "0,1\n1,\n,3\n");
+  let input_file = InputFile::csv_with_options(
+    path.clone(),
+    CsvOptions {
+      empty_value: Some("-1".to_string()),
+      ..Default::default()
+    },
+  );
+  assert_eq!(loaded_tuples(&input_file), vec![(0, 1), (1, -1), (-1, 3)]);
+
+  // Without an empty value, empty fields cannot be parsed as integers
+  let input_file = InputFile::csv(path);
+  let types = <TupleType as FromType<(i32, i32)>>::from_type();
+  match io::load(&input_file, &types) {
+    Err(IOError::ValueParseError { .. }) => {}
+    r => panic!("Expected value parse error, found {:?}", r),
+  }
+}
+
+#[test]
+fn load_csv_parse_error_location_1() {
+  let types = <TupleType as FromType<(i32, i32)>>::from_type();
+
+  // Rows count the lines of the file, including the header
+  let path = write_temp_file("scallop_test_load_csv_location_1.csv", "a,b\n0,1\n1,x\n");
+  let input_file = InputFile::csv_with_options(
+    path,
+    CsvOptions {
+      has_header: true,
+      ..Default::default()
+    },
+  );
+  match io::load(&input_file, &types) {
+    Err(e @ IOError::ValueParseError { row: 3, col: 2, .. }) => {
+      assert_eq!(e.to_string(), "IO: Row 3, column 2: Cannot parse value `x` as `i32`")
+    }
+    r => panic!("Expected value parse error, found {:?}", r),
+  }
+
+  // Columns refer to the file, even when the fields are selected by the header
+  let path = write_temp_file("scallop_test_load_csv_location_2.csv", "a,b,c\n0,1,2\ny,3,4\n");
+  let columns = vec!["c".to_string(), "a".to_string()];
+  let input_file = InputFile::csv_with_options(
+    path,
+    CsvOptions {
+      has_header: true,
+      column_mapping: Some(columns),
+      ..Default::default()
+    },
+  );
+  match io::load(&input_file, &types) {
+    Err(IOError::ValueParseError { row: 3, col: 1, .. }) => {}
+    r => panic!("Expected value parse error, found {:?}", r),
+  }
+
+  // Files split line by line skip the blank lines but still count them
+  let path = write_temp_file("scallop_test_load_csv_location_3.txt", "0 1\n\n2 3 4\n");
+  let input_file = InputFile::csv_with_options(
+    path,
+    CsvOptions {
+      deliminator: Delimiter::Whitespace,
+      ..Default::default()
+    },
+  );
+  match io::load(&input_file, &types) {
+    Err(e @ IOError::ArityMismatch { row: 3, .. }) => {
+      assert_eq!(e.to_string(), "IO: Arity mismatch at row 3; expected 2, found 3")
+    }
+    r => panic!("Expected arity mismatch, found {:?}", r),
+  }
+}
+
+#[test]
+fn load_csv_duration_seconds_1() {
+  let path = write_temp_file("scallop_test_load_csv_duration_1.csv", "0,90\n1,-5\n2,1h\n");
+  let input_file = InputFile::csv(path);
+  let types = <TupleType as FromType<(i32, chrono::Duration)>>::from_type();
+  let tuples = io::load(&input_file, &types).expect("Cannot load file");
+  let seconds = tuples
+    .into_iter()
+    .map(|(_, t)| t[1].as_value().as_duration().num_seconds())
+    .collect::<Vec<_>>();
+  assert_eq!(seconds, vec![90, -5, 3600]);
+}
+
+#[test]
+fn load_csv_symbol_1() {
+  let path = write_temp_file("scallop_test_load_csv_symbol_1.csv", "alice,bob\nbob,alice\n");
+  let types = TupleType::from_types(&[ValueType::Symbol, ValueType::Symbol], false);
+  let tuples = io::load(&InputFile::csv(path), &types).expect("Cannot load file");
+  let (t1, t2) = (&tuples[0].1, &tuples[1].1);
+  assert_eq!(t1[0], t2[1]);
+  assert_eq!(t1[0].as_value(), Value::symbol("alice"));
+  assert_eq!(t1[1].as_str(), "bob");
+  assert_eq!(t1.to_string(), "(\"alice\", \"bob\")");
+}
+
+#[test]
+fn load_csv_probability_interval_1() {
+  let path = write_temp_file("scallop_test_load_csv_interval_1.csv", "0.3:0.7,0,1\n0.5,1,2\n");
+  let input_file = InputFile::csv_with_options(
+    path,
+    CsvOptions {
+      has_probability: true,
+      ..Default::default()
+    },
+  );
+  let types = <TupleType as FromType<(i32, i32)>>::from_type();
+  let tags = io::load(&input_file, &types)
+    .expect("Cannot load file")
+    .into_iter()
+    .map(|(tag, _)| tag)
+    .collect::<Vec<_>>();
+  assert_eq!(
+    tags,
+    vec![DynamicInputTag::Interval(0.3, 0.7), DynamicInputTag::Float(0.5)]
+  );
+}
+
+#[test]
+fn load_csv_probability_interval_invalid_1() {
+  let path = write_temp_file("scallop_test_load_csv_interval_invalid_1.csv", "0.7:0.3,0,1\n");
+  let input_file = InputFile::csv_with_options(
+    path,
+    CsvOptions {
+      has_probability: true,
+      ..Default::default()
+    },
+  );
+  let types = <TupleType as FromType<(i32, i32)>>::from_type();
+  match io::load(&input_file, &types) {
+    Err(IOError::CannotParseProbability { value }) => assert_eq!(value, "0.7:0.3"),
+    r => panic!("Expected probability parse error, found {:?}", r),
+  }
+}
+
+#[test]
+fn load_csv_probability_interval_provenance_1() {
+  let path = write_temp_file("scallop_test_load_csv_interval_prov_1.csv", "0.3:0.7,0,1\n0.5,1,2\n");
+  let input_file = InputFile::csv_with_options(
+    path,
+    CsvOptions {
+      has_probability: true,
+      ..Default::default()
+    },
+  );
+  let types = <TupleType as FromType<(i32, i32)>>::from_type();
+  let edges = io::load(&input_file, &types).expect("Cannot load file");
+
+  // Run the loaded facts under the interval provenance
+  let prov = interval_prob::IntervalProbProvenance::default();
+  let mut ctx = IntegrateContext::<_, RcFamily>::new(prov);
+  ctx
+    .add_program(
+      r#"
+      type edge(i32, i32)
+      rel path(a, c) = edge(a, c) or (path(a, b) and edge(b, c))
+    "#,
+    )
+    .expect("Compilation error");
+  ctx
+    .edb()
+    .add_dynamic_input_facts("edge", edges)
+    .expect("Cannot add facts");
+  ctx.run().expect("Runtime error");
+
+  // The bounds are propagated through the conjunction
+  let path = ctx.computed_relation_ref("path").expect("Cannot find relation");
+  let (tag, _) = path
+    .iter()
+    .find(|(_, t)| t == &Tuple::from((0i32, 2i32)))
+    .expect("Cannot find tuple");
+  assert!((tag.lo - 0.15).abs() < 0.001);
+  assert!((tag.hi - 0.35).abs() < 0.001);
+}
+
+#[test]
+fn load_csv_datetime_1() {
+  let path = write_temp_file(
+    "scallop_test_load_csv_datetime_1.csv",
+    "0,2019-01-04T00:00:00Z\n1,2019-01-04T08:00:00+08:00\n",
+  );
+  let input_file = InputFile::csv(path);
+  let types = <TupleType as FromType<(i32, chrono::DateTime<chrono::Utc>)>>::from_type();
+  let tuples = io::load(&input_file, &types).expect("Cannot load file");
+  let dates = tuples
+    .into_iter()
+    .map(|(_, t)| t[1].as_value().as_date_time())
+    .collect::<Vec<_>>();
+  assert_eq!(dates[0], dates[1]);
+
+  // Malformed date times are reported as parse errors
+  let path = write_temp_file("scallop_test_load_csv_datetime_2.csv", "0,yesterday-ish\n");
+  match io::load(&InputFile::csv(path), &types) {
+    Err(IOError::ValueParseError { .. }) => {}
+    r => panic!("Expected value parse error, found {:?}", r),
+  }
+}
+
+#[test]
+fn load_csv_nested_tuple_1() {
+  let path = write_temp_file(
+    "scallop_test_load_csv_nested_tuple_1.csv",
+    "0,\"(3, (alice, true))\"\n1,\"(5,(bob,false))\"\n",
+  );
+  let record = ValueType::Tuple(vec![
+    ValueType::I32,
+    ValueType::Tuple(vec![ValueType::String, ValueType::Bool]),
+  ]);
+  let types = TupleType::from_types(&[ValueType::I32, record.clone()], false);
+  let tuples = io::load(&InputFile::csv(path), &types).expect("Cannot load file");
+  let records = tuples
+    .into_iter()
+    .map(|(_, t)| t[1].as_value().clone())
+    .collect::<Vec<_>>();
+  assert_eq!(
+    records,
+    vec![
+      Value::Tuple(vec![
+        Value::I32(3),
+        Value::Tuple(vec![Value::String("alice".to_string()), Value::Bool(true)]),
+      ]),
+      Value::Tuple(vec![
+        Value::I32(5),
+        Value::Tuple(vec![Value::String("bob".to_string()), Value::Bool(false)]),
+      ]),
+    ]
+  );
+  assert_eq!(records[0].value_type(), record);
+  assert!(records[0] < records[1]);
+
+  // Groups of the wrong arity are reported as parse errors
+  let path = write_temp_file("scallop_test_load_csv_nested_tuple_2.csv", "0,\"(3, (alice))\"\n");
+  match io::load(&InputFile::csv(path), &types) {
+    Err(IOError::ValueParseError { .. }) => {}
+    r => panic!("Expected value parse error, found {:?}", r),
+  }
+
+  // Relation types themselves still have to be flat
+  let nested = TupleType::Tuple(Box::new([
+    TupleType::Value(ValueType::I32),
+    TupleType::from_types(&[ValueType::I32, ValueType::Bool], false),
+  ]));
+  let path = write_temp_file("scallop_test_load_csv_nested_tuple_3.csv", "0,\"(3, true)\"\n");
+  match io::load(&InputFile::csv(path), &nested) {
+    Err(IOError::InvalidType { .. }) => {}
+    r => panic!("Expected invalid type, found {:?}", r),
+  }
+}
+
+#[test]
+fn load_csv_quote_char_attribute_1() {
+  let ram = compile_string_to_ram(
+    r#"
+    @file("name.csv", deliminator = "\t", quote_char = "'", empty_value = "none")
+    type name(i32, String)
+  "#
+    .to_string(),
+  )
+  .expect("Compilation error");
+  let expected = InputFile::csv_with_options(
+    PathBuf::from("name.csv"),
+    CsvOptions {
+      deliminator: Delimiter::Byte(b'\t'),
+      quote_char: Some(b'\''),
+      empty_value: Some("none".to_string()),
+      ..Default::default()
+    },
+  );
+  assert_eq!(ram.relation("name").unwrap().input_file, Some(expected));
+}
+
+#[test]
+fn load_csv_column_mapping_1() {
+  let path = write_temp_file(
+    "scallop_test_load_csv_column_mapping_1.csv",
+    "prob,id,name,age\n0.5,0,alice,30\n0.9,1,bob,40\n",
+  );
+  let columns = vec!["age".to_string(), "id".to_string()];
+  let input_file = InputFile::csv_with_options(
+    path,
+    CsvOptions {
+      has_header: true,
+      has_probability: true,
+      column_mapping: Some(columns),
+      ..Default::default()
+    },
+  );
+  let types = <TupleType as FromType<(i32, i32)>>::from_type();
+  let tuples = io::load(&input_file, &types).expect("Cannot load file");
+  assert_eq!(tuples[0].0, DynamicInputTag::Float(0.5));
+  assert_eq!(
+    tuples
+      .into_iter()
+      .map(|(_, t)| (t[0].as_i32(), t[1].as_i32()))
+      .collect::<Vec<_>>(),
+    vec![(30, 0), (40, 1)]
+  );
+}
+
+#[test]
+fn load_csv_column_mapping_whitespace_1() {
+  let path = write_temp_file("scallop_test_load_csv_column_mapping_3.txt", "a  b c\n1 2 3\n4 5  6\n");
+  let columns = vec!["c".to_string(), "a".to_string()];
+  let input_file = InputFile::csv_with_options(
+    path,
+    CsvOptions {
+      deliminator: Delimiter::Whitespace,
+      has_header: true,
+      column_mapping: Some(columns),
+      ..Default::default()
+    },
+  );
+  assert_eq!(loaded_tuples(&input_file), vec![(3, 1), (6, 4)]);
+}
+
+#[test]
+fn load_csv_column_mapping_missing_column_1() {
+  let path = write_temp_file("scallop_test_load_csv_column_mapping_2.csv", "id,name\n0,alice\n");
+  let columns = vec!["id".to_string(), "age".to_string()];
+  let input_file = InputFile::csv_with_options(
+    path,
+    CsvOptions {
+      has_header: true,
+      column_mapping: Some(columns),
+      ..Default::default()
+    },
+  );
+  let types = <TupleType as FromType<(i32, i32)>>::from_type();
+  match io::load(&input_file, &types) {
+    Err(e @ IOError::MissingCSVColumn { .. }) => {
+      assert_eq!(
+        e.to_string(),
+        "IO: Column `age` is not found in the CSV header [id, name]"
+      )
+    }
+    r => panic!("Expected missing column error, found {:?}", r),
+  }
+}
+
+#[test]
+fn load_csv_column_mapping_arity_mismatch_1() {
+  // The records need to have as many fields as the header, even if some of the fields are not selected
+  let path = write_temp_file(
+    "scallop_test_load_csv_column_mapping_4.csv",
+    "id,name,age\n0,alice,30\n1,bob,40,extra\n",
+  );
+  let columns = vec!["age".to_string(), "id".to_string()];
+  let input_file = InputFile::csv_with_options(
+    path,
+    CsvOptions {
+      has_header: true,
+      column_mapping: Some(columns),
+      ..Default::default()
+    },
+  );
+  let types = <TupleType as FromType<(i32, i32)>>::from_type();
+  match io::load(&input_file, &types) {
+    Err(IOError::ArityMismatch { row: 3, expected: 3, found: 4 }) => {}
+    r => panic!("Expected arity mismatch, found {:?}", r),
+  }
+}
+
+#[test]
+fn load_csv_columns_attribute_1() {
+  let ram = compile_string_to_ram(
+    r#"
+    @file("person.csv", has_header = true, columns = "id, age")
+    type person(i32, i32)
+  "#
+    .to_string(),
+  )
+  .expect("Compilation error");
+  let expected = InputFile::csv_with_options(
+    PathBuf::from("person.csv"),
+    CsvOptions {
+      has_header: true,
+      column_mapping: Some(vec!["id".to_string(), "age".to_string()]),
+      ..Default::default()
+    },
+  );
+  assert_eq!(ram.relation("person").unwrap().input_file, Some(expected));
+
+  // Columns can only be selected by name when there is a header
+  assert!(compile_string_to_ram(
+    r#"
+    @file("person.csv", columns = "id, age")
+    type person(i32, i32)
+  "#
+    .to_string(),
+  )
+  .is_err());
+}
+
+#[test]
+fn load_csv_comment_char_1() {
+  let path = write_temp_file(
+    "scallop_test_load_csv_comment_1.csv",
+    "# exported edges\na,b\n0,1\n# 1,x\n\n1,2\n\n2,3\n\n\n",
+  );
+  let input_file = InputFile::csv_with_options(
+    path.clone(),
+    CsvOptions {
+      has_header: true,
+      comment_char: Some(b'#'),
+      ..Default::default()
+    },
+  );
+  assert_eq!(loaded_tuples(&input_file), vec![(0, 1), (1, 2), (2, 3)]);
+
+  // The same applies to files split line by line
+  let path = write_temp_file("scallop_test_load_csv_comment_2.txt", "% edges\n0 1\n\n% 1 x\n1 2\n\n");
+  let input_file = InputFile::csv_with_options(
+    path,
+    CsvOptions {
+      deliminator: Delimiter::Whitespace,
+      comment_char: Some(b'%'),
+      ..Default::default()
+    },
+  );
+  assert_eq!(loaded_tuples(&input_file), vec![(0, 1), (1, 2)]);
+}
+
+#[test]
+fn load_csv_blank_lines_1() {
+  // Blank lines are skipped even without a comment character
+  let path = write_temp_file("scallop_test_load_csv_blank_1.csv", "0,1\n\n1,2\n\n\n");
+  assert_eq!(loaded_tuples(&InputFile::csv(path)), vec![(0, 1), (1, 2)]);
+}
+
+#[test]
+fn load_csv_quoted_empty_row_1() {
+  // A quoted empty field is data rather than a blank line
+  let path = write_temp_file("scallop_test_load_csv_quoted_empty_1.csv", "a\n\"\"\nb\n");
+  let types = <TupleType as FromType<(String,)>>::from_type();
+  let tuples = io::load(&InputFile::csv(path), &types).expect("Cannot load file");
+  let strings = tuples.into_iter().map(|(_, t)| t[0].as_string()).collect::<Vec<_>>();
+  assert_eq!(strings, vec!["a", "", "b"]);
+}
+
+#[test]
+fn load_csv_empty_fields_row_1() {
+  // A row of empty fields is filled with the empty value instead of being skipped
+  let path = write_temp_file("scallop_test_load_csv_empty_fields_1.csv", "0,1\n,\n1,2\n");
+  let input_file = InputFile::csv_with_options(
+    path,
+    CsvOptions {
+      empty_value: Some("-1".to_string()),
+      ..Default::default()
+    },
+  );
+  assert_eq!(loaded_tuples(&input_file), vec![(0, 1), (-1, -1), (1, 2)]);
+}
+
+#[test]
+fn load_csv_comment_char_attribute_1() {
+  let ram = compile_string_to_ram(
+    r##"
+    @file("edge.csv", comment_char = "#")
+    type edge(i32, i32)
+  "##
+      .to_string(),
+  )
+  .expect("Compilation error");
+  let expected = InputFile::csv_with_options(
+    PathBuf::from("edge.csv"),
+    CsvOptions {
+      comment_char: Some(b'#'),
+      ..Default::default()
+    },
+  );
+  assert_eq!(ram.relation("edge").unwrap().input_file, Some(expected));
+
+  // The comment character needs to be a single character
+  assert!(compile_string_to_ram(
+    r#"
+    @file("edge.csv", comment_char = "//")
+    type edge(i32, i32)
+  "#
+    .to_string(),
+  )
+  .is_err());
+}
+
+#[test]
+fn load_json_arrays_1() {
+  let path = write_temp_file("scallop_test_load_json_1.json", "[[0.5, 0, 1], [0.9, \"1\", 2]]");
+  let input_file = InputFile::json_with_options(path, None, Some(true));
+  let types = <TupleType as FromType<(i32, i32)>>::from_type();
+  let tuples = io::load(&input_file, &types).expect("Cannot load file");
+  assert_eq!(tuples[0].0, DynamicInputTag::Float(0.5));
+  assert_eq!(tuples[1].0, DynamicInputTag::Float(0.9));
+  assert_eq!(loaded_tuples(&input_file), vec![(0, 1), (1, 2)]);
+}
+
+#[test]
+fn load_json_arity_mismatch_1() {
+  let types = <TupleType as FromType<(i32, i32)>>::from_type();
+  let inputs = vec![
+    ("scallop_test_load_json_7.json", "[[]]", 0),
+    ("scallop_test_load_json_8.json", "[[0.5, 0, 1, 2]]", 3),
+  ];
+  for (name, content, expected_found) in inputs {
+    let input_file = InputFile::json_with_options(write_temp_file(name, content), None, Some(true));
+    match io::load(&input_file, &types) {
+      Err(IOError::ArityMismatch { row: 1, expected: 2, found }) => assert_eq!(found, expected_found),
+      r => panic!("Expected arity mismatch error, found {:?}", r),
+    }
+  }
+}
+
+#[test]
+fn load_json_objects_1() {
+  let path = write_temp_file(
+    "scallop_test_load_json_2.json",
+    r#"[{"to": 1, "from": 0, "label": "a"}, {"from": 1, "to": 2}]"#,
+  );
+  let fields = vec!["from".to_string(), "to".to_string()];
+  let input_file = InputFile::json_with_options(path, Some(fields), None);
+  assert_eq!(loaded_tuples(&input_file), vec![(0, 1), (1, 2)]);
+}
+
+#[test]
+fn load_json_malformed_1() {
+  let types = <TupleType as FromType<(i32, i32)>>::from_type();
+  let inputs = vec![
+    ("scallop_test_load_json_3.json", "[[0, 1], [1, 2"),
+    ("scallop_test_load_json_4.json", "{\"from\": 0, \"to\": 1}"),
+    ("scallop_test_load_json_5.json", "[[0, null]]"),
+    ("scallop_test_load_json_6.json", "[{\"from\": 0, \"to\": 1}]"),
+  ];
+  for (name, content) in inputs {
+    let input_file = InputFile::json(write_temp_file(name, content));
+    match io::load(&input_file, &types) {
+      Err(IOError::CannotParseJSON { .. }) => {}
+      r => panic!("Expected JSON parse error, found {:?}", r),
+    }
+  }
+}
+
+#[test]
+fn load_json_attribute_1() {
+  let ram = compile_string_to_ram(
+    r#"
+    @file("edge.json", fields = "from, to", has_probability = true)
+    type edge(i32, i32)
+  "#
+    .to_string(),
+  )
+  .expect("Compilation error");
+  let expected = InputFile::json_with_options(
+    PathBuf::from("edge.json"),
+    Some(vec!["from".to_string(), "to".to_string()]),
+    Some(true),
+  );
+  assert_eq!(ram.relation("edge").unwrap().input_file, Some(expected));
+}
+
+#[test]
+fn store_csv_append_1() {
+  let path = write_temp_file("scallop_test_store_csv_append_1.csv", "0,1\n");
+  let output_file = OutputFile::CSV(OutputCSVFile::new_with_options(path.clone(), None, Some(true)));
+  io::store(
+    &output_file,
+    vec![Tuple::from((1i32, 2i32)), Tuple::from((2i32, 3i32))].into_iter(),
+  )
+  .expect("Cannot store file");
+  let content = fs::read_to_string(&path).expect("Cannot read file");
+  assert_eq!(content.lines().collect::<Vec<_>>(), vec!["0,1", "1,2", "2,3"]);
+
+  // Without `append` the file is truncated
+  let output_file = OutputFile::CSV(OutputCSVFile::new(path.clone()));
+  io::store(&output_file, vec![Tuple::from((5i32, 6i32))].into_iter()).expect("Cannot store file");
+  let content = fs::read_to_string(&path).expect("Cannot read file");
+  assert_eq!(content.lines().collect::<Vec<_>>(), vec!["5,6"]);
+}
+
+#[test]
+fn store_csv_append_attribute_1() {
+  let ram = compile_string_to_ram(
+    r#"
+    rel edge = {(0, 1), (1, 2)}
+    @file("edge_out.csv", append = true)
+    query edge
+  "#
+    .to_string(),
+  )
+  .unwrap();
+  match ram.output_option("edge") {
+    Some(OutputOption::File(OutputFile::CSV(f))) => assert!(f.append),
+    o => panic!("Expected CSV output file, found {:?}", o),
+  }
+}
+
+#[test]
+fn store_json_arrays_1() {
+  let path = std::env::temp_dir().join("scallop_test_store_json_1.json");
+  let tuples = vec![
+    Tuple::from((0.1f32, 0.1f64, "say \"hi\"\n".to_string())),
+    Tuple::from((f32::INFINITY, f64::NAN, "é\\".to_string())),
+  ];
+  io::store(&OutputFile::JSON(OutputJSONFile::new(path.clone())), tuples.into_iter()).expect("Cannot store file");
+  let content = fs::read_to_string(&path).expect("Cannot read file");
+  assert_eq!(
+    content.lines().collect::<Vec<_>>(),
+    vec!["[", r#"[0.1,0.1,"say \"hi\"\n"],"#, r#"["inf","NaN","é\\"]"#, "]"]
+  );
+}
+
+#[test]
+fn store_json_objects_1() {
+  let path = std::env::temp_dir().join("scallop_test_store_json_2.json");
+  let fields = vec!["id".to_string(), "big".to_string()];
+  let output_file = OutputFile::JSON(OutputJSONFile::new_with_options(path.clone(), Some(fields), None));
+  io::store(&output_file, vec![Tuple::from((1i32, i128::MAX))].into_iter()).expect("Cannot store file");
+  let content = fs::read_to_string(&path).expect("Cannot read file");
+  assert_eq!(content, format!("[\n{{\"id\":1,\"big\":{}}}\n]\n", i128::MAX));
+
+  // The number of fields needs to match the arity of the tuples
+  match io::store(&output_file, vec![Tuple::from((1i32,))].into_iter()) {
+    Err(IOError::CannotWriteRecord { .. }) => {}
+    r => panic!("Expected cannot write record error, found {:?}", r),
+  }
+}
+
+#[test]
+fn store_load_json_round_trip_1() {
+  let path = std::env::temp_dir().join("scallop_test_store_json_3.json");
+  let _ = fs::remove_file(&path);
+  for fields in vec![None, Some(vec!["from".to_string(), "to".to_string()])] {
+    // The records appended to the file are loaded along with the stored ones
+    let output_file = OutputFile::JSON(OutputJSONFile::new_with_options(path.clone(), fields.clone(), None));
+    io::store(&output_file, vec![Tuple::from((0i32, 1i32))].into_iter()).expect("Cannot store file");
+    let output_file = OutputFile::JSON(OutputJSONFile::new_with_options(path.clone(), fields.clone(), Some(true)));
+    io::store(&output_file, std::iter::empty::<Tuple>()).expect("Cannot store file");
+    io::store(
+      &output_file,
+      vec![Tuple::from((1i32, 2i32)), Tuple::from((2i32, 3i32))].into_iter(),
+    )
+    .expect("Cannot store file");
+    let input_file = InputFile::json_with_options(path.clone(), fields, None);
+    assert_eq!(loaded_tuples(&input_file), vec![(0, 1), (1, 2), (2, 3)]);
+  }
+
+  // Appending to a file that does not hold an array of records is an error
+  fs::write(&path, "{\"from\": 0}").expect("Cannot write file");
+  let output_file = OutputFile::JSON(OutputJSONFile::new_with_options(path.clone(), None, Some(true)));
+  match io::store(&output_file, vec![Tuple::from((1i32, 2i32))].into_iter()) {
+    Err(IOError::CannotWriteRecord { .. }) => {}
+    r => panic!("Expected cannot write record error, found {:?}", r),
+  }
+}
+
+#[test]
+fn store_json_attribute_1() {
+  let ram = compile_string_to_ram(
+    r#"
+    rel edge = {(0, 1), (1, 2)}
+    @file("edge_out.json", fields = "from, to")
+    query edge
+  "#
+    .to_string(),
+  )
+  .unwrap();
+  match ram.output_option("edge") {
+    Some(OutputOption::File(OutputFile::JSON(f))) => {
+      assert_eq!(f.fields, Some(vec!["from".to_string(), "to".to_string()]));
+      assert!(!f.append);
+    }
+    o => panic!("Expected JSON output file, found {:?}", o),
+  }
+}
+
+#[test]
+fn load_txt_lines_1() {
+  let path = write_temp_file(
+    "scallop_test_load_txt_2.txt",
+    "hello  world\n\n\tindented line \r\nlast",
+  );
+  let types = <TupleType as FromType<(String,)>>::from_type();
+  let tuples = io::load(&InputFile::txt(path), &types).expect("Cannot load file");
+  let lines = tuples.into_iter().map(|(_, t)| t[0].as_string()).collect::<Vec<_>>();
+  assert_eq!(lines, vec!["hello  world", "", "\tindented line ", "last"]);
+}
+
+#[test]
+fn load_txt_lines_invalid_type_1() {
+  let path = write_temp_file("scallop_test_load_txt_3.txt", "0\n1\n");
+  for types in vec![
+    <TupleType as FromType<(i32,)>>::from_type(),
+    <TupleType as FromType<(String, String)>>::from_type(),
+  ] {
+    match io::load(&InputFile::txt(path.clone()), &types) {
+      Err(IOError::InvalidType { .. }) => {}
+      r => panic!("Expected invalid type error, found {:?}", r),
+    }
+  }
+}
@@ -1,4 +1,13 @@
+use std::cell::RefCell;
+use std::collections::BTreeSet;
+
+use scallop_core::common::tuple::Tuple;
+use scallop_core::integrate::*;
+use scallop_core::runtime::error::*;
+use scallop_core::runtime::monitor::*;
+use scallop_core::runtime::provenance::*;
 use scallop_core::testing::*;
+use scallop_core::utils::*;
 
 #[test]
 fn edge_path_iter_limit() {
@@ -10,3 +19,226 @@ fn edge_path_iter_limit() {
     8,
   )
 }
+
+#[test]
+fn edge_path_run_stats() {
+  let (idb, stats) = interpret_string_with_stats(
+    r#"
+    rel edge = {(0, 1), (1, 2), (2, 3), (3, 4)}
+    rel path(a, c) = edge(a, c) or path(a, b) and edge(b, c)
+    rel num_paths(n) = n := count(a, b: path(a, b))
+    "#
+    .to_string(),
+  )
+  .expect("Interpret error");
+  assert!(idb.has_relation("path"));
+  assert!(stats.total_iterations > 0);
+  assert_eq!(
+    stats.total_iterations,
+    stats.strata.iter().map(|s| s.iterations).sum::<usize>()
+  );
+  assert!(stats.strata.len() >= 2);
+  assert_eq!(stats.relation_sizes.get("edge"), Some(&4));
+  assert_eq!(stats.relation_sizes.get("path"), Some(&10));
+  assert_eq!(stats.relation_sizes.get("num_paths"), Some(&1));
+}
+
+#[test]
+fn edge_path_stats_report() {
+  let monitor = StatisticsMonitor::new();
+  let mut ctx = IntegrateContext::<_, RcFamily>::new(unit::UnitProvenance::default());
+  ctx
+    .add_program(
+      r#"
+      type edge(usize, usize)
+      rel path(a, c) = edge(a, c) or (path(a, b) and edge(b, c))
+      rel num_paths(n) = n := count(a, b: path(a, b))
+      "#,
+    )
+    .expect("Compilation error");
+
+  // First run
+  ctx
+    .edb()
+    .add_facts("edge", vec![(0usize, 1usize), (1, 2)])
+    .expect("Cannot add facts");
+  ctx.run_with_monitor(&monitor).expect("Runtime error");
+  let first_report = monitor.report();
+  assert!(first_report.len() >= 2);
+  assert!(first_report.windows(2).all(|w| w[0].stratum_id < w[1].stratum_id));
+
+  // Second run; the statistics of each stratum should accumulate
+  ctx
+    .edb()
+    .add_facts("edge", vec![(2usize, 3usize)])
+    .expect("Cannot add facts");
+  ctx.run_with_monitor(&monitor).expect("Runtime error");
+  let report = monitor.report();
+  assert_eq!(report.len(), first_report.len());
+  for (before, after) in first_report.iter().zip(report.iter()) {
+    assert_eq!(before.stratum_id, after.stratum_id);
+    assert!(after.iterations > before.iterations);
+    assert!(after.time >= before.time);
+  }
+  assert_eq!(
+    report.iter().map(|s| s.iterations).sum::<usize>(),
+    monitor.run_stats().total_iterations
+  );
+}
+
+#[test]
+fn edge_path_conditional_stop() {
+  let monitor = ConditionalStopMonitor::new(|iteration_count| iteration_count >= 3);
+  let idb = interpret_string_with_ctx_and_monitor(
+    r#"
+    rel edge = {(0, 1), (1, 2), (2, 3), (3, 4)}
+    rel path(a, c) = edge(a, c) or path(a, b) and edge(b, c)
+    "#
+    .to_string(),
+    unit::UnitProvenance::default(),
+    &monitor,
+  )
+  .expect("Interpret error");
+
+  // Only the paths of length 1 and 2 are derived before stopping
+  expect_output_collection(
+    "path",
+    idb.get_output_collection_ref("path").unwrap(),
+    vec![(0i32, 1i32), (0, 2), (1, 2), (1, 3), (2, 3), (2, 4), (3, 4)],
+  );
+}
+
+#[test]
+fn edge_path_output_callback() {
+  let prov = min_max_prob::MinMaxProbProvenance::default();
+  let mut ctx = IntegrateContext::<_, RcFamily>::new(prov);
+  ctx
+    .add_program(
+      r#"
+      type edge(usize, usize)
+      rel path(a, c) = edge(a, c) or (path(a, b) and edge(b, c))
+      @hidden rel tmp(a) = edge(a, _)
+      "#,
+    )
+    .expect("Compilation error");
+  ctx
+    .add_facts(
+      "edge",
+      vec![
+        (Some(0.5), (0usize, 1usize).into()),
+        (Some(0.8), (1usize, 2usize).into()),
+      ],
+      false,
+    )
+    .expect("Cannot add facts");
+
+  // Stream the outputs while they are recovered
+  let mut streamed = vec![];
+  ctx
+    .run_with_output_callback(|r, t, p| streamed.push((r.to_string(), t.to_string(), *p)))
+    .expect("Runtime error");
+  streamed.sort_by(|a, b| (&a.0, &a.1).cmp(&(&b.0, &b.1)));
+  let expected = vec![
+    ("edge".to_string(), "(0, 1)".to_string(), 0.5),
+    ("edge".to_string(), "(1, 2)".to_string(), 0.8),
+    ("path".to_string(), "(0, 1)".to_string(), 0.5),
+    ("path".to_string(), "(0, 2)".to_string(), 0.5),
+    ("path".to_string(), "(1, 2)".to_string(), 0.8),
+  ];
+  assert_eq!(streamed, expected);
+
+  // The streamed tuples are not stored, but the relations can still be recovered afterwards
+  assert!(ctx.internal_context().exec_ctx.relation_ref("path").is_none());
+  let path = ctx.computed_relation_ref("path").expect("Cannot get relation");
+  assert_eq!(path.len(), 3);
+}
+
+#[test]
+fn nat_relation_size_limit() {
+  let mut ctx = IntegrateContext::<_, RcFamily>::new(unit::UnitProvenance::default());
+  ctx
+    .add_program(
+      r#"
+      rel nat(0)
+      rel nat(n + 1) = nat(n)
+      "#,
+    )
+    .expect("Compilation error");
+  ctx.set_relation_size_limit(100);
+  match ctx.run() {
+    Err(IntegrateError::Runtime(RuntimeError::RelationSizeLimitExceeded { relation, limit })) => {
+      assert_eq!(relation, "nat");
+      assert_eq!(limit, 100);
+    }
+    r => panic!("Expected the relation size limit to be exceeded, found {:?}", r),
+  }
+}
+
+#[test]
+fn edge_path_within_relation_size_limit() {
+  let mut ctx = IntegrateContext::<_, RcFamily>::new(unit::UnitProvenance::default());
+  ctx
+    .add_program(
+      r#"
+      rel edge = {(0, 1), (1, 2), (2, 3), (3, 4)}
+      rel path(a, c) = edge(a, c) or path(a, b) and edge(b, c)
+      "#,
+    )
+    .expect("Compilation error");
+  ctx.set_relation_size_limit(10);
+  ctx.run().expect("Runtime error");
+  assert_eq!(
+    ctx.computed_relation_ref("path").expect("Cannot get relation").len(),
+    10
+  );
+}
+
+#[test]
+fn edge_path_derived_tuples() {
+  struct RecordingMonitor {
+    trace: TupleTraceMonitor,
+    observed: RefCell<BTreeSet<String>>,
+    recorded: RefCell<Vec<(String, Tuple)>>,
+  }
+
+  impl Monitor<unit::UnitProvenance> for RecordingMonitor {
+    fn observe_derived_tuple(&self, relation: &str, tup: &Tuple, _: &unit::Unit) {
+      self.observed.borrow_mut().insert(relation.to_string());
+      if self.trace.is_traced(relation) {
+        self.recorded.borrow_mut().push((relation.to_string(), tup.clone()));
+      }
+    }
+  }
+
+  let monitor = RecordingMonitor {
+    trace: TupleTraceMonitor::new(vec!["path"]),
+    observed: RefCell::new(BTreeSet::new()),
+    recorded: RefCell::new(vec![]),
+  };
+  let mut ctx = IntegrateContext::<_, RcFamily>::new(unit::UnitProvenance::default());
+  ctx
+    .add_program(
+      r#"
+      rel edge = {(0, 1), (1, 2), (2, 3)}
+      rel path(a, c) = edge(a, c) or path(a, b) and edge(b, c)
+      rel start(a) = path(a, _) and not path(_, a)
+      "#,
+    )
+    .expect("Compilation error");
+  ctx.run_with_monitor(&monitor).expect("Runtime error");
+
+  // Every path is reported exactly once, as the tags of the unit provenance never get updated
+  let path = |a: i32, b: i32| ("path".to_string(), Tuple::from((a, b)));
+  let recorded = monitor.recorded.borrow().iter().cloned().collect::<BTreeSet<_>>();
+  assert_eq!(monitor.recorded.borrow().len(), 6);
+  assert_eq!(
+    recorded,
+    vec![path(0, 1), path(1, 2), path(2, 3), path(0, 2), path(1, 3), path(0, 3)]
+      .into_iter()
+      .collect::<BTreeSet<_>>()
+  );
+
+  // The tuples of `start` are derived as well, but filtered out as the relation is not traced
+  assert!(monitor.observed.borrow().contains("start"));
+  assert!(monitor.recorded.borrow().iter().all(|(r, _)| r == "path"));
+}
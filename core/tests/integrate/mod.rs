@@ -5,6 +5,7 @@ mod edb;
 mod ff;
 mod fp;
 mod incr;
+mod io;
 mod iter;
 mod prob;
 mod time;
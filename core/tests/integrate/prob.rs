@@ -1,5 +1,7 @@
+use scallop_core::common::value::Value;
 use scallop_core::runtime::provenance::*;
 use scallop_core::testing::*;
+use scallop_core::utils::RcFamily;
 
 #[test]
 fn test_how_many_3_add_mult() {
@@ -15,6 +17,78 @@ fn test_how_many_3_add_mult() {
   )
 }
 
+#[test]
+fn test_variance_add_mult() {
+  let ctx = add_mult_prob::AddMultProbProvenance::default();
+  expect_interpret_result_with_tag(
+    r#"
+      rel data = {0.5::(1), 0.5::(3)}
+      rel result(v) = v := variance(x: data(x))
+    "#,
+    ctx,
+    ("result", vec![(0.5, (0.0,)), (0.25, (1.0,))]),
+    add_mult_prob::AddMultProbProvenance::soft_cmp,
+  )
+}
+
+#[test]
+fn test_stddev_min_max_prob() {
+  let ctx = min_max_prob::MinMaxProbProvenance::default();
+  expect_interpret_result_with_tag(
+    r#"
+      rel data = {0.9::(1), 0.4::(3)}
+      rel result(s) = s := stddev(x: data(x))
+    "#,
+    ctx,
+    ("result", vec![(0.6, (0.0,)), (0.4, (1.0,))]),
+    min_max_prob::MinMaxProbProvenance::cmp,
+  )
+}
+
+#[test]
+fn test_collect_add_mult() {
+  let ctx = add_mult_prob::AddMultProbProvenance::default();
+  expect_interpret_result_with_tag(
+    r#"
+      rel item = {0.5::(1), 0.4::(2)}
+      rel result(l) = l := collect(x: item(x))
+    "#,
+    ctx,
+    (
+      "result",
+      vec![
+        (0.3, (Value::List(vec![]),)),
+        (0.3, (Value::List(vec![1.into()]),)),
+        (0.2, (Value::List(vec![2.into()]),)),
+        (0.2, (Value::List(vec![1.into(), 2.into()]),)),
+      ],
+    ),
+    add_mult_prob::AddMultProbProvenance::soft_cmp,
+  )
+}
+
+#[test]
+fn test_normalize_add_mult() {
+  let ctx = add_mult_prob::AddMultProbProvenance::default();
+  expect_interpret_result_with_tag(
+    r#"
+      rel count = {0.5::("x", 1), 0.4::("y", 3)}
+      rel result(x, w) = w := normalize(c, x: count(x, c))
+    "#,
+    ctx,
+    (
+      "result",
+      vec![
+        (0.3, ("x".to_string(), 1.0)),
+        (0.2, ("y".to_string(), 1.0)),
+        (0.2, ("x".to_string(), 0.25)),
+        (0.2, ("y".to_string(), 0.75)),
+      ],
+    ),
+    add_mult_prob::AddMultProbProvenance::soft_cmp,
+  )
+}
+
 #[test]
 fn test_min_max_with_recursion() {
   let ctx = min_max_prob::MinMaxProbProvenance::default();
@@ -45,3 +119,152 @@ fn test_min_max_with_recursion() {
     min_max_prob::MinMaxProbProvenance::cmp,
   )
 }
+
+#[test]
+fn test_debug_derived_tags() {
+  use std::cell::RefCell;
+
+  use scallop_core::common::tuple::Tuple;
+  use scallop_core::integrate::IntegrateContext;
+  use scallop_core::runtime::monitor::Monitor;
+  use scallop_core::utils::RcFamily;
+
+  #[derive(Default)]
+  struct DerivedTags(RefCell<Vec<(String, String, String)>>);
+
+  impl<Prov: Provenance> Monitor<Prov> for DerivedTags {
+    fn observe_derived_tuple(&self, relation: &str, tup: &Tuple, tag: &Prov::Tag) {
+      self
+        .0
+        .borrow_mut()
+        .push((relation.to_string(), tup.to_string(), format!("{:?}", tag)));
+    }
+  }
+
+  let prov = min_max_prob::MinMaxProbProvenance::default();
+  let mut ctx = IntegrateContext::<_, RcFamily>::new(prov);
+  ctx
+    .add_program(
+      r#"
+        rel edge = {0.5::(0, 1), 0.8::(1, 2)}
+        rel path(a, b) = edge(a, b)
+      "#,
+    )
+    .unwrap();
+
+  // The derived facts are reported with their tags whether or not the tags are debugged
+  let m = DerivedTags::default();
+  ctx.run_with_monitor(&m).unwrap();
+  assert!(m
+    .0
+    .borrow()
+    .contains(&("path".to_string(), "(0, 1)".to_string(), "0.5".to_string())));
+
+  // Only the relations computed by the run are reported; `path` is reused from the previous run
+  ctx.set_debug_tags(true);
+  ctx.add_rule("result(b) = path(0, b)").unwrap();
+  let m = DerivedTags::default();
+  ctx.run_with_monitor(&m).unwrap();
+  assert!(m
+    .0
+    .borrow()
+    .contains(&("result".to_string(), "(1)".to_string(), "0.5".to_string())));
+  assert!(m.0.borrow().iter().all(|(r, _, _)| r != "path"));
+}
+
+#[test]
+fn test_rank_add_mult() {
+  let ctx = add_mult_prob::AddMultProbProvenance::default();
+  expect_interpret_result_with_tag(
+    r#"
+      rel data = {0.9::(3), 0.4::(1)}
+      rel ranked(i, x) = (i, x) := rank(x: data(x))
+    "#,
+    ctx,
+    ("ranked", vec![(0.4, (0usize, 1)), (0.9, (1usize, 3))]),
+    add_mult_prob::AddMultProbProvenance::soft_cmp,
+  )
+}
+
+#[test]
+fn test_bdd_path_shared_facts() {
+  let ctx = bdd::BDDProvenance::<RcFamily>::new();
+  expect_interpret_result_with_tag(
+    r#"
+      rel edge = {0.5::(0, 1), 0.6::(1, 2), 0.6::(0, 2), 0.9::(2, 3)}
+      rel path(a, b) = edge(a, b) \/ path(a, c) /\ edge(c, b)
+      query path
+    "#,
+    ctx,
+    (
+      "path",
+      vec![
+        (0.5, (0, 1)),
+        (0.6, (1, 2)),
+        (0.72, (0, 2)),
+        (0.9, (2, 3)),
+        (0.54, (1, 3)),
+        (0.648, (0, 3)),
+      ],
+    ),
+    bdd::BDDProvenance::<RcFamily>::soft_cmp,
+  )
+}
+
+#[test]
+fn test_bdd_mutually_exclusive_count() {
+  let ctx = bdd::BDDProvenance::<RcFamily>::new();
+  expect_interpret_result_with_tag(
+    r#"
+      rel digit = {0.3::(0); 0.5::(1)}
+      rel result(n) = n := count(d: digit(d))
+    "#,
+    ctx,
+    ("result", vec![(0.2, (0usize,)), (0.8, (1usize,))]),
+    bdd::BDDProvenance::<RcFamily>::soft_cmp,
+  )
+}
+
+#[test]
+fn test_bdd_mutually_exclusive_sum_2() {
+  let ctx = bdd::BDDProvenance::<RcFamily>::new();
+  expect_interpret_result_with_tag(
+    r#"
+      rel digit = {0.3::(0); 0.5::(1); 0.2::(2)}
+      rel result(a + b) = digit(a), digit(b)
+    "#,
+    ctx,
+    ("result", vec![(0.3, (0,)), (0.5, (2,)), (0.2, (4,))]),
+    bdd::BDDProvenance::<RcFamily>::soft_cmp,
+  )
+}
+
+#[test]
+fn test_argmax_min_max_prob() {
+  // Same as `max[p](s: score(p, s))`, the tuple achieving the maximum is not conditioned on the other tuples
+  let ctx = min_max_prob::MinMaxProbProvenance::default();
+  expect_interpret_result_with_tag(
+    r#"
+      rel score = {0.9::(0, 90), 0.3::(1, 95), 0.5::(2, 80)}
+      rel best(s, p) = (s, p) := argmax[s](p: score(p, s))
+    "#,
+    ctx,
+    ("best", vec![(1.0, (95, 1))]),
+    min_max_prob::MinMaxProbProvenance::cmp,
+  )
+}
+
+#[test]
+fn test_top_k_add_mult() {
+  // Without argument, the tuples are ranked by their probabilities rather than their values
+  let ctx = add_mult_prob::AddMultProbProvenance::default();
+  expect_interpret_result_with_tag(
+    r#"
+      rel score = {0.2::(90), 0.9::(70), 0.5::(80)}
+      rel best(s) = s := top<2>(s: score(s))
+    "#,
+    ctx,
+    ("best", vec![(0.9, (70,)), (0.5, (80,))]),
+    add_mult_prob::AddMultProbProvenance::soft_cmp,
+  )
+}
@@ -125,3 +125,69 @@ fn get_month0_1() {
     ("r", vec![(0u32,)])
   )
 }
+
+#[test]
+fn parse_datetime_1() {
+  expect_interpret_result(
+    r#"
+      rel p = {"2019-01-04T00:00:00Z", "not a date"}
+      rel r($datetime(s)) = p(s)
+    "#,
+    ("r", vec![(Utc.with_ymd_and_hms(2019, 01, 04, 0, 0, 0).unwrap(),)]),
+  )
+}
+
+#[test]
+fn datetime_diff_1() {
+  expect_interpret_result(
+    r#"
+      rel p = {(t"2019-01-04T00:01:30Z", t"2019-01-04T00:00:00Z")}
+      rel r($datetime_diff(a, b), $datetime_diff(b, a)) = p(a, b)
+    "#,
+    ("r", vec![(90i64, -90i64)]),
+  )
+}
+
+#[test]
+fn datetime_compare_chronological_1() {
+  expect_interpret_result(
+    r#"
+      rel p = {("9", "2019-01-04T00:00:00Z"), ("10", "2020-01-04T00:00:00+08:00")}
+      rel before(a, b) = p(a, s1), p(b, s2), $datetime(s1) < $datetime(s2)
+    "#,
+    ("before", vec![("9".to_string(), "10".to_string())]),
+  )
+}
+
+#[test]
+fn datetime_add_1() {
+  expect_interpret_result(
+    r#"
+      rel p = {(t"2019-01-04T00:00:00Z", d"90s")}
+      rel r($datetime_add(a, d)) = p(a, d)
+    "#,
+    ("r", vec![(Utc.with_ymd_and_hms(2019, 01, 04, 0, 1, 30).unwrap(),)]),
+  )
+}
+
+#[test]
+fn duration_seconds_1() {
+  expect_interpret_result(
+    r#"
+      rel p = {(t"2019-01-04T00:01:30Z", t"2019-01-04T00:00:00Z")}
+      rel r($duration_seconds(a - b), $duration_seconds(b - a)) = p(a, b)
+    "#,
+    ("r", vec![(90i64, -90i64)]),
+  )
+}
+
+#[test]
+fn datetime_add_duration_op_1() {
+  expect_interpret_result(
+    r#"
+      rel p = {(t"2019-01-04T00:01:30Z", t"2019-01-04T00:00:00Z")}
+      rel r(b + (a - b) + (a - b)) = p(a, b)
+    "#,
+    ("r", vec![(Utc.with_ymd_and_hms(2019, 01, 04, 0, 3, 0).unwrap(),)]),
+  )
+}
@@ -1,4 +1,5 @@
 use scallop_core::common::aggregate_op::AggregateOp;
+use scallop_core::common::value_type::ValueType;
 use scallop_core::runtime::dynamic::dataflow::*;
 use scallop_core::runtime::dynamic::*;
 use scallop_core::runtime::env::*;
@@ -48,3 +49,176 @@ fn test_dynamic_aggregate_count_1() {
 
   expect_collection(&agg.complete(&ctx), vec![2usize]);
 }
+
+#[test]
+fn test_dynamic_aggregate_count_distinct_1() {
+  let ctx = unit::UnitProvenance::default();
+  let rt = RuntimeEnvironment::default();
+
+  // A batch with a duplicated tuple
+  let batch = vec![
+    DynamicElement::new(1i32, unit::Unit),
+    DynamicElement::new(1i32, unit::Unit),
+    DynamicElement::new(2i32, unit::Unit),
+  ];
+
+  let count_distinct: DynamicAggregator = AggregateOp::CountDistinct.into();
+  let result = count_distinct.aggregate(batch.clone(), &ctx, &rt);
+  assert_eq!(result.len(), 1);
+  assert_eq!(result[0].tuple, 2usize.into());
+
+  let count: DynamicAggregator = AggregateOp::Count.into();
+  assert_eq!(count.aggregate(batch, &ctx, &rt)[0].tuple, 3usize.into());
+}
+
+#[test]
+fn test_dynamic_aggregate_variance_1() {
+  let ctx = unit::UnitProvenance::default();
+  let rt = RuntimeEnvironment::default();
+
+  let batch = vec![
+    DynamicElement::new(1i32, unit::Unit),
+    DynamicElement::new(3i32, unit::Unit),
+  ];
+
+  let variance: DynamicAggregator = AggregateOp::Variance(ValueType::I32).into();
+  let result = variance.aggregate(batch.clone(), &ctx, &rt);
+  assert_eq!(result.len(), 1);
+  assert_eq!(result[0].tuple, 1.0f64.into());
+
+  let std_dev: DynamicAggregator = AggregateOp::StdDev(ValueType::I32).into();
+  assert_eq!(std_dev.aggregate(batch, &ctx, &rt)[0].tuple, 1.0f64.into());
+
+  // An empty batch produces no output
+  assert!(variance.aggregate(vec![], &ctx, &rt).is_empty());
+}
+
+#[test]
+fn test_dynamic_aggregate_median_1() {
+  let ctx = unit::UnitProvenance::default();
+  let rt = RuntimeEnvironment::default();
+
+  let median: DynamicAggregator = AggregateOp::Median(ValueType::F32).into();
+  let odd = vec![
+    DynamicElement::new(2.5f32, unit::Unit),
+    DynamicElement::new(-1.0f32, unit::Unit),
+    DynamicElement::new(7.0f32, unit::Unit),
+  ];
+  assert_eq!(median.aggregate(odd, &ctx, &rt)[0].tuple, 2.5f64.into());
+
+  let even = vec![
+    DynamicElement::new(1.0f32, unit::Unit),
+    DynamicElement::new(2.0f32, unit::Unit),
+  ];
+  assert_eq!(median.aggregate(even, &ctx, &rt)[0].tuple, 1.5f64.into());
+
+  // An empty batch produces no output
+  assert!(median.aggregate(vec![], &ctx, &rt).is_empty());
+}
+
+#[test]
+fn test_dynamic_aggregate_weighted_sum_1() {
+  let ctx = unit::UnitProvenance::default();
+  let rt = RuntimeEnvironment::default();
+
+  let weighted_sum: DynamicAggregator = AggregateOp::WeightedSum(ValueType::F64).into();
+  let batch = vec![
+    DynamicElement::new((2.0f64, 0.5f64), unit::Unit),
+    DynamicElement::new((3.0f64, 2.0f64), unit::Unit),
+  ];
+  assert_eq!(weighted_sum.aggregate(batch, &ctx, &rt)[0].tuple, 7.0f64.into());
+
+  // A single element yields the product of its value and weight
+  let single = vec![DynamicElement::new((4.0f64, 0.25f64), unit::Unit)];
+  assert_eq!(weighted_sum.aggregate(single, &ctx, &rt)[0].tuple, 1.0f64.into());
+
+  // An empty batch produces no output
+  assert!(weighted_sum.aggregate(vec![], &ctx, &rt).is_empty());
+}
+
+#[test]
+fn test_dynamic_aggregate_sample_1() {
+  let ctx = unit::UnitProvenance::default();
+  let rt = RuntimeEnvironment::new_with_random_seed(1234);
+
+  // Sample by the weight column, which is dropped from the result
+  let sample: DynamicAggregator = AggregateOp::Sample(Some(ValueType::I32)).into();
+  let batch = vec![
+    DynamicElement::new((1i32, 'a'), unit::Unit),
+    DynamicElement::new((3i32, 'b'), unit::Unit),
+    DynamicElement::new((0i32, 'c'), unit::Unit),
+  ];
+  let mut counts = [0; 3];
+  for _ in 0..1000 {
+    let result = sample.aggregate(batch.clone(), &ctx, &rt);
+    assert_eq!(result.len(), 1);
+    counts[(result[0].tuple.as_char() as u8 - b'a') as usize] += 1;
+  }
+  assert!(counts[0] > 150 && counts[0] < 350, "unexpected counts {:?}", counts);
+  assert_eq!(counts[2], 0);
+
+  // The same seed gives the same samples
+  let draw = |seed| {
+    let rt = RuntimeEnvironment::new_with_random_seed(seed);
+    (0..10)
+      .map(|_| sample.aggregate(batch.clone(), &ctx, &rt)[0].tuple.clone())
+      .collect::<Vec<_>>()
+  };
+  assert_eq!(draw(5), draw(5));
+
+  // Nothing is sampled without any positive weight
+  let zero = vec![DynamicElement::new((0i32, 'a'), unit::Unit)];
+  assert!(sample.aggregate(zero, &ctx, &rt).is_empty());
+  assert!(sample.aggregate(vec![], &ctx, &rt).is_empty());
+}
+
+#[test]
+fn test_dynamic_aggregate_normalize_1() {
+  let ctx = unit::UnitProvenance::default();
+  let rt = RuntimeEnvironment::default();
+
+  // The value column is replaced by the normalized weight at the end of the tuple
+  let normalize: DynamicAggregator = AggregateOp::Normalize(ValueType::I32).into();
+  let batch = vec![
+    DynamicElement::new((1i32, 'a'), unit::Unit),
+    DynamicElement::new((3i32, 'b'), unit::Unit),
+  ];
+  let result = normalize.aggregate(batch, &ctx, &rt);
+  assert_eq!(result.len(), 2);
+  assert_eq!(result[0].tuple[0].as_char(), 'a');
+  assert!((result[0].tuple[1].as_f64() - 0.25).abs() < 1e-9);
+  assert_eq!(result[1].tuple[0].as_char(), 'b');
+  assert!((result[1].tuple[1].as_f64() - 0.75).abs() < 1e-9);
+
+  // Nothing is produced when the values sum up to zero, or for an empty batch
+  let zero = vec![DynamicElement::new((0i32, 'a'), unit::Unit)];
+  assert!(normalize.aggregate(zero, &ctx, &rt).is_empty());
+  assert!(normalize.aggregate(vec![], &ctx, &rt).is_empty());
+}
+
+#[test]
+fn test_dynamic_aggregate_geometric_harmonic_mean_1() {
+  let ctx = unit::UnitProvenance::default();
+  let rt = RuntimeEnvironment::default();
+
+  let batch = vec![
+    DynamicElement::new(1u32, unit::Unit),
+    DynamicElement::new(9u32, unit::Unit),
+  ];
+  let geometric_mean: DynamicAggregator = AggregateOp::GeometricMean(ValueType::U32).into();
+  let result = geometric_mean.aggregate(batch.clone(), &ctx, &rt);
+  assert!((result[0].tuple.as_f64() - 3.0).abs() < 1e-9);
+  let harmonic_mean: DynamicAggregator = AggregateOp::HarmonicMean(ValueType::U32).into();
+  let result = harmonic_mean.aggregate(batch, &ctx, &rt);
+  assert!((result[0].tuple.as_f64() - 1.8).abs() < 1e-9);
+
+  // The geometric mean of a single number is the number itself
+  let single = vec![DynamicElement::new(3u32, unit::Unit)];
+  assert_eq!(geometric_mean.aggregate(single, &ctx, &rt)[0].tuple.as_f64(), 3.0);
+
+  // A zero produces no output, and neither does an empty batch
+  let zero = vec![DynamicElement::new(0u32, unit::Unit)];
+  assert!(geometric_mean.aggregate(zero.clone(), &ctx, &rt).is_empty());
+  assert!(harmonic_mean.aggregate(zero, &ctx, &rt).is_empty());
+  assert!(harmonic_mean.aggregate(vec![], &ctx, &rt).is_empty());
+}
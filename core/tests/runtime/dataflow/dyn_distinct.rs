@@ -0,0 +1,38 @@
+use scallop_core::common::expr::Expr;
+use scallop_core::common::tuple::Tuple;
+use scallop_core::runtime::dynamic::dataflow::*;
+use scallop_core::runtime::dynamic::*;
+use scallop_core::runtime::env::*;
+use scallop_core::runtime::provenance::*;
+
+#[test]
+fn test_dyn_distinct_1() {
+  let ctx = natural::NaturalProvenance;
+  let rt = RuntimeEnvironment::new_std();
+
+  // Relations
+  let mut source = DynamicRelation::<natural::NaturalProvenance>::new();
+  source.insert_tagged(
+    &ctx,
+    vec![
+      (Some(1), (0i32, 5i32)),
+      (Some(2), (1, 3)),
+      (Some(3), (2, 5)),
+      (Some(4), (3, 7)),
+    ],
+  );
+  source.changed(&ctx);
+
+  // Projecting onto the second column yields duplicated tuples, whose tags are added up
+  let result = DynamicDataflow::dynamic_relation(&source)
+    .project(Expr::access(1))
+    .distinct(&ctx)
+    .iter_recent(&rt)
+    .flatten()
+    .map(|e| (e.tuple, e.tag))
+    .collect::<Vec<_>>();
+  assert_eq!(
+    result,
+    vec![(Tuple::from(3i32), 2), (Tuple::from(5i32), 4), (Tuple::from(7i32), 4)]
+  );
+}
@@ -35,7 +35,7 @@ where
     );
     strata_1.add_output_relation("_color_rev");
     strata_1.add_output_relation("_colors_key");
-    strata_1.run(&ctx, &mut rt)
+    strata_1.run(&ctx, &mut rt).unwrap()
   };
 
   let mut result_2 = {
@@ -49,7 +49,7 @@ where
         .project((Expr::access(0), Expr::access(2))),
     );
     strata_2.add_output_relation("color_count");
-    strata_2.run(&ctx, &mut rt)
+    strata_2.run(&ctx, &mut rt).unwrap()
   };
 
   result_2.remove("color_count").unwrap()
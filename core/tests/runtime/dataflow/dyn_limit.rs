@@ -0,0 +1,40 @@
+use scallop_core::common::tuple::Tuple;
+use scallop_core::runtime::dynamic::dataflow::*;
+use scallop_core::runtime::dynamic::*;
+use scallop_core::runtime::env::*;
+use scallop_core::runtime::provenance::*;
+
+#[test]
+fn test_dyn_limit_1() {
+  let mut ctx = unit::UnitProvenance;
+  let rt = RuntimeEnvironment::new_std();
+
+  // Relations
+  let mut source = DynamicRelation::<unit::UnitProvenance>::new();
+  source.insert_untagged(&mut ctx, vec![(0i32, 5i32), (1, 3), (2, 9), (3, 7)]);
+  source.changed(&ctx);
+
+  // The three largest values on the second column
+  let top_3 = DynamicDataflow::dynamic_relation(&source)
+    .sort(vec![(1, false)])
+    .limit(3)
+    .iter_recent(&rt)
+    .flatten()
+    .map(|e| e.tuple)
+    .collect::<Vec<_>>();
+  assert_eq!(
+    top_3,
+    vec![(2i32, 9i32), (3, 7), (0, 5)]
+      .into_iter()
+      .map(Tuple::from)
+      .collect::<Vec<_>>()
+  );
+
+  // Limiting to more elements than there are keeps all of them
+  let all = DynamicDataflow::dynamic_relation(&source)
+    .limit(10)
+    .iter_recent(&rt)
+    .flatten()
+    .count();
+  assert_eq!(all, 4);
+}
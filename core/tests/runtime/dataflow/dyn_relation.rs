@@ -23,3 +23,35 @@ fn simple_relation_dataflow() {
 
   expect_collection(&target.complete(&ctx), vec![(0usize, 1usize), (1usize, 2usize)]);
 }
+
+#[test]
+fn complete_streaming_relation() {
+  let ctx = min_max_prob::MinMaxProbProvenance::default();
+
+  // Two relations receiving the same facts over several iterations
+  let mut r1 = DynamicRelation::<min_max_prob::MinMaxProbProvenance>::new();
+  let mut r2 = DynamicRelation::<min_max_prob::MinMaxProbProvenance>::new();
+  let batches = vec![
+    vec![(Some(0.3), (1usize, 2usize)), (Some(0.6), (3usize, 4usize))],
+    vec![(Some(0.8), (1usize, 2usize)), (Some(0.5), (0usize, 1usize))],
+    vec![(Some(0.2), (5usize, 6usize)), (Some(0.1), (0usize, 1usize))],
+  ];
+  for batch in batches {
+    for r in [&mut r1, &mut r2] {
+      r.insert_tagged(&ctx, batch.clone());
+      r.changed(&ctx);
+    }
+  }
+  while r1.changed(&ctx) || r2.changed(&ctx) {}
+
+  let completed = r1.complete(&ctx);
+  let streamed = r2.complete_streaming(&ctx).collect::<Vec<_>>();
+  assert_eq!(streamed.len(), 4);
+  assert_eq!(
+    streamed,
+    completed
+      .iter()
+      .map(|e| (e.tag.clone(), e.tuple.clone()))
+      .collect::<Vec<_>>()
+  );
+}
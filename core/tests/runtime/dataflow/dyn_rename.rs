@@ -0,0 +1,31 @@
+use scallop_core::common::tuple::Tuple;
+use scallop_core::runtime::dynamic::dataflow::*;
+use scallop_core::runtime::dynamic::*;
+use scallop_core::runtime::env::*;
+use scallop_core::runtime::provenance::*;
+
+#[test]
+fn test_dyn_rename_1() {
+  let mut ctx = unit::UnitProvenance;
+  let rt = RuntimeEnvironment::new_std();
+
+  // Relations
+  let mut source = DynamicRelation::<unit::UnitProvenance>::new();
+  source.insert_untagged(&mut ctx, vec![(0i32, 5usize, "a"), (1, 3, "b")]);
+  source.changed(&ctx);
+
+  // The output column `i` is the input column `permutation[i]`
+  let renamed = DynamicDataflow::dynamic_relation(&source)
+    .rename(vec![2, 0, 1])
+    .iter_recent(&rt)
+    .flatten()
+    .map(|e| e.tuple)
+    .collect::<Vec<_>>();
+  assert_eq!(
+    renamed,
+    vec![("a", 0i32, 5usize), ("b", 1, 3)]
+      .into_iter()
+      .map(Tuple::from)
+      .collect::<Vec<_>>()
+  );
+}
@@ -0,0 +1,47 @@
+use scallop_core::common::tuple::Tuple;
+use scallop_core::runtime::dynamic::dataflow::*;
+use scallop_core::runtime::dynamic::*;
+use scallop_core::runtime::env::*;
+use scallop_core::runtime::provenance::*;
+
+fn sorted_tuples(
+  source: &DynamicRelation<unit::UnitProvenance>,
+  order: Vec<(usize, bool)>,
+  rt: &RuntimeEnvironment,
+) -> Vec<Tuple> {
+  DynamicDataflow::dynamic_relation(source)
+    .sort(order)
+    .iter_recent(rt)
+    .flatten()
+    .map(|e| e.tuple)
+    .collect()
+}
+
+#[test]
+fn test_dyn_sort_1() {
+  let mut ctx = unit::UnitProvenance;
+  let rt = RuntimeEnvironment::new_std();
+
+  // Relations
+  let mut source = DynamicRelation::<unit::UnitProvenance>::new();
+  source.insert_untagged(&mut ctx, vec![(0i32, 5i32), (1, 3), (2, 9), (3, 3)]);
+  source.changed(&ctx);
+
+  // Descending on the second column; ties broken by the first column in ascending order
+  assert_eq!(
+    sorted_tuples(&source, vec![(1, false)], &rt),
+    vec![(2i32, 9i32), (0, 5), (1, 3), (3, 3)]
+      .into_iter()
+      .map(Tuple::from)
+      .collect::<Vec<_>>()
+  );
+
+  // Ascending on the second column, then descending on the first column
+  assert_eq!(
+    sorted_tuples(&source, vec![(1, true), (0, false)], &rt),
+    vec![(3i32, 3i32), (1, 3), (0, 5), (2, 9)]
+      .into_iter()
+      .map(Tuple::from)
+      .collect::<Vec<_>>()
+  );
+}
@@ -1,5 +1,6 @@
 mod dyn_aggregate;
 mod dyn_difference;
+mod dyn_distinct;
 mod dyn_exclusion;
 mod dyn_filter;
 mod dyn_find;
@@ -8,9 +9,12 @@ mod dyn_group_aggregate;
 mod dyn_group_by_key;
 mod dyn_intersect;
 mod dyn_join;
+mod dyn_limit;
 mod dyn_product;
 mod dyn_project;
 mod dyn_relation;
+mod dyn_rename;
+mod dyn_sort;
 
 mod sta_collection;
 mod sta_filter;
@@ -42,7 +42,7 @@ where
   iter.add_output_relation("edge");
 
   // Run the iteration
-  let mut result = iter.run(&ctx, &mut rt);
+  let mut result = iter.run(&ctx, &mut rt).unwrap();
 
   // Test the result
   expect_collection(&result["path"], vec![(0, 1), (1, 2), (0, 2), (1, 3), (0, 3)]);
@@ -94,7 +94,7 @@ where
       Dataflow::relation("color").project((Expr::access(1), Expr::access(0))),
     );
     strata_1.add_output_relation("_color_rev");
-    strata_1.run(&ctx, &mut rt)
+    strata_1.run(&ctx, &mut rt).unwrap()
   };
 
   let result_2 = {
@@ -106,7 +106,7 @@ where
       Dataflow::reduce(AggregateOp::Count, "_color_rev", ReduceGroupByType::Implicit),
     );
     strata_2.add_output_relation("color_count");
-    strata_2.run(&ctx, &mut rt)
+    strata_2.run(&ctx, &mut rt).unwrap()
   };
 
   let mut result_3 = {
@@ -118,7 +118,7 @@ where
       Dataflow::reduce(AggregateOp::Argmax, "color_count", ReduceGroupByType::None),
     );
     strata_3.add_output_relation("max_color_count");
-    strata_3.run(&ctx, &mut rt)
+    strata_3.run(&ctx, &mut rt).unwrap()
   };
 
   expect_collection(&result_3["max_color_count"], vec![("blue", 3usize)]);
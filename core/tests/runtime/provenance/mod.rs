@@ -1,3 +1,5 @@
 mod disjunction;
 mod prob;
+mod proof_tree;
+mod softmax;
 mod top_bottom_k;
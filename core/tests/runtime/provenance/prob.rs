@@ -30,7 +30,7 @@ fn test_simple_probability_count() {
       Dataflow::relation("color").project((Expr::access(1), Expr::access(0))),
     );
     strata_1.add_output_relation("_color_rev");
-    strata_1.run(&ctx, &mut rt)
+    strata_1.run(&ctx, &mut rt).unwrap()
   };
 
   let result_2 = {
@@ -42,7 +42,7 @@ fn test_simple_probability_count() {
       Dataflow::reduce(AggregateOp::Count, "_color_rev", ReduceGroupByType::Implicit),
     );
     strata_2.add_output_relation("color_count");
-    strata_2.run(&ctx, &mut rt)
+    strata_2.run(&ctx, &mut rt).unwrap()
   };
 
   println!("{:?}", result_2)
@@ -73,7 +73,7 @@ fn test_min_max_prob_count_max() {
       Dataflow::relation("color").project((Expr::access(1), Expr::access(0))),
     );
     strata_1.add_output_relation("_color_rev");
-    strata_1.run(&ctx, &mut rt)
+    strata_1.run(&ctx, &mut rt).unwrap()
   };
 
   let result_2 = {
@@ -85,7 +85,7 @@ fn test_min_max_prob_count_max() {
       Dataflow::reduce(AggregateOp::Count, "_color_rev", ReduceGroupByType::Implicit),
     );
     strata_2.add_output_relation("color_count");
-    strata_2.run(&ctx, &mut rt)
+    strata_2.run(&ctx, &mut rt).unwrap()
   };
 
   println!("{:?}", result_2);
@@ -99,7 +99,7 @@ fn test_min_max_prob_count_max() {
       Dataflow::reduce(AggregateOp::Argmax, "color_count", ReduceGroupByType::None),
     );
     strata_3.add_output_relation("max_color");
-    strata_3.run(&ctx, &mut rt)
+    strata_3.run(&ctx, &mut rt).unwrap()
   };
 
   println!("{:?}", result_2["color_count"]);
@@ -0,0 +1,84 @@
+use scallop_core::integrate::*;
+use scallop_core::runtime::provenance::proof_tree::*;
+use scallop_core::runtime::provenance::*;
+use scallop_core::utils::RcFamily;
+
+#[test]
+fn test_proof_tree_semiring_1() {
+  let ctx = ProofTreeProvenance::<RcFamily>::default();
+  let a = ctx.tagging_fn(());
+  let b = ctx.tagging_fn(());
+  let c = ctx.tagging_fn(());
+
+  // The trivial proof is dropped from conjunctions, and nested nodes are flattened
+  let ab = ctx.mult(&ctx.mult(&a, &ctx.one()), &b);
+  let abc = ctx.mult(&ab, &c);
+  assert_eq!(abc.to_string(), "and(0, 1, 2)");
+  assert_eq!(ctx.add(&ctx.zero(), &ab).to_string(), "and(0, 1)");
+
+  // Alternative proofs are unioned, and the ones subsumed by others are dropped
+  let b_or_c = ctx.add(&b, &c);
+  let a_and_b_or_c = ctx.mult(&a, &b_or_c);
+  assert_eq!(ctx.add(&ab, &a_and_b_or_c).to_string(), "and(0, or(1, 2))");
+  assert_eq!(ctx.add(&c, &ab).to_string(), "or(2, and(0, 1))");
+  assert_eq!(
+    a_and_b_or_c.to_json(),
+    serde_json::json!({ "and": [0, { "or": [1, 2] }] })
+  );
+}
+
+#[test]
+fn test_proof_tree_path_1() {
+  let prov = ProofTreeProvenance::<RcFamily>::default();
+  let mut ctx = IntegrateContext::<_, RcFamily>::new(prov);
+  ctx
+    .add_program(
+      r#"
+      rel edge = {(0, 1), (1, 2), (0, 2), (2, 3)}
+      rel path(a, c) = edge(a, c) or (path(a, b) and edge(b, c))
+    "#,
+    )
+    .expect("Compilation error");
+  ctx.run().expect("Runtime error");
+
+  // Fact `i` is the `i`-th edge
+  let path = ctx.computed_relation_ref("path").unwrap();
+  let trees = path
+    .iter()
+    .map(|(tag, tuple)| (tuple.to_string(), tag.to_string()))
+    .collect::<Vec<_>>();
+  assert_eq!(
+    trees,
+    vec![
+      ("(0, 1)".to_string(), "0".to_string()),
+      ("(0, 2)".to_string(), "or(2, and(1, 0))".to_string()),
+      ("(0, 3)".to_string(), "and(3, or(2, and(1, 0)))".to_string()),
+      ("(1, 2)".to_string(), "1".to_string()),
+      ("(1, 3)".to_string(), "and(3, 1)".to_string()),
+      ("(2, 3)".to_string(), "3".to_string()),
+    ]
+  );
+}
+
+#[test]
+fn test_proof_tree_cyclic_path_1() {
+  // Cyclic graphs have infinitely many proofs, so the trees are capped
+  let prov = ProofTreeProvenance::<RcFamily>::new(50);
+  let mut ctx = IntegrateContext::<_, RcFamily>::new(prov);
+  ctx
+    .add_program(
+      r#"
+      rel edge = {(0, 1), (1, 0)}
+      rel path(a, c) = edge(a, c) or (path(a, b) and edge(b, c))
+    "#,
+    )
+    .expect("Compilation error");
+  ctx.run().expect("Runtime error");
+
+  let path = ctx.computed_relation_ref("path").unwrap();
+  assert_eq!(path.len(), 4);
+  for (tag, _) in path.iter() {
+    assert!(tag.size() <= 50);
+    assert!(!tag.facts().is_empty());
+  }
+}
@@ -0,0 +1,54 @@
+use scallop_core::runtime::provenance::diff_softmax::*;
+use scallop_core::runtime::provenance::*;
+use scallop_core::utils::RcFamily;
+
+fn assert_close(OutputDiffProb(prob, deriv): OutputDiffProb, expected_prob: f64, expected_deriv: Vec<(usize, f64)>) {
+  assert!((prob - expected_prob).abs() < 1e-6, "{} != {}", prob, expected_prob);
+  assert_eq!(deriv.len(), expected_deriv.len());
+  for ((i, w), (j, v)) in deriv.into_iter().zip(expected_deriv) {
+    assert_eq!(i, j);
+    assert!((w - v).abs() < 1e-6, "{} != {}", w, v);
+  }
+}
+
+#[test]
+fn test_diff_softmax_group() {
+  let ctx = DiffSoftmaxProvenance::<(), RcFamily>::new(3);
+  let a = ctx.tagging_fn((0.0, (), Some(0)).into());
+  let b = ctx.tagging_fn((2.0f64.ln(), (), Some(0)).into());
+  let _c = ctx.tagging_fn((1.0f64.ln(), (), Some(0)).into());
+
+  // Softmax normalized probabilities: 0.25, 0.5, 0.25
+  assert_close(ctx.recover_fn(&a), 0.25, vec![(0, 0.1875), (1, -0.125), (2, -0.0625)]);
+  assert_close(ctx.recover_fn(&b), 0.5, vec![(0, -0.125), (1, 0.25), (2, -0.125)]);
+
+  // The facts of a group are mutually exclusive
+  assert!(ctx.discard(&ctx.mult(&a, &b)));
+}
+
+#[test]
+fn test_diff_softmax_independent() {
+  let ctx = DiffSoftmaxProvenance::<(), RcFamily>::new(3);
+  let a = ctx.tagging_fn((0.0, (), Some(0)).into());
+  let _ = ctx.tagging_fn((0.0, (), Some(0)).into());
+  let c = ctx.tagging_fn((0.0, (), None).into());
+
+  // A fact without group has its sigmoid probability
+  assert_close(ctx.recover_fn(&c), 0.5, vec![(2, 0.25)]);
+
+  // Gradients are back-propagated through the softmax of the group
+  let ac = ctx.mult(&a, &c);
+  assert_close(ctx.recover_fn(&ac), 0.25, vec![(0, 0.125), (1, -0.125), (2, 0.125)]);
+}
+
+#[test]
+fn test_diff_softmax_constant_logit() {
+  let ctx = DiffSoftmaxProvenance::<(), RcFamily>::new(3);
+  let a = ctx.tagging_fn((0.5f64.ln(), (), Some(0)).into());
+  let b = ctx.tagging_fn(InputDiffLogit::from_prob(0.5, Some(0)));
+
+  // Only the facts with external tags receive gradients
+  assert_close(ctx.recover_fn(&a), 0.5, vec![(0, 0.25)]);
+  assert_close(ctx.recover_fn(&b), 0.5, vec![(0, -0.25)]);
+  assert_eq!(ctx.input_tags().len(), 1);
+}
@@ -1,3 +1,4 @@
+use scallop_core::common::tuple::Tuple;
 use scallop_core::runtime::provenance::*;
 use scallop_core::utils::RcFamily;
 
@@ -45,6 +46,42 @@ mod diff {
     // Should only contain a and b
     println!("{:?}", nanb_or_cd);
   }
+
+  #[test]
+  fn test_diff_top_bottom_k_clauses_saturation_epsilon_1() {
+    let mut ctx = DiffTopBottomKClausesProvenance::<(), RcFamily>::new(3);
+    let a = ctx.tagging_fn((0.9, (), None).into());
+    let b = ctx.tagging_fn((0.001, (), None).into());
+    let a_or_b = ctx.add(&a, &b);
+
+    // By default, the formulas need to be equal
+    assert!(!ctx.saturated(&a, &a_or_b));
+
+    // With an epsilon, it suffices for the probabilities to be close
+    ctx.set_saturation_epsilon(0.01);
+    assert!(ctx.saturated(&a, &a_or_b));
+    ctx.set_saturation_epsilon(0.00001);
+    assert!(!ctx.saturated(&a, &a_or_b));
+  }
+
+  #[test]
+  fn test_diff_top_bottom_k_clauses_cross_entropy_1() {
+    let ctx = DiffTopBottomKClausesProvenance::<(), RcFamily>::new(3);
+    let a = ctx.tagging_fn((0.8, (), None).into());
+    let b = ctx.tagging_fn((0.5, (), None).into());
+    let a_and_b = ctx.mult(&a, &b);
+
+    // The output (1) has probability 0.4, and (2) is not derived
+    let outputs = vec![(Tuple::from((1i32,)), ctx.recover_fn(&a_and_b))];
+    let targets = vec![(Tuple::from((1i32,)), 1.0), (Tuple::from((2i32,)), 0.0)];
+    let (loss, grad) = ctx.cross_entropy(&outputs, &targets);
+
+    // The loss is averaged over the two targets, and the missing output contributes (almost) nothing
+    assert!((loss - (-(0.4f64).ln() / 2.0)).abs() < 1e-5);
+    assert_eq!(grad.len(), 2);
+    assert!((grad[0] - (-1.25 * 0.5)).abs() < 1e-5);
+    assert!((grad[1] - (-1.25 * 0.8)).abs() < 1e-5);
+  }
 }
 
 mod normal {
@@ -88,4 +125,22 @@ mod normal {
     let r = ctx.top_bottom_k_mult(&t1, &t2, k);
     println!("{:?}", r);
   }
+
+  #[test]
+  fn test_top_bottom_k_clauses_saturation_epsilon_1() {
+    let mut ctx = top_bottom_k_clauses::TopBottomKClausesProvenance::<RcFamily>::new(3);
+    let a = ctx.tagging_fn(0.9.into());
+    let b = ctx.tagging_fn(0.001.into());
+    let a_or_b = ctx.add(&a, &b);
+
+    // By default, the formulas need to be equal
+    assert!(!ctx.saturated(&a, &a_or_b));
+    assert!(ctx.saturated(&a_or_b, &a_or_b));
+
+    // With an epsilon, it suffices for the probabilities to be close
+    ctx.set_saturation_epsilon(0.01);
+    assert!(ctx.saturated(&a, &a_or_b));
+    ctx.set_saturation_epsilon(0.00001);
+    assert!(!ctx.saturated(&a, &a_or_b));
+  }
 }
@@ -1 +1,2 @@
+mod tuple;
 mod value;
@@ -0,0 +1,40 @@
+use scallop_core::common::tuple::*;
+use scallop_core::common::value::*;
+
+#[test]
+fn tuple_from_values_1() {
+  let t = Tuple::from_values(vec![Value::I32(1), Value::String("a".to_string())]);
+  assert_eq!(t.arity(), 2);
+  assert_eq!(t, Tuple::from((1i32, "a".to_string())));
+}
+
+#[test]
+fn tuple_try_into_array_1() {
+  let t = Tuple::from((1i32, 2i32, 3i32));
+  let [a, b, c] = t.try_into_array::<3>().unwrap();
+  assert_eq!((a, b, c), (Value::I32(1), Value::I32(2), Value::I32(3)));
+}
+
+#[test]
+fn tuple_try_into_array_2() {
+  assert!(Tuple::from((1i32, 2i32)).try_into_array::<3>().is_none());
+  assert!(Tuple::from((1i32, (2i32, 3i32))).try_into_array::<2>().is_none());
+}
+
+#[test]
+fn tuple_get_as_1() {
+  let t = Tuple::from((5usize, "x", true));
+  assert_eq!(t.get_as::<usize>(0), Some(5));
+  assert_eq!(t.get_as::<&str>(1), Some("x"));
+  assert_eq!(t.get_as::<bool>(2), Some(true));
+  assert_eq!(t.get_as::<i32>(0), None);
+  assert_eq!(t.get_as::<bool>(3), None);
+}
+
+#[test]
+fn tuple_cmp_nan_1() {
+  let t1 = Tuple::from((1i32, f64::NAN));
+  let t2 = Tuple::from((1i32, 0.5f64));
+  assert_eq!(t1.partial_cmp(&t2), Some(std::cmp::Ordering::Greater));
+  assert_eq!(t1.cmp(&Tuple::from((1i32, f64::NAN))), std::cmp::Ordering::Equal);
+}
@@ -1,6 +1,8 @@
 use std::convert::*;
 
+use scallop_core::common::tuple::*;
 use scallop_core::common::value::*;
+use scallop_core::common::value_type::*;
 
 #[test]
 fn value_try_into_1() {
@@ -15,3 +17,47 @@ fn value_try_into_2() {
   let p: usize = v.try_into().unwrap_or(0);
   assert_eq!(p, 0);
 }
+
+#[test]
+fn value_cmp_nan_1() {
+  let nan = Value::F64(f64::NAN);
+  assert_eq!(nan, Value::F64(f64::NAN));
+  assert!(nan > Value::F64(f64::INFINITY));
+  assert!(Value::F32(f32::NAN) > Value::F32(1.0));
+  assert!(Value::F64(1.0) < Value::F64(2.0));
+}
+
+#[test]
+fn value_cmp_nan_2() {
+  let mut values = vec![
+    Value::F64(3.0),
+    Value::F64(f64::NAN),
+    Value::F64(-1.0),
+    Value::F64(f64::NAN),
+  ];
+  values.sort();
+  values.dedup();
+  assert_eq!(values, vec![Value::F64(-1.0), Value::F64(3.0), Value::F64(f64::NAN)]);
+}
+
+#[test]
+fn value_parse_quoted_tuple_1() {
+  let ty = ValueType::Tuple(vec![ValueType::String, ValueType::List(Box::new(ValueType::String))]);
+  let v = ty.parse(r#"("a, (b", ["c\"]", d])"#).unwrap();
+  assert_eq!(
+    v,
+    Value::Tuple(vec![
+      Value::String("a, (b".to_string()),
+      Value::List(vec![Value::String("c\"]".to_string()), Value::String("d".to_string())]),
+    ])
+  );
+
+  // Displayed tuples are parsed back
+  assert_eq!(ty.parse(&v.to_string()).unwrap(), v);
+}
+
+#[test]
+fn value_type_tuple_as_f64_1() {
+  assert_eq!(ValueType::I32.tuple_as_f64(&Tuple::from(3i32)), Some(3.0));
+  assert_eq!(ValueType::String.tuple_as_f64(&Tuple::from(Value::String("3".to_string()))), None);
+}
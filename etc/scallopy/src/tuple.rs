@@ -1,7 +1,7 @@
 // use std::rc::Rc;
 
 use pyo3::exceptions::{PyIndexError, PyTypeError};
-use pyo3::{prelude::*, types::PyTuple};
+use pyo3::{prelude::*, types::PyList, types::PyTuple};
 
 use scallop_core::common::tuple::Tuple;
 use scallop_core::common::tuple_type::TupleType;
@@ -64,6 +64,13 @@ pub fn to_python_value(val: &Value) -> Py<PyAny> {
     // RcString(s) => Python::with_gil(|py| s.to_object(py)),
     DateTime(d) => Python::with_gil(|py| d.to_string().to_object(py)),
     Duration(d) => Python::with_gil(|py| d.to_string().to_object(py)),
+    Value::Tuple(vs) => {
+      Python::with_gil(|py| PyTuple::new(py, vs.iter().map(to_python_value).collect::<Vec<_>>()).into())
+    }
+    Value::List(vs) => {
+      Python::with_gil(|py| PyList::new(py, vs.iter().map(to_python_value).collect::<Vec<_>>()).into())
+    }
+    Symbol(_) => Python::with_gil(|py| val.as_str().to_object(py)),
   }
 }
 
@@ -87,6 +94,7 @@ pub fn from_python_value(v: &PyAny, ty: &ValueType) -> PyResult<Value> {
     ValueType::Bool => Ok(Value::Bool(v.extract()?)),
     ValueType::Str => panic!(""),
     ValueType::String => Ok(Value::String(v.extract()?)),
+    ValueType::Symbol => Ok(Value::symbol(v.extract()?)),
     // ValueType::RcString => Ok(Tuple::Value(Value::RcString(Rc::new(
     //   v.extract::<String>()?,
     // )))),
@@ -98,5 +106,26 @@ pub fn from_python_value(v: &PyAny, ty: &ValueType) -> PyResult<Value> {
       let dt = utils::parse_duration_string(v.extract()?).ok_or(PyTypeError::new_err("Cannot parse into Duration"))?;
       Ok(Value::Duration(dt))
     }
+    ValueType::Tuple(ts) => {
+      let tup: &PyTuple = v.downcast()?;
+      if tup.len() == ts.len() {
+        let elems = ts
+          .iter()
+          .enumerate()
+          .map(|(i, t)| from_python_value(tup.get_item(i)?, t))
+          .collect::<PyResult<Vec<_>>>()?;
+        Ok(Value::Tuple(elems))
+      } else {
+        Err(PyIndexError::new_err("Invalid tuple size"))
+      }
+    }
+    ValueType::List(t) => {
+      let list: &PyList = v.downcast()?;
+      let elems = list
+        .iter()
+        .map(|e| from_python_value(e, t))
+        .collect::<PyResult<Vec<_>>>()?;
+      Ok(Value::List(elems))
+    }
   }
 }
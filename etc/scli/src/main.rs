@@ -29,9 +29,21 @@ struct Options {
   #[structopt(long)]
   iter_limit: Option<usize>,
 
+  /// Abort when a relation holds more facts than this limit
+  #[structopt(long)]
+  relation_size_limit: Option<usize>,
+
   #[structopt(long)]
   seed: Option<u64>,
 
+  /// Sort the groundings of foreign predicates so that runs are reproducible
+  #[structopt(long)]
+  deterministic: bool,
+
+  /// Use compensated summation for the floating point sums and variances of aggregations
+  #[structopt(long)]
+  stable_float_aggregation: bool,
+
   /// General debug option
   #[structopt(short, long)]
   debug: bool,
@@ -67,17 +79,19 @@ struct Options {
   /// Do not remove unused relations
   #[structopt(long)]
   do_not_remove_unused_relations: bool,
+
+  /// Require the relations used in rules to be declared with `type`
+  #[structopt(long)]
+  strict_relation_declarations: bool,
 }
 
 struct MonitorOptions {
-  pub debug_tag: bool,
   pub debug_runtime: bool,
 }
 
 impl From<&Options> for MonitorOptions {
   fn from(opt: &Options) -> Self {
     Self {
-      debug_tag: opt.debug_tag,
       debug_runtime: opt.debug_runtime,
     }
   }
@@ -85,14 +99,11 @@ impl From<&Options> for MonitorOptions {
 
 impl MonitorOptions {
   fn needs_monitor(&self) -> bool {
-    self.debug_tag || self.debug_runtime
+    self.debug_runtime
   }
 
   fn build<Prov: provenance::Provenance>(&self) -> monitor::DynamicMonitors<Prov> {
     let mut monitor = monitor::DynamicMonitors::new();
-    if self.debug_tag {
-      monitor.add(monitor::DebugTagsMonitor);
-    }
     if self.debug_runtime {
       monitor.add(monitor::DebugRuntimeMonitor);
     }
@@ -112,6 +123,7 @@ fn main() -> Result<(), String> {
       debug_back: opt.debug_back,
       debug_ram: opt.debug_ram,
       do_not_remove_unused_relations: opt.do_not_remove_unused_relations,
+      strict_relation_declarations: opt.strict_relation_declarations,
       output_all: opt.output_all,
       ..Default::default()
     },
@@ -124,6 +136,10 @@ fn main() -> Result<(), String> {
       random_seed: opt.seed.unwrap_or(DEFAULT_RANDOM_SEED),
       early_discard: !opt.no_early_discard,
       iter_limit: opt.iter_limit,
+      relation_size_limit: opt.relation_size_limit,
+      debug_tags: opt.debug_tag,
+      deterministic: opt.deterministic,
+      stable_float_aggregation: opt.stable_float_aggregation,
     },
   };
 
@@ -151,6 +167,10 @@ fn main() -> Result<(), String> {
       let ctx = provenance::proofs::ProofsProvenance::<RcFamily>::default();
       interpret(ctx, &opt.input, integrate_opt, predicate_set, monitor_options)
     }
+    "prooftree" => {
+      let ctx = provenance::proof_tree::ProofTreeProvenance::<RcFamily>::default();
+      interpret(ctx, &opt.input, integrate_opt, predicate_set, monitor_options)
+    }
     "minmaxprob" => {
       let ctx = provenance::min_max_prob::MinMaxProbProvenance::default();
       interpret(ctx, &opt.input, integrate_opt, predicate_set, monitor_options)
@@ -159,6 +179,10 @@ fn main() -> Result<(), String> {
       let ctx = provenance::add_mult_prob::AddMultProbProvenance::default();
       interpret(ctx, &opt.input, integrate_opt, predicate_set, monitor_options)
     }
+    "intervalprob" => {
+      let ctx = provenance::interval_prob::IntervalProbProvenance::default();
+      interpret(ctx, &opt.input, integrate_opt, predicate_set, monitor_options)
+    }
     "topkproofs" => {
       let ctx = provenance::top_k_proofs::TopKProofsProvenance::<RcFamily>::new(opt.top_k);
       interpret(ctx, &opt.input, integrate_opt, predicate_set, monitor_options)
@@ -167,6 +191,10 @@ fn main() -> Result<(), String> {
       let ctx = provenance::top_bottom_k_clauses::TopBottomKClausesProvenance::<RcFamily>::new(opt.top_k);
       interpret(ctx, &opt.input, integrate_opt, predicate_set, monitor_options)
     }
+    "bdd" => {
+      let ctx = provenance::bdd::BDDProvenance::<RcFamily>::new();
+      interpret(ctx, &opt.input, integrate_opt, predicate_set, monitor_options)
+    }
     _ => {
       Err(format!("Unknown provenance semiring `{}`", opt.provenance))
     }
@@ -122,6 +122,12 @@ where
 
         // If query is non-empty, compile and execute the program
         if !queries.is_empty() {
+          // Warn about the relations that are declared but never used
+          let warnings = front_context.dead_relation_warnings();
+          if warnings.has_warning() {
+            warnings.report_warnings();
+          }
+
           // This is a query event, turn all things in the context into a back program
           let mut back_ir = front_context.to_back_program();
           if let Err(e) = back_ir.apply_optimizations(&options) {